@@ -0,0 +1,129 @@
+//! N-API bindings so JavaScript toolchains can run the checker in-process.
+//!
+//! Every check has an `*Async` twin that runs on the libuv thread pool and resolves a
+//! `Promise`, keeping the event loop free while large projects are checked.
+
+use napi::bindgen_prelude::*;
+use napi_derive::napi;
+use std::collections::HashMap;
+use tsc_rs::api::{load_project, options_from_value};
+use tsc_rs::{CompilerOptions, Diagnostic, Program};
+
+#[napi(object, js_name = "Diagnostic")]
+pub struct JsDiagnostic {
+    pub file: String,
+    /// Byte offset of the error, when it has a location.
+    pub start: Option<u32>,
+    pub length: Option<u32>,
+    pub message: String,
+    /// `"error"` or `"warning"`.
+    pub category: String,
+}
+
+impl From<Diagnostic> for JsDiagnostic {
+    fn from(diagnostic: Diagnostic) -> Self {
+        Self {
+            file: diagnostic.file_name,
+            start: diagnostic.span.map(|span| span.start),
+            length: diagnostic.span.map(|span| span.end - span.start),
+            message: diagnostic.message,
+            category: diagnostic.category.to_string(),
+        }
+    }
+}
+
+fn compiler_options(options: Option<serde_json::Value>) -> Result<CompilerOptions> {
+    options_from_value(options.unwrap_or_default()).map_err(Error::from_reason)
+}
+
+fn single_file(source: String, options: Option<serde_json::Value>) -> Result<Program> {
+    let mut program = Program::new(compiler_options(options)?);
+    program.add_file("input.ts", source);
+    Ok(program)
+}
+
+pub struct CheckTask(Program);
+
+impl Task for CheckTask {
+    type Output = Vec<Diagnostic>;
+    type JsValue = Vec<JsDiagnostic>;
+
+    fn compute(&mut self) -> Result<Self::Output> {
+        Ok(self.0.check())
+    }
+
+    fn resolve(&mut self, _env: Env, output: Self::Output) -> Result<Self::JsValue> {
+        Ok(output.into_iter().map(JsDiagnostic::from).collect())
+    }
+}
+
+/// Type checks a single source string as `input.ts`.
+#[napi]
+pub fn check(source: String, options: Option<serde_json::Value>) -> Result<Vec<JsDiagnostic>> {
+    let program = single_file(source, options)?;
+    Ok(program
+        .check()
+        .into_iter()
+        .map(JsDiagnostic::from)
+        .collect())
+}
+
+#[napi]
+pub fn check_async(
+    source: String,
+    options: Option<serde_json::Value>,
+) -> Result<AsyncTask<CheckTask>> {
+    Ok(AsyncTask::new(CheckTask(single_file(source, options)?)))
+}
+
+/// A set of in-memory files checked together, mirroring [`tsc_rs::Program`].
+///
+/// File names are resolved against `/`, so `main.ts` is reported as `/main.ts`.
+#[napi(js_name = "Program")]
+pub struct JsProgram {
+    program: Program,
+}
+
+#[napi]
+impl JsProgram {
+    #[napi(constructor)]
+    pub fn new(files: HashMap<String, String>, options: Option<serde_json::Value>) -> Result<Self> {
+        Ok(Self {
+            program: load_project(files, compiler_options(options)?),
+        })
+    }
+
+    #[napi(getter)]
+    pub fn file_names(&self) -> Vec<String> {
+        self.program
+            .files()
+            .iter()
+            .map(|file| file.name.clone())
+            .collect()
+    }
+
+    /// Replaces a file's contents; returns `false` if the program has no such file.
+    #[napi]
+    pub fn update_file(&mut self, file_name: String, source: String) -> bool {
+        if self.program.file(&file_name).is_none() {
+            return false;
+        }
+        self.program.update_file(&file_name, source);
+        true
+    }
+
+    #[napi]
+    pub fn check(&self) -> Vec<JsDiagnostic> {
+        self.program
+            .check()
+            .into_iter()
+            .map(JsDiagnostic::from)
+            .collect()
+    }
+
+    /// Checks a snapshot of the program off the main thread.
+    #[napi]
+    pub fn check_async(&self) -> AsyncTask<CheckTask> {
+        AsyncTask::new(CheckTask(self.program.clone()))
+    }
+}
@@ -0,0 +1,48 @@
+//! Criterion benchmarks of each stage of checking a generated project, to measure changes
+//! to one stage without the noise of a whole run:
+//!
+//! ```text
+//! cargo bench --bench check
+//! ```
+//!
+//! Whole projects are timed end to end by `examples/bench.rs`.
+
+use criterion::{BatchSize, Criterion, criterion_group, criterion_main};
+use std::path::Path;
+use tsc_rs::parser::parse_typescript;
+use tsc_rs::{CompilerOptions, FileSystem, Program};
+
+mod generated;
+
+use generated::generated_project;
+
+// Small enough that each sample takes milliseconds
+const MODULES: usize = 50;
+
+fn bench_stages(c: &mut Criterion) {
+    let (fs, roots) = generated_project(MODULES);
+    let sources: Vec<String> = roots
+        .iter()
+        .map(|root| fs.read_file(Path::new(root)).unwrap())
+        .collect();
+    let load = || Program::load(CompilerOptions::default(), &roots, &fs);
+
+    c.bench_function("parse", |b| {
+        b.iter(|| {
+            for source in &sources {
+                parse_typescript(source).unwrap();
+            }
+        })
+    });
+    c.bench_function("load", |b| b.iter(load));
+    // Programs keep what they've checked, so each sample checks a fresh one
+    c.bench_function("check", |b| {
+        b.iter_batched(load, |program| program.check(), BatchSize::SmallInput)
+    });
+    c.bench_function("emit", |b| {
+        b.iter_batched(load, |program| program.emit(), BatchSize::SmallInput)
+    });
+}
+
+criterion_group!(benches, bench_stages);
+criterion_main!(benches);
@@ -0,0 +1,43 @@
+//! A project of interdependent modules generated to a given size, shared by the criterion
+//! benchmarks and the end-to-end runner in `examples/bench.rs`.
+
+use tsc_rs::InMemoryFileSystem;
+
+/// Modules that each import the two before them and export a class, a function, and an
+/// object type built from what they imported.
+pub fn generated_project(modules: usize) -> (InMemoryFileSystem, Vec<String>) {
+    let mut fs = InMemoryFileSystem::new();
+    let mut roots = Vec::new();
+    for i in 0..modules {
+        let mut source = String::new();
+        let deps: Vec<usize> = (i.saturating_sub(2)..i).collect();
+        for dep in &deps {
+            source.push_str(&format!(
+                "import {{ make{dep}, Shape{dep} }} from \"./module{dep}\";\n"
+            ));
+        }
+        source.push_str(&format!(
+            "export type Shape{i} = {{ id: number; name: string; tags: string[] }};\n\
+             export function make{i}(id: number): Shape{i} {{\n    \
+                 return {{ id, name: \"m{i}\", tags: [\"a\", \"b\"] }};\n\
+             }}\n\
+             export class Store{i} {{\n    \
+                 items: Shape{i}[] = [];\n    \
+                 add(item: Shape{i}): number {{\n        \
+                     this.items.push(item);\n        \
+                     return this.items.length;\n    \
+                 }}\n\
+             }}\n"
+        ));
+        for dep in &deps {
+            source.push_str(&format!(
+                "let from{dep}: Shape{dep} = make{dep}({i});\n\
+                 let names{dep} = [from{dep}].map((s) => s.name.toUpperCase());\n"
+            ));
+        }
+        let name = format!("/module{i}.ts");
+        fs.add_file(&name, source);
+        roots.push(name);
+    }
+    (fs, roots)
+}
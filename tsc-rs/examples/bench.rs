@@ -0,0 +1,161 @@
+//! Checks whole projects end to end and reports how long it took and how much memory it
+//! used, as a baseline for performance work:
+//!
+//! ```text
+//! cargo run --release --example bench -- [<project dir>...] [--iterations <n>] [--record <file>]
+//! ```
+//!
+//! Each project is every `.ts` file under its directory, outside `node_modules`. Without
+//! any directories, a generated project of interdependent modules is checked instead.
+//! `--record` appends one CSV line per project, tagged with the crate version, so results
+//! can be compared across releases. `cargo make bench` runs it over pinned releases of
+//! real projects, fetched into `target/bench-projects`.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+use tsc_rs::{CompilerOptions, PerformanceStats, Program, RealFileSystem};
+
+#[path = "../benches/generated/mod.rs"]
+mod generated;
+
+use generated::generated_project;
+
+// Size of the generated project used when no directories are given
+const GENERATED_MODULES: usize = 200;
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut projects = Vec::new();
+    let mut iterations = 5;
+    let mut record = None;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--iterations" => match rest.next().and_then(|n| n.parse().ok()) {
+                Some(n) if n > 0 => iterations = n,
+                _ => fail("Option '--iterations' expects a positive number."),
+            },
+            "--record" => match rest.next() {
+                Some(path) => record = Some(PathBuf::from(path)),
+                None => fail("Option '--record' expects a file name."),
+            },
+            dir => projects.push(PathBuf::from(dir)),
+        }
+    }
+
+    // Each project is loaded afresh for every run, so earlier results aren't reused
+    type Loader = Box<dyn Fn() -> Program>;
+    let loaders: Vec<(String, Loader)> = match projects.is_empty() {
+        true => {
+            let (fs, roots) = generated_project(GENERATED_MODULES);
+            vec![(
+                format!("generated-{}", GENERATED_MODULES),
+                Box::new(move || Program::load(CompilerOptions::default(), &roots, &fs)),
+            )]
+        }
+        false => projects
+            .into_iter()
+            .map(|dir| -> (String, Loader) {
+                let roots = project_sources(&dir);
+                (
+                    dir.display().to_string(),
+                    Box::new(move || {
+                        Program::load(CompilerOptions::default(), &roots, &RealFileSystem)
+                    }),
+                )
+            })
+            .collect(),
+    };
+
+    println!(
+        "{:<32}{:>8}{:>10}{:>12}{:>12}{:>12}",
+        "project", "files", "lines", "median", "fastest", "memory"
+    );
+    for (name, load) in loaders {
+        let mut times = Vec::new();
+        let mut stats = PerformanceStats::default();
+        for _ in 0..iterations {
+            let program = load();
+            let started = Instant::now();
+            stats = program.check_with_stats().1;
+            times.push(started.elapsed());
+        }
+        times.sort();
+        let median = times[times.len() / 2];
+        let memory_kb = stats.memory_used.map_or(0, |bytes| bytes / 1024);
+        println!(
+            "{:<32}{:>8}{:>10}{:>12}{:>12}{:>11}K",
+            name,
+            stats.files,
+            stats.lines,
+            millis(median),
+            millis(times[0]),
+            memory_kb
+        );
+        if let Some(path) = &record {
+            append_record(path, &name, &stats, median, memory_kb);
+        }
+    }
+}
+
+fn project_sources(dir: &Path) -> Vec<PathBuf> {
+    let mut sources = Vec::new();
+    collect_sources(dir, &mut sources);
+    if sources.is_empty() {
+        fail(&format!("No .ts files found under '{}'.", dir.display()));
+    }
+    sources.sort();
+    sources
+}
+
+fn collect_sources(dir: &Path, sources: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            if path.file_name().is_some_and(|name| name != "node_modules") {
+                collect_sources(&path, sources);
+            }
+        } else if path.extension().is_some_and(|ext| ext == "ts") {
+            sources.push(path);
+        }
+    }
+}
+
+fn append_record(path: &Path, project: &str, stats: &PerformanceStats, median: Duration, kb: u64) {
+    let is_new = !path.exists();
+    let file = std::fs::OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(path);
+    let written = file.and_then(|mut file| {
+        if is_new {
+            writeln!(file, "version,project,files,lines,median_ms,memory_kb")?;
+        }
+        writeln!(
+            file,
+            "{},{},{},{},{:.1},{}",
+            env!("CARGO_PKG_VERSION"),
+            project,
+            stats.files,
+            stats.lines,
+            median.as_secs_f64() * 1000.0,
+            kb
+        )
+    });
+    if let Err(err) = written {
+        fail(&format!("Could not write '{}': {}", path.display(), err));
+    }
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,130 @@
+//! Runs the conformance suite of a TypeScript checkout and compares the results with the
+//! cases that passed last time:
+//!
+//! ```text
+//! cargo run --example conformance -- <TypeScript checkout> [--passing <file>] [--update]
+//! ```
+//!
+//! `--passing` names the file listing previously passing cases, one per line
+//! (`conformance-passing.txt` by default); `--update` rewrites it with this run's results.
+
+use std::collections::BTreeSet;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::{Path, PathBuf};
+use tsc_rs::conformance::{parse_error_baseline, parse_test_case, passing_delta, run_case};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut checkout = None;
+    let mut passing_file = PathBuf::from("conformance-passing.txt");
+    let mut update = false;
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        match arg.as_str() {
+            "--passing" => match rest.next() {
+                Some(path) => passing_file = PathBuf::from(path),
+                None => fail("Option '--passing' expects a file name."),
+            },
+            "--update" => update = true,
+            path => checkout = Some(PathBuf::from(path)),
+        }
+    }
+    let Some(checkout) = checkout else {
+        fail("Expected the path of a TypeScript checkout.");
+    };
+
+    let mut cases = Vec::new();
+    collect_cases(&checkout.join("tests/cases/conformance"), &mut cases);
+    cases.sort();
+
+    let (mut skipped, mut crashed) = (0, 0);
+    let mut now_passing = BTreeSet::new();
+    for path in &cases {
+        let Ok(source) = std::fs::read_to_string(path) else {
+            skipped += 1;
+            continue;
+        };
+        let name = path
+            .strip_prefix(&checkout)
+            .unwrap_or(path)
+            .to_string_lossy()
+            .replace('\\', "/");
+        let case = parse_test_case(&name, &source);
+        if case.has_variants {
+            skipped += 1;
+            continue;
+        }
+        let stem = path.file_stem().unwrap_or_default().to_string_lossy();
+        let baseline = checkout.join(format!("tests/baselines/reference/{}.errors.txt", stem));
+        let expected = std::fs::read_to_string(baseline)
+            .map(|baseline| parse_error_baseline(&baseline))
+            .unwrap_or_default();
+        match catch_unwind(AssertUnwindSafe(|| run_case(&case, expected))) {
+            Ok(result) if result.passed() => {
+                now_passing.insert(name);
+            }
+            Ok(_) => {}
+            Err(_) => {
+                eprintln!("crashed: {}", name);
+                crashed += 1;
+            }
+        }
+    }
+
+    let run = cases.len() - skipped;
+    println!(
+        "{} of {} cases passed ({} skipped, {} crashed)",
+        now_passing.len(),
+        run,
+        skipped,
+        crashed
+    );
+    let passing: BTreeSet<String> = std::fs::read_to_string(&passing_file)
+        .map(|text| text.lines().map(str::to_string).collect())
+        .unwrap_or_default();
+    let (regressed, fixed) = passing_delta(&passing, &now_passing);
+    for name in &fixed {
+        println!("now passing: {}", name);
+    }
+    for name in &regressed {
+        println!("no longer passing: {}", name);
+    }
+    if update {
+        let text: String = now_passing
+            .iter()
+            .map(|name| format!("{}\n", name))
+            .collect();
+        if let Err(err) = std::fs::write(&passing_file, text) {
+            fail(&format!(
+                "Could not write '{}': {}",
+                passing_file.display(),
+                err
+            ));
+        }
+    }
+    if !regressed.is_empty() {
+        std::process::exit(1);
+    }
+}
+
+fn collect_cases(dir: &Path, cases: &mut Vec<PathBuf>) {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_cases(&path, cases);
+        } else if path
+            .extension()
+            .is_some_and(|ext| ext == "ts" || ext == "tsx")
+        {
+            cases.push(path);
+        }
+    }
+}
+
+fn fail(message: &str) -> ! {
+    eprintln!("error: {}", message);
+    std::process::exit(1);
+}
@@ -0,0 +1,24 @@
+//! Mutated TypeScript, checked and emitted as a whole program. Run with the dictionary
+//! and the fixtures as a seed corpus:
+//!
+//! ```text
+//! cargo +nightly fuzz run check corpus/check ../tests/fixtures -- -dict=typescript.dict
+//! ```
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tsc_rs::{CompilerOptions, Program};
+
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data).into_owned();
+    let options = CompilerOptions {
+        isolated_modules: true,
+        no_fallthrough_cases_in_switch: true,
+        ..Default::default()
+    };
+    let mut program = Program::new(options);
+    program.add_file("input.ts", source.as_str());
+    program.check();
+    let _ = program.emit();
+    program.completions_at_position("input.ts", (source.len() / 2) as u32);
+});
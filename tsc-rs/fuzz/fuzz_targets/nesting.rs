@@ -0,0 +1,30 @@
+//! Expressions and types nested or repeated thousands of times, which would overflow the
+//! stack or take quadratic time if recursion or relations weren't bounded.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tsc_rs::{CompilerOptions, Program};
+
+fuzz_target!(|input: (u8, u16)| {
+    let (shape, count) = input;
+    let n = count as usize;
+    let source = match shape % 8 {
+        0 => format!("let x = {}1{};", "(".repeat(n), ")".repeat(n)),
+        1 => format!("let x = {}1{};", "[".repeat(n), "]".repeat(n)),
+        2 => format!("let x = {}1{};", "{ a: ".repeat(n), " }".repeat(n)),
+        3 => format!("let x: {}number{} = [];", "Array<".repeat(n), ">".repeat(n)),
+        4 => format!("let x = {}1;", "!".repeat(n)),
+        5 => format!("let o: any = {{}};\nlet x = o{};", ".a".repeat(n)),
+        6 => format!("let f = {}1;", "() => ".repeat(n)),
+        _ => format!(
+            "type U = {};\nlet u: U = 0;",
+            (0..n.max(1))
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(" | ")
+        ),
+    };
+    let mut program = Program::new(CompilerOptions::default());
+    program.add_file("input.ts", source);
+    program.check();
+});
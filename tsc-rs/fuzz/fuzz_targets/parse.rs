@@ -0,0 +1,12 @@
+//! Arbitrary bytes, parsed as each kind of TypeScript file.
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use tsc_rs::parser::{parse_typescript_recovering, source_type_for};
+
+fuzz_target!(|data: &[u8]| {
+    let source = String::from_utf8_lossy(data);
+    for file_name in ["input.ts", "input.tsx", "input.d.ts"] {
+        parse_typescript_recovering(&source, source_type_for(file_name));
+    }
+});
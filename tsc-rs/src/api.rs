@@ -0,0 +1,157 @@
+//! JSON-in, JSON-out entry points shared by the JavaScript bindings.
+
+use crate::diagnostics::Diagnostic;
+use crate::host::InMemoryFileSystem;
+use crate::options::CompilerOptions;
+use crate::program::Program;
+use serde_json::{Value, json};
+use std::collections::BTreeMap;
+
+/// Parses a JSON `compilerOptions` object; an empty string means the defaults.
+pub fn parse_options(options: &str) -> Result<CompilerOptions, String> {
+    if options.trim().is_empty() {
+        return Ok(CompilerOptions::default());
+    }
+    serde_json::from_str(options).map_err(|err| format!("Invalid compiler options: {}", err))
+}
+
+/// Converts an already-parsed `compilerOptions` value; `null` means the defaults.
+pub fn options_from_value(options: Value) -> Result<CompilerOptions, String> {
+    if options.is_null() {
+        return Ok(CompilerOptions::default());
+    }
+    serde_json::from_value(options).map_err(|err| format!("Invalid compiler options: {}", err))
+}
+
+/// Loads `(path, source)` pairs as one program with every file as a root.
+///
+/// Relative paths are resolved against `/`.
+pub fn load_project(
+    files: impl IntoIterator<Item = (String, String)>,
+    options: CompilerOptions,
+) -> Program {
+    let mut fs = InMemoryFileSystem::new();
+    let mut roots = Vec::new();
+    for (path, source) in files {
+        fs.add_file(&path, source);
+        roots.push(path);
+    }
+    Program::load(options, &roots, &fs)
+}
+
+/// `{ file, start, length, message, category, code }`, with `start`/`length` null when
+/// there is no span, `category` either `"error"` or `"warning"`, and `code` TypeScript's
+/// number for the message or null.
+pub fn diagnostic_to_json(diagnostic: &Diagnostic) -> Value {
+    json!({
+        "file": diagnostic.file_name,
+        "start": diagnostic.span.map(|span| span.start),
+        "length": diagnostic.span.map(|span| span.size()),
+        "message": diagnostic.message,
+        "category": diagnostic.category.to_string(),
+        "code": diagnostic.code,
+    })
+}
+
+pub fn diagnostics_to_json(diagnostics: &[Diagnostic]) -> String {
+    Value::from(
+        diagnostics
+            .iter()
+            .map(diagnostic_to_json)
+            .collect::<Vec<_>>(),
+    )
+    .to_string()
+}
+
+/// Checks a single source string, returning its diagnostics as a JSON array.
+pub fn check(source: &str, options: &str) -> Result<String, String> {
+    let options = parse_options(options)?;
+    Ok(diagnostics_to_json(&crate::check(source, &options)))
+}
+
+/// Checks a JSON object of `{ "path": "source" }` as one program; see [`load_project`].
+pub fn check_project(files: &str, options: &str) -> Result<String, String> {
+    let options = parse_options(options)?;
+    let files: BTreeMap<String, String> =
+        serde_json::from_str(files).map_err(|err| format!("Invalid files: {}", err))?;
+    Ok(diagnostics_to_json(&load_project(files, options).check()))
+}
+
+/// Loads a JSON object of `{ "path": "source" }` and returns its import graph as JSON; see
+/// [`ModuleGraph::to_json`](crate::graph::ModuleGraph::to_json).
+pub fn module_graph(files: &str, options: &str) -> Result<String, String> {
+    let options = parse_options(options)?;
+    let files: BTreeMap<String, String> =
+        serde_json::from_str(files).map_err(|err| format!("Invalid files: {}", err))?;
+    Ok(load_project(files, options)
+        .module_graph()
+        .to_json()
+        .to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_returns_json_diagnostics() {
+        let output = check("let x: string = 1;", "").unwrap();
+        let diagnostics: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(
+            diagnostics,
+            json!([{
+                "file": "input.ts",
+                "start": 4,
+                "length": 9,
+                "message": "Type 'number' is not assignable to type 'string'",
+                "category": "error",
+                "code": 2322,
+            }])
+        );
+
+        assert_eq!(check("let x = 1;", "{}").unwrap(), "[]");
+        let off = r#"{ "diagnosticSeverities": { "2322": "off" } }"#;
+        assert_eq!(check("let x: string = 1;", off).unwrap(), "[]");
+        assert!(
+            check("", r#"{ "notAnOption": true }"#)
+                .unwrap_err()
+                .contains("notAnOption")
+        );
+    }
+
+    #[test]
+    fn test_check_project() {
+        let files = json!({
+            "main.ts": "import { name } from './util';\nlet n: number = name;",
+            "util.ts": "export const name = 'util';",
+        });
+        let output = check_project(&files.to_string(), "").unwrap();
+        let diagnostics: Value = serde_json::from_str(&output).unwrap();
+        assert_eq!(diagnostics.as_array().unwrap().len(), 1);
+        assert_eq!(diagnostics[0]["file"], "/main.ts");
+
+        assert!(check_project("[]", "").is_err());
+    }
+
+    #[test]
+    fn test_module_graph() {
+        let files = json!({
+            "main.ts": "import type { Name } from './util';",
+            "util.ts": "export type Name = string;",
+        });
+        let graph: Value =
+            serde_json::from_str(&module_graph(&files.to_string(), "").unwrap()).unwrap();
+        assert_eq!(graph["files"], json!(["/main.ts", "/util.ts"]));
+        assert_eq!(graph["edges"][0]["to"], "/util.ts");
+        assert_eq!(graph["edges"][0]["typeOnly"], true);
+    }
+
+    #[test]
+    fn test_options_from_value() {
+        assert_eq!(
+            options_from_value(Value::Null).unwrap(),
+            CompilerOptions::default()
+        );
+        assert!(options_from_value(json!({ "bogus": 1 })).is_err());
+    }
+}
@@ -0,0 +1,404 @@
+// Apparent members of primitives and arrays, standing in for lib.d.ts until it is loaded
+use crate::types::{AliasType, ClassType, ObjectType, Property, Type, TypeParameter};
+use std::sync::{Arc, LazyLock};
+
+fn method(name: &str, params: Vec<Type>, return_type: Type) -> Property {
+    Property::new(name, Type::function(params, return_type))
+}
+
+/// `method`, with only its first `required` parameters needing an argument.
+fn optional_after(required: usize, mut method: Property) -> Property {
+    let function = match &mut method.ty {
+        Type::Generic { body, .. } => Arc::make_mut(body),
+        function => function,
+    };
+    if let Type::Function {
+        required: count, ..
+    } = function
+    {
+        *count = required;
+    }
+    method
+}
+
+/// `method`, with its last parameter taking any number of arguments.
+fn variadic(mut method: Property) -> Property {
+    if let Type::Function { rest, .. } = &mut method.ty {
+        *rest = true;
+    }
+    method
+}
+
+/// A method generic over one type parameter, built from its placeholder `T` by `signature`.
+fn generic_method(
+    name: &str,
+    type_param: &str,
+    signature: impl FnOnce(Type) -> (Vec<Type>, Type),
+) -> Property {
+    let type_param = Arc::new(TypeParameter {
+        name: type_param.to_string(),
+        constraint: None,
+        default: None,
+        is_const: false,
+    });
+    let (params, return_type) = signature(Type::TypeParameter(type_param.clone()));
+    Property::new(
+        name,
+        Type::Generic {
+            type_params: vec![type_param],
+            body: Arc::new(Type::function(params, return_type)),
+        },
+    )
+}
+
+fn callback(params: Vec<Type>, return_type: Type) -> Type {
+    Type::function(params, return_type)
+}
+
+/// The `(value, index, array)` callback array methods call for each element.
+fn element_callback(array: &Type, elem: &Type, return_type: Type) -> Type {
+    callback(vec![elem.clone(), Type::Number, array.clone()], return_type)
+}
+
+fn readonly(name: &str, ty: Type) -> Property {
+    Property {
+        readonly: true,
+        ..Property::new(name, ty)
+    }
+}
+
+// Constructors of the lib's `Error` family, whose instances share the `Error` shape
+const ERROR_CLASSES: &[&str] = &[
+    "Error",
+    "EvalError",
+    "RangeError",
+    "ReferenceError",
+    "SyntaxError",
+    "TypeError",
+    "URIError",
+];
+
+/// A lib interface named `name`, which messages print by its name.
+fn interface(name: &str, properties: Vec<Property>) -> Arc<AliasType> {
+    let interface = Arc::new(AliasType::new(name));
+    interface.resolve(Type::ObjectType(Arc::new(ObjectType::new(properties))));
+    interface
+}
+
+/// The interface of the instances the lib's error constructor `name` makes, like
+/// `RangeError`, each of them an `Error`.
+fn error_interface(name: &str) -> Option<Type> {
+    static ERRORS: LazyLock<Vec<Arc<AliasType>>> = LazyLock::new(|| {
+        ERROR_CLASSES
+            .iter()
+            .map(|name| {
+                interface(
+                    name,
+                    vec![
+                        Property::new("name", Type::String),
+                        Property::new("message", Type::String),
+                        Property {
+                            optional: true,
+                            ..Property::new("stack", Type::String)
+                        },
+                    ],
+                )
+            })
+            .collect()
+    });
+    let index = ERROR_CLASSES.iter().position(|class| *class == name)?;
+    Some(Type::Alias(ERRORS[index].clone()))
+}
+
+/// The lib's `Error` interface.
+pub fn error_type() -> Type {
+    error_interface("Error").expect("Error is one of the error classes")
+}
+
+/// The lib's `RegExp` interface, the type of regular expression literals.
+pub fn regexp_type() -> Type {
+    static REGEXP: LazyLock<Arc<AliasType>> = LazyLock::new(|| {
+        // `RegExpExecArray` is the matched strings, with where the match was
+        let exec_array = Type::Array(Arc::new(Type::String));
+        interface(
+            "RegExp",
+            vec![
+                method(
+                    "exec",
+                    vec![Type::String],
+                    Type::Union(vec![exec_array, Type::Null]),
+                ),
+                method("test", vec![Type::String], Type::Boolean),
+                readonly("source", Type::String),
+                readonly("flags", Type::String),
+                readonly("global", Type::Boolean),
+                readonly("ignoreCase", Type::Boolean),
+                readonly("multiline", Type::Boolean),
+                readonly("dotAll", Type::Boolean),
+                readonly("sticky", Type::Boolean),
+                readonly("unicode", Type::Boolean),
+                Property::new("lastIndex", Type::Number),
+            ],
+        )
+    });
+    Type::Alias(REGEXP.clone())
+}
+
+/// The type a global type name refers to when nothing in the file declares it.
+pub fn global_type(name: &str) -> Option<Type> {
+    match name {
+        "RegExp" => Some(regexp_type()),
+        name => error_interface(name),
+    }
+}
+
+/// The static side of the lib's `Promise`.
+fn promise_constructor() -> Type {
+    static PROMISE: LazyLock<Type> = LazyLock::new(|| {
+        Type::ObjectType(Arc::new(ObjectType::new(vec![
+            // Resolving with a promise adopts its value, which `Type::promise` unwraps
+            optional_after(
+                0,
+                generic_method("resolve", "T", |value| {
+                    (vec![value.clone()], Type::promise(value))
+                }),
+            ),
+            optional_after(
+                0,
+                method("reject", vec![Type::Any], Type::promise(Type::Never)),
+            ),
+        ])))
+    });
+    PROMISE.clone()
+}
+
+/// The type of a global value when nothing in the file declares its name.
+pub fn global_value(name: &str) -> Option<Type> {
+    match name {
+        "Promise" => return Some(promise_constructor()),
+        // Not literals, since `NaN` isn't equal to itself and `Infinity` has no literal type
+        "NaN" | "Infinity" => return Some(Type::Number),
+        _ => {}
+    }
+    let Type::ObjectType(instance) = global_type(name)?.resolved().clone() else {
+        return None;
+    };
+    Some(Type::Class(Arc::new(ClassType {
+        name: name.to_string(),
+        params: vec![Type::String],
+        instance: (*instance).clone(),
+        statics: ObjectType::default(),
+    })))
+}
+
+/// Every global value the lib declares, by name, as `globalThis` has them.
+pub fn global_values() -> Vec<(&'static str, Type)> {
+    ["Promise", "NaN", "Infinity", "RegExp"]
+        .iter()
+        .chain(ERROR_CLASSES)
+        .filter_map(|&name| Some((name, global_value(name)?)))
+        .collect()
+}
+
+/// The wrapper interface of a primitive, whose members are built the first time any
+/// value of that primitive is used and shared from then on.
+fn wrapper_interface(ty: &Type) -> Option<&'static ObjectType> {
+    static STRING: LazyLock<ObjectType> = LazyLock::new(|| {
+        ObjectType::new(vec![
+            readonly("length", Type::Number),
+            method("charAt", vec![Type::Number], Type::String),
+            method("charCodeAt", vec![Type::Number], Type::Number),
+            optional_after(
+                1,
+                method("includes", vec![Type::String, Type::Number], Type::Boolean),
+            ),
+            optional_after(
+                1,
+                method("indexOf", vec![Type::String, Type::Number], Type::Number),
+            ),
+            optional_after(
+                1,
+                method(
+                    "split",
+                    vec![Type::String, Type::Number],
+                    Type::Array(Arc::new(Type::String)),
+                ),
+            ),
+            method("toLowerCase", vec![], Type::String),
+            method("toUpperCase", vec![], Type::String),
+            method("trim", vec![], Type::String),
+        ])
+    });
+    static NUMBER: LazyLock<ObjectType> = LazyLock::new(|| {
+        ObjectType::new(vec![
+            optional_after(0, method("toFixed", vec![Type::Number], Type::String)),
+            optional_after(0, method("toString", vec![Type::Number], Type::String)),
+        ])
+    });
+    static BOOLEAN: LazyLock<ObjectType> =
+        LazyLock::new(|| ObjectType::new(vec![method("valueOf", vec![], Type::Boolean)]));
+
+    match ty {
+        Type::String | Type::StringLiteral(_) => Some(&STRING),
+        Type::Number | Type::NumberLiteral(_) => Some(&NUMBER),
+        Type::Boolean | Type::BooleanLiteral(_) => Some(&BOOLEAN),
+        _ => None,
+    }
+}
+
+/// The property `name` of `ty`'s wrapper or lib interface, building no more of the
+/// interface than looking it up needs.
+pub fn apparent_property(ty: &Type, name: &str) -> Option<Property> {
+    match wrapper_interface(ty) {
+        Some(interface) => interface.property(name).cloned(),
+        None => apparent_properties(ty).into_iter().find(|p| p.name == name),
+    }
+}
+
+/// The properties a value of type `ty` exposes through its wrapper or lib interface.
+pub fn apparent_properties(ty: &Type) -> Vec<Property> {
+    if let Some(interface) = wrapper_interface(ty) {
+        return interface.properties.clone();
+    }
+    match ty {
+        Type::Array(elem) => vec![
+            Property::new("length", Type::Number),
+            variadic(method("push", vec![ty.clone()], Type::Number)),
+            method(
+                "pop",
+                vec![],
+                Type::Union(vec![(**elem).clone(), Type::Undefined]),
+            ),
+            optional_after(
+                1,
+                method(
+                    "includes",
+                    vec![(**elem).clone(), Type::Number],
+                    Type::Boolean,
+                ),
+            ),
+            optional_after(
+                1,
+                method(
+                    "indexOf",
+                    vec![(**elem).clone(), Type::Number],
+                    Type::Number,
+                ),
+            ),
+            optional_after(0, method("join", vec![Type::String], Type::String)),
+            generic_method("map", "U", |result| {
+                (
+                    vec![element_callback(ty, elem, result.clone())],
+                    Type::Array(Arc::new(result)),
+                )
+            }),
+            method(
+                "filter",
+                vec![element_callback(ty, elem, Type::Unknown)],
+                ty.clone(),
+            ),
+            method(
+                "find",
+                vec![element_callback(ty, elem, Type::Unknown)],
+                Type::Union(vec![(**elem).clone(), Type::Undefined]),
+            ),
+            method(
+                "findIndex",
+                vec![element_callback(ty, elem, Type::Unknown)],
+                Type::Number,
+            ),
+            method(
+                "some",
+                vec![element_callback(ty, elem, Type::Unknown)],
+                Type::Boolean,
+            ),
+            method(
+                "every",
+                vec![element_callback(ty, elem, Type::Unknown)],
+                Type::Boolean,
+            ),
+            method(
+                "forEach",
+                vec![element_callback(ty, elem, Type::Void)],
+                Type::Void,
+            ),
+            optional_after(
+                1,
+                generic_method("reduce", "U", |result| {
+                    let reducer = callback(
+                        vec![result.clone(), (**elem).clone(), Type::Number, ty.clone()],
+                        result.clone(),
+                    );
+                    (vec![reducer, result.clone()], result)
+                }),
+            ),
+        ],
+        Type::Tuple(types) => vec![readonly("length", Type::NumberLiteral(types.len() as f64))],
+        Type::Promise(value) => vec![
+            optional_after(
+                0,
+                generic_method("then", "TResult", |result| {
+                    (
+                        vec![
+                            callback(vec![(**value).clone()], result.clone()),
+                            callback(vec![Type::Any], result.clone()),
+                        ],
+                        Type::promise(result),
+                    )
+                }),
+            ),
+            optional_after(
+                0,
+                generic_method("catch", "TResult", |result| {
+                    (
+                        vec![callback(vec![Type::Any], result.clone())],
+                        Type::promise(Type::Union(vec![(**value).clone(), result])),
+                    )
+                }),
+            ),
+            optional_after(
+                0,
+                method(
+                    "finally",
+                    vec![callback(vec![], Type::Void)],
+                    Type::Promise(value.clone()),
+                ),
+            ),
+        ],
+        Type::Readonly(ty) => apparent_properties(ty)
+            .into_iter()
+            .filter(|p| p.name != "push" && p.name != "pop")
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apparent_properties() {
+        let string_members = apparent_properties(&Type::StringLiteral("hi".to_string()));
+        assert!(string_members.iter().any(|p| p.name == "length"));
+
+        let array_members = apparent_properties(&Type::Array(Arc::new(Type::Number)));
+        let pop = array_members.iter().find(|p| p.name == "pop").unwrap();
+        assert_eq!(pop.ty.to_string(), "() => number | undefined");
+
+        assert!(apparent_properties(&Type::Any).is_empty());
+    }
+
+    #[test]
+    fn test_wrapper_interfaces_are_shared() {
+        let string = wrapper_interface(&Type::String).unwrap();
+        let literal = wrapper_interface(&Type::StringLiteral("hi".to_string())).unwrap();
+        assert!(std::ptr::eq(string, literal));
+
+        let upper = apparent_property(&Type::String, "toUpperCase").unwrap();
+        assert_eq!(upper.ty.to_string(), "() => string");
+        assert!(apparent_property(&Type::Number, "toUpperCase").is_none());
+        let pop = apparent_property(&Type::Array(Arc::new(Type::Boolean)), "pop").unwrap();
+        assert_eq!(pop.ty.to_string(), "() => boolean | undefined");
+    }
+}
@@ -0,0 +1,473 @@
+use crate::parser::{parse_typescript_recovering, source_type_for};
+use crate::program::Program;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use std::collections::HashSet;
+use std::path::{Component, Path};
+
+/// A replacement of the text at `span`; an empty span inserts.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TextEdit {
+    pub span: Span,
+    pub new_text: String,
+}
+
+/// A change an editor can offer to make to one file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CodeFix {
+    /// What the fix does, like `Remove unused import 'x'`.
+    pub description: String,
+    pub file_name: String,
+    pub edits: Vec<TextEdit>,
+}
+
+/// The names a file refers to and declares, as written, whatever scope they're in.
+#[derive(Default)]
+struct Names {
+    referenced: Vec<(String, Span)>,
+    declared: HashSet<String>,
+}
+
+impl<'a> Visit<'a> for Names {
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        self.referenced.push((it.name.to_string(), it.span));
+    }
+
+    fn visit_binding_identifier(&mut self, it: &BindingIdentifier<'a>) {
+        self.declared.insert(it.name.to_string());
+    }
+}
+
+fn text(source: &str, span: Span) -> &str {
+    &source[span.start as usize..span.end as usize]
+}
+
+/// The local name a specifier brings into scope.
+fn local_name<'s>(specifier: &'s ImportDeclarationSpecifier) -> &'s str {
+    match specifier {
+        ImportDeclarationSpecifier::ImportSpecifier(import) => &import.local.name,
+        ImportDeclarationSpecifier::ImportDefaultSpecifier(import) => &import.local.name,
+        ImportDeclarationSpecifier::ImportNamespaceSpecifier(import) => &import.local.name,
+    }
+}
+
+/// `decl` written with only `specifiers`, keeping its quotes, attributes and semicolon.
+fn import_text(
+    decl: &ImportDeclaration,
+    source: &str,
+    specifiers: &[&ImportDeclarationSpecifier],
+) -> String {
+    let mut clauses = Vec::new();
+    let mut named = Vec::new();
+    for specifier in specifiers {
+        match specifier {
+            ImportDeclarationSpecifier::ImportSpecifier(import) => {
+                named.push(text(source, import.span))
+            }
+            specifier => clauses.push(text(source, specifier.span()).to_string()),
+        }
+    }
+    if !named.is_empty() {
+        clauses.push(format!("{{ {} }}", named.join(", ")));
+    }
+    let keyword = match decl.import_kind.is_type() {
+        true => "import type",
+        false => "import",
+    };
+    let attributes = decl.with_clause.as_ref().map_or(String::new(), |with| {
+        format!(" {}", text(source, with.span))
+    });
+    let semicolon = match text(source, decl.span).ends_with(';') {
+        true => ";",
+        false => "",
+    };
+    format!(
+        "{} {} from {}{}{}",
+        keyword,
+        clauses.join(", "),
+        text(source, decl.source.span),
+        attributes,
+        semicolon
+    )
+}
+
+/// The span of a whole statement along with the line break ending it.
+fn with_line_break(source: &str, span: Span) -> Span {
+    let rest = &source[span.end as usize..];
+    let line_break = match rest {
+        _ if rest.starts_with("\r\n") => 2,
+        _ if rest.starts_with('\n') => 1,
+        _ => 0,
+    };
+    Span::new(span.start, span.end + line_break)
+}
+
+/// How a file at `from` imports the file at `to`: a relative path without the extension.
+fn module_specifier(from: &str, to: &str) -> String {
+    let to = [".d.ts", ".tsx", ".ts"]
+        .iter()
+        .find_map(|extension| to.strip_suffix(extension))
+        .unwrap_or(to);
+    let from_dir: Vec<Component> = Path::new(from)
+        .parent()
+        .map_or_else(Vec::new, |dir| dir.components().collect());
+    let to: Vec<Component> = Path::new(to).components().collect();
+    let common = from_dir.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut parts: Vec<String> = vec!["..".to_string(); from_dir.len() - common];
+    if parts.is_empty() {
+        parts.push(".".to_string());
+    }
+    parts.extend(
+        to[common..]
+            .iter()
+            .map(|part| part.as_os_str().to_string_lossy().into_owned()),
+    );
+    parts.join("/")
+}
+
+impl Program {
+    /// Fixes for the problems in `span` of a file: removing the unused imports there, and
+    /// importing names used there that another file exports.
+    pub fn code_fixes(&self, file_name: &str, span: Span) -> Vec<CodeFix> {
+        let overlaps = |other: Span| other.start <= span.end && span.start <= other.end;
+        let mut fixes: Vec<CodeFix> = self
+            .unused_import_fixes(file_name)
+            .into_iter()
+            .filter(|(decl, _)| overlaps(*decl))
+            .map(|(_, fix)| fix)
+            .collect();
+        fixes.extend(
+            self.missing_import_fixes(file_name)
+                .into_iter()
+                .filter(|(reference, _)| overlaps(*reference))
+                .map(|(_, fix)| fix),
+        );
+        fixes
+    }
+
+    /// A fix removing every import the file doesn't use, if there are any.
+    pub fn remove_unused_imports(&self, file_name: &str) -> Option<CodeFix> {
+        let edits: Vec<TextEdit> = self
+            .unused_import_fixes(file_name)
+            .into_iter()
+            .flat_map(|(_, fix)| fix.edits)
+            .collect();
+        (!edits.is_empty()).then(|| CodeFix {
+            description: "Remove unused imports".to_string(),
+            file_name: file_name.to_string(),
+            edits,
+        })
+    }
+
+    /// A fix for each import declaration with names the file never refers to, with the
+    /// span of the declaration.
+    fn unused_import_fixes(&self, file_name: &str) -> Vec<(Span, CodeFix)> {
+        let Some(file) = self.file(file_name) else {
+            return Vec::new();
+        };
+        let source = &file.source;
+        let parsed = parse_typescript_recovering(source, source_type_for(file_name));
+        let mut names = Names::default();
+        names.visit_program(&parsed.program);
+        let used: HashSet<&str> = names.referenced.iter().map(|(n, _)| n.as_str()).collect();
+
+        let mut fixes = Vec::new();
+        for statement in &parsed.program.body {
+            let Statement::ImportDeclaration(decl) = statement else {
+                continue;
+            };
+            // Imports for their side effects have nothing to be unused
+            let Some(specifiers) = &decl.specifiers else {
+                continue;
+            };
+            let (kept, unused): (Vec<_>, Vec<_>) = specifiers
+                .iter()
+                .partition(|specifier| used.contains(local_name(specifier)));
+            if unused.is_empty() {
+                continue;
+            }
+            let unused: Vec<&str> = unused.into_iter().map(local_name).collect();
+            let (description, edit) = match kept.is_empty() {
+                true => (
+                    format!("Remove import from {}", text(source, decl.source.span)),
+                    TextEdit {
+                        span: with_line_break(source, decl.span),
+                        new_text: String::new(),
+                    },
+                ),
+                false => (
+                    format!("Remove unused import '{}'", unused.join("', '")),
+                    TextEdit {
+                        span: decl.span,
+                        new_text: import_text(decl, source, &kept),
+                    },
+                ),
+            };
+            fixes.push((
+                decl.span,
+                CodeFix {
+                    description,
+                    file_name: file_name.to_string(),
+                    edits: vec![edit],
+                },
+            ));
+        }
+        fixes
+    }
+
+    /// A fix for each name the file uses without declaring or importing it, importing it
+    /// from each other file that exports it, with the span of the use.
+    fn missing_import_fixes(&self, file_name: &str) -> Vec<(Span, CodeFix)> {
+        let Some(file) = self.file(file_name) else {
+            return Vec::new();
+        };
+        let source = &file.source;
+        let parsed = parse_typescript_recovering(source, source_type_for(file_name));
+        let mut names = Names::default();
+        names.visit_program(&parsed.program);
+
+        let checked = self.check_files();
+        let mut offered = HashSet::new();
+        let mut fixes = Vec::new();
+        for (name, span) in &names.referenced {
+            if names.declared.contains(name) || !offered.insert(name) {
+                continue;
+            }
+            for (other, checked) in self.files().iter().zip(&checked) {
+                if other.name == file_name
+                    || other.name.contains("/node_modules/")
+                    || !checked
+                        .checker
+                        .exported_symbols(&other.name)
+                        .iter()
+                        .any(|export| export.name == name.as_str())
+                {
+                    continue;
+                }
+                let specifier = module_specifier(file_name, &other.name);
+                fixes.push((
+                    *span,
+                    CodeFix {
+                        description: format!("Add import from \"{}\"", specifier),
+                        file_name: file_name.to_string(),
+                        edits: vec![import_edit(&parsed.program, source, name, &specifier)],
+                    },
+                ));
+            }
+        }
+        fixes
+    }
+
+    /// A fix sorting each run of consecutive imports by module and the names each imports,
+    /// and removing the names the file doesn't use, if that changes anything.
+    pub fn organize_imports(&self, file_name: &str) -> Option<CodeFix> {
+        let file = self.file(file_name)?;
+        let source = &file.source;
+        let parsed = parse_typescript_recovering(source, source_type_for(file_name));
+        let mut names = Names::default();
+        names.visit_program(&parsed.program);
+        let used: HashSet<&str> = names.referenced.iter().map(|(n, _)| n.as_str()).collect();
+
+        // Runs of imports, split where another statement or a side-effect import comes
+        // between, since those may depend on what's imported before them
+        let mut runs: Vec<Vec<&ImportDeclaration>> = vec![Vec::new()];
+        for statement in &parsed.program.body {
+            match statement {
+                Statement::ImportDeclaration(decl) if decl.specifiers.is_some() => {
+                    runs.last_mut().unwrap().push(decl)
+                }
+                _ => runs.push(Vec::new()),
+            }
+        }
+
+        let mut edits = Vec::new();
+        for run in runs.into_iter().filter(|run| !run.is_empty()) {
+            let mut imports: Vec<(&str, String)> = run
+                .iter()
+                .filter_map(|decl| {
+                    let mut kept: Vec<&ImportDeclarationSpecifier> = decl
+                        .specifiers
+                        .iter()
+                        .flatten()
+                        .filter(|specifier| used.contains(local_name(specifier)))
+                        .collect();
+                    if kept.is_empty() {
+                        return None;
+                    }
+                    kept.sort_by_key(|specifier| match specifier {
+                        ImportDeclarationSpecifier::ImportSpecifier(import) => {
+                            (1, import.imported.name().to_lowercase())
+                        }
+                        // Default and namespace imports come before the braces
+                        _ => (0, String::new()),
+                    });
+                    Some((decl.source.value.as_str(), import_text(decl, source, &kept)))
+                })
+                .collect();
+            imports.sort_by_key(|(module, _)| module.to_lowercase());
+
+            let span = Span::new(run[0].span.start, run[run.len() - 1].span.end);
+            let organized: Vec<String> = imports.into_iter().map(|(_, text)| text).collect();
+            let organized = organized.join("\n");
+            if organized != text(source, span) {
+                let span = match organized.is_empty() {
+                    true => with_line_break(source, span),
+                    false => span,
+                };
+                edits.push(TextEdit {
+                    span,
+                    new_text: organized,
+                });
+            }
+        }
+        (!edits.is_empty()).then(|| CodeFix {
+            description: "Organize imports".to_string(),
+            file_name: file_name.to_string(),
+            edits,
+        })
+    }
+}
+
+/// The edit importing `name` from `specifier`: added to an existing import of the module
+/// with braces, or as a new import after the others.
+fn import_edit(
+    program: &oxc_ast::ast::Program,
+    source: &str,
+    name: &str,
+    specifier: &str,
+) -> TextEdit {
+    let imports = program.body.iter().filter_map(|statement| match statement {
+        Statement::ImportDeclaration(decl) => Some(decl),
+        _ => None,
+    });
+    let mut last_import = None;
+    for decl in imports {
+        last_import = Some(decl.span);
+        let mut named = decl.specifiers.iter().flatten().filter_map(|s| match s {
+            ImportDeclarationSpecifier::ImportSpecifier(import) => Some(import.span),
+            _ => None,
+        });
+        if decl.source.value == specifier
+            && !decl.import_kind.is_type()
+            && let Some(last) = named.next_back()
+        {
+            return TextEdit {
+                span: Span::new(last.end, last.end),
+                new_text: format!(", {}", name),
+            };
+        }
+    }
+    let statement = format!("import {{ {} }} from \"{}\";\n", name, specifier);
+    match last_import {
+        Some(span) => {
+            let end = with_line_break(source, span).end;
+            TextEdit {
+                span: Span::new(end, end),
+                new_text: match end == span.end {
+                    // The last import ends the file without a line break
+                    true => format!("\n{}", statement.trim_end()),
+                    false => statement,
+                },
+            }
+        }
+        None => TextEdit {
+            span: Span::new(0, 0),
+            new_text: statement,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestProject;
+
+    const MATH: &str = "export function square(n: number): number { return n * n; }\nexport const pi = 3.14;\nexport type Angle = number;";
+    const UTIL: &str = "export function clamp(n: number): number { return n; }";
+
+    fn apply(source: &str, fix: &CodeFix) -> String {
+        let mut edits = fix.edits.clone();
+        edits.sort_by_key(|edit| std::cmp::Reverse(edit.span.start));
+        let mut source = source.to_string();
+        for edit in edits {
+            source.replace_range(
+                edit.span.start as usize..edit.span.end as usize,
+                &edit.new_text,
+            );
+        }
+        source
+    }
+
+    fn program(main: &str) -> Program {
+        TestProject::new()
+            .file("/src/math.ts", MATH)
+            .file("/src/lib/util.ts", UTIL)
+            .file("/src/main.ts", main)
+            .roots(&["/src/main.ts", "/src/lib/util.ts"])
+            .program()
+    }
+
+    #[test]
+    fn test_remove_unused_imports() {
+        let main = "import { square, pi, type Angle } from \"./math\";\nimport { clamp } from \"./lib/util\";\nlet a: Angle = square(2);\n";
+        let program = program(main);
+        let fix = program.remove_unused_imports("/src/main.ts").unwrap();
+        assert_eq!(
+            apply(main, &fix),
+            "import { square, type Angle } from \"./math\";\nlet a: Angle = square(2);\n"
+        );
+
+        let span = Span::new(0, 5);
+        let fixes = program.code_fixes("/src/main.ts", span);
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].description, "Remove unused import 'pi'");
+
+        assert!(program.remove_unused_imports("/src/math.ts").is_none());
+    }
+
+    #[test]
+    fn test_add_missing_imports() {
+        let main = "import { pi } from \"./math\";\nlet a = square(pi);\nlet b = clamp(a);";
+        let program = program(main);
+        let at = |text: &str| {
+            let start = main.find(text).unwrap() as u32;
+            program.code_fixes("/src/main.ts", Span::new(start, start + 1))
+        };
+
+        let fixes = at("square");
+        assert_eq!(fixes.len(), 1);
+        assert_eq!(fixes[0].description, "Add import from \"./math\"");
+        assert!(apply(main, &fixes[0]).starts_with("import { pi, square } from \"./math\";\n"));
+
+        let fixes = at("clamp");
+        assert_eq!(fixes[0].description, "Add import from \"./lib/util\"");
+        assert!(apply(main, &fixes[0]).starts_with(
+            "import { pi } from \"./math\";\nimport { clamp } from \"./lib/util\";\nlet a"
+        ));
+        assert!(at("let b").is_empty());
+
+        assert_eq!(
+            module_specifier("/src/lib/util.ts", "/src/math.ts"),
+            "../math"
+        );
+    }
+
+    #[test]
+    fn test_organize_imports() {
+        let main = "import { square, pi } from \"./math\";\nimport \"./polyfill\";\nimport { clamp } from \"./lib/util\";\nimport { Angle } from \"./math\";\nlet a = clamp(square(pi));\n";
+        let project = program(main);
+        let fix = project.organize_imports("/src/main.ts").unwrap();
+        assert_eq!(
+            apply(main, &fix),
+            "import { pi, square } from \"./math\";\nimport \"./polyfill\";\nimport { clamp } from \"./lib/util\";\nlet a = clamp(square(pi));\n"
+        );
+
+        let organized = apply(main, &fix);
+        assert!(
+            program(&organized)
+                .organize_imports("/src/main.ts")
+                .is_none()
+        );
+    }
+}
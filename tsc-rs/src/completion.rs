@@ -0,0 +1,235 @@
+use crate::parser::parse_typescript;
+use crate::program::Program;
+use crate::symbols::SymbolKind;
+use crate::type_checker::TypeChecker;
+use crate::types::Type;
+use oxc_ast::ast::Statement;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionKind {
+    Variable,
+    Function,
+    Parameter,
+    Alias,
+    Class,
+    Enum,
+    Namespace,
+    Property,
+    Method,
+}
+
+impl From<SymbolKind> for CompletionKind {
+    fn from(kind: SymbolKind) -> Self {
+        match kind {
+            SymbolKind::Variable => CompletionKind::Variable,
+            SymbolKind::Function => CompletionKind::Function,
+            SymbolKind::Parameter => CompletionKind::Parameter,
+            SymbolKind::Alias => CompletionKind::Alias,
+            SymbolKind::Class => CompletionKind::Class,
+            SymbolKind::Enum => CompletionKind::Enum,
+            SymbolKind::Namespace => CompletionKind::Namespace,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CompletionItem {
+    pub name: String,
+    pub kind: CompletionKind,
+    /// The candidate's type, formatted for display.
+    pub detail: String,
+}
+
+// Stands in for the member name being typed so `obj.` still parses
+const PLACEHOLDER: &str = "__completion";
+
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_' || c == '$'
+}
+
+impl Program {
+    /// The names that can be written at the byte `offset` in the file. An offset inside
+    /// a character has none.
+    pub fn completions_at_position(&self, file_name: &str, offset: u32) -> Vec<CompletionItem> {
+        let Some(file) = self.file(file_name) else {
+            return Vec::new();
+        };
+        let offset = (offset as usize).min(file.source.len());
+        if !file.source.is_char_boundary(offset) {
+            return Vec::new();
+        }
+        let before = &file.source[..offset];
+        let word_start = before.trim_end_matches(is_identifier_char).len();
+
+        let mut items = if let Some(dot) = before[..word_start].strip_suffix('.').map(str::len) {
+            self.member_completions(file_name, &file.source, offset, dot)
+        } else {
+            let Some(checker) = self.check_file(file_name) else {
+                return Vec::new();
+            };
+            match import_specifier_at(&file.source, offset as u32) {
+                Some(specifier) => export_completions(&checker, &specifier),
+                None => scope_completions(&checker, offset as u32),
+            }
+        };
+        items.sort_by(|a, b| a.name.cmp(&b.name));
+        items
+    }
+
+    fn member_completions(
+        &self,
+        file_name: &str,
+        source: &str,
+        offset: usize,
+        dot: usize,
+    ) -> Vec<CompletionItem> {
+        let checker = if parse_typescript(source).is_ok() {
+            self.check_file(file_name)
+        } else {
+            let mut patched = self.clone();
+            let mut source = source.to_string();
+            source.insert_str(offset, PLACEHOLDER);
+            patched.update_file(file_name, source);
+            patched.check_file(file_name)
+        };
+        let Some(checker) = checker else {
+            return Vec::new();
+        };
+        let Some(receiver) = dot
+            .checked_sub(1)
+            .and_then(|end| checker.type_at(end as u32))
+        else {
+            return Vec::new();
+        };
+        checker
+            .properties_of(&receiver.ty)
+            .into_iter()
+            .map(|p| CompletionItem {
+                kind: if matches!(p.ty, Type::Function { .. }) {
+                    CompletionKind::Method
+                } else {
+                    CompletionKind::Property
+                },
+                detail: p.ty.to_string(),
+                name: p.name.to_string(),
+            })
+            .collect()
+    }
+}
+
+fn scope_completions(checker: &TypeChecker, offset: u32) -> Vec<CompletionItem> {
+    checker
+        .visible_symbols_at(offset)
+        .into_iter()
+        .map(|symbol| CompletionItem {
+            name: symbol.name.to_string(),
+            kind: symbol.kind.into(),
+            detail: symbol.ty.to_string(),
+        })
+        .collect()
+}
+
+fn export_completions(checker: &TypeChecker, specifier: &str) -> Vec<CompletionItem> {
+    checker
+        .module_exports(specifier)
+        .unwrap_or_default()
+        .iter()
+        .filter(|export| export.name != "default")
+        .map(|export| CompletionItem {
+            name: export.name.to_string(),
+            kind: export.kind.into(),
+            detail: export.ty.to_string(),
+        })
+        .collect()
+}
+
+/// The module specifier of the import whose specifier list contains `offset`.
+fn import_specifier_at(source: &str, offset: u32) -> Option<String> {
+    let ts_program = parse_typescript(source).ok()?;
+    ts_program.program.body.iter().find_map(|stmt| match stmt {
+        Statement::ImportDeclaration(decl)
+            if decl.span.start < offset && offset <= decl.source.span.start =>
+        {
+            Some(decl.source.value.to_string())
+        }
+        _ => None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestProject;
+
+    fn names(items: &[CompletionItem]) -> Vec<&str> {
+        items.iter().map(|i| i.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_scope_completions() {
+        let source = "let count = 1;\nfunction greet(name: string): string { return name; }\n";
+        let program = TestProject::new().file("/main.ts", source).program();
+
+        let items = program.completions_at_position("/main.ts", source.len() as u32);
+        assert_eq!(names(&items), vec!["count", "greet"]);
+        assert_eq!(items[0].kind, CompletionKind::Variable);
+        assert_eq!(items[0].detail, "number");
+        assert_eq!(items[1].kind, CompletionKind::Function);
+        assert_eq!(items[1].detail, "(string) => string");
+
+        // Parameters are only in scope inside their function
+        let offset = source.find("return").unwrap() as u32;
+        let items = program.completions_at_position("/main.ts", offset);
+        assert_eq!(names(&items), vec!["count", "greet", "name"]);
+        assert_eq!(items[2].kind, CompletionKind::Parameter);
+    }
+
+    #[test]
+    fn test_member_completions_after_dot() {
+        let source = "let point = { x: 1, label: 'a' };\npoint.";
+        let program = TestProject::new().file("/main.ts", source).program();
+
+        let items = program.completions_at_position("/main.ts", source.len() as u32);
+        assert_eq!(names(&items), vec!["label", "x"]);
+        assert_eq!(items[0].kind, CompletionKind::Property);
+        assert_eq!(items[0].detail, "string");
+
+        let source = "let word = 'hi';\nword.to";
+        let program = TestProject::new().file("/main.ts", source).program();
+        let items = program.completions_at_position("/main.ts", source.len() as u32);
+        assert!(names(&items).contains(&"toUpperCase"));
+        assert!(items.iter().any(|i| i.kind == CompletionKind::Method));
+
+        let source = "let café = { é: 1 };
+café.";
+        let program = TestProject::new().file("/main.ts", source).program();
+        let items = program.completions_at_position("/main.ts", source.len() as u32);
+        assert_eq!(names(&items), vec!["é"]);
+        // Inside the two bytes of the last `é`
+        let inside = source.rfind('é').unwrap() as u32 + 1;
+        assert!(
+            program
+                .completions_at_position("/main.ts", inside)
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_import_specifier_completions() {
+        let main = r#"import { } from "./math";"#;
+        let program = TestProject::new()
+            .file("/main.ts", main)
+            .file(
+                "/math.ts",
+                "export const pi = 3.14;\nexport function twice(n: number): number { return n * 2; }",
+            )
+            .roots(&["/main.ts"])
+            .program();
+
+        let offset = main.find('}').unwrap() as u32;
+        let items = program.completions_at_position("/main.ts", offset);
+        assert_eq!(names(&items), vec!["pi", "twice"]);
+        assert_eq!(items[1].kind, CompletionKind::Function);
+        assert_eq!(items[1].detail, "(number) => number");
+    }
+}
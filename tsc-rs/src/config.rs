@@ -0,0 +1,903 @@
+//! `tsconfig.json` projects: their compiler options, and the root files their `files`,
+//! `include` and `exclude` lists pick out.
+//!
+//! Like TypeScript, the file may have comments and trailing commas, and may inherit
+//! from other configs through `extends`.
+
+use crate::diagnostics::{Diagnostic, Message};
+use crate::file_inclusion::FileInclusion;
+use crate::host::{FileSystem, normalize_path};
+use crate::options::CompilerOptions;
+use crate::program::Program;
+use serde::Deserialize;
+use serde::de::{self, Deserializer, Visitor};
+use serde_json::{Map, Value};
+use std::path::{Path, PathBuf};
+
+/// Directories package managers install into, which wildcards never descend into
+/// unless a pattern names them.
+const PACKAGE_FOLDERS: &[&str] = &["node_modules", "bower_components", "jspm_packages"];
+
+/// Extensions of the files `include` patterns pick up, declaration files last so that a
+/// source file wins over the declaration file emitted next to it.
+const EXTENSIONS: &[&str] = &[".ts", ".tsx", ".mts", ".cts", ".d.ts", ".d.mts", ".d.cts"];
+
+/// Compiler options TypeScript has that this checker doesn't read, and ignores without
+/// warning about them.
+const IGNORED_OPTIONS: &[&str] = &[
+    "allowArbitraryExtensions",
+    "allowImportingTsExtensions",
+    "allowJs",
+    "allowSyntheticDefaultImports",
+    "allowUmdGlobalAccess",
+    "allowUnreachableCode",
+    "allowUnusedLabels",
+    "assumeChangesOnlyAffectDirectDependencies",
+    "baseUrl",
+    "charset",
+    "checkJs",
+    "composite",
+    "customConditions",
+    "declaration",
+    "declarationDir",
+    "declarationMap",
+    "diagnostics",
+    "disableReferencedProjectLoad",
+    "disableSizeLimit",
+    "disableSolutionSearching",
+    "disableSourceOfProjectReferenceRedirect",
+    "downlevelIteration",
+    "emitBOM",
+    "emitDeclarationOnly",
+    "emitDecoratorMetadata",
+    "erasableSyntaxOnly",
+    "esModuleInterop",
+    "exactOptionalPropertyTypes",
+    "explainFiles",
+    "extendedDiagnostics",
+    "generateCpuProfile",
+    "generateTrace",
+    "importHelpers",
+    "importsNotUsedAsValues",
+    "incremental",
+    "inlineSourceMap",
+    "inlineSources",
+    "isolatedDeclarations",
+    "jsx",
+    "jsxFactory",
+    "jsxFragmentFactory",
+    "jsxImportSource",
+    "keyofStringsOnly",
+    "lib",
+    "libReplacement",
+    "listEmittedFiles",
+    "listFiles",
+    "locale",
+    "mapRoot",
+    "maxNodeModuleJsDepth",
+    "moduleDetection",
+    "moduleResolution",
+    "moduleSuffixes",
+    "newLine",
+    "noCheck",
+    "noEmitHelpers",
+    "noImplicitReturns",
+    "noImplicitUseStrict",
+    "noLib",
+    "noPropertyAccessFromIndexSignature",
+    "noResolve",
+    "noStrictGenericChecks",
+    "noUncheckedIndexedAccess",
+    "noUncheckedSideEffectImports",
+    "noUnusedLocals",
+    "noUnusedParameters",
+    "out",
+    "outFile",
+    "paths",
+    "plugins",
+    "preserveValueImports",
+    "preserveWatchOutput",
+    "pretty",
+    "reactNamespace",
+    "removeComments",
+    "resolveJsonModule",
+    "resolvePackageJsonExports",
+    "resolvePackageJsonImports",
+    "rewriteRelativeImportExtensions",
+    "rootDirs",
+    "sourceMap",
+    "sourceRoot",
+    "stopBuildOnErrors",
+    "strictBuiltinIteratorReturn",
+    "stripInternal",
+    "suppressExcessPropertyErrors",
+    "suppressImplicitAnyIndexErrors",
+    "tsBuildInfoFile",
+    "typeRoots",
+    "types",
+    "useDefineForClassFields",
+    "verbatimModuleSyntax",
+];
+
+/// Compiler options whose paths are relative to the config that sets them.
+const PATH_OPTIONS: &[&str] = &["outDir", "rootDir"];
+
+/// The parts of a `tsconfig.json` this checker reads.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ProjectConfig {
+    pub compiler_options: CompilerOptions,
+    /// Names in `compilerOptions` that aren't compiler options at all. These, and the
+    /// options this checker doesn't read, are left out of `compiler_options`.
+    #[serde(skip)]
+    pub unknown_options: Vec<String>,
+    /// Files that are roots whatever `include` and `exclude` say.
+    pub files: Option<Vec<String>>,
+    /// Patterns of root files, `**/*` when neither this nor `files` is given.
+    pub include: Option<Vec<String>>,
+    /// Patterns of what `include` shouldn't pick up. When not given, packages and the
+    /// output directory are left out.
+    pub exclude: Option<Vec<String>>,
+}
+
+impl ProjectConfig {
+    /// Reads a config's JSON, which may have comments and trailing commas. What it
+    /// `extends` isn't read; [`ProjectConfig::read`] merges that in.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        Self::from_value(parse_jsonc(json)?)
+    }
+
+    /// Reads the config at `config_path` along with the configs it `extends`, whose
+    /// options it overrides one by one and whose `files`, `include` and `exclude` it
+    /// inherits unless it has its own.
+    pub fn read(config_path: &Path, fs: &dyn FileSystem) -> Result<Self, String> {
+        Self::read_with(config_path, fs, Map::new())
+    }
+
+    /// Reads the config at `config_path` as [`ProjectConfig::read`] does, with the compiler
+    /// options in `command_line`, by their `tsconfig.json` names, overriding its own.
+    pub fn read_with(
+        config_path: &Path,
+        fs: &dyn FileSystem,
+        command_line: Map<String, Value>,
+    ) -> Result<Self, String> {
+        let mut config = read_merged(config_path, fs, &mut Vec::new())?;
+        merge(
+            &mut config,
+            Map::from_iter([("compilerOptions".to_string(), Value::Object(command_line))]),
+        );
+        Self::from_value(Value::Object(config))
+    }
+
+    fn from_value(mut value: Value) -> Result<Self, String> {
+        let known = field_names::<CompilerOptions>();
+        let mut unknown_options = Vec::new();
+        if let Some(Value::Object(options)) = value.get_mut("compilerOptions") {
+            options.retain(|name, _| {
+                if !known.contains(&name.as_str()) && !IGNORED_OPTIONS.contains(&name.as_str()) {
+                    unknown_options.push(name.clone());
+                }
+                known.contains(&name.as_str())
+            });
+        }
+        if let Some(object) = value.as_object_mut() {
+            object.remove("extends");
+        }
+        let config: Self = serde_json::from_value(value).map_err(|err| err.to_string())?;
+        Ok(Self {
+            unknown_options,
+            ..config
+        })
+    }
+
+    /// The root files of the project configured in `dir`, with why each is one, `files`
+    /// first in the order listed and then what `include` picks up, by directory.
+    pub fn root_files(&self, dir: &Path, fs: &dyn FileSystem) -> Vec<(PathBuf, FileInclusion)> {
+        let mut roots: Vec<(PathBuf, FileInclusion)> = Vec::new();
+        for file in self.files.iter().flatten() {
+            let path = normalize_path(&dir.join(file));
+            if !roots.iter().any(|(root, _)| *root == path) {
+                roots.push((path, FileInclusion::FilesList));
+            }
+        }
+
+        let include: Vec<Pattern> = match (&self.include, &self.files) {
+            (Some(include), _) => include.iter().map(|p| Pattern::include(dir, p)).collect(),
+            (None, Some(_)) => Vec::new(),
+            (None, None) => vec![Pattern::include(dir, "**/*")],
+        };
+        let exclude: Vec<Pattern> = match &self.exclude {
+            Some(exclude) => exclude.iter().map(|p| Pattern::new(dir, p)).collect(),
+            None => PACKAGE_FOLDERS
+                .iter()
+                .map(|folder| Pattern::new(dir, folder))
+                .chain(
+                    self.compiler_options
+                        .out_dir
+                        .iter()
+                        .map(|out_dir| Pattern::new(dir, &out_dir.to_string_lossy())),
+                )
+                .collect(),
+        };
+        let mut matched = Vec::new();
+        if !include.is_empty() {
+            walk(dir, &include, &exclude, fs, &mut matched);
+        }
+        let included: Vec<PathBuf> = roots
+            .iter()
+            .map(|(path, _)| path.clone())
+            .chain(matched.iter().map(|(path, _)| path.clone()))
+            .collect();
+        for (path, pattern) in matched {
+            if roots.iter().any(|(root, _)| *root == path) {
+                continue;
+            }
+            // `a.d.ts` is left out when `a.ts` is picked up, since it's `a.ts`'s output
+            if let Some(stem) = declaration_stem(&path)
+                && EXTENSIONS[..4].iter().any(|extension| {
+                    included.contains(&PathBuf::from(format!("{}{}", stem, extension)))
+                })
+            {
+                continue;
+            }
+            roots.push((path, FileInclusion::IncludePattern(pattern)));
+        }
+        roots
+    }
+}
+
+/// Parses JSON that may have `//` and `/* */` comments and commas before a closing bracket,
+/// as `tsconfig.json` files may.
+fn parse_jsonc(text: &str) -> Result<Value, String> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let mut json = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => while chars.next_if(|c| *c != '\n').is_some() {},
+            ('/', Some('*')) => {
+                chars.next();
+                let mut last = ' ';
+                for c in chars.by_ref() {
+                    if last == '*' && c == '/' {
+                        break;
+                    }
+                    last = c;
+                }
+                json.push(' ');
+            }
+            ('}' | ']', _) => {
+                // A trailing comma is dropped, keeping what came after it
+                let before = json.trim_end();
+                if before.ends_with(',') {
+                    json.replace_range(before.len() - 1..before.len(), " ");
+                }
+                json.push(c);
+            }
+            _ => json.push(c),
+        }
+    }
+    serde_json::from_str(&json).map_err(|err| err.to_string())
+}
+
+/// The config at `path`, with what it `extends` merged in and the paths it
+/// inherits made absolute. `chain` is the configs that led here, to catch cycles.
+fn read_merged(
+    path: &Path,
+    fs: &dyn FileSystem,
+    chain: &mut Vec<PathBuf>,
+) -> Result<Map<String, Value>, String> {
+    if chain.iter().any(|seen| seen == path) {
+        return Err(format!(
+            "Circularity detected while resolving configuration: {}",
+            chain
+                .iter()
+                .chain([&path.to_path_buf()])
+                .map(|path| path.display().to_string())
+                .collect::<Vec<_>>()
+                .join(" -> ")
+        ));
+    }
+    let json = fs
+        .read_file(path)
+        .map_err(|_| format!("Cannot read file '{}'.", path.display()))?;
+    let Value::Object(mut config) = parse_jsonc(&json)
+        .map_err(|err| format!("Could not parse '{}': {}", path.display(), err))?
+    else {
+        return Err(format!("'{}' is not a JSON object.", path.display()));
+    };
+    let dir = path.parent().unwrap_or(Path::new("/"));
+    let bases = match config.remove("extends") {
+        None => Vec::new(),
+        Some(Value::String(base)) => vec![base],
+        Some(Value::Array(bases)) => bases
+            .iter()
+            .map(|base| base.as_str().map(str::to_string))
+            .collect::<Option<_>>()
+            .ok_or("Compiler option 'extends' requires a value of type string or Array.")?,
+        Some(_) => {
+            return Err(
+                "Compiler option 'extends' requires a value of type string or Array.".to_string(),
+            );
+        }
+    };
+
+    chain.push(path.to_path_buf());
+    let mut merged = Map::new();
+    for base in bases {
+        let base_path =
+            resolve_extends(&base, dir, fs).ok_or_else(|| format!("File '{}' not found.", base))?;
+        let base_config = read_merged(&base_path, fs, chain)?;
+        let base_dir = base_path.parent().unwrap_or(Path::new("/"));
+        merge(&mut merged, rebase(base_config, base_dir));
+    }
+    chain.pop();
+    merge(&mut merged, config);
+    Ok(merged)
+}
+
+/// Merges `config` over `base`: its compiler options one by one, and everything else
+/// whole.
+fn merge(base: &mut Map<String, Value>, config: Map<String, Value>) {
+    for (key, value) in config {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Object(base_options)), Value::Object(options))
+                if key == "compilerOptions" =>
+            {
+                base_options.extend(options);
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// `config`'s root file lists and path options made absolute, since they're relative to
+/// `dir`, where it is, and not to the config that extends it.
+fn rebase(mut config: Map<String, Value>, dir: &Path) -> Map<String, Value> {
+    let absolute = |value: &mut Value| {
+        if let Value::String(path) = value {
+            *path = rebase_pattern(dir, path);
+        }
+    };
+    for key in ["files", "include", "exclude"] {
+        if let Some(Value::Array(paths)) = config.get_mut(key) {
+            paths.iter_mut().for_each(absolute);
+        }
+    }
+    if let Some(Value::Object(options)) = config.get_mut("compilerOptions") {
+        for key in PATH_OPTIONS {
+            options.get_mut(*key).map(absolute);
+        }
+    }
+    config
+}
+
+/// `pattern` as an absolute path from `dir`, normalizing only up to its first wildcard.
+fn rebase_pattern(dir: &Path, pattern: &str) -> String {
+    let (literal, rest) = match pattern.find(['*', '?']) {
+        Some(wildcard) => match pattern[..wildcard].rfind('/') {
+            Some(slash) => (&pattern[..slash], &pattern[slash + 1..]),
+            None => ("", pattern),
+        },
+        None => (pattern, ""),
+    };
+    let literal = normalize_path(&dir.join(literal));
+    match rest {
+        "" => literal.display().to_string(),
+        rest => format!("{}/{}", literal.display(), rest),
+    }
+}
+
+/// The config `extends` names from a config in `dir`: a path, `.json` optional, or a file
+/// in a package from the nearest `node_modules` that has it.
+fn resolve_extends(name: &str, dir: &Path, fs: &dyn FileSystem) -> Option<PathBuf> {
+    let candidates = |path: PathBuf| {
+        let with_json = PathBuf::from(format!("{}.json", path.display()));
+        [path.clone(), with_json, path.join("tsconfig.json")]
+    };
+    let is_path = name.starts_with("./") || name.starts_with("../") || name.starts_with('/');
+    if is_path {
+        return candidates(normalize_path(&dir.join(name)))
+            .into_iter()
+            .find(|path| fs.file_exists(path));
+    }
+    dir.ancestors()
+        .flat_map(|dir| candidates(dir.join("node_modules").join(name)))
+        .find(|path| fs.file_exists(path))
+}
+
+/// The names `T` deserializes fields from, as its `Deserialize` impl asks for them.
+fn field_names<'de, T: Deserialize<'de>>() -> &'static [&'static str] {
+    struct FieldNames<'f>(&'f mut &'static [&'static str]);
+
+    impl<'de> Deserializer<'de> for FieldNames<'_> {
+        type Error = de::value::Error;
+
+        fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+            Err(de::Error::custom("not a struct"))
+        }
+
+        fn deserialize_struct<V: Visitor<'de>>(
+            self,
+            _: &'static str,
+            fields: &'static [&'static str],
+            _: V,
+        ) -> Result<V::Value, Self::Error> {
+            *self.0 = fields;
+            Err(de::Error::custom("only the field names are wanted"))
+        }
+
+        serde::forward_to_deserialize_any! {
+            bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes
+            byte_buf option unit unit_struct newtype_struct seq tuple tuple_struct map enum
+            identifier ignored_any
+        }
+    }
+
+    let mut fields: &'static [&'static str] = &[];
+    let _ = T::deserialize(FieldNames(&mut fields));
+    fields
+}
+
+/// The path of a declaration file without its extension.
+fn declaration_stem(path: &Path) -> Option<String> {
+    let path = path.to_string_lossy();
+    EXTENSIONS[4..]
+        .iter()
+        .find_map(|extension| path.strip_suffix(extension))
+        .map(str::to_string)
+}
+
+/// A path pattern from `include` or `exclude`, split into segments relative to the root.
+struct Pattern {
+    /// The pattern as written, for explaining what it matched.
+    text: String,
+    segments: Vec<String>,
+}
+
+impl Pattern {
+    fn new(dir: &Path, text: &str) -> Self {
+        let absolute = dir.join(text.trim_end_matches('/'));
+        let absolute = match text.contains(['*', '?']) {
+            // Normalizing would fold `**/..`, which patterns don't use
+            true => absolute,
+            false => normalize_path(&absolute),
+        };
+        Self {
+            text: text.to_string(),
+            segments: segments(&absolute),
+        }
+    }
+
+    /// An `include` pattern, where a last segment without wildcards or an extension names
+    /// a directory to include everything in.
+    fn include(dir: &Path, text: &str) -> Self {
+        let mut pattern = Self::new(dir, text);
+        if let Some(last) = pattern.segments.last()
+            && !last.contains(['*', '?'])
+            && !EXTENSIONS.iter().any(|extension| last.ends_with(extension))
+        {
+            pattern.segments.push("**".to_string());
+            pattern.segments.push("*".to_string());
+        }
+        pattern
+    }
+
+    /// Whether the pattern matches the whole of `path`.
+    fn matches(&self, path: &[String]) -> bool {
+        matches_segments(&self.segments, path)
+    }
+
+    /// Whether the pattern matches `path` or a directory it's in, as `exclude` patterns do.
+    fn matches_within(&self, path: &[String]) -> bool {
+        (1..=path.len()).any(|end| self.matches(&path[..end]))
+    }
+
+    /// Whether a file inside the directory `path` could match the pattern.
+    fn could_match_inside(&self, path: &[String]) -> bool {
+        could_match_prefix(&self.segments, path)
+    }
+}
+
+fn segments(path: &Path) -> Vec<String> {
+    path.components()
+        .map(|component| component.as_os_str().to_string_lossy().to_string())
+        .collect()
+}
+
+/// Whether a wildcard may stand for the directory or file `name`: not hidden ones, and
+/// not package folders, which have to be named.
+fn wildcard_may_match(name: &str) -> bool {
+    !name.starts_with('.') && !PACKAGE_FOLDERS.contains(&name)
+}
+
+fn matches_segments(pattern: &[String], path: &[String]) -> bool {
+    match pattern.split_first() {
+        None => path.is_empty(),
+        Some((first, rest)) if first == "**" => (0..=path.len()).any(|skipped| {
+            path[..skipped].iter().all(|name| wildcard_may_match(name))
+                && matches_segments(rest, &path[skipped..])
+        }),
+        Some((first, rest)) => match path.split_first() {
+            Some((name, path)) => matches_name(first, name) && matches_segments(rest, path),
+            None => false,
+        },
+    }
+}
+
+/// Whether some path starting with `prefix` could match `pattern`.
+fn could_match_prefix(pattern: &[String], prefix: &[String]) -> bool {
+    let Some((name, prefix_rest)) = prefix.split_first() else {
+        return true;
+    };
+    match pattern.split_first() {
+        None => false,
+        Some((first, rest)) if first == "**" => {
+            could_match_prefix(rest, prefix)
+                || (wildcard_may_match(name) && could_match_prefix(pattern, prefix_rest))
+        }
+        Some((first, rest)) => matches_name(first, name) && could_match_prefix(rest, prefix_rest),
+    }
+}
+
+/// Whether one segment of a pattern, with `*` for any run of characters and `?` for any
+/// one character, matches `name`.
+fn matches_name(pattern: &str, name: &str) -> bool {
+    if !pattern.contains(['*', '?']) {
+        return pattern == name;
+    }
+    if !wildcard_may_match(name) {
+        return false;
+    }
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    // Which prefixes of `name` the pattern so far matches
+    let mut matched = vec![false; name.len() + 1];
+    matched[0] = true;
+    for &p in &pattern {
+        let mut next = vec![false; name.len() + 1];
+        for i in 0..=name.len() {
+            next[i] = match p {
+                '*' => matched[i] || (i > 0 && next[i - 1]),
+                '?' => i > 0 && matched[i - 1],
+                c => i > 0 && matched[i - 1] && name[i - 1] == c,
+            };
+        }
+        matched = next;
+    }
+    matched[name.len()]
+}
+
+/// Collects the files under `dir` an `include` pattern matches and no `exclude` pattern
+/// does, with the pattern that matched, skipping directories nothing could match in.
+fn walk(
+    dir: &Path,
+    include: &[Pattern],
+    exclude: &[Pattern],
+    fs: &dyn FileSystem,
+    matched: &mut Vec<(PathBuf, String)>,
+) {
+    let Ok(entries) = fs.read_dir(dir) else {
+        return;
+    };
+    let (files, dirs): (Vec<PathBuf>, Vec<PathBuf>) =
+        entries.into_iter().partition(|entry| fs.file_exists(entry));
+    for file in files {
+        let name = file.to_string_lossy();
+        if !EXTENSIONS.iter().any(|extension| name.ends_with(extension)) {
+            continue;
+        }
+        let path = segments(&file);
+        if exclude.iter().any(|pattern| pattern.matches_within(&path)) {
+            continue;
+        }
+        if let Some(pattern) = include.iter().find(|pattern| pattern.matches(&path)) {
+            matched.push((file, pattern.text.clone()));
+        }
+    }
+    for dir in dirs {
+        let path = segments(&dir);
+        if !exclude.iter().any(|pattern| pattern.matches_within(&path))
+            && include
+                .iter()
+                .any(|pattern| pattern.could_match_inside(&path))
+        {
+            walk(&dir, include, exclude, fs, matched);
+        }
+    }
+}
+
+impl Program {
+    /// Loads the project configured by the `tsconfig.json` at `config_path`: its root files
+    /// and everything they import, checked with its compiler options.
+    pub fn load_project(config_path: &Path, fs: &dyn FileSystem) -> Result<Self, String> {
+        Self::load_project_with(config_path, fs, Map::new())
+    }
+
+    /// Loads the project configured at `config_path` as [`Program::load_project`] does,
+    /// with the compiler options given on the command line overriding the config's, as
+    /// `tsc -p` does.
+    pub fn load_project_with(
+        config_path: &Path,
+        fs: &dyn FileSystem,
+        command_line: Map<String, Value>,
+    ) -> Result<Self, String> {
+        let config_path = normalize_path(&fs.current_dir().unwrap_or_default().join(config_path));
+        let config = ProjectConfig::read_with(&config_path, fs, command_line)?;
+        let dir = config_path.parent().unwrap_or(Path::new("/"));
+        let roots = config.root_files(dir, fs);
+        let paths: Vec<&PathBuf> = roots.iter().map(|(path, _)| path).collect();
+        let mut program = Self::load(config.compiler_options, &paths, fs);
+        let config_name = config_path.to_string_lossy();
+        for option in &config.unknown_options {
+            program.push_load_diagnostic(
+                Diagnostic::from_message(config_name.as_ref(), Message::new(5023, &[option]), None)
+                    .warning(),
+            );
+        }
+        program.set_root_inclusions(
+            roots
+                .into_iter()
+                .map(|(path, inclusion)| (path.to_string_lossy().to_string(), inclusion))
+                .collect(),
+        );
+        Ok(program)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::InMemoryFileSystem;
+
+    fn root_files(config: &str, files: &[&str]) -> Vec<String> {
+        let mut fs = InMemoryFileSystem::new();
+        for file in files {
+            fs.add_file(file, "");
+        }
+        ProjectConfig::parse(config)
+            .unwrap()
+            .root_files(Path::new("/project"), &fs)
+            .into_iter()
+            .map(|(path, _)| path.to_string_lossy().to_string())
+            .collect()
+    }
+
+    const FILES: &[&str] = &[
+        "/project/src/index.ts",
+        "/project/src/util/strings.ts",
+        "/project/src/util/strings.test.ts",
+        "/project/src/view.tsx",
+        "/project/src/styles.css",
+        "/project/src/index.d.ts",
+        "/project/types/globals.d.ts",
+        "/project/node_modules/pkg/index.d.ts",
+        "/project/.cache/stale.ts",
+        "/project/dist/index.d.ts",
+        "/project/scripts/build.ts",
+    ];
+
+    #[test]
+    fn test_default_include() {
+        assert_eq!(
+            root_files("{}", FILES),
+            vec![
+                "/project/dist/index.d.ts",
+                "/project/scripts/build.ts",
+                "/project/src/index.ts",
+                "/project/src/view.tsx",
+                "/project/src/util/strings.test.ts",
+                "/project/src/util/strings.ts",
+                "/project/types/globals.d.ts",
+            ]
+        );
+        // The output directory is left out too, unless `exclude` says otherwise
+        assert_eq!(
+            root_files(r#"{ "compilerOptions": { "outDir": "dist" } }"#, FILES).len(),
+            6
+        );
+    }
+
+    #[test]
+    fn test_include_and_exclude() {
+        let config = r#"{
+            "include": ["src", "types/*.d.ts"],
+            "exclude": ["**/*.test.ts"]
+        }"#;
+        assert_eq!(
+            root_files(config, FILES),
+            vec![
+                "/project/src/index.ts",
+                "/project/src/view.tsx",
+                "/project/src/util/strings.ts",
+                "/project/types/globals.d.ts",
+            ]
+        );
+        // Packages are left out unless `exclude` is given, and even then wildcards only go
+        // into package folders that are named
+        assert_eq!(
+            root_files(r#"{ "include": ["node_modules/*/index.d.ts"] }"#, FILES),
+            Vec::<String>::new()
+        );
+        assert_eq!(
+            root_files(
+                r#"{ "include": ["**/*.d.ts"], "exclude": ["types"] }"#,
+                FILES
+            ),
+            vec!["/project/dist/index.d.ts", "/project/src/index.d.ts"]
+        );
+    }
+
+    #[test]
+    fn test_files_list() {
+        // `files` wins over `exclude`, and leaves `include` empty unless it's given
+        let config = r#"{ "files": ["scripts/build.ts"], "exclude": ["scripts"] }"#;
+        assert_eq!(root_files(config, FILES), vec!["/project/scripts/build.ts"]);
+        let config = r#"{
+            "files": ["scripts/build.ts"],
+            "include": ["src/util/*"],
+            "exclude": ["scripts"]
+        }"#;
+        assert_eq!(
+            root_files(config, FILES),
+            vec![
+                "/project/scripts/build.ts",
+                "/project/src/util/strings.test.ts",
+                "/project/src/util/strings.ts",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_load_project() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/project/tsconfig.json",
+            r#"{ "compilerOptions": { "strict": false }, "include": ["src"] }"#,
+        );
+        fs.add_file("/project/src/main.ts", "import { a } from \"../lib/a\";");
+        fs.add_file("/project/lib/a.ts", "export const a = 1;");
+        let program = Program::load_project(Path::new("/project/tsconfig.json"), &fs).unwrap();
+        assert_eq!(program.options().strict, Some(false));
+        assert_eq!(
+            program.explain_files(),
+            "/project/src/main.ts
+  Matched by include pattern 'src' in tsconfig.json
+/project/lib/a.ts
+  Imported via \"../lib/a\" from file '/project/src/main.ts'
+"
+        );
+        assert!(Program::load_project(Path::new("/missing.json"), &fs).is_err());
+    }
+
+    #[test]
+    fn test_command_line_options() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/project/tsconfig.json",
+            r#"{ "compilerOptions": { "noEmit": false, "strict": true } }"#,
+        );
+        fs.add_file("/project/main.ts", "export {};");
+        let command_line = Map::from_iter([
+            ("noEmit".to_string(), Value::Bool(true)),
+            ("skipLibCheck".to_string(), Value::Bool(true)),
+        ]);
+        let program =
+            Program::load_project_with(Path::new("/project/tsconfig.json"), &fs, command_line)
+                .unwrap();
+        let options = program.options();
+        assert!(options.no_emit);
+        assert!(options.skip_lib_check);
+        assert_eq!(options.strict, Some(true));
+    }
+
+    #[test]
+    fn test_parse_jsonc() {
+        let config = ProjectConfig::parse(
+            r#"{
+                // Comments and trailing commas, as tsc accepts
+                "compilerOptions": { "outDir": "out//*not a comment*/", },
+                /* "include": ["lib"], */
+                "include": ["src",],
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.compiler_options.out_dir,
+            Some(PathBuf::from("out//*not a comment*/"))
+        );
+        assert_eq!(config.include, Some(vec!["src".to_string()]));
+        assert!(ProjectConfig::parse("{ \"include\": [\"a\" \"b\"] }").is_err());
+    }
+
+    #[test]
+    fn test_unknown_options() {
+        // Options the checker doesn't read are ignored, and ones that don't exist warned about
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/project/tsconfig.json",
+            r#"{
+                "compilerOptions": {
+                    "esModuleInterop": true,
+                    "lib": ["es2020", "dom"],
+                    "jsx": "react-jsx",
+                    "noEmit": true,
+                    "notAnOption": 1
+                }
+            }"#,
+        );
+        fs.add_file("/project/main.ts", "export {};");
+        let program = Program::load_project(Path::new("/project/tsconfig.json"), &fs).unwrap();
+        assert!(program.options().no_emit);
+        let diagnostics = program.check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].code, Some(5023));
+        assert_eq!(diagnostics[0].file_name, "/project/tsconfig.json");
+        assert_eq!(diagnostics[0].category, crate::DiagnosticCategory::Warning);
+        // Known options with values they can't have are still errors
+        assert!(ProjectConfig::parse(r#"{ "compilerOptions": { "target": "es3" } }"#).is_err());
+    }
+
+    #[test]
+    fn test_extends() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/project/configs/base.json",
+            r#"{
+                "extends": "@tsconfig/strictest",
+                "compilerOptions": { "strict": false, "outDir": "../dist" },
+                "include": ["../src/**/*"]
+            }"#,
+        );
+        fs.add_file(
+            "/project/node_modules/@tsconfig/strictest/tsconfig.json",
+            r#"{ "compilerOptions": { "strict": true, "noImplicitOverride": true } }"#,
+        );
+        fs.add_file(
+            "/project/tsconfig.json",
+            r#"{ "extends": ["./configs/base"], "compilerOptions": { "noEmit": true } }"#,
+        );
+        fs.add_file("/project/src/main.ts", "");
+        fs.add_file("/project/configs/src/other.ts", "");
+
+        let config = ProjectConfig::read(Path::new("/project/tsconfig.json"), &fs).unwrap();
+        let options = &config.compiler_options;
+        assert_eq!(options.strict, Some(false));
+        assert!(options.no_implicit_override && options.no_emit);
+        assert_eq!(options.out_dir, Some(PathBuf::from("/project/dist")));
+        // Inherited patterns are relative to the config they're written in
+        assert_eq!(config.include, Some(vec!["/project/src/**/*".to_string()]));
+        let roots: Vec<PathBuf> = config
+            .root_files(Path::new("/project"), &fs)
+            .into_iter()
+            .map(|(path, _)| path)
+            .collect();
+        assert_eq!(roots, vec![PathBuf::from("/project/src/main.ts")]);
+
+        fs.add_file("/a.json", r#"{ "extends": "./b.json" }"#);
+        fs.add_file("/b.json", r#"{ "extends": "./a", }"#);
+        let err = ProjectConfig::read(Path::new("/a.json"), &fs).unwrap_err();
+        assert!(err.starts_with("Circularity detected"), "{}", err);
+        fs.add_file("/c.json", r#"{ "extends": "missing" }"#);
+        assert_eq!(
+            ProjectConfig::read(Path::new("/c.json"), &fs),
+            Err("File 'missing' not found.".to_string())
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Runs cases from the TypeScript repository's `tests/cases/conformance` suite and compares
+//! the diagnostics with its `.errors.txt` baselines, so progress toward tsc parity can be
+//! measured. `examples/conformance.rs` runs a whole checkout.
+
+use crate::diagnostics::Diagnostic;
+use crate::host::InMemoryFileSystem;
+use crate::line_index::LineIndex;
+use crate::options::CompilerOptions;
+use crate::program::Program;
+use serde_json::{Map, Value};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A conformance test file split into the files it declares with `// @filename:`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TestCase {
+    pub files: Vec<(String, String)>,
+    pub options: CompilerOptions,
+    /// `// @option: value` settings that don't map onto [`CompilerOptions`].
+    pub unsupported_options: Vec<String>,
+    /// Whether a setting lists several values, which tsc runs as separate variants with
+    /// their own baselines.
+    pub has_variants: bool,
+}
+
+/// One error from a `.errors.txt` baseline, or reported by tsc-rs, in the baseline's
+/// `file(line,column): message` form with one-based positions.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct BaselineError {
+    pub file: Option<String>,
+    pub line: u32,
+    pub column: u32,
+    pub message: String,
+}
+
+/// Formats the error like a baseline line, without tsc's error code.
+impl fmt::Display for BaselineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(file) = &self.file {
+            write!(f, "{}({},{}): ", file, self.line, self.column)?;
+        }
+        write!(f, "error: {}", self.message)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CaseResult {
+    pub expected: Vec<BaselineError>,
+    pub actual: Vec<BaselineError>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.expected == self.actual
+    }
+
+    /// Expected errors tsc-rs didn't report.
+    pub fn missing(&self) -> Vec<&BaselineError> {
+        self.expected
+            .iter()
+            .filter(|e| !self.actual.contains(e))
+            .collect()
+    }
+
+    /// Errors tsc-rs reported that tsc doesn't.
+    pub fn unexpected(&self) -> Vec<&BaselineError> {
+        self.actual
+            .iter()
+            .filter(|e| !self.expected.contains(e))
+            .collect()
+    }
+}
+
+/// Splits a test file named `name` into its files and compiler settings.
+pub fn parse_test_case(name: &str, source: &str) -> TestCase {
+    let mut files: Vec<(String, String)> = Vec::new();
+    let mut current = String::new();
+    let mut current_name: Option<String> = None;
+    let mut settings = Map::new();
+    let mut unsupported_options = Vec::new();
+    let mut has_variants = false;
+
+    for line in source.split_inclusive('\n') {
+        let Some((key, value)) = directive(line) else {
+            current.push_str(line);
+            continue;
+        };
+        if key.eq_ignore_ascii_case("filename") {
+            if let Some(file_name) = current_name.take() {
+                files.push((file_name, std::mem::take(&mut current)));
+            }
+            current.clear();
+            current_name = Some(value.to_string());
+            continue;
+        }
+        has_variants |= value.contains(',');
+        let value = match value {
+            "true" => Value::Bool(true),
+            "false" => Value::Bool(false),
+            _ => Value::from(value),
+        };
+        // Options are checked one at a time so an unsupported one doesn't drop the rest
+        let single = Map::from_iter([(key.to_string(), value.clone())]);
+        match serde_json::from_value::<CompilerOptions>(Value::Object(single)) {
+            Ok(_) => {
+                settings.insert(key.to_string(), value);
+            }
+            Err(_) => unsupported_options.push(key.to_string()),
+        }
+    }
+    files.push((current_name.unwrap_or_else(|| name.to_string()), current));
+
+    TestCase {
+        files,
+        options: serde_json::from_value(Value::Object(settings)).unwrap_or_default(),
+        unsupported_options,
+        has_variants,
+    }
+}
+
+/// The `key` and `value` of a `// @key: value` line.
+fn directive(line: &str) -> Option<(&str, &str)> {
+    let rest = line
+        .trim()
+        .strip_prefix("//")?
+        .trim_start()
+        .strip_prefix('@')?;
+    let (key, value) = rest.split_once(':')?;
+    Some((key.trim(), value.trim()))
+}
+
+/// The errors listed at the top of a `.errors.txt` baseline, before the annotated sources.
+pub fn parse_error_baseline(baseline: &str) -> Vec<BaselineError> {
+    let mut errors: Vec<_> = baseline
+        .lines()
+        .take_while(|line| !line.starts_with("===="))
+        .filter_map(parse_baseline_line)
+        .collect();
+    errors.sort();
+    errors
+}
+
+fn parse_baseline_line(line: &str) -> Option<BaselineError> {
+    let (location, rest) = match line.split_once(": error TS") {
+        Some((location, rest)) => (Some(location), rest),
+        None => (None, line.strip_prefix("error TS")?),
+    };
+    let (_code, message) = rest.split_once(": ")?;
+    let (file, line, column) = match location {
+        Some(location) => {
+            let (file, position) = location.strip_suffix(')')?.rsplit_once('(')?;
+            let (line, column) = position.split_once(',')?;
+            (
+                Some(file.to_string()),
+                line.parse().ok()?,
+                column.parse().ok()?,
+            )
+        }
+        None => (None, 0, 0),
+    };
+    Some(BaselineError {
+        file,
+        line,
+        column,
+        message: message.to_string(),
+    })
+}
+
+/// Checks the case's files together and compares the errors with `expected`, which is
+/// empty for cases without an `.errors.txt` baseline.
+pub fn run_case(case: &TestCase, expected: Vec<BaselineError>) -> CaseResult {
+    let mut fs = InMemoryFileSystem::new();
+    for (name, source) in &case.files {
+        fs.add_file(name, source.as_str());
+    }
+    let roots: Vec<&str> = case.files.iter().map(|(name, _)| name.as_str()).collect();
+    let program = Program::load(case.options.clone(), &roots, &fs);
+    let mut actual: Vec<_> = program
+        .check()
+        .iter()
+        .filter(|diagnostic| diagnostic.category == crate::DiagnosticCategory::Error)
+        .map(|diagnostic| to_baseline_error(case, diagnostic))
+        .collect();
+    actual.sort();
+    CaseResult { expected, actual }
+}
+
+fn to_baseline_error(case: &TestCase, diagnostic: &Diagnostic) -> BaselineError {
+    let file = diagnostic.file_name.trim_start_matches('/');
+    let source = case
+        .files
+        .iter()
+        .find(|(name, _)| name == file)
+        .map(|(_, source)| source.as_str());
+    let (line, column) = match (diagnostic.span, source) {
+        (Some(span), Some(source)) => {
+            let (line, column) = LineIndex::new(source).line_col(span.start);
+            (line + 1, column + 1)
+        }
+        _ => (0, 0),
+    };
+    BaselineError {
+        file: Some(file.to_string()),
+        line,
+        column,
+        message: diagnostic.message.clone(),
+    }
+}
+
+/// The cases in `passing` that no longer pass, and those in `now_passing` that didn't
+/// before.
+pub fn passing_delta<'a>(
+    passing: &'a BTreeSet<String>,
+    now_passing: &'a BTreeSet<String>,
+) -> (Vec<&'a str>, Vec<&'a str>) {
+    let regressed = passing
+        .difference(now_passing)
+        .map(String::as_str)
+        .collect();
+    let fixed = now_passing
+        .difference(passing)
+        .map(String::as_str)
+        .collect();
+    (regressed, fixed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_test_case() {
+        let source = "// @strict: false\n// @target: es5, es2015\n// @declaration: true\n\
+                      // @filename: a.ts\nexport const a = 1;\n// @Filename: b.ts\nimport { a } from './a';\n";
+        let case = parse_test_case("tests/cases/conformance/multi.ts", source);
+        assert_eq!(
+            case.files,
+            vec![
+                ("a.ts".to_string(), "export const a = 1;\n".to_string()),
+                ("b.ts".to_string(), "import { a } from './a';\n".to_string())
+            ]
+        );
+        assert_eq!(case.options.strict, Some(false));
+        assert_eq!(case.unsupported_options, vec!["target", "declaration"]);
+        assert!(case.has_variants);
+
+        let case = parse_test_case("single.ts", "let x: number = 1;\n");
+        assert_eq!(case.files[0].0, "single.ts");
+        assert!(!case.has_variants);
+    }
+
+    #[test]
+    fn test_parse_error_baseline() {
+        let baseline = "\
+error TS5107: Option 'target=ES3' is deprecated.
+single.ts(2,5): error TS2322: Type 'string' is not assignable to type 'number'.
+
+
+==== single.ts (1 errors) ====
+    let x: number = 1;
+    let y: number = 'a';
+        ~
+!!! error TS2322: Type 'string' is not assignable to type 'number'.
+";
+        let errors = parse_error_baseline(baseline);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors[0].file, None);
+        assert_eq!(
+            errors[1],
+            BaselineError {
+                file: Some("single.ts".to_string()),
+                line: 2,
+                column: 5,
+                message: "Type 'string' is not assignable to type 'number'.".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_run_case() {
+        let case = parse_test_case("single.ts", "let x = 1;\nlet y: number = 'a';\n");
+        let expected = vec![BaselineError {
+            file: Some("single.ts".to_string()),
+            line: 2,
+            column: 5,
+            message: "Type 'string' is not assignable to type 'number'.".to_string(),
+        }];
+        let result = run_case(&case, expected.clone());
+        assert_eq!(result.actual.len(), 1);
+        assert_eq!(result.actual[0].file.as_deref(), Some("single.ts"));
+        assert_eq!(result.actual[0].line, 2);
+
+        let clean = parse_test_case("clean.ts", "let x: number = 1;\n");
+        assert!(run_case(&clean, Vec::new()).passed());
+        let result = run_case(&clean, expected);
+        assert!(!result.passed());
+        assert_eq!(result.missing().len(), 1);
+        assert!(result.unexpected().is_empty());
+    }
+
+    #[test]
+    fn test_passing_delta() {
+        let before = BTreeSet::from(["a.ts".to_string(), "b.ts".to_string()]);
+        let after = BTreeSet::from(["b.ts".to_string(), "c.ts".to_string()]);
+        assert_eq!(passing_delta(&before, &after), (vec!["a.ts"], vec!["c.ts"]));
+    }
+}
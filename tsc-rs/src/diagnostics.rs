@@ -0,0 +1,630 @@
+use crate::options::DiagnosticSeverity;
+use crate::parser::SyntaxError;
+use crate::types::TypeError;
+use oxc_span::Span;
+use std::collections::{BTreeMap, HashSet};
+use std::fmt;
+
+/// How serious a diagnostic is; only errors mean the program is invalid.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DiagnosticCategory {
+    #[default]
+    Error,
+    Warning,
+}
+
+impl fmt::Display for DiagnosticCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiagnosticCategory::Error => write!(f, "error"),
+            DiagnosticCategory::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// A problem reported for a source file, either by the parser or the type checker.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Diagnostic {
+    pub file_name: String,
+    pub message: String,
+    pub span: Option<Span>,
+    pub category: DiagnosticCategory,
+    /// The number TypeScript reports the message under, like 2322, when it's one of the
+    /// messages in [`MESSAGES`].
+    pub code: Option<u32>,
+    /// What goes in each placeholder of the message reported under `code`, for translating
+    /// it.
+    pub arguments: Vec<String>,
+}
+
+impl Diagnostic {
+    /// A diagnostic with a message of its own, which no catalog translates.
+    pub fn new(file_name: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            file_name: file_name.into(),
+            message: message.into(),
+            span: None,
+            category: DiagnosticCategory::Error,
+            code: None,
+            arguments: Vec::new(),
+        }
+    }
+
+    pub fn with_span(file_name: impl Into<String>, message: impl Into<String>, span: Span) -> Self {
+        Self {
+            span: Some(span),
+            ..Self::new(file_name, message)
+        }
+    }
+
+    /// A diagnostic reporting one of [`MESSAGES`], as a warning if its code is one.
+    pub fn from_message(
+        file_name: impl Into<String>,
+        message: Message,
+        span: Option<Span>,
+    ) -> Self {
+        let diagnostic = Self {
+            file_name: file_name.into(),
+            message: message.text(),
+            span,
+            category: DiagnosticCategory::Error,
+            code: Some(message.code),
+            arguments: message.arguments,
+        };
+        match WARNINGS.contains(&message.code) {
+            true => diagnostic.warning(),
+            false => diagnostic,
+        }
+    }
+
+    /// The same diagnostic, reported as a warning rather than an error.
+    pub fn warning(self) -> Self {
+        Self {
+            category: DiagnosticCategory::Warning,
+            ..self
+        }
+    }
+
+    pub fn from_syntax_error(file_name: impl Into<String>, error: &SyntaxError) -> Self {
+        Self {
+            span: error.span,
+            ..Self::new(file_name, error.message.clone())
+        }
+    }
+
+    pub fn from_type_error(file_name: impl Into<String>, error: &TypeError) -> Self {
+        Self::from_message(file_name, error.message.clone(), error.span)
+    }
+}
+
+/// One of [`MESSAGES`]: the code it's reported under and what goes in each of its
+/// placeholders.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    pub code: u32,
+    pub arguments: Vec<String>,
+}
+
+impl Message {
+    pub fn new(code: u32, arguments: &[&dyn fmt::Display]) -> Self {
+        debug_assert!(message_template(code).is_some(), "no message {}", code);
+        Self {
+            code,
+            arguments: arguments.iter().map(ToString::to_string).collect(),
+        }
+    }
+
+    /// The message in English.
+    pub fn text(&self) -> String {
+        format_message(
+            message_template(self.code).unwrap_or_default(),
+            &self.arguments,
+        )
+    }
+}
+
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text())
+    }
+}
+
+/// Every message the checker reports, keyed by the code TypeScript reports it under.
+/// `{0}`, `{1}` and so on stand for the parts that vary, numbered the way TypeScript's own
+/// catalogs number them, so a translated catalog can rearrange them. Messages TypeScript
+/// doesn't have are numbered from 100001, which no catalog translates.
+pub const MESSAGES: &[(u32, &str)] = &[
+    (2322, "Type '{0}' is not assignable to type '{1}'"),
+    (
+        2345,
+        "Argument of type '{0}' is not assignable to parameter of type '{1}'{2}.",
+    ),
+    (2339, "Property '{0}' does not exist on type '{1}'."),
+    (2304, "Cannot find name '{0}'."),
+    (2305, "Module '{0}' has no exported member '{1}'."),
+    (1192, "Module '{0}' has no default export."),
+    (
+        2307,
+        "Cannot find module '{0}' or its corresponding type declarations.",
+    ),
+    (
+        2348,
+        "Value of type '{0}' is not callable. Did you mean to include 'new'?",
+    ),
+    (2349, "This expression is not callable.{0}"),
+    (2351, "This expression is not constructable.{0}"),
+    (
+        2356,
+        "An arithmetic operand must be of type 'any', 'number', 'bigint' or an enum type.",
+    ),
+    (
+        2353,
+        "Object literal may only specify known properties, and '{0}' does not exist in type '{1}'.",
+    ),
+    (
+        2367,
+        "This comparison appears to be unintentional because the types '{0}' and '{1}' have no overlap.",
+    ),
+    (
+        2432,
+        "In an enum with multiple declarations, only one declaration can omit an initializer for its first enum element.",
+    ),
+    (
+        2310,
+        "Type '{0}' recursively references itself as a base type.",
+    ),
+    (2456, "Type alias '{0}' circularly references itself."),
+    (
+        2469,
+        "The '{0}' operator cannot be applied to type 'symbol'.",
+    ),
+    (2473, "Enum declarations must all be const or non-const."),
+    (
+        2474,
+        "const enum member initializers must be constant expressions.",
+    ),
+    (
+        2475,
+        "'const' enums can only be used in property or index access expressions or the right hand side of an import declaration or export assignment or type query.",
+    ),
+    (
+        2476,
+        "A const enum member can only be accessed using a string literal.",
+    ),
+    (
+        2488,
+        "Type '{0}' must have a '[Symbol.iterator]()' method that returns an iterator.",
+    ),
+    (
+        2493,
+        "Tuple type '{0}' of length '{1}' has no element at index '{2}'.",
+    ),
+    (2538, "Type '{0}' cannot be used as an index type."),
+    (
+        2540,
+        "Cannot assign to '{0}' because it is a read-only property.",
+    ),
+    (2571, "Object is of type 'unknown'."),
+    (18046, "'{0}' is of type 'unknown'."),
+    (2555, "Expected at least {0} arguments, but got {1}."),
+    (2554, "Expected {0} arguments, but got {1}."),
+    (
+        2564,
+        "Property '{0}' has no initializer and is not definitely assigned in the constructor.",
+    ),
+    (
+        2703,
+        "The operand of a 'delete' operator must be a property reference.",
+    ),
+    (
+        2704,
+        "The operand of a 'delete' operator cannot be a read-only property.",
+    ),
+    (2736, "Operator '{0}' cannot be applied to type '{1}'."),
+    (
+        2748,
+        "Cannot access ambient const enums when 'isolatedModules' is enabled.",
+    ),
+    (2790, "The operand of a 'delete' operator must be optional."),
+    (
+        1066,
+        "In ambient enum declarations member initializer must be constant expression.",
+    ),
+    (
+        1149,
+        "File name '{0}' differs from already included file name '{1}' only in casing.",
+    ),
+    (
+        1205,
+        "Re-exporting a type when 'isolatedModules' is enabled requires using 'export type'.",
+    ),
+    (
+        1208,
+        "'{0}' cannot be compiled under '--isolatedModules' because it is considered a global script file. Add an import, export, or an empty 'export {}' statement to make it a module.",
+    ),
+    (1206, "Decorators are not valid here."),
+    (
+        1238,
+        "Unable to resolve signature of class decorator when called as an expression.",
+    ),
+    (
+        1239,
+        "Unable to resolve signature of parameter decorator when called as an expression.",
+    ),
+    (
+        1240,
+        "Unable to resolve signature of property decorator when called as an expression.",
+    ),
+    (
+        1241,
+        "Unable to resolve signature of method decorator when called as an expression.",
+    ),
+    (
+        1271,
+        "Decorator function return type '{0}' is not assignable to type '{1}'.",
+    ),
+    (
+        1309,
+        "The current file is a CommonJS module and cannot use 'await' at the top level.",
+    ),
+    (
+        1378,
+        "Top-level 'await' expressions are only allowed when the 'module' option is set to 'es2022', 'esnext', 'system', 'node16', 'nodenext', or 'preserve', and the 'target' option is set to 'es2017' or higher.",
+    ),
+    (
+        4112,
+        "This member cannot have an 'override' modifier because its containing class '{0}' does not extend another class.",
+    ),
+    (
+        4113,
+        "This member cannot have an 'override' modifier because it is not declared in the base class '{0}'.",
+    ),
+    (
+        4114,
+        "This member must have an 'override' modifier because it overrides a member in the base class '{0}'.",
+    ),
+    (5023, "Unknown compiler option '{0}'."),
+    (5033, "Could not write file '{0}': {1}."),
+    (6053, "File '{0}' not found."),
+    (
+        6059,
+        "File '{0}' is not under 'rootDir' '{1}'. 'rootDir' is expected to contain all source files.",
+    ),
+    (7006, "Parameter '{0}' implicitly has an '{1}' type."),
+    (7019, "Rest parameter '{0}' implicitly has an '{1}' type."),
+    (7029, "Fallthrough case in switch."),
+    (
+        7053,
+        "Element implicitly has an 'any' type because expression of type '{0}' can't be used to index type '{1}'.",
+    ),
+    (
+        18032,
+        "The intersection '{0}' was reduced to 'never' because property '{1}' has conflicting types in some constituents.",
+    ),
+    (
+        100001,
+        "The binary operation between '{0}' and '{1}' is not allowed",
+    ),
+    (100002, "Expected an error object to be thrown."),
+];
+
+/// Codes reported as warnings, for code that's valid but almost certainly not what was meant.
+const WARNINGS: &[u32] = &[18032];
+
+/// Splits a message template into the text around its placeholders and the numbers of
+/// the placeholders, in the order they're written.
+fn template_parts(template: &str) -> (Vec<&str>, Vec<usize>) {
+    let mut texts = Vec::new();
+    let mut numbers = Vec::new();
+    let mut text_start = 0;
+    let mut search = 0;
+    while let Some(open) = template[search..].find('{').map(|index| search + index) {
+        let placeholder = template[open + 1..]
+            .split_once('}')
+            .and_then(|(digits, _)| Some((digits.len(), digits.parse::<usize>().ok()?)));
+        match placeholder {
+            Some((length, number)) => {
+                texts.push(&template[text_start..open]);
+                numbers.push(number);
+                text_start = open + length + 2;
+                search = text_start;
+            }
+            None => search = open + 1,
+        }
+    }
+    texts.push(&template[text_start..]);
+    (texts, numbers)
+}
+
+/// `template` with each placeholder replaced by its argument. Placeholders without one
+/// are left as they are.
+pub fn format_message(template: &str, arguments: &[impl AsRef<str>]) -> String {
+    let (texts, numbers) = template_parts(template);
+    let mut message = texts[0].to_string();
+    for (number, text) in numbers.iter().zip(&texts[1..]) {
+        match arguments.get(*number) {
+            Some(argument) => message.push_str(argument.as_ref()),
+            None => message.push_str(&format!("{{{}}}", number)),
+        }
+        message.push_str(text);
+    }
+    message
+}
+
+/// The message TypeScript reports under `code`, if it's one of [`MESSAGES`].
+pub fn message_template(code: u32) -> Option<&'static str> {
+    MESSAGES
+        .iter()
+        .find(|(message_code, _)| *message_code == code)
+        .map(|(_, template)| *template)
+}
+
+/// Reports each diagnostic whose code has a severity in `severities` as that instead,
+/// leaving out those turned off, and drops diagnostics identical to one before them.
+pub fn filter_diagnostics(
+    diagnostics: Vec<Diagnostic>,
+    severities: &BTreeMap<u32, DiagnosticSeverity>,
+) -> Vec<Diagnostic> {
+    let mut seen = HashSet::new();
+    diagnostics
+        .into_iter()
+        .filter_map(|diagnostic| {
+            let severity = diagnostic.code.and_then(|code| severities.get(&code));
+            match severity {
+                Some(DiagnosticSeverity::Off) => None,
+                Some(DiagnosticSeverity::Warning) => Some(diagnostic.warning()),
+                Some(DiagnosticSeverity::Error) => Some(Diagnostic {
+                    category: DiagnosticCategory::Error,
+                    ..diagnostic
+                }),
+                None => Some(diagnostic),
+            }
+        })
+        .filter(|diagnostic| {
+            seen.insert((
+                diagnostic.file_name.clone(),
+                diagnostic.span,
+                diagnostic.message.clone(),
+            ))
+        })
+        .collect()
+}
+
+/// Leaves out the errors after the first `max_errors`, returning the diagnostics still
+/// reported, warnings included, and how many errors were left out.
+pub fn cap_errors(diagnostics: Vec<Diagnostic>, max_errors: usize) -> (Vec<Diagnostic>, usize) {
+    let mut errors = 0;
+    let mut omitted = 0;
+    let kept = diagnostics
+        .into_iter()
+        .filter(|diagnostic| {
+            if diagnostic.category != DiagnosticCategory::Error {
+                return true;
+            }
+            errors += 1;
+            if errors > max_errors {
+                omitted += 1;
+            }
+            errors <= max_errors
+        })
+        .collect();
+    (kept, omitted)
+}
+
+/// The summary tsc prints after a run's errors, like `Found 3 errors in 2 files.`, with
+/// a table of how many errors each file has when there's more than one file.
+/// `position_of` gives the one-based line and column a diagnostic starts at, to point at
+/// where each file's errors start. `None` when there are no errors.
+pub fn error_summary(
+    diagnostics: &[Diagnostic],
+    position_of: impl Fn(&Diagnostic) -> Option<(u32, u32)>,
+) -> Option<String> {
+    // Each file with errors, in the order they're reported, with its first error and count
+    let mut files: Vec<(&Diagnostic, usize)> = Vec::new();
+    for diagnostic in diagnostics {
+        if diagnostic.category != DiagnosticCategory::Error {
+            continue;
+        }
+        match files
+            .iter_mut()
+            .find(|(first, _)| first.file_name == diagnostic.file_name)
+        {
+            Some((_, count)) => *count += 1,
+            None => files.push((diagnostic, 1)),
+        }
+    }
+    let location = |diagnostic: &Diagnostic| match position_of(diagnostic) {
+        Some((line, _)) => format!("{}:{}", diagnostic.file_name, line),
+        None => diagnostic.file_name.clone(),
+    };
+    let total: usize = files.iter().map(|(_, count)| count).sum();
+    match files.as_slice() {
+        [] => None,
+        [(first, 1)] => Some(format!("Found 1 error in {}\n", location(first))),
+        [(first, count)] => Some(format!(
+            "Found {} errors in the same file, starting at: {}\n",
+            count,
+            location(first)
+        )),
+        files => {
+            let mut summary = format!(
+                "Found {} errors in {} files.\n\nErrors  Files\n",
+                total,
+                files.len()
+            );
+            for (first, count) in files {
+                summary.push_str(&format!("{:>6}  {}\n", count, location(first)));
+            }
+            Some(summary)
+        }
+    }
+}
+
+impl Diagnostic {
+    /// The diagnostic as it's printed, `file(line,column): error: message` like the
+    /// baselines, where `position` is the one-based line and column it starts at.
+    pub fn display_at(&self, position: Option<(u32, u32)>) -> String {
+        match position {
+            Some((line, column)) => format!(
+                "{}({},{}): {}: {}",
+                self.file_name, line, column, self.category, self.message
+            ),
+            None => self.to_string(),
+        }
+    }
+}
+
+/// Formats the diagnostic without its position, which takes the file's source to find.
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.file_name, self.category, self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_message() {
+        let mismatch = Diagnostic::from_message(
+            "a.ts",
+            Message::new(2322, &[&"string", &"number"]),
+            Some(Span::new(0, 1)),
+        );
+        assert_eq!(
+            mismatch.message,
+            "Type 'string' is not assignable to type 'number'"
+        );
+        assert_eq!(mismatch.code, Some(2322));
+        assert_eq!(mismatch.arguments, vec!["string", "number"]);
+        assert_eq!(mismatch.category, DiagnosticCategory::Error);
+
+        let reduced =
+            Diagnostic::from_message("a.ts", Message::new(18032, &[&"A & B", &"kind"]), None);
+        assert_eq!(
+            reduced.message,
+            "The intersection 'A & B' was reduced to 'never' because property 'kind' has conflicting types in some constituents."
+        );
+        assert_eq!(reduced.category, DiagnosticCategory::Warning);
+
+        // A message that happens to read like one of TypeScript's still has no code
+        let uncoded = Diagnostic::new("a.ts", "Type 'string' is not assignable to type 'number'");
+        assert_eq!(uncoded.code, None);
+        assert!(uncoded.arguments.is_empty());
+    }
+
+    #[test]
+    fn test_message_text() {
+        assert_eq!(
+            Message::new(2493, &[&"[number]", &1, &2]).text(),
+            "Tuple type '[number]' of length '1' has no element at index '2'."
+        );
+        assert_eq!(
+            Message::new(2349, &[&""]).text(),
+            "This expression is not callable."
+        );
+        assert_eq!(
+            Message::new(2345, &[&"string", &"number", &" in call to 'f'"]).text(),
+            "Argument of type 'string' is not assignable to parameter of type 'number' in call to 'f'."
+        );
+        assert_eq!(
+            Message::new(2571, &[]).to_string(),
+            "Object is of type 'unknown'."
+        );
+    }
+
+    #[test]
+    fn test_format_message() {
+        assert_eq!(
+            format_message("{1} before {0}, {2} left, {} literal", &["a", "b"]),
+            "b before a, {2} left, {} literal"
+        );
+        let template = message_template(2322).unwrap();
+        assert_eq!(
+            format_message(template, &["string".to_string(), "number".to_string()]),
+            "Type 'string' is not assignable to type 'number'"
+        );
+    }
+
+    #[test]
+    fn test_filter_diagnostics() {
+        let mismatch = Diagnostic::from_message(
+            "a.ts",
+            Message::new(2322, &[&"string", &"number"]),
+            Some(Span::new(0, 1)),
+        );
+        let missing = Diagnostic::from_message("a.ts", Message::new(6053, &[&"b.ts"]), None);
+        let uncoded = Diagnostic::new("a.ts", "Something else.").warning();
+        let diagnostics = vec![
+            mismatch.clone(),
+            missing.clone(),
+            mismatch.clone(),
+            uncoded.clone(),
+        ];
+
+        let severities = BTreeMap::from([
+            (2322, DiagnosticSeverity::Warning),
+            (6053, DiagnosticSeverity::Off),
+        ]);
+        let filtered = filter_diagnostics(diagnostics.clone(), &severities);
+        assert_eq!(filtered, vec![mismatch.clone().warning(), uncoded.clone()]);
+
+        let unfiltered = filter_diagnostics(diagnostics, &BTreeMap::new());
+        assert_eq!(unfiltered, vec![mismatch, missing, uncoded]);
+    }
+
+    #[test]
+    fn test_cap_errors() {
+        let error = |n: u32| Diagnostic::new("a.ts", format!("Error {}.", n));
+        let warning = Diagnostic::new("a.ts", "Careful.").warning();
+        let diagnostics = vec![error(1), error(2), warning.clone(), error(3), error(4)];
+        let (kept, omitted) = cap_errors(diagnostics, 2);
+        assert_eq!(kept, vec![error(1), error(2), warning]);
+        assert_eq!(omitted, 2);
+    }
+
+    #[test]
+    fn test_display() {
+        let error = Diagnostic::with_span("a.ts", "Cannot find name 'x'.", Span::new(4, 5));
+        assert_eq!(
+            error.display_at(Some((2, 5))),
+            "a.ts(2,5): error: Cannot find name 'x'."
+        );
+        assert_eq!(error.display_at(None), "a.ts: error: Cannot find name 'x'.");
+        let warning = Diagnostic::new("b.ts", "Careful.").warning();
+        assert_eq!(warning.to_string(), "b.ts: warning: Careful.");
+    }
+
+    #[test]
+    fn test_error_summary() {
+        let error = |file: &str, start: u32| {
+            Diagnostic::with_span(file, "Cannot find name 'x'.", Span::new(start, start + 1))
+        };
+        let warning = Diagnostic::new("c.ts", "Careful.").warning();
+        let position_of = |diagnostic: &Diagnostic| diagnostic.span.map(|span| (span.start + 1, 1));
+        let summary = |diagnostics: &[Diagnostic]| error_summary(diagnostics, position_of);
+
+        assert_eq!(summary(&[]), None);
+        assert_eq!(summary(std::slice::from_ref(&warning)), None);
+        assert_eq!(
+            summary(&[error("a.ts", 2)]).unwrap(),
+            "Found 1 error in a.ts:3\n"
+        );
+        assert_eq!(
+            summary(&[error("a.ts", 2), error("a.ts", 5)]).unwrap(),
+            "Found 2 errors in the same file, starting at: a.ts:3\n"
+        );
+        assert_eq!(
+            summary(&[
+                error("a.ts", 2),
+                warning,
+                error("b.ts", 0),
+                error("a.ts", 5),
+                Diagnostic::new("b.ts", "Cannot find name 'y'."),
+            ])
+            .unwrap(),
+            "Found 4 errors in 2 files.\n\nErrors  Files\n     2  a.ts:3\n     2  b.ts:1\n"
+        );
+    }
+}
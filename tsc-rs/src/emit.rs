@@ -0,0 +1,517 @@
+//! JavaScript emit.
+//!
+//! Rather than printing a new tree, emit rewrites the original source text: TypeScript-only
+//! syntax is cut out, and enums, namespaces, and parameter properties are replaced with
+//! their runtime equivalents. Everything else is copied through byte for byte.
+//! [`CustomTransformers`] can rewrite the text before and after those steps.
+
+mod commonjs;
+mod downlevel;
+mod enums;
+mod namespaces;
+mod strip;
+mod transforms;
+
+pub use transforms::{CustomTransformers, TransformContext, Transformer};
+
+use crate::diagnostics::{Diagnostic, DiagnosticCategory, Message};
+use crate::host::{FileSystem, normalize_path};
+use crate::options::CompilerOptions;
+use crate::parser::{is_declaration_file, parse_typescript_as, source_type_for};
+use crate::program::Program;
+use oxc_ast::Visit;
+use oxc_ast::ast::{BindingIdentifier, IdentifierName, IdentifierReference, Statement};
+use oxc_span::{SourceType, Span};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutputFile {
+    pub name: String,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct EmitResult {
+    pub output_files: Vec<OutputFile>,
+    pub diagnostics: Vec<Diagnostic>,
+    /// Whether `noEmit`, or `noEmitOnError` with errors to report, kept anything from
+    /// being emitted.
+    pub emit_skipped: bool,
+}
+
+/// Converts one TypeScript module to JavaScript without looking at any other file.
+pub fn transpile(source: &str, options: &CompilerOptions) -> Result<String, String> {
+    transpile_file("input.ts", source, options)
+}
+
+/// Converts the file named `file_name` to JavaScript, parsing and emitting it as its
+/// extension requires: `.tsx` files keep their JSX, and `.mts` and `.cts` files choose
+/// their module system themselves.
+pub fn transpile_file(
+    file_name: &str,
+    source: &str,
+    options: &CompilerOptions,
+) -> Result<String, String> {
+    transpile_file_with(file_name, source, options, &CustomTransformers::default())
+}
+
+/// [`transpile_file`], running `transformers` before types are stripped and after the
+/// output is otherwise complete.
+pub fn transpile_file_with(
+    file_name: &str,
+    source: &str,
+    options: &CompilerOptions,
+    transformers: &CustomTransformers,
+) -> Result<String, String> {
+    let source_type = source_type_for(file_name);
+    let source = transforms::apply_transformers(
+        &transformers.before,
+        file_name,
+        source.into(),
+        source_type,
+    )?;
+    let ts_program = parse_typescript_as(&source, source_type)?;
+    let preserve_const_enums = options.preserve_const_enums || options.isolated_modules;
+    let javascript = strip::strip_types(&ts_program.program, &source, preserve_const_enums);
+    let javascript = downlevel::downlevel(javascript, options.target, source_type)?;
+    let javascript = match options.module_for(file_name).is_es_module() {
+        true => javascript,
+        false => commonjs::to_commonjs(&javascript, source_type)?,
+    };
+    let javascript = match options.strictness().always_strict {
+        true => use_strict(javascript, source_type)?,
+        false => javascript,
+    };
+    transforms::apply_transformers(&transformers.after, file_name, javascript, source_type)
+}
+
+/// Starts a file that isn't a module with `"use strict"`, under alwaysStrict. Modules are
+/// strict already, and CommonJS output says so itself.
+fn use_strict(javascript: String, source_type: SourceType) -> Result<String, String> {
+    let ts_program = parse_typescript_as(&javascript, source_type)?;
+    let program = &ts_program.program;
+    if program.body.iter().any(Statement::is_module_declaration) || has_use_strict(program) {
+        drop(ts_program);
+        return Ok(javascript);
+    }
+    let mut edits = TextEdits::default();
+    insert_at_top(program, &mut edits, "\"use strict\";");
+    Ok(edits.apply(&javascript))
+}
+
+fn has_use_strict(program: &oxc_ast::ast::Program) -> bool {
+    program
+        .directives
+        .iter()
+        .any(|directive| directive.directive == "use strict")
+}
+
+/// The whitespace at the start of the line containing `offset`.
+fn indent_at(source: &str, offset: u32) -> &str {
+    let line_start = source[..offset as usize].rfind('\n').map_or(0, |i| i + 1);
+    let line = &source[line_start..];
+    &line[..line.len() - line.trim_start_matches([' ', '\t']).len()]
+}
+
+/// Widens a statement's span to its whole line when nothing else is on it.
+fn line_span(source: &str, span: Span) -> Span {
+    let (start, end) = (span.start as usize, span.end as usize);
+    let line_start = source[..start].rfind('\n').map_or(0, |i| i + 1);
+    let rest = &source[end..];
+    let line_end = rest.find('\n');
+    let trailing = &rest[..line_end.unwrap_or(rest.len())];
+    if source[line_start..start].trim().is_empty() && trailing.trim().is_empty() {
+        let end = end + trailing.len() + line_end.map_or(0, |_| 1);
+        Span::new(line_start as u32, end as u32)
+    } else {
+        span
+    }
+}
+
+/// How an enum or namespace IIFE binds its object, e.g. `var E;` and `E || (E = {})`.
+#[derive(Debug)]
+struct IifeBinding {
+    /// The variable declaration, omitted when merging with an earlier declaration.
+    declaration: Option<String>,
+    argument: String,
+}
+
+/// Inserts a statement at the top of the file, after any hashbang and directives.
+fn insert_at_top(program: &oxc_ast::ast::Program, edits: &mut TextEdits, statement: &str) {
+    let prologue_end = program
+        .directives
+        .last()
+        .map(|directive| directive.span.end)
+        .or_else(|| program.hashbang.as_ref().map(|hashbang| hashbang.span.end));
+    match prologue_end {
+        Some(end) => edits.insert(end, format!("\n{statement}")),
+        None => edits.insert(0, format!("{statement}\n")),
+    }
+}
+
+/// Every name written anywhere in the file, whatever it refers to.
+fn names_in(program: &oxc_ast::ast::Program) -> HashSet<String> {
+    let mut names = Names::default();
+    names.visit_program(program);
+    names.0
+}
+
+#[derive(Default)]
+struct Names(HashSet<String>);
+
+impl<'a> Visit<'a> for Names {
+    fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+        self.0.insert(ident.name.to_string());
+    }
+
+    fn visit_binding_identifier(&mut self, ident: &BindingIdentifier<'a>) {
+        self.0.insert(ident.name.to_string());
+    }
+
+    fn visit_identifier_name(&mut self, ident: &IdentifierName<'a>) {
+        self.0.insert(ident.name.to_string());
+    }
+}
+
+/// A pending change to the source text, applied in order of `span.start`.
+#[derive(Debug)]
+struct Edit {
+    span: Span,
+    text: String,
+}
+
+#[derive(Debug, Default)]
+struct TextEdits {
+    edits: Vec<Edit>,
+}
+
+impl TextEdits {
+    fn replace(&mut self, span: Span, text: impl Into<String>) {
+        self.edits.push(Edit {
+            span,
+            text: text.into(),
+        });
+    }
+
+    fn remove(&mut self, span: Span) {
+        self.replace(span, "");
+    }
+
+    fn insert(&mut self, at: u32, text: impl Into<String>) {
+        self.replace(Span::new(at, at), text);
+    }
+
+    fn is_empty(&self) -> bool {
+        self.edits.is_empty()
+    }
+
+    /// Applies every edit; an edit starting inside an earlier replaced range is dropped,
+    /// so removing a whole declaration also discards the edits made within it.
+    fn apply(mut self, source: &str) -> String {
+        // Insertions go before a removal at the same offset, and outer removals before inner
+        self.edits.sort_by_key(|edit| {
+            (
+                edit.span.start,
+                !edit.span.is_empty(),
+                std::cmp::Reverse(edit.span.end),
+            )
+        });
+        let mut output = String::with_capacity(source.len());
+        let mut cursor = 0;
+        for edit in self.edits {
+            let start = edit.span.start as usize;
+            if start < cursor {
+                continue;
+            }
+            output.push_str(&source[cursor..start]);
+            output.push_str(&edit.text);
+            cursor = edit.span.end as usize;
+        }
+        output.push_str(&source[cursor..]);
+        output
+    }
+}
+
+fn output_extension(path: &Path) -> &'static str {
+    match path.extension().and_then(|ext| ext.to_str()) {
+        Some("mts") => "mjs",
+        Some("cts") => "cjs",
+        _ => "js",
+    }
+}
+
+/// The deepest directory containing every input, used as the default `rootDir`.
+fn common_source_directory<'a>(names: impl IntoIterator<Item = &'a str>) -> PathBuf {
+    let mut common: Option<PathBuf> = None;
+    for name in names {
+        let dir = Path::new(name)
+            .parent()
+            .unwrap_or(Path::new(""))
+            .to_path_buf();
+        common = Some(match common {
+            None => dir,
+            Some(current) => current
+                .components()
+                .zip(dir.components())
+                .take_while(|(a, b)| a == b)
+                .map(|(a, _)| a)
+                .collect(),
+        });
+    }
+    common.unwrap_or_default()
+}
+
+impl Program {
+    /// Emits JavaScript for every non-declaration file, honoring `outDir` and `rootDir`.
+    ///
+    /// Emit doesn't depend on checking, except that `noEmitOnError` checks the program
+    /// first; results are cached, so checking it again afterwards is cheap.
+    pub fn emit(&self) -> EmitResult {
+        self.emit_with_transformers(&CustomTransformers::default())
+    }
+
+    /// [`Self::emit`], running `transformers` on each file.
+    pub fn emit_with_transformers(&self, transformers: &CustomTransformers) -> EmitResult {
+        let options: &CompilerOptions = self.options();
+        if options.no_emit || (options.no_emit_on_error && self.has_errors()) {
+            return EmitResult {
+                emit_skipped: true,
+                ..Default::default()
+            };
+        }
+        let sources: Vec<_> = self
+            .files()
+            .iter()
+            .filter(|file| !is_declaration_file(&file.name))
+            .collect();
+        let root_dir = match &options.root_dir {
+            Some(dir) => normalize_path(dir),
+            None => common_source_directory(sources.iter().map(|file| file.name.as_str())),
+        };
+
+        let mut result = EmitResult::default();
+        for file in sources {
+            let path = Path::new(&file.name);
+            let output_dir = match &options.out_dir {
+                Some(out_dir) => {
+                    let Ok(relative) = path.strip_prefix(&root_dir) else {
+                        result.diagnostics.push(Diagnostic::from_message(
+                            &file.name,
+                            Message::new(6059, &[&file.name, &root_dir.display()]),
+                            None,
+                        ));
+                        continue;
+                    };
+                    normalize_path(&out_dir.join(relative))
+                }
+                None => path.to_path_buf(),
+            };
+            match transpile_file_with(&file.name, &file.source, options, transformers) {
+                Ok(text) => result.output_files.push(OutputFile {
+                    name: output_dir
+                        .with_extension(output_extension(path))
+                        .to_string_lossy()
+                        .into_owned(),
+                    text,
+                }),
+                Err(message) => result
+                    .diagnostics
+                    .push(Diagnostic::new(&file.name, message)),
+            }
+        }
+        result
+    }
+
+    fn has_errors(&self) -> bool {
+        self.check()
+            .iter()
+            .any(|diagnostic| diagnostic.category == DiagnosticCategory::Error)
+    }
+
+    /// Emits the program and writes each output through `fs`.
+    pub fn emit_to(&self, fs: &mut dyn FileSystem) -> EmitResult {
+        let mut result = self.emit();
+        for output in &result.output_files {
+            if let Err(err) = fs.write_file(Path::new(&output.name), &output.text) {
+                result.diagnostics.push(Diagnostic::from_message(
+                    &output.name,
+                    Message::new(5033, &[&output.name, &err]),
+                    None,
+                ));
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::InMemoryFileSystem;
+    use crate::testing::TestProject;
+
+    #[test]
+    fn test_text_edits_skip_nested_edits() {
+        let source = "abcdefgh";
+        let mut edits = TextEdits::default();
+        edits.remove(Span::new(2, 6));
+        edits.replace(Span::new(3, 4), "X");
+        edits.insert(6, "_");
+        edits.replace(Span::new(0, 1), "A");
+        assert_eq!(edits.apply(source), "Ab_gh");
+    }
+
+    #[test]
+    fn test_emit_paths() {
+        let project = TestProject::new()
+            .file("/proj/src/main.ts", "import { x } from './lib/util';\nx;")
+            .file("/proj/src/lib/util.ts", "export const x: number = 1;")
+            .file("/proj/src/types.d.ts", "declare const y: number;")
+            .file("/proj/src/worker.mts", "export {};");
+
+        let outputs: Vec<String> = project
+            .program()
+            .emit()
+            .output_files
+            .into_iter()
+            .map(|output| output.name)
+            .collect();
+        assert_eq!(
+            outputs,
+            vec![
+                "/proj/src/main.js",
+                "/proj/src/lib/util.js",
+                "/proj/src/worker.mjs"
+            ]
+        );
+
+        let options = CompilerOptions {
+            out_dir: Some(PathBuf::from("/proj/dist")),
+            ..Default::default()
+        };
+        let result = project.clone().options(options).program().emit();
+        assert_eq!(result.output_files[0].name, "/proj/dist/main.js");
+        assert_eq!(result.output_files[1].name, "/proj/dist/lib/util.js");
+        assert_eq!(result.output_files[1].text, "export const x = 1;");
+
+        let options = CompilerOptions {
+            out_dir: Some(PathBuf::from("/proj/dist")),
+            root_dir: Some(PathBuf::from("/proj/src/lib")),
+            ..Default::default()
+        };
+        let result = project.options(options).program().emit();
+        assert_eq!(result.output_files.len(), 1);
+        assert_eq!(result.output_files[0].name, "/proj/dist/util.js");
+        assert!(
+            result.diagnostics[0]
+                .message
+                .contains("is not under 'rootDir' '/proj/src/lib'")
+        );
+    }
+
+    #[test]
+    fn test_emit_to_writes_outputs() {
+        let program = TestProject::new()
+            .file("/src/a.ts", "let a: number = 1;")
+            .program();
+        let mut fs = InMemoryFileSystem::new();
+        let result = program.emit_to(&mut fs);
+        assert!(result.diagnostics.is_empty());
+        assert_eq!(fs.read_file(Path::new("/src/a.js")).unwrap(), "let a = 1;");
+    }
+
+    #[test]
+    fn test_emit_follows_file_extensions() {
+        let result = TestProject::new()
+            .file("/src/config.cts", "export const port: number = 80;")
+            .file("/src/worker.mts", "export const jobs: number = 1;")
+            .file(
+                "/src/view.tsx",
+                "export const view = <div>{1 as number}</div>;",
+            )
+            .program()
+            .emit();
+        assert!(result.diagnostics.is_empty(), "{:?}", result.diagnostics);
+        let outputs: Vec<(&str, &str)> = result
+            .output_files
+            .iter()
+            .map(|output| (output.name.as_str(), output.text.as_str()))
+            .collect();
+        assert_eq!(outputs[0].0, "/src/config.cjs");
+        assert!(
+            outputs[0].1.contains("exports.port = port;"),
+            "{}",
+            outputs[0].1
+        );
+        assert_eq!(outputs[1], ("/src/worker.mjs", "export const jobs = 1;"));
+        assert_eq!(
+            outputs[2],
+            ("/src/view.js", "export const view = <div>{1}</div>;")
+        );
+    }
+
+    #[test]
+    fn test_no_emit_options() {
+        let project = TestProject::new()
+            .file("/src/a.ts", "let a: number = 'a';")
+            .file("/src/b.ts", "let b: number = 1;");
+        assert_eq!(project.program().emit().output_files.len(), 2);
+
+        let result = project
+            .clone()
+            .options(CompilerOptions {
+                no_emit_on_error: true,
+                ..Default::default()
+            })
+            .program()
+            .emit();
+        assert!(result.emit_skipped);
+        assert!(result.output_files.is_empty());
+
+        let result = TestProject::new()
+            .file("/src/b.ts", "let b: number = 1;")
+            .options(CompilerOptions {
+                no_emit_on_error: true,
+                ..Default::default()
+            })
+            .program()
+            .emit();
+        assert!(!result.emit_skipped);
+        assert_eq!(result.output_files.len(), 1);
+
+        let result = project
+            .options(CompilerOptions {
+                no_emit: true,
+                ..Default::default()
+            })
+            .program()
+            .emit();
+        assert!(result.emit_skipped);
+        assert!(result.output_files.is_empty());
+    }
+
+    #[test]
+    fn test_always_strict() {
+        let options = CompilerOptions {
+            always_strict: Some(true),
+            ..Default::default()
+        };
+        assert_eq!(
+            transpile("let a: number = 1;", &options).unwrap(),
+            "\"use strict\";\nlet a = 1;"
+        );
+        assert_eq!(
+            transpile("\"use strict\";\nlet a = 1;", &options).unwrap(),
+            "\"use strict\";\nlet a = 1;"
+        );
+        assert_eq!(
+            transpile("export const a = 1;", &options).unwrap(),
+            "export const a = 1;"
+        );
+        assert_eq!(
+            transpile("let a = 1;", &CompilerOptions::default()).unwrap(),
+            "let a = 1;"
+        );
+    }
+}
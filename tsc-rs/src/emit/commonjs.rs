@@ -0,0 +1,929 @@
+//! Rewriting ES module syntax as CommonJS.
+//!
+//! Each imported module is bound once from `require()`, with TypeScript's
+//! `__importDefault`/`__importStar` helpers for default and namespace imports, and imported
+//! names are read from it where they're used. Exports are assigned again whenever their
+//! bindings are, so both stay live as ES module bindings are.
+
+use super::{TextEdits, has_use_strict, indent_at, insert_at_top, names_in};
+use crate::parser::parse_typescript_as;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::{GetSpan, SourceType};
+use oxc_syntax::scope::ScopeFlags;
+use std::collections::{HashMap, HashSet};
+
+/// Helpers in dependency order, so each is defined before the ones that call it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+enum Helper {
+    CreateBinding,
+    SetModuleDefault,
+    ImportStar,
+    ImportDefault,
+    ExportStar,
+}
+
+impl Helper {
+    fn text(self) -> &'static str {
+        match self {
+            Helper::CreateBinding => {
+                r#"var __createBinding = (this && this.__createBinding) || (Object.create ? (function(o, m, k, k2) {
+    if (k2 === undefined) k2 = k;
+    var desc = Object.getOwnPropertyDescriptor(m, k);
+    if (!desc || ("get" in desc ? !m.__esModule : desc.writable || desc.configurable)) {
+      desc = { enumerable: true, get: function() { return m[k]; } };
+    }
+    Object.defineProperty(o, k2, desc);
+}) : (function(o, m, k, k2) {
+    if (k2 === undefined) k2 = k;
+    o[k2] = m[k];
+}));"#
+            }
+            Helper::SetModuleDefault => {
+                r#"var __setModuleDefault = (this && this.__setModuleDefault) || (Object.create ? (function(o, v) {
+    Object.defineProperty(o, "default", { enumerable: true, value: v });
+}) : function(o, v) {
+    o["default"] = v;
+});"#
+            }
+            Helper::ImportStar => {
+                r#"var __importStar = (this && this.__importStar) || function (mod) {
+    if (mod && mod.__esModule) return mod;
+    var result = {};
+    if (mod != null) for (var k in mod) if (k !== "default" && Object.prototype.hasOwnProperty.call(mod, k)) __createBinding(result, mod, k);
+    __setModuleDefault(result, mod);
+    return result;
+};"#
+            }
+            Helper::ImportDefault => {
+                r#"var __importDefault = (this && this.__importDefault) || function (mod) {
+    return (mod && mod.__esModule) ? mod : { "default": mod };
+};"#
+            }
+            Helper::ExportStar => {
+                r#"var __exportStar = (this && this.__exportStar) || function(m, exports) {
+    for (var p in m) if (p !== "default" && !Object.prototype.hasOwnProperty.call(exports, p)) __createBinding(exports, m, p);
+};"#
+            }
+        }
+    }
+
+    fn dependencies(self) -> &'static [Helper] {
+        match self {
+            Helper::ImportStar => &[Helper::CreateBinding, Helper::SetModuleDefault],
+            Helper::ExportStar => &[Helper::CreateBinding],
+            _ => &[],
+        }
+    }
+}
+
+pub(super) fn to_commonjs(source: &str, source_type: SourceType) -> Result<String, String> {
+    let ts_program = parse_typescript_as(source, source_type)?;
+    let program = &ts_program.program;
+    let mut transform = CommonJs {
+        source,
+        edits: TextEdits::default(),
+        helpers: HashSet::new(),
+        used_names: names_in(program),
+        imports: HashMap::new(),
+        exports: HashMap::new(),
+        hoisted: Vec::new(),
+        temporaries: Vec::new(),
+        scopes: Vec::new(),
+        statement_expression: None,
+        parenthesized: None,
+    };
+    transform.collect(program);
+    for stmt in &program.body {
+        transform.lower_statement(stmt);
+    }
+
+    let is_module = program.body.iter().any(Statement::is_module_declaration);
+    let mut prologue = Vec::new();
+    if is_module {
+        if !has_use_strict(program) {
+            prologue.push("\"use strict\";".to_string());
+        }
+        prologue
+            .push("Object.defineProperty(exports, \"__esModule\", { value: true });".to_string());
+    }
+    let mut helpers: Vec<Helper> = transform
+        .helpers
+        .iter()
+        .flat_map(|helper| helper.dependencies().iter().copied().chain([*helper]))
+        .collect::<HashSet<_>>()
+        .into_iter()
+        .collect();
+    helpers.sort();
+    prologue.extend(helpers.into_iter().map(|helper| helper.text().to_string()));
+    // Function declarations are hoisted, so their exports are assigned before anything
+    // runs, as a module importing this one in a cycle may call them first
+    prologue.append(&mut transform.hoisted);
+    if !transform.temporaries.is_empty() {
+        prologue.push(format!("var {};", transform.temporaries.join(", ")));
+    }
+    if !prologue.is_empty() {
+        insert_at_top(program, &mut transform.edits, &prologue.join("\n"));
+    }
+    Ok(transform.edits.apply(source))
+}
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+}
+
+/// `object.name`, or `object["name"]` when the name isn't an identifier.
+fn property(object: &str, name: &str) -> String {
+    if is_identifier(name) {
+        format!("{object}.{name}")
+    } else {
+        format!(
+            "{object}[{}]",
+            serde_json::to_string(name).unwrap_or_default()
+        )
+    }
+}
+
+fn pattern_names(pattern: &BindingPattern) -> impl Iterator<Item = String> {
+    pattern
+        .get_binding_identifiers()
+        .into_iter()
+        .map(|id| id.name.to_string())
+}
+
+fn parameter_names(params: &FormalParameters) -> HashSet<String> {
+    params
+        .items
+        .iter()
+        .map(|param| &param.pattern)
+        .chain(params.rest.as_ref().map(|rest| &rest.argument))
+        .flat_map(pattern_names)
+        .collect()
+}
+
+/// The names a declaration binds.
+fn declared_names(declaration: &Declaration) -> Vec<String> {
+    match declaration {
+        Declaration::VariableDeclaration(var) => var
+            .declarations
+            .iter()
+            .flat_map(|declarator| pattern_names(&declarator.id))
+            .collect(),
+        _ => declaration
+            .id()
+            .map(|id| id.name.to_string())
+            .into_iter()
+            .collect(),
+    }
+}
+
+/// The names scoped to a list of statements: its `let`, `const`, class and function
+/// declarations.
+fn lexical_names(statements: &[Statement]) -> HashSet<String> {
+    statements
+        .iter()
+        .filter_map(Statement::as_declaration)
+        .filter(|declaration| {
+            !matches!(declaration, Declaration::VariableDeclaration(var) if var.kind.is_var())
+        })
+        .flat_map(declared_names)
+        .collect()
+}
+
+/// The names a function body declares with `var`, outside the functions nested in it.
+fn var_names(statements: &[Statement]) -> HashSet<String> {
+    #[derive(Default)]
+    struct VarNames(HashSet<String>);
+
+    impl<'a> Visit<'a> for VarNames {
+        fn visit_variable_declaration(&mut self, decl: &VariableDeclaration<'a>) {
+            if decl.kind.is_var() {
+                for declarator in &decl.declarations {
+                    self.0.extend(pattern_names(&declarator.id));
+                }
+            }
+            walk::walk_variable_declaration(self, decl);
+        }
+
+        fn visit_function(&mut self, _func: &Function<'a>, _flags: ScopeFlags) {}
+
+        fn visit_arrow_function_expression(&mut self, _arrow: &ArrowFunctionExpression<'a>) {}
+
+        fn visit_static_block(&mut self, _block: &StaticBlock<'a>) {}
+    }
+
+    let mut names = VarNames::default();
+    for stmt in statements {
+        names.visit_statement(stmt);
+    }
+    names.0
+}
+
+/// The names in an assignment target that are assigned to.
+fn assigned_names(target: &AssignmentTarget) -> Vec<String> {
+    #[derive(Default)]
+    struct AssignedNames(Vec<String>);
+
+    impl<'a> Visit<'a> for AssignedNames {
+        fn visit_simple_assignment_target(&mut self, target: &SimpleAssignmentTarget<'a>) {
+            if let SimpleAssignmentTarget::AssignmentTargetIdentifier(id) = target {
+                self.0.push(id.name.to_string());
+            }
+        }
+
+        fn visit_assignment_target_with_default(
+            &mut self,
+            target: &AssignmentTargetWithDefault<'a>,
+        ) {
+            self.visit_assignment_target(&target.binding);
+        }
+
+        fn visit_assignment_target_property_identifier(
+            &mut self,
+            property: &AssignmentTargetPropertyIdentifier<'a>,
+        ) {
+            self.0.push(property.binding.name.to_string());
+        }
+    }
+
+    let mut names = AssignedNames::default();
+    names.visit_assignment_target(target);
+    names.0
+}
+
+struct CommonJs<'s> {
+    source: &'s str,
+    edits: TextEdits,
+    helpers: HashSet<Helper>,
+    used_names: HashSet<String>,
+    /// What each imported name is read as, like `util_1.a`. Namespace imports aren't here,
+    /// since they're bound to the module object itself.
+    imports: HashMap<String, String>,
+    /// The names each of the module's own bindings is exported as.
+    exports: HashMap<String, Vec<String>>,
+    /// Export assignments made before the module body runs.
+    hoisted: Vec<String>,
+    temporaries: Vec<String>,
+    /// The names declared in each scope within the module being visited, innermost last.
+    scopes: Vec<HashSet<String>>,
+    /// The expression of the statement being visited, whose value isn't used.
+    statement_expression: Option<Span>,
+    /// The expression in the parentheses being visited.
+    parenthesized: Option<Span>,
+}
+
+impl CommonJs<'_> {
+    fn text(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    fn require(&self, specifier: &StringLiteral) -> String {
+        format!("require({})", self.text(specifier.span))
+    }
+
+    /// A fresh name for a required module, like `util_1` for `"./lib/util"`.
+    fn module_binding(&mut self, specifier: &str) -> String {
+        let base = specifier
+            .rsplit('/')
+            .find(|segment| !segment.is_empty() && *segment != "." && *segment != "..")
+            .unwrap_or("module");
+        let base = base.split('.').next().unwrap_or(base);
+        let mut base: String = base
+            .chars()
+            .map(|c| if c.is_alphanumeric() { c } else { '_' })
+            .collect();
+        if !is_identifier(&base) {
+            base.insert(0, '_');
+        }
+        let name = (1..)
+            .map(|i| format!("{base}_{i}"))
+            .find(|name| !self.used_names.contains(name))
+            .unwrap_or_default();
+        self.used_names.insert(name.clone());
+        name
+    }
+
+    /// A fresh `_a`, `_b`, ... variable, declared at the top of the module.
+    fn temporary(&mut self) -> String {
+        let name = (0..)
+            .map(|i: usize| {
+                let letter = (b'a' + (i % 26) as u8) as char;
+                match i / 26 {
+                    0 => format!("_{letter}"),
+                    round => format!("_{letter}{round}"),
+                }
+            })
+            .find(|name| !self.used_names.contains(name))
+            .unwrap_or_default();
+        self.used_names.insert(name.clone());
+        self.temporaries.push(name.clone());
+        name
+    }
+
+    fn import_star(&mut self, require: &str) -> String {
+        self.helpers.insert(Helper::ImportStar);
+        format!("__importStar({require})")
+    }
+
+    fn is_shadowed(&self, name: &str) -> bool {
+        self.scopes.iter().any(|scope| scope.contains(name))
+    }
+
+    /// What a reference to `name` reads, when it's an import.
+    fn import_access(&self, name: &str) -> Option<String> {
+        let access = self.imports.get(name)?;
+        (!self.is_shadowed(name)).then(|| access.clone())
+    }
+
+    /// The names the module's own binding `name` is exported as, where it isn't shadowed.
+    fn exported_names(&self, name: &str) -> Option<&[String]> {
+        let exported = self.exports.get(name)?;
+        (!self.is_shadowed(name)).then_some(exported.as_slice())
+    }
+
+    /// `exports.a = exports.b = ` for a binding exported as `a` and `b`.
+    fn export_chain(exported: &[String]) -> String {
+        exported
+            .iter()
+            .map(|name| format!("{} = ", property("exports", name)))
+            .collect()
+    }
+
+    /// Gathers what the module imports and exports before anything refers to it, since
+    /// references may come before the declarations.
+    fn collect(&mut self, program: &Program) {
+        for stmt in &program.body {
+            match stmt {
+                Statement::ImportDeclaration(decl) => self.lower_import(decl, decl.span),
+                Statement::ExportNamedDeclaration(decl) if decl.source.is_none() => {
+                    if let Some(declaration) = &decl.declaration {
+                        for name in declared_names(declaration) {
+                            self.exports.entry(name.clone()).or_default().push(name);
+                        }
+                    }
+                    for specifier in &decl.specifiers {
+                        let local = specifier.local.name().to_string();
+                        if !self.imports.contains_key(&local) {
+                            let exported = specifier.exported.name().to_string();
+                            self.exports.entry(local).or_default().push(exported);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        for stmt in &program.body {
+            let function = match stmt {
+                Statement::FunctionDeclaration(func) => func,
+                Statement::ExportNamedDeclaration(decl) => match &decl.declaration {
+                    Some(Declaration::FunctionDeclaration(func)) => func,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let Some(id) = &function.id else {
+                continue;
+            };
+            if let Some(exported) = self.exports.get(id.name.as_str()) {
+                let chain = Self::export_chain(exported);
+                self.hoisted.push(format!("{chain}{};", id.name));
+            }
+        }
+    }
+
+    fn lower_import(&mut self, decl: &ImportDeclaration, span: Span) {
+        let require = self.require(&decl.source);
+        let specifiers: &[ImportDeclarationSpecifier] =
+            decl.specifiers.as_deref().map_or(&[], |s| s);
+        let mut default = None;
+        let mut namespace = None;
+        let mut named = Vec::new();
+        for specifier in specifiers {
+            match specifier {
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(s) => default = Some(&s.local),
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) => {
+                    namespace = Some(&s.local);
+                }
+                ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                    named.push((s.imported.name().to_string(), &s.local));
+                }
+            }
+        }
+        // Imported names are read from the module object where they're used, so they
+        // see its exports as they are then, as ES module bindings do
+        let text = match (default, namespace) {
+            (None, None) if named.is_empty() => format!("{require};"),
+            (_, None) => {
+                let binding = self.module_binding(&decl.source.value);
+                let module = match (default, named.is_empty()) {
+                    (None, _) => require,
+                    (Some(_), true) => {
+                        self.helpers.insert(Helper::ImportDefault);
+                        format!("__importDefault({require})")
+                    }
+                    (Some(_), false) => self.import_star(&require),
+                };
+                if let Some(default) = default {
+                    let access = format!("{binding}.default");
+                    self.imports.insert(default.name.to_string(), access);
+                }
+                for (imported, local) in named {
+                    let access = property(&binding, &imported);
+                    self.imports.insert(local.name.to_string(), access);
+                }
+                format!("const {binding} = {module};")
+            }
+            (default, Some(namespace)) => {
+                let star = self.import_star(&require);
+                if let Some(default) = default {
+                    let access = format!("{}.default", namespace.name);
+                    self.imports.insert(default.name.to_string(), access);
+                }
+                format!("const {} = {star};", namespace.name)
+            }
+        };
+        self.edits.replace(span, text);
+    }
+
+    /// Lowers one of the module's top-level statements.
+    fn lower_statement(&mut self, stmt: &Statement) {
+        let span = stmt.span();
+        match stmt {
+            // Replaced while collecting
+            Statement::ImportDeclaration(_) => {}
+            Statement::ExportNamedDeclaration(decl) => self.lower_export_named(decl, span),
+            Statement::ExportDefaultDeclaration(decl) => self.lower_export_default(decl, span),
+            Statement::ExportAllDeclaration(decl) => self.lower_export_all(decl, span),
+            _ => {
+                self.visit_statement(stmt);
+                if let Some(declaration) = stmt.as_declaration() {
+                    self.export_declared(declaration, span);
+                }
+            }
+        }
+    }
+
+    /// Assigns the exports of what `declaration` declares after the statement at `span`,
+    /// once they have their values. Functions' are hoisted instead.
+    fn export_declared(&mut self, declaration: &Declaration, span: Span) {
+        if matches!(declaration, Declaration::FunctionDeclaration(_)) {
+            return;
+        }
+        let indent = indent_at(self.source, span.start);
+        let assignments: String = declared_names(declaration)
+            .iter()
+            .flat_map(|name| {
+                let exported = self.exports.get(name).cloned().unwrap_or_default();
+                exported.into_iter().map(move |exported| {
+                    format!("\n{indent}{} = {name};", property("exports", &exported))
+                })
+            })
+            .collect();
+        if !assignments.is_empty() {
+            self.edits.insert(span.end, assignments);
+        }
+    }
+
+    fn lower_export_named(&mut self, decl: &ExportNamedDeclaration<'_>, span: Span) {
+        let indent = indent_at(self.source, span.start).to_string();
+        if let Some(declaration) = &decl.declaration {
+            self.edits
+                .remove(Span::new(span.start, declaration.span().start));
+            self.visit_declaration(declaration);
+            self.export_declared(declaration, span);
+            return;
+        }
+
+        let statements: Vec<String> = match &decl.source {
+            Some(source) => {
+                let binding = self.module_binding(&source.value);
+                let mut statements = vec![format!("const {binding} = {};", self.require(source))];
+                statements.extend(decl.specifiers.iter().map(|spec| {
+                    Self::export_getter(
+                        &spec.exported.name(),
+                        &property(&binding, &spec.local.name()),
+                    )
+                }));
+                statements
+            }
+            // The module's own bindings are exported where they're declared
+            None => decl
+                .specifiers
+                .iter()
+                .filter_map(|spec| {
+                    let access = self.imports.get(spec.local.name().as_str())?;
+                    Some(Self::export_getter(&spec.exported.name(), access))
+                })
+                .collect(),
+        };
+        match statements.is_empty() {
+            true => self.edits.remove(super::line_span(self.source, span)),
+            false => self
+                .edits
+                .replace(span, statements.join(&format!("\n{indent}"))),
+        }
+    }
+
+    /// Exports `name` as a getter reading `access`, so it stays live.
+    fn export_getter(name: &str, access: &str) -> String {
+        format!(
+            "Object.defineProperty(exports, {}, {{ enumerable: true, get: function () {{ return {access}; }} }});",
+            serde_json::to_string(name).unwrap_or_default()
+        )
+    }
+
+    fn lower_export_default(&mut self, decl: &ExportDefaultDeclaration<'_>, span: Span) {
+        let indent = indent_at(self.source, span.start).to_string();
+        let (id, start, name_at) = match &decl.declaration {
+            ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
+                (func.id.as_ref(), func.span.start, func.params.span.start)
+            }
+            ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                (class.id.as_ref(), class.span.start, class.span.start + 5)
+            }
+            ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => return,
+            kind => {
+                let Some(expr) = kind.as_expression() else {
+                    return;
+                };
+                self.edits.replace(
+                    Span::new(span.start, expr.span().start),
+                    "exports.default = ",
+                );
+                self.visit_expression(expr);
+                return;
+            }
+        };
+        self.edits.remove(Span::new(span.start, start));
+        let name = match id {
+            Some(id) => id.name.to_string(),
+            // `export default function () {}` still declares a hoisted function, so it needs a name
+            None => {
+                let name = self.module_binding("default");
+                let before = self.source[..name_at as usize].chars().next_back();
+                let space = if before.is_some_and(char::is_whitespace) {
+                    ""
+                } else {
+                    " "
+                };
+                let trailing = if self.source[name_at as usize..].starts_with(char::is_whitespace) {
+                    ""
+                } else {
+                    " "
+                };
+                let text = match &decl.declaration {
+                    ExportDefaultDeclarationKind::ClassDeclaration(_) => {
+                        format!(" {name}{trailing}")
+                    }
+                    _ => format!("{space}{name}"),
+                };
+                self.edits.insert(name_at, text);
+                name
+            }
+        };
+        walk::walk_export_default_declaration(self, decl);
+        match &decl.declaration {
+            ExportDefaultDeclarationKind::FunctionDeclaration(_) => {
+                self.hoisted.push(format!("exports.default = {name};"));
+            }
+            _ => self
+                .edits
+                .insert(span.end, format!("\n{indent}exports.default = {name};")),
+        }
+    }
+
+    fn lower_export_all(&mut self, decl: &ExportAllDeclaration, span: Span) {
+        let require = self.require(&decl.source);
+        let text = match &decl.exported {
+            Some(exported) => {
+                let star = self.import_star(&require);
+                format!("{} = {star};", property("exports", &exported.name()))
+            }
+            None => {
+                self.helpers.insert(Helper::ExportStar);
+                format!("__exportStar({require}, exports);")
+            }
+        };
+        self.edits.replace(span, text);
+    }
+
+    /// Wraps the expression at `span` in `open` and `close`, with parentheses unless it
+    /// already has them.
+    fn wrap(&mut self, span: Span, open: &str, close: &str) {
+        let (open, close) = match self.parenthesized == Some(span) {
+            true => (open.to_string(), close.to_string()),
+            false => (format!("({open}"), format!("{close})")),
+        };
+        self.edits.insert(span.start, open);
+        self.edits.insert(span.end, close);
+    }
+
+    fn in_scope(&mut self, names: HashSet<String>, visit: impl FnOnce(&mut Self)) {
+        self.scopes.push(names);
+        visit(self);
+        self.scopes.pop();
+    }
+}
+
+impl<'a> Visit<'a> for CommonJs<'_> {
+    fn visit_import_expression(&mut self, expr: &ImportExpression<'a>) {
+        let specifier = self.text(expr.source.span()).to_string();
+        let star = self.import_star(&format!("require({specifier})"));
+        self.edits
+            .replace(expr.span, format!("Promise.resolve().then(() => {star})"));
+    }
+
+    fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+        if let Some(access) = self.import_access(&ident.name) {
+            self.edits.replace(ident.span, access);
+        }
+    }
+
+    fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+        // Called without the module as `this`, as a plain function would be
+        if let Expression::Identifier(callee) = &call.callee
+            && let Some(access) = self.import_access(&callee.name)
+        {
+            self.edits.replace(callee.span, format!("(0, {access})"));
+            for argument in &call.arguments {
+                self.visit_argument(argument);
+            }
+            return;
+        }
+        walk::walk_call_expression(self, call);
+    }
+
+    fn visit_tagged_template_expression(&mut self, expr: &TaggedTemplateExpression<'a>) {
+        if let Expression::Identifier(tag) = &expr.tag
+            && let Some(access) = self.import_access(&tag.name)
+        {
+            self.edits.replace(tag.span, format!("(0, {access})"));
+            self.visit_template_literal(&expr.quasi);
+            return;
+        }
+        walk::walk_tagged_template_expression(self, expr);
+    }
+
+    fn visit_object_property(&mut self, property: &ObjectProperty<'a>) {
+        if property.shorthand
+            && let Expression::Identifier(value) = &property.value
+            && let Some(access) = self.import_access(&value.name)
+        {
+            self.edits
+                .replace(property.span, format!("{}: {access}", value.name));
+            return;
+        }
+        walk::walk_object_property(self, property);
+    }
+
+    fn visit_expression_statement(&mut self, stmt: &ExpressionStatement<'a>) {
+        let outer = self.statement_expression.replace(stmt.expression.span());
+        walk::walk_expression_statement(self, stmt);
+        self.statement_expression = outer;
+    }
+
+    fn visit_parenthesized_expression(&mut self, expr: &ParenthesizedExpression<'a>) {
+        let outer = self.parenthesized.replace(expr.expression.span());
+        walk::walk_parenthesized_expression(self, expr);
+        self.parenthesized = outer;
+    }
+
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        let is_statement = self.statement_expression == Some(expr.span);
+        // Writes to exported bindings update their exports too
+        if let AssignmentTarget::AssignmentTargetIdentifier(id) = &expr.left {
+            if let Some(exported) = self.exported_names(&id.name) {
+                let chain = Self::export_chain(exported);
+                match is_statement {
+                    true => self.edits.insert(expr.span.start, chain),
+                    false => self.wrap(expr.span, &chain, ""),
+                }
+            }
+        } else {
+            let updates: Vec<String> = assigned_names(&expr.left)
+                .into_iter()
+                .filter_map(|name| {
+                    let exported = self.exported_names(&name)?;
+                    Some(format!("{}{name}", Self::export_chain(exported)))
+                })
+                .collect();
+            if !updates.is_empty() {
+                let temp = self.temporary();
+                let close = format!(", {}, {temp}", updates.join(", "));
+                self.wrap(expr.span, &format!("{temp} = "), &close);
+            }
+        }
+        walk::walk_assignment_expression(self, expr);
+    }
+
+    fn visit_update_expression(&mut self, expr: &UpdateExpression<'a>) {
+        if let SimpleAssignmentTarget::AssignmentTargetIdentifier(id) = &expr.argument
+            && let Some(exported) = self.exported_names(&id.name)
+        {
+            let chain = Self::export_chain(exported);
+            if self.statement_expression == Some(expr.span) {
+                self.edits
+                    .insert(expr.span.end, format!(", {chain}{}", id.name));
+            } else if expr.prefix {
+                self.wrap(expr.span, &chain, "");
+            } else {
+                // `q++` still evaluates to the old value
+                let temp = self.temporary();
+                let close = format!(", {chain}{}, {temp}", id.name);
+                self.wrap(expr.span, &format!("{temp} = "), &close);
+            }
+        }
+        walk::walk_update_expression(self, expr);
+    }
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        let mut names = parameter_names(&func.params);
+        if func.is_expression()
+            && let Some(id) = &func.id
+        {
+            names.insert(id.name.to_string());
+        }
+        if let Some(body) = &func.body {
+            names.extend(var_names(&body.statements));
+            names.extend(lexical_names(&body.statements));
+        }
+        self.in_scope(names, |this| walk::walk_function(this, func, flags));
+    }
+
+    fn visit_arrow_function_expression(&mut self, arrow: &ArrowFunctionExpression<'a>) {
+        let mut names = parameter_names(&arrow.params);
+        names.extend(var_names(&arrow.body.statements));
+        names.extend(lexical_names(&arrow.body.statements));
+        self.in_scope(names, |this| {
+            walk::walk_arrow_function_expression(this, arrow);
+        });
+    }
+
+    fn visit_static_block(&mut self, block: &StaticBlock<'a>) {
+        let mut names = var_names(&block.body);
+        names.extend(lexical_names(&block.body));
+        self.in_scope(names, |this| walk::walk_static_block(this, block));
+    }
+
+    fn visit_block_statement(&mut self, block: &BlockStatement<'a>) {
+        let names = lexical_names(&block.body);
+        self.in_scope(names, |this| walk::walk_block_statement(this, block));
+    }
+
+    fn visit_switch_statement(&mut self, stmt: &SwitchStatement<'a>) {
+        let names = stmt
+            .cases
+            .iter()
+            .flat_map(|case| lexical_names(&case.consequent))
+            .collect();
+        self.in_scope(names, |this| walk::walk_switch_statement(this, stmt));
+    }
+
+    fn visit_for_statement(&mut self, stmt: &ForStatement<'a>) {
+        let names = match &stmt.init {
+            Some(ForStatementInit::VariableDeclaration(decl)) if !decl.kind.is_var() => decl
+                .declarations
+                .iter()
+                .flat_map(|declarator| pattern_names(&declarator.id))
+                .collect(),
+            _ => HashSet::new(),
+        };
+        self.in_scope(names, |this| walk::walk_for_statement(this, stmt));
+    }
+
+    fn visit_for_in_statement(&mut self, stmt: &ForInStatement<'a>) {
+        let names = loop_names(&stmt.left);
+        self.in_scope(names, |this| walk::walk_for_in_statement(this, stmt));
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement<'a>) {
+        let names = loop_names(&stmt.left);
+        self.in_scope(names, |this| walk::walk_for_of_statement(this, stmt));
+    }
+
+    fn visit_catch_clause(&mut self, clause: &CatchClause<'a>) {
+        let names = clause
+            .param
+            .iter()
+            .flat_map(|param| pattern_names(&param.pattern))
+            .collect();
+        self.in_scope(names, |this| walk::walk_catch_clause(this, clause));
+    }
+
+    fn visit_class(&mut self, class: &Class<'a>) {
+        let names = match (&class.id, class.is_expression()) {
+            (Some(id), true) => HashSet::from([id.name.to_string()]),
+            _ => HashSet::new(),
+        };
+        self.in_scope(names, |this| walk::walk_class(this, class));
+    }
+}
+
+/// The names a `for...in` or `for...of` loop declares for each iteration.
+fn loop_names(left: &ForStatementLeft) -> HashSet<String> {
+    match left {
+        ForStatementLeft::VariableDeclaration(decl) if !decl.kind.is_var() => decl
+            .declarations
+            .iter()
+            .flat_map(|declarator| pattern_names(&declarator.id))
+            .collect(),
+        _ => HashSet::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::emit::transpile;
+    use crate::options::{CompilerOptions, ModuleKind};
+
+    fn emit(source: &str) -> String {
+        let options = CompilerOptions {
+            module: ModuleKind::CommonJS,
+            ..Default::default()
+        };
+        transpile(source, &options).unwrap()
+    }
+
+    /// Drops the `"use strict"` and `__esModule` lines and any helpers.
+    fn body(output: &str) -> String {
+        let lines: Vec<&str> = output.lines().collect();
+        let start = lines
+            .iter()
+            .rposition(|line| line.starts_with('}') || line.contains("\"__esModule\""))
+            .map_or(0, |index| index + 1);
+        lines[start..].join("\n")
+    }
+
+    #[test]
+    fn test_imports() {
+        let output = emit(
+            "import \"./setup\";\nimport { a, b as c } from \"./lib/util\";\nimport def from \"dep\";\nimport * as ns from \"ns\";\nimport d2, { e } from \"./d2\";\na(c, def, ns, d2, e);",
+        );
+        assert!(output.starts_with(
+            "\"use strict\";\nObject.defineProperty(exports, \"__esModule\", { value: true });\nvar __createBinding"
+        ));
+        assert!(output.contains("var __importDefault"));
+        assert_eq!(
+            body(&output),
+            "require(\"./setup\");\nconst util_1 = require(\"./lib/util\");\nconst dep_1 = __importDefault(require(\"dep\"));\nconst ns = __importStar(require(\"ns\"));\nconst d2_1 = __importStar(require(\"./d2\"));\n(0, util_1.a)(util_1.b, dep_1.default, ns, d2_1.default, d2_1.e);"
+        );
+    }
+
+    #[test]
+    fn test_exports() {
+        let output = emit(
+            "export const x = 1, y = 2;\nexport function f() {}\nexport class C {}\nconst z = 3;\nexport { z, z as \"w-w\" };\nexport default z;",
+        );
+        assert_eq!(
+            output,
+            "\"use strict\";\nObject.defineProperty(exports, \"__esModule\", { value: true });\nexports.f = f;\nconst x = 1, y = 2;\nexports.x = x;\nexports.y = y;\nfunction f() {}\nclass C {}\nexports.C = C;\nconst z = 3;\nexports.z = z;\nexports[\"w-w\"] = z;\nexports.default = z;"
+        );
+        assert_eq!(
+            emit("export default function () {}"),
+            "\"use strict\";\nObject.defineProperty(exports, \"__esModule\", { value: true });\nexports.default = default_1;\nfunction default_1() {}"
+        );
+    }
+
+    #[test]
+    fn test_reexports_and_dynamic_import() {
+        let output = emit(
+            "export { a, default as b } from \"./a\";\nexport * from \"./b\";\nexport * as c from \"./c\";\nconst m = import(\"./m\");",
+        );
+        assert_eq!(
+            body(&output),
+            "const a_1 = require(\"./a\");\nObject.defineProperty(exports, \"a\", { enumerable: true, get: function () { return a_1.a; } });\nObject.defineProperty(exports, \"b\", { enumerable: true, get: function () { return a_1.default; } });\n__exportStar(require(\"./b\"), exports);\nexports.c = __importStar(require(\"./c\"));\nconst m = Promise.resolve().then(() => __importStar(require(\"./m\")));"
+        );
+    }
+
+    #[test]
+    fn test_scripts_are_unchanged() {
+        assert_eq!(emit("let a = 1;"), "let a = 1;");
+    }
+
+    #[test]
+    fn test_imports_are_live() {
+        let output = emit(
+            "import { a, t } from \"./a\";\nimport { b as c } from \"./b\";\nlet o = { a, c: c.x };\nt`x`;\nfunction f(a) { return a + c; }\nconst g = () => { let c = 1; return [a, c]; };\ntry {} catch (a) { a; }\nfor (const a of [c]) a;\nexport { c as d };",
+        );
+        assert_eq!(
+            body(&output),
+            "const a_1 = require(\"./a\");\nconst b_1 = require(\"./b\");\nlet o = { a: a_1.a, c: b_1.b.x };\n(0, a_1.t)`x`;\nfunction f(a) { return a + b_1.b; }\nconst g = () => { let c = 1; return [a_1.a, c]; };\ntry {} catch (a) { a; }\nfor (const a of [b_1.b]) a;\nObject.defineProperty(exports, \"d\", { enumerable: true, get: function () { return b_1.b; } });"
+        );
+    }
+
+    #[test]
+    fn test_exports_are_live() {
+        let output = emit(
+            "export { q as r };\nexport let q = 1, p = 0;\nq = 2;\nlet x = (q += 1);\nq++;\nx = q++;\n({ q, p } = o);\nfunction f(q) { q = 3; }\nexport enum E { A }",
+        );
+        assert_eq!(
+            output,
+            "\"use strict\";\nObject.defineProperty(exports, \"__esModule\", { value: true });\nvar _a, _b;\nlet q = 1, p = 0;\nexports.r = q;\nexports.q = q;\nexports.p = p;\nexports.r = exports.q = q = 2;\nlet x = (exports.r = exports.q = q += 1);\nq++, exports.r = exports.q = q;\nx = (_a = q++, exports.r = exports.q = q, _a);\n(_b = { q, p } = o, exports.r = exports.q = q, exports.p = p, _b);\nfunction f(q) { q = 3; }\nvar E;\nexports.E = E;\n(function (E) {\n    E[E[\"A\"] = 0] = \"A\";\n})(E || (exports.E = E = {}));"
+        );
+    }
+}
@@ -0,0 +1,147 @@
+//! Lowering of newer syntax for older `target`s.
+//!
+//! Each feature is its own pass over the JavaScript produced by type stripping. A pass
+//! rewrites only the outermost occurrence of nested syntax and copies the inner text
+//! through untouched, so it is rerun on its own output until nothing changes.
+
+mod async_functions;
+mod class_fields;
+mod for_await;
+mod logical_assignment;
+mod nullish_coalescing;
+mod optional_chaining;
+mod temporaries;
+
+use super::TextEdits;
+use crate::options::ScriptTarget;
+use crate::parser::parse_typescript_as;
+use oxc_ast::ast::{Expression, Program};
+use oxc_span::{GetSpan, SourceType};
+use temporaries::Temporaries;
+
+type Pass = fn(&Program, &str, &mut Temporaries) -> TextEdits;
+
+/// Every pass with the first target that supports its syntax natively, in the order they run.
+const PASSES: &[(ScriptTarget, Pass)] = &[
+    (ScriptTarget::ES2022, class_fields::lower),
+    (ScriptTarget::ES2021, logical_assignment::lower),
+    (ScriptTarget::ES2020, optional_chaining::lower),
+    (ScriptTarget::ES2020, nullish_coalescing::lower),
+    (ScriptTarget::ES2018, for_await::lower),
+    (ScriptTarget::ES2017, async_functions::lower),
+];
+
+// Guards against a pass that keeps producing edits; real code nests far less deeply
+const MAX_RUNS: usize = 64;
+
+pub(super) fn downlevel(
+    mut source: String,
+    target: ScriptTarget,
+    source_type: SourceType,
+) -> Result<String, String> {
+    // Classes, arrow functions, block scoping and the generators async functions become
+    // would all need lowering too
+    if target == ScriptTarget::ES5 {
+        return Err(
+            "Emitting for target 'ES5' is not supported. Use 'ES2015' or later.".to_string(),
+        );
+    }
+    if target < ScriptTarget::ES2022 {
+        let ts_program = parse_typescript_as(&source, source_type)?;
+        if let Some(name) = class_fields::private_name(&ts_program.program) {
+            return Err(format!(
+                "Private name '{name}' can't be lowered for target '{target:?}'. Use 'ES2022' or later."
+            ));
+        }
+    }
+    let passes: Vec<Pass> = PASSES
+        .iter()
+        .filter(|(since, _)| target < *since)
+        .map(|(_, pass)| *pass)
+        .collect();
+    if passes.is_empty() {
+        return Ok(source);
+    }
+
+    let mut temporaries = Temporaries::new(&parse_typescript_as(&source, source_type)?.program);
+    for pass in passes {
+        for _ in 0..MAX_RUNS {
+            let ts_program = parse_typescript_as(&source, source_type)?;
+            let edits = pass(&ts_program.program, &source, &mut temporaries);
+            if edits.is_empty() {
+                break;
+            }
+            source = edits.apply(&source);
+        }
+    }
+    if temporaries.is_empty() {
+        return Ok(source);
+    }
+    let ts_program = parse_typescript_as(&source, source_type)?;
+    Ok(temporaries
+        .declare(&ts_program.program, &source)
+        .apply(&source))
+}
+
+/// Whether an expression can be repeated without evaluating anything twice.
+fn is_simple(expr: &Expression) -> bool {
+    matches!(
+        expr,
+        Expression::Identifier(_) | Expression::ThisExpression(_)
+    )
+}
+
+/// Text that evaluates `expr` once, and a reference to its value for later uses.
+fn capture(source: &str, expr: &Expression, temporaries: &mut Temporaries) -> (String, String) {
+    let span = expr.span();
+    let text = &source[span.start as usize..span.end as usize];
+    if is_simple(expr) {
+        return (text.to_string(), text.to_string());
+    }
+    let temp = temporaries.fresh();
+    (format!("({temp} = {text})"), temp)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::emit::transpile;
+    use crate::options::{CompilerOptions, ScriptTarget};
+
+    pub(super) fn emit(source: &str, target: ScriptTarget) -> String {
+        let options = CompilerOptions {
+            target,
+            ..Default::default()
+        };
+        transpile(source, &options).unwrap()
+    }
+
+    #[test]
+    fn test_newer_targets_leave_syntax_alone() {
+        let source = "class A { x = a?.b ?? c; async m() { await this.x; } }";
+        assert_eq!(emit(source, ScriptTarget::ESNext), source);
+        assert_eq!(emit(source, ScriptTarget::ES2022), source);
+    }
+
+    #[test]
+    fn test_es5_is_rejected() {
+        let options = CompilerOptions {
+            target: ScriptTarget::ES5,
+            ..Default::default()
+        };
+        assert_eq!(
+            transpile("const f = () => 1;", &options),
+            Err("Emitting for target 'ES5' is not supported. Use 'ES2015' or later.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_passes_compose() {
+        assert_eq!(
+            emit(
+                "\"use strict\";\nclass A { x = f()?.y ?? 1; }",
+                ScriptTarget::ES2019
+            ),
+            "\"use strict\";\nclass A { constructor() { var _a, _b; this.x = ((_b = ((_a = f()) === null || _a === void 0 ? void 0 : _a.y)) !== null && _b !== void 0 ? _b : 1); } }"
+        );
+    }
+}
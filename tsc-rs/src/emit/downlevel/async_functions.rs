@@ -0,0 +1,287 @@
+use super::super::{TextEdits, insert_at_top};
+use super::temporaries::Temporaries;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::GetSpan;
+use oxc_syntax::scope::ScopeFlags;
+
+// TypeScript's helper, emitted once per file that needs it
+const AWAITER: &str = r#"var __awaiter = (this && this.__awaiter) || function (thisArg, _arguments, P, generator) {
+    function adopt(value) { return value instanceof P ? value : new P(function (resolve) { resolve(value); }); }
+    return new (P || (P = Promise))(function (resolve, reject) {
+        function fulfilled(value) { try { step(generator.next(value)); } catch (e) { reject(e); } }
+        function rejected(value) { try { step(generator["throw"](value)); } catch (e) { reject(e); } }
+        function step(result) { result.done ? resolve(result.value) : adopt(result.value).then(fulfilled, rejected); }
+        step((generator = generator.apply(thisArg, _arguments || [])).next());
+    });
+};"#;
+
+/// Rewrites async functions as generators driven by `__awaiter`, with `await` as `yield`.
+///
+/// Async generators are left alone.
+pub(super) fn lower(program: &Program, source: &str, _temporaries: &mut Temporaries) -> TextEdits {
+    let mut lowering = AsyncFunctions {
+        source,
+        edits: TextEdits::default(),
+        frames: Vec::new(),
+        lowered: false,
+    };
+    lowering.visit_program(program);
+    if lowering.lowered {
+        insert_at_top(program, &mut lowering.edits, AWAITER);
+    }
+    lowering.edits
+}
+
+/// A function being visited.
+#[derive(Default)]
+struct Frame {
+    lowered: bool,
+    arrow: bool,
+    uses_arguments: bool,
+    /// Properties read through `super`, which a generator body can't do itself.
+    super_properties: Vec<String>,
+}
+
+struct AsyncFunctions<'s> {
+    source: &'s str,
+    edits: TextEdits,
+    frames: Vec<Frame>,
+    lowered: bool,
+}
+
+impl AsyncFunctions<'_> {
+    /// The frame that owns `this`, `arguments` and `super`, if it is being lowered.
+    fn lowered_function(&mut self) -> Option<&mut Frame> {
+        self.frames
+            .iter_mut()
+            .rev()
+            .find(|frame| !frame.arrow)
+            .filter(|frame| frame.lowered)
+    }
+
+    /// Removes an `async` keyword (and the space after it) found in `start..end`.
+    fn remove_async_keyword(&mut self, start: u32, end: u32) {
+        let text = &self.source[start as usize..end as usize];
+        let keyword = text.match_indices("async").find(|(index, _)| {
+            let before = text[..*index].chars().next_back();
+            let after = text[index + 5..].chars().next();
+            before.is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                && after.is_none_or(|c| !(c.is_alphanumeric() || c == '_' || c == '$'))
+        });
+        if let Some((index, _)) = keyword {
+            let spaces = text[index + 5..].len() - text[index + 5..].trim_start().len();
+            let keyword_start = start + index as u32;
+            self.edits
+                .remove(Span::new(keyword_start, keyword_start + 5 + spaces as u32));
+        }
+    }
+
+    fn awaiter_call(frame: &Frame) -> String {
+        let arguments = if frame.uses_arguments {
+            "arguments"
+        } else {
+            "void 0"
+        };
+        format!("__awaiter(this, {arguments}, void 0, function* () ")
+    }
+
+    fn lower_function_body(&mut self, body: &FunctionBody, frame: &Frame) {
+        let super_accessors = if frame.super_properties.is_empty() {
+            String::new()
+        } else {
+            let properties: Vec<String> = frame
+                .super_properties
+                .iter()
+                .map(|name| format!("{name}: {{ get: () => super.{name} }}"))
+                .collect();
+            format!(
+                " const _super = Object.create(null, {{ {} }});",
+                properties.join(", ")
+            )
+        };
+        self.edits.insert(
+            body.span.start,
+            format!("{{{super_accessors} return {}", Self::awaiter_call(frame)),
+        );
+        self.edits.insert(body.span.end, "); }");
+    }
+}
+
+impl<'a> Visit<'a> for AsyncFunctions<'_> {
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        let lowered = func.r#async && !func.generator && func.body.is_some();
+        self.frames.push(Frame {
+            lowered,
+            ..Frame::default()
+        });
+        walk::walk_function(self, func, flags);
+        let frame = self.frames.pop().unwrap_or_default();
+        let Some(body) = func.body.as_ref().filter(|_| lowered) else {
+            return;
+        };
+        self.lowered = true;
+        // Methods carry `async` before their key, outside the function's span
+        if self.source[func.span.start as usize..].starts_with("async") {
+            self.remove_async_keyword(func.span.start, body.span.start);
+        }
+        self.lower_function_body(body, &frame);
+    }
+
+    fn visit_method_definition(&mut self, method: &MethodDefinition<'a>) {
+        if method.value.r#async && !method.value.generator {
+            self.remove_async_keyword(method.span.start, method.key.span().start);
+        }
+        walk::walk_method_definition(self, method);
+    }
+
+    fn visit_object_property(&mut self, property: &ObjectProperty<'a>) {
+        if property.method
+            && let Expression::FunctionExpression(func) = &property.value
+            && func.r#async
+            && !func.generator
+        {
+            self.remove_async_keyword(property.span.start, property.key.span().start);
+        }
+        walk::walk_object_property(self, property);
+    }
+
+    fn visit_arrow_function_expression(&mut self, arrow: &ArrowFunctionExpression<'a>) {
+        self.frames.push(Frame {
+            lowered: arrow.r#async,
+            arrow: true,
+            ..Frame::default()
+        });
+        walk::walk_arrow_function_expression(self, arrow);
+        let frame = self.frames.pop().unwrap_or_default();
+        if !arrow.r#async {
+            return;
+        }
+        self.lowered = true;
+        self.remove_async_keyword(arrow.span.start, arrow.params.span.start);
+        let body = &arrow.body;
+        let awaiter = Self::awaiter_call(&frame);
+        if arrow.expression {
+            self.edits
+                .insert(body.span.start, format!("{awaiter}{{ return "));
+            self.edits.insert(body.span.end, "; })");
+        } else {
+            self.edits.insert(body.span.start, awaiter);
+            self.edits.insert(body.span.end, ")");
+        }
+    }
+
+    fn visit_await_expression(&mut self, expr: &AwaitExpression<'a>) {
+        if self.frames.last().is_some_and(|frame| frame.lowered) {
+            self.edits.replace(
+                Span::new(expr.span.start, expr.argument.span().start),
+                "(yield ",
+            );
+            self.edits.insert(expr.span.end, ")");
+        }
+        walk::walk_await_expression(self, expr);
+    }
+
+    fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+        if ident.name == "arguments"
+            && let Some(frame) = self.lowered_function()
+        {
+            frame.uses_arguments = true;
+        }
+    }
+
+    fn visit_call_expression(&mut self, call: &CallExpression<'a>) {
+        // `super.m(x)` becomes `_super.m.call(this, x)`
+        if let Expression::StaticMemberExpression(member) = &call.callee
+            && member.object.is_super()
+            && let Some(frame) = self.lowered_function()
+        {
+            let name = member.property.name.to_string();
+            if !frame.super_properties.contains(&name) {
+                frame.super_properties.push(name.clone());
+            }
+            let separator = if call.arguments.is_empty() { "" } else { ", " };
+            let open_paren = member.span.end as usize
+                + self.source[member.span.end as usize..]
+                    .find('(')
+                    .unwrap_or(0);
+            self.edits.replace(
+                Span::new(member.span.start, open_paren as u32 + 1),
+                format!("_super.{name}.call(this{separator}"),
+            );
+            for argument in &call.arguments {
+                self.visit_argument(argument);
+            }
+            return;
+        }
+        walk::walk_call_expression(self, call);
+    }
+
+    fn visit_static_member_expression(&mut self, member: &StaticMemberExpression<'a>) {
+        if member.object.is_super()
+            && let Some(frame) = self.lowered_function()
+        {
+            let name = member.property.name.to_string();
+            if !frame.super_properties.contains(&name) {
+                frame.super_properties.push(name.clone());
+            }
+            self.edits.replace(member.span, format!("_super.{name}"));
+            return;
+        }
+        walk::walk_static_member_expression(self, member);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::emit;
+    use crate::options::ScriptTarget;
+
+    fn without_helper(output: &str) -> &str {
+        let end = output.find("\n};\n").expect("helper") + 4;
+        &output[end..]
+    }
+
+    #[test]
+    fn test_lower_async_functions() {
+        let output = emit(
+            "async function f(x: number) { return await g(x) + 1; }",
+            ScriptTarget::ES2015,
+        );
+        assert!(output.starts_with("var __awaiter = "));
+        assert_eq!(
+            without_helper(&output),
+            "function f(x) { return __awaiter(this, void 0, void 0, function* () { return (yield g(x)) + 1; }); }"
+        );
+        assert_eq!(
+            without_helper(&emit(
+                "const h = async () => await 1;\nconst k = async (a) => { await a; };",
+                ScriptTarget::ES2016
+            )),
+            "const h = () => __awaiter(this, void 0, void 0, function* () { return (yield 1); });\nconst k = (a) => __awaiter(this, void 0, void 0, function* () { (yield a); });"
+        );
+        let source = "async function* gen() { await 1; }";
+        assert_eq!(emit(source, ScriptTarget::ES2015), source);
+        assert_eq!(
+            emit("async function f() {}", ScriptTarget::ES2017),
+            "async function f() {}"
+        );
+    }
+
+    #[test]
+    fn test_lower_async_methods() {
+        let source = "class A extends B { async m() { return super.m(arguments[0]) + super.n; } }";
+        assert_eq!(
+            without_helper(&emit(source, ScriptTarget::ES2015)),
+            "class A extends B { m() { const _super = Object.create(null, { m: { get: () => super.m }, n: { get: () => super.n } }); return __awaiter(this, arguments, void 0, function* () { return _super.m.call(this, arguments[0]) + _super.n; }); } }"
+        );
+        assert_eq!(
+            without_helper(&emit(
+                "const o = { async get() { await 0; } };",
+                ScriptTarget::ES2015
+            )),
+            "const o = { get() { return __awaiter(this, void 0, void 0, function* () { (yield 0); }); } };"
+        );
+    }
+}
@@ -0,0 +1,438 @@
+use super::super::{TextEdits, indent_at, line_span};
+use super::temporaries::Temporaries;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::GetSpan;
+use oxc_syntax::scope::ScopeFlags;
+
+/// Moves field initializers into the constructor, and static ones after the class, where
+/// their `this` becomes the class.
+///
+/// This matches TypeScript's `useDefineForClassFields: false` output: fields without an
+/// initializer are dropped. `#private` names can't be lowered this way, so
+/// [`private_name`] keeps them from reaching this pass.
+pub(super) fn lower(program: &Program, source: &str, temporaries: &mut Temporaries) -> TextEdits {
+    let mut lowering = ClassFields {
+        source,
+        temporaries,
+        edits: TextEdits::default(),
+        statement_end: None,
+    };
+    lowering.visit_program(program);
+    lowering.edits
+}
+
+/// The first `#private` name in the program, which needs an ES2022 target.
+pub(super) fn private_name(program: &Program) -> Option<String> {
+    #[derive(Default)]
+    struct PrivateNames(Option<String>);
+
+    impl<'a> Visit<'a> for PrivateNames {
+        fn visit_private_identifier(&mut self, ident: &PrivateIdentifier<'a>) {
+            if self.0.is_none() {
+                self.0 = Some(format!("#{}", ident.name));
+            }
+        }
+    }
+
+    let mut names = PrivateNames::default();
+    names.visit_program(program);
+    names.0
+}
+
+/// The `this` expressions that mean the same as `this` where `expr` is, outside any
+/// function or class within it that rebinds it.
+fn this_references(expr: &Expression) -> Vec<Span> {
+    #[derive(Default)]
+    struct ThisReferences(Vec<Span>);
+
+    impl<'a> Visit<'a> for ThisReferences {
+        fn visit_this_expression(&mut self, expr: &ThisExpression) {
+            self.0.push(expr.span);
+        }
+
+        fn visit_function(&mut self, _func: &Function<'a>, _flags: ScopeFlags) {}
+
+        fn visit_class(&mut self, _class: &Class<'a>) {}
+    }
+
+    let mut references = ThisReferences::default();
+    references.visit_expression(expr);
+    references.0
+}
+
+/// A field turned into an assignment: the accessor (`.x` or `[k]`) and its value.
+struct Assignment {
+    accessor: String,
+    value: String,
+}
+
+/// A field's span widened to its line, or to the spaces after it when it shares the line.
+fn field_span(source: &str, span: Span) -> Span {
+    let line = line_span(source, span);
+    if line != span {
+        return line;
+    }
+    let rest = &source[span.end as usize..];
+    let spaces = rest.len() - rest.trim_start_matches([' ', '\t']).len();
+    Span::new(span.start, span.end + spaces as u32)
+}
+
+/// Parameter names of a constructor, for spotting the `this.x = x;` parameter properties.
+fn parameter_names<'a>(constructor: &Function<'a>) -> Vec<&'a str> {
+    constructor
+        .params
+        .items
+        .iter()
+        .filter_map(|param| match &param.pattern.kind {
+            BindingPatternKind::AssignmentPattern(assign) => assign.left.get_binding_identifier(),
+            kind => kind.get_binding_identifier(),
+        })
+        .map(|id| id.name.as_str())
+        .collect()
+}
+
+fn is_super_call(stmt: &Statement) -> bool {
+    matches!(stmt, Statement::ExpressionStatement(expr)
+        if matches!(&expr.expression, Expression::CallExpression(call) if call.callee.is_super()))
+}
+
+fn is_parameter_property(stmt: &Statement, parameters: &[&str]) -> bool {
+    let Statement::ExpressionStatement(expr) = stmt else {
+        return false;
+    };
+    let Expression::AssignmentExpression(assign) = &expr.expression else {
+        return false;
+    };
+    let (AssignmentTarget::StaticMemberExpression(member), Expression::Identifier(value)) =
+        (&assign.left, &assign.right)
+    else {
+        return false;
+    };
+    matches!(member.object, Expression::ThisExpression(_))
+        && member.property.name == value.name
+        && parameters.contains(&value.name.as_str())
+}
+
+struct ClassFields<'s, 't> {
+    source: &'s str,
+    temporaries: &'t mut Temporaries,
+    edits: TextEdits,
+    /// End of the declaration statement the next visited class belongs to.
+    statement_end: Option<u32>,
+}
+
+impl ClassFields<'_, '_> {
+    fn text(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    /// The text of a static field's initializer, with `this` replaced by `class`.
+    fn static_value(&self, value: &Expression, class: &str) -> String {
+        let span = value.span();
+        let mut text = String::new();
+        let mut cursor = span.start;
+        for this in this_references(value) {
+            text.push_str(self.text(Span::new(cursor, this.start)));
+            text.push_str(class);
+            cursor = this.end;
+        }
+        text.push_str(self.text(Span::new(cursor, span.end)));
+        text
+    }
+
+    fn accessor(&self, key: &PropertyKey, computed: bool) -> String {
+        match key {
+            PropertyKey::StaticIdentifier(id) if !computed => format!(".{}", id.name),
+            key => format!("[{}]", self.text(key.span())),
+        }
+    }
+
+    fn assignments(&self, target: &str, assignments: &[Assignment]) -> Vec<String> {
+        assignments
+            .iter()
+            .map(|assignment| format!("{target}{} = {}", assignment.accessor, assignment.value))
+            .collect()
+    }
+
+    fn initialize_instance_fields(&mut self, class: &Class, fields: &[Assignment]) {
+        let statements = self.assignments("this", fields);
+        let body = &class.body;
+        let constructor = body.body.iter().find_map(|element| match element {
+            ClassElement::MethodDefinition(method)
+                if method.kind == MethodDefinitionKind::Constructor =>
+            {
+                method.value.body.as_ref().map(|body| (&method.value, body))
+            }
+            _ => None,
+        });
+
+        let Some((constructor, constructor_body)) = constructor else {
+            let multiline = self.text(body.span).contains('\n');
+            let (open, close, separator) = if multiline {
+                let indent = body
+                    .body
+                    .first()
+                    .map_or("", |element| indent_at(self.source, element.span().start));
+                (
+                    format!("\n{indent}"),
+                    format!("\n{indent}"),
+                    format!("\n{indent}    "),
+                )
+            } else {
+                (" ".to_string(), " ".to_string(), " ".to_string())
+            };
+            let (parameters, super_call) = if class.super_class.is_some() {
+                ("...args", format!("{separator}super(...args);"))
+            } else {
+                ("", String::new())
+            };
+            let assignments: String = statements
+                .iter()
+                .map(|statement| format!("{separator}{statement};"))
+                .collect();
+            self.edits.insert(
+                body.span.start + 1,
+                format!("{open}constructor({parameters}) {{{super_call}{assignments}{close}}}"),
+            );
+            return;
+        };
+
+        // Fields are initialized after `super()` and any parameter properties
+        let parameters = parameter_names(constructor);
+        let statements_in_body = &constructor_body.statements;
+        let super_index = statements_in_body.iter().position(is_super_call);
+        let mut anchor = super_index;
+        for (index, stmt) in statements_in_body
+            .iter()
+            .enumerate()
+            .skip(super_index.map_or(0, |index| index + 1))
+        {
+            if !is_parameter_property(stmt, &parameters) {
+                break;
+            }
+            anchor = Some(index);
+        }
+        let multiline = self.text(constructor_body.span).contains('\n');
+        let (at, indent) = match (anchor, statements_in_body.first()) {
+            (Some(index), _) => {
+                let stmt = &statements_in_body[index];
+                (
+                    stmt.span().end,
+                    indent_at(self.source, stmt.span().start).to_string(),
+                )
+            }
+            (None, Some(first)) => (
+                constructor_body.span.start + 1,
+                indent_at(self.source, first.span().start).to_string(),
+            ),
+            (None, None) => (
+                constructor_body.span.start + 1,
+                format!(
+                    "{}    ",
+                    indent_at(self.source, constructor_body.span.start)
+                ),
+            ),
+        };
+        let text: String = statements
+            .iter()
+            .map(|statement| {
+                if multiline {
+                    format!("\n{indent}{statement};")
+                } else {
+                    format!(" {statement};")
+                }
+            })
+            .collect();
+        self.edits.insert(at, text);
+    }
+
+    /// The name static fields are assigned through: the class's own, when it's declared
+    /// by a statement they can follow, and otherwise a temporary.
+    fn class_reference(&mut self, class: &Class, statement_end: Option<u32>) -> String {
+        match (statement_end, &class.id) {
+            (Some(_), Some(id)) => id.name.to_string(),
+            _ => self.temporaries.fresh(),
+        }
+    }
+
+    fn initialize_static_fields(
+        &mut self,
+        class: &Class,
+        reference: &str,
+        statement_end: Option<u32>,
+        fields: &[Assignment],
+    ) {
+        if let (Some(end), Some(_)) = (statement_end, &class.id) {
+            let indent = indent_at(self.source, class.span.start);
+            let text: String = self
+                .assignments(reference, fields)
+                .iter()
+                .map(|statement| format!("\n{indent}{statement};"))
+                .collect();
+            self.edits.insert(end, text);
+            return;
+        }
+        // A class expression becomes `(_a = class {}, _a.x = 1, _a)`
+        let temp = reference;
+        let assignments = self.assignments(temp, fields).join(", ");
+        // `export default class {}` needs a terminator once it's an expression
+        let terminator = if class.is_declaration() { ";" } else { "" };
+        self.edits.insert(class.span.start, format!("({temp} = "));
+        self.edits.insert(
+            class.span.end,
+            format!(", {assignments}, {temp}){terminator}"),
+        );
+    }
+}
+
+impl<'a> Visit<'a> for ClassFields<'_, '_> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>) {
+        self.statement_end = match stmt {
+            Statement::ClassDeclaration(_) => Some(stmt.span().end),
+            Statement::ExportNamedDeclaration(export)
+                if matches!(export.declaration, Some(Declaration::ClassDeclaration(_))) =>
+            {
+                Some(stmt.span().end)
+            }
+            Statement::ExportDefaultDeclaration(export) if matches!(&export.declaration, ExportDefaultDeclarationKind::ClassDeclaration(class) if class.id.is_some()) => {
+                Some(stmt.span().end)
+            }
+            _ => None,
+        };
+        walk::walk_statement(self, stmt);
+    }
+
+    fn visit_class(&mut self, class: &Class<'a>) {
+        let statement_end = self.statement_end.take();
+        let mut instance = Vec::new();
+        let mut statics = Vec::new();
+        for element in &class.body.body {
+            let ClassElement::PropertyDefinition(property) = element else {
+                continue;
+            };
+            self.edits.remove(field_span(self.source, property.span));
+            let Some(value) = &property.value else {
+                continue;
+            };
+            if property.r#static {
+                statics.push((property, value));
+            } else {
+                instance.push(Assignment {
+                    accessor: self.accessor(&property.key, property.computed),
+                    value: self.text(value.span()).to_string(),
+                });
+            }
+        }
+        if !instance.is_empty() {
+            self.initialize_instance_fields(class, &instance);
+        }
+        if !statics.is_empty() {
+            let reference = self.class_reference(class, statement_end);
+            let statics: Vec<Assignment> = statics
+                .into_iter()
+                .map(|(property, value)| Assignment {
+                    accessor: self.accessor(&property.key, property.computed),
+                    value: self.static_value(value, &reference),
+                })
+                .collect();
+            self.initialize_static_fields(class, &reference, statement_end, &statics);
+        }
+
+        // Moved initializers are copied as written and lowered on the next run
+        if let Some(super_class) = &class.super_class {
+            self.visit_expression(super_class);
+        }
+        for element in &class.body.body {
+            if !matches!(element, ClassElement::PropertyDefinition(_)) {
+                self.visit_class_element(element);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::emit;
+    use crate::emit::transpile;
+    use crate::options::{CompilerOptions, ScriptTarget};
+
+    #[test]
+    fn test_lower_instance_fields() {
+        let source = r#"class A extends B {
+    x = 1;
+    y;
+    "z-z" = [];
+    m() {}
+}"#;
+        assert_eq!(
+            emit(source, ScriptTarget::ES2021),
+            r#"class A extends B {
+    constructor(...args) {
+        super(...args);
+        this.x = 1;
+        this["z-z"] = [];
+    }
+    m() {}
+}"#
+        );
+        assert_eq!(
+            emit(
+                "class P {\n    a = this.x;\n    constructor(public x: number) {\n        log();\n    }\n}",
+                ScriptTarget::ES2021
+            ),
+            "class P {\n    constructor(x) {\n        this.x = x;\n        this.a = this.x;\n        log();\n    }\n}"
+        );
+    }
+
+    #[test]
+    fn test_lower_static_fields() {
+        assert_eq!(
+            emit("export class A { static x = 1; }", ScriptTarget::ES2021),
+            "export class A { }\nA.x = 1;"
+        );
+        assert_eq!(
+            emit("const C = class { static y = f(); };", ScriptTarget::ES2021),
+            "var _a;\nconst C = (_a = class { }, _a.y = f(), _a);"
+        );
+    }
+
+    #[test]
+    fn test_lower_static_this() {
+        // `this` in a static initializer is the class, unless a function rebinds it
+        assert_eq!(
+            emit(
+                "class A { static x = 1; static y = this.x + 1; static f = () => this; static g = function () { return this; }; }",
+                ScriptTarget::ES2021
+            ),
+            "class A { }\nA.x = 1;\nA.y = A.x + 1;\nA.f = () => A;\nA.g = function () { return this; };"
+        );
+        assert_eq!(
+            emit(
+                "const C = class { static s = this.name; };",
+                ScriptTarget::ES2021
+            ),
+            "var _a;\nconst C = (_a = class { }, _a.s = _a.name, _a);"
+        );
+    }
+
+    #[test]
+    fn test_private_names_need_es2022() {
+        let options = CompilerOptions {
+            target: ScriptTarget::ES2021,
+            ..Default::default()
+        };
+        assert_eq!(
+            transpile("class A { #p = 2; get() { return this.#p; } }", &options),
+            Err(
+                "Private name '#p' can't be lowered for target 'ES2021'. Use 'ES2022' or later."
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            emit("class A { #p = 2; }", ScriptTarget::ES2022),
+            "class A { #p = 2; }"
+        );
+    }
+}
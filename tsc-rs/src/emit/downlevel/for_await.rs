@@ -0,0 +1,151 @@
+use super::super::{TextEdits, insert_at_top};
+use super::temporaries::Temporaries;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::GetSpan;
+
+// TypeScript's helper, emitted once per file that needs it
+const ASYNC_VALUES: &str = r#"var __asyncValues = (this && this.__asyncValues) || function (o) {
+    if (!Symbol.asyncIterator) throw new TypeError("Symbol.asyncIterator is not defined.");
+    var m = o[Symbol.asyncIterator], i;
+    return m ? m.call(o) : (o = typeof __values === "function" ? __values(o) : o[Symbol.iterator](), i = {}, verb("next"), verb("throw"), verb("return"), i[Symbol.asyncIterator] = function () { return this; }, i);
+    function verb(n) { i[n] = o[n] && function (v) { return new Promise(function (resolve, reject) { v = o[n](v), settle(resolve, reject, v.done, v.value); }); }; }
+    function settle(resolve, reject, d, v) { Promise.resolve(v).then(function(v) { resolve({ value: v, done: d }); }, reject); }
+};"#;
+
+/// Rewrites `for await` loops as `for` loops that await each step of the iterator
+/// `__asyncValues` gets, closing it when the loop is left early.
+///
+/// The `await`s this leaves are lowered with the rest of the async function when the
+/// target is older still.
+pub(super) fn lower(program: &Program, source: &str, temporaries: &mut Temporaries) -> TextEdits {
+    let mut lowering = ForAwait {
+        source,
+        temporaries,
+        edits: TextEdits::default(),
+        lowered: false,
+    };
+    lowering.visit_program(program);
+    if lowering.lowered {
+        insert_at_top(program, &mut lowering.edits, ASYNC_VALUES);
+    }
+    lowering.edits
+}
+
+struct ForAwait<'s, 't> {
+    source: &'s str,
+    temporaries: &'t mut Temporaries,
+    edits: TextEdits,
+    lowered: bool,
+}
+
+impl ForAwait<'_, '_> {
+    fn text(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    /// Lowers `stmt`, whose rewrite starts at `start` so the `labels` before it can move
+    /// onto the new `for`, where `break` and `continue` still find them.
+    fn lower_loop(&mut self, stmt: &ForOfStatement, start: u32, labels: &str) {
+        let iterator = self.temporaries.fresh();
+        let step = self.temporaries.fresh();
+        let in_body = self.temporaries.fresh();
+        let error = self.temporaries.fresh();
+        let value = format!("{step}.value");
+        let binding = match &stmt.left {
+            ForStatementLeft::VariableDeclaration(decl) => {
+                let pattern = decl
+                    .declarations
+                    .first()
+                    .map_or("", |declarator| self.text(declarator.id.span()));
+                format!("{} {pattern} = {value};", decl.kind.as_str())
+            }
+            left => match self.text(left.span()) {
+                // A destructuring assignment can't start a statement
+                target if target.starts_with('{') => format!("({target} = {value});"),
+                target => format!("{target} = {value};"),
+            },
+        };
+        let iterable = self.text(stmt.right.span());
+        // Copied as written; anything nested is lowered on the next run
+        self.edits.replace(
+            Span::new(start, stmt.body.span().start),
+            format!(
+                "try {{ {labels}for ({in_body} = true, {iterator} = __asyncValues({iterable}); {step} = await {iterator}.next(), !{step}.done; {in_body} = true) {{ {in_body} = false; {binding} "
+            ),
+        );
+        self.edits.insert(
+            stmt.body.span().end,
+            format!(
+                " }} }} catch (e) {{ {error} = {{ error: e }}; }} finally {{ try {{ if (!{in_body} && {iterator}.return) await {iterator}.return(); }} finally {{ if ({error}) throw {error}.error; }} }}"
+            ),
+        );
+        self.lowered = true;
+    }
+}
+
+impl<'a> Visit<'a> for ForAwait<'_, '_> {
+    fn visit_labeled_statement(&mut self, stmt: &LabeledStatement<'a>) {
+        let mut labels = format!("{}: ", stmt.label.name);
+        let mut body = &stmt.body;
+        while let Statement::LabeledStatement(inner) = body {
+            labels.push_str(&format!("{}: ", inner.label.name));
+            body = &inner.body;
+        }
+        match body {
+            Statement::ForOfStatement(for_of) if for_of.r#await => {
+                self.lower_loop(for_of, stmt.span.start, &labels);
+            }
+            _ => walk::walk_labeled_statement(self, stmt),
+        }
+    }
+
+    fn visit_for_of_statement(&mut self, stmt: &ForOfStatement<'a>) {
+        if stmt.r#await {
+            self.lower_loop(stmt, stmt.span.start, "");
+        } else {
+            walk::walk_for_of_statement(self, stmt);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::emit;
+    use crate::options::ScriptTarget;
+
+    fn without_helpers(output: &str) -> &str {
+        let end = output.rfind("\n};\n").expect("helper") + 4;
+        &output[end..]
+    }
+
+    #[test]
+    fn test_lower_for_await() {
+        let output = emit(
+            "async function f(xs: AsyncIterable<number>) {\n    for await (const x of xs) log(x);\n}",
+            ScriptTarget::ES2017,
+        );
+        assert!(output.starts_with("var __asyncValues = "));
+        assert_eq!(
+            without_helpers(&output),
+            "async function f(xs) {\n    var _a, _b, _c, _d;\n    try { for (_c = true, _a = __asyncValues(xs); _b = await _a.next(), !_b.done; _c = true) { _c = false; const x = _b.value; log(x); } } catch (e) { _d = { error: e }; } finally { try { if (!_c && _a.return) await _a.return(); } finally { if (_d) throw _d.error; } }\n}"
+        );
+        let source = "async function f(xs) { for await (const x of xs) {} }";
+        assert_eq!(emit(source, ScriptTarget::ES2018), source);
+    }
+
+    #[test]
+    fn test_lower_for_await_in_lowered_async_functions() {
+        // Valid before, and still valid once `await` is `yield` in a generator
+        let output = emit(
+            "async function f(xs) { outer: for await ({ a } of xs) { continue outer; } }",
+            ScriptTarget::ES2015,
+        );
+        assert_eq!(
+            without_helpers(&output),
+            "function f(xs) { return __awaiter(this, void 0, void 0, function* () { var _a, _b, _c, _d; try { outer: for (_c = true, _a = __asyncValues(xs); _b = (yield _a.next()), !_b.done; _c = true) { _c = false; ({ a } = _b.value); { continue outer; } } } catch (e) { _d = { error: e }; } finally { try { if (!_c && _a.return) (yield _a.return()); } finally { if (_d) throw _d.error; } } }); }"
+        );
+        assert!(crate::parser::parse_typescript(&output).is_ok());
+    }
+}
@@ -0,0 +1,97 @@
+use super::super::TextEdits;
+use super::capture;
+use super::temporaries::Temporaries;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::GetSpan;
+use oxc_syntax::operator::AssignmentOperator;
+
+/// Rewrites `a ??= b` as `a ?? (a = b)`, and `||=` and `&&=` the same way, evaluating a
+/// member target's object and key only once.
+pub(super) fn lower(program: &Program, source: &str, temporaries: &mut Temporaries) -> TextEdits {
+    let mut lowering = LogicalAssignment {
+        source,
+        temporaries,
+        edits: TextEdits::default(),
+    };
+    lowering.visit_program(program);
+    lowering.edits
+}
+
+struct LogicalAssignment<'s, 't> {
+    source: &'s str,
+    temporaries: &'t mut Temporaries,
+    edits: TextEdits,
+}
+
+impl<'a> Visit<'a> for LogicalAssignment<'_, '_> {
+    fn visit_assignment_expression(&mut self, expr: &AssignmentExpression<'a>) {
+        let operator = match expr.operator {
+            AssignmentOperator::LogicalNullish => "??",
+            AssignmentOperator::LogicalOr => "||",
+            AssignmentOperator::LogicalAnd => "&&",
+            _ => {
+                walk::walk_assignment_expression(self, expr);
+                return;
+            }
+        };
+        // The target is read once and written once; `(_a = o).x ?? (_a.x = b)`
+        let (read, write) = match &expr.left {
+            AssignmentTarget::StaticMemberExpression(member) => {
+                let (object, reference) = capture(self.source, &member.object, self.temporaries);
+                let property = &member.property.name;
+                (
+                    format!("{object}.{property}"),
+                    format!("{reference}.{property}"),
+                )
+            }
+            AssignmentTarget::ComputedMemberExpression(member) => {
+                let (object, object_reference) =
+                    capture(self.source, &member.object, self.temporaries);
+                let (key, key_reference) =
+                    capture(self.source, &member.expression, self.temporaries);
+                (
+                    format!("{object}[{key}]"),
+                    format!("{object_reference}[{key_reference}]"),
+                )
+            }
+            target => {
+                let span = target.span();
+                let target = self.source[span.start as usize..span.end as usize].to_string();
+                (target.clone(), target)
+            }
+        };
+        // The value is copied as written; anything nested is lowered on the next run
+        let span = expr.right.span();
+        let value = &self.source[span.start as usize..span.end as usize];
+        self.edits
+            .replace(expr.span, format!("{read} {operator} ({write} = {value})"));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::emit;
+    use crate::options::ScriptTarget;
+
+    #[test]
+    fn test_lower_logical_assignment() {
+        assert_eq!(
+            emit("x ??= 3;\ny ||= z &&= 1;", ScriptTarget::ES2020),
+            "x ?? (x = 3);\ny || (y = z && (z = 1));"
+        );
+        assert_eq!(
+            emit(
+                "this.a ??= 1;\nf().b ||= 2;\no[k()] &&= 3;",
+                ScriptTarget::ES2020
+            ),
+            "var _a, _b;\nthis.a ?? (this.a = 1);\n(_a = f()).b || (_a.b = 2);\no[(_b = k())] && (o[_b] = 3);"
+        );
+        assert_eq!(
+            emit("x ??= 3;", ScriptTarget::ES2019),
+            "(x !== null && x !== void 0 ? x : (x = 3));"
+        );
+        assert_eq!(emit("x ??= 3;", ScriptTarget::ES2021), "x ??= 3;");
+    }
+}
@@ -0,0 +1,67 @@
+use super::super::TextEdits;
+use super::capture;
+use super::temporaries::Temporaries;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::GetSpan;
+use oxc_syntax::operator::LogicalOperator;
+
+/// Rewrites `a ?? b` as `(a !== null && a !== void 0 ? a : b)`.
+pub(super) fn lower(program: &Program, source: &str, temporaries: &mut Temporaries) -> TextEdits {
+    let mut lowering = NullishCoalescing {
+        source,
+        temporaries,
+        edits: TextEdits::default(),
+    };
+    lowering.visit_program(program);
+    lowering.edits
+}
+
+struct NullishCoalescing<'s, 't> {
+    source: &'s str,
+    temporaries: &'t mut Temporaries,
+    edits: TextEdits,
+}
+
+impl<'a> Visit<'a> for NullishCoalescing<'_, '_> {
+    fn visit_logical_expression(&mut self, expr: &LogicalExpression<'a>) {
+        if expr.operator != LogicalOperator::Coalesce {
+            walk::walk_logical_expression(self, expr);
+            return;
+        }
+        // Operands are copied as written; anything nested is lowered on the next run
+        let (value, reference) = capture(self.source, &expr.left, self.temporaries);
+        let right = expr.right.span();
+        let right = &self.source[right.start as usize..right.end as usize];
+        self.edits.replace(
+            expr.span,
+            format!("({value} !== null && {reference} !== void 0 ? {reference} : {right})"),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::emit;
+    use crate::options::ScriptTarget;
+
+    #[test]
+    fn test_lower_nullish_coalescing() {
+        assert_eq!(
+            emit("let a = b ?? 1;", ScriptTarget::ES2019),
+            "let a = (b !== null && b !== void 0 ? b : 1);"
+        );
+        assert_eq!(
+            emit(
+                "function f() {\n    return g() ?? h ?? 2;\n}",
+                ScriptTarget::ES2019
+            ),
+            "function f() {\n    var _a, _b;\n    return ((_a = ((_b = g()) !== null && _b !== void 0 ? _b : h)) !== null && _a !== void 0 ? _a : 2);\n}"
+        );
+        assert_eq!(
+            emit("let a = b ?? 1;", ScriptTarget::ES2020),
+            "let a = b ?? 1;"
+        );
+    }
+}
@@ -0,0 +1,170 @@
+use super::super::TextEdits;
+use super::temporaries::Temporaries;
+use super::{capture, is_simple};
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::GetSpan;
+use oxc_syntax::operator::UnaryOperator;
+
+/// Rewrites `a?.b` as `(a === null || a === void 0 ? void 0 : a.b)`.
+pub(super) fn lower(program: &Program, source: &str, temporaries: &mut Temporaries) -> TextEdits {
+    let mut lowering = OptionalChaining {
+        source,
+        temporaries,
+        edits: TextEdits::default(),
+    };
+    lowering.visit_program(program);
+    lowering.edits
+}
+
+/// The first `?.` in a chain: the expression before it, and the call it makes, if any.
+struct OptionalLink<'e, 'a> {
+    object: &'e Expression<'a>,
+    call: Option<&'e CallExpression<'a>>,
+}
+
+fn first_optional_call<'e, 'a>(call: &'e CallExpression<'a>) -> Option<OptionalLink<'e, 'a>> {
+    first_optional(&call.callee).or_else(|| {
+        call.optional.then_some(OptionalLink {
+            object: &call.callee,
+            call: Some(call),
+        })
+    })
+}
+
+fn first_optional_member<'e, 'a>(member: &'e MemberExpression<'a>) -> Option<OptionalLink<'e, 'a>> {
+    first_optional(member.object()).or_else(|| {
+        member.optional().then_some(OptionalLink {
+            object: member.object(),
+            call: None,
+        })
+    })
+}
+
+fn first_optional<'e, 'a>(expr: &'e Expression<'a>) -> Option<OptionalLink<'e, 'a>> {
+    match expr {
+        Expression::CallExpression(call) => first_optional_call(call),
+        Expression::TSNonNullExpression(non_null) => first_optional(&non_null.expression),
+        _ => first_optional_member(expr.as_member_expression()?),
+    }
+}
+
+fn first_optional_in_chain<'e, 'a>(element: &'e ChainElement<'a>) -> Option<OptionalLink<'e, 'a>> {
+    match element {
+        ChainElement::CallExpression(call) => first_optional_call(call),
+        ChainElement::TSNonNullExpression(non_null) => first_optional(&non_null.expression),
+        _ => first_optional_member(element.as_member_expression()?),
+    }
+}
+
+struct OptionalChaining<'s, 't> {
+    source: &'s str,
+    temporaries: &'t mut Temporaries,
+    edits: TextEdits,
+}
+
+impl OptionalChaining<'_, '_> {
+    fn text(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    /// Splits a chain at its first `?.` into a nullish test and the access it guards.
+    ///
+    /// Later `?.`s stay in the access and are lowered on the next run.
+    fn split(&mut self, chain: &ChainExpression) -> Option<(String, String)> {
+        let link = first_optional_in_chain(&chain.expression)?;
+        let object_end = link.object.span().end as usize;
+        let question = object_end + self.source[object_end..].find("?.")?;
+        let rest = &self.source[question + 2..chain.span.end as usize];
+
+        // `a.b?.()` must still call `b` with `a` as `this`
+        if let Some(call) = link.call
+            && let Some(member) = link.object.as_member_expression()
+        {
+            let object = member.object();
+            let (callee, this) = if is_simple(object) {
+                let object = self.text(object.span()).to_string();
+                (self.text(link.object.span()).to_string(), object)
+            } else {
+                let temp = self.temporaries.fresh();
+                let accessor = self.text(Span::new(object.span().end, link.object.span().end));
+                let callee = format!("({temp} = {}){accessor}", self.text(object.span()));
+                (callee, temp)
+            };
+            let function = self.temporaries.fresh();
+            let test = format!("({function} = {callee}) === null || {function} === void 0");
+            let after_paren = rest.trim_start().strip_prefix('(')?;
+            let separator = if call.arguments.is_empty() { "" } else { ", " };
+            let access = format!("{function}.call({this}{separator}{after_paren}");
+            return Some((test, access));
+        }
+
+        let (value, reference) = capture(self.source, link.object, self.temporaries);
+        let test = format!("{value} === null || {reference} === void 0");
+        let access = if rest.trim_start().starts_with(['[', '(']) {
+            format!("{reference}{rest}")
+        } else {
+            format!("{reference}.{rest}")
+        };
+        Some((test, access))
+    }
+}
+
+impl<'a> Visit<'a> for OptionalChaining<'_, '_> {
+    fn visit_chain_expression(&mut self, chain: &ChainExpression<'a>) {
+        match self.split(chain) {
+            Some((test, access)) => {
+                self.edits
+                    .replace(chain.span, format!("({test} ? void 0 : {access})"));
+            }
+            None => walk::walk_chain_expression(self, chain),
+        }
+    }
+
+    fn visit_unary_expression(&mut self, expr: &UnaryExpression<'a>) {
+        if expr.operator == UnaryOperator::Delete
+            && let Expression::ChainExpression(chain) = &expr.argument
+            && let Some((test, access)) = self.split(chain)
+        {
+            self.edits
+                .replace(expr.span, format!("({test} ? true : delete {access})"));
+            return;
+        }
+        walk::walk_unary_expression(self, expr);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::emit;
+    use crate::options::ScriptTarget;
+
+    #[test]
+    fn test_lower_optional_chains() {
+        assert_eq!(
+            emit("a?.b.c;", ScriptTarget::ES2019),
+            "(a === null || a === void 0 ? void 0 : a.b.c);"
+        );
+        assert_eq!(
+            emit("a?.[k]?.(1);", ScriptTarget::ES2019),
+            "var _a;\n(a === null || a === void 0 ? void 0 : ((_a = a[k]) === null || _a === void 0 ? void 0 : _a.call(a, 1)));"
+        );
+        assert_eq!(
+            emit("delete this?.x;", ScriptTarget::ES2019),
+            "(this === null || this === void 0 ? true : delete this.x);"
+        );
+    }
+
+    #[test]
+    fn test_optional_calls_keep_this() {
+        assert_eq!(
+            emit("o.m?.(1, 2);", ScriptTarget::ES2019),
+            "var _a;\n((_a = o.m) === null || _a === void 0 ? void 0 : _a.call(o, 1, 2));"
+        );
+        assert_eq!(
+            emit("get().m?.();", ScriptTarget::ES2019),
+            "var _a, _b;\n((_b = (_a = get()).m) === null || _b === void 0 ? void 0 : _b.call(_a));"
+        );
+    }
+}
@@ -0,0 +1,131 @@
+use super::super::{TextEdits, indent_at, insert_at_top, names_in};
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::GetSpan;
+use std::collections::HashSet;
+
+/// Allocates `_a`, `_b`, ... names that don't collide with anything in the file.
+#[derive(Debug, Default)]
+pub(super) struct Temporaries {
+    used: HashSet<String>,
+    allocated: Vec<String>,
+}
+
+impl Temporaries {
+    pub(super) fn new(program: &Program) -> Self {
+        Self {
+            used: names_in(program),
+            allocated: Vec::new(),
+        }
+    }
+
+    pub(super) fn fresh(&mut self) -> String {
+        let name = (0..)
+            .map(|i: usize| {
+                let letter = (b'a' + (i % 26) as u8) as char;
+                match i / 26 {
+                    0 => format!("_{letter}"),
+                    round => format!("_{letter}{round}"),
+                }
+            })
+            .find(|name| !self.used.contains(name))
+            .unwrap_or_default();
+        self.used.insert(name.clone());
+        self.allocated.push(name.clone());
+        name
+    }
+
+    pub(super) fn is_empty(&self) -> bool {
+        self.allocated.is_empty()
+    }
+
+    /// Declares each temporary with `var` in the closest function that uses it.
+    pub(super) fn declare(&self, program: &Program, source: &str) -> TextEdits {
+        let mut declarations = Declarations {
+            source,
+            temporaries: self.allocated.iter().map(String::as_str).collect(),
+            scopes: Vec::new(),
+            edits: TextEdits::default(),
+        };
+        declarations.visit_program(program);
+        declarations.edits
+    }
+}
+
+struct Declarations<'s> {
+    source: &'s str,
+    /// Allocated temporaries, in the order they were created.
+    temporaries: Vec<&'s str>,
+    /// Temporaries referenced in each enclosing function, innermost last.
+    scopes: Vec<Vec<String>>,
+    edits: TextEdits,
+}
+
+impl Declarations<'_> {
+    /// The innermost scope's temporaries, in allocation order.
+    fn pop_scope(&mut self) -> Vec<String> {
+        let mut names = self.scopes.pop().unwrap_or_default();
+        names.sort_by_key(|name| self.temporaries.iter().position(|temp| temp == name));
+        names
+    }
+}
+
+impl<'a> Visit<'a> for Declarations<'_> {
+    fn visit_program(&mut self, program: &Program<'a>) {
+        self.scopes.push(Vec::new());
+        walk::walk_program(self, program);
+        let names = self.pop_scope();
+        if !names.is_empty() {
+            insert_at_top(
+                program,
+                &mut self.edits,
+                &format!("var {};", names.join(", ")),
+            );
+        }
+    }
+
+    fn visit_function_body(&mut self, body: &FunctionBody<'a>) {
+        self.scopes.push(Vec::new());
+        walk::walk_function_body(self, body);
+        let names = self.pop_scope();
+        if names.is_empty() {
+            return;
+        }
+        let at = body
+            .directives
+            .last()
+            .map_or(body.span.start + 1, |directive| directive.span.end);
+        let body_text = &self.source[body.span.start as usize..body.span.end as usize];
+        let declaration = format!("var {};", names.join(", "));
+        let text = if body_text.contains('\n') {
+            let indent = match body.statements.first() {
+                Some(first) => indent_at(self.source, first.span().start).to_string(),
+                None => format!("{}    ", indent_at(self.source, body.span.start)),
+            };
+            format!("\n{indent}{declaration}")
+        } else {
+            format!(" {declaration}")
+        };
+        self.edits.insert(at, text);
+    }
+
+    fn visit_arrow_function_expression(&mut self, arrow: &ArrowFunctionExpression<'a>) {
+        // An expression body can't hold a declaration, so it shares the enclosing scope
+        if arrow.expression {
+            self.visit_formal_parameters(&arrow.params);
+            self.visit_statements(&arrow.body.statements);
+        } else {
+            walk::walk_arrow_function_expression(self, arrow);
+        }
+    }
+
+    fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+        if self.temporaries.contains(&ident.name.as_str())
+            && let Some(scope) = self.scopes.last_mut()
+            && !scope.iter().any(|name| name == ident.name.as_str())
+        {
+            scope.push(ident.name.to_string());
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use super::IifeBinding;
+use crate::enum_values::{EnumValue, member_values};
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+
+pub(super) fn js_string(value: &str) -> String {
+    serde_json::to_string(value).unwrap_or_default()
+}
+
+pub(super) fn js_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e21 {
+        format!("{}", value as i64)
+    } else {
+        value.to_string()
+    }
+}
+
+/// The value of `enum_name.member` written in its place, naming the member it came from.
+pub(super) fn inline_value(enum_name: &str, member: &str, value: &EnumValue) -> String {
+    let value = match value {
+        EnumValue::Number(n) => js_number(*n),
+        EnumValue::String(s) => js_string(s),
+    };
+    format!("{value} /* {enum_name}.{member} */")
+}
+
+/// Lowers `enum E { .. }` to an IIFE that fills in both the forward and reverse mappings.
+pub(super) fn lower_enum(
+    decl: &TSEnumDeclaration,
+    source: &str,
+    binding: &IifeBinding,
+    indent: &str,
+) -> String {
+    let name = decl.id.name.as_str();
+    let mut previous: Option<&str> = None;
+    let mut body = String::new();
+
+    let values = member_values(decl);
+    for (member, (member_name, value)) in decl.members.iter().zip(&values) {
+        let key = js_string(member_name);
+        let line = match value {
+            Some(EnumValue::Number(n)) => {
+                format!("{name}[{name}[{key}] = {}] = {key};", js_number(*n))
+            }
+            Some(EnumValue::String(s)) => format!("{name}[{key}] = {};", js_string(s)),
+            None => {
+                let expr = match &member.initializer {
+                    Some(init) => {
+                        source[init.span().start as usize..init.span().end as usize].to_string()
+                    }
+                    // Following a computed member, count up from its runtime value
+                    None => format!("{name}[{}] + 1", js_string(previous.unwrap_or_default())),
+                };
+                format!("{name}[{name}[{key}] = {expr}] = {key};")
+            }
+        };
+        body.push_str(&format!("{indent}    {line}\n"));
+        previous = Some(member_name);
+    }
+
+    let mut text = String::new();
+    if let Some(declaration) = &binding.declaration {
+        text.push_str(&format!("{declaration}\n{indent}"));
+    }
+    text.push_str(&format!(
+        "(function ({name}) {{\n{body}{indent}}})({});",
+        binding.argument
+    ));
+    text
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::emit::transpile;
+    use crate::options::CompilerOptions;
+
+    fn emit(source: &str) -> String {
+        transpile(source, &CompilerOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_lower_enums() {
+        let source = r#"enum Color { Red, Green = 5, Blue, Mask = Green | Blue, Neg = -1 }
+export enum Label { A = "a", B = `b`, AB = A + B }
+enum Runtime { X = compute(), Y }"#;
+        assert_eq!(
+            emit(source),
+            r#"var Color;
+(function (Color) {
+    Color[Color["Red"] = 0] = "Red";
+    Color[Color["Green"] = 5] = "Green";
+    Color[Color["Blue"] = 6] = "Blue";
+    Color[Color["Mask"] = 7] = "Mask";
+    Color[Color["Neg"] = -1] = "Neg";
+})(Color || (Color = {}));
+export var Label;
+(function (Label) {
+    Label["A"] = "a";
+    Label["B"] = "b";
+    Label["AB"] = "ab";
+})(Label || (Label = {}));
+var Runtime;
+(function (Runtime) {
+    Runtime[Runtime["X"] = compute()] = "X";
+    Runtime[Runtime["Y"] = Runtime["X"] + 1] = "Y";
+})(Runtime || (Runtime = {}));"#
+        );
+    }
+
+    #[test]
+    fn test_const_enums_inline() {
+        let source = r#"const enum Size { Small = 1, Large = Small * 2, Label = "big" }
+export const enum Dir { Up, Down }
+let s = Size.Large;
+console.log(Size["Label"], Dir.Down.toFixed());
+export { Size };"#;
+        assert_eq!(
+            emit(source),
+            r#"let s = 2 /* Size.Large */;
+console.log("big" /* Size.Label */, 1 /* Dir.Down */.toFixed());
+"#
+        );
+
+        let options = CompilerOptions {
+            preserve_const_enums: true,
+            ..Default::default()
+        };
+        let output = transpile("const enum E { A }\nlet a = E.A;", &options).unwrap();
+        assert_eq!(
+            output,
+            r#"var E;
+(function (E) {
+    E[E["A"] = 0] = "A";
+})(E || (E = {}));
+let a = 0 /* E.A */;"#
+        );
+    }
+
+    #[test]
+    fn test_merged_enums_declare_once() {
+        let output = emit("enum E { A }\nenum E { B = 2 }\ndeclare enum D { X }");
+        assert_eq!(output.matches("var E;").count(), 1);
+        assert!(output.contains(r#"E[E["B"] = 2] = "B";"#));
+        assert!(!output.contains("D"));
+    }
+}
@@ -0,0 +1,127 @@
+use super::IifeBinding;
+use super::strip::is_erased_declaration;
+use oxc_ast::ast::*;
+
+/// Whether a namespace contains any runtime values, as opposed to only types.
+pub(super) fn is_instantiated(decl: &TSModuleDeclaration) -> bool {
+    if decl.declare {
+        return false;
+    }
+    match &decl.body {
+        Some(TSModuleDeclarationBody::TSModuleDeclaration(inner)) => is_instantiated(inner),
+        Some(TSModuleDeclarationBody::TSModuleBlock(block)) => {
+            block.body.iter().any(|stmt| match stmt {
+                Statement::ExportNamedDeclaration(export) => export
+                    .declaration
+                    .as_ref()
+                    .is_none_or(|decl| !is_erased_declaration(decl)),
+                Statement::ImportDeclaration(import) => import.import_kind.is_value(),
+                _ => stmt
+                    .as_declaration()
+                    .is_none_or(|decl| !is_erased_declaration(decl)),
+            })
+        }
+        None => false,
+    }
+}
+
+/// The dotted names of `namespace A.B.C { .. }` and its innermost block.
+///
+/// Returns `None` for ambient module declarations like `declare module "x"` and `declare global`.
+pub(super) fn name_chain<'d, 'a>(
+    decl: &'d TSModuleDeclaration<'a>,
+) -> Option<(Vec<String>, &'d TSModuleBlock<'a>)> {
+    let mut names = Vec::new();
+    let mut current = decl;
+    loop {
+        if current.kind == TSModuleDeclarationKind::Global {
+            return None;
+        }
+        let TSModuleDeclarationName::Identifier(id) = &current.id else {
+            return None;
+        };
+        names.push(id.name.to_string());
+        match &current.body {
+            Some(TSModuleDeclarationBody::TSModuleDeclaration(inner)) => current = inner,
+            Some(TSModuleDeclarationBody::TSModuleBlock(block)) => return Some((names, block)),
+            None => return None,
+        }
+    }
+}
+
+/// The text replacing everything up to the body's `{`, and from its `}` to the end.
+pub(super) fn iife_wrapper(
+    names: &[String],
+    binding: &IifeBinding,
+    indent: &str,
+) -> (String, String) {
+    let mut header = String::new();
+    if let Some(declaration) = &binding.declaration {
+        header.push_str(&format!("{declaration}\n{indent}"));
+    }
+    header.push_str(&format!("(function ({}) {{", names[0]));
+    let mut footers = vec![format!("}})({});", binding.argument)];
+
+    for (depth, pair) in names.windows(2).enumerate() {
+        let (parent, name) = (&pair[0], &pair[1]);
+        let inner_indent = format!("{indent}{}", "    ".repeat(depth + 1));
+        header.push_str(&format!(
+            "\n{inner_indent}let {name};\n{inner_indent}(function ({name}) {{"
+        ));
+        footers.push(format!(
+            "}})({name} = {parent}.{name} || ({parent}.{name} = {{}}));\n{}",
+            &inner_indent[..inner_indent.len() - 4]
+        ));
+    }
+    (header, footers.into_iter().rev().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::emit::transpile;
+    use crate::options::CompilerOptions;
+
+    fn emit(source: &str) -> String {
+        transpile(source, &CompilerOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_lower_namespaces() {
+        let source = r#"namespace Shapes {
+    const sides = 4;
+    export const unit: number = 1;
+    export function area(n: number) { return n * sides; }
+    export enum Kind { Square }
+    export interface Shape {}
+}"#;
+        assert_eq!(
+            emit(source),
+            r#"var Shapes;
+(function (Shapes) {
+    const sides = 4;
+    const unit = 1;
+    Shapes.unit = unit;
+    function area(n) { return n * sides; }
+    Shapes.area = area;
+    let Kind;
+    (function (Kind) {
+        Kind[Kind["Square"] = 0] = "Square";
+    })(Kind = Shapes.Kind || (Shapes.Kind = {}));
+})(Shapes || (Shapes = {}));"#
+        );
+    }
+
+    #[test]
+    fn test_nested_and_type_only_namespaces() {
+        assert_eq!(
+            emit("export namespace A.B { export let x = 1; }"),
+            "export var A;\n(function (A) {\n    let B;\n    (function (B) { let x = 1;\nB.x = x; })(B = A.B || (A.B = {}));\n})(A || (A = {}));"
+        );
+        assert_eq!(
+            emit(
+                "namespace Types { export type T = number; }\ndeclare module \"x\" {}\ndeclare global { var g: number; }\nlet y = 1;"
+            ),
+            "let y = 1;"
+        );
+    }
+}
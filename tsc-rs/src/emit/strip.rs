@@ -0,0 +1,816 @@
+use super::namespaces::is_instantiated;
+use super::{IifeBinding, TextEdits, enums, indent_at, line_span, namespaces};
+use crate::enum_values::{EnumValue, member_values};
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::{GetSpan, Span};
+use oxc_syntax::scope::ScopeFlags;
+use std::collections::{HashMap, HashSet};
+
+// Modifiers that only exist in TypeScript; `static`, `async`, `get`, `set` and `accessor` stay
+const TS_MODIFIERS: &[&str] = &[
+    "public",
+    "private",
+    "protected",
+    "readonly",
+    "override",
+    "declare",
+    "abstract",
+];
+
+pub(super) fn strip_types(program: &Program, source: &str, preserve_const_enums: bool) -> String {
+    let mut references = ValueReferences::default();
+    references.visit_program(program);
+    let mut const_enums = ConstEnums::default();
+    const_enums.visit_program(program);
+    let mut type_only_names = type_only_names(&program.body);
+    if !preserve_const_enums {
+        // Nothing is left to export once their declarations are erased
+        type_only_names.extend(const_enums.0.keys().cloned());
+    }
+    let mut stripper = Stripper {
+        source,
+        edits: TextEdits::default(),
+        value_references: references.names,
+        type_only_names,
+        const_enums: const_enums.0,
+        preserve_const_enums,
+        declared: HashSet::new(),
+        scopes: vec![0],
+        namespaces: Vec::new(),
+    };
+    stripper.visit_program(program);
+    stripper.edits.apply(source)
+}
+
+/// Whether a declaration produces no JavaScript at all.
+pub(super) fn is_erased_declaration(decl: &Declaration) -> bool {
+    match decl {
+        Declaration::VariableDeclaration(var) => var.declare,
+        Declaration::FunctionDeclaration(func) => func.is_typescript_syntax(),
+        Declaration::ClassDeclaration(class) => class.declare,
+        Declaration::TSTypeAliasDeclaration(_) | Declaration::TSInterfaceDeclaration(_) => true,
+        Declaration::TSEnumDeclaration(decl) => decl.declare,
+        Declaration::TSModuleDeclaration(decl) => !is_instantiated(decl),
+        Declaration::TSImportEqualsDeclaration(decl) => decl.import_kind.is_type(),
+    }
+}
+
+fn is_erased_default(decl: &ExportDefaultDeclarationKind) -> bool {
+    match decl {
+        ExportDefaultDeclarationKind::FunctionDeclaration(func) => func.is_typescript_syntax(),
+        ExportDefaultDeclarationKind::ClassDeclaration(class) => class.declare,
+        ExportDefaultDeclarationKind::TSInterfaceDeclaration(_) => true,
+        _ => false,
+    }
+}
+
+/// Top-level names declared only as types, whose `export { .. }` specifiers must be dropped.
+fn type_only_names(statements: &[Statement]) -> HashSet<String> {
+    let mut types = HashSet::new();
+    let mut values = HashSet::new();
+    for stmt in statements {
+        let decl = match stmt {
+            Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                Some(decl) => decl,
+                None => continue,
+            },
+            _ => match stmt.as_declaration() {
+                Some(decl) => decl,
+                None => continue,
+            },
+        };
+        match decl {
+            Declaration::TSTypeAliasDeclaration(alias) => {
+                types.insert(alias.id.name.to_string());
+            }
+            Declaration::TSInterfaceDeclaration(interface) => {
+                types.insert(interface.id.name.to_string());
+            }
+            _ => {
+                if let Some(id) = decl.id() {
+                    values.insert(id.name.to_string());
+                }
+            }
+        }
+    }
+    types.retain(|name| !values.contains(name));
+    types
+}
+
+/// Every identifier referenced from a value position, for eliding type-only imports.
+#[derive(Default)]
+struct ValueReferences {
+    names: HashSet<String>,
+}
+
+impl<'a> Visit<'a> for ValueReferences {
+    fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+        self.names.insert(ident.name.to_string());
+    }
+
+    fn visit_ts_type(&mut self, _ty: &TSType<'a>) {}
+
+    fn visit_ts_type_parameter_declaration(&mut self, _decl: &TSTypeParameterDeclaration<'a>) {}
+
+    fn visit_ts_interface_declaration(&mut self, _decl: &TSInterfaceDeclaration<'a>) {}
+
+    fn visit_ts_type_alias_declaration(&mut self, _decl: &TSTypeAliasDeclaration<'a>) {}
+
+    fn visit_export_specifier(&mut self, spec: &ExportSpecifier<'a>) {
+        if spec.export_kind.is_value() {
+            walk::walk_export_specifier(self, spec);
+        }
+    }
+
+    fn visit_export_named_declaration(&mut self, decl: &ExportNamedDeclaration<'a>) {
+        if decl.export_kind.is_value() {
+            walk::walk_export_named_declaration(self, decl);
+        }
+    }
+}
+
+/// The member values of every const enum declared in the file, by enum name.
+#[derive(Default)]
+struct ConstEnums(HashMap<String, Vec<(String, Option<EnumValue>)>>);
+
+impl<'a> Visit<'a> for ConstEnums {
+    fn visit_ts_enum_declaration(&mut self, decl: &TSEnumDeclaration<'a>) {
+        if decl.r#const {
+            self.0
+                .entry(decl.id.name.to_string())
+                .or_default()
+                .extend(member_values(decl));
+        }
+    }
+}
+
+/// How an exported declaration is exposed.
+enum Export<'n> {
+    None,
+    /// `export` at module level.
+    Module,
+    /// `export` inside a namespace body; the value is also assigned to the namespace object.
+    Namespace(&'n str),
+}
+
+struct Stripper<'s> {
+    source: &'s str,
+    edits: TextEdits,
+    value_references: HashSet<String>,
+    type_only_names: HashSet<String>,
+    const_enums: HashMap<String, Vec<(String, Option<EnumValue>)>>,
+    /// Whether const enum declarations are emitted rather than erased.
+    preserve_const_enums: bool,
+    /// `(scope, name)` for enums and namespaces that already emitted their `var`.
+    declared: HashSet<(u32, String)>,
+    /// Start offsets of the function bodies and namespace blocks being visited.
+    scopes: Vec<u32>,
+    /// Names of the enclosing namespaces, innermost last.
+    namespaces: Vec<String>,
+}
+
+impl<'s> Stripper<'s> {
+    fn text(&self, span: Span) -> &str {
+        &self.source[span.start as usize..span.end as usize]
+    }
+
+    fn indent_at(&self, offset: u32) -> &'s str {
+        indent_at(self.source, offset)
+    }
+
+    /// Removes `span`, along with its line when nothing else is on it.
+    fn remove_statement(&mut self, span: Span) {
+        self.edits.remove(line_span(self.source, span));
+    }
+
+    /// Removes TypeScript modifier keywords (and their trailing whitespace) in `start..end`.
+    fn strip_modifiers(&mut self, start: u32, end: u32) {
+        let mut offset = start as usize;
+        while offset < end as usize {
+            let rest = &self.source[offset..end as usize];
+            let word_len = rest
+                .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+                .unwrap_or(rest.len());
+            if word_len == 0 {
+                let Some(c) = rest.chars().next() else { break };
+                if !c.is_whitespace() {
+                    break;
+                }
+                offset += c.len_utf8();
+                continue;
+            }
+            let word = &rest[..word_len];
+            let spaces = rest[word_len..].len() - rest[word_len..].trim_start().len();
+            if TS_MODIFIERS.contains(&word) {
+                self.edits.remove(Span::new(
+                    offset as u32,
+                    (offset + word_len + spaces) as u32,
+                ));
+            }
+            offset += word_len + spaces;
+        }
+    }
+
+    /// Removes a `?` or `!` marker following a class member's key.
+    fn strip_key_marker(&mut self, key: &PropertyKey, computed: bool) {
+        let mut offset = key.span().end as usize;
+        let skip_space = |offset: usize| {
+            let rest = &self.source[offset..];
+            offset + rest.len() - rest.trim_start().len()
+        };
+        offset = skip_space(offset);
+        if computed && self.source[offset..].starts_with(']') {
+            offset = skip_space(offset + 1);
+        }
+        if self.source[offset..].starts_with(['?', '!']) {
+            self.edits
+                .remove(Span::new(offset as u32, offset as u32 + 1));
+        }
+    }
+
+    fn modifiers_start(span: Span, decorators: &[Decorator]) -> u32 {
+        decorators.last().map_or(span.start, |d| d.span.end)
+    }
+
+    fn binding(&mut self, name: &str, export: &Export) -> IifeBinding {
+        let scope = *self.scopes.last().unwrap_or(&0);
+        let first = self.declared.insert((scope, name.to_string()));
+        match export {
+            Export::Namespace(parent) => IifeBinding {
+                declaration: first.then(|| format!("let {};", name)),
+                argument: format!("{name} = {parent}.{name} || ({parent}.{name} = {{}})"),
+            },
+            _ => IifeBinding {
+                declaration: first.then(|| match export {
+                    Export::Module => format!("export var {};", name),
+                    _ => format!("var {};", name),
+                }),
+                argument: format!("{name} || ({name} = {{}})"),
+            },
+        }
+    }
+
+    fn lower_enum(&mut self, decl: &TSEnumDeclaration, span: Span, export: &Export) {
+        if decl.r#const && !self.preserve_const_enums {
+            self.remove_statement(span);
+            return;
+        }
+        let binding = self.binding(&decl.id.name, export);
+        let indent = self.indent_at(span.start).to_string();
+        let text = enums::lower_enum(decl, self.source, &binding, &indent);
+        self.edits.replace(span, text);
+    }
+
+    fn lower_namespace(&mut self, decl: &TSModuleDeclaration<'_>, span: Span, export: &Export) {
+        let Some((names, block)) = namespaces::name_chain(decl) else {
+            self.remove_statement(span);
+            return;
+        };
+        if decl.declare || !is_instantiated(decl) {
+            self.remove_statement(span);
+            return;
+        }
+        let binding = self.binding(&names[0], export);
+        let indent = self.indent_at(span.start).to_string();
+        let (header, footer) = namespaces::iife_wrapper(&names, &binding, &indent);
+        self.edits
+            .replace(Span::new(span.start, block.span.start + 1), header);
+        self.edits
+            .replace(Span::new(block.span.end - 1, span.end), footer);
+
+        self.scopes.push(block.span.start);
+        self.namespaces.push(names.last().unwrap().clone());
+        self.visit_statements(&block.body);
+        self.namespaces.pop();
+        self.scopes.pop();
+    }
+
+    fn lower_import_equals(
+        &mut self,
+        decl: &TSImportEqualsDeclaration,
+        span: Span,
+        export: &Export,
+    ) {
+        if decl.import_kind.is_type()
+            || (matches!(export, Export::None)
+                && !self.value_references.contains(decl.id.name.as_str()))
+        {
+            self.remove_statement(span);
+            return;
+        }
+        let (keyword, value) = match &decl.module_reference {
+            TSModuleReference::ExternalModuleReference(external) => (
+                "const",
+                format!("require({})", self.text(external.expression.span)),
+            ),
+            reference => ("var", self.text(reference.span()).to_string()),
+        };
+        let name = &decl.id.name;
+        let text = match export {
+            Export::None => format!("{} {} = {};", keyword, name, value),
+            Export::Module => format!("export {} {} = {};", keyword, name, value),
+            Export::Namespace(parent) => format!(
+                "{keyword} {name} = {value};\n{}{parent}.{name} = {name};",
+                self.indent_at(span.start)
+            ),
+        };
+        self.edits.replace(span, text);
+    }
+
+    fn strip_import(&mut self, decl: &ImportDeclaration, span: Span) {
+        if decl.import_kind.is_type() {
+            self.remove_statement(span);
+            return;
+        }
+        // Side-effect imports are always kept
+        let Some(specifiers) = &decl.specifiers else {
+            return;
+        };
+        if specifiers.is_empty() {
+            return;
+        }
+        let mut default = None;
+        let mut namespace = None;
+        let mut named = Vec::new();
+        for specifier in specifiers {
+            let used =
+                |local: &BindingIdentifier| self.value_references.contains(local.name.as_str());
+            match specifier {
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(s) if used(&s.local) => {
+                    default = Some(self.text(s.span));
+                }
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(s) if used(&s.local) => {
+                    namespace = Some(self.text(s.span));
+                }
+                ImportDeclarationSpecifier::ImportSpecifier(s)
+                    if s.import_kind.is_value() && used(&s.local) =>
+                {
+                    named.push(self.text(s.span));
+                }
+                _ => {}
+            }
+        }
+        let kept = default.is_some() as usize + namespace.is_some() as usize + named.len();
+        if kept == specifiers.len() {
+            return;
+        }
+        if kept == 0 {
+            self.remove_statement(span);
+            return;
+        }
+        let mut clause: Vec<String> = default.into_iter().map(str::to_string).collect();
+        clause.extend(namespace.map(str::to_string));
+        if !named.is_empty() {
+            clause.push(format!("{{ {} }}", named.join(", ")));
+        }
+        let rest = self.text(Span::new(decl.source.span.start, decl.span.end));
+        let text = format!("import {} from {}", clause.join(", "), rest);
+        self.edits.replace(span, text);
+    }
+
+    fn strip_export_named(&mut self, decl: &ExportNamedDeclaration<'_>, span: Span) {
+        if decl.export_kind.is_type() {
+            self.remove_statement(span);
+            return;
+        }
+        if let Some(declaration) = &decl.declaration {
+            self.strip_exported_declaration(declaration, span);
+            return;
+        }
+        let kept: Vec<&str> = decl
+            .specifiers
+            .iter()
+            .filter(|spec| {
+                spec.export_kind.is_value()
+                    && (decl.source.is_some()
+                        || !self.type_only_names.contains(spec.local.name().as_str()))
+            })
+            .map(|spec| self.text(spec.span))
+            .collect();
+        if kept.len() == decl.specifiers.len() {
+            return;
+        }
+        if kept.is_empty() {
+            self.remove_statement(span);
+            return;
+        }
+        let mut text = format!("export {{ {} }}", kept.join(", "));
+        if let Some(source) = &decl.source {
+            text.push_str(" from ");
+            text.push_str(self.text(Span::new(source.span.start, decl.span.end)));
+        } else {
+            text.push(';');
+        }
+        self.edits.replace(span, text);
+    }
+
+    fn strip_exported_declaration(&mut self, decl: &Declaration<'_>, span: Span) {
+        if is_erased_declaration(decl) {
+            self.remove_statement(span);
+            return;
+        }
+        let namespace = self.namespaces.last().cloned();
+        let export = match &namespace {
+            Some(parent) => Export::Namespace(parent),
+            None => Export::Module,
+        };
+        match decl {
+            Declaration::TSEnumDeclaration(e) => return self.lower_enum(e, span, &export),
+            Declaration::TSModuleDeclaration(m) => return self.lower_namespace(m, span, &export),
+            Declaration::TSImportEqualsDeclaration(d) => {
+                return self.lower_import_equals(d, span, &export);
+            }
+            _ => {}
+        }
+        let Some(parent) = namespace else {
+            self.visit_declaration(decl);
+            return;
+        };
+        // Inside a namespace, `export` becomes an assignment to the namespace object
+        self.edits.remove(Span::new(span.start, decl.span().start));
+        self.visit_declaration(decl);
+        let indent = self.indent_at(span.start).to_string();
+        let names: Vec<String> = match decl {
+            Declaration::VariableDeclaration(var) => var
+                .declarations
+                .iter()
+                .flat_map(|d| d.id.get_binding_identifiers())
+                .map(|id| id.name.to_string())
+                .collect(),
+            _ => decl
+                .id()
+                .map(|id| id.name.to_string())
+                .into_iter()
+                .collect(),
+        };
+        let assignments: String = names
+            .iter()
+            .map(|name| format!("\n{indent}{parent}.{name} = {name};"))
+            .collect();
+        self.edits.insert(span.end, assignments);
+    }
+
+    /// Replaces a read of a const enum member with its value, returning whether it did.
+    fn inline_const_enum_member(&mut self, object: &Expression, member: &str, span: Span) -> bool {
+        let Expression::Identifier(object) = object else {
+            return false;
+        };
+        let value = self
+            .const_enums
+            .get(object.name.as_str())
+            .and_then(|members| members.iter().rev().find(|(name, _)| name == member))
+            .and_then(|(_, value)| value.as_ref());
+        let Some(value) = value else {
+            return false;
+        };
+        let text = enums::inline_value(&object.name, member, value);
+        self.edits.replace(span, text);
+        true
+    }
+
+    /// Moves `constructor(private x)` parameter properties into `this.x = x` assignments.
+    fn lower_parameter_properties(&mut self, constructor: &Function) {
+        let mut names = Vec::new();
+        for param in &constructor.params.items {
+            if param.accessibility.is_none() && !param.readonly && !param.r#override {
+                continue;
+            }
+            let pattern_start = param.pattern.kind.span().start;
+            self.strip_modifiers(
+                Self::modifiers_start(param.span, &param.decorators),
+                pattern_start,
+            );
+            let id = match &param.pattern.kind {
+                BindingPatternKind::AssignmentPattern(assign) => {
+                    assign.left.get_binding_identifier()
+                }
+                kind => kind.get_binding_identifier(),
+            };
+            if let Some(id) = id {
+                names.push(id.name.to_string());
+            }
+        }
+        let Some(body) = &constructor.body else {
+            return;
+        };
+        if names.is_empty() {
+            return;
+        }
+        // Fields must be assigned after `super()` returns
+        let super_call = body.statements.iter().find(|stmt| {
+            matches!(stmt, Statement::ExpressionStatement(expr)
+                if matches!(&expr.expression, Expression::CallExpression(call) if call.callee.is_super()))
+        });
+        let (at, indent) = match (super_call, body.statements.first()) {
+            (Some(stmt), _) => (stmt.span().end, self.indent_at(stmt.span().start)),
+            (None, Some(first)) => (body.span.start + 1, self.indent_at(first.span().start)),
+            (None, None) => (body.span.start + 1, ""),
+        };
+        let multiline = self.text(body.span).contains('\n');
+        let text: String = names
+            .iter()
+            .map(|name| {
+                if multiline {
+                    format!("\n{indent}this.{name} = {name};")
+                } else {
+                    format!(" this.{name} = {name};")
+                }
+            })
+            .collect();
+        self.edits.insert(at, text);
+    }
+}
+
+impl<'a> Visit<'a> for Stripper<'_> {
+    fn visit_statement(&mut self, stmt: &Statement<'a>) {
+        let span = stmt.span();
+        match stmt {
+            Statement::TSEnumDeclaration(decl) if !decl.declare => {
+                self.lower_enum(decl, span, &Export::None);
+            }
+            Statement::TSModuleDeclaration(decl) => self.lower_namespace(decl, span, &Export::None),
+            Statement::TSImportEqualsDeclaration(decl) => {
+                self.lower_import_equals(decl, span, &Export::None);
+            }
+            Statement::ImportDeclaration(decl) => self.strip_import(decl, span),
+            Statement::ExportNamedDeclaration(decl) => self.strip_export_named(decl, span),
+            Statement::ExportDefaultDeclaration(decl) if is_erased_default(&decl.declaration) => {
+                self.remove_statement(span);
+            }
+            Statement::ExportAllDeclaration(decl) if decl.export_kind.is_type() => {
+                self.remove_statement(span);
+            }
+            Statement::TSExportAssignment(assign) => {
+                self.edits.replace(
+                    Span::new(span.start, assign.expression.span().start),
+                    "module.exports = ",
+                );
+                self.visit_expression(&assign.expression);
+            }
+            Statement::TSNamespaceExportDeclaration(_) => self.remove_statement(span),
+            _ => match stmt.as_declaration() {
+                Some(decl) if is_erased_declaration(decl) => self.remove_statement(span),
+                _ => walk::walk_statement(self, stmt),
+            },
+        }
+    }
+
+    fn visit_function_body(&mut self, body: &FunctionBody<'a>) {
+        self.scopes.push(body.span.start);
+        // Exports can't appear in a function, so the body is outside any namespace
+        let namespaces = std::mem::take(&mut self.namespaces);
+        walk::walk_function_body(self, body);
+        self.namespaces = namespaces;
+        self.scopes.pop();
+    }
+
+    fn visit_ts_type_annotation(&mut self, annotation: &TSTypeAnnotation<'a>) {
+        self.edits.remove(annotation.span);
+    }
+
+    fn visit_ts_type_parameter_declaration(&mut self, decl: &TSTypeParameterDeclaration<'a>) {
+        self.edits.remove(decl.span);
+    }
+
+    fn visit_ts_type_parameter_instantiation(&mut self, inst: &TSTypeParameterInstantiation<'a>) {
+        self.edits.remove(inst.span);
+    }
+
+    fn visit_ts_type(&mut self, _ty: &TSType<'a>) {}
+
+    fn visit_function(&mut self, func: &Function<'a>, flags: ScopeFlags) {
+        if let Some(this_param) = &func.this_param {
+            // Take the following comma and space with it, or everything up to `)`
+            let end = func
+                .params
+                .items
+                .first()
+                .map(|param| param.span.start)
+                .or_else(|| func.params.rest.as_ref().map(|rest| rest.span.start))
+                .unwrap_or(func.params.span.end - 1);
+            self.edits.remove(Span::new(this_param.span.start, end));
+        }
+        walk::walk_function(self, func, flags);
+    }
+
+    fn visit_ts_this_parameter(&mut self, _param: &TSThisParameter<'a>) {}
+
+    fn visit_binding_pattern(&mut self, pattern: &BindingPattern<'a>) {
+        // `x?: T` and `x!: T` keep the `?`/`!` between the name and the annotation
+        if let BindingPatternKind::BindingIdentifier(id) = &pattern.kind {
+            let name_end = id.span.start + id.name.len() as u32;
+            let marker_end = pattern
+                .type_annotation
+                .as_ref()
+                .map_or(id.span.end, |annotation| annotation.span.start);
+            if marker_end > name_end {
+                self.edits.remove(Span::new(name_end, marker_end));
+            }
+        }
+        walk::walk_binding_pattern(self, pattern);
+    }
+
+    fn visit_class(&mut self, class: &Class<'a>) {
+        if class.r#abstract {
+            let scan_end = class
+                .id
+                .as_ref()
+                .map_or(class.body.span.start, |id| id.span.start);
+            self.strip_modifiers(class.span.start, scan_end);
+        }
+        if let Some(implements) = &class.implements
+            && let (Some(first), Some(last)) = (implements.first(), implements.last())
+        {
+            let before = &self.source[..first.span.start as usize];
+            if let Some(keyword) = before.rfind("implements") {
+                let start = before[..keyword].trim_end().len();
+                self.edits.remove(Span::new(start as u32, last.span.end));
+            }
+        }
+        walk::walk_class(self, class);
+    }
+
+    fn visit_ts_class_implements(&mut self, _implements: &TSClassImplements<'a>) {}
+
+    fn visit_class_element(&mut self, element: &ClassElement<'a>) {
+        let declared_field =
+            matches!(element, ClassElement::PropertyDefinition(property) if property.declare);
+        if element.is_typescript_syntax() || declared_field {
+            self.remove_statement(element.span());
+            return;
+        }
+        match element {
+            ClassElement::MethodDefinition(method) => {
+                self.strip_modifiers(
+                    Self::modifiers_start(method.span, &method.decorators),
+                    method.key.span().start,
+                );
+                if method.optional {
+                    self.strip_key_marker(&method.key, method.computed);
+                }
+                if method.kind == MethodDefinitionKind::Constructor {
+                    self.lower_parameter_properties(&method.value);
+                }
+            }
+            ClassElement::PropertyDefinition(property) => {
+                self.strip_modifiers(
+                    Self::modifiers_start(property.span, &property.decorators),
+                    property.key.span().start,
+                );
+                if property.optional || property.definite {
+                    self.strip_key_marker(&property.key, property.computed);
+                }
+            }
+            ClassElement::AccessorProperty(property) => {
+                self.strip_modifiers(
+                    Self::modifiers_start(property.span, &property.decorators),
+                    property.key.span().start,
+                );
+            }
+            ClassElement::StaticBlock(_) | ClassElement::TSIndexSignature(_) => {}
+        }
+        walk::walk_class_element(self, element);
+    }
+
+    fn visit_static_member_expression(&mut self, member: &StaticMemberExpression<'a>) {
+        if !self.inline_const_enum_member(&member.object, &member.property.name, member.span) {
+            walk::walk_static_member_expression(self, member);
+        }
+    }
+
+    fn visit_computed_member_expression(&mut self, member: &ComputedMemberExpression<'a>) {
+        let inlined = match &member.expression {
+            Expression::StringLiteral(key) => {
+                self.inline_const_enum_member(&member.object, &key.value, member.span)
+            }
+            _ => false,
+        };
+        if !inlined {
+            walk::walk_computed_member_expression(self, member);
+        }
+    }
+
+    fn visit_ts_as_expression(&mut self, expr: &TSAsExpression<'a>) {
+        self.edits
+            .remove(Span::new(expr.expression.span().end, expr.span.end));
+        self.visit_expression(&expr.expression);
+    }
+
+    fn visit_ts_satisfies_expression(&mut self, expr: &TSSatisfiesExpression<'a>) {
+        self.edits
+            .remove(Span::new(expr.expression.span().end, expr.span.end));
+        self.visit_expression(&expr.expression);
+    }
+
+    fn visit_ts_type_assertion(&mut self, expr: &TSTypeAssertion<'a>) {
+        self.edits
+            .remove(Span::new(expr.span.start, expr.expression.span().start));
+        self.visit_expression(&expr.expression);
+    }
+
+    fn visit_ts_non_null_expression(&mut self, expr: &TSNonNullExpression<'a>) {
+        self.edits
+            .remove(Span::new(expr.expression.span().end, expr.span.end));
+        self.visit_expression(&expr.expression);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::emit::transpile;
+    use crate::options::CompilerOptions;
+
+    fn emit(source: &str) -> String {
+        transpile(source, &CompilerOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_strips_annotations_and_type_syntax() {
+        assert_eq!(
+            emit(
+                "function f<T>(this: Window, a?: number, b: T = 1 as any): void { return g<string>(a!) satisfies unknown; }"
+            ),
+            "function f(a, b = 1) { return g(a); }"
+        );
+        assert_eq!(emit("let x!: number;"), "let x;");
+        assert_eq!(
+            emit("const f = <T,>(x: T): T => <T>x;"),
+            "const f = (x) => x;"
+        );
+        assert_eq!(
+            emit("try {} catch (e: unknown) {}\nlet { a }: { a: number } = o;"),
+            "try {} catch (e) {}\nlet { a } = o;"
+        );
+    }
+
+    #[test]
+    fn test_removes_type_only_declarations() {
+        let source = "interface A { x: number }\ntype B = string;\nexport type C = A;\ndeclare const d: number;\nfunction over(x: string): void;\nfunction over(x: any) {}\nlet kept = 1;\n";
+        assert_eq!(emit(source), "function over(x) {}\nlet kept = 1;\n");
+    }
+
+    #[test]
+    fn test_elides_type_only_imports_and_exports() {
+        let source = r#"import type { T } from "./t";
+import { type U, value, Unused } from "./v";
+import Only from "./types";
+import "./side-effect";
+interface Local {}
+const local = value;
+export { Local, local };
+export type { T };
+let x: U | Only | Unused;
+"#;
+        assert_eq!(
+            emit(source),
+            r#"import { value } from "./v";
+import "./side-effect";
+const local = value;
+export { local };
+let x;
+"#
+        );
+    }
+
+    #[test]
+    fn test_class_members() {
+        let source = r#"abstract class Base<T> extends Parent<T> implements I, J {
+    private readonly a?: number;
+    declare b: string;
+    public static c = 1;
+    [key: string]: any;
+    abstract run(): void;
+    protected override step?(n: number): void {}
+    constructor(public x: number, private readonly y = 2) {
+        super();
+    }
+}"#;
+        assert_eq!(
+            emit(source),
+            r#"class Base extends Parent {
+    a;
+    static c = 1;
+    step(n) {}
+    constructor(x, y = 2) {
+        super();
+        this.x = x;
+        this.y = y;
+    }
+}"#
+        );
+        assert_eq!(
+            emit("class P { constructor(readonly v: string) {} }"),
+            "class P { constructor(v) { this.v = v;} }"
+        );
+    }
+
+    #[test]
+    fn test_import_equals_and_export_assignment() {
+        assert_eq!(
+            emit("import fs = require(\"fs\");\nfs.readFileSync;\nexport = fs;"),
+            "const fs = require(\"fs\");\nfs.readFileSync;\nmodule.exports = fs;"
+        );
+    }
+}
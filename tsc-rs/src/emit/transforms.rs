@@ -0,0 +1,154 @@
+//! Custom transformers, which rewrite each file around the built-in emit steps the way
+//! `ts.CustomTransformers` does, so build tools can add their own code generation.
+//!
+//! Like the rest of emit, a transformer edits source text: it's given the parsed file and
+//! records replacements against its spans, which are applied before the next step parses
+//! the result again.
+
+use super::TextEdits;
+use crate::parser::parse_typescript_as;
+use oxc_ast::ast::Program;
+use oxc_span::{SourceType, Span};
+use std::fmt;
+use std::sync::Arc;
+
+/// A custom emit step, registered in [`CustomTransformers`].
+pub trait Transformer: Send + Sync {
+    /// Records the edits to make to `program`, the file in `context`.
+    fn transform(&self, program: &Program<'_>, context: &mut TransformContext<'_>);
+}
+
+/// Transformers to run on each emitted file, in order.
+#[derive(Clone, Default)]
+pub struct CustomTransformers {
+    /// Run on the TypeScript source, before types are stripped.
+    pub before: Vec<Arc<dyn Transformer>>,
+    /// Run on the JavaScript output, after every built-in step.
+    pub after: Vec<Arc<dyn Transformer>>,
+}
+
+impl fmt::Debug for CustomTransformers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomTransformers")
+            .field("before", &self.before.len())
+            .field("after", &self.after.len())
+            .finish()
+    }
+}
+
+/// The file a transformer is rewriting, and the edits it has made so far.
+pub struct TransformContext<'a> {
+    file_name: &'a str,
+    source: &'a str,
+    edits: TextEdits,
+}
+
+impl TransformContext<'_> {
+    pub fn file_name(&self) -> &str {
+        self.file_name
+    }
+
+    /// The text `program` was parsed from, which spans index into.
+    pub fn source(&self) -> &str {
+        self.source
+    }
+
+    pub fn replace(&mut self, span: Span, text: impl Into<String>) {
+        self.edits.replace(span, text);
+    }
+
+    pub fn remove(&mut self, span: Span) {
+        self.edits.remove(span);
+    }
+
+    pub fn insert(&mut self, at: u32, text: impl Into<String>) {
+        self.edits.insert(at, text);
+    }
+}
+
+/// Runs each of `transformers` over `source` in turn, parsing what the previous one left.
+pub(super) fn apply_transformers(
+    transformers: &[Arc<dyn Transformer>],
+    file_name: &str,
+    source: String,
+    source_type: SourceType,
+) -> Result<String, String> {
+    let mut text = source;
+    for transformer in transformers {
+        let ts_program = parse_typescript_as(&text, source_type)?;
+        let mut context = TransformContext {
+            file_name,
+            source: &text,
+            edits: TextEdits::default(),
+        };
+        transformer.transform(&ts_program.program, &mut context);
+        let edits = context.edits;
+        if !edits.is_empty() {
+            text = edits.apply(&text);
+        }
+    }
+    Ok(text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emit::transpile_file_with;
+    use crate::options::CompilerOptions;
+    use crate::testing::TestProject;
+    use oxc_ast::Visit;
+    use oxc_ast::ast::IdentifierReference;
+
+    /// Replaces references to `__DEBUG__` with `false`.
+    struct FoldDebugFlag;
+
+    impl Transformer for FoldDebugFlag {
+        fn transform(&self, program: &Program<'_>, context: &mut TransformContext<'_>) {
+            struct Flags<'c, 'a>(&'c mut TransformContext<'a>);
+            impl<'a> Visit<'a> for Flags<'_, '_> {
+                fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+                    if ident.name == "__DEBUG__" {
+                        self.0.replace(ident.span, "false");
+                    }
+                }
+            }
+            Flags(context).visit_program(program);
+        }
+    }
+
+    /// Adds a line to the top of each file.
+    struct Banner;
+
+    impl Transformer for Banner {
+        fn transform(&self, _program: &Program<'_>, context: &mut TransformContext<'_>) {
+            let banner = format!("// built from {}\n", context.file_name());
+            context.insert(0, banner);
+        }
+    }
+
+    #[test]
+    fn test_transformers_run_around_emit() {
+        let transformers = CustomTransformers {
+            before: vec![Arc::new(FoldDebugFlag)],
+            after: vec![Arc::new(Banner)],
+        };
+        let source = "declare const __DEBUG__: boolean;\nconst verbose: boolean = __DEBUG__;\n";
+        let output = transpile_file_with(
+            "input.ts",
+            source,
+            &CompilerOptions::default(),
+            &transformers,
+        )
+        .unwrap();
+        assert_eq!(output, "// built from input.ts\nconst verbose = false;\n");
+
+        let result = TestProject::new()
+            .file("/src/a.ts", "export const on = __DEBUG__;")
+            .program()
+            .emit_with_transformers(&transformers);
+        assert_eq!(
+            result.output_files[0].text,
+            "// built from /src/a.ts\nexport const on = false;"
+        );
+    }
+}
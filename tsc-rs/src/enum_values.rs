@@ -0,0 +1,108 @@
+//! Values of enum members, folded from their initializers the way TypeScript does, for
+//! checking enum types and inlining const enum members.
+
+use oxc_ast::ast::*;
+use oxc_syntax::operator::{BinaryOperator, UnaryOperator};
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum EnumValue {
+    Number(f64),
+    String(String),
+}
+
+/// Folds an initializer that only uses literals, arithmetic, and earlier members.
+fn evaluate(
+    expr: &Expression,
+    enum_name: &str,
+    members: &HashMap<String, EnumValue>,
+) -> Option<EnumValue> {
+    let number = |expr| match evaluate(expr, enum_name, members)? {
+        EnumValue::Number(n) => Some(n),
+        EnumValue::String(_) => None,
+    };
+    Some(match expr {
+        Expression::NumericLiteral(lit) => EnumValue::Number(lit.value),
+        Expression::StringLiteral(lit) => EnumValue::String(lit.value.to_string()),
+        Expression::TemplateLiteral(lit) if lit.expressions.is_empty() => EnumValue::String(
+            lit.quasis
+                .first()
+                .and_then(|quasi| quasi.value.cooked)
+                .map(|cooked| cooked.to_string())
+                .unwrap_or_default(),
+        ),
+        Expression::ParenthesizedExpression(paren) => {
+            evaluate(&paren.expression, enum_name, members)?
+        }
+        Expression::Identifier(ident) => members.get(ident.name.as_str())?.clone(),
+        Expression::StaticMemberExpression(member) if matches!(&member.object, Expression::Identifier(object) if object.name == enum_name) => {
+            members.get(member.property.name.as_str())?.clone()
+        }
+        Expression::UnaryExpression(unary) => {
+            let value = number(&unary.argument)?;
+            EnumValue::Number(match unary.operator {
+                UnaryOperator::UnaryNegation => -value,
+                UnaryOperator::UnaryPlus => value,
+                UnaryOperator::BitwiseNot => !(value as i32) as f64,
+                _ => return None,
+            })
+        }
+        Expression::BinaryExpression(binary) => {
+            let left = evaluate(&binary.left, enum_name, members)?;
+            let right = evaluate(&binary.right, enum_name, members)?;
+            match (left, right) {
+                (EnumValue::Number(l), EnumValue::Number(r)) => {
+                    EnumValue::Number(match binary.operator {
+                        BinaryOperator::Addition => l + r,
+                        BinaryOperator::Subtraction => l - r,
+                        BinaryOperator::Multiplication => l * r,
+                        BinaryOperator::Division => l / r,
+                        BinaryOperator::Remainder => l % r,
+                        BinaryOperator::Exponential => l.powf(r),
+                        BinaryOperator::BitwiseOR => ((l as i32) | (r as i32)) as f64,
+                        BinaryOperator::BitwiseAnd => ((l as i32) & (r as i32)) as f64,
+                        BinaryOperator::BitwiseXOR => ((l as i32) ^ (r as i32)) as f64,
+                        BinaryOperator::ShiftLeft => ((l as i32) << (r as u32 & 31)) as f64,
+                        BinaryOperator::ShiftRight => ((l as i32) >> (r as u32 & 31)) as f64,
+                        BinaryOperator::ShiftRightZeroFill => {
+                            ((l as i32 as u32) >> (r as u32 & 31)) as f64
+                        }
+                        _ => return None,
+                    })
+                }
+                (EnumValue::String(l), EnumValue::String(r))
+                    if binary.operator == BinaryOperator::Addition =>
+                {
+                    EnumValue::String(l + &r)
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    })
+}
+
+/// Each member's name and its value, or `None` when it's only known at runtime. Members
+/// without an initializer count up from the one before.
+pub(crate) fn member_values(decl: &TSEnumDeclaration) -> Vec<(String, Option<EnumValue>)> {
+    let name = decl.id.name.as_str();
+    let mut members = HashMap::new();
+    let mut next = Some(0.0);
+    let mut values = Vec::new();
+    for member in &decl.members {
+        let member_name = member.id.static_name().to_string();
+        let value = match &member.initializer {
+            Some(init) => evaluate(init, name, &members),
+            None => next.map(EnumValue::Number),
+        };
+        next = match &value {
+            Some(EnumValue::Number(n)) => Some(n + 1.0),
+            _ => None,
+        };
+        if let Some(value) = &value {
+            members.insert(member_name.clone(), value.clone());
+        }
+        values.push((member_name, value));
+    }
+    values
+}
@@ -0,0 +1,117 @@
+//! Why each file is part of a program, for `--listFiles` and `--explainFiles`.
+
+use crate::program::Program;
+use std::fmt;
+
+/// A reason a file is in a program.
+#[derive(Debug, Clone, PartialEq)]
+pub enum FileInclusion {
+    /// Named when the program was created.
+    Root,
+    /// Listed in the `files` of the project's `tsconfig.json`.
+    FilesList,
+    /// Matched by the pattern, as written in the `include` of the project's `tsconfig.json`.
+    IncludePattern(String),
+    /// Imported as `specifier` by the file `importer`.
+    Import { specifier: String, importer: String },
+}
+
+impl fmt::Display for FileInclusion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FileInclusion::Root => write!(f, "Root file specified for compilation"),
+            FileInclusion::FilesList => write!(f, "Part of 'files' list in tsconfig.json"),
+            FileInclusion::IncludePattern(pattern) => write!(
+                f,
+                "Matched by include pattern '{}' in tsconfig.json",
+                pattern
+            ),
+            FileInclusion::Import {
+                specifier,
+                importer,
+            } => write!(f, "Imported via \"{}\" from file '{}'", specifier, importer),
+        }
+    }
+}
+
+impl Program {
+    /// Every reason `file_name` is in the program, roots first and then each import of it
+    /// in the order the importing files were loaded.
+    pub fn file_inclusions(&self, file_name: &str) -> Vec<FileInclusion> {
+        let mut inclusions = Vec::new();
+        if self.root_names().iter().any(|root| root == file_name) {
+            inclusions.push(
+                self.root_inclusion(file_name)
+                    .cloned()
+                    .unwrap_or(FileInclusion::Root),
+            );
+        }
+        for file in self.files() {
+            for (specifier, resolved) in &file.resolved_modules {
+                if resolved == file_name {
+                    inclusions.push(FileInclusion::Import {
+                        specifier: specifier.clone(),
+                        importer: file.name.clone(),
+                    });
+                }
+            }
+        }
+        inclusions
+    }
+
+    /// Each file's name followed by why it's in the program, indented, the way
+    /// `tsc --explainFiles` prints them.
+    pub fn explain_files(&self) -> String {
+        let mut explanation = String::new();
+        for file in self.files() {
+            explanation.push_str(&file.name);
+            explanation.push('\n');
+            for inclusion in self.file_inclusions(&file.name) {
+                explanation.push_str(&format!("  {}\n", inclusion));
+            }
+        }
+        explanation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestProject;
+
+    #[test]
+    fn test_explain_files() {
+        let program = TestProject::new()
+            .file("/main.ts", "import { a } from \"./a\";\nimport \"./b\";")
+            .file("/a.ts", "import \"./b\";\nexport const a = 1;")
+            .file("/b.ts", "")
+            .roots(&["/main.ts", "/b.ts"])
+            .program();
+        assert_eq!(
+            program.file_inclusions("/b.ts"),
+            vec![
+                FileInclusion::Root,
+                FileInclusion::Import {
+                    specifier: "./b".to_string(),
+                    importer: "/main.ts".to_string(),
+                },
+                FileInclusion::Import {
+                    specifier: "./b".to_string(),
+                    importer: "/a.ts".to_string(),
+                },
+            ]
+        );
+        assert_eq!(
+            program.explain_files(),
+            "/main.ts
+  Root file specified for compilation
+/b.ts
+  Root file specified for compilation
+  Imported via \"./b\" from file '/main.ts'
+  Imported via \"./b\" from file '/a.ts'
+/a.ts
+  Imported via \"./a\" from file '/main.ts'
+"
+        );
+    }
+}
@@ -0,0 +1,347 @@
+//! The import graph between a program's files, for bundler analysis and cycle detection.
+
+use crate::parser::{parse_typescript_recovering, source_type_for};
+use crate::program::Program;
+use crate::resolver::collect_type_only_specifiers;
+use serde_json::{Value, json};
+use std::collections::HashMap;
+
+/// The files of a program and the imports between them.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ModuleGraph {
+    /// Every file, in the order the program loaded them.
+    pub files: Vec<String>,
+    pub edges: Vec<ModuleEdge>,
+}
+
+/// An import, re-export, or side-effect import of one file by another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ModuleEdge {
+    pub from: String,
+    pub to: String,
+    /// The specifier as written in `from`.
+    pub specifier: String,
+    /// Whether only types are imported, so the edge disappears from emitted JavaScript.
+    pub type_only: bool,
+}
+
+impl ModuleGraph {
+    /// `{ files, edges: [{ from, to, specifier, typeOnly }] }`.
+    pub fn to_json(&self) -> Value {
+        json!({
+            "files": self.files,
+            "edges": self
+                .edges
+                .iter()
+                .map(|edge| json!({
+                    "from": edge.from,
+                    "to": edge.to,
+                    "specifier": edge.specifier,
+                    "typeOnly": edge.type_only,
+                }))
+                .collect::<Vec<_>>(),
+        })
+    }
+
+    /// A Graphviz `digraph`, with type-only edges dashed.
+    pub fn to_dot(&self) -> String {
+        let quote = |name: &str| format!("\"{}\"", name.replace('\\', "\\\\").replace('"', "\\\""));
+        let mut dot = String::from("digraph modules {\n");
+        for file in &self.files {
+            dot.push_str(&format!("  {};\n", quote(file)));
+        }
+        for edge in &self.edges {
+            let style = match edge.type_only {
+                true => " [style=dashed]",
+                false => "",
+            };
+            dot.push_str(&format!(
+                "  {} -> {}{};\n",
+                quote(&edge.from),
+                quote(&edge.to),
+                style
+            ));
+        }
+        dot.push_str("}\n");
+        dot
+    }
+}
+
+/// Tarjan's algorithm over `imports`, an adjacency list of file indices: the groups of
+/// files that can each reach the others, in no particular order.
+fn strongly_connected(imports: &[Vec<usize>]) -> Vec<Vec<usize>> {
+    struct Search<'a> {
+        imports: &'a [Vec<usize>],
+        index: Vec<Option<usize>>,
+        low: Vec<usize>,
+        stack: Vec<usize>,
+        on_stack: Vec<bool>,
+        next: usize,
+        components: Vec<Vec<usize>>,
+    }
+
+    impl Search<'_> {
+        fn visit(&mut self, file: usize) {
+            self.index[file] = Some(self.next);
+            self.low[file] = self.next;
+            self.next += 1;
+            self.stack.push(file);
+            self.on_stack[file] = true;
+            for &dep in &self.imports[file] {
+                match self.index[dep] {
+                    None => {
+                        self.visit(dep);
+                        self.low[file] = self.low[file].min(self.low[dep]);
+                    }
+                    Some(index) if self.on_stack[dep] => {
+                        self.low[file] = self.low[file].min(index);
+                    }
+                    Some(_) => {}
+                }
+            }
+            if Some(self.low[file]) == self.index[file] {
+                let mut component = Vec::new();
+                while let Some(member) = self.stack.pop() {
+                    self.on_stack[member] = false;
+                    component.push(member);
+                    if member == file {
+                        break;
+                    }
+                }
+                self.components.push(component);
+            }
+        }
+    }
+
+    let mut search = Search {
+        imports,
+        index: vec![None; imports.len()],
+        low: vec![0; imports.len()],
+        stack: Vec::new(),
+        on_stack: vec![false; imports.len()],
+        next: 0,
+        components: Vec::new(),
+    };
+    for file in 0..imports.len() {
+        if search.index[file].is_none() {
+            search.visit(file);
+        }
+    }
+    search.components
+}
+
+/// A path from `start` back to itself through files in `members`.
+fn cycle_through(imports: &[Vec<usize>], members: &[usize], start: usize) -> Vec<usize> {
+    fn extend(
+        imports: &[Vec<usize>],
+        members: &[usize],
+        start: usize,
+        path: &mut Vec<usize>,
+        visited: &mut Vec<usize>,
+    ) -> bool {
+        let last = *path.last().unwrap_or(&start);
+        for &dep in &imports[last] {
+            if dep == start {
+                path.push(start);
+                return true;
+            }
+            if members.contains(&dep) && !visited.contains(&dep) {
+                visited.push(dep);
+                path.push(dep);
+                if extend(imports, members, start, path, visited) {
+                    return true;
+                }
+                path.pop();
+            }
+        }
+        false
+    }
+    let mut path = vec![start];
+    let mut visited = vec![start];
+    extend(imports, members, start, &mut path, &mut visited);
+    path
+}
+
+impl Program {
+    /// The file indices each file imports.
+    fn import_indices(&self) -> Vec<Vec<usize>> {
+        let indices: HashMap<&str, usize> = self
+            .files()
+            .iter()
+            .enumerate()
+            .map(|(i, file)| (file.name.as_str(), i))
+            .collect();
+        self.files()
+            .iter()
+            .map(|file| {
+                file.resolved_modules
+                    .iter()
+                    .filter_map(|(_, resolved)| indices.get(resolved.as_str()).copied())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Groups of files that import each other, directly or through other files.
+    fn cycle_groups(&self, imports: &[Vec<usize>]) -> Vec<Vec<usize>> {
+        let mut groups: Vec<Vec<usize>> = strongly_connected(imports)
+            .into_iter()
+            .filter(|group| group.len() > 1 || imports[group[0]].contains(&group[0]))
+            .map(|mut group| {
+                group.sort_unstable();
+                group
+            })
+            .collect();
+        groups.sort_unstable();
+        groups
+    }
+
+    /// Indices of the files that are part of an import cycle.
+    pub(crate) fn cyclic_files(&self) -> Vec<usize> {
+        self.cycle_groups(&self.import_indices())
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    /// One cycle through each group of files that import each other, as the file names
+    /// along it, starting and ending with the group's first-loaded file.
+    pub fn import_cycles(&self) -> Vec<Vec<String>> {
+        let imports = self.import_indices();
+        self.cycle_groups(&imports)
+            .into_iter()
+            .map(|group| {
+                cycle_through(&imports, &group, group[0])
+                    .into_iter()
+                    .map(|index| self.files()[index].name.clone())
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// The imports between this program's files that the loader resolved.
+    pub fn module_graph(&self) -> ModuleGraph {
+        let mut graph = ModuleGraph::default();
+        for file in self.files() {
+            graph.files.push(file.name.clone());
+            let ts_program = parse_typescript_recovering(&file.source, source_type_for(&file.name));
+            let type_only = collect_type_only_specifiers(&ts_program.program);
+            for (specifier, resolved) in &file.resolved_modules {
+                graph.edges.push(ModuleEdge {
+                    from: file.name.clone(),
+                    to: resolved.clone(),
+                    specifier: specifier.clone(),
+                    type_only: type_only.contains(specifier),
+                });
+            }
+        }
+        graph
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::diagnostics::DiagnosticCategory;
+    use crate::options::CompilerOptions;
+    use crate::testing::TestProject;
+    use serde_json::json;
+
+    #[test]
+    fn test_module_graph() {
+        let graph = TestProject::new()
+            .file(
+                "/src/main.ts",
+                "import { run } from './run';\nimport type { Config } from './config';",
+            )
+            .file(
+                "/src/run.ts",
+                "import './config';\nexport function run() {}",
+            )
+            .file("/src/config.ts", "export interface Config {}")
+            .roots(&["/src/main.ts"])
+            .program()
+            .module_graph();
+
+        assert_eq!(
+            graph.to_json(),
+            json!({
+                "files": ["/src/main.ts", "/src/run.ts", "/src/config.ts"],
+                "edges": [
+                    { "from": "/src/main.ts", "to": "/src/run.ts", "specifier": "./run", "typeOnly": false },
+                    { "from": "/src/main.ts", "to": "/src/config.ts", "specifier": "./config", "typeOnly": true },
+                    { "from": "/src/run.ts", "to": "/src/config.ts", "specifier": "./config", "typeOnly": false },
+                ],
+            })
+        );
+        assert_eq!(
+            graph.to_dot(),
+            concat!(
+                "digraph modules {\n",
+                "  \"/src/main.ts\";\n",
+                "  \"/src/run.ts\";\n",
+                "  \"/src/config.ts\";\n",
+                "  \"/src/main.ts\" -> \"/src/run.ts\";\n",
+                "  \"/src/main.ts\" -> \"/src/config.ts\" [style=dashed];\n",
+                "  \"/src/run.ts\" -> \"/src/config.ts\";\n",
+                "}\n",
+            )
+        );
+    }
+
+    #[test]
+    fn test_import_cycles() {
+        let project = TestProject::new()
+            .file("/a.ts", "import { b } from './b';\nexport const a: number = 1;\nexport const fromB: string = b;")
+            .file("/b.ts", "import { c } from './c';\nexport const b: string = c;")
+            .file("/c.ts", "import { a } from './a';\nexport const c = 'c';\nexport const n: number = a;")
+            .file("/d.ts", "import { a } from './a';\nimport './d';")
+            .roots(&["/a.ts", "/d.ts"]);
+        assert_eq!(
+            project.program().import_cycles(),
+            vec![
+                vec!["/a.ts", "/b.ts", "/c.ts", "/a.ts"],
+                vec!["/d.ts", "/d.ts"],
+            ]
+        );
+        assert!(project.check().is_empty());
+
+        let options = CompilerOptions {
+            report_circular_imports: true,
+            ..Default::default()
+        };
+        let diagnostics = project.options(options).check();
+        let warnings: Vec<String> = diagnostics.iter().map(|d| d.to_string()).collect();
+        assert_eq!(
+            warnings,
+            vec![
+                "/a.ts: warning: Circular import: /a.ts -> /b.ts -> /c.ts -> /a.ts.",
+                "/d.ts: warning: Circular import: /d.ts -> /d.ts.",
+            ]
+        );
+        assert!(
+            diagnostics
+                .iter()
+                .all(|d| d.category == DiagnosticCategory::Warning)
+        );
+    }
+
+    #[test]
+    fn test_types_resolve_through_cycles() {
+        let project = TestProject::new()
+            .file("/a.ts", "import { b } from './b';\nexport const a: number = 1;\nexport const wrong: number = b;")
+            .file("/b.ts", "import { a } from './a';\nexport const b: string = 'b';\nexport const fromA: string = a;")
+            .roots(&["/a.ts"]);
+        let diagnostics = project.check();
+        let messages: Vec<(&str, &str)> = diagnostics
+            .iter()
+            .map(|d| (d.file_name.as_str(), d.message.as_str()))
+            .collect();
+        assert_eq!(
+            messages,
+            vec![
+                ("/a.ts", "Type 'string' is not assignable to type 'number'"),
+                ("/b.ts", "Type 'number' is not assignable to type 'string'"),
+            ]
+        );
+    }
+}
@@ -0,0 +1,451 @@
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+/// Everything the resolver and program loader need from the outside world.
+///
+/// Implementations decide where files come from: the real disk, an in-memory map
+/// (tests, WASM), or editor buffers with unsaved changes.
+pub trait FileSystem {
+    fn read_file(&self, path: &Path) -> io::Result<String>;
+    fn file_exists(&self, path: &Path) -> bool;
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>>;
+    fn current_dir(&self) -> io::Result<PathBuf>;
+    /// Writes an emitted output, creating parent directories as needed.
+    fn write_file(&mut self, path: &Path, contents: &str) -> io::Result<()>;
+    /// Where `path` really is: with symbolic links followed, and each part cased the way the
+    /// file system stores it, which differs from how it's written on file systems that find
+    /// names whatever their case.
+    fn real_path(&self, path: &Path) -> PathBuf {
+        path.to_path_buf()
+    }
+}
+
+/// A [`FileSystem`] backed by `std::fs`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RealFileSystem;
+
+impl FileSystem for RealFileSystem {
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        std::fs::read_to_string(path)
+    }
+
+    fn file_exists(&self, path: &Path) -> bool {
+        path.is_file()
+    }
+
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let mut entries = std::fs::read_dir(path)?
+            .map(|entry| entry.map(|e| e.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        entries.sort();
+        Ok(entries)
+    }
+
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        std::env::current_dir()
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &str) -> io::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, contents)
+    }
+
+    /// The canonical path, with each part as the directory holding it lists it.
+    fn real_path(&self, path: &Path) -> PathBuf {
+        let canonical = std::fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let mut real = PathBuf::new();
+        for component in canonical.components() {
+            let Component::Normal(name) = component else {
+                real.push(component.as_os_str());
+                continue;
+            };
+            let dir = match real.as_os_str().is_empty() {
+                true => Path::new("."),
+                false => real.as_path(),
+            };
+            let listed = std::fs::read_dir(dir).ok().and_then(|entries| {
+                let names: Vec<_> = entries.flatten().map(|entry| entry.file_name()).collect();
+                match names.iter().any(|listed| listed == name) {
+                    true => None,
+                    false => names
+                        .into_iter()
+                        .find(|listed| listed.eq_ignore_ascii_case(name)),
+                }
+            });
+            real.push(listed.as_deref().unwrap_or(name));
+        }
+        real
+    }
+}
+
+/// A [`FileSystem`] holding file contents in memory, keyed by normalized absolute path.
+#[derive(Debug, Clone)]
+pub struct InMemoryFileSystem {
+    files: BTreeMap<PathBuf, String>,
+    current_dir: PathBuf,
+    // Whether files are found whatever case their names are written in
+    case_insensitive: bool,
+    // Symbolic links, by where they are, to what they point at
+    links: BTreeMap<PathBuf, PathBuf>,
+}
+
+impl InMemoryFileSystem {
+    pub fn new() -> Self {
+        Self {
+            files: BTreeMap::new(),
+            current_dir: PathBuf::from("/"),
+            case_insensitive: false,
+            links: BTreeMap::new(),
+        }
+    }
+
+    /// Finds files whatever case their names are written in, as macOS and Windows do.
+    pub fn case_insensitive(mut self) -> Self {
+        self.case_insensitive = true;
+        self
+    }
+
+    pub fn with_current_dir(mut self, dir: impl AsRef<Path>) -> Self {
+        self.current_dir = normalize_path(&Path::new("/").join(dir));
+        self
+    }
+
+    pub fn add_file(&mut self, path: impl AsRef<Path>, source: impl Into<String>) {
+        let path = normalize_path(&self.current_dir.join(path));
+        self.files.insert(path, source.into());
+    }
+
+    pub fn remove_file(&mut self, path: impl AsRef<Path>) -> Option<String> {
+        let path = normalize_path(&self.current_dir.join(path));
+        self.files.remove(&path)
+    }
+
+    /// Makes `link` a symbolic link to `target`, a file or directory, which is relative to
+    /// the directory `link` is in unless it's absolute.
+    pub fn add_symlink(&mut self, link: impl AsRef<Path>, target: impl AsRef<Path>) {
+        let link = self.absolute(link.as_ref());
+        let dir = link.parent().unwrap_or(Path::new("/"));
+        let target = normalize_path(&dir.join(target));
+        self.links.insert(link, target);
+    }
+
+    fn absolute(&self, path: &Path) -> PathBuf {
+        normalize_path(&self.current_dir.join(path))
+    }
+
+    /// `path` with every symbolic link along it followed.
+    fn follow_links(&self, mut path: PathBuf) -> PathBuf {
+        // Links to links are followed in turn, up to a limit that stops cycles
+        for _ in 0..40 {
+            let Some((link, target)) = self.links.iter().find(|(link, _)| path.starts_with(link))
+            else {
+                break;
+            };
+            let rest = path.strip_prefix(link).unwrap_or(Path::new(""));
+            path = normalize_path(&target.join(rest));
+        }
+        path
+    }
+
+    /// The path a file at `path` is stored under, if there is one.
+    fn stored(&self, path: &Path) -> Option<&PathBuf> {
+        let path = self.follow_links(self.absolute(path));
+        match self.files.get_key_value(&path) {
+            Some((stored, _)) => Some(stored),
+            None if self.case_insensitive => {
+                let path = path.to_string_lossy();
+                self.files
+                    .keys()
+                    .find(|stored| stored.to_string_lossy().eq_ignore_ascii_case(&path))
+            }
+            None => None,
+        }
+    }
+}
+
+impl Default for InMemoryFileSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl FileSystem for InMemoryFileSystem {
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        self.stored(path)
+            .map(|stored| self.files[stored].clone())
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::NotFound,
+                    format!("File '{}' not found", path.display()),
+                )
+            })
+    }
+
+    fn file_exists(&self, path: &Path) -> bool {
+        self.stored(path).is_some()
+    }
+
+    /// Entries of a linked directory are listed under the link.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let dir = self.absolute(path);
+        let target = self.follow_links(dir.clone());
+        let mut entries: Vec<PathBuf> = self
+            .files
+            .keys()
+            .filter_map(|file| {
+                let rest = file.strip_prefix(&target).ok()?;
+                let first = rest.components().next()?;
+                Some(dir.join(first))
+            })
+            .chain(
+                self.links
+                    .keys()
+                    .filter(|link| link.parent() == Some(target.as_path()))
+                    .map(|link| dir.join(link.file_name().unwrap_or_default())),
+            )
+            .collect();
+        entries.sort();
+        entries.dedup();
+        if entries.is_empty() {
+            return Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                format!("Directory '{}' not found", path.display()),
+            ));
+        }
+        Ok(entries)
+    }
+
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        Ok(self.current_dir.clone())
+    }
+
+    fn write_file(&mut self, path: &Path, contents: &str) -> io::Result<()> {
+        self.add_file(path, contents);
+        Ok(())
+    }
+
+    fn real_path(&self, path: &Path) -> PathBuf {
+        self.stored(path)
+            .cloned()
+            .unwrap_or_else(|| self.follow_links(self.absolute(path)))
+    }
+}
+
+/// Layers in-memory contents, such as unsaved editor buffers, over another [`FileSystem`].
+#[derive(Debug, Clone, Default)]
+pub struct OverlayFileSystem<F: FileSystem> {
+    base: F,
+    overlay: BTreeMap<PathBuf, String>,
+}
+
+impl<F: FileSystem> OverlayFileSystem<F> {
+    pub fn new(base: F) -> Self {
+        Self {
+            base,
+            overlay: BTreeMap::new(),
+        }
+    }
+
+    pub fn set_file(&mut self, path: impl AsRef<Path>, source: impl Into<String>) {
+        let path = self.absolute(path.as_ref());
+        self.overlay.insert(path, source.into());
+    }
+
+    pub fn remove_file(&mut self, path: impl AsRef<Path>) -> Option<String> {
+        self.overlay.remove(&self.absolute(path.as_ref()))
+    }
+
+    pub fn overlay_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.overlay.keys()
+    }
+
+    /// `path` as the overlay keys it: relative to the base's current directory.
+    fn absolute(&self, path: &Path) -> PathBuf {
+        let dir = self.base.current_dir().unwrap_or_default();
+        normalize_path(&dir.join(path))
+    }
+}
+
+impl<F: FileSystem> FileSystem for OverlayFileSystem<F> {
+    fn read_file(&self, path: &Path) -> io::Result<String> {
+        match self.overlay.get(&self.absolute(path)) {
+            Some(source) => Ok(source.clone()),
+            None => self.base.read_file(path),
+        }
+    }
+
+    fn file_exists(&self, path: &Path) -> bool {
+        self.overlay.contains_key(&self.absolute(path)) || self.base.file_exists(path)
+    }
+
+    /// Lists the base's entries with the overlay's, including the directories overlay files
+    /// are in that the base doesn't have yet.
+    fn read_dir(&self, path: &Path) -> io::Result<Vec<PathBuf>> {
+        let dir = self.absolute(path);
+        let overlaid = self.overlay.keys().filter_map(|file| {
+            let first = file.strip_prefix(&dir).ok()?.components().next()?;
+            Some(dir.join(first))
+        });
+        let mut entries: Vec<PathBuf> = overlaid.collect();
+        match self.base.read_dir(&dir) {
+            Ok(base) => entries.extend(base),
+            Err(err) if entries.is_empty() => return Err(err),
+            Err(_) => {}
+        }
+        entries.sort();
+        entries.dedup();
+        Ok(entries)
+    }
+
+    fn current_dir(&self) -> io::Result<PathBuf> {
+        self.base.current_dir()
+    }
+
+    /// Outputs go to the underlying file system, not the overlay.
+    fn write_file(&mut self, path: &Path, contents: &str) -> io::Result<()> {
+        self.base.write_file(path, contents)
+    }
+
+    fn real_path(&self, path: &Path) -> PathBuf {
+        let absolute = self.absolute(path);
+        match self.overlay.contains_key(&absolute) {
+            true => absolute,
+            false => self.base.real_path(path),
+        }
+    }
+}
+
+/// Collapses `.` and `..` components without touching the disk.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other.as_os_str()),
+        }
+    }
+    normalized
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_file_system() {
+        let mut fs = InMemoryFileSystem::new().with_current_dir("/project");
+        fs.add_file("src/index.ts", "export const x = 1;");
+        fs.add_file("src/util/math.ts", "export const y = 2;");
+
+        assert!(fs.file_exists(Path::new("/project/src/index.ts")));
+        assert!(fs.file_exists(Path::new("src/./util/../index.ts")));
+        assert!(!fs.file_exists(Path::new("src/missing.ts")));
+        assert_eq!(
+            fs.read_file(Path::new("src/index.ts")).unwrap(),
+            "export const x = 1;"
+        );
+        assert_eq!(
+            fs.read_file(Path::new("src/missing.ts"))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotFound
+        );
+        assert_eq!(
+            fs.read_dir(Path::new("src")).unwrap(),
+            vec![
+                PathBuf::from("/project/src/index.ts"),
+                PathBuf::from("/project/src/util")
+            ]
+        );
+        assert_eq!(fs.current_dir().unwrap(), PathBuf::from("/project"));
+    }
+
+    #[test]
+    fn test_in_memory_symlinks() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/store/pkg@1.0.0/index.d.ts", "export {};");
+        fs.add_symlink("/app/node_modules/pkg", "../../store/pkg@1.0.0");
+        fs.add_symlink("/app/lib", "/app/node_modules/pkg");
+
+        let linked = Path::new("/app/lib/index.d.ts");
+        assert!(fs.file_exists(linked));
+        assert_eq!(fs.read_file(linked).unwrap(), "export {};");
+        assert_eq!(
+            fs.real_path(linked),
+            PathBuf::from("/store/pkg@1.0.0/index.d.ts")
+        );
+        assert_eq!(
+            fs.read_dir(Path::new("/app/node_modules/pkg")).unwrap(),
+            vec![PathBuf::from("/app/node_modules/pkg/index.d.ts")]
+        );
+        assert_eq!(
+            fs.read_dir(Path::new("/app/node_modules")).unwrap(),
+            vec![PathBuf::from("/app/node_modules/pkg")]
+        );
+    }
+
+    #[test]
+    fn test_overlay_file_system() {
+        let mut base = InMemoryFileSystem::new();
+        base.add_file("/src/a.ts", "saved");
+        base.add_file("/src/b.ts", "untouched");
+        let mut fs = OverlayFileSystem::new(base);
+        fs.set_file("/src/a.ts", "unsaved");
+        fs.set_file("/src/new.ts", "created");
+
+        assert_eq!(fs.read_file(Path::new("/src/a.ts")).unwrap(), "unsaved");
+        assert_eq!(fs.read_file(Path::new("/src/b.ts")).unwrap(), "untouched");
+        assert!(fs.file_exists(Path::new("/src/new.ts")));
+        assert_eq!(fs.read_dir(Path::new("/src")).unwrap().len(), 3);
+
+        fs.remove_file("/src/a.ts");
+        assert_eq!(fs.read_file(Path::new("/src/a.ts")).unwrap(), "saved");
+    }
+
+    #[test]
+    fn test_overlay_directories_and_relative_paths() {
+        let mut base = InMemoryFileSystem::new().with_current_dir("/project");
+        base.add_file("src/index.ts", "saved");
+        let mut fs = OverlayFileSystem::new(base);
+        fs.set_file("src/util/math.ts", "created");
+        fs.set_file("/project/lib/deep/io.ts", "created");
+
+        assert_eq!(
+            fs.read_file(Path::new("/project/src/util/math.ts"))
+                .unwrap(),
+            "created"
+        );
+        assert!(fs.file_exists(Path::new("./src/util/../util/math.ts")));
+        assert_eq!(
+            fs.read_dir(Path::new("src")).unwrap(),
+            vec![
+                PathBuf::from("/project/src/index.ts"),
+                PathBuf::from("/project/src/util"),
+            ]
+        );
+        // Directories only the overlay has files in are listed, and can be listed
+        assert_eq!(
+            fs.read_dir(Path::new("/project")).unwrap(),
+            vec![PathBuf::from("/project/lib"), PathBuf::from("/project/src")]
+        );
+        assert_eq!(
+            fs.read_dir(Path::new("/project/lib")).unwrap(),
+            vec![PathBuf::from("/project/lib/deep")]
+        );
+        assert!(fs.read_dir(Path::new("/missing")).is_err());
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(
+            normalize_path(Path::new("/a/./b/../c.ts")),
+            PathBuf::from("/a/c.ts")
+        );
+    }
+}
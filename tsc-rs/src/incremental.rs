@@ -0,0 +1,104 @@
+//! Memoized per-file checking, so a program checked again after an edit only rechecks
+//! what the edit could have changed.
+//!
+//! Checking a file is treated as a query whose inputs are the file's source and the
+//! exports of each module it imports. A result is reused while those inputs are
+//! unchanged, and since a file's exports are compared rather than its source, editing a
+//! function body doesn't recheck the files importing it.
+//!
+//! Finer queries about a file, like the type of one of its symbols, are memoized with
+//! the file's result and depend on the same inputs: they're answered again only once the
+//! file has to be checked again.
+
+use crate::program::CheckedFile;
+use crate::symbols::ExportedSymbol;
+use crate::types::{Property, Type};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+/// What a file was checked against: its source and the exports it imported, by specifier.
+#[derive(Clone, PartialEq)]
+pub(crate) struct CheckInputs {
+    pub source: String,
+    pub imports: Vec<(String, Option<Vec<ExportedSymbol>>)>,
+}
+
+/// A question about one checked file, answered from its checker.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Query {
+    /// The type of the top-level declaration by this name.
+    TypeOfSymbol(String),
+    /// The members of the type of the expression or binding at this offset.
+    MembersOfType(u32),
+    /// The signature of the function or callee at this offset.
+    SignatureOfNode(u32),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum Answer {
+    Type(Option<Type>),
+    Members(Vec<Property>),
+}
+
+#[derive(Clone)]
+struct Memo {
+    inputs: CheckInputs,
+    checked: CheckedFile,
+    answers: HashMap<Query, Answer>,
+}
+
+/// Results of checking each file, shared between clones of a program.
+#[derive(Clone, Default)]
+pub(crate) struct CheckCache {
+    memos: Arc<Mutex<HashMap<String, Memo>>>,
+}
+
+impl CheckCache {
+    /// The result of checking `file_name` against `inputs`, if it was checked against
+    /// exactly those before.
+    pub fn get(&self, file_name: &str, inputs: &CheckInputs) -> Option<CheckedFile> {
+        let memos = self.memos.lock().unwrap_or_else(|e| e.into_inner());
+        let memo = memos.get(file_name)?;
+        (memo.inputs == *inputs).then(|| memo.checked.clone())
+    }
+
+    pub fn insert(&self, file_name: &str, inputs: CheckInputs, checked: &CheckedFile) {
+        let mut memos = self.memos.lock().unwrap_or_else(|e| e.into_inner());
+        memos.insert(
+            file_name.to_string(),
+            Memo {
+                inputs,
+                checked: checked.clone(),
+                answers: HashMap::new(),
+            },
+        );
+    }
+
+    /// The answer to `query` about `file_name` as last checked, computed by `answer` the
+    /// first time it's asked since then.
+    pub fn answer(
+        &self,
+        file_name: &str,
+        query: Query,
+        answer: impl FnOnce(&CheckedFile) -> Answer,
+    ) -> Option<Answer> {
+        let mut memos = self.memos.lock().unwrap_or_else(|e| e.into_inner());
+        let memo = memos.get_mut(file_name)?;
+        if let Some(answer) = memo.answers.get(&query) {
+            return Some(answer.clone());
+        }
+        let computed = answer(&memo.checked);
+        memo.answers.insert(query, computed.clone());
+        Some(computed)
+    }
+}
+
+impl fmt::Debug for CheckCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let memos = self.memos.lock().unwrap_or_else(|e| e.into_inner());
+        f.debug_struct("CheckCache")
+            .field("files", &memos.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -0,0 +1,174 @@
+//! Interned names, so the symbol tables and object types can copy and compare names
+//! without allocating.
+
+use std::borrow::{Borrow, Cow};
+use std::collections::HashSet;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::ops::Deref;
+use std::sync::{LazyLock, Mutex};
+
+// Every name interned so far; each is leaked once and lives for the rest of the process
+static NAMES: LazyLock<Mutex<HashSet<&'static str>>> = LazyLock::new(Default::default);
+
+/// An interned string. Two atoms for the same text share one allocation, so they compare
+/// by pointer; atoms hash like the text itself, so maps keyed by them can be looked up
+/// with a `&str`.
+#[derive(Clone, Copy)]
+pub struct Atom(&'static str);
+
+impl Atom {
+    pub fn new(text: &str) -> Self {
+        let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(&name) = names.get(text) {
+            return Atom(name);
+        }
+        let name: &'static str = Box::leak(text.into());
+        names.insert(name);
+        Atom(name)
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Deref for Atom {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl Borrow<str> for Atom {
+    fn borrow(&self) -> &str {
+        self.0
+    }
+}
+
+impl AsRef<str> for Atom {
+    fn as_ref(&self) -> &str {
+        self.0
+    }
+}
+
+impl PartialEq for Atom {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self.0, other.0)
+    }
+}
+
+impl Eq for Atom {}
+
+impl PartialEq<str> for Atom {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Atom {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Atom {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<Cow<'_, str>> for Atom {
+    fn eq(&self, other: &Cow<'_, str>) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<Atom> for &str {
+    fn eq(&self, other: &Atom) -> bool {
+        *self == other.0
+    }
+}
+
+impl Hash for Atom {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.0.hash(state)
+    }
+}
+
+impl PartialOrd for Atom {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Atom {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.cmp(other.0)
+    }
+}
+
+impl From<&str> for Atom {
+    fn from(text: &str) -> Self {
+        Atom::new(text)
+    }
+}
+
+impl From<&String> for Atom {
+    fn from(text: &String) -> Self {
+        Atom::new(text)
+    }
+}
+
+impl From<String> for Atom {
+    fn from(text: String) -> Self {
+        Atom::new(&text)
+    }
+}
+
+impl From<Cow<'_, str>> for Atom {
+    fn from(text: Cow<'_, str>) -> Self {
+        Atom::new(&text)
+    }
+}
+
+impl From<Atom> for String {
+    fn from(atom: Atom) -> Self {
+        atom.0.to_string()
+    }
+}
+
+impl fmt::Display for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+impl fmt::Debug for Atom {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self.0, f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_atoms_share_their_text() {
+        let name = String::from("count");
+        let a = Atom::new("count");
+        let b = Atom::from(name);
+        assert_eq!(a, b);
+        assert!(std::ptr::eq(a.as_str(), b.as_str()));
+        assert_ne!(a, Atom::new("total"));
+        assert_eq!(a, "count");
+        assert_eq!(a.len(), 5);
+
+        let mut map = HashMap::new();
+        map.insert(a, 1);
+        assert_eq!(map.get("count"), Some(&1));
+    }
+}
@@ -0,0 +1,347 @@
+//! Checks for code a single-file transpiler such as esbuild or swc can't emit correctly.
+//!
+//! Those tools see one file at a time, so they can't tell whether an imported name is a
+//! type that must be erased or what value an ambient const enum member stands for.
+
+use crate::diagnostics::{Diagnostic, Message};
+use crate::parser::is_declaration_file;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+use std::collections::{HashMap, HashSet};
+
+/// What importers of a module need to know about its exports under `isolatedModules`.
+#[derive(Debug, Clone, Default)]
+pub struct ModuleShape {
+    /// Exported names that only exist as types.
+    types: HashSet<String>,
+    /// Exported const enums that have no emitted object to read members from.
+    ambient_const_enums: HashSet<String>,
+}
+
+/// Collects the type-only exports and ambient const enums of a parsed file.
+pub fn module_shape(file_name: &str, program: &Program) -> ModuleShape {
+    let locals = Locals::collect(file_name, program);
+    let mut shape = ModuleShape::default();
+    let export = |shape: &mut ModuleShape, local: &str, exported: String| {
+        if locals.ambient_const_enums.contains(local) {
+            shape.ambient_const_enums.insert(exported);
+        } else if locals.types.contains(local) {
+            shape.types.insert(exported);
+        }
+    };
+
+    for stmt in &program.body {
+        let Statement::ExportNamedDeclaration(decl) = stmt else {
+            continue;
+        };
+        if let Some(declaration) = &decl.declaration {
+            if let Some(name) = declared_name(declaration) {
+                export(&mut shape, name, name.to_string());
+            }
+            continue;
+        }
+        if decl.source.is_some() {
+            continue;
+        }
+        for specifier in &decl.specifiers {
+            let exported = specifier.exported.name().to_string();
+            if decl.export_kind.is_type() || specifier.export_kind.is_type() {
+                shape.types.insert(exported);
+            } else {
+                export(&mut shape, &specifier.local.name(), exported);
+            }
+        }
+    }
+    shape
+}
+
+/// Reports every construct in `program` that can't be compiled one file at a time.
+///
+/// `imports` maps each specifier the file imports to the shape of the module it resolved to.
+pub fn check_isolated_modules(
+    file_name: &str,
+    program: &Program,
+    imports: &HashMap<&str, &ModuleShape>,
+) -> Vec<Diagnostic> {
+    if is_declaration_file(file_name) {
+        return Vec::new();
+    }
+    let mut diagnostics = Vec::new();
+    if !is_module(program) {
+        diagnostics.push(Diagnostic::from_message(
+            file_name,
+            Message::new(1208, &[&file_name]),
+            None,
+        ));
+    }
+
+    let mut locals = Locals::collect(file_name, program);
+    for stmt in &program.body {
+        let Statement::ImportDeclaration(decl) = stmt else {
+            continue;
+        };
+        let Some(shape) = imports.get(decl.source.value.as_str()) else {
+            continue;
+        };
+        for specifier in decl.specifiers.iter().flatten() {
+            let ImportDeclarationSpecifier::ImportSpecifier(import) = specifier else {
+                continue;
+            };
+            let imported = import.imported.name();
+            let local = import.local.name.to_string();
+            if decl.import_kind.is_type() || import.import_kind.is_type() {
+                locals.imported_types.insert(local);
+            } else if shape.ambient_const_enums.contains(imported.as_str()) {
+                locals.ambient_const_enums.insert(local);
+            } else if shape.types.contains(imported.as_str()) {
+                locals.imported_types.insert(local);
+            }
+        }
+    }
+
+    for stmt in &program.body {
+        let Statement::ExportNamedDeclaration(decl) = stmt else {
+            continue;
+        };
+        if decl.export_kind.is_type() {
+            continue;
+        }
+        let shape = decl
+            .source
+            .as_ref()
+            .and_then(|source| imports.get(source.value.as_str()));
+        for specifier in decl.specifiers.iter().filter(|s| !s.export_kind.is_type()) {
+            let local = specifier.local.name();
+            let is_type = match (&decl.source, shape) {
+                (Some(_), Some(shape)) => shape.types.contains(local.as_str()),
+                (Some(_), None) => false,
+                (None, _) => locals.imported_types.contains(local.as_str()),
+            };
+            if is_type {
+                diagnostics.push(Diagnostic::from_message(
+                    file_name,
+                    Message::new(1205, &[]),
+                    Some(specifier.span),
+                ));
+            }
+        }
+    }
+
+    let mut accesses = ConstEnumAccesses {
+        file_name,
+        ambient_const_enums: &locals.ambient_const_enums,
+        diagnostics: &mut diagnostics,
+    };
+    accesses.visit_program(program);
+    diagnostics
+}
+
+/// Whether `program` imports or exports anything, making it a module rather than a script
+/// whose declarations are global.
+pub(crate) fn is_module(program: &Program) -> bool {
+    program.body.iter().any(|stmt| {
+        stmt.is_module_declaration()
+            || matches!(
+                stmt,
+                Statement::TSImportEqualsDeclaration(decl)
+                    if matches!(decl.module_reference, TSModuleReference::ExternalModuleReference(_))
+            )
+    })
+}
+
+fn declared_name<'d>(declaration: &'d Declaration) -> Option<&'d str> {
+    match declaration {
+        Declaration::TSInterfaceDeclaration(decl) => Some(decl.id.name.as_str()),
+        Declaration::TSTypeAliasDeclaration(decl) => Some(decl.id.name.as_str()),
+        Declaration::TSEnumDeclaration(decl) => Some(decl.id.name.as_str()),
+        _ => None,
+    }
+}
+
+/// Top-level names declared in a file that don't produce a runtime value.
+#[derive(Default)]
+struct Locals {
+    types: HashSet<String>,
+    imported_types: HashSet<String>,
+    ambient_const_enums: HashSet<String>,
+}
+
+impl Locals {
+    fn collect(file_name: &str, program: &Program) -> Self {
+        let ambient = is_declaration_file(file_name);
+        let mut locals = Self::default();
+        for stmt in &program.body {
+            let declaration = match stmt {
+                Statement::ExportNamedDeclaration(decl) => decl.declaration.as_ref(),
+                stmt => stmt.as_declaration(),
+            };
+            match declaration {
+                Some(Declaration::TSEnumDeclaration(decl))
+                    if decl.r#const && (decl.declare || ambient) =>
+                {
+                    locals.ambient_const_enums.insert(decl.id.name.to_string());
+                }
+                Some(Declaration::TSInterfaceDeclaration(decl)) => {
+                    locals.types.insert(decl.id.name.to_string());
+                }
+                Some(Declaration::TSTypeAliasDeclaration(decl)) => {
+                    locals.types.insert(decl.id.name.to_string());
+                }
+                _ => {}
+            }
+        }
+        locals
+    }
+}
+
+struct ConstEnumAccesses<'c> {
+    file_name: &'c str,
+    ambient_const_enums: &'c HashSet<String>,
+    diagnostics: &'c mut Vec<Diagnostic>,
+}
+
+impl<'a> Visit<'a> for ConstEnumAccesses<'_> {
+    fn visit_identifier_reference(&mut self, ident: &IdentifierReference<'a>) {
+        if self.ambient_const_enums.contains(ident.name.as_str()) {
+            self.diagnostics.push(Diagnostic::from_message(
+                self.file_name,
+                Message::new(2748, &[]),
+                Some(ident.span()),
+            ));
+        }
+    }
+
+    // Type positions are erased, so naming the enum there is fine
+    fn visit_ts_type(&mut self, _ty: &TSType<'a>) {}
+
+    fn visit_export_named_declaration(&mut self, decl: &ExportNamedDeclaration<'a>) {
+        // `export { E }` re-exports the declaration rather than reading a member
+        if let Some(declaration) = &decl.declaration {
+            self.visit_declaration(declaration);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::CompilerOptions;
+    use crate::parser::parse_typescript;
+    use crate::program;
+
+    /// Checks the first file, resolving `"./name"` to the file named `/name.ts` or `/name.d.ts`.
+    fn check(files: &[(&str, &str)]) -> Vec<String> {
+        let parsed: Vec<_> = files
+            .iter()
+            .map(|(name, source)| (*name, parse_typescript(source).unwrap()))
+            .collect();
+        let shapes: Vec<(String, ModuleShape)> = parsed
+            .iter()
+            .map(|(name, ts_program)| {
+                let specifier = name
+                    .trim_end_matches(".ts")
+                    .trim_end_matches(".d")
+                    .replacen('/', "./", 1);
+                (specifier, module_shape(name, &ts_program.program))
+            })
+            .collect();
+        let imports: HashMap<&str, &ModuleShape> = shapes
+            .iter()
+            .map(|(specifier, shape)| (specifier.as_str(), shape))
+            .collect();
+        let (name, ts_program) = &parsed[0];
+        check_isolated_modules(name, &ts_program.program, &imports)
+            .into_iter()
+            .map(|d| d.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_global_script_files() {
+        assert_eq!(
+            check(&[("/a.ts", "let x = 1;")]),
+            vec![
+                "'/a.ts' cannot be compiled under '--isolatedModules' because it is considered a global script file. Add an import, export, or an empty 'export {}' statement to make it a module."
+            ]
+        );
+        assert!(check(&[("/a.ts", "let x = 1;\nexport {};")]).is_empty());
+        assert!(check(&[("/a.ts", "import fs = require('fs');")]).is_empty());
+        assert!(check(&[("/a.d.ts", "declare let x: number;")]).is_empty());
+    }
+
+    #[test]
+    fn test_type_re_exports_need_export_type() {
+        let files = [
+            (
+                "/index.ts",
+                r#"
+                import { Shape, Kind, area } from "./shapes";
+                export { Shape, area } from "./shapes";
+                export type { Shape as S } from "./shapes";
+                export { type Kind as K };
+                export { Kind };
+                "#,
+            ),
+            (
+                "/shapes.ts",
+                r#"
+                export interface Shape { size: number }
+                type Kind = "circle" | "square";
+                export { Kind };
+                export function area(shape: Shape): number { return shape.size; }
+                "#,
+            ),
+        ];
+        assert_eq!(
+            check(&files),
+            vec![
+                "Re-exporting a type when 'isolatedModules' is enabled requires using 'export type'.",
+                "Re-exporting a type when 'isolatedModules' is enabled requires using 'export type'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_ambient_const_enum_access() {
+        let files = [
+            (
+                "/index.ts",
+                r#"
+                import { Color } from "./colors";
+                declare const enum Size { Small, Large }
+                const enum Local { A }
+                let c: Color = Color.Red;
+                let s = Size.Small;
+                let l = Local.A;
+                "#,
+            ),
+            ("/colors.d.ts", "export const enum Color { Red, Green }"),
+        ];
+        assert_eq!(
+            check(&files),
+            vec![
+                "Cannot access ambient const enums when 'isolatedModules' is enabled.",
+                "Cannot access ambient const enums when 'isolatedModules' is enabled.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_program_checks_only_when_enabled() {
+        let source = "declare const enum E { A }\nlet a = E.A;";
+        let mut program = program::Program::new(CompilerOptions::default());
+        program.add_file("a.ts", source);
+        assert!(program.check().is_empty());
+
+        let mut program = program::Program::new(CompilerOptions {
+            isolated_modules: true,
+            ..Default::default()
+        });
+        program.add_file("a.ts", source);
+        let messages: Vec<String> = program.check().into_iter().map(|d| d.message).collect();
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].contains("global script file"));
+        assert!(messages[1].starts_with("Cannot access ambient const enums"));
+    }
+}
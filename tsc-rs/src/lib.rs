@@ -1,3 +1,144 @@
+pub mod api;
+pub mod builtins;
+pub mod code_fixes;
+pub mod completion;
+pub mod config;
+pub mod conformance;
+pub mod diagnostics;
+pub mod emit;
+mod enum_values;
+pub mod file_inclusion;
+pub mod graph;
+pub mod host;
+mod incremental;
+pub mod intern;
+pub mod isolated_modules;
+pub mod line_index;
+pub mod locale;
+pub mod lsp;
+pub mod navigation;
+pub mod options;
+pub mod outline;
 pub mod parser;
+pub mod performance;
+pub mod pnp;
+pub mod program;
+pub mod query;
+pub mod quick_info;
+pub mod resolver;
+pub mod rules;
+pub mod semantic_tokens;
+pub mod signature_help;
+mod stack;
+pub mod symbols;
+pub mod testing;
+pub mod trace;
 pub mod type_checker;
+pub mod type_printer;
 pub mod types;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+pub use diagnostics::{Diagnostic, DiagnosticCategory};
+pub use emit::{CustomTransformers, EmitResult, OutputFile, Transformer};
+pub use host::{FileSystem, InMemoryFileSystem, RealFileSystem};
+pub use intern::Atom;
+pub use options::{CompilerOptions, DiagnosticSeverity, ModuleKind, ScriptTarget};
+pub use parser::TypeScriptProgram;
+pub use performance::PerformanceStats;
+pub use program::Program;
+pub use rules::{Rule, RuleContext};
+pub use trace::Trace;
+
+/// Parses TypeScript source into an AST without type checking it.
+pub fn parse(source: &str) -> Result<TypeScriptProgram, Diagnostic> {
+    parser::parse_typescript(source).map_err(|message| Diagnostic::new("input.ts", message))
+}
+
+/// Converts a single TypeScript source string to JavaScript for `options.target`.
+pub fn transpile(source: &str, options: &CompilerOptions) -> Result<String, Diagnostic> {
+    emit::transpile(source, options).map_err(|message| Diagnostic::new("input.ts", message))
+}
+
+/// Type checks a single TypeScript source string and returns every diagnostic found.
+pub fn check(source: &str, options: &CompilerOptions) -> Vec<Diagnostic> {
+    let mut program = Program::new(options.clone());
+    program.add_file("input.ts", source);
+    program.check()
+}
+
+/// The type inferred for a single expression, checked as a file of its own.
+///
+/// Fails with the first syntax error; type errors don't stop the expression having a type.
+pub fn type_of_expression(
+    expression: &str,
+    options: &CompilerOptions,
+) -> Result<String, Diagnostic> {
+    // The newline ends any trailing line comment before the closing parenthesis
+    let source = format!("({}\n);", expression);
+    parse(&source)?;
+    let mut program = Program::new(options.clone());
+    program.add_file("input.ts", source);
+    let checker = program
+        .check_file("input.ts")
+        .ok_or_else(|| Diagnostic::new("input.ts", "Expression could not be checked."))?;
+    // The parenthesized expression starts the file and covers everything else typed in it
+    Ok(checker
+        .node_types()
+        .iter()
+        .filter(|(span, _)| span.start == 0)
+        .max_by_key(|(span, _)| span.size())
+        .map_or_else(|| "any".to_string(), |(_, ty)| ty.to_string()))
+}
+
+/// Lists each declaration in a file with its inferred type, one per line, prefixed with
+/// its one-based `line:column`. Nested declarations are included when `nested` is set.
+pub fn explain(file_name: &str, source: &str, options: &CompilerOptions, nested: bool) -> String {
+    let mut program = Program::new(options.clone());
+    program.add_file(file_name, source);
+    let Some(checker) = program.check_file(file_name) else {
+        return String::new();
+    };
+    let lines = line_index::LineIndex::new(source);
+    checker
+        .declarations(nested)
+        .into_iter()
+        .map(|symbol| {
+            let (line, column) = lines.line_col(symbol.span.start);
+            format!("{}:{} {}\n", line + 1, column + 1, symbol)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_type_of_expression() {
+        let options = CompilerOptions::default();
+        assert_eq!(type_of_expression("1 + 2", &options).unwrap(), "number");
+        assert_eq!(
+            type_of_expression("[1, 2].map(n => n > 1) // flags", &options).unwrap(),
+            "boolean[]"
+        );
+        assert_eq!(
+            type_of_expression("{ a: 1, b: 'x' }", &options).unwrap(),
+            "{ a: number; b: string; }"
+        );
+        assert!(type_of_expression("1 +", &options).is_err());
+    }
+
+    #[test]
+    fn test_explain() {
+        let source = "const items = [1, 2];\nfunction first(list: number[]): number {\n  const head = list[0];\n  return head;\n}\n";
+        let options = CompilerOptions::default();
+        assert_eq!(
+            explain("input.ts", source, &options, false),
+            "1:7 (variable) items: number[]\n2:10 (function) first: (number[]) => number\n"
+        );
+        assert!(
+            explain("input.ts", source, &options, true).contains("3:9 (variable) head: number\n")
+        );
+    }
+}
@@ -0,0 +1,69 @@
+/// Converts between byte offsets and zero-based line/column positions.
+///
+/// Columns are counted in UTF-16 code units, which is what editors and LSP use.
+#[derive(Debug, Clone)]
+pub struct LineIndex {
+    line_starts: Vec<u32>,
+    source: String,
+}
+
+impl LineIndex {
+    pub fn new(source: &str) -> Self {
+        let line_starts = std::iter::once(0)
+            .chain(source.match_indices('\n').map(|(i, _)| i as u32 + 1))
+            .collect();
+        Self {
+            line_starts,
+            source: source.to_string(),
+        }
+    }
+
+    pub fn line_col(&self, offset: u32) -> (u32, u32) {
+        let offset = offset.min(self.source.len() as u32);
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line] as usize;
+        let column = self.source[line_start..offset as usize]
+            .chars()
+            .map(|c| c.len_utf16() as u32)
+            .sum();
+        (line as u32, column)
+    }
+
+    pub fn offset(&self, line: u32, column: u32) -> u32 {
+        let Some(&line_start) = self.line_starts.get(line as usize) else {
+            return self.source.len() as u32;
+        };
+        let mut remaining = column;
+        let mut offset = line_start as usize;
+        for c in self.source[offset..].chars() {
+            if remaining == 0 || c == '\n' {
+                break;
+            }
+            remaining = remaining.saturating_sub(c.len_utf16() as u32);
+            offset += c.len_utf8();
+        }
+        offset as u32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_index_round_trip() {
+        let source = "let a = 1;\nlet é = \"😀\";\n";
+        let index = LineIndex::new(source);
+
+        assert_eq!(index.line_col(0), (0, 0));
+        assert_eq!(index.line_col(11), (1, 0));
+        let quote = source.find('😀').unwrap() as u32;
+        assert_eq!(index.line_col(quote), (1, 9));
+        assert_eq!(index.line_col(quote + 4), (1, 11));
+
+        assert_eq!(index.offset(1, 9), quote);
+        assert_eq!(index.offset(1, 11), quote + 4);
+        assert_eq!(index.offset(0, 100), 10);
+        assert_eq!(index.offset(9, 0), source.len() as u32);
+    }
+}
@@ -0,0 +1,139 @@
+//! Translated diagnostic messages, read from catalogs laid out the way TypeScript ships its
+//! own: `<locale>/diagnosticMessages.generated.json`, like `tsc --locale`.
+
+use crate::diagnostics::{Diagnostic, format_message};
+use crate::host::FileSystem;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Translations of the messages in [`MESSAGES`](crate::diagnostics::MESSAGES), keyed by
+/// code, with the same numbered placeholders.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MessageCatalog {
+    messages: HashMap<u32, String>,
+}
+
+impl MessageCatalog {
+    /// Reads a catalog in TypeScript's format: a JSON object whose keys end in the code of
+    /// the message they translate, like `"Cannot_find_name_0_2304": "..."`.
+    pub fn parse(json: &str) -> Result<Self, String> {
+        let entries: HashMap<String, String> =
+            serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let messages = entries
+            .into_iter()
+            .filter_map(|(key, message)| {
+                let (_, code) = key.rsplit_once('_')?;
+                Some((code.parse().ok()?, message))
+            })
+            .collect();
+        Ok(Self { messages })
+    }
+
+    /// Reads the catalog for `locale`, like `ja` or `pt-br`, from `dir`. A locale with a
+    /// territory falls back to its language's catalog, and English needs none.
+    pub fn load(locale: &str, dir: &Path, fs: &dyn FileSystem) -> Result<Option<Self>, String> {
+        let (language, territory) = match locale.split_once(['-', '_']) {
+            Some((language, territory)) => (language, Some(territory)),
+            None => (locale, None),
+        };
+        let valid = |part: &str| !part.is_empty() && part.chars().all(|c| c.is_ascii_alphabetic());
+        if !valid(language) || territory.is_some_and(|territory| !valid(territory)) {
+            return Err(
+                "Locale must be of the form <language> or <language>-<territory>. \
+                 For example 'en' or 'ja-jp'."
+                    .to_string(),
+            );
+        }
+        let language = language.to_ascii_lowercase();
+        if language == "en" {
+            return Ok(None);
+        }
+        let mut names = Vec::new();
+        if let Some(territory) = territory {
+            names.push(format!("{}-{}", language, territory.to_ascii_lowercase()));
+        }
+        names.push(language);
+        for name in names {
+            let path = dir.join(name).join("diagnosticMessages.generated.json");
+            if let Ok(json) = fs.read_file(&path) {
+                return Self::parse(&json)
+                    .map(Some)
+                    .map_err(|err| format!("Could not read '{}': {}", path.display(), err));
+            }
+        }
+        Err(format!("Unsupported locale '{}'.", locale))
+    }
+
+    /// The translation of the message reported under `code`.
+    pub fn message(&self, code: u32) -> Option<&str> {
+        self.messages.get(&code).map(String::as_str)
+    }
+
+    /// The diagnostic with its message translated, when the catalog translates it.
+    pub fn localize(&self, diagnostic: Diagnostic) -> Diagnostic {
+        let translated = diagnostic
+            .code
+            .and_then(|code| Some(format_message(self.message(code)?, &diagnostic.arguments)));
+        match translated {
+            Some(message) => Diagnostic {
+                message,
+                ..diagnostic
+            },
+            None => diagnostic,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::diagnostics::Message;
+    use crate::host::InMemoryFileSystem;
+
+    const GERMAN: &str = r#"{
+        "Type_0_is_not_assignable_to_type_1_2322": "Der Typ \"{0}\" kann dem Typ \"{1}\" nicht zugewiesen werden.",
+        "Expected_0_arguments_but_got_1_2554": "Es wurden {1} Argumente empfangen, aber {0} erwartet.",
+        "Not_a_message": "Ignored"
+    }"#;
+
+    #[test]
+    fn test_localize() {
+        let catalog = MessageCatalog::parse(GERMAN).unwrap();
+        let localize = |message: Message| {
+            catalog
+                .localize(Diagnostic::from_message("a.ts", message, None))
+                .message
+        };
+        assert_eq!(
+            localize(Message::new(2322, &[&"string", &"number"])),
+            "Der Typ \"string\" kann dem Typ \"number\" nicht zugewiesen werden."
+        );
+        assert_eq!(
+            localize(Message::new(2554, &[&2, &3])),
+            "Es wurden 3 Argumente empfangen, aber 2 erwartet."
+        );
+        assert_eq!(
+            localize(Message::new(2304, &[&"x"])),
+            "Cannot find name 'x'."
+        );
+        let uncoded = Diagnostic::new("a.ts", "Circular import: a.ts -> a.ts.");
+        assert_eq!(catalog.localize(uncoded.clone()), uncoded);
+    }
+
+    #[test]
+    fn test_load() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/locales/de/diagnosticMessages.generated.json", GERMAN);
+        let dir = Path::new("/locales");
+
+        let catalog = MessageCatalog::load("de-AT", dir, &fs).unwrap().unwrap();
+        assert!(catalog.message(2322).is_some());
+        assert!(MessageCatalog::load("DE", dir, &fs).unwrap().is_some());
+        assert_eq!(MessageCatalog::load("en-us", dir, &fs), Ok(None));
+        assert_eq!(
+            MessageCatalog::load("fr", dir, &fs),
+            Err("Unsupported locale 'fr'.".to_string())
+        );
+        assert!(MessageCatalog::load("de/../fr", dir, &fs).is_err());
+    }
+}
@@ -0,0 +1,994 @@
+// A minimal Language Server Protocol front end over stdio, backed by `Program`
+use crate::code_fixes::CodeFix;
+use crate::completion::CompletionKind;
+use crate::diagnostics::{Diagnostic, DiagnosticCategory};
+use crate::host::{FileSystem, OverlayFileSystem, RealFileSystem};
+use crate::line_index::LineIndex;
+use crate::options::CompilerOptions;
+use crate::outline::{OutlineItem, OutlineKind};
+use crate::program::Program;
+use crate::semantic_tokens::{TokenModifiers, TokenType};
+use crate::symbols::Location;
+use oxc_span::Span;
+use serde_json::{Value, json};
+use std::collections::BTreeSet;
+use std::io::{self, BufRead, Write};
+use std::path::PathBuf;
+
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const REQUEST_FAILED: i64 = -32803;
+
+/// Reads one `Content-Length` framed message, or `None` at end of input.
+pub fn read_message(reader: &mut impl BufRead) -> io::Result<Option<Value>> {
+    let mut content_length = None;
+    loop {
+        let mut line = String::new();
+        if reader.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':')
+            && name.eq_ignore_ascii_case("Content-Length")
+        {
+            content_length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let Some(length) = content_length else {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "missing Content-Length header",
+        ));
+    };
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+    serde_json::from_slice(&body)
+        .map(Some)
+        .map_err(io::Error::from)
+}
+
+pub fn write_message(writer: &mut impl Write, message: &Value) -> io::Result<()> {
+    let body = message.to_string();
+    write!(writer, "Content-Length: {}\r\n\r\n{}", body.len(), body)?;
+    writer.flush()
+}
+
+fn uri_to_path(uri: &str) -> PathBuf {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+    let mut decoded = Vec::with_capacity(path.len());
+    let bytes = path.as_bytes();
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%'
+            && let Some(byte) = path
+                .get(i + 1..i + 3)
+                .and_then(|hex| u8::from_str_radix(hex, 16).ok())
+        {
+            decoded.push(byte);
+            i += 3;
+            continue;
+        }
+        decoded.push(bytes[i]);
+        i += 1;
+    }
+    PathBuf::from(String::from_utf8_lossy(&decoded).into_owned())
+}
+
+fn path_to_uri(path: &str) -> String {
+    let mut uri = String::from("file://");
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'/' | b'-' | b'_' | b'.' | b'~' => {
+                uri.push(byte as char)
+            }
+            _ => uri.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    uri
+}
+
+fn symbol_kind(kind: OutlineKind) -> u32 {
+    match kind {
+        OutlineKind::Namespace => 3,
+        OutlineKind::Class => 5,
+        OutlineKind::Method => 6,
+        OutlineKind::Property => 7,
+        OutlineKind::Constructor => 9,
+        OutlineKind::Enum => 10,
+        OutlineKind::Interface => 11,
+        OutlineKind::Function => 12,
+        OutlineKind::Variable => 13,
+        OutlineKind::Constant => 14,
+        OutlineKind::EnumMember => 22,
+        OutlineKind::TypeAlias => 26,
+    }
+}
+
+fn completion_item_kind(kind: CompletionKind) -> u32 {
+    match kind {
+        CompletionKind::Method => 2,
+        CompletionKind::Function => 3,
+        CompletionKind::Variable | CompletionKind::Parameter | CompletionKind::Alias => 6,
+        CompletionKind::Class => 7,
+        CompletionKind::Namespace => 9,
+        CompletionKind::Enum => 13,
+        CompletionKind::Property => 10,
+    }
+}
+
+/// Editor state: open buffers layered over the disk, and the program built from them.
+pub struct LanguageServer<F: FileSystem = RealFileSystem> {
+    fs: OverlayFileSystem<F>,
+    options: CompilerOptions,
+    program: Program,
+    /// Files we last published diagnostics for, so fixed files get cleared.
+    published: BTreeSet<String>,
+    shutdown_requested: bool,
+}
+
+impl LanguageServer {
+    pub fn new(options: CompilerOptions) -> Self {
+        Self::with_file_system(options, RealFileSystem)
+    }
+}
+
+impl<F: FileSystem> LanguageServer<F> {
+    pub fn with_file_system(options: CompilerOptions, fs: F) -> Self {
+        Self {
+            fs: OverlayFileSystem::new(fs),
+            program: Program::new(options.clone()),
+            options,
+            published: BTreeSet::new(),
+            shutdown_requested: false,
+        }
+    }
+
+    /// Handles one incoming message, returning the responses and notifications to send.
+    ///
+    /// Returns `None` once the client has sent `exit`.
+    pub fn handle_message(&mut self, message: &Value) -> Option<Vec<Value>> {
+        let mut outgoing = Vec::new();
+        self.handle_message_with(message, &mut |message| outgoing.push(message))?;
+        Some(outgoing)
+    }
+
+    /// Handles one incoming message like [`Self::handle_message`], handing each response
+    /// and notification to `send` as soon as it's ready, so each file's diagnostics reach
+    /// the client while the rest of the program is still being checked.
+    pub fn handle_message_with(
+        &mut self,
+        message: &Value,
+        send: &mut dyn FnMut(Value),
+    ) -> Option<()> {
+        let method = message["method"].as_str().unwrap_or_default();
+        let params = &message["params"];
+        let id = message.get("id").cloned();
+
+        if method == "exit" {
+            return None;
+        }
+        let result = match method {
+            "initialize" => Ok(json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "hoverProvider": true,
+                    "definitionProvider": true,
+                    "referencesProvider": true,
+                    "completionProvider": { "triggerCharacters": ["."] },
+                    "signatureHelpProvider": { "triggerCharacters": ["(", ","] },
+                    "renameProvider": { "prepareProvider": true },
+                    "documentSymbolProvider": true,
+                    "semanticTokensProvider": {
+                        "legend": {
+                            "tokenTypes": TokenType::LEGEND,
+                            "tokenModifiers": TokenModifiers::LEGEND,
+                        },
+                        "full": true,
+                    },
+                    "codeActionProvider": {
+                        "codeActionKinds": ["quickfix", "source.organizeImports"],
+                    },
+                },
+                "serverInfo": { "name": "tsc-rs", "version": env!("CARGO_PKG_VERSION") },
+            })),
+            "shutdown" => {
+                self.shutdown_requested = true;
+                Ok(Value::Null)
+            }
+            "textDocument/didOpen" => {
+                let document = &params["textDocument"];
+                match (document["uri"].as_str(), document["text"].as_str()) {
+                    (Some(uri), Some(text)) => {
+                        self.fs.set_file(uri_to_path(uri), text);
+                        self.rebuild(send);
+                        return Some(());
+                    }
+                    _ => return Some(()),
+                }
+            }
+            "textDocument/didChange" => {
+                let uri = params["textDocument"]["uri"].as_str();
+                let text = params["contentChanges"]
+                    .as_array()
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change["text"].as_str());
+                if let (Some(uri), Some(text)) = (uri, text) {
+                    self.fs.set_file(uri_to_path(uri), text);
+                    self.rebuild(send);
+                    return Some(());
+                }
+                return Some(());
+            }
+            "textDocument/didClose" => {
+                if let Some(uri) = params["textDocument"]["uri"].as_str() {
+                    self.fs.remove_file(uri_to_path(uri));
+                    self.rebuild(send);
+                    return Some(());
+                }
+                return Some(());
+            }
+            "textDocument/hover" => {
+                self.at_position(params, |server, file, offset| server.hover(file, offset))
+            }
+            "textDocument/definition" => self.at_position(params, |server, file, offset| {
+                server.locations(server.program.definition_at_position(file, offset))
+            }),
+            "textDocument/references" => self.at_position(params, |server, file, offset| {
+                server.locations(server.program.references_at_position(file, offset))
+            }),
+            "textDocument/completion" => self.at_position(params, |server, file, offset| {
+                Value::from(
+                    server
+                        .program
+                        .completions_at_position(file, offset)
+                        .into_iter()
+                        .map(|item| {
+                            json!({
+                                "label": item.name,
+                                "kind": completion_item_kind(item.kind),
+                                "detail": item.detail,
+                            })
+                        })
+                        .collect::<Vec<_>>(),
+                )
+            }),
+            "textDocument/prepareRename" => self.prepare_rename(params),
+            "textDocument/rename" => self.rename(params),
+            "textDocument/codeAction" => self.code_actions(params),
+            "textDocument/semanticTokens/full" => self.semantic_tokens(params),
+            "textDocument/documentSymbol" => self.document_symbols(params),
+            "textDocument/signatureHelp" => self.at_position(params, |server, file, offset| {
+                let Some(help) = server.program.signature_help_at_position(file, offset) else {
+                    return Value::Null;
+                };
+                let signatures: Vec<Value> = help
+                    .signatures
+                    .into_iter()
+                    .map(|signature| {
+                        let parameters: Vec<Value> = signature
+                            .parameters
+                            .iter()
+                            .map(|label| json!({ "label": label }))
+                            .collect();
+                        json!({
+                            "label": signature.label,
+                            "documentation": signature.documentation,
+                            "parameters": parameters,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "signatures": signatures,
+                    "activeSignature": help.active_signature,
+                    "activeParameter": help.active_parameter,
+                })
+            }),
+            _ => Err((METHOD_NOT_FOUND, format!("Unhandled method '{}'", method))),
+        };
+
+        // Notifications never get a response, even when unrecognised
+        let Some(id) = id else {
+            return Some(());
+        };
+        send(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": code, "message": message },
+            }),
+        });
+        Some(())
+    }
+
+    pub fn shutdown_requested(&self) -> bool {
+        self.shutdown_requested
+    }
+
+    /// Reloads the program from the open buffers and publishes each file's diagnostics
+    /// through `send` as soon as the file is checked. Files the program-wide checks add
+    /// to are published again once those are done, and files that are gone are cleared.
+    fn rebuild(&mut self, send: &mut dyn FnMut(Value)) {
+        let roots: Vec<PathBuf> = self.fs.overlay_paths().cloned().collect();
+        self.program = Program::load(self.options.clone(), &roots, &self.fs);
+
+        let mut by_file: Vec<(String, Vec<Diagnostic>)> = Vec::new();
+        let rest = self.program.check_streaming(|file| {
+            send(self.publish_diagnostics(&file.file_name, &file.diagnostics));
+            by_file.push((file.file_name, file.diagnostics));
+        });
+        let mut added: BTreeSet<String> = BTreeSet::new();
+        for diagnostic in rest {
+            added.insert(diagnostic.file_name.clone());
+            match by_file
+                .iter_mut()
+                .find(|(name, _)| *name == diagnostic.file_name)
+            {
+                Some((_, diagnostics)) => diagnostics.push(diagnostic),
+                None => by_file.push((diagnostic.file_name.clone(), vec![diagnostic])),
+            }
+        }
+        for (name, diagnostics) in &by_file {
+            if added.contains(name) {
+                send(self.publish_diagnostics(name, diagnostics));
+            }
+        }
+
+        let current: BTreeSet<String> = by_file.into_iter().map(|(name, _)| name).collect();
+        for name in self.published.difference(&current) {
+            send(self.publish_diagnostics(name, &[]));
+        }
+        self.published = current;
+    }
+
+    /// The notification replacing the diagnostics the client shows for `file_name`.
+    fn publish_diagnostics(&self, file_name: &str, diagnostics: &[Diagnostic]) -> Value {
+        let diagnostics: Vec<Value> = diagnostics
+            .iter()
+            .map(|diagnostic| {
+                json!({
+                    "range": self.range(file_name, diagnostic.span.unwrap_or_default()),
+                    "severity": match diagnostic.category {
+                        DiagnosticCategory::Error => 1,
+                        DiagnosticCategory::Warning => 2,
+                    },
+                    "source": "tsc-rs",
+                    "code": diagnostic.code,
+                    "message": diagnostic.message,
+                })
+            })
+            .collect();
+        json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": path_to_uri(file_name), "diagnostics": diagnostics },
+        })
+    }
+
+    fn at_position(
+        &self,
+        params: &Value,
+        handler: impl FnOnce(&Self, &str, u32) -> Value,
+    ) -> Result<Value, (i64, String)> {
+        Ok(match self.position(params)? {
+            Some((file_name, offset)) => handler(self, &file_name, offset),
+            None => Value::Null,
+        })
+    }
+
+    /// The file and offset a request's text document position is at, or `None` if the
+    /// file isn't in the program.
+    fn position(&self, params: &Value) -> Result<Option<(String, u32)>, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str();
+        let line = params["position"]["line"].as_u64();
+        let character = params["position"]["character"].as_u64();
+        let (Some(uri), Some(line), Some(character)) = (uri, line, character) else {
+            return Err((
+                INVALID_PARAMS,
+                "Expected a text document position".to_string(),
+            ));
+        };
+        let file_name = uri_to_path(uri).to_string_lossy().into_owned();
+        let Some(file) = self.program.file(&file_name) else {
+            return Ok(None);
+        };
+        let offset = LineIndex::new(&file.source).offset(line as u32, character as u32);
+        Ok(Some((file_name, offset)))
+    }
+
+    fn prepare_rename(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let Some((file_name, offset)) = self.position(params)? else {
+            return Ok(Value::Null);
+        };
+        let info = self
+            .program
+            .prepare_rename_at_position(&file_name, offset)
+            .map_err(|message| (REQUEST_FAILED, message))?;
+        Ok(json!({
+            "range": self.range(&file_name, info.span),
+            "placeholder": info.name,
+        }))
+    }
+
+    fn rename(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let Some(new_name) = params["newName"].as_str() else {
+            return Err((INVALID_PARAMS, "Expected a new name".to_string()));
+        };
+        let Some((file_name, offset)) = self.position(params)? else {
+            return Ok(Value::Null);
+        };
+        let locations = self
+            .program
+            .rename_at_position(&file_name, offset, new_name)
+            .map_err(|message| (REQUEST_FAILED, message))?;
+        let mut changes = serde_json::Map::new();
+        for location in locations {
+            let edits = changes
+                .entry(path_to_uri(&location.file_name))
+                .or_insert_with(|| Value::Array(Vec::new()));
+            if let Value::Array(edits) = edits {
+                edits.push(json!({
+                    "range": self.range(&location.file_name, location.span),
+                    "newText": new_name,
+                }));
+            }
+        }
+        Ok(json!({ "changes": changes }))
+    }
+
+    fn code_actions(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let uri = params["textDocument"]["uri"].as_str();
+        let start = &params["range"]["start"];
+        let end = &params["range"]["end"];
+        let position = |at: &Value| Some((at["line"].as_u64()?, at["character"].as_u64()?));
+        let (Some(uri), Some(start), Some(end)) = (uri, position(start), position(end)) else {
+            return Err((INVALID_PARAMS, "Expected a text document range".to_string()));
+        };
+        let file_name = uri_to_path(uri).to_string_lossy().into_owned();
+        let Some(file) = self.program.file(&file_name) else {
+            return Ok(Value::Null);
+        };
+        let index = LineIndex::new(&file.source);
+        let span = Span::new(
+            index.offset(start.0 as u32, start.1 as u32),
+            index.offset(end.0 as u32, end.1 as u32),
+        );
+
+        // Clients that ask for some kinds only get actions of those kinds or kinds in them
+        let only: Option<Vec<&str>> = params["context"]["only"]
+            .as_array()
+            .map(|kinds| kinds.iter().filter_map(Value::as_str).collect());
+        let wanted = |kind: &str| {
+            only.as_ref().is_none_or(|only| {
+                only.iter()
+                    .any(|only| kind == *only || kind.starts_with(&format!("{}.", only)))
+            })
+        };
+        let mut fixes: Vec<(&str, CodeFix)> = Vec::new();
+        if wanted("quickfix") {
+            fixes.extend(
+                self.program
+                    .code_fixes(&file_name, span)
+                    .into_iter()
+                    .map(|fix| ("quickfix", fix)),
+            );
+        }
+        if wanted("source.organizeImports")
+            && let Some(fix) = self.program.organize_imports(&file_name)
+        {
+            fixes.push(("source.organizeImports", fix));
+        }
+        Ok(fixes
+            .into_iter()
+            .map(|(kind, fix)| {
+                let edits: Vec<Value> = fix
+                    .edits
+                    .iter()
+                    .map(|edit| {
+                        json!({
+                            "range": self.range(&fix.file_name, edit.span),
+                            "newText": edit.new_text,
+                        })
+                    })
+                    .collect();
+                json!({
+                    "title": fix.description,
+                    "kind": kind,
+                    "edit": { "changes": { path_to_uri(&fix.file_name): edits } },
+                })
+            })
+            .collect())
+    }
+
+    fn document_symbols(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let Some(uri) = params["textDocument"]["uri"].as_str() else {
+            return Err((INVALID_PARAMS, "Expected a text document".to_string()));
+        };
+        let file_name = uri_to_path(uri).to_string_lossy().into_owned();
+        Ok(self.document_symbols_of(&file_name, &self.program.outline(&file_name)))
+    }
+
+    fn document_symbols_of(&self, file_name: &str, items: &[OutlineItem]) -> Value {
+        items
+            .iter()
+            .map(|item| {
+                json!({
+                    "name": item.name,
+                    "kind": symbol_kind(item.kind),
+                    "range": self.range(file_name, item.span),
+                    "selectionRange": self.range(file_name, item.name_span),
+                    "children": self.document_symbols_of(file_name, &item.children),
+                })
+            })
+            .collect()
+    }
+
+    fn semantic_tokens(&self, params: &Value) -> Result<Value, (i64, String)> {
+        let Some(uri) = params["textDocument"]["uri"].as_str() else {
+            return Err((INVALID_PARAMS, "Expected a text document".to_string()));
+        };
+        let file_name = uri_to_path(uri).to_string_lossy().into_owned();
+        let Some(file) = self.program.file(&file_name) else {
+            return Ok(Value::Null);
+        };
+        let index = LineIndex::new(&file.source);
+        // Each token is five numbers, its position relative to the one before it
+        let mut data = Vec::new();
+        let (mut previous_line, mut previous_character) = (0, 0);
+        for token in self.program.semantic_tokens(&file_name) {
+            let (line, character) = index.line_col(token.span.start);
+            let delta_character = match line == previous_line {
+                true => character - previous_character,
+                false => character,
+            };
+            data.extend([
+                line - previous_line,
+                delta_character,
+                token.span.size(),
+                token.token_type as u32,
+                token.modifiers.bits(),
+            ]);
+            (previous_line, previous_character) = (line, character);
+        }
+        Ok(json!({ "data": data }))
+    }
+
+    fn hover(&self, file_name: &str, offset: u32) -> Value {
+        let (span, display, documentation) =
+            match self.program.quick_info_at_position(file_name, offset) {
+                Some(info) => (info.span, info.display, info.documentation),
+                // Expressions without a name still show their type
+                None => match self.program.type_at_position(file_name, offset) {
+                    Some(info) => (info.span, info.display, None),
+                    None => return Value::Null,
+                },
+            };
+        let mut value = format!("```typescript\n{}\n```", display);
+        if let Some(documentation) = documentation {
+            value.push_str(&format!("\n\n{}", documentation));
+        }
+        json!({
+            "contents": { "kind": "markdown", "value": value },
+            "range": self.range(file_name, span),
+        })
+    }
+
+    fn locations(&self, locations: Vec<Location>) -> Value {
+        locations
+            .iter()
+            .map(|location| {
+                json!({
+                    "uri": path_to_uri(&location.file_name),
+                    "range": self.range(&location.file_name, location.span),
+                })
+            })
+            .collect()
+    }
+
+    fn range(&self, file_name: &str, span: Span) -> Value {
+        let source = self
+            .program
+            .file(file_name)
+            .map_or("", |f| f.source.as_str());
+        let index = LineIndex::new(source);
+        let (start_line, start_character) = index.line_col(span.start);
+        let (end_line, end_character) = index.line_col(span.end);
+        json!({
+            "start": { "line": start_line, "character": start_character },
+            "end": { "line": end_line, "character": end_character },
+        })
+    }
+}
+
+/// Serves LSP requests from `reader` until the client exits or closes the stream.
+pub fn run(reader: &mut impl BufRead, writer: &mut impl Write) -> io::Result<()> {
+    let mut server = LanguageServer::new(CompilerOptions::default());
+    while let Some(message) = read_message(reader)? {
+        // The first write that fails ends the session
+        let mut written = Ok(());
+        let running = server.handle_message_with(&message, &mut |outgoing| {
+            if written.is_ok() {
+                written = write_message(writer, &outgoing);
+            }
+        });
+        written?;
+        if running.is_none() {
+            break;
+        }
+    }
+    Ok(())
+}
+
+/// Runs the server on the process's stdin and stdout.
+pub fn run_stdio() -> io::Result<()> {
+    run(&mut io::stdin().lock(), &mut io::stdout().lock())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::InMemoryFileSystem;
+    use std::io::Cursor;
+
+    fn server() -> LanguageServer<InMemoryFileSystem> {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/proj/math.ts", "export const pi: number = 3.14;");
+        LanguageServer::with_file_system(CompilerOptions::default(), fs)
+    }
+
+    fn open(server: &mut LanguageServer<InMemoryFileSystem>, text: &str) -> Vec<Value> {
+        server
+            .handle_message(&json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": { "textDocument": {
+                    "uri": "file:///proj/main.ts", "languageId": "typescript", "version": 1, "text": text,
+                } },
+            }))
+            .unwrap()
+    }
+
+    fn request(
+        server: &mut LanguageServer<InMemoryFileSystem>,
+        method: &str,
+        line: u32,
+        character: u32,
+    ) -> Value {
+        let responses = server
+            .handle_message(&json!({
+                "jsonrpc": "2.0",
+                "id": 7,
+                "method": method,
+                "params": {
+                    "textDocument": { "uri": "file:///proj/main.ts" },
+                    "position": { "line": line, "character": character },
+                    "context": { "includeDeclaration": true },
+                },
+            }))
+            .unwrap();
+        assert_eq!(responses.len(), 1);
+        assert_eq!(responses[0]["id"], 7);
+        responses[0]["result"].clone()
+    }
+
+    #[test]
+    fn test_framing_round_trip() {
+        let mut buffer = Vec::new();
+        write_message(&mut buffer, &json!({ "jsonrpc": "2.0", "method": "exit" })).unwrap();
+        write_message(&mut buffer, &json!({ "id": 1 })).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        assert_eq!(
+            read_message(&mut reader).unwrap().unwrap()["method"],
+            "exit"
+        );
+        assert_eq!(read_message(&mut reader).unwrap().unwrap()["id"], 1);
+        assert!(read_message(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_uri_conversion() {
+        assert_eq!(
+            uri_to_path("file:///a%20b/c.ts"),
+            PathBuf::from("/a b/c.ts")
+        );
+        assert_eq!(path_to_uri("/a b/c.ts"), "file:///a%20b/c.ts");
+    }
+
+    #[test]
+    fn test_publishes_diagnostics_on_open_and_change() {
+        let mut server = server();
+        let notifications = open(
+            &mut server,
+            "import { pi } from './math';\nlet x: string = pi;",
+        );
+        assert_eq!(notifications.len(), 2);
+        let main = notifications
+            .iter()
+            .find(|n| n["params"]["uri"] == "file:///proj/main.ts")
+            .unwrap();
+        let diagnostics = main["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0]["message"],
+            "Type 'number' is not assignable to type 'string'"
+        );
+        assert_eq!(
+            diagnostics[0]["range"]["start"],
+            json!({ "line": 1, "character": 4 })
+        );
+
+        let notifications = server
+            .handle_message(&json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didChange",
+                "params": {
+                    "textDocument": { "uri": "file:///proj/main.ts", "version": 2 },
+                    "contentChanges": [{ "text": "let x: number = 1;" }],
+                },
+            }))
+            .unwrap();
+        // math.ts is no longer imported, so its (empty) diagnostics are cleared too
+        assert_eq!(notifications.len(), 2);
+        assert!(
+            notifications
+                .iter()
+                .all(|n| n["params"]["diagnostics"] == json!([]))
+        );
+    }
+
+    #[test]
+    fn test_publishes_each_file_as_checked() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/proj/b.ts", "import './main';\nexport const b = 1;");
+        let options = CompilerOptions {
+            report_circular_imports: true,
+            ..Default::default()
+        };
+        let mut server = LanguageServer::with_file_system(options, fs);
+        let mut sent = Vec::new();
+        server.handle_message_with(
+            &json!({
+                "jsonrpc": "2.0",
+                "method": "textDocument/didOpen",
+                "params": { "textDocument": {
+                    "uri": "file:///proj/main.ts", "languageId": "typescript", "version": 1,
+                    "text": "import { b } from './b';\nlet x: string = b;",
+                } },
+            }),
+            &mut |message| sent.push(message),
+        );
+        let published: Vec<(&Value, usize)> = sent
+            .iter()
+            .map(|n| {
+                let diagnostics = n["params"]["diagnostics"].as_array().unwrap();
+                (&n["params"]["uri"], diagnostics.len())
+            })
+            .collect();
+        // Each file as it's checked, then the one the circular import is reported on again
+        assert_eq!(
+            published,
+            vec![
+                (&json!("file:///proj/b.ts"), 0),
+                (&json!("file:///proj/main.ts"), 1),
+                (&json!("file:///proj/main.ts"), 2),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_hover_definition_references_completion() {
+        let mut server = server();
+        open(&mut server, "import { pi } from './math';\nlet x = pi;\nx");
+
+        let hover = request(&mut server, "textDocument/hover", 1, 9);
+        assert_eq!(
+            hover["contents"]["value"],
+            "```typescript\n(alias) const pi: number\n```"
+        );
+
+        let definition = request(&mut server, "textDocument/definition", 1, 9);
+        assert_eq!(definition[0]["uri"], "file:///proj/math.ts");
+        assert_eq!(
+            definition[0]["range"]["start"],
+            json!({ "line": 0, "character": 13 })
+        );
+
+        let references = request(&mut server, "textDocument/references", 0, 9);
+        assert_eq!(references.as_array().unwrap().len(), 3);
+
+        let completion = request(&mut server, "textDocument/completion", 2, 1);
+        let labels: Vec<&str> = completion
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["label"].as_str().unwrap())
+            .collect();
+        assert_eq!(labels, vec!["pi", "x"]);
+    }
+
+    #[test]
+    fn test_signature_help() {
+        let mut server = server();
+        open(
+            &mut server,
+            "function area(width: number, height: number): number { return width * height; }\narea(2, 3);",
+        );
+        let help = request(&mut server, "textDocument/signatureHelp", 1, 8);
+        assert_eq!(
+            help["signatures"][0]["label"],
+            "area(width: number, height: number): number"
+        );
+        assert_eq!(
+            help["signatures"][0]["parameters"][1]["label"],
+            "height: number"
+        );
+        assert_eq!(help["activeParameter"], 1);
+        assert_eq!(
+            request(&mut server, "textDocument/signatureHelp", 0, 2),
+            Value::Null
+        );
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut server = server();
+        open(&mut server, "import { pi } from './math';\nlet x = pi;");
+
+        let prepared = request(&mut server, "textDocument/prepareRename", 1, 9);
+        assert_eq!(prepared["placeholder"], "pi");
+
+        let responses = server
+            .handle_message(&json!({
+                "jsonrpc": "2.0",
+                "id": 8,
+                "method": "textDocument/rename",
+                "params": {
+                    "textDocument": { "uri": "file:///proj/main.ts" },
+                    "position": { "line": 1, "character": 9 },
+                    "newName": "tau",
+                },
+            }))
+            .unwrap();
+        let changes = &responses[0]["result"]["changes"];
+        assert_eq!(changes["file:///proj/main.ts"].as_array().unwrap().len(), 2);
+        assert_eq!(
+            changes["file:///proj/math.ts"][0]["newText"], "tau",
+            "{}",
+            changes
+        );
+
+        let responses = server
+            .handle_message(&json!({
+                "jsonrpc": "2.0",
+                "id": 9,
+                "method": "textDocument/prepareRename",
+                "params": {
+                    "textDocument": { "uri": "file:///proj/main.ts" },
+                    "position": { "line": 1, "character": 1 },
+                },
+            }))
+            .unwrap();
+        assert_eq!(responses[0]["error"]["code"], REQUEST_FAILED);
+    }
+
+    #[test]
+    fn test_code_actions() {
+        let mut server = server();
+        open(
+            &mut server,
+            "import { pi } from './math';\nimport { b } from './b';\nlet x = pi;",
+        );
+        let code_actions = |server: &mut LanguageServer<InMemoryFileSystem>, only: Value| {
+            let responses = server
+                .handle_message(&json!({
+                    "jsonrpc": "2.0",
+                    "id": 10,
+                    "method": "textDocument/codeAction",
+                    "params": {
+                        "textDocument": { "uri": "file:///proj/main.ts" },
+                        "range": {
+                            "start": { "line": 1, "character": 0 },
+                            "end": { "line": 1, "character": 3 },
+                        },
+                        "context": { "diagnostics": [], "only": only },
+                    },
+                }))
+                .unwrap();
+            responses[0]["result"].as_array().unwrap().clone()
+        };
+
+        let actions = code_actions(&mut server, Value::Null);
+        assert_eq!(actions.len(), 2);
+        assert_eq!(actions[0]["title"], "Remove import from './b'");
+        assert_eq!(actions[0]["kind"], "quickfix");
+        let edit = &actions[0]["edit"]["changes"]["file:///proj/main.ts"][0];
+        assert_eq!(edit["range"]["start"]["line"], 1);
+        assert_eq!(edit["range"]["end"]["line"], 2);
+        assert_eq!(edit["newText"], "");
+        assert_eq!(actions[1]["kind"], "source.organizeImports");
+
+        let actions = code_actions(&mut server, json!(["source"]));
+        assert_eq!(actions.len(), 1);
+        assert_eq!(actions[0]["title"], "Organize imports");
+    }
+
+    #[test]
+    fn test_document_symbols() {
+        let mut server = server();
+        open(&mut server, "class Counter {\n    count = 0;\n}");
+        let responses = server
+            .handle_message(&json!({
+                "jsonrpc": "2.0",
+                "id": 12,
+                "method": "textDocument/documentSymbol",
+                "params": { "textDocument": { "uri": "file:///proj/main.ts" } },
+            }))
+            .unwrap();
+        let class = &responses[0]["result"][0];
+        assert_eq!(class["name"], "Counter");
+        assert_eq!(class["kind"], 5);
+        assert_eq!(class["range"]["end"]["line"], 2);
+        assert_eq!(class["selectionRange"]["start"]["character"], 6);
+        assert_eq!(class["children"][0]["name"], "count");
+        assert_eq!(class["children"][0]["kind"], 7);
+    }
+
+    #[test]
+    fn test_semantic_tokens() {
+        let mut server = server();
+        open(
+            &mut server,
+            "import { pi } from './math';\nconst tau = pi * 2;",
+        );
+        let responses = server
+            .handle_message(&json!({
+                "jsonrpc": "2.0",
+                "id": 11,
+                "method": "textDocument/semanticTokens/full",
+                "params": { "textDocument": { "uri": "file:///proj/main.ts" } },
+            }))
+            .unwrap();
+        let variable = TokenType::Variable as u32;
+        assert_eq!(
+            responses[0]["result"]["data"],
+            json!([
+                0, 9, 2, variable, 1, 1, 6, 3, variable, 5, 0, 6, 2, variable, 0
+            ])
+        );
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let mut server = server();
+        let response = server
+            .handle_message(
+                &json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {} }),
+            )
+            .unwrap();
+        assert_eq!(response[0]["result"]["capabilities"]["hoverProvider"], true);
+
+        let response = server
+            .handle_message(&json!({ "jsonrpc": "2.0", "id": 2, "method": "workspace/symbol" }))
+            .unwrap();
+        assert_eq!(response[0]["error"]["code"], METHOD_NOT_FOUND);
+
+        let response = server
+            .handle_message(&json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }))
+            .unwrap();
+        assert!(response.is_empty());
+
+        server
+            .handle_message(&json!({ "jsonrpc": "2.0", "id": 3, "method": "shutdown" }))
+            .unwrap();
+        assert!(server.shutdown_requested());
+        assert!(
+            server
+                .handle_message(&json!({ "jsonrpc": "2.0", "method": "exit" }))
+                .is_none()
+        );
+    }
+}
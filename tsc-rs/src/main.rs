@@ -1,14 +1,402 @@
-mod parser;
-mod types;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use tsc_rs::diagnostics::{cap_errors, error_summary};
+use tsc_rs::line_index::LineIndex;
+use tsc_rs::locale::MessageCatalog;
+use tsc_rs::rules::builtin_rule;
+use tsc_rs::{
+    CompilerOptions, Diagnostic, DiagnosticCategory, Program, RealFileSystem, Trace, explain,
+    type_of_expression,
+};
+
+// The options checking takes, and those of them followed by a value
+const OPTIONS: &[&str] = &[
+    "--project",
+    "-p",
+    "--traceResolution",
+    "--noEmit",
+    "--noEmitOnError",
+    "--noErrorTruncation",
+    "--skipLibCheck",
+    "--skipDefaultLibCheck",
+    "--preserveSymlinks",
+    "--listFiles",
+    "--explainFiles",
+    "--rule",
+    "--extendedDiagnostics",
+    "--pretty",
+    "--maxErrors",
+    "--locale",
+    "--generateTrace",
+];
+const VALUE_OPTIONS: &[&str] = &["--generateTrace", "--rule", "--maxErrors", "--locale"];
+// The compiler options among them, turned on by their flag
+const COMPILER_OPTIONS: &[&str] = &[
+    "traceResolution",
+    "noEmit",
+    "noEmitOnError",
+    "noErrorTruncation",
+    "skipLibCheck",
+    "skipDefaultLibCheck",
+    "preserveSymlinks",
+];
 
 fn main() {
-    let source = r#"
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.iter().any(|arg| arg == "--lsp") {
+        if let Err(err) = tsc_rs::lsp::run_stdio() {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    // `--list-graph [--format json|dot] <files>` prints the import graph from those roots
+    if args.iter().any(|arg| arg == "--list-graph") {
+        let format = args
+            .iter()
+            .position(|arg| arg == "--format")
+            .and_then(|index| args.get(index + 1));
+        let mut roots = Vec::new();
+        let mut rest = args.iter();
+        while let Some(arg) = rest.next() {
+            match arg.as_str() {
+                "--list-graph" => {}
+                "--format" => {
+                    rest.next();
+                }
+                root => roots.push(root),
+            }
+        }
+        let program = Program::load(CompilerOptions::default(), &roots, &RealFileSystem);
+        let graph = program.module_graph();
+        match format.map(String::as_str) {
+            None | Some("json") => println!("{}", graph.to_json()),
+            Some("dot") => print!("{}", graph.to_dot()),
+            Some(other) => {
+                eprintln!(
+                    "error: Unknown graph format '{}'; expected 'json' or 'dot'.",
+                    other
+                );
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // `explain [--nested] <file>` lists declarations with their types; `-` reads stdin
+    if args.first().is_some_and(|arg| arg == "explain") {
+        let nested = args.iter().any(|arg| arg == "--nested");
+        let Some(file) = args[1..].iter().find(|arg| *arg != "--nested") else {
+            eprintln!("error: 'explain' expects a file name, or '-' to read from stdin.");
+            std::process::exit(1);
+        };
+        let (file_name, source) = match file.as_str() {
+            "-" => ("input.ts", read_stdin()),
+            path => match std::fs::read_to_string(path) {
+                Ok(source) => (path, source),
+                Err(err) => {
+                    eprintln!("error: Could not read file '{}': {}", path, err);
+                    std::process::exit(1);
+                }
+            },
+        };
+        print!(
+            "{}",
+            explain(file_name, &source, &CompilerOptions::default(), nested)
+        );
+        return;
+    }
+
+    // `--typeof <expression>` prints the expression's type; `-` reads it from stdin
+    if let Some(index) = args
+        .iter()
+        .position(|arg| arg == "--typeof" || arg == "--eval")
+    {
+        let Some(expression) = args.get(index + 1) else {
+            eprintln!("error: Option '{}' expects an expression.", args[index]);
+            std::process::exit(1);
+        };
+        let expression = match expression.as_str() {
+            "-" => read_stdin(),
+            expression => expression.to_string(),
+        };
+        match type_of_expression(&expression, &CompilerOptions::default()) {
+            Ok(ty) => println!("{}", ty),
+            Err(diagnostic) => {
+                eprintln!("{}", diagnostic);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Option values aside, anything else that starts with `-` has to be an option
+    let values: Vec<usize> = args
+        .iter()
+        .enumerate()
+        .filter(|(_, arg)| VALUE_OPTIONS.contains(&arg.as_str()))
+        .map(|(index, _)| index + 1)
+        .collect();
+    if let Some(unknown) = args.iter().enumerate().find_map(|(index, arg)| {
+        let unknown = arg.starts_with('-')
+            && arg != "-"
+            && !values.contains(&index)
+            && !OPTIONS.contains(&arg.as_str());
+        unknown.then_some(arg)
+    }) {
+        eprintln!("error: Unknown compiler option '{}'.", unknown);
+        std::process::exit(1);
+    }
+
+    // `--project <path>` checks the project a `tsconfig.json`, or the one in a directory,
+    // configures
+    if let Some(index) = args
+        .iter()
+        .position(|arg| arg == "--project" || arg == "-p")
+    {
+        let Some(path) = args.get(index + 1) else {
+            eprintln!("error: Option '{}' expects a path.", args[index]);
+            std::process::exit(1);
+        };
+        let mut path = PathBuf::from(path);
+        if path.is_dir() {
+            path.push("tsconfig.json");
+        }
+        match Program::load_project_with(&path, &RealFileSystem, command_line_options(&args)) {
+            Ok(program) => check_loaded(program, &args),
+            Err(err) => {
+                eprintln!("error: {}", err);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    // Any other arguments are root files, checked with everything they import, except the
+    // values of options that take one
+    let roots: Vec<&String> = args
+        .iter()
+        .enumerate()
+        .filter(|(index, arg)| !arg.starts_with('-') && !values.contains(index))
+        .map(|(_, arg)| arg)
+        .collect();
+    if !roots.is_empty() {
+        let options: CompilerOptions =
+            serde_json::from_value(Value::Object(command_line_options(&args)))
+                .expect("command-line flags should be compiler options");
+        check_loaded(Program::load(options, &roots, &RealFileSystem), &args);
+        return;
+    }
+
+    let source = match args.iter().any(|arg| arg == "-") {
+        true => read_stdin(),
+        false => r#"
         let x: number = 42;
         let y: string = "Hello";
-    "#;
+    "#
+        .to_string(),
+    };
+
+    let mut program = Program::new(CompilerOptions::default());
+    program.add_file("input.ts", source);
+    add_rules(&mut program, &args);
+    check_and_report(&program, &args, false);
+}
+
+/// The compiler options `args` turn on, by their `tsconfig.json` names.
+fn command_line_options(args: &[String]) -> Map<String, Value> {
+    COMPILER_OPTIONS
+        .iter()
+        .filter(|name| {
+            args.iter()
+                .any(|arg| arg.strip_prefix("--") == Some(**name))
+        })
+        .map(|name| (name.to_string(), Value::Bool(true)))
+        .collect()
+}
+
+/// Checks a program loaded from disk and emits it, after printing how its files were
+/// found when `--traceResolution`, `--listFiles` or `--explainFiles` asks.
+fn check_loaded(mut program: Program, args: &[String]) {
+    add_rules(&mut program, args);
+    for line in program.resolution_trace() {
+        println!("{}", line);
+    }
+    if args.iter().any(|arg| arg == "--explainFiles") {
+        print!("{}", program.explain_files());
+    } else if args.iter().any(|arg| arg == "--listFiles") {
+        for file in program.files() {
+            println!("{}", file.name);
+        }
+    }
+    check_and_report(&program, args, true);
+}
+
+/// Enables the built-in rule named by each `--rule <name>`.
+fn add_rules(program: &mut Program, args: &[String]) {
+    let mut rest = args.iter();
+    while let Some(arg) = rest.next() {
+        if arg != "--rule" {
+            continue;
+        }
+        let Some(name) = rest.next() else {
+            eprintln!("error: Option '--rule' expects a rule name.");
+            std::process::exit(1);
+        };
+        match builtin_rule(name) {
+            Some(rule) => program.add_rule(rule),
+            None => {
+                eprintln!("error: Unknown rule '{}'.", name);
+                std::process::exit(1);
+            }
+        }
+    }
+}
+
+/// Checks `program` and reports its diagnostics, followed by performance statistics
+/// when `--extendedDiagnostics` is given and a summary of the errors with `--pretty`.
+/// `--maxErrors <n>` stops after that many errors, and `--locale <locale>` translates
+/// them. `--generateTrace <dir>` writes the run's trace events to `<dir>/trace.json`.
+/// With `emit` set, JavaScript is written next to each file unless the program's
+/// options say otherwise.
+fn check_and_report(program: &Program, args: &[String], emit: bool) {
+    let trace_dir = args
+        .iter()
+        .position(|arg| arg == "--generateTrace")
+        .map(|index| match args.get(index + 1) {
+            Some(dir) => Path::new(dir),
+            None => {
+                eprintln!("error: Option '--generateTrace' expects a directory.");
+                std::process::exit(1);
+            }
+        });
+    let mut trace = Trace::new();
+    let (mut diagnostics, stats) = match trace_dir {
+        Some(_) => program.check_with_trace(&mut trace),
+        None => program.check_with_stats(),
+    };
+    if let Some(dir) = trace_dir {
+        let written = std::fs::create_dir_all(dir)
+            .and_then(|()| std::fs::write(dir.join("trace.json"), trace.to_json().to_string()));
+        if let Err(err) = written {
+            eprintln!(
+                "error: Could not write trace to '{}': {}",
+                dir.display(),
+                err
+            );
+            std::process::exit(1);
+        }
+    }
+    if emit {
+        diagnostics.extend(program.emit_to(&mut RealFileSystem).diagnostics);
+    }
+    if args.iter().any(|arg| arg == "--extendedDiagnostics") {
+        print!("{}", stats);
+    }
+    let max_errors = args
+        .iter()
+        .position(|arg| arg == "--maxErrors")
+        .map(
+            |index| match args.get(index + 1).map(|n| n.parse::<usize>()) {
+                Some(Ok(max_errors)) => max_errors,
+                _ => {
+                    eprintln!("error: Option '--maxErrors' expects a number.");
+                    std::process::exit(1);
+                }
+            },
+        );
+    if let Some(catalog) = catalog(args) {
+        diagnostics = diagnostics
+            .into_iter()
+            .map(|diagnostic| catalog.localize(diagnostic))
+            .collect();
+    }
+    // Each file with diagnostics is indexed once, however many it has
+    let mut lines = HashMap::new();
+    for diagnostic in &diagnostics {
+        if let Some(file) = program.file(&diagnostic.file_name) {
+            lines
+                .entry(diagnostic.file_name.clone())
+                .or_insert_with(|| LineIndex::new(&file.source));
+        }
+    }
+    let position_of = |diagnostic: &Diagnostic| {
+        let index: &LineIndex = lines.get(&diagnostic.file_name)?;
+        let (line, column) = index.line_col(diagnostic.span?.start);
+        Some((line + 1, column + 1))
+    };
+    let summary = match args.iter().any(|arg| arg == "--pretty") {
+        true => error_summary(&diagnostics, position_of),
+        false => None,
+    };
+    report(&diagnostics, position_of, max_errors, summary);
+}
+
+/// The catalog `--locale <locale>` asks for, read from the directory the executable is
+/// in, the way tsc reads its own from next to `tsc.js`.
+fn catalog(args: &[String]) -> Option<MessageCatalog> {
+    let index = args.iter().position(|arg| arg == "--locale")?;
+    let Some(locale) = args.get(index + 1) else {
+        eprintln!("error: Option '--locale' expects a locale.");
+        std::process::exit(1);
+    };
+    let dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+        .unwrap_or_default();
+    match MessageCatalog::load(locale, &dir, &RealFileSystem) {
+        Ok(catalog) => catalog,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Prints diagnostics at the one-based line and column `position_of` gives, at most
+/// `max_errors` of them errors, and then `summary`, exiting with status 1 if any of them
+/// are errors.
+fn report(
+    diagnostics: &[Diagnostic],
+    position_of: impl Fn(&Diagnostic) -> Option<(u32, u32)>,
+    max_errors: Option<usize>,
+    summary: Option<String>,
+) {
+    if diagnostics.is_empty() {
+        println!("Successfully checked TypeScript code");
+        return;
+    }
+    let (reported, omitted) = match max_errors {
+        Some(max_errors) => cap_errors(diagnostics.to_vec(), max_errors),
+        None => (diagnostics.to_vec(), 0),
+    };
+    for diagnostic in &reported {
+        eprintln!("{}", diagnostic.display_at(position_of(diagnostic)));
+    }
+    match omitted {
+        0 => {}
+        1 => eprintln!("... 1 more error."),
+        n => eprintln!("... {} more errors.", n),
+    }
+    if let Some(summary) = summary {
+        eprint!("\n{}", summary);
+    }
+    if diagnostics
+        .iter()
+        .any(|diagnostic| diagnostic.category == DiagnosticCategory::Error)
+    {
+        std::process::exit(1);
+    }
+}
 
-    match parser::parse_typescript(source) {
-        Ok(_) => println!("Successfully parsed TypeScript code"),
-        Err(e) => eprintln!("Error parsing TypeScript: {}", e),
+fn read_stdin() -> String {
+    let mut source = String::new();
+    if let Err(err) = std::io::stdin().read_to_string(&mut source) {
+        eprintln!("error: Could not read from stdin: {}", err);
+        std::process::exit(1);
     }
+    source
 }
@@ -0,0 +1,341 @@
+use crate::program::Program;
+use crate::symbols::{Location, Symbol, SymbolId};
+use crate::type_checker::TypeChecker;
+use oxc_span::Span;
+
+/// The name a rename at some position would change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenameInfo {
+    /// The name's span at the position.
+    pub span: Span,
+    pub name: String,
+}
+
+// Words that can't name a binding, so can't be what something is renamed to
+const RESERVED_WORDS: &[&str] = &[
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "debugger",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "enum",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "new",
+    "null",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "typeof",
+    "var",
+    "void",
+    "while",
+    "with",
+];
+
+fn is_identifier(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_alphabetic() || c == '_' || c == '$')
+        && chars.all(|c| c.is_alphanumeric() || c == '_' || c == '$')
+        && !RESERVED_WORDS.contains(&name)
+}
+
+/// Where a symbol is ultimately declared, looking through import/export aliases.
+fn definition_of(symbol: &Symbol, file_name: &str) -> Location {
+    symbol
+        .origin
+        .clone()
+        .unwrap_or_else(|| Location::new(file_name, symbol.span))
+}
+
+fn reference_at(checker: &TypeChecker, offset: u32) -> Option<SymbolId> {
+    checker
+        .references()
+        .iter()
+        .find(|(span, _)| span.start <= offset && offset < span.end)
+        .map(|(_, id)| *id)
+}
+
+impl Program {
+    /// The declaration site(s) of the symbol referenced at `offset`.
+    pub fn definition_at_position(&self, file_name: &str, offset: u32) -> Vec<Location> {
+        let Some(checker) = self.check_file(file_name) else {
+            return Vec::new();
+        };
+        reference_at(&checker, offset)
+            .map(|id| vec![definition_of(&checker.symbols()[id.0], file_name)])
+            .unwrap_or_default()
+    }
+
+    /// Every reference, declarations included, to the symbol at `offset` across all files.
+    pub fn references_at_position(&self, file_name: &str, offset: u32) -> Vec<Location> {
+        let checked = self.check_files();
+        let Some(index) = self.files().iter().position(|f| f.name == file_name) else {
+            return Vec::new();
+        };
+        let checker = &checked[index].checker;
+        let Some(id) = reference_at(checker, offset) else {
+            return Vec::new();
+        };
+        let target = definition_of(&checker.symbols()[id.0], file_name);
+
+        let mut locations = Vec::new();
+        for (file, checked) in self.files().iter().zip(&checked) {
+            let checker = &checked.checker;
+            let mut spans: Vec<_> = checker
+                .references()
+                .iter()
+                .filter(|(_, id)| definition_of(&checker.symbols()[id.0], &file.name) == target)
+                .map(|(span, _)| *span)
+                .collect();
+            spans.sort_by_key(|span| span.start);
+            spans.dedup();
+            locations.extend(
+                spans
+                    .into_iter()
+                    .map(|span| Location::new(&file.name, span)),
+            );
+        }
+        locations
+    }
+
+    /// Checks that the name at `offset` can be renamed, failing with the reason it can't.
+    pub fn prepare_rename_at_position(
+        &self,
+        file_name: &str,
+        offset: u32,
+    ) -> Result<RenameInfo, String> {
+        let cannot_rename = || "You cannot rename this element.".to_string();
+        let checker = self.check_file(file_name).ok_or_else(cannot_rename)?;
+        let (span, id) = checker
+            .references()
+            .iter()
+            .find(|(span, _)| span.start <= offset && offset < span.end)
+            .copied()
+            .ok_or_else(cannot_rename)?;
+        let name = self.name_at(&Location::new(file_name, span));
+        // `export default` expressions are named for the export, not by anything written
+        if !is_identifier(name) {
+            return Err(cannot_rename());
+        }
+        let definition = definition_of(&checker.symbols()[id.0], file_name);
+        if definition.file_name.contains("/node_modules/") {
+            return Err(
+                "You cannot rename elements that are defined in a 'node_modules' folder."
+                    .to_string(),
+            );
+        }
+        Ok(RenameInfo {
+            span: Span::new(span.start, span.start + name.len() as u32),
+            name: name.to_string(),
+        })
+    }
+
+    /// Where renaming the name at `offset` to `new_name` edits, in every file.
+    ///
+    /// Imports and exports that give the name an alias keep the alias, so only the places
+    /// written with the old name change. Renaming an alias itself only changes the file
+    /// that declares it.
+    pub fn rename_at_position(
+        &self,
+        file_name: &str,
+        offset: u32,
+        new_name: &str,
+    ) -> Result<Vec<Location>, String> {
+        let info = self.prepare_rename_at_position(file_name, offset)?;
+        if !is_identifier(new_name) {
+            return Err(format!("'{}' is not a valid identifier.", new_name));
+        }
+        let checker = self
+            .check_file(file_name)
+            .ok_or("File is not in the program.")?;
+        let id = reference_at(&checker, offset).ok_or("You cannot rename this element.")?;
+        let definition = definition_of(&checker.symbols()[id.0], file_name);
+        let is_alias = self.name_at(&definition) != info.name;
+        Ok(self
+            .references_at_position(file_name, offset)
+            .into_iter()
+            .filter(|location| !is_alias || location.file_name == file_name)
+            .filter(|location| self.name_at(location) == info.name)
+            .map(|location| {
+                let span = location.span;
+                Location::new(
+                    location.file_name,
+                    Span::new(span.start, span.start + info.name.len() as u32),
+                )
+            })
+            .collect())
+    }
+
+    /// The name written at the start of `location`, which for declarations may also cover
+    /// a type annotation, or nothing if it's not in a file of the program.
+    fn name_at(&self, location: &Location) -> &str {
+        let text = self
+            .file(&location.file_name)
+            .and_then(|file| {
+                file.source
+                    .get(location.span.start as usize..location.span.end as usize)
+            })
+            .unwrap_or_default();
+        let end = text
+            .find(|c: char| !(c.is_alphanumeric() || c == '_' || c == '$'))
+            .unwrap_or(text.len());
+        &text[..end]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TestProject;
+
+    const MATH: &str = "export function square(n: number): number { return n * n; }";
+    const MAIN: &str = r#"import { square } from "./math";
+let a = square;
+let b = square;"#;
+
+    fn project() -> TestProject {
+        TestProject::new()
+            .file("/math.ts", MATH)
+            .file("/main.ts", MAIN)
+            .roots(&["/main.ts"])
+    }
+
+    #[test]
+    fn test_definition_across_files() {
+        let program = project().program();
+        let offset = MAIN.rfind("square").unwrap() as u32;
+        let definitions = program.definition_at_position("/main.ts", offset);
+
+        assert_eq!(definitions.len(), 1);
+        assert_eq!(definitions[0].file_name, "/math.ts");
+        assert_eq!(
+            definitions[0].span.start,
+            MATH.find("square").unwrap() as u32
+        );
+    }
+
+    #[test]
+    fn test_references_across_files() {
+        let program = project().program();
+        let offset = MATH.find("square").unwrap() as u32;
+        let references = program.references_at_position("/math.ts", offset);
+
+        let found: Vec<(&str, u32)> = references
+            .iter()
+            .map(|l| (l.file_name.as_str(), l.span.start))
+            .collect();
+        let mut expected: Vec<(&str, u32)> = MAIN
+            .match_indices("square")
+            .map(|(i, _)| ("/main.ts", i as u32))
+            .collect();
+        expected.push(("/math.ts", MATH.find("square").unwrap() as u32));
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn test_references_of_local_parameter() {
+        let program = project().program();
+        let offset = MATH.find("n: number").unwrap() as u32;
+        let references = program.references_at_position("/math.ts", offset);
+        assert_eq!(references.len(), 3);
+        assert!(references.iter().all(|l| l.file_name == "/math.ts"));
+    }
+
+    #[test]
+    fn test_rename_across_files() {
+        let program = project().program();
+        let offset = MAIN.rfind("square").unwrap() as u32;
+        let info = program
+            .prepare_rename_at_position("/main.ts", offset)
+            .unwrap();
+        assert_eq!(info.name, "square");
+
+        let edits = program
+            .rename_at_position("/main.ts", offset, "area")
+            .unwrap();
+        let mut found: Vec<(&str, u32)> = edits
+            .iter()
+            .map(|l| (l.file_name.as_str(), l.span.start))
+            .collect();
+        found.sort();
+        let mut expected: Vec<(&str, u32)> = MAIN
+            .match_indices("square")
+            .map(|(i, _)| ("/main.ts", i as u32))
+            .collect();
+        expected.push(("/math.ts", MATH.find("square").unwrap() as u32));
+        assert_eq!(found, expected);
+
+        assert_eq!(
+            program.rename_at_position("/main.ts", offset, "class"),
+            Err("'class' is not a valid identifier.".to_string())
+        );
+        assert_eq!(
+            program.prepare_rename_at_position("/main.ts", MAIN.find("let").unwrap() as u32),
+            Err("You cannot rename this element.".to_string())
+        );
+    }
+
+    #[test]
+    fn test_rename_keeps_import_aliases() {
+        const ALIASED: &str = r#"import { square as sq } from "./math";
+export { square } from "./math";
+let c = sq(2);"#;
+        let program = TestProject::new()
+            .file("/math.ts", MATH)
+            .file("/aliased.ts", ALIASED)
+            .roots(&["/aliased.ts"])
+            .program();
+        let texts = |file: &str, offset: usize, new_name: &str| -> Vec<(String, u32)> {
+            let mut edits: Vec<(String, u32)> = program
+                .rename_at_position(file, offset as u32, new_name)
+                .unwrap()
+                .into_iter()
+                .map(|l| (l.file_name, l.span.start))
+                .collect();
+            edits.sort();
+            edits
+        };
+
+        // Renaming the function changes what the import names, but not the local alias
+        let edits = texts("/math.ts", MATH.find("square").unwrap(), "area");
+        assert!(edits.contains(&(
+            "/aliased.ts".to_string(),
+            ALIASED.find("square").unwrap() as u32
+        )));
+        assert!(edits.contains(&("/math.ts".to_string(), MATH.find("square").unwrap() as u32)));
+        assert!(
+            edits
+                .iter()
+                .all(|(_, start)| *start != ALIASED.rfind("sq(").unwrap() as u32)
+        );
+
+        // Renaming the alias only changes the file declaring it
+        let edits = texts("/aliased.ts", ALIASED.rfind("sq(").unwrap(), "power");
+        let expected: Vec<(String, u32)> = ALIASED
+            .match_indices("sq")
+            .filter(|(i, _)| !ALIASED[*i..].starts_with("square"))
+            .map(|(i, _)| ("/aliased.ts".to_string(), i as u32))
+            .collect();
+        assert_eq!(edits, expected);
+    }
+}
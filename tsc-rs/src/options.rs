@@ -0,0 +1,332 @@
+use crate::parser::is_declaration_file;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Settings that control how a [`Program`](crate::program::Program) is checked.
+///
+/// New options are added as fields with a `Default` that matches the current behavior,
+/// so constructing with `..Default::default()` keeps working as the set grows.
+///
+/// Deserializes from the camelCase `compilerOptions` shape used by `tsconfig.json`.
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default, rename_all = "camelCase", deny_unknown_fields)]
+pub struct CompilerOptions {
+    /// Directory emitted JavaScript is written to; next to each source when unset.
+    pub out_dir: Option<PathBuf>,
+    /// Directory whose layout is mirrored under `out_dir`; defaults to the common
+    /// directory of all source files.
+    pub root_dir: Option<PathBuf>,
+    /// Language version emitted JavaScript must run on; newer syntax is lowered to fit.
+    pub target: ScriptTarget,
+    /// How `import` and `export` are emitted.
+    pub module: ModuleKind,
+    /// Checks without emitting any JavaScript.
+    pub no_emit: bool,
+    /// Emits nothing when checking reports an error.
+    pub no_emit_on_error: bool,
+    /// Reports code that can't be transpiled one file at a time, as esbuild and swc do.
+    pub isolated_modules: bool,
+    /// Emits `const enum` declarations as objects, although reads of their members are
+    /// still replaced with the values. `isolatedModules` implies this.
+    pub preserve_const_enums: bool,
+    /// Checks decorators with the legacy semantics that predate stage 3 decorators.
+    pub experimental_decorators: bool,
+    /// Reports `throw` statements whose value isn't an `Error`, which lose the stack trace.
+    pub only_throw_error: bool,
+    /// Reports switch cases that run on into the next case without `break` or `return`.
+    pub no_fallthrough_cases_in_switch: bool,
+    /// Reports class members that override a base class member without `override`.
+    pub no_implicit_override: bool,
+    /// Prints types in error messages in full, however long, instead of eliding members.
+    pub no_error_truncation: bool,
+    /// Names files by the path they were imported through instead of following symbolic
+    /// links, so a package linked into two places is two sets of files.
+    pub preserve_symlinks: bool,
+    /// Reports imports that name a file with different casing than it has on disk, which
+    /// only resolve on file systems that ignore case. On unless `false`.
+    pub force_consistent_casing_in_file_names: Option<bool>,
+    /// Reports only syntax errors in declaration files. What they declare is still used to
+    /// check the files importing them.
+    pub skip_lib_check: bool,
+    /// Like `skipLibCheck`, but only for declaration files marked
+    /// `/// <reference no-default-lib="true"/>`, as the lib's own are.
+    pub skip_default_lib_check: bool,
+    /// Records each step of module resolution in
+    /// [`Program::resolution_trace`](crate::program::Program::resolution_trace).
+    pub trace_resolution: bool,
+    /// Warns about files that import each other, directly or through other files.
+    pub report_circular_imports: bool,
+    /// Reports diagnostics with these codes as errors or warnings whatever they usually
+    /// are, or not at all, like `{ "2322": "warning", "7006": "off" }`.
+    pub diagnostic_severities: BTreeMap<u32, DiagnosticSeverity>,
+    /// Default for every flag in the strict family that isn't set itself. Off unless set,
+    /// as in tsc.
+    pub strict: Option<bool>,
+    /// Keeps `null` and `undefined` out of types that don't name them.
+    pub strict_null_checks: Option<bool>,
+    /// Checks function parameters contravariantly instead of both ways.
+    pub strict_function_types: Option<bool>,
+    /// Reports expressions and declarations implicitly typed `any`.
+    pub no_implicit_any: Option<bool>,
+    /// Types `catch` clause variables as `unknown` instead of `any`.
+    pub use_unknown_in_catch_variables: Option<bool>,
+    /// Types `this` in object literal methods as the literal instead of `any`.
+    pub no_implicit_this: Option<bool>,
+    /// Types the `call`, `apply` and `bind` methods of functions after their signatures
+    /// instead of `any`.
+    pub strict_bind_call_apply: Option<bool>,
+    /// Reports class properties that are neither initialized nor assigned in the
+    /// constructor, under strictNullChecks.
+    pub strict_property_initialization: Option<bool>,
+    /// Emits `"use strict"` at the top of files that aren't modules, which always are.
+    pub always_strict: Option<bool>,
+}
+
+/// What diagnostics with a code in `diagnosticSeverities` are reported as.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DiagnosticSeverity {
+    Error,
+    Warning,
+    Off,
+}
+
+impl CompilerOptions {
+    /// The module system `file_name` is emitted for: `.mts` files are always ES modules
+    /// and `.cts` files always CommonJS, whatever `module` says.
+    pub fn module_for(&self, file_name: &str) -> ModuleKind {
+        if file_name.ends_with(".mts") {
+            ModuleKind::ESNext
+        } else if file_name.ends_with(".cts") {
+            ModuleKind::CommonJS
+        } else {
+            self.module
+        }
+    }
+
+    /// Whether semantic errors in the file `file_name` with `source` go unreported, under
+    /// `skipLibCheck` or `skipDefaultLibCheck`.
+    pub fn skips_check(&self, file_name: &str, source: &str) -> bool {
+        is_declaration_file(file_name)
+            && (self.skip_lib_check || (self.skip_default_lib_check && is_default_lib(source)))
+    }
+
+    /// The strict family flags the checker consults, each resolved against `strict`.
+    pub fn strictness(&self) -> Strictness {
+        let flag = |option: Option<bool>| option.or(self.strict).unwrap_or(false);
+        Strictness {
+            strict_null_checks: flag(self.strict_null_checks),
+            strict_function_types: flag(self.strict_function_types),
+            no_implicit_any: flag(self.no_implicit_any),
+            use_unknown_in_catch_variables: flag(self.use_unknown_in_catch_variables),
+            no_implicit_this: flag(self.no_implicit_this),
+            strict_bind_call_apply: flag(self.strict_bind_call_apply),
+            strict_property_initialization: flag(self.strict_property_initialization),
+            always_strict: flag(self.always_strict),
+        }
+    }
+}
+
+/// Whether `source` marks itself as a default lib with a `no-default-lib` directive among
+/// the comments it starts with.
+fn is_default_lib(source: &str) -> bool {
+    source
+        .lines()
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with("//"))
+        .any(|line| {
+            line.starts_with("///")
+                && line.contains("<reference")
+                && line.contains("no-default-lib=\"true\"")
+        })
+}
+
+/// The strict family flags that change how code is checked and emitted, all off by
+/// default.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Strictness {
+    pub strict_null_checks: bool,
+    pub strict_function_types: bool,
+    pub no_implicit_any: bool,
+    pub use_unknown_in_catch_variables: bool,
+    pub no_implicit_this: bool,
+    pub strict_bind_call_apply: bool,
+    pub strict_property_initialization: bool,
+    pub always_strict: bool,
+}
+
+impl Strictness {
+    /// Every flag on, as under `strict`.
+    pub const STRICT: Strictness = Strictness {
+        strict_null_checks: true,
+        strict_function_types: true,
+        no_implicit_any: true,
+        use_unknown_in_catch_variables: true,
+        no_implicit_this: true,
+        strict_bind_call_apply: true,
+        strict_property_initialization: true,
+        always_strict: true,
+    };
+}
+
+/// The module system emitted JavaScript is written for.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ModuleKind {
+    /// `require()` calls and assignments to `exports`.
+    CommonJS,
+    ES2015,
+    ES2020,
+    ES2022,
+    /// ECMAScript modules, emitted as written.
+    #[default]
+    ESNext,
+}
+
+impl ModuleKind {
+    pub fn is_es_module(self) -> bool {
+        self != Self::CommonJS
+    }
+}
+
+impl std::str::FromStr for ModuleKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value.to_ascii_lowercase().as_str() {
+            "commonjs" => Self::CommonJS,
+            "es6" | "es2015" => Self::ES2015,
+            "es2020" => Self::ES2020,
+            "es2022" => Self::ES2022,
+            "esnext" => Self::ESNext,
+            _ => {
+                return Err(format!(
+                    "Argument for '--module' option must be: 'commonjs', 'es6', 'es2015', 'es2020', 'es2022', 'esnext'. Got '{}'",
+                    value
+                ));
+            }
+        })
+    }
+}
+
+impl TryFrom<String> for ModuleKind {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+/// An ECMAScript version, ordered from oldest to newest.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(try_from = "String")]
+pub enum ScriptTarget {
+    ES5,
+    ES2015,
+    ES2016,
+    ES2017,
+    ES2018,
+    ES2019,
+    ES2020,
+    ES2021,
+    ES2022,
+    /// The latest syntax; nothing is lowered.
+    #[default]
+    ESNext,
+}
+
+impl std::str::FromStr for ScriptTarget {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        Ok(match value.to_ascii_lowercase().as_str() {
+            "es5" => Self::ES5,
+            "es6" | "es2015" => Self::ES2015,
+            "es2016" => Self::ES2016,
+            "es2017" => Self::ES2017,
+            "es2018" => Self::ES2018,
+            "es2019" => Self::ES2019,
+            "es2020" => Self::ES2020,
+            "es2021" => Self::ES2021,
+            "es2022" => Self::ES2022,
+            "esnext" => Self::ESNext,
+            _ => {
+                return Err(format!(
+                    "Argument for '--target' option must be: 'es5', 'es6', 'es2015', 'es2016', 'es2017', 'es2018', 'es2019', 'es2020', 'es2021', 'es2022', 'esnext'. Got '{}'",
+                    value
+                ));
+            }
+        })
+    }
+}
+
+impl TryFrom<String> for ScriptTarget {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_target() {
+        let options: CompilerOptions = serde_json::from_str(r#"{ "target": "ES2017" }"#).unwrap();
+        assert_eq!(options.target, ScriptTarget::ES2017);
+        assert_eq!("es6".parse(), Ok(ScriptTarget::ES2015));
+        assert!(ScriptTarget::ES5 < ScriptTarget::ESNext);
+
+        let options: CompilerOptions = serde_json::from_str(r#"{ "module": "CommonJS" }"#).unwrap();
+        assert_eq!(options.module, ModuleKind::CommonJS);
+        assert!(!options.module.is_es_module());
+        assert!(
+            serde_json::from_str::<CompilerOptions>(r#"{ "target": "es4" }"#)
+                .unwrap_err()
+                .to_string()
+                .contains("Got 'es4'")
+        );
+    }
+
+    #[test]
+    fn test_module_for_file_name() {
+        let options = CompilerOptions {
+            module: ModuleKind::CommonJS,
+            ..Default::default()
+        };
+        assert_eq!(options.module_for("a.ts"), ModuleKind::CommonJS);
+        assert_eq!(options.module_for("a.mts"), ModuleKind::ESNext);
+        assert_eq!(
+            CompilerOptions::default().module_for("a.cts"),
+            ModuleKind::CommonJS
+        );
+    }
+
+    #[test]
+    fn test_strictness() {
+        assert_eq!(
+            CompilerOptions::default().strictness(),
+            Strictness::default()
+        );
+        let options: CompilerOptions = serde_json::from_str(r#"{ "strict": true }"#).unwrap();
+        assert_eq!(options.strictness(), Strictness::STRICT);
+
+        let options: CompilerOptions =
+            serde_json::from_str(r#"{ "strict": false, "strictNullChecks": true }"#).unwrap();
+        let strictness = options.strictness();
+        assert!(strictness.strict_null_checks);
+        assert!(!strictness.strict_function_types);
+        assert!(!strictness.no_implicit_any);
+        assert!(!strictness.use_unknown_in_catch_variables);
+
+        let options: CompilerOptions = serde_json::from_str(
+            r#"{ "strict": true, "noImplicitAny": false, "alwaysStrict": true }"#,
+        )
+        .unwrap();
+        assert!(options.strictness().strict_null_checks);
+        assert!(!options.strictness().no_implicit_any);
+    }
+}
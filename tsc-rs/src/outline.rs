@@ -0,0 +1,365 @@
+use crate::parser::{parse_typescript_recovering, source_type_for};
+use crate::program::Program;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutlineKind {
+    Namespace,
+    Class,
+    Interface,
+    TypeAlias,
+    Enum,
+    EnumMember,
+    Function,
+    Variable,
+    Constant,
+    Constructor,
+    Method,
+    Property,
+}
+
+/// A declaration in a file's outline, with the declarations it contains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutlineItem {
+    pub name: String,
+    pub kind: OutlineKind,
+    /// The whole declaration.
+    pub span: Span,
+    /// The declaration's name.
+    pub name_span: Span,
+    pub children: Vec<OutlineItem>,
+}
+
+impl OutlineItem {
+    fn new(name: &str, kind: OutlineKind, span: Span, name_start: u32) -> Self {
+        Self {
+            name: name.to_string(),
+            kind,
+            span,
+            // Binding spans take in type annotations
+            name_span: Span::new(name_start, name_start + name.len() as u32),
+            children: Vec::new(),
+        }
+    }
+
+    fn with_children(mut self, children: Vec<OutlineItem>) -> Self {
+        self.children = children;
+        self
+    }
+}
+
+/// The outline of a list of statements. Only functions and classes are outlined in
+/// function bodies, since their variables are implementation details.
+fn statements(body: &[Statement], in_function: bool) -> Vec<OutlineItem> {
+    body.iter()
+        .flat_map(|statement| match statement {
+            Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                Some(declaration) => self::declaration(declaration, export.span, in_function),
+                None => Vec::new(),
+            },
+            Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+                ExportDefaultDeclarationKind::FunctionDeclaration(function) => {
+                    vec![self::function(function, export.span, "default")]
+                }
+                ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                    vec![self::class(class, export.span, "default")]
+                }
+                _ => Vec::new(),
+            },
+            statement => match statement.as_declaration() {
+                Some(declaration) => self::declaration(declaration, statement.span(), in_function),
+                None => Vec::new(),
+            },
+        })
+        .collect()
+}
+
+/// `span` covers the declaration along with any `export` before it.
+fn declaration(declaration: &Declaration, span: Span, in_function: bool) -> Vec<OutlineItem> {
+    match declaration {
+        Declaration::VariableDeclaration(decl) if !in_function => {
+            let kind = match decl.kind.is_const() {
+                true => OutlineKind::Constant,
+                false => OutlineKind::Variable,
+            };
+            decl.declarations
+                .iter()
+                .flat_map(|declarator| {
+                    let span = match decl.declarations.len() {
+                        1 => span,
+                        _ => declarator.span,
+                    };
+                    let children = match &declarator.init {
+                        Some(Expression::ArrowFunctionExpression(arrow)) => {
+                            statements(&arrow.body.statements, true)
+                        }
+                        Some(Expression::FunctionExpression(function)) => function
+                            .body
+                            .as_ref()
+                            .map_or_else(Vec::new, |body| statements(&body.statements, true)),
+                        _ => Vec::new(),
+                    };
+                    declarator
+                        .id
+                        .get_binding_identifiers()
+                        .into_iter()
+                        .map(move |ident| {
+                            OutlineItem::new(&ident.name, kind, span, ident.span.start)
+                                .with_children(children.clone())
+                        })
+                })
+                .collect()
+        }
+        Declaration::FunctionDeclaration(function) => {
+            let name = function
+                .id
+                .as_ref()
+                .map_or("default", |id| id.name.as_str());
+            vec![self::function(function, span, name)]
+        }
+        Declaration::ClassDeclaration(class) => {
+            let name = class.id.as_ref().map_or("default", |id| id.name.as_str());
+            vec![self::class(class, span, name)]
+        }
+        Declaration::TSInterfaceDeclaration(interface) => {
+            let members = interface
+                .body
+                .body
+                .iter()
+                .filter_map(|signature| match signature {
+                    TSSignature::TSPropertySignature(property) => {
+                        let (name, start) = key_name(&property.key)?;
+                        let item =
+                            OutlineItem::new(&name, OutlineKind::Property, property.span, start);
+                        Some(item)
+                    }
+                    TSSignature::TSMethodSignature(method) => {
+                        let (name, start) = key_name(&method.key)?;
+                        Some(OutlineItem::new(
+                            &name,
+                            OutlineKind::Method,
+                            method.span,
+                            start,
+                        ))
+                    }
+                    _ => None,
+                })
+                .collect();
+            let id = &interface.id;
+            vec![
+                OutlineItem::new(&id.name, OutlineKind::Interface, span, id.span.start)
+                    .with_children(members),
+            ]
+        }
+        Declaration::TSTypeAliasDeclaration(alias) => {
+            let id = &alias.id;
+            vec![OutlineItem::new(
+                &id.name,
+                OutlineKind::TypeAlias,
+                span,
+                id.span.start,
+            )]
+        }
+        Declaration::TSEnumDeclaration(decl) => {
+            let members = decl
+                .members
+                .iter()
+                .map(|member| {
+                    let (name, start) = match &member.id {
+                        TSEnumMemberName::Identifier(ident) => {
+                            (ident.name.to_string(), ident.span.start)
+                        }
+                        TSEnumMemberName::String(string) => {
+                            (string.value.to_string(), string.span.start + 1)
+                        }
+                    };
+                    OutlineItem::new(&name, OutlineKind::EnumMember, member.span, start)
+                })
+                .collect();
+            let id = &decl.id;
+            vec![
+                OutlineItem::new(&id.name, OutlineKind::Enum, span, id.span.start)
+                    .with_children(members),
+            ]
+        }
+        Declaration::TSModuleDeclaration(module) => vec![self::module(module, span, in_function)],
+        _ => Vec::new(),
+    }
+}
+
+fn module(module: &TSModuleDeclaration, span: Span, in_function: bool) -> OutlineItem {
+    let (name, start) = match &module.id {
+        TSModuleDeclarationName::Identifier(ident) => (ident.name.to_string(), ident.span.start),
+        TSModuleDeclarationName::StringLiteral(string) => {
+            (format!("\"{}\"", string.value), string.span.start)
+        }
+    };
+    let children = match &module.body {
+        Some(TSModuleDeclarationBody::TSModuleBlock(block)) => statements(&block.body, in_function),
+        // `namespace a.b {}` nests `b` in `a`
+        Some(TSModuleDeclarationBody::TSModuleDeclaration(inner)) => {
+            vec![self::module(inner, inner.span, in_function)]
+        }
+        None => Vec::new(),
+    };
+    OutlineItem::new(&name, OutlineKind::Namespace, span, start).with_children(children)
+}
+
+fn function(function: &Function, span: Span, name: &str) -> OutlineItem {
+    let start = function.id.as_ref().map_or(span.start, |id| id.span.start);
+    let children = function
+        .body
+        .as_ref()
+        .map_or_else(Vec::new, |body| statements(&body.statements, true));
+    OutlineItem::new(name, OutlineKind::Function, span, start).with_children(children)
+}
+
+fn class(class: &Class, span: Span, name: &str) -> OutlineItem {
+    let start = class.id.as_ref().map_or(span.start, |id| id.span.start);
+    let members = class
+        .body
+        .body
+        .iter()
+        .filter_map(|element| match element {
+            ClassElement::MethodDefinition(method) => {
+                let kind = match method.kind {
+                    MethodDefinitionKind::Constructor => OutlineKind::Constructor,
+                    MethodDefinitionKind::Get | MethodDefinitionKind::Set => OutlineKind::Property,
+                    MethodDefinitionKind::Method => OutlineKind::Method,
+                };
+                let (name, start) = key_name(&method.key)?;
+                let children = method
+                    .value
+                    .body
+                    .as_ref()
+                    .map_or_else(Vec::new, |body| statements(&body.statements, true));
+                Some(OutlineItem::new(&name, kind, method.span, start).with_children(children))
+            }
+            ClassElement::PropertyDefinition(property) => {
+                let (name, start) = key_name(&property.key)?;
+                Some(OutlineItem::new(
+                    &name,
+                    OutlineKind::Property,
+                    property.span,
+                    start,
+                ))
+            }
+            ClassElement::AccessorProperty(property) => {
+                let (name, start) = key_name(&property.key)?;
+                Some(OutlineItem::new(
+                    &name,
+                    OutlineKind::Property,
+                    property.span,
+                    start,
+                ))
+            }
+            _ => None,
+        })
+        .collect();
+    OutlineItem::new(name, OutlineKind::Class, span, start).with_children(members)
+}
+
+/// The name a member key gives, where it's written, unless it's computed.
+fn key_name(key: &PropertyKey) -> Option<(String, u32)> {
+    match key {
+        PropertyKey::StaticIdentifier(ident) => Some((ident.name.to_string(), ident.span.start)),
+        PropertyKey::PrivateIdentifier(ident) => {
+            Some((format!("#{}", ident.name), ident.span.start))
+        }
+        PropertyKey::StringLiteral(string) => {
+            Some((string.value.to_string(), string.span.start + 1))
+        }
+        PropertyKey::NumericLiteral(number) => Some((number.value.to_string(), number.span.start)),
+        _ => None,
+    }
+}
+
+impl Program {
+    /// The declarations in a file, nested in the ones they're declared in, for outline
+    /// views and breadcrumbs.
+    pub fn outline(&self, file_name: &str) -> Vec<OutlineItem> {
+        let Some(file) = self.file(file_name) else {
+            return Vec::new();
+        };
+        let parsed = parse_typescript_recovering(&file.source, source_type_for(file_name));
+        statements(&parsed.program.body, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::TestProject;
+
+    fn outline(source: &str) -> Vec<OutlineItem> {
+        TestProject::new()
+            .file("/main.ts", source)
+            .roots(&["/main.ts"])
+            .program()
+            .outline("/main.ts")
+    }
+
+    fn tree(items: &[OutlineItem]) -> String {
+        items
+            .iter()
+            .map(|item| match item.children.is_empty() {
+                true => format!("{:?} {}", item.kind, item.name),
+                false => format!("{:?} {} [{}]", item.kind, item.name, tree(&item.children)),
+            })
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    #[test]
+    fn test_outline() {
+        let source = r#"import { x } from "./x";
+export const limit: number = 10, other = 2;
+let count = 0;
+export class Counter {
+    static #instances = 0;
+    value = 0;
+    constructor() {}
+    get doubled() { return this.value * 2; }
+    increment(): void {
+        const step = 1;
+        function helper() {}
+    }
+}
+interface Shape { area: number; scale(by: number): void }
+type Id = string;
+enum Color { Red, "Light Blue" }
+namespace Geometry.Shapes {
+    export function circle() {}
+}
+export default function () {}
+"#;
+        let items = outline(source);
+        assert_eq!(
+            tree(&items),
+            "Constant limit, Constant other, Variable count, \
+             Class Counter [Property #instances, Property value, Constructor constructor, Property doubled, Method increment [Function helper]], \
+             Interface Shape [Property area, Method scale], TypeAlias Id, \
+             Enum Color [EnumMember Red, EnumMember Light Blue], \
+             Namespace Geometry [Namespace Shapes [Function circle]], Function default"
+        );
+
+        let text = |span: Span| &source[span.start as usize..span.end as usize];
+        assert_eq!(text(items[0].name_span), "limit");
+        assert_eq!(text(items[0].span), "limit: number = 10");
+        assert!(text(items[3].span).starts_with("export class Counter {"));
+        assert_eq!(text(items[3].name_span), "Counter");
+        assert_eq!(text(items[8].span), "export default function () {}");
+    }
+
+    #[test]
+    fn test_function_variables() {
+        let items = outline(
+            "const run = () => {
+    let local = 1;
+    class Task {}
+};",
+        );
+        assert_eq!(tree(&items), "Constant run [Class Task]");
+    }
+}
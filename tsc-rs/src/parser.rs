@@ -1,38 +1,101 @@
+use crate::stack::with_large_stack;
 use oxc_allocator::Allocator;
 use oxc_ast::ast::Program;
 use oxc_parser::Parser;
-use oxc_span::SourceType;
+use oxc_span::{SourceType, Span};
 
 pub struct TypeScriptProgram {
     pub program: Program<'static>,
+    /// Syntax errors the parser recovered from; `program` is what it made of the rest.
+    pub errors: Vec<SyntaxError>,
     _allocator: Allocator, // Keep allocator alive as long as program
 }
 
+/// A syntax error reported by the parser.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SyntaxError {
+    pub message: String,
+    pub span: Option<Span>,
+}
+
 impl std::fmt::Debug for TypeScriptProgram {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("TypeScriptProgram")
             .field("program", &self.program)
+            .field("errors", &self.errors)
             .finish_non_exhaustive()
     }
 }
 
+/// How the file named `file_name` is parsed: JSX is allowed in `.tsx` files and only
+/// declarations in `.d.ts` ones. Every file may use `import` and `export`, since
+/// TypeScript compiles them to `require` for CommonJS.
+pub fn source_type_for(file_name: &str) -> SourceType {
+    match SourceType::from_path(file_name) {
+        Ok(source_type) if source_type.is_typescript() => source_type.with_module(true),
+        _ => SourceType::ts(),
+    }
+}
+
+/// Whether `file_name` is a declaration file, which only declares types for JavaScript
+/// found elsewhere.
+pub fn is_declaration_file(file_name: &str) -> bool {
+    [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|extension| file_name.ends_with(extension))
+}
+
+/// Parses `source_code` as a `.ts` file, failing with the first syntax error if there are any.
 pub fn parse_typescript(source_code: &str) -> Result<TypeScriptProgram, String> {
+    parse_typescript_as(source_code, SourceType::ts())
+}
+
+/// Parses `source_code` as `source_type`, failing with the first syntax error if there are any.
+pub fn parse_typescript_as(
+    source_code: &str,
+    source_type: SourceType,
+) -> Result<TypeScriptProgram, String> {
+    let ts_program = parse_typescript_recovering(source_code, source_type);
+    match ts_program.errors.first() {
+        Some(error) => Err(error.message.clone()),
+        None => Ok(ts_program),
+    }
+}
+
+/// Parses `source_code`, keeping every syntax error along with the AST the parser
+/// recovered, so the rest of the file can still be checked.
+///
+/// Parsing happens on a thread with a large stack, so deeply nested code can't overflow
+/// the caller's.
+pub fn parse_typescript_recovering(
+    source_code: &str,
+    source_type: SourceType,
+) -> TypeScriptProgram {
+    with_large_stack(|| parse(source_code, source_type))
+}
+
+fn parse(source_code: &str, source_type: SourceType) -> TypeScriptProgram {
     let allocator = Allocator::default();
-    let source_type = match SourceType::from_path("test.ts") {
-        Ok(st) => st.with_typescript(true).with_module(true),
-        Err(e) => return Err(format!("Unknown extension: {e:?}")),
-    };
 
     let ret = Parser::new(&allocator, source_code, source_type).parse();
 
     // ParserReturn is not a Result, but contains diagnostics if there were errors
-    if ret.errors.is_empty() {
-        Ok(TypeScriptProgram {
-            program: unsafe { std::mem::transmute(ret.program) },
-            _allocator: allocator,
+    let errors = ret
+        .errors
+        .iter()
+        .map(|error| SyntaxError {
+            message: error.message.to_string(),
+            span: error
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.first())
+                .map(|label| Span::sized(label.offset() as u32, label.len() as u32)),
         })
-    } else {
-        Err(ret.errors.first().unwrap().to_string())
+        .collect();
+    TypeScriptProgram {
+        program: unsafe { std::mem::transmute::<Program<'_>, Program<'static>>(ret.program) },
+        errors,
+        _allocator: allocator,
     }
 }
 
@@ -65,4 +128,38 @@ mod tests {
         // For now, this will pass because we're not doing type checking yet
         assert!(result.is_ok(), "Parser should accept invalid types for now");
     }
+
+    #[test]
+    fn test_parse_deeply_nested_source() {
+        let parens = format!("let x = {}1{};", "(".repeat(20_000), ")".repeat(20_000));
+        assert!(parse_typescript(&parens).is_ok());
+        let negations = format!("let x = {}1;", "!".repeat(50_000));
+        assert!(parse_typescript(&negations).is_ok());
+    }
+
+    #[test]
+    fn test_parse_recovering_keeps_every_error() {
+        let source = "const a;\nlet b: number = 1;\nconst c;\n";
+        assert!(parse_typescript(source).is_err());
+
+        let ts_program = parse_typescript_recovering(source, SourceType::ts());
+        assert_eq!(ts_program.errors.len(), 2);
+        let span = ts_program.errors[0].span.unwrap();
+        assert_eq!(&source[span.start as usize..span.end as usize], "a");
+        assert_eq!(ts_program.program.body.len(), 3);
+    }
+
+    #[test]
+    fn test_source_type_for_file_name() {
+        assert!(source_type_for("app.tsx").is_jsx());
+        assert!(!source_type_for("app.ts").is_jsx());
+        assert!(source_type_for("types.d.ts").is_typescript_definition());
+        assert!(source_type_for("types.d.mts").is_typescript_definition());
+        assert!(source_type_for("config.cts").is_module());
+        assert!(source_type_for("input").is_typescript());
+
+        let source = "const view = <div>{name}</div>;";
+        assert!(parse_typescript_as(source, source_type_for("view.ts")).is_err());
+        assert!(parse_typescript_as(source, source_type_for("view.tsx")).is_ok());
+    }
 }
@@ -0,0 +1,265 @@
+//! Yarn Plug'n'Play manifests, which say where each package is in projects that have no
+//! `node_modules` directory to look packages up in.
+//!
+//! Yarn keeps most packages zipped in its cache, and those are only found through a
+//! [`FileSystem`] that reads into zip archives; unplugged packages are plain directories.
+
+use crate::host::{FileSystem, normalize_path};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// A package the manifest knows about, identified by its name and reference.
+#[derive(Debug, Clone, PartialEq)]
+struct PnpPackage {
+    /// `None` for the top-level workspace.
+    name: Option<String>,
+    reference: Option<String>,
+    location: PathBuf,
+    /// The name each dependency is imported by, and the name and reference of the package
+    /// that provides it; `None` for a peer dependency nothing provides.
+    dependencies: Vec<(String, Option<(String, String)>)>,
+}
+
+/// The packages of a Yarn Plug'n'Play project and what each one may import.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PnpManifest {
+    /// The directory the manifest is in, which package locations are relative to.
+    dir: PathBuf,
+    packages: Vec<PnpPackage>,
+    /// Whether packages may import the top-level workspace's dependencies without
+    /// declaring them.
+    top_level_fallback: bool,
+}
+
+impl PnpManifest {
+    /// The manifest of the project `dir` is in: a `.pnp.data.json`, or the state inlined
+    /// in a `.pnp.cjs`, in `dir` or the nearest directory above it that has one.
+    pub fn find(dir: &Path, fs: &dyn FileSystem) -> Result<Option<Self>, String> {
+        for dir in dir.ancestors() {
+            let data = dir.join(".pnp.data.json");
+            if let Ok(json) = fs.read_file(&data) {
+                return Self::parse(dir, &json)
+                    .map(Some)
+                    .map_err(|err| format!("Could not read '{}': {}", data.display(), err));
+            }
+            let runtime = dir.join(".pnp.cjs");
+            if let Ok(source) = fs.read_file(&runtime) {
+                let json = inlined_state(&source).ok_or_else(|| {
+                    format!(
+                        "Could not find the state inlined in '{}'.",
+                        runtime.display()
+                    )
+                })?;
+                return Self::parse(dir, &json)
+                    .map(Some)
+                    .map_err(|err| format!("Could not read '{}': {}", runtime.display(), err));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Reads the manifest's JSON, whose package locations are relative to `dir`.
+    pub fn parse(dir: &Path, json: &str) -> Result<Self, String> {
+        let state: Value = serde_json::from_str(json).map_err(|err| err.to_string())?;
+        let registry = state
+            .get("packageRegistryData")
+            .and_then(Value::as_array)
+            .ok_or("Missing 'packageRegistryData'.")?;
+        let mut packages = Vec::new();
+        for entry in registry {
+            let [name, references] = entry.as_array().map(Vec::as_slice).unwrap_or_default() else {
+                return Err("Malformed entry in 'packageRegistryData'.".to_string());
+            };
+            for reference in references.as_array().into_iter().flatten() {
+                let [reference, info] = reference.as_array().map(Vec::as_slice).unwrap_or_default()
+                else {
+                    return Err("Malformed package in 'packageRegistryData'.".to_string());
+                };
+                let location = info
+                    .get("packageLocation")
+                    .and_then(Value::as_str)
+                    .ok_or("Missing 'packageLocation'.")?;
+                packages.push(PnpPackage {
+                    name: name.as_str().map(str::to_string),
+                    reference: reference.as_str().map(str::to_string),
+                    location: normalize_path(&dir.join(location)),
+                    dependencies: dependencies(info),
+                });
+            }
+        }
+        Ok(Self {
+            dir: dir.to_path_buf(),
+            packages,
+            top_level_fallback: state
+                .get("enableTopLevelFallback")
+                .and_then(Value::as_bool)
+                .unwrap_or(false),
+        })
+    }
+
+    /// The directory the manifest is in.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether `file` is in the project, where packages are found through the manifest.
+    pub fn covers(&self, file: &Path) -> bool {
+        file.starts_with(&self.dir)
+    }
+
+    /// The directory of the package the file `issuer` imports as `name`, when its package
+    /// depends on one by that name.
+    pub fn package_location(&self, name: &str, issuer: &Path) -> Option<PathBuf> {
+        let owner = self
+            .packages
+            .iter()
+            .filter(|package| issuer.starts_with(&package.location))
+            .max_by_key(|package| package.location.components().count())?;
+        let find = |package: &PnpPackage| {
+            package
+                .dependencies
+                .iter()
+                .find(|(dependency, _)| dependency == name)
+                .map(|(_, provider)| provider.clone())
+        };
+        let provider = match find(owner) {
+            Some(provider) => provider,
+            None if self.top_level_fallback => {
+                let top_level = self
+                    .packages
+                    .iter()
+                    .find(|package| package.name.is_none())?;
+                find(top_level)?
+            }
+            None => None,
+        };
+        let (name, reference) = provider?;
+        self.packages
+            .iter()
+            .find(|package| {
+                package.name.as_deref() == Some(name.as_str())
+                    && package.reference.as_deref() == Some(reference.as_str())
+            })
+            .map(|package| package.location.clone())
+    }
+}
+
+/// The `packageDependencies` of a package: each is a name and either a reference to the
+/// package by that name, or the name and reference of the package it's an alias for.
+fn dependencies(info: &Value) -> Vec<(String, Option<(String, String)>)> {
+    let entries = info.get("packageDependencies").and_then(Value::as_array);
+    entries
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let [name, reference] = entry.as_array()?.as_slice() else {
+                return None;
+            };
+            let name = name.as_str()?.to_string();
+            let provider = match reference {
+                Value::String(reference) => Some((name.clone(), reference.clone())),
+                Value::Array(alias) => match alias.as_slice() {
+                    [Value::String(target), Value::String(reference)] => {
+                        Some((target.clone(), reference.clone()))
+                    }
+                    _ => None,
+                },
+                _ => None,
+            };
+            Some((name, provider))
+        })
+        .collect()
+}
+
+/// The JSON a `.pnp.cjs` inlines as the string `RAW_RUNTIME_STATE`.
+fn inlined_state(source: &str) -> Option<String> {
+    let start = source.find("RAW_RUNTIME_STATE")?;
+    let rest = source[start..].split_once('=')?.1.trim_start();
+    let quote = rest.chars().next().filter(|c| *c == '\'' || *c == '"')?;
+    let mut state = String::new();
+    let mut chars = rest[1..].chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next()? {
+                'n' => state.push('\n'),
+                't' => state.push('\t'),
+                escaped => state.push(escaped),
+            },
+            c if c == quote => return Some(state),
+            c => state.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::InMemoryFileSystem;
+
+    const STATE: &str = r#"{
+        "enableTopLevelFallback": true,
+        "packageRegistryData": [
+            [null, [[null, {
+                "packageLocation": "./",
+                "packageDependencies": [["left-pad", "npm:1.3.0"], ["pad", ["left-pad", "npm:1.3.0"]]]
+            }]]],
+            ["app", [["workspace:.", {
+                "packageLocation": "./",
+                "packageDependencies": [["left-pad", "npm:1.3.0"], ["pad", ["left-pad", "npm:1.3.0"]]]
+            }]]],
+            ["left-pad", [["npm:1.3.0", {
+                "packageLocation": "./.yarn/unplugged/left-pad-npm-1.3.0/node_modules/left-pad/",
+                "packageDependencies": [["left-pad", "npm:1.3.0"], ["missing-peer", null]]
+            }]]]
+        ]
+    }"#;
+
+    #[test]
+    fn test_package_location() {
+        let manifest = PnpManifest::parse(Path::new("/app"), STATE).unwrap();
+        let left_pad =
+            PathBuf::from("/app/.yarn/unplugged/left-pad-npm-1.3.0/node_modules/left-pad");
+        let issuer = Path::new("/app/src/main.ts");
+        assert_eq!(
+            manifest.package_location("left-pad", issuer),
+            Some(left_pad.clone())
+        );
+        assert_eq!(
+            manifest.package_location("pad", issuer),
+            Some(left_pad.clone())
+        );
+        assert_eq!(manifest.package_location("react", issuer), None);
+
+        let inside = left_pad.join("index.d.ts");
+        assert_eq!(manifest.package_location("missing-peer", &inside), None);
+        // Undeclared dependencies fall back to the top level's
+        assert_eq!(manifest.package_location("pad", &inside), Some(left_pad));
+        assert!(manifest.covers(issuer));
+        assert!(!manifest.covers(Path::new("/other/main.ts")));
+    }
+
+    #[test]
+    fn test_find() {
+        let mut fs = InMemoryFileSystem::new();
+        let inlined = STATE.replace('\n', "\\n").replace('\'', "\\'");
+        fs.add_file(
+            "/app/.pnp.cjs",
+            format!(
+                "#!/usr/bin/env node\nconst RAW_RUNTIME_STATE =\n'{}';\n",
+                inlined
+            ),
+        );
+        fs.add_file("/app/src/main.ts", "");
+
+        let manifest = PnpManifest::find(Path::new("/app/src"), &fs)
+            .unwrap()
+            .unwrap();
+        assert_eq!(manifest.dir(), Path::new("/app"));
+        assert_eq!(manifest.packages.len(), 3);
+        assert_eq!(PnpManifest::find(Path::new("/other"), &fs), Ok(None));
+
+        fs.add_file("/broken/.pnp.data.json", "{}");
+        assert!(PnpManifest::find(Path::new("/broken"), &fs).is_err());
+    }
+}
@@ -0,0 +1,1096 @@
+use crate::diagnostics::{Diagnostic, Message, filter_diagnostics};
+use crate::file_inclusion::FileInclusion;
+use crate::host::{FileSystem, normalize_path};
+use crate::incremental::{Answer, CheckCache, CheckInputs, Query};
+use crate::isolated_modules::{ModuleShape, check_isolated_modules, module_shape};
+use crate::options::CompilerOptions;
+use crate::parser::{
+    SyntaxError, parse_typescript_as, parse_typescript_recovering, source_type_for,
+};
+use crate::performance::{PerformanceStats, peak_memory};
+use crate::pnp::PnpManifest;
+use crate::resolver::{collect_module_specifiers, is_relative_specifier, resolve_module_name_with};
+use crate::rules::{Rule, RuleSet};
+use crate::stack::with_large_stack;
+use crate::symbols::ExportedSymbol;
+use crate::trace::Trace;
+use crate::type_checker::TypeChecker;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+
+#[derive(Debug, Clone)]
+pub struct SourceFile {
+    pub name: String,
+    pub source: String,
+    /// `(specifier, file name)` for each import the loader resolved.
+    pub resolved_modules: Vec<(String, String)>,
+}
+
+/// What checking reported for one file, as [`Program::check_streaming`] delivers it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FileDiagnostics {
+    pub file_name: String,
+    pub diagnostics: Vec<Diagnostic>,
+    /// How many files have been delivered so far, this one included, out of `total`.
+    pub completed: usize,
+    pub total: usize,
+}
+
+/// A checked file, with the syntax errors its AST was recovered from.
+#[derive(Clone)]
+pub(crate) struct CheckedFile {
+    pub checker: TypeChecker,
+    pub syntax_errors: Vec<SyntaxError>,
+    /// What the program's rules reported for the file.
+    pub rule_diagnostics: Vec<Diagnostic>,
+}
+
+/// A set of source files checked together with one set of compiler options.
+#[derive(Debug, Clone, Default)]
+pub struct Program {
+    options: CompilerOptions,
+    files: Vec<SourceFile>,
+    // Files named when the program was created, rather than found through imports
+    root_names: Vec<String>,
+    // Why roots picked out by a project's `tsconfig.json` are roots
+    root_inclusions: Vec<(String, FileInclusion)>,
+    load_diagnostics: Vec<Diagnostic>,
+    // Each module resolution step, when `traceResolution` is set
+    resolution_trace: Vec<String>,
+    // Results of checking each file, reused until their inputs change
+    cache: CheckCache,
+    // Custom checks run over each file after the type checker
+    rules: RuleSet,
+}
+
+impl Program {
+    pub fn new(options: CompilerOptions) -> Self {
+        Self {
+            options,
+            files: Vec::new(),
+            root_names: Vec::new(),
+            root_inclusions: Vec::new(),
+            load_diagnostics: Vec::new(),
+            resolution_trace: Vec::new(),
+            cache: CheckCache::default(),
+            rules: RuleSet::default(),
+        }
+    }
+
+    /// Loads the root files and everything they import through `fs`.
+    pub fn load(
+        options: CompilerOptions,
+        root_names: &[impl AsRef<Path>],
+        fs: &dyn FileSystem,
+    ) -> Self {
+        let mut program = Self::new(options);
+        let current_dir = fs.current_dir().unwrap_or_default();
+        let consistent_casing =
+            program.options.force_consistent_casing_in_file_names != Some(false);
+        let preserve_symlinks = program.options.preserve_symlinks;
+        let mut seen = HashSet::new();
+        // Where each resolved file really is, which takes reading its directories
+        let mut real_paths: HashMap<PathBuf, PathBuf> = HashMap::new();
+        let mut queue: VecDeque<PathBuf> = root_names
+            .iter()
+            .map(|name| normalize_path(&current_dir.join(name)))
+            .collect();
+        program.root_names = queue
+            .iter()
+            .map(|path| path.to_string_lossy().to_string())
+            .collect();
+        // Yarn Plug'n'Play projects say where their packages are instead of installing
+        // them in `node_modules`
+        let project_dir = queue
+            .front()
+            .and_then(|root| root.parent())
+            .unwrap_or(&current_dir);
+        let pnp = match PnpManifest::find(project_dir, fs) {
+            Ok(pnp) => pnp,
+            Err(message) => {
+                let root = program.root_names.first().cloned().unwrap_or_default();
+                program
+                    .load_diagnostics
+                    .push(Diagnostic::new(&root, message));
+                None
+            }
+        };
+
+        while let Some(path) = queue.pop_front() {
+            if !seen.insert(path.clone()) {
+                continue;
+            }
+            let name = path.to_string_lossy().to_string();
+            let source = match fs.read_file(&path) {
+                Ok(source) => source,
+                Err(_) => {
+                    program.load_diagnostics.push(Diagnostic::from_message(
+                        &name,
+                        Message::new(6053, &[&name]),
+                        None,
+                    ));
+                    continue;
+                }
+            };
+            let mut resolved_modules = Vec::new();
+            // Scoped so the AST, which borrows `source`, is dropped before `source` moves
+            {
+                let ts_program = parse_typescript_recovering(&source, source_type_for(&name));
+                for specifier in collect_module_specifiers(&ts_program.program) {
+                    let trace = match program.options.trace_resolution {
+                        true => Some(&mut program.resolution_trace),
+                        false => None,
+                    };
+                    let resolved =
+                        resolve_module_name_with(&specifier, &path, fs, pnp.as_ref(), trace);
+                    // Files are named by where they are on disk, however they're imported, so
+                    // a package linked in from a shared store is loaded once
+                    let resolved = resolved.map(|resolved| {
+                        let real = real_paths
+                            .entry(resolved.clone())
+                            .or_insert_with(|| fs.real_path(&resolved))
+                            .clone();
+                        (real, resolved)
+                    });
+                    match resolved {
+                        Some((real, resolved)) => {
+                            let differs_in_casing = real != resolved
+                                && real
+                                    .to_string_lossy()
+                                    .eq_ignore_ascii_case(&resolved.to_string_lossy());
+                            if consistent_casing && differs_in_casing {
+                                program.load_diagnostics.push(Diagnostic::from_message(
+                                    &name,
+                                    Message::new(1149, &[&resolved.display(), &real.display()]),
+                                    None,
+                                ));
+                            }
+                            let included = match preserve_symlinks && !differs_in_casing {
+                                true => resolved,
+                                false => real,
+                            };
+                            resolved_modules
+                                .push((specifier, included.to_string_lossy().to_string()));
+                            queue.push_back(included);
+                        }
+                        None if is_relative_specifier(&specifier) => {
+                            program.load_diagnostics.push(Diagnostic::from_message(
+                                &name,
+                                Message::new(2307, &[&specifier]),
+                                None,
+                            ))
+                        }
+                        None => {}
+                    }
+                }
+            }
+            program.files.push(SourceFile {
+                name,
+                source,
+                resolved_modules,
+            });
+        }
+        program
+    }
+
+    pub fn add_file(&mut self, name: impl Into<String>, source: impl Into<String>) {
+        let name = name.into();
+        self.root_names.push(name.clone());
+        self.files.push(SourceFile {
+            name,
+            source: source.into(),
+            resolved_modules: Vec::new(),
+        });
+    }
+
+    /// Runs `rule` over every file checked from now on, after the type checker.
+    pub fn add_rule(&mut self, rule: Arc<dyn Rule>) {
+        self.rules.add(rule);
+        // Results checked without the rule can't be reused
+        self.cache = CheckCache::default();
+    }
+
+    /// Replaces the contents of an existing file, keeping its resolved imports.
+    pub fn update_file(&mut self, name: &str, source: impl Into<String>) {
+        if let Some(file) = self.files.iter_mut().find(|f| f.name == name) {
+            file.source = source.into();
+        }
+    }
+
+    /// Every module resolution step taken while loading, if `traceResolution` was set.
+    pub fn resolution_trace(&self) -> &[String] {
+        &self.resolution_trace
+    }
+
+    pub fn options(&self) -> &CompilerOptions {
+        &self.options
+    }
+
+    pub fn files(&self) -> &[SourceFile] {
+        &self.files
+    }
+
+    /// The files named when the program was created, whether or not they could be read.
+    pub fn root_names(&self) -> &[String] {
+        &self.root_names
+    }
+
+    /// Why the root `file_name` is one, when a project's configuration picked it out.
+    pub(crate) fn root_inclusion(&self, file_name: &str) -> Option<&FileInclusion> {
+        self.root_inclusions
+            .iter()
+            .find(|(name, _)| name == file_name)
+            .map(|(_, inclusion)| inclusion)
+    }
+
+    /// Adds a problem found while loading, like an option of the project's `tsconfig.json`
+    /// that isn't one.
+    pub(crate) fn push_load_diagnostic(&mut self, diagnostic: Diagnostic) {
+        self.load_diagnostics.push(diagnostic);
+    }
+
+    pub(crate) fn set_root_inclusions(&mut self, inclusions: Vec<(String, FileInclusion)>) {
+        self.root_inclusions = inclusions;
+    }
+
+    pub fn file(&self, name: &str) -> Option<&SourceFile> {
+        self.files.iter().find(|f| f.name == name)
+    }
+
+    /// The answer to `query` about the file `name`, memoized until the file is checked
+    /// again: `answer` only runs the first time it's asked since.
+    pub(crate) fn answer(
+        &self,
+        name: &str,
+        query: Query,
+        answer: impl FnOnce(&TypeChecker) -> Answer,
+    ) -> Option<Answer> {
+        self.file(name)?;
+        // Brings every file's memo up to date with its inputs, dropping stale answers
+        self.check_files();
+        self.cache
+            .answer(name, query, |checked| answer(&checked.checker))
+    }
+
+    /// Checks one file and returns its checker, so callers can query recorded types.
+    pub fn check_file(&self, name: &str) -> Option<TypeChecker> {
+        let index = self.files.iter().position(|f| f.name == name)?;
+        self.check_files()
+            .into_iter()
+            .nth(index)
+            .map(|checked| checked.checker)
+    }
+
+    /// Checks every file, reporting diagnostics as `diagnosticSeverities` classifies their
+    /// codes and leaving out duplicates of the same message at the same place.
+    pub fn check(&self) -> Vec<Diagnostic> {
+        self.check_with_stats().0
+    }
+
+    /// Checks the program like [`Self::check`], also reporting how much work that was and
+    /// how long it took.
+    pub fn check_with_stats(&self) -> (Vec<Diagnostic>, PerformanceStats) {
+        self.check_measured(None)
+    }
+
+    /// [`Self::check_with_stats`], also recording each file's parse, bind, and check and
+    /// every relation between structured types into `trace`.
+    pub fn check_with_trace(&self, trace: &mut Trace) -> (Vec<Diagnostic>, PerformanceStats) {
+        self.check_measured(Some(trace))
+    }
+
+    /// Checks the program like [`Self::check`], handing each file's diagnostics to
+    /// `on_file` as soon as nothing left to check can change them, so callers can show
+    /// progress. Files come dependencies first rather than in file order.
+    ///
+    /// Diagnostics are filtered as [`Self::check`] filters them.
+    ///
+    /// Returns the diagnostics that aren't any one file's: those from loading the program,
+    /// and the `isolatedModules` and circular import reports made once every file is done.
+    pub fn check_streaming(&self, mut on_file: impl FnMut(FileDiagnostics)) -> Vec<Diagnostic> {
+        let total = self.files.len();
+        let mut completed = 0;
+        self.check_files_measured(
+            &mut PerformanceStats::default(),
+            None,
+            &mut |index, checked| {
+                completed += 1;
+                on_file(FileDiagnostics {
+                    file_name: self.files[index].name.clone(),
+                    diagnostics: self.file_diagnostics(index, checked),
+                    completed,
+                    total,
+                });
+            },
+        );
+        let mut diagnostics = self.load_diagnostics.clone();
+        diagnostics.extend(self.program_diagnostics());
+        filter_diagnostics(diagnostics, &self.options.diagnostic_severities)
+    }
+
+    fn check_measured(&self, trace: Option<&mut Trace>) -> (Vec<Diagnostic>, PerformanceStats) {
+        let mut stats = PerformanceStats {
+            files: self.files.len(),
+            lines: self.files.iter().map(|f| f.source.lines().count()).sum(),
+            ..Default::default()
+        };
+        let mut by_file: Vec<Vec<Diagnostic>> = vec![Vec::new(); self.files.len()];
+        self.check_files_measured(&mut stats, trace, &mut |index, checked| {
+            by_file[index] = self.file_diagnostics(index, checked);
+        });
+        let mut diagnostics = self.load_diagnostics.clone();
+        diagnostics.extend(by_file.into_iter().flatten());
+        diagnostics.extend(self.program_diagnostics());
+        stats.memory_used = peak_memory();
+        (
+            filter_diagnostics(diagnostics, &self.options.diagnostic_severities),
+            stats,
+        )
+    }
+
+    /// A checked file's syntax errors, type errors and rule diagnostics, reclassified by
+    /// `diagnosticSeverities` and without duplicates. Declaration files `skipLibCheck`
+    /// covers only have syntax errors.
+    fn file_diagnostics(&self, index: usize, checked: &CheckedFile) -> Vec<Diagnostic> {
+        let file = &self.files[index];
+        let file_name = &file.name;
+        let mut diagnostics: Vec<Diagnostic> = checked
+            .syntax_errors
+            .iter()
+            .map(|e| Diagnostic::from_syntax_error(file_name, e))
+            .collect();
+        if self.options.skips_check(file_name, &file.source) {
+            return filter_diagnostics(diagnostics, &self.options.diagnostic_severities);
+        }
+        diagnostics.extend(
+            checked
+                .checker
+                .get_type_errors()
+                .iter()
+                .map(|e| Diagnostic::from_type_error(file_name, e)),
+        );
+        diagnostics.extend(checked.rule_diagnostics.iter().cloned());
+        filter_diagnostics(diagnostics, &self.options.diagnostic_severities)
+    }
+
+    /// The diagnostics about how files fit together, made once every file is checked.
+    fn program_diagnostics(&self) -> Vec<Diagnostic> {
+        let mut diagnostics = Vec::new();
+        if self.options.isolated_modules {
+            diagnostics.extend(self.check_isolated_modules());
+        }
+        if self.options.report_circular_imports {
+            diagnostics.extend(self.import_cycles().into_iter().map(|cycle| {
+                Diagnostic::new(
+                    &cycle[0],
+                    format!("Circular import: {}.", cycle.join(" -> ")),
+                )
+                .warning()
+            }));
+        }
+        diagnostics
+    }
+
+    /// Reports what single-file transpilers can't handle; parse errors are already reported.
+    fn check_isolated_modules(&self) -> Vec<Diagnostic> {
+        let parsed: Vec<_> = self
+            .files
+            .iter()
+            .map(|file| parse_typescript_as(&file.source, source_type_for(&file.name)).ok())
+            .collect();
+        let shapes: HashMap<&str, ModuleShape> = self
+            .files
+            .iter()
+            .zip(&parsed)
+            .filter_map(|(file, ts_program)| {
+                let ts_program = ts_program.as_ref()?;
+                Some((
+                    file.name.as_str(),
+                    module_shape(&file.name, &ts_program.program),
+                ))
+            })
+            .collect();
+
+        let mut diagnostics = Vec::new();
+        for (file, ts_program) in self.files.iter().zip(&parsed) {
+            let Some(ts_program) = ts_program else {
+                continue;
+            };
+            let imports: HashMap<&str, &ModuleShape> = file
+                .resolved_modules
+                .iter()
+                .filter_map(|(specifier, resolved)| {
+                    Some((specifier.as_str(), shapes.get(resolved.as_str())?))
+                })
+                .collect();
+            diagnostics.extend(check_isolated_modules(
+                &file.name,
+                &ts_program.program,
+                &imports,
+            ));
+        }
+        diagnostics
+    }
+
+    /// Checks every file, dependencies first, returning results in file order.
+    ///
+    /// Files that import each other are checked again until their exports settle, so
+    /// what they import from later files in the cycle isn't left as `any`. Files with
+    /// syntax errors are checked as far as the parser could recover them.
+    pub(crate) fn check_files(&self) -> Vec<CheckedFile> {
+        self.check_files_measured(&mut PerformanceStats::default(), None, &mut |_, _| {})
+    }
+
+    /// [`Self::check_files`], adding the time spent, files rechecked and what the results
+    /// hold to `stats` and what was done when to `trace`. Each file's index and result go
+    /// to `on_checked` once it won't be checked again.
+    fn check_files_measured(
+        &self,
+        stats: &mut PerformanceStats,
+        mut trace: Option<&mut Trace>,
+        on_checked: &mut dyn FnMut(usize, &CheckedFile),
+    ) -> Vec<CheckedFile> {
+        let mut results: Vec<Option<CheckedFile>> = self.files.iter().map(|_| None).collect();
+        let mut exports: HashMap<&str, Vec<ExportedSymbol>> = HashMap::new();
+        let mut done = |index: usize, checked: &CheckedFile, stats: &mut PerformanceStats| {
+            stats.symbols += checked.checker.symbols().len();
+            stats.types += checked.checker.node_types().len();
+            on_checked(index, checked);
+        };
+
+        let order = self.check_order();
+        let cyclic = self.cyclic_files();
+        for &index in &order {
+            let checked = self.check_file_at(index, &mut exports, stats, trace.as_deref_mut());
+            if !cyclic.contains(&index) {
+                done(index, &checked, stats);
+            }
+            results[index] = Some(checked);
+        }
+        let in_cycles: Vec<usize> = order.into_iter().filter(|i| cyclic.contains(i)).collect();
+        for _ in 0..in_cycles.len() {
+            let mut settled = true;
+            for &index in &in_cycles {
+                let before = exports.get(self.files[index].name.as_str()).cloned();
+                results[index] =
+                    Some(self.check_file_at(index, &mut exports, stats, trace.as_deref_mut()));
+                stats.rechecked_files += 1;
+                settled &= before.as_ref() == exports.get(self.files[index].name.as_str());
+            }
+            if settled {
+                break;
+            }
+        }
+        for &index in &in_cycles {
+            if let Some(checked) = &results[index] {
+                done(index, checked, stats);
+            }
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    /// Checks one file against the exports of the files checked so far, adding its own.
+    fn check_file_at<'a>(
+        &'a self,
+        index: usize,
+        exports: &mut HashMap<&'a str, Vec<ExportedSymbol>>,
+        stats: &mut PerformanceStats,
+        trace: Option<&mut Trace>,
+    ) -> CheckedFile {
+        let file = &self.files[index];
+        let inputs = CheckInputs {
+            source: file.source.clone(),
+            imports: file
+                .resolved_modules
+                .iter()
+                .map(|(specifier, resolved)| {
+                    (specifier.clone(), exports.get(resolved.as_str()).cloned())
+                })
+                .collect(),
+        };
+        let looked_up = Instant::now();
+        if let Some(checked) = self.cache.get(&file.name, &inputs) {
+            exports.insert(&file.name, checked.checker.exported_symbols(&file.name));
+            stats.reused_files += 1;
+            // Traced like a check, so every file shows up in the trace
+            if let Some(trace) = trace {
+                trace.record(
+                    "checkSourceFile",
+                    "check",
+                    looked_up,
+                    Instant::now(),
+                    vec![("path", file.name.clone()), ("reused", "true".to_string())],
+                );
+            }
+            return checked;
+        }
+        let started = Instant::now();
+        let ts_program = parse_typescript_recovering(&file.source, source_type_for(&file.name));
+        let parsed = Instant::now();
+        let mut checker = TypeChecker::new();
+        checker.set_experimental_decorators(self.options.experimental_decorators);
+        checker.set_only_throw_error(self.options.only_throw_error);
+        checker.set_strictness(self.options.strictness());
+        checker.set_module_kind(self.options.module_for(&file.name));
+        checker.set_target(self.options.target);
+        checker.set_no_fallthrough_cases_in_switch(self.options.no_fallthrough_cases_in_switch);
+        checker.set_no_implicit_override(self.options.no_implicit_override);
+        checker.set_no_error_truncation(self.options.no_error_truncation);
+        if trace.is_some() {
+            checker.trace_relations();
+        }
+        for (specifier, resolved) in &file.resolved_modules {
+            if let Some(module_exports) = exports.get(resolved.as_str()) {
+                checker.set_module_exports(specifier.clone(), module_exports.clone());
+            }
+        }
+        // Checking recurses as deeply as the source nests, like parsing
+        let rules = &self.rules;
+        let skip_rules = rules.is_empty() || self.options.skips_check(&file.name, &file.source);
+        let (mut checker, ts_program, bound, rule_diagnostics) = with_large_stack(move || {
+            checker.bind_program(&ts_program.program);
+            let bound = Instant::now();
+            checker.check_bound_program(&ts_program.program);
+            let rule_diagnostics = match skip_rules {
+                true => Vec::new(),
+                false => rules.run(&file.name, &file.source, &ts_program.program, &checker),
+            };
+            (checker, ts_program, bound, rule_diagnostics)
+        });
+        exports.insert(&file.name, checker.exported_symbols(&file.name));
+        let checked = Instant::now();
+        stats.parse_time += parsed - started;
+        stats.bind_time += bound - parsed;
+        stats.check_time += checked - bound;
+        if let Some(trace) = trace {
+            let path = || vec![("path", file.name.clone())];
+            trace.record("createSourceFile", "parse", started, parsed, path());
+            trace.record("bindSourceFile", "bind", parsed, bound, path());
+            trace.record("checkSourceFile", "check", bound, checked, path());
+            trace.record_relations(checker.take_traced_relations());
+        }
+        let checked = CheckedFile {
+            checker,
+            syntax_errors: ts_program.errors,
+            rule_diagnostics,
+        };
+        self.cache.insert(&file.name, inputs, &checked);
+        checked
+    }
+
+    /// File indices in post-order over imports, so each file follows what it imports.
+    fn check_order(&self) -> Vec<usize> {
+        let indices: HashMap<&str, usize> = self
+            .files
+            .iter()
+            .enumerate()
+            .map(|(i, f)| (f.name.as_str(), i))
+            .collect();
+        let mut visited = vec![false; self.files.len()];
+        let mut order = Vec::with_capacity(self.files.len());
+
+        fn visit(
+            index: usize,
+            files: &[SourceFile],
+            indices: &HashMap<&str, usize>,
+            visited: &mut [bool],
+            order: &mut Vec<usize>,
+        ) {
+            if visited[index] {
+                return;
+            }
+            visited[index] = true;
+            for (_, resolved) in &files[index].resolved_modules {
+                if let Some(&dep) = indices.get(resolved.as_str()) {
+                    visit(dep, files, indices, visited, order);
+                }
+            }
+            order.push(index);
+        }
+
+        for index in 0..self.files.len() {
+            visit(index, &self.files, &indices, &mut visited, &mut order);
+        }
+        order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::InMemoryFileSystem;
+    use crate::testing::TestProject;
+
+    #[test]
+    fn test_program_reports_diagnostics_per_file() {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_file("a.ts", "let x: number = 42;");
+        program.add_file("b.ts", "let y: string = 42;");
+
+        let diagnostics = program.check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file_name, "b.ts");
+        assert_eq!(
+            diagnostics[0].message,
+            "Type 'number' is not assignable to type 'string'"
+        );
+        assert!(diagnostics[0].span.is_some());
+    }
+
+    #[test]
+    fn test_program_reports_parse_errors() {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_file("broken.ts", "let = ;");
+
+        let diagnostics = program.check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file_name, "broken.ts");
+    }
+
+    #[test]
+    fn test_program_checks_recovered_files() {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_file("broken.ts", "const a;\nlet b: string = 1;\nconst c;");
+
+        let diagnostics = program.check();
+        let messages: Vec<&str> = diagnostics.iter().map(|d| d.message.as_str()).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Missing initializer in const declaration",
+                "Missing initializer in const declaration",
+                "Type 'number' is not assignable to type 'string'",
+            ]
+        );
+        assert!(diagnostics.iter().all(|d| d.span.is_some()));
+    }
+
+    #[test]
+    fn test_program_checks_deeply_nested_source() {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_file(
+            "members.ts",
+            format!("let o: any = {{}};\nlet x = o{};", ".a".repeat(20_000)),
+        );
+        program.add_file(
+            "types.ts",
+            format!(
+                "let x: {}number{} = [];",
+                "Array<".repeat(2_000),
+                ">".repeat(2_000)
+            ),
+        );
+        program.add_file("arrows.ts", format!("let f = {}1;", "() => ".repeat(5_000)));
+        assert!(program.check().is_empty());
+    }
+
+    #[test]
+    fn test_program_check_with_stats() {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_file("a.ts", "let x: number = 42;\nlet y = x;");
+        program.add_file("b.ts", "function f(n: number) {}");
+
+        let (diagnostics, stats) = program.check_with_stats();
+        assert!(diagnostics.is_empty());
+        assert_eq!(stats.files, 2);
+        assert_eq!(stats.lines, 3);
+        assert_eq!(stats.symbols, 4);
+        assert!(stats.types >= stats.symbols);
+        assert_eq!(stats.rechecked_files, 0);
+        assert_eq!(
+            stats.total_time(),
+            stats.parse_time + stats.bind_time + stats.check_time
+        );
+    }
+
+    #[test]
+    fn test_program_reuses_results_whose_inputs_are_unchanged() {
+        let mut program = TestProject::new()
+            .file(
+                "/main.ts",
+                "import { f } from './lib';\nlet n: number = f();",
+            )
+            .file("/lib.ts", "export function f(): number { return 1; }")
+            .roots(&["/main.ts"])
+            .program();
+        assert_eq!(program.check_with_stats().1.reused_files, 0);
+        assert_eq!(program.check_with_stats().1.reused_files, 2);
+
+        // Editing a body leaves lib's exports alone, so main isn't checked again
+        program.update_file("/lib.ts", "export function f(): number { return 2; }");
+        assert_eq!(program.check_with_stats().1.reused_files, 1);
+
+        program.update_file("/lib.ts", "export function f(): string { return ''; }");
+        let (diagnostics, stats) = program.check_with_stats();
+        assert_eq!(stats.reused_files, 0);
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file_name, "/main.ts");
+    }
+
+    #[test]
+    fn test_program_check_with_trace() {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_file(
+            "a.ts",
+            "let p: { x: number } = { x: 1 };\nlet n: number = 1;",
+        );
+
+        let mut trace = Trace::new();
+        let (diagnostics, _) = program.check_with_trace(&mut trace);
+        assert!(diagnostics.is_empty());
+        let names: Vec<_> = trace.events().iter().map(|e| e.name).collect();
+        assert_eq!(
+            names,
+            vec![
+                "createSourceFile",
+                "bindSourceFile",
+                "checkSourceFile",
+                "structuredTypeRelatedTo"
+            ]
+        );
+        assert_eq!(trace.events()[0].args, vec![("path", "a.ts".to_string())]);
+        let relation = &trace.events()[3];
+        assert_eq!(relation.category, "checkTypes");
+        assert_eq!(
+            relation.args,
+            vec![
+                ("source", "{ x: number; }".to_string()),
+                ("target", "{ x: number; }".to_string())
+            ]
+        );
+
+        // A reused result is traced as a check of its own
+        let mut trace = Trace::new();
+        program.check_with_trace(&mut trace);
+        let events = trace.events();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].name, "checkSourceFile");
+        assert_eq!(
+            events[0].args,
+            vec![("path", "a.ts".to_string()), ("reused", "true".to_string())]
+        );
+    }
+
+    #[test]
+    fn test_program_records_resolution_trace() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/main.ts", r#"import "./lib";"#);
+        fs.add_file("/lib.ts", "");
+
+        let program = Program::load(CompilerOptions::default(), &["/main.ts"], &fs);
+        assert!(program.resolution_trace().is_empty());
+
+        let options = CompilerOptions {
+            trace_resolution: true,
+            ..Default::default()
+        };
+        let program = Program::load(options, &["/main.ts"], &fs);
+        assert_eq!(
+            program.resolution_trace().last().unwrap(),
+            "======== Module name './lib' was successfully resolved to '/lib.ts'. ========"
+        );
+    }
+
+    #[test]
+    fn test_program_load_follows_imports() {
+        let mut fs = InMemoryFileSystem::new().with_current_dir("/project");
+        fs.add_file(
+            "index.ts",
+            r#"import { x } from "./lib"; import "./missing";"#,
+        );
+        fs.add_file("lib/index.ts", r#"export * from "../util";"#);
+        fs.add_file("util.ts", "export const x = 1; let bad: string = 1;");
+        fs.add_file("unused.ts", "let y: number = 'unused';");
+
+        let program = Program::load(CompilerOptions::default(), &["index.ts"], &fs);
+        let names: Vec<&str> = program.files().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "/project/index.ts",
+                "/project/lib/index.ts",
+                "/project/util.ts"
+            ]
+        );
+
+        let messages: Vec<String> = program.check().into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Cannot find module './missing' or its corresponding type declarations.",
+                "Type 'number' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_program_load_reports_missing_root() {
+        let fs = InMemoryFileSystem::new();
+        let program = Program::load(CompilerOptions::default(), &["/nope.ts"], &fs);
+        let diagnostics = program.check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].message, "File '/nope.ts' not found.");
+    }
+
+    #[test]
+    fn test_program_checks_imports_against_exports() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/main.ts",
+            r#"
+            import { count, label as name } from "./values";
+            import double, { missing } from "./math";
+            let a: string = count;
+            let b: string = name;
+            let c: string = double;
+            "#,
+        );
+        fs.add_file(
+            "/values.ts",
+            r#"
+            export const count = 1;
+            const label = "x";
+            export { label };
+            "#,
+        );
+        fs.add_file(
+            "/math.ts",
+            "export default function double(n: number): number { return n * 2; }",
+        );
+
+        let program = Program::load(CompilerOptions::default(), &["/main.ts"], &fs);
+        let messages: Vec<String> = program.check().into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            messages,
+            vec![
+                "Module '\"./math\"' has no exported member 'missing'.",
+                "Type 'number' is not assignable to type 'string'",
+                "Type '(number) => number' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_program_skips_lib_check() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/main.ts",
+            "import { version } from \"./vendor\";\nlet v: number = version;",
+        );
+        fs.add_file(
+            "/vendor.d.ts",
+            "export declare const version: string;\nlet broken: number = \"x\";",
+        );
+        fs.add_file(
+            "/lib.d.ts",
+            "/// <reference no-default-lib=\"true\"/>\nlet broken: number = \"x\";",
+        );
+        let check = |options: CompilerOptions| {
+            let program = Program::load(options, &["/main.ts", "/lib.d.ts"], &fs);
+            program
+                .check()
+                .into_iter()
+                .map(|d| d.file_name)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            check(CompilerOptions::default()),
+            vec!["/main.ts", "/lib.d.ts", "/vendor.d.ts"]
+        );
+        let skip_default_lib_check = CompilerOptions {
+            skip_default_lib_check: true,
+            ..Default::default()
+        };
+        assert_eq!(
+            check(skip_default_lib_check),
+            vec!["/main.ts", "/vendor.d.ts"]
+        );
+        // The declarations still type what imports them
+        let skip_lib_check = CompilerOptions {
+            skip_lib_check: true,
+            ..Default::default()
+        };
+        assert_eq!(check(skip_lib_check), vec!["/main.ts"]);
+    }
+
+    #[test]
+    fn test_program_reports_inconsistent_casing() {
+        let mut fs = InMemoryFileSystem::new().case_insensitive();
+        fs.add_file(
+            "/main.ts",
+            "import { a } from \"./Util\";\nimport { b } from \"./util\";",
+        );
+        fs.add_file("/util.ts", "export const a = 1, b = 2;");
+
+        let program = Program::load(CompilerOptions::default(), &["/main.ts"], &fs);
+        let names: Vec<&str> = program.files().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["/main.ts", "/util.ts"]);
+        let diagnostics = program.check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].file_name, "/main.ts");
+        assert_eq!(
+            diagnostics[0].message,
+            "File name '/Util.ts' differs from already included file name '/util.ts' only in casing."
+        );
+        assert_eq!(diagnostics[0].code, Some(1149));
+
+        let options = CompilerOptions {
+            force_consistent_casing_in_file_names: Some(false),
+            ..Default::default()
+        };
+        assert!(
+            Program::load(options, &["/main.ts"], &fs)
+                .check()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn test_program_resolves_pnp_packages() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/app/src/main.ts",
+            "import { pad } from \"left-pad\";\nlet x: string = pad;",
+        );
+        fs.add_file(
+            "/app/.pnp.data.json",
+            r#"{ "packageRegistryData": [
+                [null, [[null, {
+                    "packageLocation": "./",
+                    "packageDependencies": [["left-pad", "npm:1.3.0"]]
+                }]]],
+                ["left-pad", [["npm:1.3.0", {
+                    "packageLocation": "./.yarn/unplugged/left-pad-npm-1.3.0/node_modules/left-pad/"
+                }]]]
+            ] }"#,
+        );
+        let left_pad = "/app/.yarn/unplugged/left-pad-npm-1.3.0/node_modules/left-pad/index.d.ts";
+        fs.add_file(left_pad, "export declare const pad: number;");
+
+        let program = Program::load(CompilerOptions::default(), &["/app/src/main.ts"], &fs);
+        let names: Vec<&str> = program.files().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(names, vec!["/app/src/main.ts", left_pad]);
+        let messages: Vec<String> = program.check().into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            messages,
+            vec!["Type 'number' is not assignable to type 'string'"]
+        );
+
+        fs.add_file("/app/.pnp.data.json", "{}");
+        let program = Program::load(CompilerOptions::default(), &["/app/src/main.ts"], &fs);
+        assert!(
+            program.check()[0]
+                .message
+                .starts_with("Could not read '/app/.pnp.data.json'")
+        );
+    }
+
+    #[test]
+    fn test_program_follows_symlinked_packages() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file(
+            "/app/main.ts",
+            "import { a } from \"a\";\nimport { b } from \"b\";\nlet x: string = a;",
+        );
+        let store = "/app/node_modules/.pnpm";
+        for package in ["a", "b"] {
+            fs.add_file(
+                format!("{store}/{package}@1.0.0/node_modules/{package}/index.d.ts"),
+                format!("import \"shared\";\nexport declare const {package}: number;"),
+            );
+            fs.add_symlink(
+                format!("/app/node_modules/{package}"),
+                format!(".pnpm/{package}@1.0.0/node_modules/{package}"),
+            );
+            fs.add_symlink(
+                format!("{store}/{package}@1.0.0/node_modules/shared"),
+                "../../shared@1.0.0/node_modules/shared",
+            );
+        }
+        fs.add_file(
+            format!("{store}/shared@1.0.0/node_modules/shared/index.d.ts"),
+            "export {};",
+        );
+
+        // Both packages' `shared` is the one file in the store
+        let program = Program::load(CompilerOptions::default(), &["/app/main.ts"], &fs);
+        let names: Vec<&str> = program.files().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "/app/main.ts",
+                "/app/node_modules/.pnpm/a@1.0.0/node_modules/a/index.d.ts",
+                "/app/node_modules/.pnpm/b@1.0.0/node_modules/b/index.d.ts",
+                "/app/node_modules/.pnpm/shared@1.0.0/node_modules/shared/index.d.ts",
+            ]
+        );
+        let messages: Vec<String> = program.check().into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            messages,
+            vec!["Type 'number' is not assignable to type 'string'"]
+        );
+
+        // Through the links, `shared` isn't a dependency anything can see
+        let options = CompilerOptions {
+            preserve_symlinks: true,
+            ..Default::default()
+        };
+        let program = Program::load(options, &["/app/main.ts"], &fs);
+        let names: Vec<&str> = program.files().iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec![
+                "/app/main.ts",
+                "/app/node_modules/a/index.d.ts",
+                "/app/node_modules/b/index.d.ts",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_program_check_streaming() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/main.ts", "import { a } from \"./a\";\nlet x: string = a;");
+        fs.add_file("/a.ts", "import { b } from \"./b\";\nexport const a = 1;");
+        fs.add_file("/b.ts", "import { a } from \"./a\";\nexport const b = 2;");
+        let options = CompilerOptions {
+            report_circular_imports: true,
+            ..CompilerOptions::default()
+        };
+        let program = Program::load(options, &["/main.ts", "/missing.ts"], &fs);
+
+        let mut delivered = Vec::new();
+        let rest = program.check_streaming(|file| {
+            delivered.push((
+                file.file_name,
+                file.diagnostics.len(),
+                file.completed,
+                file.total,
+            ))
+        });
+        // The cycle's files are only done once it settles, after what doesn't depend on it
+        assert_eq!(
+            delivered,
+            vec![
+                ("/main.ts".to_string(), 1, 1, 3),
+                ("/b.ts".to_string(), 0, 2, 3),
+                ("/a.ts".to_string(), 0, 3, 3),
+            ]
+        );
+        let rest: Vec<String> = rest.into_iter().map(|d| d.message).collect();
+        assert_eq!(
+            rest,
+            vec![
+                "File '/missing.ts' not found.",
+                "Circular import: /a.ts -> /b.ts -> /a.ts.",
+            ]
+        );
+
+        let mut streamed: Vec<Diagnostic> = Vec::new();
+        let rest = program.check_streaming(|file| streamed.extend(file.diagnostics));
+        assert_eq!(streamed.len() + rest.len(), program.check().len());
+    }
+}
@@ -0,0 +1,283 @@
+use crate::incremental::{Answer, Query};
+use crate::program::Program;
+use crate::symbols::{Symbol, SymbolKind};
+use crate::type_checker::TypeChecker;
+use crate::types::{Property, Type};
+use oxc_span::Span;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeInfo {
+    pub span: Span,
+    pub ty: Type,
+    pub display: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    pub span: Span,
+    pub symbol: Symbol,
+    pub display: String,
+}
+
+fn contains(span: Span, offset: u32) -> bool {
+    span.start <= offset && offset < span.end
+}
+
+impl TypeChecker {
+    /// The type of the innermost expression or binding covering `offset`.
+    pub fn type_at(&self, offset: u32) -> Option<TypeInfo> {
+        self.node_types()
+            .iter()
+            .filter(|(span, _)| contains(*span, offset))
+            .min_by_key(|(span, _)| span.size())
+            .map(|(span, ty)| TypeInfo {
+                span: *span,
+                ty: ty.clone(),
+                display: ty.to_string(),
+            })
+    }
+
+    /// The file's own declarations in source order, leaving out parameters and imports.
+    ///
+    /// Only top-level declarations are included unless `nested` is set, in which case
+    /// those inside functions and blocks are too.
+    pub fn declarations(&self, nested: bool) -> Vec<&Symbol> {
+        let mut declarations: Vec<&Symbol> = match nested {
+            true => self.symbols().iter().collect(),
+            false => self.visible_symbols(),
+        };
+        declarations
+            .retain(|symbol| !matches!(symbol.kind, SymbolKind::Parameter | SymbolKind::Alias));
+        declarations.sort_by_key(|symbol| symbol.span.start);
+        declarations
+    }
+
+    /// The symbol named by the identifier covering `offset`, if it resolved to one.
+    pub fn symbol_at(&self, offset: u32) -> Option<SymbolInfo> {
+        self.references()
+            .iter()
+            .find(|(span, _)| contains(*span, offset))
+            .map(|(span, id)| {
+                let symbol = self.symbols()[id.0].clone();
+                SymbolInfo {
+                    span: *span,
+                    display: symbol.to_string(),
+                    symbol,
+                }
+            })
+    }
+
+    /// The signature of the innermost function or callee covering `offset`.
+    pub fn signature_at(&self, offset: u32) -> Option<Type> {
+        self.node_types()
+            .iter()
+            .filter(|(span, ty)| {
+                contains(*span, offset)
+                    && matches!(ty.resolved(), Type::Function { .. } | Type::Generic { .. })
+            })
+            .min_by_key(|(span, _)| span.size())
+            .map(|(_, ty)| ty.resolved().clone())
+    }
+}
+
+impl Program {
+    pub fn type_at_position(&self, file_name: &str, offset: u32) -> Option<TypeInfo> {
+        self.check_file(file_name)?.type_at(offset)
+    }
+
+    pub fn symbol_at_position(&self, file_name: &str, offset: u32) -> Option<SymbolInfo> {
+        self.check_file(file_name)?.symbol_at(offset)
+    }
+
+    /// The type of the top-level declaration `name` in `file_name`.
+    ///
+    /// Like the other queries below, the answer is memoized until an edit means the file
+    /// has to be checked again.
+    pub fn type_of_symbol(&self, file_name: &str, name: &str) -> Option<Type> {
+        let query = Query::TypeOfSymbol(name.to_string());
+        match self.answer(file_name, query, |checker| {
+            let symbol = checker
+                .declarations(false)
+                .into_iter()
+                .find(|s| s.name == name);
+            Answer::Type(symbol.map(|symbol| symbol.ty.clone()))
+        })? {
+            Answer::Type(ty) => ty,
+            Answer::Members(_) => None,
+        }
+    }
+
+    /// The members of the type of the innermost expression or binding covering `offset`,
+    /// including those it has as a primitive, like `length` on a string.
+    pub fn members_of_type(&self, file_name: &str, offset: u32) -> Vec<Property> {
+        let answer = self.answer(file_name, Query::MembersOfType(offset), |checker| {
+            let ty = checker.type_at(offset).map(|info| info.ty);
+            Answer::Members(ty.map_or_else(Vec::new, |ty| checker.properties_of(&ty)))
+        });
+        match answer {
+            Some(Answer::Members(members)) => members,
+            _ => Vec::new(),
+        }
+    }
+
+    /// The signature of the innermost function, method or callee covering `offset`.
+    pub fn signature_of_node(&self, file_name: &str, offset: u32) -> Option<Type> {
+        match self.answer(file_name, Query::SignatureOfNode(offset), |checker| {
+            Answer::Type(checker.signature_at(offset))
+        })? {
+            Answer::Type(ty) => ty,
+            Answer::Members(_) => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::options::CompilerOptions;
+    use crate::symbols::SymbolKind;
+    use crate::testing::TestProject;
+
+    fn program(source: &str) -> Program {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_file("main.ts", source);
+        program
+    }
+
+    #[test]
+    fn test_type_at_position() {
+        let source = "let count = 1 + 2;\nlet label: string = 'n: ' + count;";
+        let program = program(source);
+
+        let offset = source.find("1 + 2").unwrap() as u32;
+        let info = program.type_at_position("main.ts", offset).unwrap();
+        assert_eq!(info.ty, Type::Number);
+        assert_eq!(info.span, Span::new(offset, offset + 1));
+
+        let offset = source.rfind("count").unwrap() as u32;
+        let info = program.type_at_position("main.ts", offset).unwrap();
+        assert_eq!(info.display, "number");
+
+        let offset = source.find("'n: ' + count").unwrap() as u32 + 5;
+        let info = program.type_at_position("main.ts", offset).unwrap();
+        assert_eq!(info.ty, Type::String);
+
+        assert!(program.type_at_position("main.ts", 3).is_none());
+        assert!(program.type_at_position("other.ts", 0).is_none());
+    }
+
+    #[test]
+    fn test_symbol_at_position() {
+        let source = "function double(n: number): number { return n * 2; }\nlet x = double;";
+        let program = program(source);
+
+        let offset = source.rfind("double").unwrap() as u32 + 2;
+        let info = program.symbol_at_position("main.ts", offset).unwrap();
+        assert_eq!(info.symbol.name, "double");
+        assert_eq!(info.symbol.kind, SymbolKind::Function);
+        assert_eq!(
+            info.symbol.span.start,
+            source.find("double").unwrap() as u32
+        );
+        assert_eq!(info.display, "(function) double: (number) => number");
+
+        let offset = source.find("n * 2").unwrap() as u32;
+        let info = program.symbol_at_position("main.ts", offset).unwrap();
+        assert_eq!(info.symbol.kind, SymbolKind::Parameter);
+        assert_eq!(info.display, "(parameter) n: number");
+
+        let offset = source.find("2;").unwrap() as u32;
+        assert!(program.symbol_at_position("main.ts", offset).is_none());
+    }
+
+    #[test]
+    fn test_declarations() {
+        let source = "import { a } from './a';\nfunction outer(n: number) { const inner = n > 1; return inner; }\nconst total = 1;";
+        let checker = program(source).check_file("main.ts").unwrap();
+        let names = |nested| -> Vec<String> {
+            checker
+                .declarations(nested)
+                .into_iter()
+                .map(|symbol| symbol.to_string())
+                .collect()
+        };
+        assert_eq!(
+            names(false),
+            vec!["(function) outer: (number) => any", "(variable) total: 1"]
+        );
+        assert_eq!(
+            names(true),
+            vec![
+                "(function) outer: (number) => any",
+                "(variable) inner: boolean",
+                "(variable) total: 1"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_queries() {
+        let source = "const label = 'x';\nfunction pad(text: string, width: number): string { return text; }\npad(label, 2);";
+        let program = program(source);
+        assert_eq!(
+            program
+                .type_of_symbol("main.ts", "pad")
+                .unwrap()
+                .to_string(),
+            "(string, number) => string"
+        );
+        assert_eq!(program.type_of_symbol("main.ts", "missing"), None);
+        assert_eq!(program.type_of_symbol("other.ts", "pad"), None);
+
+        let offset = source.rfind("label").unwrap() as u32;
+        let members = program.members_of_type("main.ts", offset);
+        assert!(members.iter().any(|member| member.name == "length"));
+
+        let offset = source.rfind("pad").unwrap() as u32;
+        assert_eq!(
+            program
+                .signature_of_node("main.ts", offset)
+                .unwrap()
+                .to_string(),
+            "(string, number) => string"
+        );
+        assert_eq!(program.signature_of_node("main.ts", 0), None);
+    }
+
+    #[test]
+    fn test_queries_are_memoized_until_their_file_changes() {
+        let mut program = TestProject::new()
+            .file("/main.ts", "import { f } from './lib';\nconst n = f();")
+            .file("/lib.ts", "export function f(): number { return 1; }")
+            .roots(&["/main.ts"])
+            .program();
+        let answered = std::cell::Cell::new(0);
+        let ask = |program: &Program| {
+            program.answer(
+                "/main.ts",
+                Query::TypeOfSymbol("n".to_string()),
+                |checker| {
+                    answered.set(answered.get() + 1);
+                    let symbol = checker
+                        .declarations(false)
+                        .into_iter()
+                        .find(|s| s.name == "n");
+                    Answer::Type(symbol.map(|symbol| symbol.ty.clone()))
+                },
+            )
+        };
+        assert_eq!(ask(&program), Some(Answer::Type(Some(Type::Number))));
+        ask(&program);
+        assert_eq!(answered.get(), 1);
+
+        // An edit that leaves lib's exports alone doesn't change what main's answers
+        // depend on
+        program.update_file("/lib.ts", "export function f(): number { return 2; }");
+        ask(&program);
+        assert_eq!(answered.get(), 1);
+
+        program.update_file("/lib.ts", "export function f(): string { return ''; }");
+        assert_eq!(ask(&program), Some(Answer::Type(Some(Type::String))));
+        assert_eq!(answered.get(), 2);
+    }
+}
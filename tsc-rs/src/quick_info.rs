@@ -0,0 +1,640 @@
+use crate::parser::{TypeScriptProgram, parse_typescript_recovering, source_type_for};
+use crate::program::Program;
+use crate::symbols::{Symbol, SymbolKind};
+use crate::type_checker::TypeChecker;
+use crate::types::{ClassType, Type};
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::{GetSpan, Span};
+use oxc_syntax::scope::ScopeFlags;
+use std::collections::HashMap;
+use std::fmt;
+
+/// What an editor shows on hovering over a name: its declaration written the way
+/// TypeScript writes it, like `const x: string` or `(method) Foo.bar(): void`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QuickInfo {
+    /// The name the information is about.
+    pub span: Span,
+    pub display: String,
+    /// The text of the JSDoc comment on the declaration, without its tags.
+    pub documentation: Option<String>,
+}
+
+/// What a declaration's type doesn't say about it.
+#[derive(Debug, Clone, PartialEq)]
+enum Declaration {
+    /// A variable, the keyword declaring it, and the names of the parameters of the
+    /// function it's initialized with.
+    Variable(&'static str, Option<Vec<String>>),
+    /// A function and the names of its parameters.
+    Function(Vec<String>),
+    Class,
+    Enum,
+    Namespace,
+}
+
+/// A class member, as found in the class body.
+#[derive(Debug, Clone)]
+struct Member {
+    /// Parameter names, for methods.
+    params: Option<Vec<String>>,
+    /// Where a JSDoc comment describing it would attach.
+    start: u32,
+}
+
+/// The key class members are found under: the class name, the member name and whether
+/// it's static.
+type MemberKey = (String, String, bool);
+
+/// Finds the declaration of the name at `offset`, and collects every class member in the
+/// file along the way.
+struct Finder {
+    offset: u32,
+    /// The keyword and start of the variable declaration being visited.
+    variable: Option<(&'static str, u32)>,
+    /// The name of the class whose body is being visited.
+    class: Option<String>,
+    /// Where each exported declaration's `export` keyword starts, by where the declaration
+    /// itself does, since that's what its comment attaches to.
+    exports: HashMap<u32, u32>,
+    declaration: Option<(Declaration, u32)>,
+    /// The member whose name covers `offset`, where it's declared in a class body.
+    definition: Option<(MemberKey, Span)>,
+    /// The object and property span of `object.property` with the property at `offset`.
+    access: Option<(Span, String, Span)>,
+    members: HashMap<MemberKey, Member>,
+}
+
+fn contains(span: Span, offset: u32) -> bool {
+    span.start <= offset && offset < span.end
+}
+
+/// The names of a function's parameters, with destructured ones numbered the way
+/// TypeScript numbers them.
+fn parameter_names(params: &FormalParameters) -> Vec<String> {
+    let name = |index: usize, pattern: &BindingPattern| match &pattern.kind {
+        BindingPatternKind::BindingIdentifier(ident) => ident.name.to_string(),
+        BindingPatternKind::AssignmentPattern(assignment) => match &assignment.left.kind {
+            BindingPatternKind::BindingIdentifier(ident) => ident.name.to_string(),
+            _ => format!("__{}", index),
+        },
+        _ => format!("__{}", index),
+    };
+    let mut names: Vec<String> = params
+        .items
+        .iter()
+        .enumerate()
+        .map(|(index, param)| name(index, &param.pattern))
+        .collect();
+    if let Some(rest) = &params.rest {
+        names.push(name(names.len(), &rest.argument));
+    }
+    names
+}
+
+/// A function type written with its parameters named, like `<T>(value: T, count?:
+/// number): T`.
+pub(crate) struct Signature {
+    /// `<T, U>` for generic functions, and empty otherwise.
+    pub type_params: String,
+    /// Each parameter as `name: type`, marked as optional or rest where it is.
+    pub params: Vec<String>,
+    pub return_type: Type,
+}
+
+impl Signature {
+    /// The signature of `ty` with parameters called `names`, or `None` if it isn't a
+    /// function. Parameters without a known name are numbered.
+    pub(crate) fn of(names: &[String], ty: &Type) -> Option<Self> {
+        let (type_params, function) = match ty {
+            Type::Generic { type_params, body } => {
+                let names: Vec<&str> = type_params.iter().map(|p| p.name.as_str()).collect();
+                (format!("<{}>", names.join(", ")), &**body)
+            }
+            ty => (String::new(), ty),
+        };
+        let Type::Function {
+            params,
+            return_type,
+            required,
+            rest,
+        } = function.resolved()
+        else {
+            return None;
+        };
+        let params = params
+            .iter()
+            .enumerate()
+            .map(|(index, param)| {
+                let name = names
+                    .get(index)
+                    .cloned()
+                    .unwrap_or_else(|| format!("arg{}", index));
+                match (*rest && index + 1 == params.len(), index >= *required) {
+                    (true, _) => format!("...{}: {}", name, param),
+                    (false, true) => format!("{}?: {}", name, param),
+                    (false, false) => format!("{}: {}", name, param),
+                }
+            })
+            .collect();
+        Some(Self {
+            type_params,
+            params,
+            return_type: (**return_type).clone(),
+        })
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}({}): {}",
+            self.type_params,
+            self.params.join(", "),
+            self.return_type
+        )
+    }
+}
+
+/// How a function or method is written after its name: its signature, or its type for
+/// anything that isn't a function.
+fn signature(names: &[String], ty: &Type) -> String {
+    Signature::of(names, ty).map_or_else(|| format!(": {}", ty), |signature| signature.to_string())
+}
+
+/// The text of the JSDoc comment attached to the token at `start`, up to its first tag.
+fn documentation(parsed: &TypeScriptProgram, source: &str, start: u32) -> Option<String> {
+    let comment = parsed
+        .program
+        .comments
+        .iter()
+        .rev()
+        .find(|comment| comment.attached_to == start && comment.is_jsdoc(source))?;
+    let content = comment.content_span();
+    let lines: Vec<&str> = source[content.start as usize..content.end as usize]
+        .lines()
+        .map(|line| line.trim().trim_start_matches('*').trim())
+        .take_while(|line| !line.starts_with('@'))
+        .filter(|line| !line.is_empty())
+        .collect();
+    (!lines.is_empty()).then(|| lines.join("\n"))
+}
+
+impl Finder {
+    fn search(parsed: &TypeScriptProgram, offset: u32) -> Self {
+        let mut finder = Self {
+            offset,
+            variable: None,
+            class: None,
+            exports: HashMap::new(),
+            declaration: None,
+            definition: None,
+            access: None,
+            members: HashMap::new(),
+        };
+        finder.visit_program(&parsed.program);
+        finder
+    }
+
+    fn declare(&mut self, span: Span, declaration: Declaration, start: u32) {
+        if contains(span, self.offset) {
+            let start = self.exports.get(&start).copied().unwrap_or(start);
+            self.declaration = Some((declaration, start));
+        }
+    }
+
+    fn add_member(&mut self, key: &PropertyKey, is_static: bool, member: Member) {
+        let (Some(class), Some(name)) = (&self.class, key.static_name()) else {
+            return;
+        };
+        let member_key = (class.clone(), name.into_owned(), is_static);
+        if contains(key.span(), self.offset) {
+            self.definition = Some((member_key.clone(), key.span()));
+        }
+        self.members.entry(member_key).or_insert(member);
+    }
+}
+
+impl<'a> Visit<'a> for Finder {
+    fn visit_export_named_declaration(&mut self, it: &ExportNamedDeclaration<'a>) {
+        if let Some(declaration) = &it.declaration {
+            self.exports.insert(declaration.span().start, it.span.start);
+        }
+        walk::walk_export_named_declaration(self, it);
+    }
+
+    fn visit_export_default_declaration(&mut self, it: &ExportDefaultDeclaration<'a>) {
+        self.exports
+            .insert(it.declaration.span().start, it.span.start);
+        walk::walk_export_default_declaration(self, it);
+    }
+
+    fn visit_variable_declaration(&mut self, it: &VariableDeclaration<'a>) {
+        let keyword = match it.kind {
+            VariableDeclarationKind::Var => "var",
+            VariableDeclarationKind::Let => "let",
+            _ => "const",
+        };
+        let start = self.exports.get(&it.span.start).copied();
+        let enclosing = self
+            .variable
+            .replace((keyword, start.unwrap_or(it.span.start)));
+        walk::walk_variable_declaration(self, it);
+        self.variable = enclosing;
+    }
+
+    fn visit_binding_identifier(&mut self, it: &BindingIdentifier<'a>) {
+        if let Some((keyword, start)) = self.variable
+            && contains(it.span, self.offset)
+        {
+            self.declaration = Some((Declaration::Variable(keyword, None), start));
+        }
+    }
+
+    fn visit_variable_declarator(&mut self, it: &VariableDeclarator<'a>) {
+        walk::walk_variable_declarator(self, it);
+        let params = match &it.init {
+            Some(Expression::ArrowFunctionExpression(arrow)) => parameter_names(&arrow.params),
+            Some(Expression::FunctionExpression(function)) => parameter_names(&function.params),
+            _ => return,
+        };
+        if let Some((Declaration::Variable(_, names), _)) = &mut self.declaration
+            && contains(it.id.span(), self.offset)
+        {
+            *names = Some(params);
+        }
+    }
+
+    fn visit_formal_parameters(&mut self, it: &FormalParameters<'a>) {
+        // Parameters of a function in a variable's initializer aren't that variable
+        let enclosing = self.variable.take();
+        walk::walk_formal_parameters(self, it);
+        self.variable = enclosing;
+    }
+
+    fn visit_function(&mut self, it: &Function<'a>, flags: ScopeFlags) {
+        if let Some(id) = &it.id {
+            self.declare(
+                id.span,
+                Declaration::Function(parameter_names(&it.params)),
+                it.span.start,
+            );
+        }
+        walk::walk_function(self, it, flags);
+    }
+
+    fn visit_class(&mut self, it: &Class<'a>) {
+        if let Some(id) = &it.id {
+            self.declare(id.span, Declaration::Class, it.span.start);
+        }
+        let name = it
+            .id
+            .as_ref()
+            .map_or("(Anonymous class)".to_string(), |id| id.name.to_string());
+        let enclosing = self.class.replace(name);
+        walk::walk_class(self, it);
+        self.class = enclosing;
+    }
+
+    fn visit_method_definition(&mut self, it: &MethodDefinition<'a>) {
+        if it.kind != MethodDefinitionKind::Constructor {
+            let params = (it.kind == MethodDefinitionKind::Method)
+                .then(|| parameter_names(&it.value.params));
+            let start = it.span.start;
+            self.add_member(&it.key, it.r#static, Member { params, start });
+        }
+        walk::walk_method_definition(self, it);
+    }
+
+    fn visit_property_definition(&mut self, it: &PropertyDefinition<'a>) {
+        let start = it.span.start;
+        self.add_member(
+            &it.key,
+            it.r#static,
+            Member {
+                params: None,
+                start,
+            },
+        );
+        walk::walk_property_definition(self, it);
+    }
+
+    fn visit_static_member_expression(&mut self, it: &StaticMemberExpression<'a>) {
+        if contains(it.property.span, self.offset) {
+            self.access = Some((
+                it.object.span(),
+                it.property.name.to_string(),
+                it.property.span,
+            ));
+        }
+        walk::walk_static_member_expression(self, it);
+    }
+
+    fn visit_ts_enum_declaration(&mut self, it: &TSEnumDeclaration<'a>) {
+        self.declare(it.id.span, Declaration::Enum, it.span.start);
+        walk::walk_ts_enum_declaration(self, it);
+    }
+
+    fn visit_ts_module_declaration(&mut self, it: &TSModuleDeclaration<'a>) {
+        self.declare(it.id.span(), Declaration::Namespace, it.span.start);
+        walk::walk_ts_module_declaration(self, it);
+    }
+}
+
+/// The symbol of the class `object` is, or is an instance of, among the classes `checker`
+/// knows, and whether it's the class itself.
+fn class_of<'c>(checker: &'c TypeChecker, object: &Type) -> Option<(&'c Symbol, bool)> {
+    checker.symbols().iter().find_map(|symbol| {
+        let Type::Class(class) = &symbol.ty else {
+            return None;
+        };
+        match object.resolved() {
+            Type::Class(other) if other.name == class.name => Some((symbol, true)),
+            Type::ObjectType(instance) if **instance == class.instance => Some((symbol, false)),
+            _ => None,
+        }
+    })
+}
+
+/// The type of the member `name` of `class`, or of its instances unless `is_static`.
+fn member_type(class: &ClassType, name: &str, is_static: bool) -> Option<Type> {
+    let members = match is_static {
+        true => &class.statics,
+        false => &class.instance,
+    };
+    members.property(name).map(|property| property.ty.clone())
+}
+
+/// What quick info and signature help know about a name.
+pub(crate) struct Described {
+    pub span: Span,
+    pub display: String,
+    pub documentation: Option<String>,
+    pub ty: Type,
+    /// The names of the parameters, for functions and methods declared with them.
+    pub params: Option<Vec<String>>,
+}
+
+/// A class member as TypeScript shows it, with the JSDoc comment on its declaration in
+/// `parsed` if that's where it was found.
+fn describe_member(
+    (class_name, name, _): &MemberKey,
+    span: Span,
+    ty: Type,
+    member: Option<&Member>,
+    parsed: &TypeScriptProgram,
+    source: &str,
+) -> Described {
+    let params = member.and_then(|member| member.params.clone());
+    let display = match &params {
+        Some(params) => format!("(method) {}.{}{}", class_name, name, signature(params, &ty)),
+        None => format!("(property) {}.{}: {}", class_name, name, ty),
+    };
+    Described {
+        span,
+        display,
+        documentation: member.and_then(|member| documentation(parsed, source, member.start)),
+        ty,
+        params,
+    }
+}
+
+impl Program {
+    /// How the name at `offset` is declared, written the way TypeScript shows it on hover.
+    pub fn quick_info_at_position(&self, file_name: &str, offset: u32) -> Option<QuickInfo> {
+        self.describe_at(file_name, offset)
+            .map(|described| QuickInfo {
+                span: described.span,
+                display: described.display,
+                documentation: described.documentation,
+            })
+    }
+
+    /// The name at `offset`: a symbol, a class member where it's declared, or the property
+    /// of a member access.
+    pub(crate) fn describe_at(&self, file_name: &str, offset: u32) -> Option<Described> {
+        let file = self.file(file_name)?;
+        let checker = self.check_file(file_name)?;
+        if let Some(info) = checker.symbol_at(offset) {
+            return Some(self.describe_symbol(file_name, info.span, &info.symbol));
+        }
+
+        let parsed = parse_typescript_recovering(&file.source, source_type_for(file_name));
+        let finder = Finder::search(&parsed, offset);
+        if let Some((key, span)) = &finder.definition {
+            let (class_name, name, is_static) = key;
+            let ty = checker
+                .symbols()
+                .iter()
+                .find(|symbol| {
+                    symbol.kind == SymbolKind::Class && symbol.name == class_name.as_str()
+                })
+                .and_then(|symbol| match &symbol.ty {
+                    Type::Class(class) => member_type(class, name, *is_static),
+                    _ => None,
+                })
+                .unwrap_or(Type::Any);
+            let member = finder.members.get(key);
+            return Some(describe_member(
+                key,
+                *span,
+                ty,
+                member,
+                &parsed,
+                &file.source,
+            ));
+        }
+
+        let (object, name, span) = finder.access?;
+        let object = checker
+            .node_types()
+            .iter()
+            .find(|(node, _)| *node == object)
+            .map(|(_, ty)| ty.clone())?;
+        let ty = checker
+            .type_at(span.start)
+            .map_or(Type::Any, |info| info.ty);
+        let Some((class, is_static)) = class_of(&checker, &object) else {
+            // Members of anything but a class are shown as plain properties
+            return Some(Described {
+                span,
+                display: format!("(property) {}: {}", name, ty),
+                documentation: None,
+                ty,
+                params: None,
+            });
+        };
+        let Type::Class(class_type) = &class.ty else {
+            return None;
+        };
+        let key = (class_type.name.clone(), name, is_static);
+        // The member is found in the body of the class, wherever that's declared
+        let (declared_in, offset) = class
+            .origin
+            .as_ref()
+            .map_or((file_name, class.span.start), |origin| {
+                (origin.file_name.as_str(), origin.span.start)
+            });
+        let declaring = self.file(declared_in)?;
+        let parsed = parse_typescript_recovering(&declaring.source, source_type_for(declared_in));
+        let members = Finder::search(&parsed, offset).members;
+        Some(describe_member(
+            &key,
+            span,
+            ty,
+            members.get(&key),
+            &parsed,
+            &declaring.source,
+        ))
+    }
+
+    /// A symbol as TypeScript shows it, looking up its declaration where it was originally
+    /// declared, and the JSDoc comment on that declaration.
+    fn describe_symbol(&self, file_name: &str, span: Span, symbol: &Symbol) -> Described {
+        let (declared_in, offset) = symbol
+            .origin
+            .as_ref()
+            .map_or((file_name, symbol.span.start), |origin| {
+                (origin.file_name.as_str(), origin.span.start)
+            });
+        let declaration = self.file(declared_in).and_then(|file| {
+            let parsed = parse_typescript_recovering(&file.source, source_type_for(declared_in));
+            let (declaration, start) = Finder::search(&parsed, offset).declaration?;
+            Some((declaration, documentation(&parsed, &file.source, start)))
+        });
+        let (declaration, documentation) = match declaration {
+            Some((declaration, documentation)) => (Some(declaration), documentation),
+            None => (None, None),
+        };
+
+        let name = &symbol.name;
+        let ty = symbol.ty.clone();
+        let found = declaration.is_some();
+        let (display, params) = match declaration {
+            Some(Declaration::Variable(keyword, params)) => {
+                (format!("{} {}: {}", keyword, name, ty), params)
+            }
+            Some(Declaration::Function(params)) => (
+                format!("function {}{}", name, signature(&params, &ty)),
+                Some(params),
+            ),
+            Some(Declaration::Class) => (format!("class {}", name), None),
+            Some(Declaration::Enum) => (format!("enum {}", name), None),
+            Some(Declaration::Namespace) => (format!("namespace {}", name), None),
+            // Without a declaration to go by, only the symbol's own kind is known
+            None => (symbol.to_string(), None),
+        };
+        let display = match (symbol.kind, found) {
+            (SymbolKind::Alias, true) => format!("(alias) {}", display),
+            _ => display,
+        };
+        Described {
+            span,
+            display,
+            documentation,
+            ty,
+            params,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TestProject;
+
+    const SHAPES: &str = r#"/**
+ * The number of sides every shape has at least.
+ * @see Shape
+ */
+export const MIN_SIDES = 3;
+export class Shape {
+    /** How many sides the shape has. */
+    sides: number = 3;
+    /** Scales the shape by `factor`. */
+    scale(factor: number, origin?: string): void {}
+    static unit(): number { return 1; }
+}
+export function area<T>(shape: T, ...sizes: number[]): number { return 0; }
+"#;
+    const MAIN: &str = r#"import { MIN_SIDES, Shape, area } from "./shapes";
+let count = MIN_SIDES;
+const shape = new Shape();
+shape.scale(2);
+Shape.unit();
+area(shape);
+function describe(label: string): string { return label; }
+const point = { x: 1 };
+point.x;
+"#;
+
+    fn quick_info(file: &str, source: &str, text: &str) -> (String, Option<String>) {
+        let program = TestProject::new()
+            .file("/shapes.ts", SHAPES)
+            .file("/main.ts", MAIN)
+            .roots(&["/main.ts"])
+            .program();
+        let offset = source.find(text).unwrap() as u32;
+        let info = program
+            .quick_info_at_position(file, offset)
+            .unwrap_or_else(|| panic!("no quick info for {}", text));
+        (info.display, info.documentation)
+    }
+
+    #[test]
+    fn test_declarations() {
+        let display = |text| quick_info("/main.ts", MAIN, text).0;
+        assert_eq!(display("count"), "let count: number");
+        assert_eq!(
+            display("shape ="),
+            "const shape: { sides: number; scale: (number, string) => void; }"
+        );
+        assert_eq!(
+            display("describe"),
+            "function describe(label: string): string"
+        );
+        assert_eq!(display("label: string)"), "(parameter) label: string");
+        assert_eq!(
+            display("area(shape)"),
+            "(alias) function area<T>(shape: T, ...sizes: number[]): number"
+        );
+        assert_eq!(quick_info("/shapes.ts", SHAPES, "Shape {").0, "class Shape");
+    }
+
+    #[test]
+    fn test_members() {
+        let display = |text| quick_info("/main.ts", MAIN, text).0;
+        assert_eq!(
+            display("scale(2)"),
+            "(method) Shape.scale(factor: number, origin?: string): void"
+        );
+        assert_eq!(display("unit()"), "(method) Shape.unit(): number");
+        assert_eq!(display("x;"), "(property) x: number");
+        assert_eq!(
+            quick_info("/shapes.ts", SHAPES, "sides:").0,
+            "(property) Shape.sides: number"
+        );
+    }
+
+    #[test]
+    fn test_documentation() {
+        assert_eq!(
+            quick_info("/main.ts", MAIN, "MIN_SIDES;"),
+            (
+                "(alias) const MIN_SIDES: 3".to_string(),
+                Some("The number of sides every shape has at least.".to_string())
+            )
+        );
+        assert_eq!(
+            quick_info("/main.ts", MAIN, "scale(2)").1.as_deref(),
+            Some("Scales the shape by `factor`.")
+        );
+        assert_eq!(
+            quick_info("/shapes.ts", SHAPES, "sides:").1.as_deref(),
+            Some("How many sides the shape has.")
+        );
+        assert_eq!(quick_info("/main.ts", MAIN, "count").1, None);
+    }
+}
@@ -0,0 +1,563 @@
+use crate::host::{FileSystem, normalize_path};
+use crate::pnp::PnpManifest;
+use oxc_ast::ast::{ImportDeclarationSpecifier, Program, Statement};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+const EXTENSIONS: [&str; 3] = [".ts", ".tsx", ".d.ts"];
+
+/// Resolves an import specifier relative to the file that contains it. Package names
+/// are looked up in the `node_modules` directories of the file's directory and those
+/// above it.
+///
+/// The path is the one the lookup went through; a package symlinked into `node_modules`,
+/// as pnpm links them, resolves under the link rather than where it's stored.
+pub fn resolve_module_name(
+    specifier: &str,
+    containing_file: &Path,
+    fs: &dyn FileSystem,
+) -> Option<PathBuf> {
+    resolve(specifier, containing_file, fs, None, None)
+}
+
+/// Like [`resolve_module_name`], also describing each step in `trace` as
+/// `--traceResolution` does: the candidates tried, and which one was used.
+pub fn resolve_module_name_traced(
+    specifier: &str,
+    containing_file: &Path,
+    fs: &dyn FileSystem,
+    trace: &mut Vec<String>,
+) -> Option<PathBuf> {
+    resolve_module_name_with(specifier, containing_file, fs, None, Some(trace))
+}
+
+/// Resolves an import specifier like [`resolve_module_name`], looking package names up in
+/// `pnp` for files in its project, which has no `node_modules` to walk, and describing
+/// each step in `trace` when there is one.
+pub fn resolve_module_name_with(
+    specifier: &str,
+    containing_file: &Path,
+    fs: &dyn FileSystem,
+    pnp: Option<&PnpManifest>,
+    trace: Option<&mut Vec<String>>,
+) -> Option<PathBuf> {
+    let Some(trace) = trace else {
+        return resolve(specifier, containing_file, fs, pnp, None);
+    };
+    trace.push(format!(
+        "======== Resolving module '{}' from '{}'. ========",
+        specifier,
+        containing_file.display()
+    ));
+    let resolved = resolve(specifier, containing_file, fs, pnp, Some(trace));
+    trace.push(match &resolved {
+        Some(path) => format!(
+            "======== Module name '{}' was successfully resolved to '{}'. ========",
+            specifier,
+            path.display()
+        ),
+        None => format!(
+            "======== Module name '{}' was not resolved. ========",
+            specifier
+        ),
+    });
+    resolved
+}
+
+fn resolve(
+    specifier: &str,
+    containing_file: &Path,
+    fs: &dyn FileSystem,
+    pnp: Option<&PnpManifest>,
+    trace: Option<&mut Vec<String>>,
+) -> Option<PathBuf> {
+    if !is_relative_specifier(specifier) {
+        return match pnp {
+            Some(pnp) if pnp.covers(containing_file) => {
+                resolve_pnp_package(specifier, containing_file, fs, pnp, trace)
+            }
+            _ => resolve_package(specifier, containing_file, fs, trace),
+        };
+    }
+    let dir = containing_file.parent().unwrap_or(Path::new(""));
+    first_existing(candidates(&normalize_path(&dir.join(specifier))), fs, trace)
+}
+
+/// The files an import of `base` could mean, in the order they're tried: `base` itself
+/// when it has a TypeScript extension, then with each extension, then its index file.
+fn candidates(base: &Path) -> Vec<PathBuf> {
+    let base_str = base.to_string_lossy();
+
+    let mut candidates = Vec::new();
+    if EXTENSIONS.iter().any(|ext| base_str.ends_with(ext)) {
+        candidates.push(base.to_path_buf());
+    }
+    // `./foo.js` in TypeScript source refers to `./foo.ts`
+    if let Some(stem) = base_str.strip_suffix(".js") {
+        candidates.extend(
+            EXTENSIONS
+                .iter()
+                .map(|ext| PathBuf::from(format!("{stem}{ext}"))),
+        );
+    }
+    candidates.extend(
+        EXTENSIONS
+            .iter()
+            .map(|ext| PathBuf::from(format!("{base_str}{ext}"))),
+    );
+    candidates.extend(
+        EXTENSIONS
+            .iter()
+            .map(|ext| base.join(format!("index{ext}"))),
+    );
+    candidates
+}
+
+fn first_existing(
+    candidates: Vec<PathBuf>,
+    fs: &dyn FileSystem,
+    mut trace: Option<&mut Vec<String>>,
+) -> Option<PathBuf> {
+    candidates.into_iter().find(|path| {
+        let exists = fs.file_exists(path);
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.push(match exists {
+                true => format!(
+                    "File '{}' exists - use it as a name resolution result.",
+                    path.display()
+                ),
+                false => format!("File '{}' does not exist.", path.display()),
+            });
+        }
+        exists
+    })
+}
+
+/// Splits a package specifier into the package's name, scope included, and the path
+/// within it, like `@scope/pkg` and `sub/file` for `@scope/pkg/sub/file`.
+fn split_package_name(specifier: &str) -> Option<(&str, Option<&str>)> {
+    let name_end = match specifier.starts_with('@') {
+        true => {
+            let scope_end = specifier.find('/')?;
+            specifier[scope_end + 1..]
+                .find('/')
+                .map(|index| scope_end + 1 + index)
+        }
+        false => specifier.find('/'),
+    };
+    match name_end {
+        Some(end) => Some((&specifier[..end], Some(&specifier[end + 1..]))),
+        None => Some((specifier, None)),
+    }
+}
+
+/// The name `@types` gives the types of a package: scoped packages like `@scope/pkg` are
+/// named `scope__pkg`.
+fn types_package_name(name: &str) -> String {
+    match name.strip_prefix('@') {
+        Some(scoped) => scoped.replacen('/', "__", 1),
+        None => name.to_string(),
+    }
+}
+
+/// Resolves a package name from the nearest `node_modules` directory that has it, trying
+/// the package's own types before those in `@types`.
+fn resolve_package(
+    specifier: &str,
+    containing_file: &Path,
+    fs: &dyn FileSystem,
+    mut trace: Option<&mut Vec<String>>,
+) -> Option<PathBuf> {
+    let (name, subpath) = split_package_name(specifier)?;
+    let types_name = types_package_name(name);
+    if let Some(trace) = trace.as_deref_mut() {
+        trace.push(format!(
+            "Loading module '{}' from 'node_modules' folder.",
+            specifier
+        ));
+    }
+    let mut dir = containing_file.parent();
+    while let Some(current) = dir {
+        dir = current.parent();
+        if current
+            .file_name()
+            .is_some_and(|name| name == "node_modules")
+        {
+            continue;
+        }
+        let modules = current.join("node_modules");
+        if fs.read_dir(&modules).is_err() {
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.push(format!(
+                    "Directory '{}' does not exist, skipping all lookups in it.",
+                    modules.display()
+                ));
+            }
+            continue;
+        }
+        for package in [modules.join(name), modules.join("@types").join(&types_name)] {
+            let resolved = resolve_in_package(&package, subpath, fs, trace.as_deref_mut());
+            if resolved.is_some() {
+                return resolved;
+            }
+        }
+    }
+    None
+}
+
+/// Resolves a package name from where a Yarn Plug'n'Play manifest says the dependency of
+/// that name is, for the package `containing_file` is in. Packages only see the `@types`
+/// packages they depend on.
+fn resolve_pnp_package(
+    specifier: &str,
+    containing_file: &Path,
+    fs: &dyn FileSystem,
+    pnp: &PnpManifest,
+    mut trace: Option<&mut Vec<String>>,
+) -> Option<PathBuf> {
+    let (name, subpath) = split_package_name(specifier)?;
+    if let Some(trace) = trace.as_deref_mut() {
+        trace.push(format!(
+            "Loading module '{}' with the Plug'n'Play manifest in '{}'.",
+            specifier,
+            pnp.dir().display()
+        ));
+    }
+    let types_name = format!("@types/{}", types_package_name(name));
+    for dependency in [name, types_name.as_str()] {
+        let Some(package) = pnp.package_location(dependency, containing_file) else {
+            if let Some(trace) = trace.as_deref_mut() {
+                trace.push(format!(
+                    "Package '{}' is not a dependency of the file's package.",
+                    dependency
+                ));
+            }
+            continue;
+        };
+        let resolved = resolve_in_package(&package, subpath, fs, trace.as_deref_mut());
+        if resolved.is_some() {
+            return resolved;
+        }
+    }
+    None
+}
+
+/// Resolves `subpath` within the package in `package`, or the package's types entry
+/// point when there's no subpath: its `package.json`'s `types` or `typings`, or else its
+/// index file.
+fn resolve_in_package(
+    package: &Path,
+    subpath: Option<&str>,
+    fs: &dyn FileSystem,
+    mut trace: Option<&mut Vec<String>>,
+) -> Option<PathBuf> {
+    if let Some(subpath) = subpath {
+        return first_existing(candidates(&package.join(subpath)), fs, trace);
+    }
+    let manifest = package.join("package.json");
+    let types = fs
+        .read_file(&manifest)
+        .ok()
+        .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+        .and_then(|json| {
+            let types = json.get("types").or_else(|| json.get("typings"))?;
+            types.as_str().map(str::to_string)
+        });
+    if let Some(types) = types {
+        let entry = normalize_path(&package.join(&types));
+        if let Some(trace) = trace.as_deref_mut() {
+            trace.push(format!(
+                "'package.json' has 'types' field '{}' that references '{}'.",
+                types,
+                entry.display()
+            ));
+        }
+        let resolved = first_existing(candidates(&entry), fs, trace.as_deref_mut());
+        if resolved.is_some() {
+            return resolved;
+        }
+    }
+    first_existing(candidates(package), fs, trace)
+}
+
+pub fn is_relative_specifier(specifier: &str) -> bool {
+    specifier.starts_with("./") || specifier.starts_with("../") || specifier.starts_with('/')
+}
+
+/// Collects the specifiers of every import and re-export in a parsed file.
+pub fn collect_module_specifiers(program: &Program) -> Vec<String> {
+    program
+        .body
+        .iter()
+        .filter_map(|stmt| match stmt {
+            Statement::ImportDeclaration(decl) => Some(decl.source.value.to_string()),
+            Statement::ExportNamedDeclaration(decl) => {
+                decl.source.as_ref().map(|s| s.value.to_string())
+            }
+            Statement::ExportAllDeclaration(decl) => Some(decl.source.value.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+/// The specifiers a file only imports types from, which are erased from emitted JavaScript:
+/// every import and re-export of them is `import type`, `export type`, or names only types.
+pub fn collect_type_only_specifiers(program: &Program) -> HashSet<String> {
+    let mut type_only = HashSet::new();
+    let mut value = HashSet::new();
+    for stmt in &program.body {
+        let (source, is_type) = match stmt {
+            Statement::ImportDeclaration(decl) => (
+                &decl.source,
+                decl.import_kind.is_type()
+                    || decl.specifiers.as_ref().is_some_and(|specifiers| {
+                        !specifiers.is_empty()
+                            && specifiers.iter().all(|specifier| match specifier {
+                                ImportDeclarationSpecifier::ImportSpecifier(s) => {
+                                    s.import_kind.is_type()
+                                }
+                                _ => false,
+                            })
+                    }),
+            ),
+            Statement::ExportNamedDeclaration(decl) => match &decl.source {
+                Some(source) => (
+                    source,
+                    decl.export_kind.is_type()
+                        || (!decl.specifiers.is_empty()
+                            && decl.specifiers.iter().all(|s| s.export_kind.is_type())),
+                ),
+                None => continue,
+            },
+            Statement::ExportAllDeclaration(decl) => (&decl.source, decl.export_kind.is_type()),
+            _ => continue,
+        };
+        match is_type {
+            true => type_only.insert(source.value.to_string()),
+            false => value.insert(source.value.to_string()),
+        };
+    }
+    type_only.retain(|specifier| !value.contains(specifier));
+    type_only
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::host::InMemoryFileSystem;
+    use crate::parser::parse_typescript;
+
+    #[test]
+    fn test_resolve_relative_modules() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/src/index.ts", "");
+        fs.add_file("/src/math.ts", "");
+        fs.add_file("/src/types.d.ts", "");
+        fs.add_file("/src/util/index.tsx", "");
+
+        let from = Path::new("/src/index.ts");
+        assert_eq!(
+            resolve_module_name("./math", from, &fs),
+            Some(PathBuf::from("/src/math.ts"))
+        );
+        assert_eq!(
+            resolve_module_name("./math.js", from, &fs),
+            Some(PathBuf::from("/src/math.ts"))
+        );
+        assert_eq!(
+            resolve_module_name("./types", from, &fs),
+            Some(PathBuf::from("/src/types.d.ts"))
+        );
+        assert_eq!(
+            resolve_module_name("./util", from, &fs),
+            Some(PathBuf::from("/src/util/index.tsx"))
+        );
+        assert_eq!(resolve_module_name("./missing", from, &fs), None);
+        assert_eq!(resolve_module_name("react", from, &fs), None);
+    }
+
+    #[test]
+    fn test_trace_resolution() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/src/util/index.ts", "");
+
+        let mut trace = Vec::new();
+        let from = Path::new("/src/main.ts");
+        let resolved = resolve_module_name_traced("./util", from, &fs, &mut trace);
+        assert_eq!(resolved, Some(PathBuf::from("/src/util/index.ts")));
+        assert_eq!(
+            trace,
+            vec![
+                "======== Resolving module './util' from '/src/main.ts'. ========",
+                "File '/src/util.ts' does not exist.",
+                "File '/src/util.tsx' does not exist.",
+                "File '/src/util.d.ts' does not exist.",
+                "File '/src/util/index.ts' exists - use it as a name resolution result.",
+                "======== Module name './util' was successfully resolved to '/src/util/index.ts'. ========",
+            ]
+        );
+
+        let mut trace = Vec::new();
+        assert_eq!(
+            resolve_module_name_traced("react", from, &fs, &mut trace),
+            None
+        );
+        assert_eq!(
+            &trace[1..],
+            [
+                "Loading module 'react' from 'node_modules' folder.",
+                "Directory '/src/node_modules' does not exist, skipping all lookups in it.",
+                "Directory '/node_modules' does not exist, skipping all lookups in it.",
+                "======== Module name 'react' was not resolved. ========",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resolve_packages() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/app/src/main.ts", "");
+        fs.add_file(
+            "/app/node_modules/typed/package.json",
+            r#"{ "name": "typed", "types": "./dist/main" }"#,
+        );
+        fs.add_file("/app/node_modules/typed/dist/main.d.ts", "");
+        fs.add_file("/app/node_modules/typed/extra.d.ts", "");
+        fs.add_file("/app/node_modules/plain/index.ts", "");
+        fs.add_file("/app/node_modules/@types/untyped/index.d.ts", "");
+        fs.add_file("/app/node_modules/@types/scope__lib/index.d.ts", "");
+        fs.add_file("/node_modules/outer/index.d.ts", "");
+
+        let from = Path::new("/app/src/main.ts");
+        let resolve = |specifier| resolve_module_name(specifier, from, &fs);
+        assert_eq!(
+            resolve("typed"),
+            Some(PathBuf::from("/app/node_modules/typed/dist/main.d.ts"))
+        );
+        assert_eq!(
+            resolve("typed/extra"),
+            Some(PathBuf::from("/app/node_modules/typed/extra.d.ts"))
+        );
+        assert_eq!(
+            resolve("plain"),
+            Some(PathBuf::from("/app/node_modules/plain/index.ts"))
+        );
+        assert_eq!(
+            resolve("untyped"),
+            Some(PathBuf::from("/app/node_modules/@types/untyped/index.d.ts"))
+        );
+        assert_eq!(
+            resolve("@scope/lib"),
+            Some(PathBuf::from(
+                "/app/node_modules/@types/scope__lib/index.d.ts"
+            ))
+        );
+        assert_eq!(
+            resolve("outer"),
+            Some(PathBuf::from("/node_modules/outer/index.d.ts"))
+        );
+        assert_eq!(resolve("missing"), None);
+    }
+
+    #[test]
+    fn test_resolve_pnp_packages() {
+        let mut fs = InMemoryFileSystem::new();
+        fs.add_file("/app/src/main.ts", "");
+        // Also installed, but not where the manifest says the dependency is
+        fs.add_file("/app/node_modules/typed/index.d.ts", "");
+        let typed = "/app/.yarn/unplugged/typed-npm-1.0.0/node_modules/typed";
+        fs.add_file(
+            format!("{typed}/package.json"),
+            r#"{ "types": "lib/main.d.ts" }"#,
+        );
+        fs.add_file(format!("{typed}/lib/main.d.ts"), "");
+        fs.add_file(format!("{typed}/extra.d.ts"), "");
+        let untyped = "/app/.yarn/unplugged/@types-untyped-npm-1.0.0/node_modules/@types/untyped";
+        fs.add_file(format!("{untyped}/index.d.ts"), "");
+        fs.add_file("/elsewhere/node_modules/typed/index.d.ts", "");
+        let pnp = PnpManifest::parse(
+            Path::new("/app"),
+            r#"{ "packageRegistryData": [
+                [null, [[null, { "packageLocation": "./", "packageDependencies": [
+                    ["typed", "npm:1.0.0"], ["untyped", "npm:1.0.0"], ["@types/untyped", "npm:1.0.0"]
+                ] }]]],
+                ["typed", [["npm:1.0.0", {
+                    "packageLocation": "./.yarn/unplugged/typed-npm-1.0.0/node_modules/typed/"
+                }]]],
+                ["@types/untyped", [["npm:1.0.0", {
+                    "packageLocation": "./.yarn/unplugged/@types-untyped-npm-1.0.0/node_modules/@types/untyped/"
+                }]]]
+            ] }"#,
+        )
+        .unwrap();
+
+        let from = Path::new("/app/src/main.ts");
+        let resolve =
+            |specifier, from| resolve_module_name_with(specifier, from, &fs, Some(&pnp), None);
+        assert_eq!(
+            resolve("typed", from),
+            Some(PathBuf::from(format!("{typed}/lib/main.d.ts")))
+        );
+        assert_eq!(
+            resolve("typed/extra", from),
+            Some(PathBuf::from(format!("{typed}/extra.d.ts")))
+        );
+        assert_eq!(
+            resolve("untyped", from),
+            Some(PathBuf::from(format!("{untyped}/index.d.ts")))
+        );
+        assert_eq!(resolve("missing", from), None);
+        // Files outside the project still look in `node_modules`
+        assert_eq!(
+            resolve("typed", Path::new("/elsewhere/main.ts")),
+            Some(PathBuf::from("/elsewhere/node_modules/typed/index.d.ts"))
+        );
+
+        let mut trace = Vec::new();
+        resolve_module_name_with("missing", from, &fs, Some(&pnp), Some(&mut trace));
+        assert_eq!(
+            &trace[1..],
+            [
+                "Loading module 'missing' with the Plug'n'Play manifest in '/app'.",
+                "Package 'missing' is not a dependency of the file's package.",
+                "Package '@types/missing' is not a dependency of the file's package.",
+                "======== Module name 'missing' was not resolved. ========",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_collect_module_specifiers() {
+        let source = r#"
+            import { a } from "./a";
+            export { b } from "./b";
+            export * from "./c";
+            export const d = 1;
+        "#;
+        let ts_program = parse_typescript(source).unwrap();
+        assert_eq!(
+            collect_module_specifiers(&ts_program.program),
+            vec!["./a", "./b", "./c"]
+        );
+    }
+
+    #[test]
+    fn test_collect_type_only_specifiers() {
+        let source = r#"
+            import type { A } from "./a";
+            import { type B, type C } from "./b";
+            import { type D, e } from "./d";
+            export type { F } from "./f";
+            export type * from "./g";
+            import type { H } from "./h";
+            import { h } from "./h";
+            import "./side-effect";
+        "#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut specifiers: Vec<String> = collect_type_only_specifiers(&ts_program.program)
+            .into_iter()
+            .collect();
+        specifiers.sort();
+        assert_eq!(specifiers, vec!["./a", "./b", "./f", "./g"]);
+    }
+}
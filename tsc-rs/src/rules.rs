@@ -0,0 +1,256 @@
+//! Custom checks that run over each file once it has been type checked, such as
+//! type-aware lint rules. A [`Rule`] sees every AST node along with the types the checker
+//! recorded for it, and reports its own diagnostics through a [`RuleContext`].
+
+use crate::diagnostics::Diagnostic;
+use crate::type_checker::TypeChecker;
+use crate::types::Type;
+use oxc_ast::ast::*;
+use oxc_ast::{AstKind, Visit};
+use oxc_span::{GetSpan, Span};
+use std::fmt;
+use std::sync::Arc;
+
+/// A check registered with [`crate::Program::add_rule`].
+pub trait Rule: Send + Sync {
+    /// The name diagnostics are tagged with, like `no-floating-promises`.
+    fn name(&self) -> &str;
+
+    /// Called for every node in the file, parents before their children.
+    fn check_node(&self, node: &AstKind<'_>, context: &mut RuleContext<'_>);
+}
+
+/// What a rule can see of the file being checked, and where it reports problems.
+pub struct RuleContext<'a> {
+    file_name: &'a str,
+    source: &'a str,
+    checker: &'a TypeChecker,
+    rule: &'a str,
+    diagnostics: Vec<Diagnostic>,
+}
+
+impl<'a> RuleContext<'a> {
+    pub fn file_name(&self) -> &str {
+        self.file_name
+    }
+
+    pub fn source(&self) -> &str {
+        self.source
+    }
+
+    /// The file's checker, for its symbols and references.
+    pub fn checker(&self) -> &TypeChecker {
+        self.checker
+    }
+
+    /// The type the checker recorded for the node at exactly `span`, if any.
+    pub fn type_of(&self, span: Span) -> Option<&Type> {
+        self.checker
+            .node_types()
+            .iter()
+            .rev()
+            .find(|(recorded, _)| *recorded == span)
+            .map(|(_, ty)| ty)
+    }
+
+    /// Reports an error at `span`, tagged with the rule's name.
+    pub fn report(&mut self, span: Span, message: impl fmt::Display) {
+        let diagnostic = self.diagnostic(span, message);
+        self.diagnostics.push(diagnostic);
+    }
+
+    /// Reports a warning at `span`, tagged with the rule's name.
+    pub fn warn(&mut self, span: Span, message: impl fmt::Display) {
+        let diagnostic = self.diagnostic(span, message).warning();
+        self.diagnostics.push(diagnostic);
+    }
+
+    fn diagnostic(&self, span: Span, message: impl fmt::Display) -> Diagnostic {
+        let message = format!("{} ({})", message, self.rule);
+        Diagnostic::with_span(self.file_name, message, span)
+    }
+}
+
+/// The rules a program runs, in the order they were added.
+#[derive(Clone, Default)]
+pub(crate) struct RuleSet(Vec<Arc<dyn Rule>>);
+
+impl RuleSet {
+    pub fn add(&mut self, rule: Arc<dyn Rule>) {
+        self.0.push(rule);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Runs every rule over `program`, checked by `checker`.
+    pub fn run(
+        &self,
+        file_name: &str,
+        source: &str,
+        program: &Program<'_>,
+        checker: &TypeChecker,
+    ) -> Vec<Diagnostic> {
+        let mut walker = RuleWalker {
+            rules: &self.0,
+            contexts: self
+                .0
+                .iter()
+                .map(|rule| RuleContext {
+                    file_name,
+                    source,
+                    checker,
+                    rule: rule.name(),
+                    diagnostics: Vec::new(),
+                })
+                .collect(),
+        };
+        walker.visit_program(program);
+        walker
+            .contexts
+            .into_iter()
+            .flat_map(|context| context.diagnostics)
+            .collect()
+    }
+}
+
+impl fmt::Debug for RuleSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|rule| rule.name()))
+            .finish()
+    }
+}
+
+struct RuleWalker<'r> {
+    rules: &'r [Arc<dyn Rule>],
+    contexts: Vec<RuleContext<'r>>,
+}
+
+impl<'a> Visit<'a> for RuleWalker<'_> {
+    fn enter_node(&mut self, kind: AstKind<'a>) {
+        for (rule, context) in self.rules.iter().zip(&mut self.contexts) {
+            rule.check_node(&kind, context);
+        }
+    }
+}
+
+/// The built-in rule called `name`, for enabling rules by name as `--rule` does.
+pub fn builtin_rule(name: &str) -> Option<Arc<dyn Rule>> {
+    match name {
+        "no-floating-promises" => Some(Arc::new(NoFloatingPromises)),
+        _ => None,
+    }
+}
+
+/// Reports promises created by expression statements and then dropped, whose rejections
+/// would go unhandled.
+pub struct NoFloatingPromises;
+
+impl Rule for NoFloatingPromises {
+    fn name(&self) -> &str {
+        "no-floating-promises"
+    }
+
+    fn check_node(&self, node: &AstKind<'_>, context: &mut RuleContext<'_>) {
+        let AstKind::ExpressionStatement(statement) = node else {
+            return;
+        };
+        let expression = statement.expression.without_parentheses();
+        if handles_rejection(expression) {
+            return;
+        }
+        if let Some(Type::Promise(_)) = context.type_of(expression.span()) {
+            context.report(
+                statement.span,
+                "Promises must be awaited, or have their rejection handled with '.catch'.",
+            );
+        }
+    }
+}
+
+/// Whether `expression` is a `.catch(...)` call or a `.then(...)` with a rejection handler.
+fn handles_rejection(expression: &Expression) -> bool {
+    let Expression::CallExpression(call) = expression else {
+        return false;
+    };
+    let Some(member) = call.callee.as_member_expression() else {
+        return false;
+    };
+    match member.static_property_name() {
+        Some("catch") => true,
+        Some("then") => call.arguments.len() >= 2,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CompilerOptions, DiagnosticCategory, Program};
+
+    struct NoDebugger;
+
+    impl Rule for NoDebugger {
+        fn name(&self) -> &str {
+            "no-debugger"
+        }
+
+        fn check_node(&self, node: &AstKind<'_>, context: &mut RuleContext<'_>) {
+            if let AstKind::DebuggerStatement(statement) = node {
+                context.warn(statement.span, "Unexpected 'debugger' statement.");
+            }
+        }
+    }
+
+    fn rule_messages(source: &str, rule: Arc<dyn Rule>) -> Vec<String> {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_rule(rule);
+        program.add_file("input.ts", source);
+        program
+            .check()
+            .into_iter()
+            .map(|diagnostic| diagnostic.message)
+            .collect()
+    }
+
+    #[test]
+    fn test_rules_report_through_the_program() {
+        let mut program = Program::new(CompilerOptions::default());
+        program.add_rule(Arc::new(NoDebugger));
+        program.add_file("input.ts", "let x = 1;\ndebugger;\n");
+        let diagnostics = program.check();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics[0].message,
+            "Unexpected 'debugger' statement. (no-debugger)"
+        );
+        assert_eq!(diagnostics[0].category, DiagnosticCategory::Warning);
+        assert_eq!(diagnostics[0].span, Some(Span::new(11, 20)));
+    }
+
+    #[test]
+    fn test_no_floating_promises() {
+        let rule = builtin_rule("no-floating-promises").unwrap();
+        let source = "
+            declare function load(): Promise<string>;
+            async function run() {
+                load();
+                await load();
+                load().catch(() => {});
+                load().then(() => {}, () => {});
+                load().then(() => {});
+                void load();
+            }
+        ";
+        assert_eq!(
+            rule_messages(source, rule),
+            vec![
+                "Promises must be awaited, or have their rejection handled with '.catch'. (no-floating-promises)";
+                2
+            ]
+        );
+        assert!(builtin_rule("no-such-rule").is_none());
+    }
+}
@@ -0,0 +1,689 @@
+use crate::parser::{parse_typescript_recovering, source_type_for};
+use crate::program::Program;
+use crate::symbols::SymbolKind;
+use crate::type_checker::TypeChecker;
+use crate::types::Type;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::Span;
+use oxc_syntax::scope::{ScopeFlags, ScopeId};
+use std::cell::Cell;
+use std::collections::HashMap;
+
+/// What a name is, for highlighting it. The discriminants index [`TokenType::LEGEND`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenType {
+    Namespace,
+    Class,
+    Enum,
+    Interface,
+    TypeParameter,
+    Type,
+    Parameter,
+    Variable,
+    Property,
+    EnumMember,
+    Function,
+    Method,
+}
+
+impl TokenType {
+    /// The names LSP clients know each token type by, in discriminant order.
+    pub const LEGEND: [&'static str; 12] = [
+        "namespace",
+        "class",
+        "enum",
+        "interface",
+        "typeParameter",
+        "type",
+        "parameter",
+        "variable",
+        "property",
+        "enumMember",
+        "function",
+        "method",
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        Self::LEGEND[*self as usize]
+    }
+}
+
+/// What sets a name apart from others of its type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TokenModifiers {
+    /// The name is being declared rather than referred to.
+    pub declaration: bool,
+    pub is_static: bool,
+    /// The name can't be assigned to: a `const`, a `readonly` member or an enum member.
+    pub readonly: bool,
+}
+
+impl TokenModifiers {
+    /// The names LSP clients know each modifier by, in the order of [`TokenModifiers::bits`].
+    pub const LEGEND: [&'static str; 3] = ["declaration", "static", "readonly"];
+
+    /// The modifiers as a set of bits indexing [`TokenModifiers::LEGEND`].
+    pub fn bits(&self) -> u32 {
+        [self.declaration, self.is_static, self.readonly]
+            .iter()
+            .enumerate()
+            .filter(|(_, set)| **set)
+            .map(|(index, _)| 1 << index)
+            .sum()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SemanticToken {
+    pub span: Span,
+    pub token_type: TokenType,
+    pub modifiers: TokenModifiers,
+}
+
+/// What a declared name is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Meaning {
+    token_type: TokenType,
+    readonly: bool,
+}
+
+impl Meaning {
+    fn new(token_type: TokenType) -> Self {
+        Self {
+            token_type,
+            readonly: false,
+        }
+    }
+}
+
+/// Which scope the binding identifiers being visited are declared in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Target {
+    Current,
+    /// The closest function or module scope, for `var`s.
+    Function,
+    /// The scope enclosing the current one, for function declarations, whose names are
+    /// visited once the function's own scope is entered.
+    Parent,
+}
+
+struct Scope {
+    parent: Option<usize>,
+    function: bool,
+    names: HashMap<String, Meaning>,
+}
+
+/// A `object.property` access that may be to a class or enum member.
+enum MemberAccess {
+    /// `Name.property`, with the scope `Name` is referred to in.
+    Named(String, usize),
+    /// `this.property` in the body of the class with this name.
+    This(String),
+}
+
+/// Collects the declarations in each scope of a file and the names referred to in them,
+/// to be matched up once the whole file has been seen.
+struct Classifier<'c> {
+    checker: Option<&'c TypeChecker>,
+    scopes: Vec<Scope>,
+    current: usize,
+    /// What the binding identifiers being visited declare, and where.
+    declaring: Option<(Meaning, Target)>,
+    /// The class whose body is being visited.
+    class: Option<String>,
+    /// Class members by class name, member name and whether they're static.
+    members: HashMap<(String, String, bool), Meaning>,
+    declarations: Vec<SemanticToken>,
+    references: Vec<(Span, String, usize)>,
+    accesses: Vec<(Span, String, MemberAccess)>,
+}
+
+impl Classifier<'_> {
+    fn declaring(&mut self, meaning: Meaning, target: Target, visit: impl FnOnce(&mut Self)) {
+        let outer = self.declaring.replace((meaning, target));
+        visit(self);
+        self.declaring = outer;
+    }
+
+    fn not_declaring(&mut self, visit: impl FnOnce(&mut Self)) {
+        let outer = self.declaring.take();
+        visit(self);
+        self.declaring = outer;
+    }
+
+    fn declare(&mut self, span: Span, name: &str, meaning: Meaning, target: Target) {
+        let mut scope = self.current;
+        match target {
+            Target::Current => {}
+            Target::Function => {
+                while !self.scopes[scope].function
+                    && let Some(parent) = self.scopes[scope].parent
+                {
+                    scope = parent;
+                }
+            }
+            Target::Parent => scope = self.scopes[scope].parent.unwrap_or(scope),
+        }
+        self.scopes[scope].names.insert(name.to_string(), meaning);
+        self.declarations.push(SemanticToken {
+            // Binding spans take in type annotations
+            span: Span::new(span.start, span.start + name.len() as u32),
+            token_type: meaning.token_type,
+            modifiers: TokenModifiers {
+                declaration: true,
+                readonly: meaning.readonly,
+                ..TokenModifiers::default()
+            },
+        });
+    }
+
+    fn resolve(&self, name: &str, mut scope: usize) -> Option<Meaning> {
+        loop {
+            if let Some(meaning) = self.scopes[scope].names.get(name) {
+                return Some(*meaning);
+            }
+            scope = self.scopes[scope].parent?;
+        }
+    }
+
+    /// What an import brings in, going by what the module it's from exports.
+    fn imported(&self, module: &str, name: &str) -> Meaning {
+        let Some(exports) = self
+            .checker
+            .and_then(|checker| checker.module_exports(module))
+        else {
+            return Meaning::new(TokenType::Variable);
+        };
+        let Some(export) = exports.iter().find(|export| export.name == name) else {
+            // Only values are exported symbols, so this is a type
+            return Meaning::new(TokenType::Type);
+        };
+        let token_type = match (export.kind, &export.ty) {
+            (SymbolKind::Class, _) | (_, Type::Class(_)) => TokenType::Class,
+            (SymbolKind::Enum, _) => TokenType::Enum,
+            (SymbolKind::Namespace, _) => TokenType::Namespace,
+            (SymbolKind::Function, _) | (_, Type::Function { .. } | Type::Generic { .. }) => {
+                TokenType::Function
+            }
+            _ => TokenType::Variable,
+        };
+        Meaning::new(token_type)
+    }
+
+    fn tokens(mut self) -> Vec<SemanticToken> {
+        let mut tokens = std::mem::take(&mut self.declarations);
+        for (span, name, scope) in &self.references {
+            if let Some(meaning) = self.resolve(name, *scope) {
+                tokens.push(SemanticToken {
+                    span: *span,
+                    token_type: meaning.token_type,
+                    modifiers: TokenModifiers {
+                        readonly: meaning.readonly,
+                        ..TokenModifiers::default()
+                    },
+                });
+            }
+        }
+        for (span, property, access) in &self.accesses {
+            let (meaning, is_static) = match access {
+                MemberAccess::Named(object, scope) => match self.resolve(object, *scope) {
+                    Some(Meaning {
+                        token_type: TokenType::Enum,
+                        ..
+                    }) => (
+                        Some(Meaning {
+                            token_type: TokenType::EnumMember,
+                            readonly: true,
+                        }),
+                        false,
+                    ),
+                    Some(Meaning {
+                        token_type: TokenType::Class,
+                        ..
+                    }) => {
+                        let key = (object.clone(), property.clone(), true);
+                        (self.members.get(&key).copied(), true)
+                    }
+                    _ => (None, false),
+                },
+                MemberAccess::This(class) => {
+                    let key = (class.clone(), property.clone(), false);
+                    (self.members.get(&key).copied(), false)
+                }
+            };
+            if let Some(meaning) = meaning {
+                tokens.push(SemanticToken {
+                    span: *span,
+                    token_type: meaning.token_type,
+                    modifiers: TokenModifiers {
+                        declaration: false,
+                        is_static,
+                        readonly: meaning.readonly,
+                    },
+                });
+            }
+        }
+        tokens.sort_by_key(|token| token.span.start);
+        tokens.dedup_by_key(|token| token.span.start);
+        tokens
+    }
+}
+
+impl<'a> Visit<'a> for Classifier<'_> {
+    fn enter_scope(&mut self, flags: ScopeFlags, _: &Cell<Option<ScopeId>>) {
+        self.scopes.push(Scope {
+            parent: Some(self.current),
+            function: flags.is_function() || flags.is_ts_module_block(),
+            names: HashMap::new(),
+        });
+        self.current = self.scopes.len() - 1;
+    }
+
+    fn leave_scope(&mut self) {
+        self.current = self.scopes[self.current].parent.unwrap_or(0);
+    }
+
+    fn visit_binding_identifier(&mut self, it: &BindingIdentifier<'a>) {
+        let (meaning, target) = self
+            .declaring
+            .unwrap_or((Meaning::new(TokenType::Variable), Target::Current));
+        self.declare(it.span, &it.name, meaning, target);
+    }
+
+    fn visit_identifier_reference(&mut self, it: &IdentifierReference<'a>) {
+        self.references
+            .push((it.span, it.name.to_string(), self.current));
+    }
+
+    fn visit_variable_declarator(&mut self, it: &VariableDeclarator<'a>) {
+        let meaning = Meaning {
+            token_type: TokenType::Variable,
+            readonly: it.kind.is_const(),
+        };
+        let target = match it.kind {
+            VariableDeclarationKind::Var => Target::Function,
+            _ => Target::Current,
+        };
+        self.declaring(meaning, target, |classifier| {
+            classifier.visit_binding_pattern(&it.id)
+        });
+        if let Some(init) = &it.init {
+            self.not_declaring(|classifier| classifier.visit_expression(init));
+        }
+    }
+
+    fn visit_binding_pattern(&mut self, it: &BindingPattern<'a>) {
+        self.visit_binding_pattern_kind(&it.kind);
+        // Annotations and default values refer to names rather than declaring them
+        if let Some(annotation) = &it.type_annotation {
+            self.not_declaring(|classifier| classifier.visit_ts_type_annotation(annotation));
+        }
+    }
+
+    fn visit_assignment_pattern(&mut self, it: &AssignmentPattern<'a>) {
+        self.visit_binding_pattern(&it.left);
+        self.not_declaring(|classifier| classifier.visit_expression(&it.right));
+    }
+
+    fn visit_binding_property(&mut self, it: &BindingProperty<'a>) {
+        self.not_declaring(|classifier| classifier.visit_property_key(&it.key));
+        self.visit_binding_pattern(&it.value);
+    }
+
+    fn visit_formal_parameter(&mut self, it: &FormalParameter<'a>) {
+        let meaning = Meaning {
+            token_type: TokenType::Parameter,
+            readonly: it.readonly,
+        };
+        self.declaring(meaning, Target::Current, |classifier| {
+            walk::walk_formal_parameter(classifier, it)
+        });
+    }
+
+    fn visit_function(&mut self, it: &Function<'a>, flags: ScopeFlags) {
+        // The name is visited once the function's scope is entered, where it's only
+        // declared for function expressions
+        let target = match it.r#type {
+            FunctionType::FunctionExpression => Target::Current,
+            _ => Target::Parent,
+        };
+        self.not_declaring(|classifier| {
+            classifier.enter_scope(flags, &it.scope_id);
+            if let Some(id) = &it.id {
+                classifier.declare(id.span, &id.name, Meaning::new(TokenType::Function), target);
+            }
+            if let Some(type_parameters) = &it.type_parameters {
+                classifier.visit_ts_type_parameter_declaration(type_parameters);
+            }
+            if let Some(this_param) = &it.this_param {
+                classifier.visit_ts_this_parameter(this_param);
+            }
+            classifier.visit_formal_parameters(&it.params);
+            if let Some(return_type) = &it.return_type {
+                classifier.visit_ts_type_annotation(return_type);
+            }
+            if let Some(body) = &it.body {
+                classifier.visit_function_body(body);
+            }
+            classifier.leave_scope();
+        });
+    }
+
+    fn visit_class(&mut self, it: &Class<'a>) {
+        self.not_declaring(|classifier| {
+            classifier.visit_decorators(&it.decorators);
+            if let Some(id) = &it.id {
+                let meaning = Meaning::new(TokenType::Class);
+                classifier.declare(id.span, &id.name, meaning, Target::Current);
+            }
+            classifier.enter_scope(ScopeFlags::StrictMode, &it.scope_id);
+            if let Some(type_parameters) = &it.type_parameters {
+                classifier.visit_ts_type_parameter_declaration(type_parameters);
+            }
+            if let Some(super_class) = &it.super_class {
+                classifier.visit_expression(super_class);
+            }
+            if let Some(super_type_parameters) = &it.super_type_parameters {
+                classifier.visit_ts_type_parameter_instantiation(super_type_parameters);
+            }
+            if let Some(implements) = &it.implements {
+                classifier.visit_ts_class_implementses(implements);
+            }
+            let outer = std::mem::replace(
+                &mut classifier.class,
+                it.id.as_ref().map(|id| id.name.to_string()),
+            );
+            classifier.visit_class_body(&it.body);
+            classifier.class = outer;
+            classifier.leave_scope();
+        });
+    }
+
+    fn visit_property_definition(&mut self, it: &PropertyDefinition<'a>) {
+        let meaning = Meaning {
+            token_type: TokenType::Property,
+            readonly: it.readonly,
+        };
+        self.declare_member(&it.key, it.r#static, meaning);
+        walk::walk_property_definition(self, it);
+    }
+
+    fn visit_method_definition(&mut self, it: &MethodDefinition<'a>) {
+        let token_type = match it.kind {
+            MethodDefinitionKind::Get | MethodDefinitionKind::Set => TokenType::Property,
+            _ => TokenType::Method,
+        };
+        if it.kind != MethodDefinitionKind::Constructor {
+            self.declare_member(&it.key, it.r#static, Meaning::new(token_type));
+        }
+        walk::walk_method_definition(self, it);
+    }
+
+    fn visit_static_member_expression(&mut self, it: &StaticMemberExpression<'a>) {
+        let access = match &it.object {
+            Expression::Identifier(object) => {
+                Some(MemberAccess::Named(object.name.to_string(), self.current))
+            }
+            Expression::ThisExpression(_) => self.class.clone().map(MemberAccess::This),
+            _ => None,
+        };
+        if let Some(access) = access {
+            let property = it.property.name.to_string();
+            self.accesses.push((it.property.span, property, access));
+        }
+        walk::walk_static_member_expression(self, it);
+    }
+
+    fn visit_ts_enum_declaration(&mut self, it: &TSEnumDeclaration<'a>) {
+        self.declare(
+            it.id.span,
+            &it.id.name,
+            Meaning::new(TokenType::Enum),
+            Target::Current,
+        );
+        self.enter_scope(ScopeFlags::empty(), &it.scope_id);
+        let meaning = Meaning {
+            token_type: TokenType::EnumMember,
+            readonly: true,
+        };
+        // Members refer to the ones before them without naming the enum
+        for member in &it.members {
+            if let TSEnumMemberName::Identifier(name) = &member.id {
+                self.declare(name.span, &name.name, meaning, Target::Current);
+            }
+            if let Some(initializer) = &member.initializer {
+                self.not_declaring(|classifier| classifier.visit_expression(initializer));
+            }
+        }
+        self.leave_scope();
+    }
+
+    fn visit_ts_type_alias_declaration(&mut self, it: &TSTypeAliasDeclaration<'a>) {
+        self.declare(
+            it.id.span,
+            &it.id.name,
+            Meaning::new(TokenType::Type),
+            Target::Current,
+        );
+        self.enter_scope(ScopeFlags::empty(), &it.scope_id);
+        if let Some(type_parameters) = &it.type_parameters {
+            self.visit_ts_type_parameter_declaration(type_parameters);
+        }
+        self.not_declaring(|classifier| classifier.visit_ts_type(&it.type_annotation));
+        self.leave_scope();
+    }
+
+    fn visit_ts_interface_declaration(&mut self, it: &TSInterfaceDeclaration<'a>) {
+        let meaning = Meaning::new(TokenType::Interface);
+        self.declare(it.id.span, &it.id.name, meaning, Target::Current);
+        self.not_declaring(|classifier| {
+            classifier.enter_scope(ScopeFlags::empty(), &it.scope_id);
+            if let Some(type_parameters) = &it.type_parameters {
+                classifier.visit_ts_type_parameter_declaration(type_parameters);
+            }
+            if let Some(extends) = &it.extends {
+                classifier.visit_ts_interface_heritages(extends);
+            }
+            classifier.visit_ts_interface_body(&it.body);
+            classifier.leave_scope();
+        });
+    }
+
+    fn visit_ts_type_parameter(&mut self, it: &TSTypeParameter<'a>) {
+        let meaning = Meaning::new(TokenType::TypeParameter);
+        self.declare(it.name.span, &it.name.name, meaning, Target::Current);
+        self.not_declaring(|classifier| {
+            if let Some(constraint) = &it.constraint {
+                classifier.visit_ts_type(constraint);
+            }
+            if let Some(default) = &it.default {
+                classifier.visit_ts_type(default);
+            }
+        });
+    }
+
+    fn visit_ts_module_declaration(&mut self, it: &TSModuleDeclaration<'a>) {
+        if let TSModuleDeclarationName::Identifier(id) = &it.id {
+            let meaning = Meaning::new(TokenType::Namespace);
+            self.declare(id.span, &id.name, meaning, Target::Current);
+        }
+        self.not_declaring(|classifier| {
+            classifier.enter_scope(ScopeFlags::TsModuleBlock, &it.scope_id);
+            if let Some(body) = &it.body {
+                classifier.visit_ts_module_declaration_body(body);
+            }
+            classifier.leave_scope();
+        });
+    }
+
+    fn visit_import_declaration(&mut self, it: &ImportDeclaration<'a>) {
+        let module = it.source.value.as_str();
+        for specifier in it.specifiers.iter().flatten() {
+            let (local, meaning) = match specifier {
+                ImportDeclarationSpecifier::ImportSpecifier(import) => (
+                    &import.local,
+                    self.imported(module, &import.imported.name()),
+                ),
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(import) => {
+                    (&import.local, self.imported(module, "default"))
+                }
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(import) => {
+                    (&import.local, Meaning::new(TokenType::Namespace))
+                }
+            };
+            self.declare(local.span, &local.name, meaning, Target::Current);
+        }
+    }
+}
+
+impl Classifier<'_> {
+    /// Declares a member of the class whose body is being visited, if it has a plain name.
+    fn declare_member(&mut self, key: &PropertyKey, is_static: bool, meaning: Meaning) {
+        let (Some(class), PropertyKey::StaticIdentifier(name)) = (&self.class, key) else {
+            return;
+        };
+        let key = (class.clone(), name.name.to_string(), is_static);
+        self.members.insert(key, meaning);
+        self.declarations.push(SemanticToken {
+            span: name.span,
+            token_type: meaning.token_type,
+            modifiers: TokenModifiers {
+                declaration: true,
+                is_static,
+                readonly: meaning.readonly,
+            },
+        });
+    }
+}
+
+impl Program {
+    /// Every name in a file that can be classified, in source order, with what it is.
+    pub fn semantic_tokens(&self, file_name: &str) -> Vec<SemanticToken> {
+        let Some(file) = self.file(file_name) else {
+            return Vec::new();
+        };
+        let parsed = parse_typescript_recovering(&file.source, source_type_for(file_name));
+        let checker = self.check_file(file_name);
+        let mut classifier = Classifier {
+            checker: checker.as_ref(),
+            scopes: vec![Scope {
+                parent: None,
+                function: true,
+                names: HashMap::new(),
+            }],
+            current: 0,
+            declaring: None,
+            class: None,
+            members: HashMap::new(),
+            declarations: Vec::new(),
+            references: Vec::new(),
+            accesses: Vec::new(),
+        };
+        classifier.visit_program(&parsed.program);
+        classifier.tokens()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TestProject;
+
+    /// Each token as its text, type name and modifier bits.
+    fn tokens(source: &str) -> Vec<(String, &'static str, u32)> {
+        let program = TestProject::new()
+            .file(
+                "/shapes.ts",
+                "export class Circle {}\nexport function area(): number { return 0; }\nexport type Size = number;",
+            )
+            .file("/main.ts", source)
+            .roots(&["/main.ts"])
+            .program();
+        program
+            .semantic_tokens("/main.ts")
+            .into_iter()
+            .map(|token| {
+                let text = &source[token.span.start as usize..token.span.end as usize];
+                (
+                    text.to_string(),
+                    token.token_type.as_str(),
+                    token.modifiers.bits(),
+                )
+            })
+            .collect()
+    }
+
+    fn token(text: &str, token_type: &'static str, bits: u32) -> (String, &'static str, u32) {
+        (text.to_string(), token_type, bits)
+    }
+
+    const DECLARATION: u32 = 1;
+    const STATIC: u32 = 2;
+    const READONLY: u32 = 4;
+
+    #[test]
+    fn test_declarations_and_references() {
+        let source = "const limit = 1;\nlet count = limit;\nfunction clamp<T>(value: T, max = limit): T { var inner = value; return inner; }\nclamp(count);";
+        assert_eq!(
+            tokens(source),
+            vec![
+                token("limit", "variable", DECLARATION | READONLY),
+                token("count", "variable", DECLARATION),
+                token("limit", "variable", READONLY),
+                token("clamp", "function", DECLARATION),
+                token("T", "typeParameter", DECLARATION),
+                token("value", "parameter", DECLARATION),
+                token("T", "typeParameter", 0),
+                token("max", "parameter", DECLARATION),
+                token("limit", "variable", READONLY),
+                token("T", "typeParameter", 0),
+                token("inner", "variable", DECLARATION),
+                token("value", "parameter", 0),
+                token("inner", "variable", 0),
+                token("clamp", "function", 0),
+                token("count", "variable", 0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_types_enums_and_class_members() {
+        let source = "interface Named { name: string }\nenum Color { Red, Green = Red }\nclass Point {\n    static readonly origin = 0;\n    x = Point.origin;\n    move(): Color { return this.x > 0 ? Color.Red : Color.Green; }\n}\nlet named: Named;";
+        let tokens = tokens(source);
+        for expected in [
+            token("Named", "interface", DECLARATION),
+            token("Color", "enum", DECLARATION),
+            token("Red", "enumMember", DECLARATION | READONLY),
+            token("Point", "class", DECLARATION),
+            token("origin", "property", DECLARATION | STATIC | READONLY),
+            token("origin", "property", STATIC | READONLY),
+            token("x", "property", 0),
+            token("move", "method", DECLARATION),
+            token("Green", "enumMember", READONLY),
+            token("named", "variable", DECLARATION),
+            token("Named", "interface", 0),
+        ] {
+            assert!(tokens.contains(&expected), "{:?} in {:?}", expected, tokens);
+        }
+        // `Green = Red` refers to the member before it
+        let reds: Vec<_> = tokens.iter().filter(|(text, ..)| text == "Red").collect();
+        assert_eq!(reds.len(), 3);
+    }
+
+    #[test]
+    fn test_imports_take_the_kind_they_are_exported_as() {
+        let source = "import { Circle, area, type Size } from \"./shapes\";\nlet size: Size = area();\nnew Circle();";
+        let tokens = tokens(source);
+        assert_eq!(
+            &tokens[..3],
+            &[
+                token("Circle", "class", DECLARATION),
+                token("area", "function", DECLARATION),
+                token("Size", "type", DECLARATION),
+            ]
+        );
+        assert!(tokens.contains(&token("Size", "type", 0)));
+        assert!(tokens.contains(&token("Circle", "class", 0)));
+    }
+}
@@ -0,0 +1,225 @@
+use crate::parser::{parse_typescript, parse_typescript_recovering, source_type_for};
+use crate::program::Program;
+use crate::quick_info::Signature;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::{GetSpan, Span};
+
+/// What an editor shows while the arguments of a call are being typed: the callee's
+/// signature, with the parameter the argument at the position is passed to picked out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureHelp {
+    pub signatures: Vec<SignatureInformation>,
+    /// The index into `signatures` of the one the call uses.
+    pub active_signature: usize,
+    /// The index of the parameter the argument at the position is passed to.
+    pub active_parameter: usize,
+    /// The call's argument list, parentheses included.
+    pub applicable_span: Span,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SignatureInformation {
+    /// The whole signature, like `scale(factor: number, origin?: string): void`.
+    pub label: String,
+    /// Each parameter the way `label` writes it, like `factor: number`.
+    pub parameters: Vec<String>,
+    pub documentation: Option<String>,
+}
+
+/// A call whose argument list covers the position.
+struct Call {
+    /// The name the callee goes by and where it is, when it has one.
+    callee: Option<(String, u32)>,
+    arguments: Span,
+    /// How many arguments come before the position.
+    preceding: usize,
+}
+
+/// Finds the innermost call whose argument list covers `offset`.
+struct CallFinder<'s> {
+    source: &'s str,
+    offset: u32,
+    found: Option<Call>,
+}
+
+impl<'a> Visit<'a> for CallFinder<'_> {
+    fn visit_call_expression(&mut self, it: &CallExpression<'a>) {
+        let after_callee = it
+            .type_parameters
+            .as_ref()
+            .map_or(it.callee.span().end, |params| params.span.end);
+        let open = self.source[after_callee as usize..it.span.end as usize]
+            .find('(')
+            .map(|index| after_callee + index as u32);
+        if let Some(open) = open
+            && open < self.offset
+            && self.offset < it.span.end
+        {
+            let callee = match it.callee.without_parentheses() {
+                Expression::Identifier(ident) => Some((ident.name.to_string(), ident.span.start)),
+                Expression::StaticMemberExpression(member) => {
+                    Some((member.property.name.to_string(), member.property.span.start))
+                }
+                _ => None,
+            };
+            let preceding = it
+                .arguments
+                .iter()
+                .filter(|argument| {
+                    // An argument comes before the position once the comma after it does
+                    let end = argument.span().end;
+                    end <= self.offset
+                        && self.source[end as usize..self.offset as usize]
+                            .trim_start()
+                            .starts_with(',')
+                })
+                .count();
+            self.found = Some(Call {
+                callee,
+                arguments: Span::new(open, it.span.end),
+                preceding,
+            });
+        }
+        // Calls nested in the arguments are closer to the position
+        walk::walk_call_expression(self, it);
+    }
+}
+
+impl Program {
+    /// The signature of the call whose argument list `offset` is in, and the parameter
+    /// the argument there is passed to.
+    pub fn signature_help_at_position(
+        &self,
+        file_name: &str,
+        offset: u32,
+    ) -> Option<SignatureHelp> {
+        let file = self.file(file_name)?;
+        if parse_typescript(&file.source).is_err() {
+            // An argument list still being typed is closed, so the call parses
+            let mut source = file.source.clone();
+            source.insert(offset as usize, ')');
+            let mut patched = self.clone();
+            patched.update_file(file_name, source);
+            return patched.signature_help_in(file_name, offset);
+        }
+        self.signature_help_in(file_name, offset)
+    }
+
+    fn signature_help_in(&self, file_name: &str, offset: u32) -> Option<SignatureHelp> {
+        let file = self.file(file_name)?;
+        let parsed = parse_typescript_recovering(&file.source, source_type_for(file_name));
+        let mut finder = CallFinder {
+            source: &file.source,
+            offset,
+            found: None,
+        };
+        finder.visit_program(&parsed.program);
+        let call = finder.found?;
+        let (name, position) = call.callee?;
+
+        let described = self.describe_at(file_name, position)?;
+        let names = described.params.unwrap_or_default();
+        let signature = Signature::of(&names, &described.ty)?;
+        // Everything after the last parameter goes to it when it's a rest parameter
+        let last = signature.params.len().saturating_sub(1);
+        let active_parameter = match signature.params.last() {
+            Some(param) if param.starts_with("...") => call.preceding.min(last),
+            _ => call.preceding,
+        };
+        Some(SignatureHelp {
+            signatures: vec![SignatureInformation {
+                label: format!("{}{}", name, signature),
+                parameters: signature.params,
+                documentation: described.documentation,
+            }],
+            active_signature: 0,
+            active_parameter,
+            applicable_span: call.arguments,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::testing::TestProject;
+
+    const GEOMETRY: &str = r#"/** Scales every size by `factor`. */
+export function scale(factor: number, ...sizes: number[]): number[] { return sizes; }
+export class Point {
+    move(dx: number, dy?: number): void {}
+}
+"#;
+
+    fn signature_help(source: &str, marker: &str) -> Option<(String, Vec<String>, usize)> {
+        let program = TestProject::new()
+            .file("/geometry.ts", GEOMETRY)
+            .file("/main.ts", source)
+            .roots(&["/main.ts"])
+            .program();
+        let offset = source.find(marker).unwrap() + marker.len();
+        let help = program.signature_help_at_position("/main.ts", offset as u32)?;
+        let signature = help.signatures.into_iter().next().unwrap();
+        Some((signature.label, signature.parameters, help.active_parameter))
+    }
+
+    #[test]
+    fn test_active_parameter() {
+        let source = r#"import { scale, Point } from "./geometry";
+const double = (value: number, by: number) => value * by;
+double(1, 2);
+scale(2, 1, 2, 3);
+new Point().move(1);
+"#;
+        let (label, parameters, active) = signature_help(source, "double(").unwrap();
+        assert_eq!(label, "double(value: number, by: number): number");
+        assert_eq!(parameters, vec!["value: number", "by: number"]);
+        assert_eq!(active, 0);
+        assert_eq!(signature_help(source, "double(1,").unwrap().2, 1);
+
+        let (label, _, active) = signature_help(source, "scale(2, 1, 2,").unwrap();
+        assert_eq!(label, "scale(factor: number, ...sizes: number[]): number[]");
+        assert_eq!(active, 1);
+
+        let (label, _, active) = signature_help(source, "move(").unwrap();
+        assert_eq!(label, "move(dx: number, dy?: number): void");
+        assert_eq!(active, 0);
+
+        assert!(signature_help(source, "import {").is_none());
+    }
+
+    #[test]
+    fn test_nested_and_unfinished_calls() {
+        let source = "import { scale } from \"./geometry\";\nconst twice = (n: number) => n * 2;\nscale(twice(1), 2);";
+        let (label, _, active) = signature_help(source, "twice(").unwrap();
+        assert_eq!(label, "twice(n: number): number");
+        assert_eq!(active, 0);
+        assert_eq!(signature_help(source, "twice(1), ").unwrap().2, 1);
+
+        let unfinished = "function clamp(value: number, max: number) {}\nclamp(1, ";
+        let (label, _, active) = signature_help(unfinished, "clamp(1, ").unwrap();
+        assert_eq!(label, "clamp(value: number, max: number): any");
+        assert_eq!(active, 1);
+    }
+
+    #[test]
+    fn test_documentation() {
+        let source = "import { scale } from \"./geometry\";\nscale(1);";
+        let program = TestProject::new()
+            .file("/geometry.ts", GEOMETRY)
+            .file("/main.ts", source)
+            .roots(&["/main.ts"])
+            .program();
+        let help = program
+            .signature_help_at_position("/main.ts", source.find("1)").unwrap() as u32)
+            .unwrap();
+        assert_eq!(
+            help.signatures[0].documentation.as_deref(),
+            Some("Scales every size by `factor`.")
+        );
+        let start = source.find("(1)").unwrap() as u32;
+        assert_eq!(help.applicable_span.start, start);
+        assert_eq!(help.applicable_span.end, start + 3);
+    }
+}
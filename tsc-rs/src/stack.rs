@@ -0,0 +1,30 @@
+//! Running deeply recursive work, like parsing and checking, on a thread with room for it.
+//!
+//! The parser and checker recurse once per level of nesting in the source, so generated
+//! or hostile code nested thousands of levels deep would overflow the 2 MiB stacks that
+//! spawned threads get by default.
+
+/// Stack size for parsing and checking; only the pages actually used are committed.
+#[cfg(not(target_arch = "wasm32"))]
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// Runs `f` on a thread with a large stack, waiting for its result and passing on any
+/// panic.
+#[cfg(not(target_arch = "wasm32"))]
+pub(crate) fn with_large_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    std::thread::scope(|scope| {
+        let handle = std::thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, f)
+            .expect("failed to spawn a thread to parse or check on");
+        handle
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic))
+    })
+}
+
+/// WebAssembly has no threads to spawn, so `f` runs on the current stack.
+#[cfg(target_arch = "wasm32")]
+pub(crate) fn with_large_stack<T: Send>(f: impl FnOnce() -> T + Send) -> T {
+    f()
+}
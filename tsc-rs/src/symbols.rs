@@ -0,0 +1,73 @@
+use crate::intern::Atom;
+use crate::types::Type;
+use oxc_span::Span;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SymbolId(pub usize);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SymbolKind {
+    Variable,
+    Function,
+    Parameter,
+    Alias,
+    Class,
+    Enum,
+    Namespace,
+}
+
+impl fmt::Display for SymbolKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SymbolKind::Variable => write!(f, "variable"),
+            SymbolKind::Function => write!(f, "function"),
+            SymbolKind::Parameter => write!(f, "parameter"),
+            SymbolKind::Alias => write!(f, "alias"),
+            SymbolKind::Class => write!(f, "class"),
+            SymbolKind::Enum => write!(f, "enum"),
+            SymbolKind::Namespace => write!(f, "namespace"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub file_name: String,
+    pub span: Span,
+}
+
+impl Location {
+    pub fn new(file_name: impl Into<String>, span: Span) -> Self {
+        Self {
+            file_name: file_name.into(),
+            span,
+        }
+    }
+}
+
+/// A named declaration, with the span of the identifier that declared it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Symbol {
+    pub name: Atom,
+    pub kind: SymbolKind,
+    pub ty: Type,
+    pub span: Span,
+    /// Where an imported or re-exported alias was originally declared.
+    pub origin: Option<Location>,
+}
+
+/// A symbol as seen by modules importing it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExportedSymbol {
+    pub name: Atom,
+    pub kind: SymbolKind,
+    pub ty: Type,
+    pub location: Location,
+}
+
+impl fmt::Display for Symbol {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}) {}: {}", self.kind, self.name, self.ty)
+    }
+}
@@ -0,0 +1,104 @@
+//! Helpers for checking multi-file programs entirely in memory.
+
+use crate::diagnostics::Diagnostic;
+use crate::host::InMemoryFileSystem;
+use crate::options::CompilerOptions;
+use crate::program::Program;
+
+/// An in-memory project: a map of path -> source plus the files to start loading from.
+#[derive(Debug, Clone, Default)]
+pub struct TestProject {
+    fs: InMemoryFileSystem,
+    files: Vec<String>,
+    roots: Option<Vec<String>>,
+    options: CompilerOptions,
+}
+
+impl TestProject {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn file(mut self, path: &str, source: &str) -> Self {
+        self.fs.add_file(path, source);
+        self.files.push(path.to_string());
+        self
+    }
+
+    /// Restricts the root set; by default every added file is a root.
+    pub fn roots(mut self, roots: &[&str]) -> Self {
+        self.roots = Some(roots.iter().map(|r| r.to_string()).collect());
+        self
+    }
+
+    pub fn options(mut self, options: CompilerOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn fs(&self) -> &InMemoryFileSystem {
+        &self.fs
+    }
+
+    pub fn program(&self) -> Program {
+        let roots = self.roots.as_ref().unwrap_or(&self.files);
+        Program::load(self.options.clone(), roots, &self.fs)
+    }
+
+    pub fn check(&self) -> Vec<Diagnostic> {
+        self.program().check()
+    }
+}
+
+/// Checks every `(path, source)` pair as a root file and returns all diagnostics.
+pub fn check_files(files: &[(&str, &str)]) -> Vec<Diagnostic> {
+    files
+        .iter()
+        .fold(TestProject::new(), |project, (path, source)| {
+            project.file(path, source)
+        })
+        .check()
+}
+
+/// Flattens diagnostics into `(file_name, message)` pairs for concise assertions.
+pub fn messages(diagnostics: &[Diagnostic]) -> Vec<(&str, &str)> {
+    diagnostics
+        .iter()
+        .map(|d| (d.file_name.as_str(), d.message.as_str()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_files() {
+        let diagnostics = check_files(&[
+            ("/a.ts", "let x: number = 1;"),
+            ("/b.ts", "let y: boolean = 'no';"),
+        ]);
+        assert_eq!(
+            messages(&diagnostics),
+            vec![("/b.ts", "Type 'string' is not assignable to type 'boolean'")]
+        );
+    }
+
+    #[test]
+    fn test_project_roots_follow_imports() {
+        let project = TestProject::new()
+            .file("/src/main.ts", r#"import "./dep";"#)
+            .file("/src/dep.ts", "let z: string = 3;")
+            .file("/src/unused.ts", "let w: string = 3;")
+            .roots(&["/src/main.ts"]);
+
+        let diagnostics = project.check();
+        assert_eq!(
+            messages(&diagnostics),
+            vec![(
+                "/src/dep.ts",
+                "Type 'number' is not assignable to type 'string'"
+            )]
+        );
+    }
+}
@@ -0,0 +1,143 @@
+//! Trace events for a checking run, in the Chrome trace event format read by
+//! `chrome://tracing` and `@typescript/analyze-trace`, like `tsc --generateTrace`.
+
+use serde_json::{Value, json};
+use std::time::{Duration, Instant};
+
+/// A span of work during checking, such as parsing one file.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceEvent {
+    pub name: &'static str,
+    pub category: &'static str,
+    /// When the work started, relative to the start of the trace.
+    pub start: Duration,
+    pub duration: Duration,
+    pub args: Vec<(&'static str, String)>,
+}
+
+/// A type relation timed by the checker, recorded while tracing so slow ones can be found.
+#[derive(Debug, Clone)]
+pub(crate) struct TimedRelation {
+    pub started: Instant,
+    pub ended: Instant,
+    pub source: String,
+    pub target: String,
+}
+
+/// The events recorded by [`Program::check_with_trace`](crate::program::Program::check_with_trace).
+#[derive(Debug, Clone)]
+pub struct Trace {
+    origin: Instant,
+    events: Vec<TraceEvent>,
+}
+
+impl Default for Trace {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Trace {
+    /// Starts an empty trace whose timestamps count from now.
+    pub fn new() -> Self {
+        Trace {
+            origin: Instant::now(),
+            events: Vec::new(),
+        }
+    }
+
+    pub fn events(&self) -> &[TraceEvent] {
+        &self.events
+    }
+
+    pub(crate) fn record(
+        &mut self,
+        name: &'static str,
+        category: &'static str,
+        started: Instant,
+        ended: Instant,
+        args: Vec<(&'static str, String)>,
+    ) {
+        self.events.push(TraceEvent {
+            name,
+            category,
+            start: started.saturating_duration_since(self.origin),
+            duration: ended.saturating_duration_since(started),
+            args,
+        });
+    }
+
+    pub(crate) fn record_relations(&mut self, relations: Vec<TimedRelation>) {
+        for relation in relations {
+            self.record(
+                "structuredTypeRelatedTo",
+                "checkTypes",
+                relation.started,
+                relation.ended,
+                vec![("source", relation.source), ("target", relation.target)],
+            );
+        }
+    }
+
+    /// The trace as a JSON array of complete (`"ph": "X"`) events, with timestamps in
+    /// microseconds, preceded by metadata naming the process and thread.
+    pub fn to_json(&self) -> Value {
+        let metadata = [("process_name", "tsc-rs"), ("thread_name", "Main")]
+            .into_iter()
+            .map(|(name, value)| {
+                json!({ "name": name, "ph": "M", "pid": 1, "tid": 1, "args": { "name": value } })
+            });
+        let events = self.events.iter().map(|event| {
+            let args: serde_json::Map<String, Value> = event
+                .args
+                .iter()
+                .map(|(key, value)| (key.to_string(), Value::from(value.as_str())))
+                .collect();
+            json!({
+                "name": event.name,
+                "cat": event.category,
+                "ph": "X",
+                "pid": 1,
+                "tid": 1,
+                "ts": micros(event.start),
+                "dur": micros(event.duration),
+                "args": args,
+            })
+        });
+        Value::Array(metadata.chain(events).collect())
+    }
+}
+
+fn micros(duration: Duration) -> f64 {
+    duration.as_nanos() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trace_to_json() {
+        let mut trace = Trace::new();
+        let started = trace.origin + Duration::from_micros(5);
+        trace.record(
+            "createSourceFile",
+            "parse",
+            started,
+            started + Duration::from_micros(20),
+            vec![("path", "/main.ts".to_string())],
+        );
+
+        let json = trace.to_json();
+        let events = json.as_array().unwrap();
+        assert_eq!(events.len(), 3);
+        assert_eq!(events[0]["ph"], "M");
+        assert_eq!(events[0]["args"]["name"], "tsc-rs");
+        assert_eq!(events[2]["name"], "createSourceFile");
+        assert_eq!(events[2]["cat"], "parse");
+        assert_eq!(events[2]["ph"], "X");
+        assert_eq!(events[2]["ts"], 5.0);
+        assert_eq!(events[2]["dur"], 20.0);
+        assert_eq!(events[2]["args"]["path"], "/main.ts");
+    }
+}
@@ -1,109 +1,632 @@
+use crate::builtins::{global_type, global_value, regexp_type};
+use crate::diagnostics::Message;
+use crate::intern::Atom;
+use crate::isolated_modules::is_module;
+use crate::options::{ModuleKind, ScriptTarget, Strictness};
+use crate::symbols::*;
+use crate::trace::TimedRelation;
+use crate::type_printer::TypePrinter;
 use crate::types::*;
 use oxc_ast::ast::*;
-use std::collections::HashMap;
+use oxc_span::{GetSpan, Span};
+use oxc_syntax::operator::UnaryOperator;
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 use std::sync::Arc;
+use std::time::Instant;
 
+mod aliases;
+mod arrays;
+mod assertions;
+mod assignments;
+mod classes;
+mod closures;
+mod decorators;
+mod enums;
+mod exceptions;
+mod flow;
+mod functions;
+mod generics;
+mod interfaces;
+mod loops;
+mod modules;
+mod namespaces;
+mod narrowing;
+mod objects;
+mod operators;
+mod scopes;
+
+use functions::function_type;
+use narrowing::literal_type;
+use objects::record_type;
+use operators::{bigint_literal_value, negated_bigint, numeric_literal_value};
+use scopes::Scope;
+
+/// Whether `ty` is or includes a literal type.
+fn has_literal(ty: &Type) -> bool {
+    match ty.resolved() {
+        Type::StringLiteral(_)
+        | Type::NumberLiteral(_)
+        | Type::BooleanLiteral(_)
+        | Type::BigIntLiteral(_) => true,
+        Type::Union(types) => types.iter().any(has_literal),
+        _ => false,
+    }
+}
+
+/// The primitive a literal type belongs to, and any other type as written, to describe a
+/// value by in messages as tsc does.
+fn literal_widened(ty: &Type) -> Type {
+    match ty.resolved() {
+        Type::StringLiteral(_)
+        | Type::NumberLiteral(_)
+        | Type::BooleanLiteral(_)
+        | Type::BigIntLiteral(_) => ty.widened(),
+        _ => ty.clone(),
+    }
+}
+
+#[derive(Clone)]
 pub struct TypeChecker {
     errors: Vec<String>,
-    symbol_table: HashMap<String, Type>,
+    // Where each of `errors` was found
+    error_spans: Vec<Span>,
+    // The code and arguments each of `errors` was built from
+    error_messages: Vec<Message>,
+    // Scopes being checked, the module's first and the innermost last
+    scopes: Vec<Scope>,
+    // Scopes already checked, kept for position queries
+    closed_scopes: Vec<Scope>,
+    symbols: Vec<Symbol>,
+    // Every checked expression and declared binding, for position queries
+    node_types: Vec<(Span, Type)>,
+    references: Vec<(Span, SymbolId)>,
+    // Exports of already-checked modules, keyed by the specifier this file imports them with
+    module_exports: HashMap<String, Vec<ExportedSymbol>>,
+    exports: Vec<(Atom, SymbolId)>,
+    // Type aliases, interfaces and class instance types in scope, with the span of the
+    // declaration each came from
+    type_aliases: HashMap<String, (Span, Arc<AliasType>)>,
+    // Top-level interface declarations, whose members are resolved once something needs them
+    interfaces: HashSet<Span>,
+    // Type parameters in scope, innermost last
+    type_parameters: Vec<Arc<TypeParameter>>,
+    // Keys of the mapped types whose value types are being checked, innermost last
+    mapped_keys: RefCell<Vec<Arc<TypeParameter>>>,
+    // The type the expression at this span is expected to have, for inferring type arguments
+    contextual_type: Option<(Span, Type)>,
+    // Whether literals are being checked as if written with `as const`
+    const_context: bool,
+    // Whether decorators follow the legacy `experimentalDecorators` semantics
+    experimental_decorators: bool,
+    // Whether throwing anything but an `Error` is reported
+    only_throw_error: bool,
+    // Whether switch cases that run on into the next one are reported
+    no_fallthrough_cases_in_switch: bool,
+    // Whether overriding a base class member without `override` is reported
+    no_implicit_override: bool,
+    // Whether types in error messages are printed in full, however long
+    no_error_truncation: bool,
+    // The strict family flags in effect
+    strictness: Strictness,
+    // What `this` is in the function being checked, when it's known
+    this_type: Option<Type>,
+    // What `this` is in the next function checked, set for object literal methods
+    method_this: Option<Type>,
+    // The module system and language version the file is emitted for
+    module_kind: ModuleKind,
+    target: ScriptTarget,
+    // Types symbols are narrowed to in the statements being checked, innermost last
+    narrowed: Vec<(SymbolId, Type)>,
+    // Variables declared with `const`, whose narrowings hold even inside closures
+    constants: HashSet<SymbolId>,
+    // Constants initialized with a literal, whose literal type widens when it's copied
+    // into a variable that can change
+    literal_constants: HashSet<SymbolId>,
+    // Every identifier assigned to in the file, by name
+    assignments: Vec<(Atom, Span)>,
+    // Constants holding a `typeof` or discriminant comparison, which narrow like the
+    // comparison itself
+    condition_aliases: HashMap<SymbolId, narrowing::Guard>,
+    // Constants destructured from a variable, with the property each holds; comparing
+    // one narrows the variable by that property
+    discriminant_aliases: HashMap<SymbolId, (SymbolId, String)>,
+    // Enums declared with `const`, which have no object to reference at runtime
+    const_enums: HashSet<SymbolId>,
+    // Enums with a declaration whose first member has no initializer, which only one may
+    enums_counting_from_zero: HashSet<SymbolId>,
+    // Unannotated variables declared without a value or as `[]`, whose type follows what's
+    // assigned or pushed
+    evolving: HashSet<SymbolId>,
+    // Relations between structured types and how long each took, when being traced
+    relation_trace: Option<RefCell<Vec<TimedRelation>>>,
+    // Whether the file is a script, whose top-level declarations are global
+    is_script: bool,
+    // Whether the body of a `declare global` is being checked
+    declaring_globals: bool,
+    // Declarations that are also properties of `globalThis`, in the order they're declared
+    globals: Vec<SymbolId>,
 }
 
 impl TypeChecker {
     pub fn new() -> Self {
         TypeChecker {
             errors: Vec::new(),
-            symbol_table: HashMap::new(),
+            error_spans: Vec::new(),
+            error_messages: Vec::new(),
+            scopes: vec![Scope::module()],
+            closed_scopes: Vec::new(),
+            symbols: Vec::new(),
+            node_types: Vec::new(),
+            references: Vec::new(),
+            module_exports: HashMap::new(),
+            exports: Vec::new(),
+            type_aliases: HashMap::new(),
+            interfaces: HashSet::new(),
+            type_parameters: Vec::new(),
+            mapped_keys: RefCell::new(Vec::new()),
+            contextual_type: None,
+            const_context: false,
+            experimental_decorators: false,
+            only_throw_error: false,
+            no_fallthrough_cases_in_switch: false,
+            no_implicit_override: false,
+            no_error_truncation: false,
+            strictness: Strictness::STRICT,
+            this_type: None,
+            method_this: None,
+            module_kind: ModuleKind::default(),
+            target: ScriptTarget::default(),
+            narrowed: Vec::new(),
+            constants: HashSet::new(),
+            literal_constants: HashSet::new(),
+            assignments: Vec::new(),
+            condition_aliases: HashMap::new(),
+            discriminant_aliases: HashMap::new(),
+            const_enums: HashSet::new(),
+            enums_counting_from_zero: HashSet::new(),
+            evolving: HashSet::new(),
+            relation_trace: None,
+            is_script: false,
+            declaring_globals: false,
+            globals: Vec::new(),
+        }
+    }
+
+    fn declare_symbol(&mut self, name: &str, kind: SymbolKind, ty: Type, span: Span) -> SymbolId {
+        let id = self.add_symbol(name, kind, ty, span);
+        self.bind(name, id, false);
+        id
+    }
+
+    /// Records a symbol declared at `span` without bringing its name into scope.
+    fn add_symbol(&mut self, name: &str, kind: SymbolKind, ty: Type, span: Span) -> SymbolId {
+        let id = self.create_symbol(name, kind, ty.clone(), span);
+        self.node_types.push((span, ty));
+        self.references.push((span, id));
+        id
+    }
+
+    fn create_symbol(&mut self, name: &str, kind: SymbolKind, ty: Type, span: Span) -> SymbolId {
+        let id = SymbolId(self.symbols.len());
+        self.symbols.push(Symbol {
+            name: Atom::new(name),
+            kind,
+            ty,
+            span,
+            origin: None,
+        });
+        id
+    }
+
+    /// Checks decorators with the legacy `experimentalDecorators` semantics instead of
+    /// stage 3 ones.
+    pub fn set_experimental_decorators(&mut self, enabled: bool) {
+        self.experimental_decorators = enabled;
+    }
+
+    /// Reports `throw` statements whose value isn't an `Error`.
+    pub fn set_only_throw_error(&mut self, enabled: bool) {
+        self.only_throw_error = enabled;
+    }
+
+    /// Reports switch cases whose statements fall through to the next case.
+    pub fn set_no_fallthrough_cases_in_switch(&mut self, enabled: bool) {
+        self.no_fallthrough_cases_in_switch = enabled;
+    }
+
+    /// Reports members that override a base class member without the `override` keyword.
+    pub fn set_no_implicit_override(&mut self, enabled: bool) {
+        self.no_implicit_override = enabled;
+    }
+
+    /// Prints types in error messages in full instead of eliding what's past
+    /// [`DEFAULT_TRUNCATION_LENGTH`](crate::type_printer::DEFAULT_TRUNCATION_LENGTH).
+    pub fn set_no_error_truncation(&mut self, enabled: bool) {
+        self.no_error_truncation = enabled;
+    }
+
+    /// Checks with the given strict family flags instead of all of them.
+    pub fn set_strictness(&mut self, strictness: Strictness) {
+        self.strictness = strictness;
+    }
+
+    /// Checks the file as emitted for `module_kind`, which decides whether it may use
+    /// top-level `await`.
+    pub fn set_module_kind(&mut self, module_kind: ModuleKind) {
+        self.module_kind = module_kind;
+    }
+
+    pub fn set_target(&mut self, target: ScriptTarget) {
+        self.target = target;
+    }
+
+    /// Times relations between structured types, for [`Self::take_traced_relations`].
+    pub(crate) fn trace_relations(&mut self) {
+        self.relation_trace = Some(RefCell::new(Vec::new()));
+    }
+
+    pub(crate) fn take_traced_relations(&mut self) -> Vec<TimedRelation> {
+        self.relation_trace
+            .as_mut()
+            .map(|relations| std::mem::take(relations.get_mut()))
+            .unwrap_or_default()
+    }
+
+    /// Reports the message TypeScript reports under `code` at `span`, with `arguments` in
+    /// its placeholders.
+    fn error(&mut self, code: u32, arguments: &[&dyn fmt::Display], span: Span) {
+        self.report(Message::new(code, arguments), span);
+    }
+
+    /// Reports `message` at `span`.
+    fn report(&mut self, message: Message, span: Span) {
+        self.errors.push(message.text());
+        self.error_spans.push(span);
+        self.error_messages.push(message);
+    }
+
+    /// Reports `actual` not being assignable to `expected` at `span`, or, when it's an
+    /// object literal's type, giving a property `expected` has no place for. Returns
+    /// whether anything was reported.
+    fn report_unassignable(&mut self, expected: &Type, actual: &Type, span: Span) -> bool {
+        if self.report_excess_property(expected, actual, span) {
+            return true;
+        }
+        if self.is_assignable(expected, actual) {
+            return false;
+        }
+        // As in tsc, a literal is described by its primitive unless a literal was expected
+        let actual = match has_literal(expected) {
+            true => actual.clone(),
+            false => literal_widened(actual),
+        };
+        self.error(2322, &[&actual, expected], span);
+        true
+    }
+
+    /// Warns about an intersection written as `ty` that's `never` because its members give
+    /// a discriminant property conflicting types, which reads as if it were a real type.
+    fn report_reduced_intersection(&mut self, ty: &TSType) {
+        let TSType::TSIntersectionType(intersection) = ty else {
+            return;
+        };
+        let members: Vec<Type> = intersection
+            .types
+            .iter()
+            .map(|ty| self.check_type(ty))
+            .collect();
+        let Some(name) = conflicting_property(&members) else {
+            return;
+        };
+        self.error(
+            18032,
+            &[&Type::Intersection(members), &name],
+            intersection.span,
+        );
+    }
+
+    /// Reports a property an object literal gives that `expected` has no place for, where
+    /// the literal names it, or at `span` when that isn't known.
+    fn report_excess_property(&mut self, expected: &Type, actual: &Type, span: Span) -> bool {
+        let Some((name, target, property_span)) = excess_property(expected, actual) else {
+            return false;
+        };
+        self.error(2353, &[&name, &target], property_span.unwrap_or(span));
+        true
+    }
+
+    /// Whether `actual` can be assigned to `expected` under the strict flags in effect.
+    fn is_assignable(&self, expected: &Type, actual: &Type) -> bool {
+        let Some(relations) = &self.relation_trace else {
+            return is_assignable_with(expected, actual, self.strictness);
+        };
+        if !is_structured(expected) && !is_structured(actual) {
+            return is_assignable_with(expected, actual, self.strictness);
         }
+        let started = Instant::now();
+        let assignable = is_assignable_with(expected, actual, self.strictness);
+        relations.borrow_mut().push(TimedRelation {
+            started,
+            ended: Instant::now(),
+            source: actual.to_string(),
+            target: expected.to_string(),
+        });
+        assignable
     }
 
     pub fn check_program(&mut self, program: &Program) {
-        for item in &program.body {
-            self.check_statement(item);
+        self.bind_program(program);
+        self.check_bound_program(program);
+    }
+
+    /// Declares the program's interfaces, classes and type aliases and notes what it assigns to,
+    /// which checking relies on; [`Self::check_program`] does this before checking.
+    pub fn bind_program(&mut self, program: &Program) {
+        self.is_script = !is_module(program);
+        self.error_printer().scope(|| {
+            self.declare_interfaces(program);
+            self.declare_class_types(program);
+            self.declare_type_aliases(program);
+            self.collect_assignments(program);
+        });
+    }
+
+    /// Checks a program already passed to [`Self::bind_program`].
+    pub fn check_bound_program(&mut self, program: &Program) {
+        self.error_printer()
+            .scope(|| self.check_statements(&program.body));
+    }
+
+    /// How the types in error messages are printed.
+    fn error_printer(&self) -> TypePrinter {
+        match self.no_error_truncation {
+            true => TypePrinter::default(),
+            false => TypePrinter::truncating(),
         }
     }
 
     fn check_statement(&mut self, stmt: &Statement) {
         match stmt {
-            Statement::VariableDeclaration(var_decl) => {
-                for decl in &var_decl.declarations {
-                    if let BindingPatternKind::BindingIdentifier(ident) = &decl.id.kind {
-                        let var_type = if let Some(type_ann) = &decl.id.type_annotation {
-                            self.check_type(&type_ann.type_annotation)
-                        } else if let Some(init) = &decl.init {
-                            self.check_expression(init)
-                        } else {
-                            Type::Any
-                        };
-                        self.symbol_table
-                            .insert(ident.name.to_string(), var_type.clone());
-
-                        if let Some(init) = &decl.init {
-                            let init_type = self.check_expression(init);
-                            if !check_type_compatibility(&var_type, &init_type) {
-                                self.errors.push(format!(
-                                    "Type '{}' is not assignable to type '{}'",
-                                    init_type, var_type
-                                ));
-                            }
+            Statement::VariableDeclaration(var_decl) => self.check_variable_declaration(var_decl),
+            Statement::FunctionDeclaration(func_decl) => self.check_function_declaration(func_decl),
+            Statement::ClassDeclaration(class) => self.check_class_declaration(class),
+            Statement::ImportDeclaration(decl) => self.check_import_declaration(decl),
+            Statement::ExportNamedDeclaration(decl) => self.check_export_named_declaration(decl),
+            Statement::ExportDefaultDeclaration(decl) => {
+                self.check_export_default_declaration(decl)
+            }
+            Statement::ExportAllDeclaration(decl) => self.check_export_all_declaration(decl),
+            Statement::ExpressionStatement(expr_stmt) => {
+                self.check_expression(&expr_stmt.expression);
+            }
+            Statement::BlockStatement(block) => {
+                self.enter_scope(block.span, false);
+                self.check_statements(&block.body);
+                self.exit_scope();
+            }
+            Statement::IfStatement(stmt) => self.check_if_statement(stmt),
+            Statement::TSTypeAliasDeclaration(decl) => self.check_type_alias_declaration(decl),
+            Statement::TSInterfaceDeclaration(decl) => self.check_interface_declaration(decl),
+            Statement::TSEnumDeclaration(decl) => self.check_enum_declaration(decl),
+            Statement::TSModuleDeclaration(decl) => self.check_namespace_declaration(decl),
+            Statement::SwitchStatement(stmt) => self.check_switch_statement(stmt),
+            Statement::ThrowStatement(stmt) => self.check_throw_statement(stmt),
+            Statement::TryStatement(stmt) => self.check_try_statement(stmt),
+            Statement::WhileStatement(stmt) => self.check_while_statement(stmt),
+            Statement::DoWhileStatement(stmt) => self.check_do_while_statement(stmt),
+            Statement::ForStatement(stmt) => self.check_for_statement(stmt),
+            Statement::ForOfStatement(stmt) => self.check_for_of_statement(stmt),
+            Statement::ForInStatement(stmt) => self.check_for_in_statement(stmt),
+            Statement::LabeledStatement(stmt) => self.check_statement(&stmt.body),
+            _ => {}
+        }
+    }
+
+    fn check_declaration(&mut self, decl: &Declaration) {
+        match decl {
+            Declaration::VariableDeclaration(var_decl) => self.check_variable_declaration(var_decl),
+            Declaration::FunctionDeclaration(func_decl) => {
+                self.check_function_declaration(func_decl)
+            }
+            Declaration::ClassDeclaration(class) => self.check_class_declaration(class),
+            Declaration::TSTypeAliasDeclaration(decl) => self.check_type_alias_declaration(decl),
+            Declaration::TSInterfaceDeclaration(decl) => self.check_interface_declaration(decl),
+            Declaration::TSEnumDeclaration(decl) => self.check_enum_declaration(decl),
+            Declaration::TSModuleDeclaration(decl) => self.check_namespace_declaration(decl),
+            _ => {}
+        }
+    }
+
+    fn check_variable_declaration(&mut self, var_decl: &VariableDeclaration) {
+        for decl in &var_decl.declarations {
+            if let BindingPatternKind::BindingIdentifier(ident) = &decl.id.kind {
+                let annotated = decl.id.type_annotation.as_ref().map(|ann| {
+                    self.report_reduced_intersection(&ann.type_annotation);
+                    self.check_type(&ann.type_annotation)
+                });
+                let mut init_type = decl.init.as_ref().map(|init| match &annotated {
+                    Some(annotated) => self.check_expression_in_context(init, annotated),
+                    None => self.check_expression(init),
+                });
+                // `const k = "key"` is `"key"`, and a variable copying it again `string`
+                let literal = decl.init.as_ref().and_then(literal_type);
+                if annotated.is_none() {
+                    match (&decl.init, &literal) {
+                        (_, Some(literal)) if var_decl.kind.is_const() => {
+                            init_type = Some(literal.clone());
+                        }
+                        (Some(Expression::Identifier(ident)), _)
+                            if !var_decl.kind.is_const()
+                                && self.lookup(&ident.name).is_some_and(|id| {
+                                    // How an import was declared isn't known here
+                                    self.literal_constants.contains(&id)
+                                        || self.symbols[id.0].kind == SymbolKind::Alias
+                                }) =>
+                        {
+                            init_type = init_type.map(|ty| ty.widened());
                         }
+                        _ => {}
                     }
                 }
-            }
-            Statement::FunctionDeclaration(func_decl) => {
-                // Add function to symbol table
-                if let Some(ident) = &func_decl.id {
-                    let mut param_types = Vec::new();
-                    for param in &func_decl.params.items {
-                        let param_type = if let Some(type_ann) = &param.pattern.type_annotation {
-                            self.check_type(&type_ann.type_annotation)
-                        } else {
-                            Type::Any
-                        };
-                        if let BindingPatternKind::BindingIdentifier(ident) = &param.pattern.kind {
-                            self.symbol_table
-                                .insert(ident.name.to_string(), param_type.clone());
+                // `const C = class {}` names the class after the variable, as its `name` does
+                if let Some(Expression::ClassExpression(class)) = &decl.init
+                    && class.id.is_none()
+                    && let Some(Type::Class(class_type)) = &mut init_type
+                {
+                    Arc::make_mut(class_type).name = ident.name.to_string();
+                }
+                let var_type = if let Some(annotated) = annotated {
+                    annotated
+                } else if let Some(init_type) = &init_type {
+                    init_type.clone()
+                } else {
+                    Type::Any
+                };
+                let id = self.add_symbol(
+                    &ident.name,
+                    SymbolKind::Variable,
+                    var_type.clone(),
+                    ident.span,
+                );
+                self.bind(&ident.name, id, var_decl.kind.is_var());
+                if decl.id.type_annotation.is_none()
+                    && let Some(Expression::ArrayExpression(array)) = &decl.init
+                    && array.elements.is_empty()
+                {
+                    // `[]` takes its element type from what's pushed into it
+                    self.evolving.insert(id);
+                }
+                if var_decl.kind.is_const() {
+                    self.constants.insert(id);
+                    if decl.id.type_annotation.is_none() && literal.is_some() {
+                        self.literal_constants.insert(id);
+                    }
+                    if let Some(init) = &decl.init {
+                        self.record_condition_alias(id, init);
+                    }
+                } else if decl.id.type_annotation.is_none()
+                    && decl.init.is_none()
+                    && !var_decl.declare
+                {
+                    // `let x;` holds `undefined` until something else is assigned
+                    self.evolving.insert(id);
+                    self.narrowed.push((id, Type::Undefined));
+                }
+
+                if let Some(init_type) = init_type {
+                    self.report_unassignable(&var_type, &init_type, ident.span);
+                }
+            } else {
+                let ty = match (&decl.id.type_annotation, &decl.init) {
+                    (Some(ann), init) => {
+                        let annotated = self.check_type(&ann.type_annotation);
+                        if let Some(init) = init {
+                            let init_type = self.check_expression_in_context(init, &annotated);
+                            self.report_unassignable(&annotated, &init_type, init.span());
                         }
-                        param_types.push(param_type);
+                        annotated
                     }
-                    let return_type = if let Some(return_type) = &func_decl.return_type {
-                        self.check_type(&return_type.type_annotation)
-                    } else {
-                        Type::Any
-                    };
-
-                    self.symbol_table.insert(
-                        ident.name.to_string(),
-                        Type::Function {
-                            params: param_types.clone(),
-                            return_type: Arc::new(return_type.clone()),
-                        },
-                    );
-
-                    // Check function body
-                    if let Some(body) = &func_decl.body {
-                        for stmt in &body.statements {
-                            match stmt {
-                                Statement::ReturnStatement(ret_stmt) => {
-                                    if let Some(arg) = &ret_stmt.argument {
-                                        let actual_return_type = self.check_expression(arg);
-                                        if !check_type_compatibility(
-                                            &return_type,
-                                            &actual_return_type,
-                                        ) {
-                                            self.errors.push(format!(
-                                                "Type '{}' is not assignable to type '{}'",
-                                                actual_return_type, return_type
-                                            ));
-                                        }
-                                    }
+                    (None, Some(init)) => self.check_expression(init),
+                    (None, None) => Type::Any,
+                };
+                match &decl.id.kind {
+                    BindingPatternKind::ArrayPattern(pattern) => {
+                        self.declare_array_pattern(pattern, &ty, var_decl.kind);
+                    }
+                    BindingPatternKind::ObjectPattern(pattern) => {
+                        self.declare_object_pattern(pattern, &ty, var_decl.kind, &decl.init);
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+
+    /// Declares a function declaration's name with the type its signature gives it.
+    fn declare_function(&mut self, func_decl: &Function) {
+        let Some(ident) = &func_decl.id else {
+            return;
+        };
+        let type_params = self.enter_type_parameters(func_decl.type_parameters.as_deref());
+        let param_types = self.signature_parameters(&func_decl.params);
+        let return_type = self.declared_return_type(func_decl);
+        self.exit_type_parameters(&type_params);
+        self.declare_symbol(
+            &ident.name,
+            SymbolKind::Function,
+            Self::generic(
+                type_params,
+                function_type(&func_decl.params, param_types, return_type),
+            ),
+            ident.span,
+        );
+    }
+
+    /// The type of an optional parameter or property declared as `ty`, which also holds
+    /// `undefined` under strictNullChecks.
+    pub(super) fn optional_type(&self, ty: Type, optional: bool) -> Type {
+        match optional && self.strictness.strict_null_checks {
+            true => union_of(vec![ty, Type::Undefined]),
+            false => ty,
+        }
+    }
+
+    fn declared_return_type(&self, func_decl: &Function) -> Type {
+        if let Some(return_type) = &func_decl.return_type {
+            self.check_type(&return_type.type_annotation)
+        } else if func_decl.r#async {
+            Type::promise(Type::Any)
+        } else {
+            Type::Any
+        }
+    }
+
+    fn check_function_declaration(&mut self, func_decl: &Function) {
+        if let Some(ident) = &func_decl.id {
+            // Usually hoisted already by `check_statements`
+            if !self.is_declared_here(&ident.name, ident.span) {
+                self.declare_function(func_decl);
+            }
+
+            self.enter_scope(func_decl.span, true);
+            let narrowed = self.enter_closure(func_decl.span, true);
+            let type_params = self.enter_type_parameters(func_decl.type_parameters.as_deref());
+            self.declare_parameters(&func_decl.params, &(Vec::new(), false));
+
+            // An async function's returns are what its promise resolves to
+            let return_type = match func_decl.r#async {
+                true => self.declared_return_type(func_decl).awaited(),
+                false => self.declared_return_type(func_decl),
+            };
+
+            // Check function body
+            if let Some(body) = &func_decl.body {
+                self.hoist_functions(&body.statements);
+                for stmt in &body.statements {
+                    match stmt {
+                        Statement::ReturnStatement(ret_stmt) => {
+                            if let Some(arg) = &ret_stmt.argument {
+                                let mut actual_return_type =
+                                    self.check_expression_in_context(arg, &return_type);
+                                if func_decl.r#async {
+                                    actual_return_type = actual_return_type.awaited();
                                 }
-                                _ => self.check_statement(stmt),
+                                self.report_unassignable(
+                                    &return_type,
+                                    &actual_return_type,
+                                    arg.span(),
+                                );
                             }
                         }
+                        _ => self.check_statement(stmt),
                     }
                 }
             }
-            _ => {}
+            self.exit_type_parameters(&type_params);
+            self.exit_closure(narrowed);
+            self.exit_scope();
         }
     }
 
@@ -139,43 +662,151 @@ impl TypeChecker {
                     .collect();
                 Type::Tuple(types)
             }
+            TSType::TSIntersectionType(intersection) => intersection_of(
+                intersection
+                    .types
+                    .iter()
+                    .map(|t| self.check_type(t))
+                    .collect(),
+            ),
             TSType::TSUnionType(union_type) => {
                 let types: Vec<Type> = union_type
                     .types
                     .iter()
                     .map(|t| self.check_type(t))
                     .collect();
-                Type::Union(types)
+                union_of(types)
             }
             TSType::TSFunctionType(func_type) => {
-                let params: Vec<Type> = func_type
-                    .params
-                    .items
-                    .iter()
-                    .filter_map(|t| {
-                        t.pattern
-                            .type_annotation
-                            .as_ref()
-                            .map(|ann| self.check_type(&ann.type_annotation))
-                    })
-                    .collect();
-                let return_type = Arc::new(self.check_type(&func_type.return_type.type_annotation));
-                Type::Function {
-                    params,
-                    return_type,
+                let params = self.signature_parameters(&func_type.params);
+                let return_type = self.check_type(&func_type.return_type.type_annotation);
+                function_type(&func_type.params, params, return_type)
+            }
+            TSType::TSTypeLiteral(literal) => self.check_type_literal(literal),
+            TSType::TSMappedType(mapped) => self.check_mapped_type(mapped),
+            TSType::TSLiteralType(literal) => match &literal.literal {
+                TSLiteral::StringLiteral(literal) => Type::StringLiteral(literal.value.to_string()),
+                TSLiteral::NumericLiteral(literal) => Type::NumberLiteral(literal.value),
+                TSLiteral::BooleanLiteral(literal) => Type::BooleanLiteral(literal.value),
+                TSLiteral::BigIntLiteral(literal) => {
+                    parse_bigint_literal(&literal.raw).map_or(Type::BigInt, Type::BigIntLiteral)
+                }
+                TSLiteral::UnaryExpression(unary)
+                    if unary.operator == UnaryOperator::UnaryNegation =>
+                {
+                    if let Some(value) = numeric_literal_value(&unary.argument) {
+                        Type::NumberLiteral(0.0 - value)
+                    } else if let Some(value) = bigint_literal_value(&unary.argument) {
+                        Type::BigIntLiteral(negated_bigint(&value))
+                    } else {
+                        Type::Any
+                    }
                 }
+                _ => Type::Any,
+            },
+            TSType::TSTypeOperatorType(operator)
+                if operator.operator == TSTypeOperatorOperator::Readonly =>
+            {
+                Type::Readonly(Arc::new(self.check_type(&operator.type_annotation)))
+            }
+            TSType::TSTypeOperatorType(operator)
+                if operator.operator == TSTypeOperatorOperator::Unique =>
+            {
+                Type::UniqueSymbol(operator.span)
             }
+            TSType::TSParenthesizedType(parenthesized) => {
+                self.check_type(&parenthesized.type_annotation)
+            }
+            TSType::TSTypeReference(reference) => match &reference.type_name {
+                TSTypeName::IdentifierReference(ident) => {
+                    let name = ident.name.as_str();
+                    let type_arguments = reference.type_parameters.as_ref();
+                    let mapped_key = self
+                        .mapped_keys
+                        .borrow()
+                        .iter()
+                        .rev()
+                        .find(|p| p.name == name)
+                        .cloned();
+                    if let Some(key) = mapped_key {
+                        Type::TypeParameter(key)
+                    } else if let Some(param) =
+                        self.type_parameters.iter().rev().find(|p| p.name == name)
+                    {
+                        Type::TypeParameter(param.clone())
+                    } else if let Some((_, alias)) = self.type_aliases.get(name) {
+                        Type::Alias(alias.clone())
+                    } else if name == "Array"
+                        && let Some(element) = type_arguments.and_then(|args| args.params.first())
+                    {
+                        Type::Array(Arc::new(self.check_type(element)))
+                    } else if name == "Promise"
+                        && let Some(value) = type_arguments.and_then(|args| args.params.first())
+                    {
+                        Type::promise(self.check_type(value))
+                    } else if name == "Awaited"
+                        && let Some(value) = type_arguments.and_then(|args| args.params.first())
+                    {
+                        self.check_type(value).awaited()
+                    } else if name == "Record"
+                        && let Some([keys, value]) = type_arguments.map(|args| &args.params[..])
+                    {
+                        record_type(&self.check_type(keys), self.check_type(value))
+                    } else {
+                        global_type(name).unwrap_or(Type::Any)
+                    }
+                }
+                _ => Type::Any,
+            },
             _ => Type::Any,
         }
     }
 
+    /// Checks `expr` where a value of type `expected` is wanted, so calls can infer from it.
+    fn check_expression_in_context(&mut self, expr: &Expression, expected: &Type) -> Type {
+        let outer = self
+            .contextual_type
+            .replace((expr.span(), expected.clone()));
+        let ty = self.check_expression(expr);
+        self.contextual_type = outer;
+        ty
+    }
+
+    /// Whether the literal at `span` keeps its literal type instead of widening, as it does
+    /// under `as const` or where a literal type is expected.
+    fn keeps_literal(&self, span: Span) -> bool {
+        self.const_context
+            || matches!(&self.contextual_type, Some((expected_span, expected))
+                if *expected_span == span && has_literal(expected))
+    }
+
     pub fn check_expression(&mut self, expr: &Expression) -> Type {
+        let ty = self.infer_expression(expr);
+        self.node_types.push((expr.span(), ty.clone()));
+        ty
+    }
+
+    fn infer_expression(&mut self, expr: &Expression) -> Type {
         match expr {
+            Expression::NumericLiteral(literal) if self.keeps_literal(literal.span) => {
+                Type::NumberLiteral(literal.value)
+            }
+            Expression::StringLiteral(literal) if self.keeps_literal(literal.span) => {
+                Type::StringLiteral(literal.value.to_string())
+            }
+            Expression::BooleanLiteral(literal) if self.keeps_literal(literal.span) => {
+                Type::BooleanLiteral(literal.value)
+            }
+            Expression::BigIntLiteral(literal) if self.keeps_literal(literal.span) => {
+                parse_bigint_literal(&literal.raw).map_or(Type::BigInt, Type::BigIntLiteral)
+            }
             Expression::NumericLiteral(_) => Type::Number,
             Expression::BigIntLiteral(_) => Type::BigInt,
             Expression::StringLiteral(_) => Type::String,
             Expression::BooleanLiteral(_) => Type::Boolean,
             Expression::NullLiteral(_) => Type::Null,
+            Expression::RegExpLiteral(_) => regexp_type(),
+            Expression::ThisExpression(_) => self.this_type.clone().unwrap_or(Type::Any),
             Expression::Identifier(ident) => match ident.name.as_str() {
                 "number" => Type::Number,
                 "string" => Type::String,
@@ -187,24 +818,52 @@ impl TypeChecker {
                 "void" => Type::Void,
                 "unknown" => Type::Unknown,
                 "any" => Type::Any,
-                _ => self
-                    .symbol_table
-                    .get(ident.name.as_str())
-                    .cloned()
-                    .unwrap_or(Type::Any),
-            },
-            Expression::ArrayExpression(array_expr) => {
-                if let Some(first) = array_expr.elements.first() {
-                    if let Some(expr) = first.as_expression() {
-                        let elem_type = self.check_expression(expr);
-                        Type::Array(Arc::new(elem_type))
-                    } else {
-                        Type::Array(Arc::new(Type::Any))
+                name => match self.lookup(name) {
+                    Some(id) => {
+                        self.check_const_enum_reference(ident);
+                        self.references.push((ident.span, id));
+                        self.symbol_type(id).regular()
                     }
-                } else {
-                    Type::Array(Arc::new(Type::Any))
-                }
+                    None if name == "globalThis" => self.global_this_type(),
+                    None => global_value(name).unwrap_or(Type::Any),
+                },
+            },
+            Expression::ArrayExpression(array) => self.check_array_expression(array),
+            Expression::ObjectExpression(object) => self.check_object_expression(object),
+            Expression::StaticMemberExpression(member) => {
+                self.check_static_member_expression(member)
             }
+            Expression::ComputedMemberExpression(member) => {
+                self.check_computed_member_expression(member)
+            }
+            Expression::ParenthesizedExpression(paren) => self.check_expression(&paren.expression),
+            Expression::SequenceExpression(sequence) => sequence
+                .expressions
+                .iter()
+                .map(|expr| self.check_expression(expr))
+                .last()
+                .unwrap_or(Type::Any),
+            Expression::FunctionExpression(func) => self.check_function(func),
+            Expression::ArrowFunctionExpression(arrow) => {
+                self.check_arrow_function_expression(arrow)
+            }
+            Expression::ClassExpression(class) => self.check_class(class),
+            Expression::TSAsExpression(assertion) => {
+                self.check_type_assertion(&assertion.expression, &assertion.type_annotation)
+            }
+            Expression::TSTypeAssertion(assertion) => {
+                self.check_type_assertion(&assertion.expression, &assertion.type_annotation)
+            }
+            // What a call returns has been stored, so it's no longer a fresh literal
+            Expression::CallExpression(call) => self.check_call_expression(call).regular(),
+            Expression::AwaitExpression(await_expr) => {
+                self.check_top_level_await(await_expr);
+                self.check_expression(&await_expr.argument).awaited()
+            }
+            Expression::NewExpression(new) => self.check_new_expression(new),
+            Expression::UnaryExpression(unary) => self.check_unary_expression(unary),
+            Expression::UpdateExpression(update) => self.check_update_expression(update),
+            Expression::AssignmentExpression(assign) => self.check_assignment_expression(assign),
             Expression::BinaryExpression(bin_expr) => {
                 let left_type = self.check_expression(&bin_expr.left);
                 let right_type = self.check_expression(&bin_expr.right);
@@ -214,15 +873,20 @@ impl TypeChecker {
                         if matches!(left_type, Type::String) || matches!(right_type, Type::String) {
                             Type::String
                         } else {
-                            match (left_type.clone(), right_type.clone()) {
+                            // Only joining with a string works whatever the other value is
+                            let left_unknown =
+                                self.report_unknown_operand(&bin_expr.left, &left_type);
+                            let right_unknown =
+                                self.report_unknown_operand(&bin_expr.right, &right_type);
+                            if left_unknown || right_unknown {
+                                return Type::ERROR;
+                            }
+                            match (left_type.widened(), right_type.widened()) {
                                 (Type::BigInt, Type::BigInt) => Type::BigInt,
                                 (Type::Number, Type::Number) => Type::Number,
                                 (Type::BigInt, _) | (_, Type::BigInt) => {
-                                    self.errors.push(format!(
-                                        "The binary operation between '{}' and '{}' is not allowed",
-                                        left_type, right_type
-                                    ));
-                                    Type::Number
+                                    self.error(100001, &[&left_type, &right_type], bin_expr.span);
+                                    Type::ERROR
                                 }
                                 _ => Type::Number, // Default to number for other numeric operations
                             }
@@ -233,27 +897,27 @@ impl TypeChecker {
                     | BinaryOperator::Division
                     | BinaryOperator::Remainder
                     | BinaryOperator::Exponential => {
-                        match (left_type.clone(), right_type.clone()) {
+                        match (left_type.widened(), right_type.widened()) {
                             (Type::BigInt, Type::BigInt) => Type::BigInt,
                             (Type::Number, Type::Number) => Type::Number,
                             (Type::BigInt, _) | (_, Type::BigInt) => {
-                                self.errors.push(format!(
-                                    "The binary operation between '{}' and '{}' is not allowed",
-                                    left_type, right_type
-                                ));
-                                Type::Number
+                                self.error(100001, &[&left_type, &right_type], bin_expr.span);
+                                Type::ERROR
                             }
                             _ => Type::Any,
                         }
                     }
+                    BinaryOperator::Equality
+                    | BinaryOperator::Inequality
+                    | BinaryOperator::StrictEquality
+                    | BinaryOperator::StrictInequality => {
+                        self.check_comparison(bin_expr, &left_type, &right_type);
+                        Type::Boolean
+                    }
                     BinaryOperator::LessThan
                     | BinaryOperator::LessEqualThan
                     | BinaryOperator::GreaterThan
                     | BinaryOperator::GreaterEqualThan
-                    | BinaryOperator::Equality
-                    | BinaryOperator::Inequality
-                    | BinaryOperator::StrictEquality
-                    | BinaryOperator::StrictInequality
                     | BinaryOperator::In
                     | BinaryOperator::Instanceof => Type::Boolean,
 
@@ -263,20 +927,16 @@ impl TypeChecker {
                     | BinaryOperator::ShiftLeft
                     | BinaryOperator::ShiftRight
                     | BinaryOperator::ShiftRightZeroFill => {
-                        match (left_type.clone(), right_type.clone()) {
+                        match (left_type.widened(), right_type.widened()) {
                             (Type::BigInt, Type::BigInt) => Type::BigInt,
                             (Type::Number, Type::Number) => Type::Number,
                             (Type::BigInt, _) | (_, Type::BigInt) => {
-                                self.errors.push(format!(
-                                    "The binary operation between '{}' and '{}' is not allowed",
-                                    left_type, right_type
-                                ));
-                                Type::Number
+                                self.error(100001, &[&left_type, &right_type], bin_expr.span);
+                                Type::ERROR
                             }
                             _ => Type::Number, // Default to Number for bitwise operations
                         }
                     }
-                    _ => Type::Any,
                 }
             }
             _ => Type::Any,
@@ -286,6 +946,55 @@ impl TypeChecker {
     pub fn get_errors(&self) -> &[String] {
         &self.errors
     }
+
+    /// The errors with where each was found.
+    pub fn get_type_errors(&self) -> Vec<TypeError> {
+        self.error_messages
+            .iter()
+            .zip(&self.error_spans)
+            .map(|(message, span)| TypeError::with_span(message.clone(), *span))
+            .collect()
+    }
+
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// Symbols whose names are in scope at the end of the checked file.
+    pub fn visible_symbols(&self) -> Vec<&Symbol> {
+        self.scopes[0]
+            .names()
+            .map(|id| &self.symbols[id.0])
+            .collect()
+    }
+
+    pub fn node_types(&self) -> &[(Span, Type)] {
+        &self.node_types
+    }
+
+    pub fn references(&self) -> &[(Span, SymbolId)] {
+        &self.references
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Whether relating `ty` means comparing its structure rather than a single flag or value.
+fn is_structured(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Union(_)
+            | Type::Tuple(_)
+            | Type::Function { .. }
+            | Type::ObjectType(_)
+            | Type::Class(_)
+            | Type::Alias(_)
+            | Type::Generic { .. }
+    )
 }
 
 #[cfg(test)]
@@ -420,6 +1129,7 @@ mod tests {
 
         // Helper function to get the type of a variable declaration
         let program = parse_typescript(source).unwrap();
+        #[allow(clippy::collapsible_if)]
         let mut get_var_type = |var_name: &str| -> Type {
             for stmt in &program.program.body {
                 if let Statement::VariableDeclaration(var_decl) = stmt {
@@ -469,6 +1179,31 @@ mod tests {
         assert!(matches!(get_var_type("b6"), Type::Number));
     }
 
+    #[test]
+    fn test_object_types_and_property_access() {
+        let source = r#"
+            let point: { x: number; y: number; label?: string } = { x: 1, y: 2 };
+            let x: number = point.x;
+            let label: number = point.label;
+            let missing = point.z;
+            let bad: { x: number } = { y: 1 };
+            let len: number = "abc".length;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'string | undefined' is not assignable to type 'number'",
+                "Property 'z' does not exist on type '{ x: number; y: number; label?: string | undefined; }'.",
+                "Object literal may only specify known properties, and 'y' does not exist in type '{ x: number; }'.",
+            ]
+        );
+    }
+
     #[test]
     fn test_bigint_binary_expression_types() {
         let mut checker = TypeChecker::new();
@@ -502,6 +1237,7 @@ mod tests {
 
         let program = parse_typescript(ts_program).unwrap();
         checker.check_program(&program.program);
+        #[allow(clippy::collapsible_if)]
         let mut get_var_type = |var_name: &str| -> Type {
             for stmt in &program.program.body {
                 if let Statement::VariableDeclaration(var_decl) = stmt {
@@ -534,8 +1270,8 @@ mod tests {
         assert_eq!(get_var_type("h"), Type::BigInt);
 
         // Test mixed BigInt and Number operations (should be Any due to errors)
-        assert_eq!(get_var_type("i"), Type::Number);
-        assert_eq!(get_var_type("j"), Type::Number);
+        assert_eq!(get_var_type("i"), Type::ERROR);
+        assert_eq!(get_var_type("j"), Type::ERROR);
 
         // Test BigInt bitwise operation results
         assert_eq!(get_var_type("k"), Type::BigInt);
@@ -545,8 +1281,8 @@ mod tests {
         assert_eq!(get_var_type("o"), Type::BigInt);
 
         // Test mixed BigInt and Number bitwise operations (should be Any due to errors)
-        assert_eq!(get_var_type("p"), Type::Number);
-        assert_eq!(get_var_type("q"), Type::Number);
+        assert_eq!(get_var_type("p"), Type::ERROR);
+        assert_eq!(get_var_type("q"), Type::ERROR);
 
         // Verify that appropriate error messages were generated
         assert!(
@@ -556,4 +1292,2462 @@ mod tests {
                 .any(|e| e.contains("The binary operation between"))
         );
     }
+
+    #[test]
+    fn test_unary_and_update_expressions() {
+        let source = r#"
+            let n: number = 1;
+            let big: bigint = 2n;
+            let s: string = "a";
+            let neg = -n;
+            let negBig = -big;
+            let not = !s;
+            let kind = typeof s;
+            let nothing = void n;
+            let inc = n++;
+            let negString = -s;
+            let inverted = ~s;
+            ++s;
+            let plusBig = +big;
+            declare const sym: symbol;
+            -sym;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+
+        assert_eq!(type_of("neg"), "number");
+        assert_eq!(type_of("negBig"), "bigint");
+        assert_eq!(type_of("not"), "boolean");
+        assert_eq!(
+            type_of("kind"),
+            "\"string\" | \"number\" | \"bigint\" | \"boolean\" | \"symbol\" | \"undefined\" | \"object\" | \"function\""
+        );
+        assert_eq!(type_of("nothing"), "undefined");
+        assert_eq!(type_of("inc"), "number");
+        assert_eq!(type_of("negString"), "number");
+        assert_eq!(type_of("inverted"), "number");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "An arithmetic operand must be of type 'any', 'number', 'bigint' or an enum type.",
+                "Operator '+' cannot be applied to type 'bigint'.",
+                "The '-' operator cannot be applied to type 'symbol'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_typeof_narrowing() {
+        let source = r#"
+            let x: string | number | boolean = 1;
+            switch (typeof x) {
+                case "string":
+                    let s: string = x;
+                    break;
+                case "number":
+                case "bigint":
+                    let n: number = x;
+                    break;
+                default:
+                    let b: boolean = x;
+                    let wrong: string = x;
+            }
+            if (typeof x !== "number") {
+                let notNumber: number = x;
+            } else {
+                let isNumber: number = x;
+            }
+            let after: string = x;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'boolean' is not assignable to type 'string'",
+                "Type 'string | boolean' is not assignable to type 'number'",
+                "Type 'string | number | boolean' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_delete_expressions() {
+        let source = r#"
+            let o: { a?: number; b: number; c: number | undefined; readonly d?: string } = { b: 1, c: 2 };
+            let ok = delete o.a;
+            delete o.b;
+            delete o.c;
+            delete o.d;
+            delete o;
+            delete o["b"];
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let ok = checker
+            .visible_symbols()
+            .into_iter()
+            .find(|s| s.name == "ok")
+            .map(|s| s.ty.clone());
+        assert_eq!(ok, Some(Type::Boolean));
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "The operand of a 'delete' operator must be optional.",
+                "The operand of a 'delete' operator cannot be a read-only property.",
+                "The operand of a 'delete' operator must be a property reference.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parenthesized_and_sequence_expressions() {
+        let source = r#"
+            let x: string | number = 1;
+            let wrapped = (("text"));
+            let last = (x, true, 2n);
+            let bad: string = (1, 2);
+            if ((typeof x) === "string") {
+                let s: string = (x);
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.clone())
+                .unwrap()
+        };
+        assert_eq!(type_of("wrapped"), Type::String);
+        assert_eq!(type_of("last"), Type::BigInt);
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'number' is not assignable to type 'string'"]
+        );
+    }
+
+    #[test]
+    fn test_class_expressions_and_iifes() {
+        let source = r#"
+            class Point {
+                static origin = 0;
+                label?: string;
+                constructor(public x: number, readonly y: number) {}
+                get sum(): number { return this.x + this.y; }
+                scale(by: number) { return by; }
+            }
+            const Counter = class {
+                count: number = "zero";
+            };
+            const one = (function () { return 1; })();
+            const greeting = (() => "hi")();
+            const p = new Point(1, 2);
+            let x: string = p.x;
+            let origin: number = Point.origin;
+            let missing = Point.nope;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("Point"), "typeof Point");
+        assert_eq!(type_of("Counter"), "typeof Counter");
+        assert_eq!(type_of("one"), "number");
+        assert_eq!(type_of("greeting"), "string");
+        assert_eq!(
+            type_of("p"),
+            "{ label?: string | undefined; x: number; readonly y: number; readonly sum: number; scale: (number) => number; }"
+        );
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'string' is not assignable to type 'number'",
+                "Type 'number' is not assignable to type 'string'",
+                "Property 'nope' does not exist on type 'typeof Point'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_element_access_expressions() {
+        let source = r#"
+            function f(list: string[], pair: [string, number], i: number) {
+                let scores: { [name: string]: number; total: string } = { total: "x" };
+                let first = list[i];
+                let name = pair[0];
+                let count = pair[1];
+                let either = pair[i];
+                let total = scores["total"];
+                let score = scores["alice"];
+                let size = list["length"];
+                let char = "abc"[1];
+                let past = pair[2];
+                let flag = list[true];
+                let plain: { a: number } = { a: 1 };
+                let key: string = "a";
+                let loose = plain[key];
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("first"), "string");
+        assert_eq!(type_of("name"), "string");
+        assert_eq!(type_of("count"), "number");
+        assert_eq!(type_of("either"), "string | number");
+        assert_eq!(type_of("total"), "string");
+        assert_eq!(type_of("score"), "number");
+        assert_eq!(type_of("size"), "number");
+        assert_eq!(type_of("char"), "string");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Tuple type '[string, number]' of length '2' has no element at index '2'.",
+                "Type 'boolean' cannot be used as an index type.",
+                "Element implicitly has an 'any' type because expression of type 'string' can't be used to index type '{ a: number; }'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_comparisons_without_overlap() {
+        let source = r#"
+            let n: number = 1;
+            let s: string | number = "x";
+            let a = "a" === 1;
+            let b = n !== "1";
+            let c = s === 1;
+            let d = n === null;
+            let e = "a" == "b";
+            let f = true === "a";
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "This comparison appears to be unintentional because the types '\"a\"' and '1' have no overlap.",
+                "This comparison appears to be unintentional because the types 'number' and '\"1\"' have no overlap.",
+                "This comparison appears to be unintentional because the types '\"a\"' and '\"b\"' have no overlap.",
+                "This comparison appears to be unintentional because the types 'true' and '\"a\"' have no overlap.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_recursive_type_aliases() {
+        let source = r#"
+            type Json = string | number | boolean | null | Json[] | { [key: string]: Json };
+            let doc: Json = { list: [1, 2], nested: { deep: "yes" } };
+            let bad: Json = () => 1;
+
+            type Tree = { value: number; children: Tree[] };
+            type Forest = { value: number; children: Forest[] };
+            type Labeled = { value: string; children: Labeled[] };
+            function f(tree: Tree) {
+                let forest: Forest = tree;
+                let labeled: Labeled = tree;
+            }
+
+            type Loop = Again;
+            type Again = Loop;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type alias 'Again' circularly references itself.",
+                "Type '() => number' is not assignable to type 'Json'",
+                "Type 'Tree' is not assignable to type 'Labeled'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interfaces() {
+        let source = r#"
+            function area(shape: Square): number { return shape.size * shape.size; }
+            interface Named { name: string }
+            interface Square extends Named { size: number; next?: Square }
+            interface Square { color: string }
+            const square: Square = { name: "a", size: 1, color: "red" };
+            const missing: Square = { name: "b", size: 2 };
+            let label: number = square.name;
+            const next: Square | undefined = square.next;
+
+            interface Unused { value: Elsewhere }
+            interface Elsewhere { value: number }
+            function local() {
+                interface Point { x: number }
+                const point: Point = { x: "1" };
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type '{ name: string; size: number; }' is not assignable to type 'Square'",
+                "Type 'string' is not assignable to type 'number'",
+                "Type '{ x: string; }' is not assignable to type 'Point'",
+            ]
+        );
+        // Members are only resolved for interfaces something refers to
+        let resolved = |name: &str| checker.type_aliases[name].1.is_resolved();
+        assert!(resolved("Square") && resolved("Named"));
+        assert!(!resolved("Unused") && !resolved("Elsewhere"));
+    }
+
+    #[test]
+    fn test_class_instance_types() {
+        let source = r#"
+            function origin(): Point { return new Point(); }
+            class Point { x = 0; next?: Point; }
+            const point: Point = new Point();
+            let x: string = origin().x;
+            const literal: Point = { x: 1, y: 2 };
+            const next: Point | undefined = point.next;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'number' is not assignable to type 'string'",
+                "Object literal may only specify known properties, and 'y' does not exist in type 'Point'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_mapped_types() {
+        let source = r#"
+            type Keys = "a" | "b";
+            type Flags = { readonly [K in Keys]?: boolean };
+            type Boxes = { [K in Keys]: { key: K } };
+            const flags: Flags = { a: true };
+            flags.b = false;
+            declare const boxes: Boxes;
+            let key: "b" = boxes.a.key;
+            type Names = { [K in number]: string };
+            const names: Names = { 1: "one" };
+            type Unknown<T> = { [K in keyof T]: T[K] };
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Cannot assign to 'b' because it is a read-only property.",
+                "Type '\"a\"' is not assignable to type '\"b\"'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_generic_inference() {
+        let source = r#"
+            function identity<T>(value: T): T { return value; }
+            function first<T>(items: Array<T>): T { return items[0]; }
+            declare function make<T>(): T[];
+            declare function fallback<T = boolean>(): T;
+            declare function constrained<T extends string>(): T;
+
+            let a = identity(1);
+            let b = first(["x"]);
+            const xs: string[] = make();
+            let ys = make();
+            let c = identity<string>("s");
+            let d: number = fallback();
+            let e = fallback();
+            let f = constrained();
+            const n: number = identity("s");
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("identity"), "<T>(T) => T");
+        assert_eq!(type_of("a"), "number");
+        assert_eq!(type_of("b"), "string");
+        assert_eq!(type_of("ys"), "unknown[]");
+        assert_eq!(type_of("c"), "string");
+        assert_eq!(type_of("e"), "boolean");
+        assert_eq!(type_of("f"), "string");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'string' is not assignable to type 'number'"]
+        );
+    }
+
+    #[test]
+    fn test_const_type_parameters() {
+        let source = r#"
+            function exact<const T>(value: T): T { return value; }
+            function loose<T>(value: T): T { return value; }
+            function names<const T extends readonly string[]>(value: T): T { return value; }
+
+            const tuple = exact(["a", 1, true]);
+            const object = exact({ x: 1, tags: ["t"] });
+            const widened = loose(["a"]);
+            const list = names(["x", "y"]);
+            const view: readonly string[] = widened;
+            const copy: string[] = list;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("tuple"), "readonly [\"a\", 1, true]");
+        assert_eq!(
+            type_of("object"),
+            "{ readonly x: 1; readonly tags: readonly [\"t\"]; }"
+        );
+        assert_eq!(type_of("widened"), "string[]");
+        assert_eq!(type_of("list"), "readonly [\"x\", \"y\"]");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'readonly [\"x\", \"y\"]' is not assignable to type 'string[]'"]
+        );
+    }
+
+    #[test]
+    fn test_decorators() {
+        let source = r#"
+            function bound(value: any, context: any): void {}
+            function legacy(target: any, key: string, descriptor: any) {}
+            function inject(target: any, key: any, index: number) {}
+            function tag(value: any, context: any): number { return 1; }
+            const notCallable = 1;
+
+            @tag
+            class Point {
+                @bound
+                @legacy
+                move(@inject dx: number) {}
+
+                @notCallable
+                x = 0;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Decorator function return type 'number' is not assignable to type 'void | typeof Point'.",
+                "Decorators are not valid here.",
+                "Unable to resolve signature of method decorator when called as an expression.",
+                "This expression is not callable.",
+            ]
+        );
+
+        let mut checker = TypeChecker::new();
+        checker.set_experimental_decorators(true);
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Unable to resolve signature of class decorator when called as an expression.",
+                "This expression is not callable.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_promises() {
+        let source = r#"
+            declare function load(): Promise<string>;
+            declare const thenable: { then: (resolve: (value: boolean) => void) => void };
+
+            const lengths = load().then(text => text.length);
+            const nested = load().then(text => load());
+            const recovered = load().catch(reason => 0);
+            const settled = load().finally(() => {});
+            const immediate = async () => 1;
+            async function wrap(n: number) { return n; }
+            async function run(): Promise<number> {
+                const text = await load();
+                const flag = await thenable;
+                const plain = await 5;
+                return text.length;
+            }
+            async function bad(): Promise<number> { return "x"; }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("lengths"), "Promise<number>");
+        assert_eq!(type_of("nested"), "Promise<string>");
+        assert_eq!(type_of("recovered"), "Promise<string | number>");
+        assert_eq!(type_of("settled"), "Promise<string>");
+        assert_eq!(type_of("immediate"), "() => Promise<number>");
+        assert_eq!(type_of("wrap"), "(number) => Promise<any>");
+        assert_eq!(type_of("text"), "string");
+        assert_eq!(type_of("flag"), "boolean");
+        assert_eq!(type_of("plain"), "number");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'string' is not assignable to type 'number'"]
+        );
+    }
+
+    #[test]
+    fn test_array_callback_methods() {
+        let source = r#"
+            const numbers = [1, 2, 3];
+            const doubled = numbers.map(x => x * 2);
+            const labels = numbers.map((n, i) => "item " + i);
+            const evens = numbers.filter(n => n % 2 === 0);
+            const found = numbers.find(n => n > 1);
+            const total = numbers.reduce((sum, n) => sum + n, 0);
+            const shouted = ["a"].map(function (w) { return w.toUpperCase(); });
+            [{ id: 1 }].forEach(item => item.missing);
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("doubled"), "number[]");
+        assert_eq!(type_of("labels"), "string[]");
+        assert_eq!(type_of("evens"), "number[]");
+        assert_eq!(type_of("found"), "number | undefined");
+        assert_eq!(type_of("total"), "number");
+        assert_eq!(type_of("shouted"), "string[]");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Property 'missing' does not exist on type '{ id: number; }'."]
+        );
+    }
+
+    #[test]
+    fn test_contextual_object_literals() {
+        let source = r#"
+            type Handler = {
+                kind: "click" | "key";
+                run: (event: { x: number }) => number;
+                nested: { mode: "fast" };
+                extra: { [key: string]: (input: boolean) => boolean };
+            };
+            declare function make<T>(): T[];
+            declare function register(options: { callback: (value: string) => void; level: 1 | 2 }): void;
+
+            const handler: Handler = {
+                kind: "click",
+                run: click => click.x,
+                nested: { mode: "fast", },
+                extra: { flip: input => !input },
+            };
+            const listing: { items: string[] } = { items: make() };
+            register({ callback: value => value.length, level: 1 });
+            const broken: Handler = {
+                kind: "key",
+                run: press => press.missing,
+                nested: { mode: "fast" },
+                extra: {},
+            };
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("click"), "{ x: number; }");
+        assert_eq!(type_of("input"), "boolean");
+        assert_eq!(type_of("value"), "string");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Property 'missing' does not exist on type '{ x: number; }'."]
+        );
+    }
+
+    #[test]
+    fn test_nested_scopes() {
+        let source = r#"
+            function outer(): number {
+                return inner();
+                function inner(): number { return 1; }
+            }
+            function shadow(value: string) {
+                {
+                    const value = 1;
+                    const inBlock: number = value;
+                }
+                const outside: string = value;
+            }
+            const value = true;
+            {
+                var hoisted = "x";
+                let blockOnly = 1;
+            }
+            const fromVar: string = hoisted;
+            const early: string = later();
+            function later(): string { return "x"; }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert!(
+            checker.get_errors().is_empty(),
+            "{:?}",
+            checker.get_errors()
+        );
+
+        let visible: Vec<(&str, String)> = checker
+            .visible_symbols()
+            .into_iter()
+            .map(|s| (s.name.as_str(), s.ty.to_string()))
+            .collect();
+        assert!(visible.contains(&("value", "true".to_string())));
+        assert!(visible.iter().any(|(name, _)| *name == "hoisted"));
+        assert!(!visible.iter().any(|(name, _)| *name == "inner"));
+        assert!(!visible.iter().any(|(name, _)| *name == "blockOnly"));
+
+        let offset = source.find("return inner").unwrap() as u32;
+        let names: Vec<&str> = checker
+            .visible_symbols_at(offset)
+            .into_iter()
+            .map(|s| s.name.as_str())
+            .collect();
+        assert!(names.contains(&"inner"));
+    }
+
+    #[test]
+    fn test_closure_narrowing() {
+        let source = r#"
+            function run(stable: string | number, reassigned: string | number) {
+                const fixed: string | number = stable;
+                let settled: string | number = stable;
+                if (typeof stable === "string") {
+                    const readStable = () => stable;
+                }
+                if (typeof reassigned === "string") {
+                    const readReassigned = () => reassigned;
+                }
+                reassigned = 1;
+                if (typeof fixed === "string") {
+                    const readFixed = () => fixed;
+                    function hoistedFixed(): string { return fixed; }
+                }
+                if (typeof settled === "string") {
+                    const readSettled = function () { return settled; };
+                    function hoistedSettled(): string { return settled; }
+                }
+            }
+            function defined(x: string | undefined, y: string | null) {
+                if (x !== undefined) {
+                    [1].forEach(() => { let s: string = x; });
+                }
+                if (y != null) {
+                    const readY = () => y;
+                }
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("readStable"), "() => string");
+        assert_eq!(type_of("readReassigned"), "() => string | number");
+        assert_eq!(type_of("readFixed"), "() => string");
+        assert_eq!(type_of("readSettled"), "() => string");
+        assert_eq!(type_of("readY"), "() => string");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'string | number' is not assignable to type 'string'"]
+        );
+    }
+
+    #[test]
+    fn test_throw_statements() {
+        let source = r#"
+            class HttpError extends Error {
+                status: number = 500;
+            }
+            function fail(reason: unknown, code: number) {
+                throw new Error("failed");
+                throw new HttpError("not found");
+                throw reason;
+                throw "failed";
+                throw { message: "failed" };
+                throw code;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert!(checker.get_errors().is_empty());
+
+        let mut checker = TypeChecker::new();
+        checker.set_only_throw_error(true);
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Expected an error object to be thrown.",
+                "Expected an error object to be thrown.",
+                "Expected an error object to be thrown.",
+            ]
+        );
+        let http_error = checker
+            .symbols()
+            .iter()
+            .find(|s| s.name == "HttpError")
+            .map(|s| s.ty.clone())
+            .unwrap();
+        let Type::Class(class) = http_error else {
+            panic!("expected a class");
+        };
+        assert!(class.instance.property("message").is_some());
+        assert!(class.instance.property("status").is_some());
+    }
+
+    #[test]
+    fn test_no_fallthrough_cases_in_switch() {
+        let source = r#"
+            function describe(kind: string, flag: boolean): string {
+                let label = "";
+                switch (kind) {
+                    case "a":
+                    case "b":
+                        return "letter";
+                    case "c":
+                        label = "c";
+                    case "d":
+                        if (flag) {
+                            break;
+                        } else {
+                            throw new Error("d");
+                        }
+                    case "e":
+                        if (flag) {
+                            break;
+                        }
+                    case "f":
+                        try {
+                            return "f";
+                        } finally {
+                            label = "f";
+                        }
+                    default:
+                        label = "other";
+                }
+                return label;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert!(checker.get_errors().is_empty());
+
+        let mut checker = TypeChecker::new();
+        checker.set_no_fallthrough_cases_in_switch(true);
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Fallthrough case in switch.", "Fallthrough case in switch."]
+        );
+        let spans: Vec<&str> = checker
+            .error_spans
+            .iter()
+            .map(|span| &source[span.start as usize..span.start as usize + 8])
+            .collect();
+        assert_eq!(spans, vec!["case \"c\"", "case \"e\""]);
+    }
+
+    #[test]
+    fn test_strictness_flags() {
+        let source = r#"
+            const count: number = null;
+            const handler: (value: string | number) => void = (value: string) => {};
+            const point = { x: 1 };
+            const key: string = "x";
+            const coordinate = point[key];
+            try {
+                run();
+            } catch (error) {
+                const caught = error;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'null' is not assignable to type 'number'",
+                "Type '(string) => void' is not assignable to type '(string | number) => void'",
+                "Element implicitly has an 'any' type because expression of type 'string' can't be used to index type '{ x: number; }'.",
+            ]
+        );
+        let type_of = |checker: &TypeChecker, name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of(&checker, "caught"), "unknown");
+
+        let mut checker = TypeChecker::new();
+        checker.set_strictness(Strictness::default());
+        checker.check_program(&ts_program.program);
+        assert!(checker.get_errors().is_empty());
+        assert_eq!(type_of(&checker, "coordinate"), "any");
+        assert_eq!(type_of(&checker, "caught"), "any");
+    }
+
+    #[test]
+    fn test_top_level_await() {
+        let source = r#"
+            const ready: Promise<number> = Promise.resolve(1);
+            const value = await ready;
+            async function load() {
+                return await ready;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let check = |module_kind: ModuleKind, target: ScriptTarget| {
+            let mut checker = TypeChecker::new();
+            checker.set_module_kind(module_kind);
+            checker.set_target(target);
+            checker.check_program(&ts_program.program);
+            checker.get_errors().to_vec()
+        };
+        assert!(check(ModuleKind::ESNext, ScriptTarget::ES2017).is_empty());
+        assert_eq!(
+            check(ModuleKind::CommonJS, ScriptTarget::ESNext),
+            vec!["The current file is a CommonJS module and cannot use 'await' at the top level."]
+        );
+        let settings = check(ModuleKind::ES2015, ScriptTarget::ESNext);
+        assert_eq!(settings.len(), 1);
+        assert!(settings[0].starts_with("Top-level 'await' expressions are only allowed"));
+        assert_eq!(check(ModuleKind::ESNext, ScriptTarget::ES2015), settings);
+    }
+
+    #[test]
+    fn test_assignment_narrowing() {
+        let source = r#"
+            let x: string | number | boolean = 1;
+            x = "str";
+            let s: string = x;
+            x = 2;
+            let n: number = x;
+            let wrong: string = x;
+            if (typeof x === "number") {
+                x = true;
+            }
+            let either: number | boolean = x;
+            let notBoolean: number = x;
+            if (s.length > 1) {
+                x = "a";
+            } else {
+                x = "b";
+            }
+            let branches: string = x;
+            if (s.length > 2) {
+                x = 3;
+                throw new Error();
+            }
+            let afterThrow: string = x;
+            x = {};
+            x += 1;
+            let compound: string = x;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'number' is not assignable to type 'string'",
+                "Type 'number | boolean' is not assignable to type 'number'",
+                "Type '{}' is not assignable to type 'string | number | boolean'",
+                "Type 'string | number | boolean' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_loop_narrowing() {
+        let source = r#"
+            let total: number = 0;
+            let x: string | number | boolean = "start";
+            x = "a";
+            while (total < 3) {
+                let s: string = x;
+                x = 1;
+            }
+            let afterWhile: string = x;
+
+            let y: string | number | boolean = "b";
+            y = "b";
+            for (let i = 0; i < 3; i++) {
+                y = y + "!";
+            }
+            let stillString: string = y;
+
+            let z: string | number = "c";
+            z = "c";
+            for (const item of [1, 2]) {
+                if (item > 1) {
+                    break;
+                }
+                z = item;
+            }
+            let afterBreak: string = z;
+
+            for (const n of [1, 2, 3]) {
+                let element: string = n;
+            }
+            for (const key in { a: 1 }) {
+                let k: number = key;
+            }
+            do {
+                total = "none";
+            } while (total < 10);
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'string | number' is not assignable to type 'string'",
+                "Type 'string | number' is not assignable to type 'string'",
+                "Type 'string | number' is not assignable to type 'string'",
+                "Type 'number' is not assignable to type 'string'",
+                "Type 'string' is not assignable to type 'number'",
+                "Type 'string' is not assignable to type 'number'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_aliased_condition_narrowing() {
+        let source = r#"
+            declare function pick(): string | number;
+            function describe(x: string | number) {
+                const isString = typeof x === "string";
+                if (isString) {
+                    let s: string = x;
+                } else {
+                    let n: number = x;
+                }
+                const notNumber = typeof x !== "number";
+                if (!notNumber) {
+                    let n: number = x;
+                }
+            }
+            let y: string | number = pick();
+            const isNumber = typeof y === "number";
+            y = pick();
+            if (isNumber) {
+                let n: number = y;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'string | number' is not assignable to type 'number'"]
+        );
+    }
+
+    #[test]
+    fn test_discriminant_narrowing() {
+        let source = r#"
+            type Shape = { kind: "circle"; radius: number } | { kind: "square"; side: number };
+            declare const shape: Shape;
+            if (shape.kind === "circle") {
+                let r: number = shape.radius;
+            } else {
+                let s: number = shape.side;
+            }
+            const { kind } = shape;
+            if (kind === "square") {
+                let s: number = shape.side;
+            } else {
+                let r: number = shape.radius;
+            }
+            const isCircle = shape.kind === "circle";
+            if (isCircle) {
+                let r: number = shape.radius;
+            }
+            function area(s: Shape) {
+                s = shape;
+                const { kind } = s;
+                if (kind === "circle") {
+                    let r: number = s.radius;
+                }
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Property 'radius' does not exist on type '{ kind: \"circle\"; radius: number; } | { kind: \"square\"; side: number; }'."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_computed_property_keys() {
+        let source = r#"
+            declare const key: string;
+            declare const index: number;
+            declare const literal: "b";
+            const named = { a: 1, [literal]: "x", [2]: true };
+            const byString = { a: 1, [key]: "x" };
+            const byNumber = { [index]: true, [`c${key}`]: 1 };
+            const k = "key";
+            const byConstant = { [k]: 1, b: "s" };
+            let copy = k;
+            let b: string = named.b;
+            let a: number = byString.a;
+            let other: number = byString.other;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("named"), "{ a: number; b: string; 2: boolean; }");
+        assert_eq!(
+            type_of("byString"),
+            "{ [key: string]: string | number; a: number; }"
+        );
+        assert_eq!(
+            type_of("byNumber"),
+            "{ [key: string]: number | boolean; [key: number]: boolean; }"
+        );
+        assert_eq!(type_of("k"), "\"key\"");
+        assert_eq!(type_of("byConstant"), "{ key: number; b: string; }");
+        assert_eq!(type_of("copy"), "string");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'string | number' is not assignable to type 'number'"]
+        );
+    }
+
+    #[test]
+    fn test_accessor_write_types() {
+        let source = r#"
+            class Box {
+                get size(): number {
+                    return 1;
+                }
+                set size(value: number | string) {}
+                get fixed(): number {
+                    return 1;
+                }
+            }
+            const box = new Box();
+            box.size = "10";
+            box.size = 3;
+            box.size = true;
+            let read: string = box.size;
+            box.fixed = 2;
+
+            let thing: {
+                get name(): string;
+                set name(value: string | undefined);
+                count: number;
+            } = { name: "a", count: 1 };
+            thing.name = undefined;
+            thing.count = "one";
+            let name: number = thing.name;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'boolean' is not assignable to type 'number | string'",
+                "Type 'number' is not assignable to type 'string'",
+                "Cannot assign to 'fixed' because it is a read-only property.",
+                "Type 'string' is not assignable to type 'number'",
+                "Type 'string' is not assignable to type 'number'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_interface_accessor_write_types() {
+        let source = r#"
+            interface Input {
+                get value(): string;
+                set value(next: string | number);
+                get id(): number;
+            }
+            declare const input: Input;
+            input.value = "a";
+            input.value = 1;
+            input.value = true;
+            let text: string = input.value;
+            let count: number = input.value;
+            input.id = 2;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'boolean' is not assignable to type 'string | number'",
+                "Type 'string' is not assignable to type 'number'",
+                "Cannot assign to 'id' because it is a read-only property.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_override_modifiers() {
+        let source = r#"
+            class Base {
+                name: string = "base";
+                greet(): string {
+                    return "hi";
+                }
+                static create(): number {
+                    return 1;
+                }
+            }
+            class Derived extends Base {
+                override name: string = "derived";
+                greet(): string {
+                    return "hello";
+                }
+                override missing(): void {}
+                static create(): number {
+                    return 2;
+                }
+            }
+            class Alone {
+                override name: string = "alone";
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let check = |no_implicit_override: bool| {
+            let mut checker = TypeChecker::new();
+            checker.set_no_implicit_override(no_implicit_override);
+            checker.check_program(&ts_program.program);
+            checker.get_errors().to_vec()
+        };
+        let always = vec![
+            "This member cannot have an 'override' modifier because it is not declared in the base class 'Base'.",
+            "This member cannot have an 'override' modifier because its containing class 'Alone' does not extend another class.",
+        ];
+        assert_eq!(check(false), always);
+        assert_eq!(
+            check(true),
+            vec![
+                "This member must have an 'override' modifier because it overrides a member in the base class 'Base'.",
+                always[0],
+                "This member must have an 'override' modifier because it overrides a member in the base class 'Base'.",
+                always[1],
+            ]
+        );
+    }
+
+    #[test]
+    fn test_record_types() {
+        let source = r#"
+            type Handlers = Record<string, (value: string) => number>;
+            const handlers: Handlers = {
+                length: (value) => value.length,
+                parse: (value) => value.toUpperCase(),
+            };
+            const point: Record<"x" | "y", number> = { x: 1, y: 2 };
+            const scores = { alice: 1, bob: 2 };
+            const byName: Record<string, number> = scores;
+            const mixed: Record<string, number> = { a: 1, b: "two" };
+            const byIndex: Record<number, string> = { 0: "zero", 1: "one" };
+            const copy: { [key: string]: number } = byName;
+            let x: number = point.x;
+            let anyName: number = byName.anything;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("point"), "{ x: number; y: number; }");
+        assert_eq!(type_of("byIndex"), "{ [key: number]: string; }");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type '{ length: (string) => number; parse: (string) => string; }' is not assignable to type 'Handlers'",
+                "Type '{ a: number; b: string; }' is not assignable to type '{ [key: string]: number; }'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enums() {
+        let source = r#"
+            enum Color { Red, Green = 5, Blue }
+            const enum Size { Small = 1, Large = Small * 2, Random = Math.random() }
+            let color: Color = Color.Green;
+            let other: Color = 7;
+            let name: string = Color[0];
+            let large = Size.Large;
+            let small = Size["Small"];
+            declare const key: string;
+            let byKey = Size[key];
+            let sizes = Size;
+            Color.Red = 3;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("color"), "Color");
+        assert_eq!(type_of("large"), "Size");
+        assert_eq!(type_of("small"), "Size");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "const enum member initializers must be constant expressions.",
+                "Type '7' is not assignable to type 'Color'",
+                "A const enum member can only be accessed using a string literal.",
+                "'const' enums can only be used in property or index access expressions or the right hand side of an import declaration or export assignment or type query.",
+                "Cannot assign to 'Red' because it is a read-only property.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_enum_merging() {
+        let source = r#"
+            enum Color { Red, Green }
+            namespace Color {
+                export function parse(text: string): Color {
+                    return text === "red" ? Color.Red : Color.Green;
+                }
+            }
+            enum Color { Blue = 2 }
+            enum Color { Extra }
+            const enum Color { Other = 9 }
+            declare enum Remote { A, B = 3, C = compute() }
+            namespace Outer.Inner {
+                export const depth = 2;
+            }
+            let parsed = Color.parse("red");
+            let blue: Color = Color.Blue;
+            let remote: 0 = Remote.A;
+            let depth: string = Outer.Inner.depth;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let symbol = |name: &str| {
+            checker
+                .visible_symbols()
+                .into_iter()
+                .find(|s| s.name == name)
+                .map(|s| s.to_string())
+                .unwrap()
+        };
+        assert_eq!(symbol("parsed"), "(variable) parsed: Color");
+        assert_eq!(
+            symbol("Color"),
+            "(enum) Color: { [key: number]: string; readonly Red: Color; readonly Green: Color; parse: (string) => Color; readonly Blue: Color; readonly Extra: Color; readonly Other: Color; }"
+        );
+        assert_eq!(
+            symbol("Outer"),
+            "(namespace) Outer: { Inner: { depth: 2; }; }"
+        );
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "In an enum with multiple declarations, only one declaration can omit an initializer for its first enum element.",
+                "Enum declarations must all be const or non-const.",
+                "In ambient enum declarations member initializer must be constant expression.",
+                "Type 'Remote' is not assignable to type '0'",
+                "Type 'number' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_branded_types() {
+        let source = r#"
+            declare const usd: unique symbol;
+            type UserId = string & { readonly __brand: unique symbol };
+            type OrderId = string & { readonly __brand: unique symbol };
+            type Usd = number & { [usd]: true };
+            function userId(raw: string): UserId {
+                return raw as UserId;
+            }
+            const id = userId("a");
+            const plain: string = id;
+            const unbranded: UserId = "b";
+            const order: OrderId = id;
+            const length: number = id.length;
+            const price = 5 as Usd;
+            let either: UserId | number = id;
+            if (typeof either === "string") {
+                const narrowed = either;
+            }
+            type Impossible = string & number;
+            declare const impossible: Impossible;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("price"), "number & { [usd]: true; }");
+        assert_eq!(
+            type_of("narrowed"),
+            "string & { readonly __brand: unique symbol; }"
+        );
+        assert_eq!(type_of("impossible"), "never");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'string' is not assignable to type 'UserId'",
+                "Type 'UserId' is not assignable to type 'OrderId'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unknown_must_be_narrowed() {
+        let source = r#"
+            declare const value: unknown;
+            declare function load(): unknown;
+            const member = value.name;
+            const element = value["name"];
+            const called = value();
+            const built = new value();
+            const nested = load().name;
+            const sum = value + 1;
+            const joined = value + "!";
+            const text: string = value;
+            const kept: unknown = value;
+            if (typeof value === "string") {
+                const length = value.length;
+            }
+            type Absorbed = string | unknown;
+            declare const absorbed: Absorbed;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let absorbed = checker
+            .symbols()
+            .iter()
+            .find(|s| s.name == "absorbed")
+            .map(|s| s.ty.resolved().to_string());
+        assert_eq!(absorbed.as_deref(), Some("unknown"));
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "'value' is of type 'unknown'.",
+                "'value' is of type 'unknown'.",
+                "'value' is of type 'unknown'.",
+                "'value' is of type 'unknown'.",
+                "Object is of type 'unknown'.",
+                "'value' is of type 'unknown'.",
+                "Type 'unknown' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_never_is_the_bottom_type() {
+        let source = r#"
+            const fail = (message: string) => {
+                throw new Error(message);
+            };
+            const branches = function (flag: boolean) {
+                if (flag) {
+                    throw new Error("yes");
+                } else {
+                    throw new Error("no");
+                }
+            };
+            const sometimes = (flag: boolean) => {
+                if (flag) {
+                    return 1;
+                }
+                throw new Error("no");
+            };
+            const asynchronous = async () => {
+                throw new Error("later");
+            };
+            declare const nothing: never;
+            const text: string = nothing;
+            const impossible: never = "text";
+            type Reduced = string | never;
+            declare const reduced: Reduced;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("fail"), "(string) => never");
+        assert_eq!(type_of("branches"), "(boolean) => never");
+        assert_eq!(type_of("sometimes"), "(boolean) => void");
+        assert_eq!(type_of("asynchronous"), "() => Promise<never>");
+        assert_eq!(type_of("reduced"), "string");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'string' is not assignable to type 'never'"]
+        );
+    }
+
+    #[test]
+    fn test_void_callbacks_accept_any_return() {
+        let source = r#"
+            function each(items: number[], callback: (item: number) => void): void {}
+            function double(n: number): number {
+                return n * 2;
+            }
+            each([1, 2], (item) => item * 2);
+            each([1, 2], double);
+            const push: (item: number) => void = (item) => [item].push(item);
+            const count: (item: number) => number = (item) => {};
+            function nothing(): void {
+                return 1;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type '(number) => void' is not assignable to type '(number) => number'",
+                "Type 'number' is not assignable to type 'void'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_callbacks_may_take_fewer_parameters() {
+        let source = r#"
+            function each(callback: (value: string, index: number) => void): void {}
+            each(() => {});
+            each((value) => value.length);
+            const ignoring: (a: number, b: number) => number = (a) => a;
+            const needy: (a: number) => number = (a, b) => a;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Parameter 'b' implicitly has an 'any' type.",
+                "Type '(number, any) => number' is not assignable to type '(number) => number'"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_strict_property_initialization() {
+        let source = r#"
+            class Account {
+                id: number;
+                name: string;
+                nickname?: string;
+                note: string | undefined;
+                balance = 0;
+                owner!: string;
+                static count: number;
+                constructor() {
+                    this.name = "x";
+                }
+            }
+            declare class Ambient {
+                id: number;
+            }
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Property 'id' has no initializer and is not definitely assigned in the constructor."
+            ]
+        );
+
+        let mut checker = TypeChecker::new();
+        checker.set_strictness(Strictness {
+            strict_property_initialization: false,
+            ..Strictness::STRICT
+        });
+        checker.check_program(&ts_program.program);
+        assert!(checker.get_errors().is_empty());
+    }
+
+    #[test]
+    fn test_strict_bind_call_apply() {
+        let source = r#"
+            function add(a: number, b: number): number { return a + b; }
+            const sum: number = add.call(null, 1, 2);
+            add.call(null, "1", 2);
+            add.apply(null, [1, 2]);
+            const increment = add.bind(null, 1);
+            const total: number = increment(2);
+            increment("2");
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Argument of type 'string' is not assignable to parameter of type 'number'.",
+                "Argument of type 'string' is not assignable to parameter of type 'number'.",
+            ]
+        );
+
+        let mut checker = TypeChecker::new();
+        checker.set_strictness(Strictness {
+            strict_bind_call_apply: false,
+            ..Strictness::STRICT
+        });
+        checker.check_program(&ts_program.program);
+        assert!(checker.get_errors().is_empty());
+    }
+
+    #[test]
+    fn test_implicit_any_parameters() {
+        let source = r#"
+            function f(x, y = 1, ...rest) {}
+            declare const untyped: any;
+            untyped((value) => value);
+            const p = new Promise<number>((resolve) => resolve(1));
+            const g = function (z: number, w) {};
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Parameter 'x' implicitly has an 'any' type.",
+                "Rest parameter 'rest' implicitly has an 'any[]' type.",
+                "Parameter 'w' implicitly has an 'any' type.",
+            ]
+        );
+
+        let mut checker = TypeChecker::new();
+        checker.set_strictness(Strictness::default());
+        checker.check_program(&ts_program.program);
+        assert!(checker.get_errors().is_empty());
+    }
+
+    #[test]
+    fn test_undefined_in_uninitialized_and_optional_types() {
+        let source = r#"
+            let value;
+            const before = value;
+            value = 5;
+            const number = value;
+            value = "five";
+            const string = value;
+            let either;
+            if (Math.random() > 0.5) {
+                either = 1;
+            } else {
+                either = "one";
+            }
+            const merged = either;
+
+            type Options = { size?: number; log?(): void };
+            function configure(options: Options, name?: string) {
+                const size = options.size;
+                const label = name;
+                const required: string = name;
+            }
+            class Box {
+                width?: number;
+                constructor(public height?: number) {}
+            }
+            const width = new Box().width;
+            const height = new Box().height;
+            const explicit: Options = { size: undefined };
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("before"), "undefined");
+        assert_eq!(type_of("number"), "number");
+        assert_eq!(type_of("string"), "string");
+        assert_eq!(type_of("merged"), "number | string");
+        assert_eq!(type_of("size"), "number | undefined");
+        assert_eq!(type_of("label"), "string | undefined");
+        assert_eq!(type_of("configure"), "(Options, string | undefined) => any");
+        assert_eq!(type_of("width"), "number | undefined");
+        assert_eq!(type_of("height"), "number | undefined");
+        assert_eq!(
+            checker.get_errors(),
+            vec!["Type 'string | undefined' is not assignable to type 'string'"]
+        );
+    }
+
+    #[test]
+    fn test_arrays_evolve_with_pushed_values() {
+        let source = r#"
+            const values = [];
+            values.push(1);
+            const numbers = values;
+            values.push("two", 3);
+            const mixed = values;
+            let flags = [];
+            flags.unshift(true);
+            const booleans = flags;
+            const typed: number[] = [];
+            typed.push(1);
+            const stillTyped = typed;
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("numbers"), "number[]");
+        assert_eq!(type_of("mixed"), "(number | string)[]");
+        assert_eq!(type_of("booleans"), "boolean[]");
+        assert_eq!(type_of("stillTyped"), "number[]");
+        assert!(checker.get_errors().is_empty());
+    }
+
+    #[test]
+    fn test_array_spreads() {
+        let source = r#"
+            const numbers = [1, 2];
+            const pair: [number, string] = [1, "one"];
+            const appended = [...numbers, 3];
+            const mixed = [...numbers, "three"];
+            const fromTuple = [...pair, true];
+            const tuple: [number, string, boolean] = [...pair, true];
+            const frozen = [...pair, 3] as const;
+            const characters = [..."abc"];
+            const holes = [1, , 2];
+            const invalid = [...42];
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("appended"), "number[]");
+        assert_eq!(type_of("mixed"), "(number | string)[]");
+        assert_eq!(type_of("fromTuple"), "(number | string | boolean)[]");
+        assert_eq!(type_of("tuple"), "[number, string, boolean]");
+        assert_eq!(type_of("frozen"), "readonly [number, string, 3]");
+        assert_eq!(type_of("characters"), "string[]");
+        assert_eq!(type_of("holes"), "(number | undefined)[]");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'number' must have a '[Symbol.iterator]()' method that returns an iterator."
+            ]
+        );
+    }
+
+    #[test]
+    fn test_custom_iterables() {
+        let source = r#"
+            type Result = { done: false; value: number } | { done: true; value: undefined };
+            class Countdown {
+                constructor(private from: number) {}
+                [Symbol.iterator](): { next(): Result } {
+                    return null as any;
+                }
+            }
+            type Words = { [Symbol.iterator](): { next(): { done: boolean; value: string } } };
+            declare const words: Words;
+            for (const count of new Countdown(3)) {
+                const current = count;
+            }
+            for (const word of words) {
+                const each = word;
+            }
+            const counts = [...new Countdown(2)];
+            const [first, second] = words;
+            const [head, ...tail] = [1, "two", true] as [number, string, boolean];
+            const [one, ...more] = [1, 2, 3];
+            for (const digit of 42) {
+            }
+            const [nothing] = {};
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("current"), "number");
+        assert_eq!(type_of("each"), "string");
+        assert_eq!(type_of("counts"), "number[]");
+        assert_eq!(type_of("second"), "string");
+        assert_eq!(type_of("head"), "number");
+        assert_eq!(type_of("tail"), "[string, boolean]");
+        assert_eq!(type_of("more"), "number[]");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'number' must have a '[Symbol.iterator]()' method that returns an iterator.",
+                "Type '{}' must have a '[Symbol.iterator]()' method that returns an iterator.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_awaited_unwraps_nested_promises() {
+        let source = r#"
+            type Nested = Awaited<Promise<Promise<number>>>;
+            type Mixed = Awaited<string | Promise<boolean>>;
+            type Plain = Awaited<bigint>;
+            type Thenable = Awaited<{ then(onfulfilled: (value: Promise<symbol>) => void): void }>;
+            declare const nested: Nested;
+            declare const mixed: Mixed;
+            declare const plain: Plain;
+            declare const thenable: Thenable;
+            const resolved = Promise.resolve(Promise.resolve(1));
+            const rejected = Promise.reject(new Error("no"));
+            const run = async () => {
+                const value = await Promise.resolve(Promise.resolve("text"));
+                const direct = await 1;
+                return value;
+            };
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("nested"), "number");
+        assert_eq!(type_of("mixed"), "string | boolean");
+        assert_eq!(type_of("plain"), "bigint");
+        assert_eq!(type_of("thenable"), "symbol");
+        assert_eq!(type_of("resolved"), "Promise<number>");
+        assert_eq!(type_of("rejected"), "Promise<never>");
+        assert_eq!(type_of("value"), "string");
+        assert_eq!(type_of("direct"), "number");
+        assert_eq!(type_of("run"), "() => Promise<string>");
+        assert!(checker.get_errors().is_empty());
+    }
+
+    #[test]
+    fn test_calling_and_constructing_unsuitable_values() {
+        let source = r#"
+            const count = 5;
+            count();
+            const point = { x: 1 };
+            point();
+            class Widget {}
+            Widget();
+            new count();
+            new point();
+            const widget = new Widget();
+            const error = Error("allowed");
+            const promise = new Promise<number>((resolve) => resolve(1));
+            type Labeled = { (value: number): string; label: string };
+            declare const labeled: Labeled;
+            const called = labeled(1);
+            const label = labeled.label;
+            type Check = (value: number) => boolean;
+            declare const maybe: Check | number;
+            maybe(1);
+            declare const anything: any;
+            anything();
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let type_of = |name: &str| {
+            checker
+                .symbols()
+                .iter()
+                .find(|s| s.name == name)
+                .map(|s| s.ty.resolved().to_string())
+                .unwrap()
+        };
+        assert_eq!(type_of("promise"), "Promise<number>");
+        assert_eq!(type_of("called"), "string");
+        assert_eq!(type_of("label"), "string");
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "This expression is not callable. Type 'number' has no call signatures.",
+                "This expression is not callable. Type '{ x: number; }' has no call signatures.",
+                "Value of type 'typeof Widget' is not callable. Did you mean to include 'new'?",
+                "This expression is not constructable. Type 'number' has no construct signatures.",
+                "This expression is not constructable. Type '{ x: number; }' has no construct signatures.",
+                "This expression is not callable. Type 'Check | number' has no call signatures.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_argument_count_and_types() {
+        let source = r#"
+            function add(a: number, b: number): number {
+                return a + b;
+            }
+            function greet(name: string, greeting?: string) {}
+            function sum(label: string, ...values: number[]) {
+                const all = values;
+            }
+            add(1, "two");
+            add(1, 2, 3, 4);
+            add(1);
+            greet();
+            greet("Ada", "Hi", 3);
+            sum("total", 1, 2, 3);
+            sum("total", 1, "two");
+            sum();
+            [1, 2].push(3, 4);
+            "a,b".split(",");
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let reported: Vec<(&str, &str)> = checker
+            .errors
+            .iter()
+            .zip(&checker.error_spans)
+            .map(|(message, span)| {
+                (
+                    message.as_str(),
+                    &source[span.start as usize..span.end as usize],
+                )
+            })
+            .collect();
+        assert_eq!(
+            reported,
+            vec![
+                (
+                    "Argument of type 'string' is not assignable to parameter of type 'number'.",
+                    "\"two\""
+                ),
+                ("Expected 2 arguments, but got 4.", "3, 4"),
+                ("Expected 2 arguments, but got 1.", "add(1)"),
+                ("Expected 1-2 arguments, but got 0.", "greet()"),
+                ("Expected 1-2 arguments, but got 3.", "3"),
+                (
+                    "Argument of type 'string' is not assignable to parameter of type 'number'.",
+                    "\"two\""
+                ),
+                ("Expected at least 1 arguments, but got 0.", "sum()"),
+            ]
+        );
+        let all = checker.symbols().iter().find(|s| s.name == "all").unwrap();
+        assert_eq!(all.ty.to_string(), "number[]");
+    }
+
+    #[test]
+    fn test_generic_call_errors_show_instantiation() {
+        let source = r#"
+            function first<T>(items: T[], fallback?: T): T {
+                return items[0];
+            }
+            const utils = {
+                clamp<T extends number>(value: T, ...bounds: T[]): T {
+                    return value;
+                },
+            };
+            first<number>(["a"]);
+            utils.clamp<number>(1, 2, "3");
+        "#;
+
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let messages = checker.get_errors();
+        assert_eq!(
+            messages,
+            vec![
+                "Argument of type 'string[]' is not assignable to parameter of type 'number[]' in call to 'first<number>(number[], number | undefined): number'.",
+                "Argument of type 'string' is not assignable to parameter of type 'number' in call to 'clamp<number>(number, ...number[]): number'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_long_types_in_errors_are_truncated() {
+        let fields: Vec<String> = (0..30).map(|n| format!("field{}: {}", n, n)).collect();
+        let source = format!("let settings: number = {{ {} }};", fields.join(", "));
+        let ts_program = parse_typescript(&source).unwrap();
+
+        let message = |no_error_truncation: bool| {
+            let mut checker = TypeChecker::new();
+            checker.set_no_error_truncation(no_error_truncation);
+            checker.check_program(&ts_program.program);
+            checker.get_errors()[0].clone()
+        };
+        let truncated = message(false);
+        assert!(
+            truncated.starts_with("Type '{ field0: number; field1: number; "),
+            "{}",
+            truncated
+        );
+        assert!(
+            truncated.ends_with(
+                "field9: number; ... 20 more ...; }' is not assignable to type 'number'"
+            ),
+            "{}",
+            truncated
+        );
+        assert!(message(true).ends_with("field29: number; }' is not assignable to type 'number'"));
+    }
+
+    #[test]
+    fn test_errors_are_reported_once_per_expression() {
+        let source = r#"
+declare let u: unknown;
+let sum = u + 1;
+let text: string = sum;
+let mixed = 1n + 1;
+let label: string = mixed;
+let point = { x: 1 };
+let missing = point.y;
+let length: string = missing.length;
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let messages = checker.get_errors();
+        assert_eq!(messages.len(), 3, "{:?}", messages);
+        assert!(
+            messages[0].contains("'u' is of type 'unknown'"),
+            "{:?}",
+            messages
+        );
+        assert!(
+            messages[1].contains("'bigint' and 'number'"),
+            "{:?}",
+            messages
+        );
+        assert!(
+            messages[2].contains("Property 'y' does not exist"),
+            "{:?}",
+            messages
+        );
+    }
+
+    #[test]
+    fn test_numeric_literal_forms() {
+        let source = r#"
+let hex: 31 = 0x1F;
+let binary: 5 = 0b101;
+let octal: 15 = 0o17;
+let separated: 1000000 = 1_000_000;
+let exponent: 1500 = 1.5e3;
+let negative: -1 = -1;
+let negativeZero: 0 = -0;
+let wrong: 1 = 0x2;
+let huge: 1 = 1e21;
+let tiny: 1 = -1e-7;
+let notANumber: string = NaN;
+let infinite: number = Infinity;
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let messages = checker.get_errors();
+        assert_eq!(
+            messages,
+            vec![
+                "Type '2' is not assignable to type '1'",
+                "Type '1e+21' is not assignable to type '1'",
+                "Type '-1e-7' is not assignable to type '1'",
+                "Type 'number' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bigint_literal_types() {
+        let source = r#"
+let exact: 10n = 10n;
+let hex: 255n = 0xFFn;
+let negative: -5n = -5n;
+let wide: bigint = exact;
+let sum: bigint = exact + hex;
+let wrong: 1n = 2n;
+let mixed: 1n = 1;
+let narrow: 1n = wide;
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        let messages = checker.get_errors();
+        assert_eq!(
+            messages,
+            vec![
+                "Type '2n' is not assignable to type '1n'",
+                "Type '1' is not assignable to type '1n'",
+                "Type 'bigint' is not assignable to type '1n'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_regular_expression_literals() {
+        let source = r#"
+let pattern = /ab+c/gi;
+let matched: boolean = pattern.test("abc");
+let groups = pattern.exec("abc");
+let first: string = groups ? groups[0] : "";
+let flags: string = pattern.flags;
+let typed: RegExp = /x/;
+let constructed = new RegExp("x");
+let wrong: number = pattern.source;
+pattern.missing;
+let text: string = /x/;
+let error: string = new Error("failed");
+let range: string = new RangeError("out of range");
+let caught: Error = new TypeError("wrong");
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'string' is not assignable to type 'number'",
+                "Property 'missing' does not exist on type 'RegExp'.",
+                "Type 'RegExp' is not assignable to type 'string'",
+                "Type 'Error' is not assignable to type 'string'",
+                "Type 'RangeError' is not assignable to type 'string'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_object_literal_methods() {
+        let source = r#"
+let counter = {
+    count: 1,
+    greet() { return "hi"; },
+    ["named"]() { return 2; },
+    get size() { return 2; },
+    set size(value: number | string) {},
+    get label() { return "l"; },
+    bump() { return this.count; },
+    nested() { return function () { return this; }; },
+};
+let greeting: number = counter.greet();
+let named: number = counter.named();
+let size: string = counter.size;
+counter.size = "3";
+counter.label = "x";
+let bumped: string = counter.bump();
+let typed: { total: number; read(): number } = {
+    total: 1,
+    read() { return this.total; },
+};
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let check = |no_implicit_this: bool| {
+            let mut checker = TypeChecker::new();
+            checker.set_strictness(Strictness {
+                no_implicit_this,
+                ..Strictness::STRICT
+            });
+            checker.check_program(&ts_program.program);
+            checker
+                .get_errors()
+                .iter()
+                .map(|error| error.to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            check(true),
+            vec![
+                "Type 'string' is not assignable to type 'number'",
+                "Type 'number' is not assignable to type 'string'",
+                "Cannot assign to 'label' because it is a read-only property.",
+                "Type 'number' is not assignable to type 'string'",
+            ]
+        );
+        // `this` is `any` without `noImplicitThis`
+        assert_eq!(check(false).len(), 3);
+    }
+
+    #[test]
+    fn test_excess_properties_of_fresh_literals() {
+        let source = r#"
+type Point = { x: number; y: number };
+let direct: Point = { x: 1, y: 2, z: 3 };
+let stored = { x: 1, y: 2, z: 3 };
+let indirect: Point = stored;
+let nested: { at: Point } = { at: { x: 1, y: 2, z: 3 } };
+let either: { a: number } | { b: number } = { a: 1, b: 2 };
+let dictionary: { [key: string]: number } = { x: 1, y: 2 };
+function make() { return { x: 1, y: 2, z: 3 }; }
+let made: Point = make();
+function take(point: Point) {}
+take({ x: 1, y: 2, w: 4 });
+take(stored);
+let assigned: Point = stored;
+assigned = { x: 1, y: 2, extra: true };
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Object literal may only specify known properties, and 'z' does not exist in type 'Point'.",
+                "Object literal may only specify known properties, and 'z' does not exist in type 'Point'.",
+                "Object literal may only specify known properties, and 'w' does not exist in type 'Point'.",
+                "Object literal may only specify known properties, and 'extra' does not exist in type 'Point'.",
+            ]
+        );
+        // Each is reported where the literal names the property
+        let spans: Vec<(u32, &str)> = checker
+            .error_spans
+            .iter()
+            .map(|span| {
+                let line = source[..span.start as usize].lines().count() as u32;
+                (line, &source[span.start as usize..span.end as usize])
+            })
+            .collect();
+        assert_eq!(spans, vec![(3, "z"), (6, "z"), (12, "w"), (15, "extra")]);
+    }
+
+    #[test]
+    fn test_union_property_access() {
+        let source = r#"
+type Circle = { kind: "circle"; radius: number; label: string };
+type Square = { kind: "square"; size: number; label: number };
+declare let shape: Circle | Square;
+let kind: "circle" | "square" = shape.kind;
+let label: string = shape.label;
+let radius = shape.radius;
+declare let text: string | string[];
+let length: number = text.length;
+let upper = text.toUpperCase();
+declare let maybe: Circle | undefined;
+let maybeLabel: string = maybe.label;
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "Type 'string | number' is not assignable to type 'string'",
+                "Property 'radius' does not exist on type 'Circle | Square'.",
+                "Property 'toUpperCase' does not exist on type 'string | string[]'.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_intersection_members() {
+        let source = r#"
+type Named = { name: string; id: string | number; tag?: string };
+type Numbered = { id: number; tag?: string; count: number };
+declare let both: Named & Numbered;
+let id: number = both.id;
+let name: string = both.name;
+let text: string = both.count;
+let merged: Named & Numbered = { name: "a", id: "b", count: 1 };
+type Circle = { kind: "circle" };
+type Square = { kind: "square" };
+type Impossible = Circle & Square;
+let shape: Circle & Square = { kind: "circle" };
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.check_program(&ts_program.program);
+        assert_eq!(
+            checker.get_errors(),
+            vec![
+                "The intersection 'Circle & Square' was reduced to 'never' because property 'kind' has conflicting types in some constituents.",
+                "Type 'number' is not assignable to type 'string'",
+                "Type '{ name: string; id: string; count: number; }' is not assignable to type 'Named & Numbered'",
+                "The intersection 'Circle & Square' was reduced to 'never' because property 'kind' has conflicting types in some constituents.",
+                "Type '{ kind: string; }' is not assignable to type 'never'",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_global_this() {
+        let errors = |source: &str| {
+            let ts_program = parse_typescript(source).unwrap();
+            let mut checker = TypeChecker::new();
+            checker.check_program(&ts_program.program);
+            checker.get_errors().to_vec()
+        };
+        let script = r#"
+var count = 1;
+let local = "a";
+function reset(): void {}
+let n: number = globalThis.count;
+globalThis.count = 2;
+globalThis.count = "two";
+globalThis.reset();
+globalThis.local;
+let nan: string = globalThis.NaN;
+let error = new globalThis.Error("failed");
+"#;
+        assert_eq!(
+            errors(script),
+            vec![
+                "Type 'string' is not assignable to type 'number'",
+                "Property 'local' does not exist on type 'typeof globalThis'.",
+                "Type 'number' is not assignable to type 'string'",
+            ]
+        );
+
+        let module = r#"
+export var exported = 1;
+declare global {
+    var version: string;
+}
+let v: string = globalThis.version;
+let w: string = version;
+globalThis.exported;
+"#;
+        assert_eq!(
+            errors(module),
+            vec!["Property 'exported' does not exist on type 'typeof globalThis'."]
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let source = r#"
+let n: number = "a";
+const o = { a: 1 } as const;
+o.a = 2;
+function f(x) {}
+switch (n) {
+    case 1:
+        n++;
+    case 2:
+        break;
+}
+"#;
+        let ts_program = parse_typescript(source).unwrap();
+        let mut checker = TypeChecker::new();
+        checker.set_strictness(Strictness::STRICT);
+        checker.set_no_fallthrough_cases_in_switch(true);
+        checker.check_program(&ts_program.program);
+        let errors: Vec<(u32, Vec<String>)> = checker
+            .get_type_errors()
+            .into_iter()
+            .map(|error| (error.message.code, error.message.arguments))
+            .collect();
+        let arguments = |arguments: &[&str]| arguments.iter().map(|a| a.to_string()).collect();
+        assert_eq!(
+            errors,
+            vec![
+                (2322, arguments(&["string", "number"])),
+                (2540, arguments(&["a"])),
+                (7006, arguments(&["x", "any"])),
+                (7029, arguments(&[])),
+            ]
+        );
+    }
 }
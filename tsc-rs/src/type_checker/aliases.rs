@@ -0,0 +1,75 @@
+use super::TypeChecker;
+use crate::types::*;
+use oxc_ast::ast::*;
+use std::sync::Arc;
+
+/// Whether `ty` is `alias` itself without going through an object, array, or function,
+/// which would make the alias stand for nothing.
+fn refers_directly_to(alias: &Arc<AliasType>, ty: &Type, depth: usize) -> bool {
+    if depth > 64 {
+        return true;
+    }
+    match ty {
+        Type::Alias(other) => {
+            Arc::ptr_eq(alias, other) || refers_directly_to(alias, other.target(), depth + 1)
+        }
+        Type::Union(types) => types
+            .iter()
+            .any(|ty| refers_directly_to(alias, ty, depth + 1)),
+        _ => false,
+    }
+}
+
+impl TypeChecker {
+    /// Makes every top-level alias visible before any is resolved, so they can refer to
+    /// each other and themselves regardless of order.
+    pub(super) fn declare_type_aliases(&mut self, program: &Program) {
+        let declarations: Vec<&TSTypeAliasDeclaration> = program
+            .body
+            .iter()
+            .filter_map(|stmt| match stmt {
+                Statement::TSTypeAliasDeclaration(decl) => Some(&**decl),
+                Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                    Some(Declaration::TSTypeAliasDeclaration(decl)) => Some(&**decl),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect();
+        for decl in &declarations {
+            self.type_aliases.insert(
+                decl.id.name.to_string(),
+                (decl.span, Arc::new(AliasType::new(decl.id.name.as_str()))),
+            );
+        }
+        for decl in declarations {
+            self.check_type_alias_declaration(decl);
+        }
+    }
+
+    pub(super) fn check_type_alias_declaration(&mut self, decl: &TSTypeAliasDeclaration) {
+        let alias = match self.type_aliases.get(decl.id.name.as_str()) {
+            // Already resolved by `declare_type_aliases`
+            Some((span, alias)) if *span == decl.span => {
+                if alias.is_resolved() {
+                    return;
+                }
+                alias.clone()
+            }
+            _ => {
+                let alias = Arc::new(AliasType::new(decl.id.name.as_str()));
+                self.type_aliases
+                    .insert(decl.id.name.to_string(), (decl.span, alias.clone()));
+                alias
+            }
+        };
+        let target = self.check_type(&decl.type_annotation);
+        self.report_reduced_intersection(&decl.type_annotation);
+        if refers_directly_to(&alias, &target, 0) {
+            self.error(2456, &[&decl.id.name], decl.id.span);
+            alias.resolve(Type::ERROR);
+        } else {
+            alias.resolve(target);
+        }
+    }
+}
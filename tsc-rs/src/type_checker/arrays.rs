@@ -0,0 +1,191 @@
+use super::TypeChecker;
+use super::assignments::merge_types;
+use crate::symbols::SymbolKind;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use std::sync::Arc;
+
+/// The type of each value iterating over `ty` gives, or `None` if it isn't iterable.
+///
+/// Besides arrays and strings, anything with a `[Symbol.iterator]()` method is iterable,
+/// giving the values its iterator's `next()` results hold.
+pub(super) fn iterated_type(ty: &Type) -> Option<Type> {
+    match ty.resolved() {
+        Type::ObjectType(object) => {
+            let method = object.property("[Symbol.iterator]")?;
+            let Type::Function { return_type, .. } = method.ty.resolved() else {
+                return None;
+            };
+            iterator_value_type(return_type)
+        }
+        Type::Any => Some(Type::Any),
+        Type::Array(element) => Some((**element).clone()),
+        Type::Tuple(types) => Some(merge_types(&Type::Any, types.iter().cloned())),
+        Type::Readonly(ty) => iterated_type(ty),
+        Type::String | Type::StringLiteral(_) => Some(Type::String),
+        Type::Union(types) => types
+            .iter()
+            .map(iterated_type)
+            .collect::<Option<Vec<_>>>()
+            .map(|types| merge_types(&Type::Any, types)),
+        _ => None,
+    }
+}
+
+/// The type of the values an iterator gives: the `value` of each result its `next()`
+/// returns, apart from the ones marked `done: true`.
+fn iterator_value_type(iterator: &Type) -> Option<Type> {
+    let Type::ObjectType(iterator) = iterator.resolved() else {
+        return matches!(iterator.resolved(), Type::Any).then_some(Type::Any);
+    };
+    let Type::Function { return_type, .. } = iterator.property("next")?.ty.resolved() else {
+        return None;
+    };
+    let results = match return_type.resolved() {
+        Type::Union(types) => types.clone(),
+        ty => vec![ty.clone()],
+    };
+    let values = results.iter().filter_map(|result| match result.resolved() {
+        Type::ObjectType(result) => match result.property("done") {
+            Some(done) if done.ty == Type::BooleanLiteral(true) => None,
+            _ => Some(result.property("value").map_or(Type::Any, |p| p.ty.clone())),
+        },
+        _ => Some(Type::Any),
+    });
+    Some(merge_types(&Type::Any, values))
+}
+
+/// The element types of a tuple, whether or not it's readonly.
+fn tuple_elements(ty: &Type) -> Option<&[Type]> {
+    match ty.resolved() {
+        Type::Tuple(types) => Some(types),
+        Type::Readonly(ty) => tuple_elements(ty),
+        _ => None,
+    }
+}
+
+impl TypeChecker {
+    /// The values iterating over `ty` gives, reporting it at `span` if it isn't iterable.
+    pub(super) fn check_iterable(&mut self, ty: &Type, span: Span) -> Type {
+        iterated_type(ty).unwrap_or_else(|| {
+            self.error(2488, &[&ty], span);
+            Type::Any
+        })
+    }
+
+    /// Declares the names bound by `const [a, b, ...rest] = value`, where `ty` is the type
+    /// of the value. Tuples give each name its element's type; other iterables give every
+    /// name the type of their values.
+    pub(super) fn declare_array_pattern(
+        &mut self,
+        pattern: &ArrayPattern,
+        ty: &Type,
+        kind: VariableDeclarationKind,
+    ) {
+        let iterated = self.check_iterable(ty, pattern.span);
+        let tuple = tuple_elements(ty).map(<[Type]>::to_vec);
+        let declare = |checker: &mut Self, ident: &BindingIdentifier, ty: Type| {
+            let id = checker.add_symbol(&ident.name, SymbolKind::Variable, ty, ident.span);
+            checker.bind(&ident.name, id, kind.is_var());
+            if kind.is_const() {
+                checker.constants.insert(id);
+            }
+        };
+        for (index, element) in pattern.elements.iter().enumerate() {
+            if let Some(BindingPattern {
+                kind: BindingPatternKind::BindingIdentifier(ident),
+                ..
+            }) = element
+            {
+                let ty = match &tuple {
+                    Some(types) => types.get(index).cloned().unwrap_or(Type::Undefined),
+                    None => iterated.clone(),
+                };
+                declare(self, ident, ty);
+            }
+        }
+        if let Some(rest) = &pattern.rest
+            && let BindingPatternKind::BindingIdentifier(ident) = &rest.argument.kind
+        {
+            let ty = match &tuple {
+                Some(types) => {
+                    Type::Tuple(types.iter().skip(pattern.elements.len()).cloned().collect())
+                }
+                None => Type::Array(Arc::new(iterated)),
+            };
+            declare(self, ident, ty);
+        }
+    }
+
+    /// An array literal, which is a tuple where one is expected and under `as const`, and
+    /// otherwise an array of everything it holds. Spread elements add the values iterating
+    /// over them gives.
+    pub(super) fn check_array_expression(&mut self, array: &ArrayExpression) -> Type {
+        let contextual = self
+            .contextual_type
+            .as_ref()
+            .filter(|(span, _)| *span == array.span)
+            .map(|(_, ty)| ty.clone());
+        let expected_tuple = contextual.as_ref().and_then(tuple_elements);
+        let expected_element = match contextual.as_ref().map(Type::resolved) {
+            Some(Type::Array(element)) => Some((**element).clone()),
+            Some(Type::Readonly(ty)) => match ty.resolved() {
+                Type::Array(element) => Some((**element).clone()),
+                _ => None,
+            },
+            _ => None,
+        };
+
+        // `None` once a spread of unknown length means the result can't be a tuple
+        let mut elements: Option<Vec<Type>> = Some(Vec::new());
+        let mut element_types = Vec::new();
+        for element in &array.elements {
+            let position = elements.as_ref().map_or(usize::MAX, Vec::len);
+            let expected = expected_tuple
+                .and_then(|types| types.get(position))
+                .or(expected_element.as_ref());
+            match element {
+                ArrayExpressionElement::SpreadElement(spread) => {
+                    let ty = self.check_expression(&spread.argument);
+                    let iterated = self.check_iterable(&ty, spread.argument.span());
+                    match (tuple_elements(&ty), &mut elements) {
+                        (Some(types), Some(elements)) => elements.extend(types.iter().cloned()),
+                        _ => elements = None,
+                    }
+                    element_types.push(iterated);
+                }
+                ArrayExpressionElement::Elision(_) => {
+                    if let Some(elements) = &mut elements {
+                        elements.push(Type::Undefined);
+                    }
+                    element_types.push(Type::Undefined);
+                }
+                element => {
+                    let expr = element.to_expression();
+                    let ty = match expected {
+                        Some(expected) => self.check_expression_in_context(expr, expected),
+                        None => self.check_expression(expr),
+                    };
+                    if let Some(elements) = &mut elements {
+                        elements.push(ty.clone());
+                    }
+                    element_types.push(ty);
+                }
+            }
+        }
+
+        match elements {
+            Some(elements) if self.const_context => Type::Readonly(Arc::new(Type::Tuple(elements))),
+            Some(elements) if expected_tuple.is_some() => Type::Tuple(elements),
+            _ if element_types.is_empty() => Type::Array(Arc::new(Type::Any)),
+            _ => {
+                let element = merge_types(&Type::Any, element_types);
+                match self.const_context {
+                    true => Type::Readonly(Arc::new(Type::Array(Arc::new(element)))),
+                    false => Type::Array(Arc::new(element)),
+                }
+            }
+        }
+    }
+}
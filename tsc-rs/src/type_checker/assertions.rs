@@ -0,0 +1,17 @@
+use super::TypeChecker;
+use crate::types::*;
+use oxc_ast::ast::*;
+
+impl TypeChecker {
+    /// `expr as T` and `<T>expr`, which take the value to be a `T`; `as const` instead reads
+    /// it as if every literal in it kept its literal type.
+    pub(super) fn check_type_assertion(&mut self, expr: &Expression, annotation: &TSType) -> Type {
+        if let TSType::TSTypeReference(reference) = annotation
+            && matches!(&reference.type_name, TSTypeName::IdentifierReference(ident) if ident.name == "const")
+        {
+            return self.check_expression_as_const(expr);
+        }
+        self.check_expression(expr);
+        self.check_type(annotation)
+    }
+}
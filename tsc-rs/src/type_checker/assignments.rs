@@ -0,0 +1,160 @@
+use super::TypeChecker;
+use crate::symbols::SymbolId;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_syntax::operator::AssignmentOperator;
+use std::sync::Arc;
+
+/// The members of a declared union that a value of `assigned` could be, so reads after
+/// `x = value` see what was assigned rather than everything `x` may hold.
+fn assignment_reduced_type(declared: &Type, assigned: &Type) -> Type {
+    let Type::Union(members) = declared.resolved() else {
+        return declared.clone();
+    };
+    let assigned_members = match assigned.resolved() {
+        Type::Union(types) => types.clone(),
+        ty => vec![ty.clone()],
+    };
+    let reduced: Vec<Type> = members
+        .iter()
+        .filter(|member| {
+            assigned_members
+                .iter()
+                .any(|assigned| check_type_compatibility(member, assigned))
+        })
+        .cloned()
+        .collect();
+    match reduced.is_empty() {
+        true => declared.clone(),
+        false => union_of(reduced),
+    }
+}
+
+/// The union of `types`, without repeating a member that several of them share, and
+/// listing members of `declared` in the order it does.
+pub(super) fn merge_types(declared: &Type, types: impl IntoIterator<Item = Type>) -> Type {
+    let mut members: Vec<Type> = Vec::new();
+    for ty in types {
+        let flattened = match ty {
+            Type::Union(types) => types,
+            ty => vec![ty],
+        };
+        for member in flattened {
+            if !members.contains(&member) {
+                members.push(member);
+            }
+        }
+    }
+    if let Type::Union(order) = declared.resolved() {
+        members.sort_by_key(|member| {
+            order
+                .iter()
+                .position(|ty| ty == member)
+                .unwrap_or(usize::MAX)
+        });
+    }
+    union_of(members)
+}
+
+impl TypeChecker {
+    pub(super) fn check_assignment_expression(&mut self, assign: &AssignmentExpression) -> Type {
+        if let AssignmentTarget::StaticMemberExpression(member) = &assign.left {
+            return self.check_member_assignment(assign, member);
+        }
+        let AssignmentTarget::AssignmentTargetIdentifier(ident) = &assign.left else {
+            return self.check_expression(&assign.right);
+        };
+        let Some(id) = self.lookup(&ident.name) else {
+            return self.check_expression(&assign.right);
+        };
+        self.references.push((ident.span, id));
+        let declared = self.symbols[id.0].ty.clone();
+        if assign.operator != AssignmentOperator::Assign {
+            // Compound assignments compute a new value from the old, so only the declared
+            // type is known to hold afterwards
+            self.check_expression(&assign.right);
+            self.narrow_by_assignment(id, declared.clone());
+            return declared;
+        }
+
+        let assigned = self.check_expression_in_context(&assign.right, &declared);
+        if self.evolving.contains(&id) {
+            // Evolves to the type of the value assigned, as if it had been declared with it
+            self.narrow_by_assignment(id, assigned.clone());
+            return assigned;
+        }
+        if self.report_unassignable(&declared, &assigned, ident.span) {
+            self.narrow_by_assignment(id, declared);
+        } else {
+            self.narrow_by_assignment(id, assignment_reduced_type(&declared, &assigned));
+        }
+        assigned
+    }
+
+    /// Checks an assignment to `object.property` against the property's write type, which
+    /// its setter may make different from what reading it gives.
+    fn check_member_assignment(
+        &mut self,
+        assign: &AssignmentExpression,
+        member: &StaticMemberExpression,
+    ) -> Type {
+        let object = self.check_member_object(&member.object);
+        let property = match object.resolved() {
+            Type::ObjectType(object) => object.property(&member.property.name).cloned(),
+            Type::Class(class) => class.statics.property(&member.property.name).cloned(),
+            _ => None,
+        };
+        let Some(property) = property else {
+            return self.check_expression(&assign.right);
+        };
+        if property.readonly {
+            self.error(2540, &[&property.name], member.property.span);
+        }
+        if assign.operator != AssignmentOperator::Assign {
+            self.check_expression(&assign.right);
+            return property.ty;
+        }
+        let expected = property.write_type();
+        let assigned = self.check_expression_in_context(&assign.right, expected);
+        if !property.readonly {
+            self.report_unassignable(expected, &assigned, member.span);
+        }
+        assigned
+    }
+
+    /// Adds the types of values pushed into an array declared as `[]` to its element type.
+    /// Returns whether `call` pushed into one, which takes values of any type.
+    pub(super) fn evolve_array(&mut self, call: &CallExpression, arguments: &[Type]) -> bool {
+        let Expression::StaticMemberExpression(member) = &call.callee else {
+            return false;
+        };
+        let Expression::Identifier(ident) = &member.object else {
+            return false;
+        };
+        if !matches!(member.property.name.as_str(), "push" | "unshift") || arguments.is_empty() {
+            return false;
+        }
+        let Some(id) = self.lookup(&ident.name) else {
+            return false;
+        };
+        if !self.evolving.contains(&id) {
+            return false;
+        }
+        let current = self.symbol_type(id);
+        // Until something is pushed, the declared `any[]` says nothing about the elements
+        let elements = match current.resolved() {
+            Type::Array(element) if current != self.symbols[id.0].ty => vec![(**element).clone()],
+            _ => Vec::new(),
+        };
+        let element = merge_types(&Type::Any, elements.into_iter().chain(arguments.to_vec()));
+        self.narrow_by_assignment(id, Type::Array(Arc::new(element)));
+        true
+    }
+
+    /// Narrows `id` to `ty` from here on, replacing any narrowing made before.
+    fn narrow_by_assignment(&mut self, id: SymbolId, ty: Type) {
+        if self.symbol_type(id) != ty {
+            self.narrowed.push((id, ty));
+        }
+    }
+}
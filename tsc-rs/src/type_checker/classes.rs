@@ -0,0 +1,355 @@
+use super::functions::lacks_construct_signatures;
+use super::objects::well_known_symbol_name;
+use super::{TypeChecker, literal_widened};
+use crate::builtins::global_type;
+use crate::symbols::SymbolKind;
+use crate::types::ClassType;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+use std::sync::Arc;
+
+impl TypeChecker {
+    /// Makes the instance type of every top-level class visible by name before any class
+    /// is checked. Its members are filled in when the class is, and references before
+    /// then share them from that point on.
+    pub(super) fn declare_class_types(&mut self, program: &Program) {
+        for stmt in &program.body {
+            let class = match stmt {
+                Statement::ClassDeclaration(class) => &**class,
+                Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                    Some(Declaration::ClassDeclaration(class)) => &**class,
+                    _ => continue,
+                },
+                Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+                    ExportDefaultDeclarationKind::ClassDeclaration(class) => &**class,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            if let Some(ident) = &class.id
+                && !self.type_aliases.contains_key(ident.name.as_str())
+            {
+                self.type_aliases.insert(
+                    ident.name.to_string(),
+                    (class.span, Arc::new(AliasType::new(ident.name.as_str()))),
+                );
+            }
+        }
+    }
+
+    pub(super) fn check_class_declaration(&mut self, class: &Class) {
+        let ty = self.check_class(class);
+        if let Some(ident) = &class.id {
+            if let Type::Class(class_type) = &ty {
+                let instance = Type::ObjectType(Arc::new(class_type.instance.clone()));
+                match self.type_aliases.get(ident.name.as_str()) {
+                    Some((span, handle)) if *span == class.span => handle.resolve(instance),
+                    _ => {
+                        let handle = Arc::new(AliasType::new(ident.name.as_str()));
+                        handle.resolve(instance);
+                        self.type_aliases
+                            .insert(ident.name.to_string(), (class.span, handle));
+                    }
+                }
+            }
+            self.declare_symbol(&ident.name, SymbolKind::Class, ty, ident.span);
+        }
+    }
+
+    /// The constructor type of a class, checking its members along the way.
+    pub(super) fn check_class(&mut self, class: &Class) -> Type {
+        let mut class_type = ClassType {
+            name: class
+                .id
+                .as_ref()
+                .map_or("(Anonymous class)".to_string(), |id| id.name.to_string()),
+            params: Vec::new(),
+            instance: ObjectType::default(),
+            statics: ObjectType::default(),
+        };
+        let base = match class
+            .super_class
+            .as_ref()
+            .map(|sc| self.check_expression(sc))
+        {
+            Some(Type::Class(base)) => Some(base),
+            _ => None,
+        };
+        self.check_override_modifiers(class, &class_type.name, base.as_deref());
+        self.check_property_initialization(class);
+        for element in &class.body.body {
+            match element {
+                ClassElement::PropertyDefinition(prop) => {
+                    let Some(name) = prop.key.static_name() else {
+                        continue;
+                    };
+                    let property = Property {
+                        optional: prop.optional,
+                        readonly: prop.readonly,
+                        ..Property::new(name, self.check_property_definition(prop))
+                    };
+                    members(&mut class_type, prop.r#static).push(property);
+                }
+                ClassElement::MethodDefinition(method) => {
+                    self.check_method_definition(&mut class_type, method)
+                }
+                _ => {}
+            }
+        }
+        // Members the class doesn't override are inherited
+        if let Some(base) = base {
+            if class_type.params.is_empty() {
+                class_type.params = base.params.clone();
+            }
+            inherit(&mut class_type.instance, &base.instance);
+            inherit(&mut class_type.statics, &base.statics);
+        }
+        let class_type = Arc::new(class_type);
+        self.check_class_decorators(class, &class_type);
+        Type::Class(class_type)
+    }
+
+    /// Reports `override` on members the base class doesn't have, and under
+    /// `noImplicitOverride`, members that override one without saying so.
+    fn check_override_modifiers(&mut self, class: &Class, name: &str, base: Option<&ClassType>) {
+        for element in &class.body.body {
+            let (key, is_static, is_override) = match element {
+                ClassElement::PropertyDefinition(prop) => {
+                    (&prop.key, prop.r#static, prop.r#override)
+                }
+                ClassElement::MethodDefinition(method)
+                    if method.kind != MethodDefinitionKind::Constructor =>
+                {
+                    (&method.key, method.r#static, method.r#override)
+                }
+                _ => continue,
+            };
+            let Some(member) = key.static_name() else {
+                continue;
+            };
+            let (code, class_name) = match (class.super_class.as_ref(), base) {
+                (None, _) if is_override => (4112, name),
+                // Without a known base class there's nothing to compare with
+                (_, None) => continue,
+                (_, Some(base)) => {
+                    let members = match is_static {
+                        true => &base.statics,
+                        false => &base.instance,
+                    };
+                    match (members.property(&member).is_some(), is_override) {
+                        (false, true) => (4113, &*base.name),
+                        (true, false) if self.no_implicit_override => (4114, &*base.name),
+                        _ => continue,
+                    }
+                }
+            };
+            self.error(code, &[&class_name], key.span());
+        }
+    }
+
+    /// Reports instance properties whose type doesn't allow `undefined` but that are
+    /// neither initialized nor assigned in the constructor, under
+    /// strictPropertyInitialization.
+    ///
+    /// Only assignments to `this.x` directly in the constructor body count; one in a
+    /// branch or a method the constructor calls needs a `!` on the property.
+    fn check_property_initialization(&mut self, class: &Class) {
+        let strictness = self.strictness;
+        if !strictness.strict_property_initialization
+            || !strictness.strict_null_checks
+            || class.declare
+        {
+            return;
+        }
+        let constructor = class.body.body.iter().find_map(|element| match element {
+            ClassElement::MethodDefinition(method)
+                if method.kind == MethodDefinitionKind::Constructor =>
+            {
+                method.value.body.as_ref()
+            }
+            _ => None,
+        });
+        let assigned = |name: &str| {
+            constructor.is_some_and(|body| {
+                body.statements.iter().any(|stmt| {
+                    let Statement::ExpressionStatement(stmt) = stmt else {
+                        return false;
+                    };
+                    let Expression::AssignmentExpression(assignment) = &stmt.expression else {
+                        return false;
+                    };
+                    matches!(&assignment.left, AssignmentTarget::StaticMemberExpression(member)
+                        if matches!(member.object, Expression::ThisExpression(_))
+                            && member.property.name == name)
+                })
+            })
+        };
+        for element in &class.body.body {
+            let ClassElement::PropertyDefinition(prop) = element else {
+                continue;
+            };
+            if prop.r#static
+                || prop.value.is_some()
+                || prop.optional
+                || prop.definite
+                || prop.declare
+                || prop.r#type == PropertyDefinitionType::TSAbstractPropertyDefinition
+            {
+                continue;
+            }
+            let (Some(ann), Some(name)) = (&prop.type_annotation, prop.key.static_name()) else {
+                continue;
+            };
+            if allows_undefined(&self.check_type(&ann.type_annotation)) || assigned(&name) {
+                continue;
+            }
+            self.error(2564, &[&name], prop.key.span());
+        }
+    }
+
+    fn check_property_definition(&mut self, prop: &PropertyDefinition) -> Type {
+        let value = prop.value.as_ref().map(|v| (v, self.check_expression(v)));
+        let Some(ann) = &prop.type_annotation else {
+            return value.map_or(Type::Any, |(_, ty)| ty);
+        };
+        let declared = self.optional_type(self.check_type(&ann.type_annotation), prop.optional);
+        if let Some((value, ty)) = value {
+            self.report_unassignable(&declared, &ty, value.span());
+        }
+        declared
+    }
+
+    fn check_method_definition(&mut self, class_type: &mut ClassType, method: &MethodDefinition) {
+        let ty = self.check_function(&method.value);
+        let Type::Function {
+            params,
+            return_type,
+            ..
+        } = ty.clone()
+        else {
+            return;
+        };
+        if method.kind == MethodDefinitionKind::Constructor {
+            class_type.params = params;
+            // `constructor(private x: number)` also declares an instance property
+            for param in &method.value.params.items {
+                if let BindingPatternKind::BindingIdentifier(ident) = &param.pattern.kind
+                    && (param.accessibility.is_some() || param.readonly)
+                {
+                    let ty = self.parameter_type(&param.pattern).unwrap_or(Type::Any);
+                    class_type.instance.properties.push(Property {
+                        readonly: param.readonly,
+                        ..Property::new(ident.name.as_str(), ty)
+                    });
+                }
+            }
+            return;
+        }
+
+        let Some(name) = well_known_symbol_name(&method.key)
+            .or_else(|| method.key.static_name().map(|name| name.into_owned()))
+        else {
+            return;
+        };
+        let members = members(class_type, method.r#static);
+        match method.kind {
+            MethodDefinitionKind::Get => {
+                return add_accessor(members, &name, (*return_type).clone(), true);
+            }
+            MethodDefinitionKind::Set => {
+                let ty = params.into_iter().next().unwrap_or(Type::Any);
+                return add_accessor(members, &name, ty, false);
+            }
+            _ => {}
+        }
+        if members.iter().any(|p| p.name == name) {
+            return;
+        }
+        members.push(Property {
+            optional: method.optional,
+            ..Property::new(name, ty)
+        });
+    }
+
+    pub(super) fn check_new_expression(&mut self, new: &NewExpression) -> Type {
+        // The lib's `Promise` constructor makes a promise of what its executor resolves with
+        if let Expression::Identifier(ident) = &new.callee
+            && ident.name == "Promise"
+            && self.lookup(&ident.name).is_none()
+        {
+            let value = new
+                .type_parameters
+                .as_ref()
+                .and_then(|args| args.params.first())
+                .map_or(Type::Any, |value| self.check_type(value));
+            let executor = Type::function(
+                vec![
+                    Type::function(vec![value.clone()], Type::Void),
+                    Type::function(vec![Type::Any], Type::Void),
+                ],
+                Type::Void,
+            );
+            for arg in &new.arguments {
+                if let Some(expr) = arg.as_expression() {
+                    self.check_expression_in_context(expr, &executor);
+                }
+            }
+            return Type::promise(value);
+        }
+        let callee = self.check_expression(&new.callee);
+        if !self.report_unknown_operand(&new.callee, &callee) && lacks_construct_signatures(&callee)
+        {
+            self.error(
+                2351,
+                &[&format!(
+                    " Type '{}' has no construct signatures.",
+                    literal_widened(&callee)
+                )],
+                new.callee.span(),
+            );
+        }
+        // Constructor parameters aren't modeled, so arguments are taken as if by `any`
+        for arg in &new.arguments {
+            if let Some(expr) = arg.as_expression() {
+                self.check_expression_in_context(expr, &Type::Any);
+            }
+        }
+        // The lib's own constructors make instances of its interface of the same name
+        if let Expression::Identifier(ident) = &new.callee
+            && self.lookup(&ident.name).is_none()
+            && let Some(instance) = global_type(&ident.name)
+        {
+            return instance;
+        }
+        match callee {
+            Type::Class(class) => Type::ObjectType(Arc::new(class.instance.clone())),
+            _ => Type::Any,
+        }
+    }
+}
+
+/// Whether a property of type `ty` may be left `undefined`.
+fn allows_undefined(ty: &Type) -> bool {
+    match ty.resolved() {
+        Type::Any | Type::Unknown | Type::Undefined | Type::Void => true,
+        Type::Union(types) => types.iter().any(allows_undefined),
+        _ => false,
+    }
+}
+
+fn members(class_type: &mut ClassType, is_static: bool) -> &mut Vec<Property> {
+    if is_static {
+        &mut class_type.statics.properties
+    } else {
+        &mut class_type.instance.properties
+    }
+}
+
+fn inherit(members: &mut ObjectType, base: &ObjectType) {
+    for property in &base.properties {
+        if members.property(&property.name).is_none() {
+            members.properties.push(property.clone());
+        }
+    }
+}
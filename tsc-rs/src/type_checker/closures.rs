@@ -0,0 +1,67 @@
+use super::TypeChecker;
+use crate::intern::Atom;
+use crate::symbols::SymbolId;
+use crate::types::Type;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use oxc_span::Span;
+
+/// Every identifier the program assigns to, by name and position.
+#[derive(Default)]
+struct Assignments(Vec<(Atom, Span)>);
+
+impl<'a> Visit<'a> for Assignments {
+    fn visit_simple_assignment_target(&mut self, target: &SimpleAssignmentTarget<'a>) {
+        if let SimpleAssignmentTarget::AssignmentTargetIdentifier(ident) = target {
+            self.0.push((Atom::new(&ident.name), ident.span));
+        }
+        walk::walk_simple_assignment_target(self, target);
+    }
+
+    fn visit_assignment_target_property_identifier(
+        &mut self,
+        property: &AssignmentTargetPropertyIdentifier<'a>,
+    ) {
+        self.0
+            .push((Atom::new(&property.binding.name), property.binding.span));
+        walk::walk_assignment_target_property_identifier(self, property);
+    }
+}
+
+impl TypeChecker {
+    pub(super) fn collect_assignments(&mut self, program: &Program) {
+        let mut assignments = Assignments::default();
+        assignments.visit_program(program);
+        self.assignments = assignments.0;
+    }
+
+    /// Starts checking the body of the function at `span`, dropping narrowings that may no
+    /// longer hold by the time it's called. Returns what [`Self::exit_closure`] restores.
+    ///
+    /// Narrowings of constants always hold. Those of other variables hold in function
+    /// expressions the variable isn't assigned in or after, but never in hoisted function
+    /// declarations, which can be called before the narrowing happens.
+    pub(super) fn enter_closure(&mut self, span: Span, hoisted: bool) -> Vec<(SymbolId, Type)> {
+        let retained: Vec<(SymbolId, Type)> = self
+            .narrowed
+            .iter()
+            .filter(|(id, _)| {
+                self.constants.contains(id)
+                    || (!hoisted && !self.is_assigned_after(&self.symbols[id.0].name, span.start))
+            })
+            .cloned()
+            .collect();
+        std::mem::replace(&mut self.narrowed, retained)
+    }
+
+    pub(super) fn exit_closure(&mut self, narrowed: Vec<(SymbolId, Type)>) {
+        self.narrowed = narrowed;
+    }
+
+    fn is_assigned_after(&self, name: &str, offset: u32) -> bool {
+        self.assignments
+            .iter()
+            .any(|(assigned, span)| assigned == name && span.start > offset)
+    }
+}
@@ -0,0 +1,209 @@
+use super::TypeChecker;
+use crate::types::ClassType;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+use std::sync::Arc;
+
+/// What a decorator is applied to, which decides the arguments it's called with.
+#[derive(Debug, Clone, Copy)]
+enum DecoratorKind {
+    Class,
+    Method,
+    Property,
+    Parameter,
+}
+
+impl DecoratorKind {
+    /// The code of the message reporting a decorator of this kind that can't be called.
+    fn unresolved_signature_code(self) -> u32 {
+        match self {
+            Self::Class => 1238,
+            Self::Parameter => 1239,
+            Self::Property => 1240,
+            Self::Method => 1241,
+        }
+    }
+}
+
+impl TypeChecker {
+    /// Checks the decorators on a class and its members once the class type is known.
+    ///
+    /// Decorators are called as stage 3 decorators, `(value, context)`, unless
+    /// `experimentalDecorators` is set, in which case they're called with the legacy
+    /// `(target, key, descriptor)` arguments and parameters may be decorated too.
+    pub(super) fn check_class_decorators(&mut self, class: &Class, class_type: &Arc<ClassType>) {
+        let constructor = Type::Class(class_type.clone());
+        let arguments = match self.experimental_decorators {
+            true => vec![constructor.clone()],
+            false => vec![constructor.clone(), Type::Any],
+        };
+        for decorator in &class.decorators {
+            self.check_decorator(
+                decorator,
+                DecoratorKind::Class,
+                &arguments,
+                Some(&constructor),
+            );
+        }
+
+        for element in &class.body.body {
+            match element {
+                ClassElement::MethodDefinition(method) => {
+                    self.check_method_decorators(class_type, method)
+                }
+                ClassElement::PropertyDefinition(prop) => self.check_property_decorators(
+                    class_type,
+                    &prop.decorators,
+                    &prop.key,
+                    prop.r#static,
+                ),
+                ClassElement::AccessorProperty(prop) => self.check_property_decorators(
+                    class_type,
+                    &prop.decorators,
+                    &prop.key,
+                    prop.r#static,
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    fn check_method_decorators(&mut self, class_type: &Arc<ClassType>, method: &MethodDefinition) {
+        let key = property_key(&method.key);
+        let target = legacy_target(class_type, method.r#static);
+        for (index, param) in method.value.params.items.iter().enumerate() {
+            for decorator in &param.decorators {
+                if !self.experimental_decorators {
+                    self.error(1206, &[], decorator.span);
+                    continue;
+                }
+                let arguments = match method.kind {
+                    MethodDefinitionKind::Constructor => vec![
+                        Type::Class(class_type.clone()),
+                        Type::Undefined,
+                        Type::NumberLiteral(index as f64),
+                    ],
+                    _ => vec![
+                        target.clone(),
+                        key.clone(),
+                        Type::NumberLiteral(index as f64),
+                    ],
+                };
+                self.check_decorator(decorator, DecoratorKind::Parameter, &arguments, None);
+            }
+        }
+        if method.decorators.is_empty() {
+            return;
+        }
+
+        let value = method.key.static_name().and_then(|name| {
+            let members = match method.r#static {
+                true => &class_type.statics,
+                false => &class_type.instance,
+            };
+            let ty = members.property(&name)?.ty.clone();
+            Some(match method.kind {
+                MethodDefinitionKind::Get => Type::function(Vec::new(), ty),
+                MethodDefinitionKind::Set => Type::function(vec![ty], Type::Void),
+                _ => ty,
+            })
+        });
+        let value = value.unwrap_or(Type::Any);
+        let arguments = match self.experimental_decorators {
+            true => vec![target, key, Type::Any],
+            false => vec![value.clone(), Type::Any],
+        };
+        // Legacy decorators may return a replacement property descriptor
+        let replacement = (!self.experimental_decorators).then_some(&value);
+        for decorator in &method.decorators {
+            self.check_decorator(decorator, DecoratorKind::Method, &arguments, replacement);
+        }
+    }
+
+    fn check_property_decorators(
+        &mut self,
+        class_type: &Arc<ClassType>,
+        decorators: &[Decorator],
+        key: &PropertyKey,
+        is_static: bool,
+    ) {
+        let arguments = match self.experimental_decorators {
+            true => vec![legacy_target(class_type, is_static), property_key(key)],
+            // A field has no value until it is initialized
+            false => vec![Type::Undefined, Type::Any],
+        };
+        for decorator in decorators {
+            self.check_decorator(decorator, DecoratorKind::Property, &arguments, None);
+        }
+    }
+
+    /// Checks that `decorator` can be called with `arguments`, and that what it returns can
+    /// replace `value` when decorators of its kind may do so.
+    fn check_decorator(
+        &mut self,
+        decorator: &Decorator,
+        kind: DecoratorKind,
+        arguments: &[Type],
+        value: Option<&Type>,
+    ) {
+        let callee = match self.check_expression(&decorator.expression).resolved() {
+            Type::Generic { type_params, body } => {
+                self.instantiate_call(type_params, body, None, arguments, None)
+            }
+            callee => callee.clone(),
+        };
+        let (params, return_type) = match callee {
+            Type::Any => return,
+            Type::Function {
+                params,
+                return_type,
+                ..
+            } => (params, return_type),
+            _ => {
+                self.error(2349, &[&""], decorator.expression.span());
+                return;
+            }
+        };
+        let callable = params.len() <= arguments.len()
+            && params
+                .iter()
+                .zip(arguments)
+                .all(|(param, argument)| self.is_assignable(param, argument));
+        if !callable {
+            self.error(
+                kind.unresolved_signature_code(),
+                &[],
+                decorator.expression.span(),
+            );
+            return;
+        }
+        if let Some(value) = value
+            && !matches!(
+                return_type.resolved(),
+                Type::Void | Type::Undefined | Type::Any
+            )
+            && !self.is_assignable(value, &return_type)
+        {
+            self.error(
+                1271,
+                &[&return_type, &format!("void | {}", value)],
+                decorator.expression.span(),
+            );
+        }
+    }
+}
+
+/// The `target` a legacy member decorator receives: the prototype, or the class when static.
+fn legacy_target(class_type: &Arc<ClassType>, is_static: bool) -> Type {
+    match is_static {
+        true => Type::Class(class_type.clone()),
+        false => Type::ObjectType(Arc::new(class_type.instance.clone())),
+    }
+}
+
+/// The `key` a legacy member decorator receives.
+fn property_key(key: &PropertyKey) -> Type {
+    key.static_name()
+        .map_or(Type::Any, |name| Type::StringLiteral(name.to_string()))
+}
@@ -0,0 +1,167 @@
+use super::TypeChecker;
+use crate::enum_values::{EnumValue, member_values};
+use crate::symbols::SymbolKind;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+use std::sync::Arc;
+
+impl TypeChecker {
+    /// Declares an enum as both a value, the object holding its members, and a type, the
+    /// union of its members' values. Declaring it again, or a namespace of the same name,
+    /// adds to the members.
+    pub(super) fn check_enum_declaration(&mut self, decl: &TSEnumDeclaration) {
+        let name = decl.id.name.as_str();
+        let merged = self.declared_here(name).filter(|&id| {
+            matches!(
+                self.symbols[id.0].kind,
+                SymbolKind::Enum | SymbolKind::Namespace
+            )
+        });
+        let earlier_enum = merged.filter(|&id| self.symbols[id.0].kind == SymbolKind::Enum);
+        if let Some(id) = earlier_enum {
+            if self.const_enums.contains(&id) != decl.r#const {
+                self.error(2473, &[], decl.id.span);
+            }
+            if decl
+                .members
+                .first()
+                .is_some_and(|member| member.initializer.is_none())
+                && !self.enums_counting_from_zero.insert(id)
+            {
+                self.error(2432, &[], decl.members[0].span);
+            }
+        }
+
+        let values = member_values(decl);
+        // Earlier declarations' members stay part of the enum's type
+        let earlier_alias = earlier_enum
+            .and_then(|_| self.type_aliases.get(name))
+            .map(|(_, alias)| alias.clone());
+        let mut member_types: Vec<Type> = match earlier_alias.as_ref().map(|a| a.target()) {
+            Some(Type::Union(types)) => types.clone(),
+            Some(ty) => vec![ty.clone()],
+            None => Vec::new(),
+        };
+        let mut reverse_mapped = false;
+        for (member, (_, value)) in decl.members.iter().zip(&values) {
+            // Ambient enums leave members without an initializer to be filled in at runtime
+            let value = match decl.declare && !decl.r#const && member.initializer.is_none() {
+                true => &None,
+                false => value,
+            };
+            let ty = match value {
+                Some(EnumValue::Number(n)) => {
+                    reverse_mapped = true;
+                    Type::NumberLiteral(*n)
+                }
+                Some(EnumValue::String(s)) => Type::StringLiteral(s.clone()),
+                None => {
+                    if let Some(init) = &member.initializer {
+                        if decl.r#const {
+                            self.error(2474, &[], init.span());
+                        } else if decl.declare {
+                            self.error(1066, &[], init.span());
+                        } else {
+                            self.check_expression(init);
+                        }
+                    }
+                    reverse_mapped = true;
+                    Type::Number
+                }
+            };
+            if !member_types.contains(&ty) {
+                member_types.push(ty);
+            }
+        }
+
+        let alias = Arc::new(AliasType::new(name));
+        alias.resolve(match member_types.is_empty() {
+            true => Type::Number,
+            false => union_of(member_types),
+        });
+        self.type_aliases
+            .insert(name.to_string(), (decl.span, alias.clone()));
+        let mut object = match merged.map(|id| self.symbols[id.0].ty.resolved().clone()) {
+            Some(Type::ObjectType(object)) => (*object).clone(),
+            _ => ObjectType::default(),
+        };
+        if let Some(earlier) = &earlier_alias {
+            for property in &mut object.properties {
+                if matches!(&property.ty, Type::Alias(ty) if Arc::ptr_eq(ty, earlier)) {
+                    property.ty = Type::Alias(alias.clone());
+                }
+            }
+        }
+        for (member, _) in &values {
+            let mut property = Property::new(member.as_str(), Type::Alias(alias.clone()));
+            property.readonly = true;
+            object.properties.retain(|p| p.name != property.name);
+            object.properties.push(property);
+        }
+        // Numeric members can be looked up by value, except in const enums, which have no
+        // object at runtime
+        if reverse_mapped && !decl.r#const {
+            object.number_index = Some(Type::String);
+        }
+        let ty = Type::ObjectType(Arc::new(object));
+        let id = match merged {
+            Some(id) => {
+                let symbol = &mut self.symbols[id.0];
+                symbol.kind = SymbolKind::Enum;
+                symbol.ty = ty;
+                id
+            }
+            None => self.declare_symbol(name, SymbolKind::Enum, ty, decl.id.span),
+        };
+        if decl.r#const && earlier_enum.is_none() {
+            self.const_enums.insert(id);
+        }
+        if earlier_enum.is_none()
+            && decl
+                .members
+                .first()
+                .is_some_and(|member| member.initializer.is_none())
+        {
+            self.enums_counting_from_zero.insert(id);
+        }
+    }
+
+    /// Checks the object of a member access, the one place besides type queries a const
+    /// enum may be named.
+    pub(super) fn check_member_object(&mut self, object: &Expression) -> Type {
+        if let Expression::Identifier(ident) = object
+            && let Some(id) = self.lookup(&ident.name)
+            && self.const_enums.contains(&id)
+        {
+            self.references.push((ident.span, id));
+            let ty = self.symbol_type(id);
+            self.node_types.push((ident.span, ty.clone()));
+            return ty;
+        }
+        self.check_expression(object)
+    }
+
+    /// Reports a const enum named anywhere [`Self::check_member_object`] doesn't allow.
+    pub(super) fn check_const_enum_reference(&mut self, ident: &IdentifierReference) {
+        if let Some(id) = self.lookup(&ident.name)
+            && self.const_enums.contains(&id)
+        {
+            self.error(2475, &[], ident.span);
+        }
+    }
+
+    /// Reports `E[key]` on a const enum unless `key` is a string literal, since the member
+    /// must be known to be inlined. Returns whether the access is allowed.
+    pub(super) fn check_const_enum_index(&mut self, member: &ComputedMemberExpression) -> bool {
+        if let Expression::Identifier(ident) = &member.object
+            && let Some(id) = self.lookup(&ident.name)
+            && self.const_enums.contains(&id)
+            && !matches!(member.expression, Expression::StringLiteral(_))
+        {
+            self.error(2476, &[], member.expression.span());
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,50 @@
+use super::TypeChecker;
+use crate::builtins::error_type;
+use crate::symbols::SymbolKind;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+
+impl TypeChecker {
+    pub(super) fn check_throw_statement(&mut self, stmt: &ThrowStatement) {
+        let thrown = self.check_expression(&stmt.argument);
+        // `any` and `unknown` may well hold an error, as a rethrown `catch` variable does
+        if self.only_throw_error
+            && !matches!(thrown.resolved(), Type::Any | Type::Unknown)
+            && !self.is_assignable(&error_type(), &thrown)
+        {
+            self.error(100002, &[], stmt.argument.span());
+        }
+    }
+
+    pub(super) fn check_try_statement(&mut self, stmt: &TryStatement) {
+        // The handler may run after only part of the block, and the block may not finish,
+        // so what either assigns is merged with what came before
+        let mut exits = vec![Vec::new()];
+        self.enter_scope(stmt.block.span, false);
+        exits.push(self.check_branch(None, &stmt.block.body));
+        self.exit_scope();
+        if let Some(handler) = &stmt.handler {
+            self.enter_scope(handler.span, false);
+            if let Some(param) = &handler.param
+                && let BindingPatternKind::BindingIdentifier(ident) = &param.pattern.kind
+            {
+                // Anything can be thrown, so the caught value is only known when annotated
+                let ty = match &param.pattern.type_annotation {
+                    Some(annotation) => self.check_type(&annotation.type_annotation),
+                    None if self.strictness.use_unknown_in_catch_variables => Type::Unknown,
+                    None => Type::Any,
+                };
+                self.declare_symbol(&ident.name, SymbolKind::Variable, ty, ident.span);
+            }
+            exits.push(self.check_branch(None, &handler.body.body));
+            self.exit_scope();
+        }
+        self.merge_branches(exits);
+        if let Some(finalizer) = &stmt.finalizer {
+            self.enter_scope(finalizer.span, false);
+            self.check_statements(&finalizer.body);
+            self.exit_scope();
+        }
+    }
+}
@@ -0,0 +1,85 @@
+use super::TypeChecker;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_syntax::scope::ScopeFlags;
+
+/// Whether control can reach the end of `stmts`, rather than always leaving through
+/// `break`, `continue`, `return`, or `throw` first.
+pub(super) fn can_complete_normally(stmts: &[Statement]) -> bool {
+    stmts.iter().all(statement_completes_normally)
+}
+
+pub(super) fn statement_completes_normally(stmt: &Statement) -> bool {
+    match stmt {
+        Statement::BreakStatement(_)
+        | Statement::ContinueStatement(_)
+        | Statement::ReturnStatement(_)
+        | Statement::ThrowStatement(_) => false,
+        Statement::BlockStatement(block) => can_complete_normally(&block.body),
+        Statement::IfStatement(stmt) => {
+            statement_completes_normally(&stmt.consequent)
+                || stmt
+                    .alternate
+                    .as_ref()
+                    .is_none_or(statement_completes_normally)
+        }
+        Statement::TryStatement(stmt) => {
+            let finalizer_completes = stmt
+                .finalizer
+                .as_ref()
+                .is_none_or(|finalizer| can_complete_normally(&finalizer.body));
+            let body_completes = can_complete_normally(&stmt.block.body)
+                || stmt
+                    .handler
+                    .as_ref()
+                    .is_some_and(|handler| can_complete_normally(&handler.body.body));
+            finalizer_completes && body_completes
+        }
+        // Loops and nested switches may be left by a `break` of their own
+        _ => true,
+    }
+}
+
+/// Whether a function body made of `stmts` has a `return` anywhere, not counting those
+/// of functions nested in it.
+pub(super) fn contains_return(stmts: &[Statement]) -> bool {
+    #[derive(Default)]
+    struct Returns(bool);
+
+    impl<'a> Visit<'a> for Returns {
+        fn visit_return_statement(&mut self, _: &ReturnStatement<'a>) {
+            self.0 = true;
+        }
+
+        fn visit_function(&mut self, _: &Function<'a>, _: ScopeFlags) {}
+
+        fn visit_arrow_function_expression(&mut self, _: &ArrowFunctionExpression<'a>) {}
+
+        fn visit_class(&mut self, _: &Class<'a>) {}
+    }
+
+    let mut returns = Returns::default();
+    for stmt in stmts {
+        returns.visit_statement(stmt);
+    }
+    returns.0
+}
+
+impl TypeChecker {
+    /// Reports cases whose statements run on into the next case under
+    /// `noFallthroughCasesInSwitch`. Empty cases share the next case's statements and are
+    /// allowed.
+    pub(super) fn check_switch_fallthrough(&mut self, stmt: &SwitchStatement) {
+        if !self.no_fallthrough_cases_in_switch {
+            return;
+        }
+        let Some((_, cases)) = stmt.cases.split_last() else {
+            return;
+        };
+        for case in cases {
+            if !case.consequent.is_empty() && can_complete_normally(&case.consequent) {
+                self.error(7029, &[], case.span);
+            }
+        }
+    }
+}
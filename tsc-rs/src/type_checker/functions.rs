@@ -0,0 +1,564 @@
+use super::flow::{can_complete_normally, contains_return};
+use super::generics::{instantiate, is_const_argument};
+use super::{TypeChecker, literal_widened};
+use crate::diagnostics::Message;
+use crate::symbols::SymbolKind;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use std::sync::Arc;
+
+impl TypeChecker {
+    /// Declares each parameter and returns their types, ending with the array a `...rest`
+    /// parameter collects.
+    ///
+    /// Unannotated parameters take their type from `contextual`, the parameters of the
+    /// function type expected where this one is written and whether it ends with a rest
+    /// parameter, and are `any` otherwise.
+    pub(super) fn declare_parameters(
+        &mut self,
+        params: &FormalParameters,
+        contextual: &(Vec<Type>, bool),
+    ) -> Vec<Type> {
+        let (contextual, contextual_rest) = contextual;
+        let mut types: Vec<Type> = params
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, param)| {
+                let contextual = Type::parameter_at(contextual, *contextual_rest, index);
+                let ty = self
+                    .parameter_type(&param.pattern)
+                    .or_else(|| contextual.clone())
+                    .unwrap_or(Type::Any);
+                if let BindingPatternKind::BindingIdentifier(ident) = &param.pattern.kind {
+                    if param.pattern.type_annotation.is_none() && contextual.is_none() {
+                        self.report_implicit_any(
+                            Message::new(7006, &[&ident.name, &"any"]),
+                            ident.span,
+                        );
+                    }
+                    self.declare_symbol(&ident.name, SymbolKind::Parameter, ty.clone(), ident.span);
+                }
+                ty
+            })
+            .collect();
+        if let Some(rest) = &params.rest {
+            let ty = self.rest_parameter_type(rest);
+            if let BindingPatternKind::BindingIdentifier(ident) = &rest.argument.kind {
+                if rest.argument.type_annotation.is_none() && contextual.is_empty() {
+                    self.report_implicit_any(
+                        Message::new(7019, &[&ident.name, &"any[]"]),
+                        ident.span,
+                    );
+                }
+                self.declare_symbol(&ident.name, SymbolKind::Parameter, ty.clone(), ident.span);
+            }
+            types.push(ty);
+        }
+        types
+    }
+
+    /// Reports a declaration typed `any` only because nothing says otherwise, under
+    /// noImplicitAny.
+    fn report_implicit_any(&mut self, message: Message, span: Span) {
+        if self.strictness.no_implicit_any {
+            self.report(message, span);
+        }
+    }
+
+    /// The array a `...rest` parameter collects the remaining arguments into.
+    pub(super) fn rest_parameter_type(&self, rest: &BindingRestElement) -> Type {
+        rest.argument
+            .type_annotation
+            .as_ref()
+            .map_or(Type::Array(Arc::new(Type::Any)), |ann| {
+                self.check_type(&ann.type_annotation)
+            })
+    }
+
+    /// The types of `params` as a signature lists them, without declaring anything.
+    pub(super) fn signature_parameters(&self, params: &FormalParameters) -> Vec<Type> {
+        let mut types: Vec<Type> = params
+            .items
+            .iter()
+            .map(|param| self.parameter_type(&param.pattern).unwrap_or(Type::Any))
+            .collect();
+        if let Some(rest) = &params.rest {
+            types.push(self.rest_parameter_type(rest));
+        }
+        types
+    }
+
+    /// The type a parameter's annotation gives it, including `undefined` if it's optional.
+    pub(super) fn parameter_type(&self, pattern: &BindingPattern) -> Option<Type> {
+        let ann = pattern.type_annotation.as_ref()?;
+        Some(self.optional_type(self.check_type(&ann.type_annotation), pattern.optional))
+    }
+
+    /// Reports calling `callee`, of type `ty`, if it can't be called.
+    fn report_not_callable(&mut self, callee: &Expression, ty: &Type) {
+        if !lacks_call_signatures(ty) {
+            return;
+        }
+        // Like the lib's `ErrorConstructor`, global constructors may be called without `new`
+        if let (Expression::Identifier(ident), Type::Class(_)) = (callee, ty.resolved())
+            && self.lookup(&ident.name).is_none()
+        {
+            return;
+        }
+        let message = match ty.resolved() {
+            Type::Class(_) => Message::new(2348, &[ty]),
+            _ => Message::new(
+                2349,
+                &[&format!(
+                    " Type '{}' has no call signatures.",
+                    literal_widened(ty)
+                )],
+            ),
+        };
+        self.report(message, callee.span());
+    }
+
+    /// Checks a function body's statements and returns what the function returns.
+    ///
+    /// Returned values are checked against `declared` when there is one; otherwise the
+    /// return type is inferred from them, or `void` when nothing is returned. Values returned
+    /// from an async function are awaited first.
+    fn check_function_body(
+        &mut self,
+        body: &FunctionBody,
+        declared: Option<Type>,
+        is_async: bool,
+    ) -> Type {
+        let mut returned = Vec::new();
+        self.hoist_functions(&body.statements);
+        for stmt in &body.statements {
+            let Statement::ReturnStatement(ret) = stmt else {
+                self.check_statement(stmt);
+                continue;
+            };
+            let Some(arg) = &ret.argument else {
+                continue;
+            };
+            let ty = match &declared {
+                Some(declared) => self.check_expression_in_context(arg, declared),
+                None => self.check_expression(arg),
+            };
+            let ty = match is_async {
+                true => ty.awaited(),
+                false => ty,
+            };
+            if let Some(declared) = &declared {
+                self.report_unassignable(declared, &ty, arg.span());
+            }
+            if !returned.contains(&ty) {
+                returned.push(ty);
+            }
+        }
+        declared.unwrap_or_else(|| match returned.len() {
+            // A body that can only be left by throwing never returns at all
+            0 if !contains_return(&body.statements) && !can_complete_normally(&body.statements) => {
+                Type::Never
+            }
+            0 => Type::Void,
+            _ => union_of(returned).regular(),
+        })
+    }
+
+    /// The type of a function expression or method, checking its body.
+    pub(super) fn check_function(&mut self, func: &Function) -> Type {
+        // Literals in the body aren't part of an enclosing `as const` value
+        let const_context = std::mem::take(&mut self.const_context);
+        // Arrow functions see the `this` around them, but other functions have their own
+        let this_type = std::mem::replace(&mut self.this_type, self.method_this.take());
+        let contextual = self.contextual_parameters(func.span);
+        self.enter_scope(func.span, true);
+        let narrowed = self.enter_closure(func.span, false);
+        let type_params = self.enter_type_parameters(func.type_parameters.as_deref());
+        let params = self.declare_parameters(&func.params, &contextual);
+        let declared = func
+            .return_type
+            .as_ref()
+            .map(|ann| self.check_type(&ann.type_annotation));
+        let return_type = match &func.body {
+            Some(body) if func.r#async => {
+                Type::promise(self.check_function_body(body, declared.map(|ty| ty.awaited()), true))
+            }
+            Some(body) => self.check_function_body(body, declared, false),
+            None => declared.unwrap_or(Type::Any),
+        };
+        self.exit_type_parameters(&type_params);
+        self.exit_closure(narrowed);
+        self.exit_scope();
+        self.const_context = const_context;
+        self.this_type = this_type;
+        Self::generic(
+            type_params,
+            function_type(&func.params, params, return_type),
+        )
+    }
+
+    pub(super) fn check_arrow_function_expression(
+        &mut self,
+        arrow: &ArrowFunctionExpression,
+    ) -> Type {
+        let const_context = std::mem::take(&mut self.const_context);
+        let contextual = self.contextual_parameters(arrow.span);
+        self.enter_scope(arrow.span, true);
+        let narrowed = self.enter_closure(arrow.span, false);
+        let type_params = self.enter_type_parameters(arrow.type_parameters.as_deref());
+        let params = self.declare_parameters(&arrow.params, &contextual);
+        let declared = arrow.return_type.as_ref().map(|ann| match arrow.r#async {
+            true => self.check_type(&ann.type_annotation).awaited(),
+            false => self.check_type(&ann.type_annotation),
+        });
+        let return_type = match arrow.get_expression() {
+            Some(expr) => {
+                let ty = match &declared {
+                    Some(declared) => self.check_expression_in_context(expr, declared),
+                    None => self.check_expression(expr),
+                };
+                let ty = match arrow.r#async {
+                    true => ty.awaited(),
+                    false => ty,
+                };
+                match declared {
+                    Some(declared) => {
+                        self.report_unassignable(&declared, &ty, expr.span());
+                        declared
+                    }
+                    None => ty.regular(),
+                }
+            }
+            None => self.check_function_body(&arrow.body, declared, arrow.r#async),
+        };
+        self.exit_closure(narrowed);
+        self.exit_scope();
+        let return_type = match arrow.r#async {
+            true => Type::promise(return_type),
+            false => return_type,
+        };
+        self.exit_type_parameters(&type_params);
+        self.const_context = const_context;
+        Self::generic(
+            type_params,
+            function_type(&arrow.params, params, return_type),
+        )
+    }
+
+    /// The parameter types of the function type expected for the function at `span`, and
+    /// whether it ends with a rest parameter.
+    fn contextual_parameters(&self, span: Span) -> (Vec<Type>, bool) {
+        match &self.contextual_type {
+            Some((expected_span, expected)) if *expected_span == span => {
+                match expected.resolved() {
+                    Type::Any => any_parameters(),
+                    expected => parameters_of(expected),
+                }
+            }
+            _ => (Vec::new(), false),
+        }
+    }
+
+    pub(super) fn check_call_expression(&mut self, call: &CallExpression) -> Type {
+        let contextual = self
+            .contextual_type
+            .as_ref()
+            .filter(|(span, _)| *span == call.span)
+            .map(|(_, ty)| ty.clone());
+        let callee = self.check_expression(&call.callee);
+        if !self.report_unknown_operand(&call.callee, &callee) {
+            self.report_not_callable(&call.callee, &callee);
+        }
+        let callee = signature_of(&callee);
+        let (declared_params, declared_rest) = parameters_of(&callee);
+        // Callbacks are checked last, so their parameters can be typed by what the other
+        // arguments infer
+        let mut arguments: Vec<Option<Type>> = call
+            .arguments
+            .iter()
+            .enumerate()
+            .map(|(index, arg)| match arg.as_expression() {
+                Some(expr) if is_context_sensitive(expr) => None,
+                Some(expr) if is_const_argument(&callee, index) => {
+                    Some(self.check_expression_as_const(expr))
+                }
+                Some(expr) => Some(
+                    match Type::parameter_at(&declared_params, declared_rest, index) {
+                        Some(expected) => self.check_expression_in_context(expr, &expected),
+                        None => self.check_expression(expr),
+                    },
+                ),
+                None => Some(Type::Any),
+            })
+            .collect();
+        let expected = match &callee {
+            // Type parameters nothing is known about yet stand for `any`
+            Type::Generic { type_params, body } => {
+                let known: Vec<_> = self
+                    .infer_type_arguments(
+                        type_params,
+                        body,
+                        call.type_parameters.as_deref(),
+                        &arguments,
+                        contextual.as_ref(),
+                    )
+                    .into_iter()
+                    .map(|(param, ty)| (param, ty.unwrap_or(Type::Any)))
+                    .collect();
+                instantiate(body, &known)
+            }
+            callee => callee.clone(),
+        };
+        let (expected_params, expected_rest) = parameters_of(&expected);
+        for (index, arg) in call.arguments.iter().enumerate() {
+            if let Some(expr) = arg.as_expression()
+                && arguments[index].is_none()
+            {
+                arguments[index] = Some(
+                    match Type::parameter_at(&expected_params, expected_rest, index) {
+                        Some(expected) => self.check_expression_in_context(expr, &expected),
+                        None => self.check_expression(expr),
+                    },
+                );
+            }
+        }
+        let arguments: Vec<Type> = arguments
+            .into_iter()
+            .map(|arg| arg.unwrap_or(Type::Any))
+            .collect();
+        let evolved = self.evolve_array(call, &arguments);
+        let (callee, type_arguments) = match callee {
+            Type::Generic { type_params, body } => {
+                let type_arguments = self.call_type_arguments(
+                    &type_params,
+                    &body,
+                    call.type_parameters.as_deref(),
+                    &arguments,
+                    contextual.as_ref(),
+                );
+                let instantiated = instantiate(&body, &type_arguments);
+                let type_arguments = type_arguments.into_iter().map(|(_, ty)| ty).collect();
+                (instantiated, Some(type_arguments))
+            }
+            callee => (callee, None),
+        };
+        if !evolved {
+            // Generic calls name the signature they were instantiated to, since the
+            // parameter types come from what was inferred
+            let instantiation = type_arguments.map(|type_arguments: Vec<Type>| {
+                display_signature(callee_name(&call.callee), &type_arguments, &callee)
+            });
+            self.check_arguments(call, &callee, &arguments, instantiation.as_deref());
+        }
+        match callee {
+            Type::Function { return_type, .. } if self.is_function_bind(&call.callee) => {
+                bound_function(&return_type, call.arguments.len())
+            }
+            Type::Function { return_type, .. } => (*return_type).clone(),
+            _ => Type::Any,
+        }
+    }
+
+    /// Whether `callee` is a function's own `bind` method, typed under strictBindCallApply.
+    fn is_function_bind(&self, callee: &Expression) -> bool {
+        let Expression::StaticMemberExpression(member) = callee.without_parentheses() else {
+            return false;
+        };
+        let object = member.object.span();
+        self.strictness.strict_bind_call_apply
+            && member.property.name == "bind"
+            && self
+                .node_types
+                .iter()
+                .rev()
+                .find(|(span, _)| *span == object)
+                .is_some_and(|(_, ty)| matches!(ty.resolved(), Type::Function { .. }))
+    }
+
+    /// Checks that a call passes `callee` as many arguments as it takes, and that each
+    /// argument, of the type in `arguments`, fits the parameter it's passed to.
+    /// `instantiation` describes a generic callee's signature as this call instantiated it.
+    fn check_arguments(
+        &mut self,
+        call: &CallExpression,
+        callee: &Type,
+        arguments: &[Type],
+        instantiation: Option<&str>,
+    ) {
+        let Type::Function {
+            params,
+            required,
+            rest,
+            ..
+        } = callee
+        else {
+            return;
+        };
+        let count = call.arguments.len();
+        let maximum = params.len();
+        // Spread arguments may stand for any number of values
+        let spread = call.arguments.iter().any(Argument::is_spread);
+        if !spread && (count < *required || (!rest && count > maximum)) {
+            let message = match (*rest, *required == maximum) {
+                (true, _) => Message::new(2555, &[required, &count]),
+                (false, true) => Message::new(2554, &[required, &count]),
+                (false, false) => {
+                    Message::new(2554, &[&format!("{}-{}", required, maximum), &count])
+                }
+            };
+            // Extra arguments are pointed out themselves, missing ones at the call
+            let span = match call.arguments.get(maximum..) {
+                Some([first, .., last]) => Span::new(first.span().start, last.span().end),
+                Some([only]) => only.span(),
+                _ => call.span,
+            };
+            self.report(message, span);
+            return;
+        }
+        for (index, (arg, ty)) in call.arguments.iter().zip(arguments).enumerate() {
+            if let Some(expr) = arg.as_expression()
+                && let Some(param) = Type::parameter_at(params, *rest, index)
+                && !self.report_excess_property(&param, ty, expr.span())
+                && !self.is_assignable(&param, ty)
+            {
+                let context = instantiation
+                    .map(|signature| format!(" in call to '{}'", signature))
+                    .unwrap_or_default();
+                self.error(2345, &[ty, &param, &context], expr.span());
+            }
+        }
+    }
+}
+
+/// What a function's `bind` returns when passed `count` arguments, given the function:
+/// the function without the parameters after `this` that are bound.
+fn bound_function(return_type: &Type, count: usize) -> Type {
+    let bound = count.saturating_sub(1);
+    match return_type {
+        Type::Function {
+            params,
+            return_type,
+            required,
+            rest,
+        } if bound > 0 && (!*rest || bound < params.len()) => Type::Function {
+            params: params.iter().skip(bound).cloned().collect(),
+            return_type: return_type.clone(),
+            required: required.saturating_sub(bound),
+            rest: *rest,
+        },
+        ty => ty.clone(),
+    }
+}
+
+/// The name a call's callee goes by in messages, if it has a simple one.
+fn callee_name<'a>(callee: &'a Expression) -> Option<&'a str> {
+    match callee.without_parentheses() {
+        Expression::Identifier(ident) => Some(&ident.name),
+        Expression::StaticMemberExpression(member) => Some(&member.property.name),
+        _ => None,
+    }
+}
+
+/// The parameters of a function type, and whether the last is a rest parameter.
+fn parameters_of(ty: &Type) -> (Vec<Type>, bool) {
+    match ty {
+        Type::Function { params, rest, .. } => (params.clone(), *rest),
+        Type::Any => any_parameters(),
+        _ => (Vec::new(), false),
+    }
+}
+
+/// The parameters `any` is called with: as many as are passed, each `any`, as if it
+/// took `...args: any[]`. Callbacks passed to it aren't implicitly `any`.
+fn any_parameters() -> (Vec<Type>, bool) {
+    (vec![Type::Array(Arc::new(Type::Any))], true)
+}
+
+/// The type of a function declaring `params`, whose types are `types`.
+pub(super) fn function_type(
+    params: &FormalParameters,
+    types: Vec<Type>,
+    return_type: Type,
+) -> Type {
+    // Parameters after the last required one may be left out
+    let required = params
+        .items
+        .iter()
+        .rposition(|param| {
+            !param.pattern.optional
+                && !matches!(param.pattern.kind, BindingPatternKind::AssignmentPattern(_))
+        })
+        .map_or(0, |index| index + 1);
+    Type::Function {
+        params: types,
+        return_type: Arc::new(return_type),
+        required,
+        rest: params.rest.is_some(),
+    }
+}
+
+/// The function type a call to a value of type `ty` uses, looking through aliases and
+/// intersections with the object a callable value also is.
+fn signature_of(ty: &Type) -> Type {
+    match ty.resolved() {
+        Type::Intersection(types) => types
+            .iter()
+            .find(|ty| matches!(ty.resolved(), Type::Function { .. } | Type::Generic { .. }))
+            .map_or_else(|| ty.clone(), signature_of),
+        resolved @ (Type::Function { .. } | Type::Generic { .. }) => resolved.clone(),
+        _ => ty.clone(),
+    }
+}
+
+/// Whether values of `ty` certainly have no call signatures. Anything the checker can't
+/// tell, like `any`, a type parameter, or a possibly missing value, isn't reported here.
+fn lacks_call_signatures(ty: &Type) -> bool {
+    match ty.resolved() {
+        Type::Union(types) => types.iter().any(lacks_call_signatures),
+        Type::Intersection(types) => types.iter().all(lacks_call_signatures),
+        Type::Number
+        | Type::String
+        | Type::Boolean
+        | Type::BigInt
+        | Type::Symbol
+        | Type::UniqueSymbol(_)
+        | Type::StringLiteral(_)
+        | Type::NumberLiteral(_)
+        | Type::BooleanLiteral(_)
+        | Type::BigIntLiteral(_)
+        | Type::Void
+        | Type::Object
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::Readonly(_)
+        | Type::Promise(_)
+        | Type::ObjectType(_)
+        | Type::Class(_) => true,
+        _ => false,
+    }
+}
+
+/// Whether values of `ty` certainly have no construct signatures.
+pub(super) fn lacks_construct_signatures(ty: &Type) -> bool {
+    match ty.resolved() {
+        Type::Union(types) => types.iter().any(lacks_construct_signatures),
+        Type::Intersection(types) => types.iter().all(lacks_construct_signatures),
+        Type::Class(_) => false,
+        ty => lacks_call_signatures(ty),
+    }
+}
+
+/// Whether `expr` is, or is an object literal holding, a function written inline, whose
+/// parameters the call can type.
+fn is_context_sensitive(expr: &Expression) -> bool {
+    match expr {
+        Expression::ArrowFunctionExpression(_) | Expression::FunctionExpression(_) => true,
+        Expression::ObjectExpression(object) => object.properties.iter().any(|property| {
+            matches!(property, ObjectPropertyKind::ObjectProperty(property)
+                if is_context_sensitive(&property.value))
+        }),
+        _ => false,
+    }
+}
@@ -0,0 +1,363 @@
+use super::TypeChecker;
+use crate::options::Strictness;
+use crate::types::*;
+use oxc_ast::ast::*;
+use std::sync::Arc;
+
+/// Where a candidate for a type argument came from; earlier variants win.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum InferencePriority {
+    /// Matched against the type of an argument.
+    Argument,
+    /// Matched against the type the call's result is expected to have.
+    ReturnType,
+}
+
+struct Inference {
+    param: Arc<TypeParameter>,
+    candidates: Vec<(InferencePriority, Type)>,
+}
+
+impl Inference {
+    /// The type argument the best candidates combine to, if there are any.
+    fn infer(&self, strictness: Strictness) -> Option<Type> {
+        let best = self
+            .candidates
+            .iter()
+            .map(|(priority, _)| *priority)
+            .min()?;
+        let mut candidates: Vec<Type> = Vec::new();
+        for (_, ty) in self.candidates.iter().filter(|(p, _)| *p == best) {
+            if !candidates.contains(ty) {
+                candidates.push(ty.clone());
+            }
+        }
+        // Prefer a candidate every other one fits, so `[1, n]` infers `number`
+        let inferred = candidates
+            .iter()
+            .find(|c| {
+                candidates
+                    .iter()
+                    .all(|o| is_assignable_with(c, o, strictness))
+            })
+            .cloned()
+            .unwrap_or_else(|| union_of(candidates));
+        Some(match &self.param.constraint {
+            Some(constraint) if !is_assignable_with(constraint, &inferred, strictness) => {
+                constraint.clone()
+            }
+            _ => inferred,
+        })
+    }
+}
+
+/// The type argument used when nothing was inferred: the default, else the constraint.
+fn fallback(param: &TypeParameter) -> Type {
+    param
+        .default
+        .clone()
+        .or_else(|| param.constraint.clone())
+        .unwrap_or(Type::Unknown)
+}
+
+/// Records what each type parameter in `target` corresponds to in `source`.
+fn infer_from(
+    target: &Type,
+    source: &Type,
+    priority: InferencePriority,
+    inferences: &mut [Inference],
+    depth: usize,
+) {
+    if depth > 32 {
+        return;
+    }
+    let depth = depth + 1;
+    match (target, source.resolved()) {
+        (Type::TypeParameter(param), source) => {
+            if let Some(inference) = inferences.iter_mut().find(|i| Arc::ptr_eq(&i.param, param)) {
+                inference.candidates.push((priority, source.clone()));
+            }
+        }
+        (Type::Alias(alias), source) => {
+            infer_from(alias.target(), source, priority, inferences, depth)
+        }
+        (Type::Array(target), Type::Array(source)) => {
+            infer_from(target, source, priority, inferences, depth)
+        }
+        (Type::Readonly(target), Type::Readonly(source)) => {
+            infer_from(target, source, priority, inferences, depth)
+        }
+        (Type::Readonly(target), source) => infer_from(target, source, priority, inferences, depth),
+        (Type::Promise(target), Type::Promise(source)) => {
+            infer_from(target, source, priority, inferences, depth)
+        }
+        (Type::Array(target), Type::Tuple(sources)) => {
+            for source in sources {
+                infer_from(target, source, priority, inferences, depth);
+            }
+        }
+        (Type::Tuple(targets), Type::Tuple(sources)) => {
+            for (target, source) in targets.iter().zip(sources) {
+                infer_from(target, source, priority, inferences, depth);
+            }
+        }
+        (Type::Union(targets), source) => {
+            for target in targets {
+                infer_from(target, source, priority, inferences, depth);
+            }
+        }
+        (
+            Type::Function {
+                params: target_params,
+                return_type: target_return,
+                ..
+            },
+            Type::Function {
+                params: source_params,
+                return_type: source_return,
+                ..
+            },
+        ) => {
+            for (target, source) in target_params.iter().zip(source_params) {
+                infer_from(target, source, priority, inferences, depth);
+            }
+            infer_from(target_return, source_return, priority, inferences, depth);
+        }
+        (Type::ObjectType(target), Type::ObjectType(source)) => {
+            for property in &target.properties {
+                if let Some(source) = source.property(&property.name) {
+                    infer_from(&property.ty, &source.ty, priority, inferences, depth);
+                }
+            }
+            if let Some(index) = &target.string_index {
+                for property in &source.properties {
+                    infer_from(index, &property.ty, priority, inferences, depth);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Whether `ty` mentions a `const` type parameter, directly or through its elements.
+fn mentions_const_parameter(ty: &Type) -> bool {
+    match ty {
+        Type::TypeParameter(param) => param.is_const,
+        Type::Array(elem) | Type::Readonly(elem) => mentions_const_parameter(elem),
+        Type::Tuple(types) | Type::Union(types) => types.iter().any(mentions_const_parameter),
+        Type::ObjectType(object) => object
+            .properties
+            .iter()
+            .any(|p| mentions_const_parameter(&p.ty)),
+        _ => false,
+    }
+}
+
+/// Whether the argument at `index` of a call to `callee` infers a `const` type parameter,
+/// and so is checked as if written with `as const`.
+pub(super) fn is_const_argument(callee: &Type, index: usize) -> bool {
+    match callee {
+        Type::Generic { body, .. } => match &**body {
+            Type::Function { params, .. } => {
+                params.get(index).is_some_and(mentions_const_parameter)
+            }
+            _ => false,
+        },
+        _ => false,
+    }
+}
+
+/// Replaces each type parameter in `ty` with its argument from `arguments`.
+pub(super) fn instantiate(ty: &Type, arguments: &[(Arc<TypeParameter>, Type)]) -> Type {
+    let map = |ty: &Type| instantiate(ty, arguments);
+    match ty {
+        Type::TypeParameter(param) => arguments
+            .iter()
+            .find(|(p, _)| Arc::ptr_eq(p, param))
+            .map_or_else(|| ty.clone(), |(_, arg)| arg.clone()),
+        Type::Union(types) => Type::Union(types.iter().map(map).collect()),
+        Type::Intersection(types) => intersection_of(types.iter().map(map).collect()),
+        Type::Array(elem) => Type::Array(Arc::new(map(elem))),
+        Type::Readonly(elem) => Type::Readonly(Arc::new(map(elem))),
+        Type::Promise(value) => Type::promise(map(value)),
+        Type::Tuple(types) => Type::Tuple(types.iter().map(map).collect()),
+        Type::Function {
+            params,
+            return_type,
+            required,
+            rest,
+        } => Type::Function {
+            params: params.iter().map(map).collect(),
+            return_type: Arc::new(map(return_type)),
+            required: *required,
+            rest: *rest,
+        },
+        Type::ObjectType(object) => Type::ObjectType(Arc::new(ObjectType {
+            properties: object
+                .properties
+                .iter()
+                .map(|p| Property {
+                    ty: map(&p.ty),
+                    write_type: p.write_type.as_ref().map(map),
+                    ..p.clone()
+                })
+                .collect(),
+            string_index: object.string_index.as_ref().map(map),
+            number_index: object.number_index.as_ref().map(map),
+            fresh: object.fresh,
+            property_spans: object.property_spans.clone(),
+        })),
+        Type::Generic { type_params, body } => Type::Generic {
+            type_params: type_params.clone(),
+            body: Arc::new(map(body)),
+        },
+        _ => ty.clone(),
+    }
+}
+
+impl TypeChecker {
+    /// Brings a declaration's type parameters into scope for `check_type`.
+    ///
+    /// Callers remove them again with [`Self::exit_type_parameters`].
+    pub(super) fn enter_type_parameters(
+        &mut self,
+        decl: Option<&TSTypeParameterDeclaration>,
+    ) -> Vec<Arc<TypeParameter>> {
+        let Some(decl) = decl else {
+            return Vec::new();
+        };
+        let mut params = Vec::new();
+        for param in &decl.params {
+            let type_param = Arc::new(TypeParameter {
+                name: param.name.name.to_string(),
+                constraint: param.constraint.as_ref().map(|ty| self.check_type(ty)),
+                default: param.default.as_ref().map(|ty| self.check_type(ty)),
+                is_const: param.r#const,
+            });
+            self.type_parameters.push(type_param.clone());
+            params.push(type_param);
+        }
+        params
+    }
+
+    /// Checks `expr` as if written with `as const`: literals keep their literal types,
+    /// array literals become readonly tuples, and object literal properties are readonly.
+    pub(super) fn check_expression_as_const(&mut self, expr: &Expression) -> Type {
+        let outer = std::mem::replace(&mut self.const_context, true);
+        let ty = self.check_expression(expr);
+        self.const_context = outer;
+        ty
+    }
+
+    pub(super) fn exit_type_parameters(&mut self, params: &[Arc<TypeParameter>]) {
+        let len = self.type_parameters.len().saturating_sub(params.len());
+        self.type_parameters.truncate(len);
+    }
+
+    /// The type of something declared with `type_params`, generic only if there are any.
+    pub(super) fn generic(type_params: Vec<Arc<TypeParameter>>, body: Type) -> Type {
+        if type_params.is_empty() {
+            return body;
+        }
+        Type::Generic {
+            type_params,
+            body: Arc::new(body),
+        }
+    }
+
+    /// Chooses type arguments for a call to a generic function and instantiates it.
+    ///
+    /// Explicit type arguments are used as written. Others are inferred from the
+    /// arguments, then from `contextual` (the type the result is expected to have), and
+    /// finally fall back to the parameter's default or constraint.
+    pub(super) fn instantiate_call(
+        &self,
+        type_params: &[Arc<TypeParameter>],
+        body: &Type,
+        type_arguments: Option<&TSTypeParameterInstantiation>,
+        arguments: &[Type],
+        contextual: Option<&Type>,
+    ) -> Type {
+        let type_arguments =
+            self.call_type_arguments(type_params, body, type_arguments, arguments, contextual);
+        instantiate(body, &type_arguments)
+    }
+
+    /// The type arguments a call with `arguments` instantiates a generic function with,
+    /// falling back to each parameter's default or constraint where nothing is inferred.
+    pub(super) fn call_type_arguments(
+        &self,
+        type_params: &[Arc<TypeParameter>],
+        body: &Type,
+        type_arguments: Option<&TSTypeParameterInstantiation>,
+        arguments: &[Type],
+        contextual: Option<&Type>,
+    ) -> Vec<(Arc<TypeParameter>, Type)> {
+        let arguments: Vec<Option<Type>> = arguments.iter().cloned().map(Some).collect();
+        self.infer_type_arguments(type_params, body, type_arguments, &arguments, contextual)
+            .into_iter()
+            .map(|(param, ty)| {
+                let ty = ty.unwrap_or_else(|| fallback(&param));
+                (param, ty)
+            })
+            .collect()
+    }
+
+    /// The type arguments known from explicit type arguments, the arguments checked so far
+    /// (`None` for the rest), and `contextual`; `None` where nothing is known yet.
+    pub(super) fn infer_type_arguments(
+        &self,
+        type_params: &[Arc<TypeParameter>],
+        body: &Type,
+        type_arguments: Option<&TSTypeParameterInstantiation>,
+        arguments: &[Option<Type>],
+        contextual: Option<&Type>,
+    ) -> Vec<(Arc<TypeParameter>, Option<Type>)> {
+        let explicit: Vec<Type> = type_arguments
+            .map(|args| args.params.iter().map(|ty| self.check_type(ty)).collect())
+            .unwrap_or_default();
+        let mut inferences: Vec<Inference> = type_params
+            .iter()
+            .map(|param| Inference {
+                param: param.clone(),
+                candidates: Vec::new(),
+            })
+            .collect();
+        if let Type::Function {
+            params,
+            return_type,
+            rest,
+            ..
+        } = body
+        {
+            for (index, arg) in arguments.iter().enumerate() {
+                if let Some(arg) = arg
+                    && let Some(param) = Type::parameter_at(params, *rest, index)
+                {
+                    infer_from(&param, arg, InferencePriority::Argument, &mut inferences, 0);
+                }
+            }
+            if let Some(contextual) = contextual {
+                infer_from(
+                    return_type,
+                    contextual,
+                    InferencePriority::ReturnType,
+                    &mut inferences,
+                    0,
+                );
+            }
+        }
+
+        inferences
+            .iter()
+            .enumerate()
+            .map(|(i, inference)| {
+                let ty = explicit
+                    .get(i)
+                    .cloned()
+                    .or_else(|| inference.infer(self.strictness));
+                (inference.param.clone(), ty)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,195 @@
+use super::TypeChecker;
+use crate::builtins::global_type;
+use crate::types::*;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_ast::visit::walk;
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::sync::Arc;
+
+/// The names syntax refers to as types, not looking into interface bodies, whose
+/// references only matter once the interface itself is needed.
+#[derive(Default)]
+struct TypeReferences {
+    names: BTreeSet<String>,
+}
+
+impl<'a> Visit<'a> for TypeReferences {
+    fn visit_ts_type_reference(&mut self, reference: &TSTypeReference<'a>) {
+        if let TSTypeName::IdentifierReference(ident) = &reference.type_name {
+            self.names.insert(ident.name.to_string());
+        }
+        walk::walk_ts_type_reference(self, reference);
+    }
+
+    fn visit_ts_interface_heritage(&mut self, heritage: &TSInterfaceHeritage<'a>) {
+        if let Expression::Identifier(ident) = &heritage.expression {
+            self.names.insert(ident.name.to_string());
+        }
+        walk::walk_ts_interface_heritage(self, heritage);
+    }
+
+    fn visit_ts_interface_declaration(&mut self, _: &TSInterfaceDeclaration<'a>) {}
+}
+
+/// The names an interface's members and the interfaces it extends refer to as types.
+fn interface_references(decl: &TSInterfaceDeclaration) -> BTreeSet<String> {
+    let mut references = TypeReferences::default();
+    walk::walk_ts_interface_declaration(&mut references, decl);
+    references.names
+}
+
+impl TypeChecker {
+    /// Makes every top-level interface visible by name, and resolves the members of those
+    /// the rest of the program refers to, directly or through other interfaces. Each
+    /// interface's members are resolved once and shared by every reference to it; those
+    /// of interfaces nothing refers to never are.
+    pub(super) fn declare_interfaces(&mut self, program: &Program) {
+        let mut declarations: HashMap<&str, Vec<&TSInterfaceDeclaration>> = HashMap::new();
+        for stmt in &program.body {
+            let decl = match stmt {
+                Statement::TSInterfaceDeclaration(decl) => &**decl,
+                Statement::ExportNamedDeclaration(export) => match &export.declaration {
+                    Some(Declaration::TSInterfaceDeclaration(decl)) => &**decl,
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            declarations
+                .entry(decl.id.name.as_str())
+                .or_default()
+                .push(decl);
+            self.interfaces.insert(decl.span);
+        }
+        for (name, decls) in &declarations {
+            // A type alias by the same name is a duplicate; the alias keeps the name
+            if !self.type_aliases.contains_key(*name) {
+                self.type_aliases.insert(
+                    name.to_string(),
+                    (decls[0].span, Arc::new(AliasType::new(*name))),
+                );
+            }
+        }
+
+        let mut references = TypeReferences::default();
+        references.visit_program(program);
+        let mut wanted: Vec<String> = references.names.into_iter().collect();
+        let mut needed = BTreeSet::new();
+        while let Some(name) = wanted.pop() {
+            let Some(decls) = declarations.get(name.as_str()) else {
+                continue;
+            };
+            if needed.insert(name) {
+                wanted.extend(decls.iter().flat_map(|decl| interface_references(decl)));
+            }
+        }
+        let mut resolving = Vec::new();
+        for name in &needed {
+            self.resolve_interface(name, &declarations, &mut resolving);
+        }
+    }
+
+    /// Resolves the members of the interface `name` from its `declarations`, after those
+    /// of the interfaces it extends. `resolving` is the interfaces whose bases are being
+    /// resolved, to catch one extending itself.
+    fn resolve_interface(
+        &mut self,
+        name: &str,
+        declarations: &HashMap<&str, Vec<&TSInterfaceDeclaration>>,
+        resolving: &mut Vec<String>,
+    ) {
+        let Some((_, handle)) = self.type_aliases.get(name).cloned() else {
+            return;
+        };
+        let Some(decls) = declarations.get(name) else {
+            return;
+        };
+        if handle.is_resolved() || !self.interfaces.contains(&decls[0].span) {
+            return;
+        }
+        resolving.push(name.to_string());
+        for decl in decls {
+            for heritage in decl.extends.iter().flatten() {
+                let Expression::Identifier(base) = &heritage.expression else {
+                    continue;
+                };
+                if resolving.iter().any(|name| name == base.name.as_str()) {
+                    self.error(2310, &[&name], decl.id.span);
+                    continue;
+                }
+                self.resolve_interface(&base.name, declarations, resolving);
+            }
+        }
+        resolving.pop();
+        let ty = self.interface_type(decls);
+        handle.resolve(ty);
+    }
+
+    /// Checks an interface declared anywhere but the top level, where it's declared and
+    /// resolved as it's reached. Top-level ones are resolved when something needs them.
+    pub(super) fn check_interface_declaration(&mut self, decl: &TSInterfaceDeclaration) {
+        if self.interfaces.contains(&decl.span) {
+            return;
+        }
+        let handle = Arc::new(AliasType::new(decl.id.name.as_str()));
+        self.type_aliases
+            .insert(decl.id.name.to_string(), (decl.span, handle.clone()));
+        handle.resolve(self.interface_type(&[decl]));
+    }
+
+    /// The type of an interface with `declarations`: the members they declare, with those
+    /// of the types they extend that none of them redeclares.
+    fn interface_type(&self, declarations: &[&TSInterfaceDeclaration]) -> Type {
+        let mut object = ObjectType::default();
+        let mut call_signature = None;
+        for decl in declarations {
+            let (members, call) = self.signature_members(&decl.body.body);
+            for property in members.properties {
+                match object
+                    .properties
+                    .iter_mut()
+                    .find(|p| p.name == property.name)
+                {
+                    Some(existing) => *existing = property,
+                    None => object.properties.push(property),
+                }
+            }
+            object.string_index = members.string_index.or(object.string_index);
+            object.number_index = members.number_index.or(object.number_index);
+            call_signature = call_signature.or(call);
+        }
+        let mut seen = HashSet::new();
+        for decl in declarations {
+            for heritage in decl.extends.iter().flatten() {
+                let Expression::Identifier(base) = &heritage.expression else {
+                    continue;
+                };
+                if !seen.insert(base.name.as_str()) {
+                    continue;
+                }
+                let base = match self.type_aliases.get(base.name.as_str()) {
+                    Some((_, handle)) => handle.target().clone(),
+                    None => global_type(&base.name).unwrap_or(Type::Any),
+                };
+                let base = merged_object(&[base]);
+                for property in base.properties {
+                    if object.property(&property.name).is_none() {
+                        object.properties.push(property);
+                    }
+                }
+                object.string_index = object.string_index.or(base.string_index);
+                object.number_index = object.number_index.or(base.number_index);
+            }
+        }
+        let has_members = !object.properties.is_empty()
+            || object.string_index.is_some()
+            || object.number_index.is_some();
+        let object = Type::ObjectType(Arc::new(object));
+        // A callable interface is both a function and an object with its other members
+        match call_signature {
+            Some(function) if !has_members => function,
+            Some(function) => intersection_of(vec![function, object]),
+            None => object,
+        }
+    }
+}
@@ -0,0 +1,193 @@
+use super::TypeChecker;
+use super::assignments::merge_types;
+use crate::symbols::{SymbolId, SymbolKind};
+use crate::types::*;
+use oxc_ast::Visit;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+use std::slice;
+
+// Passes over a loop body looking for the types at its head to settle; past this, variables
+// assigned in the loop go back to their declared types
+const MAX_LOOP_PASSES: usize = 4;
+
+/// Whether a loop body contains a `break` or `continue`, which leave it part way through.
+fn has_jumps(body: &Statement) -> bool {
+    #[derive(Default)]
+    struct Jumps(bool);
+
+    impl<'a> Visit<'a> for Jumps {
+        fn visit_break_statement(&mut self, _: &BreakStatement<'a>) {
+            self.0 = true;
+        }
+
+        fn visit_continue_statement(&mut self, _: &ContinueStatement<'a>) {
+            self.0 = true;
+        }
+    }
+
+    let mut jumps = Jumps::default();
+    jumps.visit_statement(body);
+    jumps.0
+}
+
+impl TypeChecker {
+    pub(super) fn check_while_statement(&mut self, stmt: &WhileStatement) {
+        self.check_loop(stmt.span, Some(&stmt.test), None, &stmt.body);
+    }
+
+    pub(super) fn check_do_while_statement(&mut self, stmt: &DoWhileStatement) {
+        self.check_loop(stmt.span, Some(&stmt.test), None, &stmt.body);
+    }
+
+    pub(super) fn check_for_statement(&mut self, stmt: &ForStatement) {
+        self.enter_scope(stmt.span, false);
+        match &stmt.init {
+            Some(ForStatementInit::VariableDeclaration(decl)) => {
+                self.check_variable_declaration(decl)
+            }
+            Some(init) => {
+                if let Some(expr) = init.as_expression() {
+                    self.check_expression(expr);
+                }
+            }
+            None => {}
+        }
+        self.check_loop(
+            stmt.span,
+            stmt.test.as_ref(),
+            stmt.update.as_ref(),
+            &stmt.body,
+        );
+        self.exit_scope();
+    }
+
+    pub(super) fn check_for_of_statement(&mut self, stmt: &ForOfStatement) {
+        let iterable = self.check_expression(&stmt.right);
+        let element = self.check_iterable(&iterable, stmt.right.span());
+        let element = match stmt.r#await {
+            true => element.awaited(),
+            false => element,
+        };
+        self.enter_scope(stmt.span, false);
+        self.declare_loop_variable(&stmt.left, element);
+        self.check_loop(stmt.span, None, None, &stmt.body);
+        self.exit_scope();
+    }
+
+    pub(super) fn check_for_in_statement(&mut self, stmt: &ForInStatement) {
+        self.check_expression(&stmt.right);
+        self.enter_scope(stmt.span, false);
+        self.declare_loop_variable(&stmt.left, Type::String);
+        self.check_loop(stmt.span, None, None, &stmt.body);
+        self.exit_scope();
+    }
+
+    /// Declares the variable a `for...of` or `for...in` loop binds each value to.
+    fn declare_loop_variable(&mut self, left: &ForStatementLeft, ty: Type) {
+        let ForStatementLeft::VariableDeclaration(decl) = left else {
+            return;
+        };
+        for declarator in &decl.declarations {
+            if let BindingPatternKind::BindingIdentifier(ident) = &declarator.id.kind {
+                let id = self.add_symbol(&ident.name, SymbolKind::Variable, ty.clone(), ident.span);
+                self.bind(&ident.name, id, decl.kind.is_var());
+                if decl.kind.is_const() {
+                    self.constants.insert(id);
+                }
+            }
+        }
+    }
+
+    /// Checks a loop's test, body, and update at the types its variables have at the head
+    /// of every iteration, and continues with what they may be once it ends.
+    ///
+    /// Narrowings of variables the loop assigns only hold at its head if the types the body
+    /// leaves them with fit within them, so the head types are widened until they settle.
+    fn check_loop(
+        &mut self,
+        span: Span,
+        test: Option<&Expression>,
+        update: Option<&Expression>,
+        body: &Statement,
+    ) {
+        let assigned = self.assigned_in(span);
+        let head = self.loop_head_types(&assigned, test, body);
+        for (id, ty) in head {
+            if self.symbol_type(id) != ty {
+                self.narrowed.push((id, ty));
+            }
+        }
+        if let Some(test) = test {
+            self.check_expression(test);
+        }
+        let exit = self.check_branch(None, slice::from_ref(body));
+        if let Some(update) = update {
+            self.check_expression(update);
+        }
+        // The body may not run at all, so the head types still apply
+        self.merge_branches(vec![Vec::new(), exit]);
+    }
+
+    /// The types `assigned` have at the head of every iteration of the loop over `body`.
+    fn loop_head_types(
+        &self,
+        assigned: &[SymbolId],
+        test: Option<&Expression>,
+        body: &Statement,
+    ) -> Vec<(SymbolId, Type)> {
+        let declared: Vec<(SymbolId, Type)> = assigned
+            .iter()
+            .map(|&id| (id, self.symbols[id.0].ty.clone()))
+            .collect();
+        let mut head: Vec<(SymbolId, Type)> = assigned
+            .iter()
+            .map(|&id| (id, self.symbol_type(id)))
+            .collect();
+        // Jumps leave the body with types its end doesn't show, so assume the worst
+        if head == declared || has_jumps(body) {
+            return declared;
+        }
+        for _ in 0..MAX_LOOP_PASSES {
+            // A trial pass on a copy, so its errors and symbols aren't kept
+            let mut trial = self.clone();
+            trial.narrowed.extend(head.iter().cloned());
+            if let Some(test) = test {
+                trial.check_expression(test);
+            }
+            let exit = trial.check_branch(None, slice::from_ref(body));
+            let next: Vec<(SymbolId, Type)> = head
+                .iter()
+                .map(|(id, ty)| {
+                    let left = exit
+                        .iter()
+                        .rev()
+                        .find(|(assigned, _)| assigned == id)
+                        .map_or_else(|| ty.clone(), |(_, ty)| ty.clone());
+                    let merged = merge_types(&self.symbols[id.0].ty, [ty.clone(), left]);
+                    (*id, merged)
+                })
+                .collect();
+            if next == head {
+                return head;
+            }
+            head = next;
+        }
+        declared
+    }
+
+    /// The variables in scope that are assigned somewhere within `span`.
+    fn assigned_in(&self, span: Span) -> Vec<SymbolId> {
+        let mut assigned = Vec::new();
+        for (name, at) in &self.assignments {
+            if span.start <= at.start
+                && at.end <= span.end
+                && let Some(id) = self.lookup(name)
+                && !assigned.contains(&id)
+            {
+                assigned.push(id);
+            }
+        }
+        assigned
+    }
+}
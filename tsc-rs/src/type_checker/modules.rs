@@ -0,0 +1,249 @@
+use super::TypeChecker;
+use crate::diagnostics::Message;
+use crate::intern::Atom;
+use crate::options::{ModuleKind, ScriptTarget};
+use crate::symbols::*;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::{GetSpan, Span};
+
+impl TypeChecker {
+    /// Makes the exports of an already-checked module visible to imports of `specifier`.
+    pub fn set_module_exports(
+        &mut self,
+        specifier: impl Into<String>,
+        exports: Vec<ExportedSymbol>,
+    ) {
+        self.module_exports.insert(specifier.into(), exports);
+    }
+
+    /// Reports `await` outside any function when the file's module system or target
+    /// can't express it.
+    pub(super) fn check_top_level_await(&mut self, expr: &AwaitExpression) {
+        if self.in_function() {
+            return;
+        }
+        // 1378 names the module and target settings that would allow it
+        let code = match self.module_kind {
+            ModuleKind::CommonJS => 1309,
+            ModuleKind::ES2015 | ModuleKind::ES2020 => 1378,
+            _ if self.target < ScriptTarget::ES2017 => 1378,
+            _ => return,
+        };
+        self.error(code, &[], expr.span);
+    }
+
+    /// The symbols this file exports, located in `file_name` unless they are re-exports.
+    pub fn exported_symbols(&self, file_name: &str) -> Vec<ExportedSymbol> {
+        self.exports
+            .iter()
+            .map(|(name, id)| {
+                let symbol = &self.symbols[id.0];
+                ExportedSymbol {
+                    name: *name,
+                    kind: symbol.kind,
+                    ty: symbol.ty.clone(),
+                    location: symbol
+                        .origin
+                        .clone()
+                        .unwrap_or_else(|| Location::new(file_name, symbol.span)),
+                }
+            })
+            .collect()
+    }
+
+    /// The exports visible to imports of `specifier`, if that module was checked.
+    pub fn module_exports(&self, specifier: &str) -> Option<&[ExportedSymbol]> {
+        self.module_exports.get(specifier).map(Vec::as_slice)
+    }
+
+    /// Looks up `name` in the exports of `specifier`.
+    ///
+    /// Returns `Ok(None)` when the module itself is unknown, since the loader already
+    /// reported it, and `Err` with a message when the module lacks the member.
+    fn lookup_export(
+        &self,
+        specifier: &str,
+        name: &str,
+    ) -> Result<Option<&ExportedSymbol>, Message> {
+        let Some(exports) = self.module_exports.get(specifier) else {
+            return Ok(None);
+        };
+        match exports.iter().find(|e| e.name == name) {
+            Some(export) => Ok(Some(export)),
+            None if name == "default" => Err(Message::new(1192, &[&format!("\"{}\"", specifier)])),
+            None => Err(Message::new(2305, &[&format!("\"{}\"", specifier), &name])),
+        }
+    }
+
+    /// Resolves `name` from `specifier` into an alias symbol, reporting missing members.
+    fn import_alias(
+        &mut self,
+        specifier: &str,
+        name: &str,
+        local: &str,
+        span: Span,
+        in_scope: bool,
+    ) -> SymbolId {
+        let (ty, origin) = match self.lookup_export(specifier, name) {
+            Ok(Some(export)) => (export.ty.clone(), Some(export.location.clone())),
+            Ok(None) => (Type::Any, None),
+            Err(message) => {
+                self.report(message, span);
+                (Type::Any, None)
+            }
+        };
+        let id = if in_scope {
+            self.declare_symbol(local, SymbolKind::Alias, ty, span)
+        } else {
+            self.add_symbol(local, SymbolKind::Alias, ty, span)
+        };
+        self.symbols[id.0].origin = origin;
+        id
+    }
+
+    pub(super) fn check_import_declaration(&mut self, decl: &ImportDeclaration) {
+        let specifier = decl.source.value.as_str();
+        for import in decl.specifiers.iter().flatten() {
+            match import {
+                ImportDeclarationSpecifier::ImportSpecifier(import) => {
+                    let id = self.import_alias(
+                        specifier,
+                        &import.imported.name(),
+                        &import.local.name,
+                        import.local.span,
+                        true,
+                    );
+                    if import.imported.span() != import.local.span {
+                        self.references.push((import.imported.span(), id));
+                    }
+                }
+                ImportDeclarationSpecifier::ImportDefaultSpecifier(import) => {
+                    self.import_alias(
+                        specifier,
+                        "default",
+                        &import.local.name,
+                        import.local.span,
+                        true,
+                    );
+                }
+                ImportDeclarationSpecifier::ImportNamespaceSpecifier(import) => {
+                    self.declare_symbol(
+                        &import.local.name,
+                        SymbolKind::Alias,
+                        Type::Any,
+                        import.local.span,
+                    );
+                }
+            }
+        }
+    }
+
+    pub(super) fn check_export_named_declaration(&mut self, decl: &ExportNamedDeclaration) {
+        if let Some(declaration) = &decl.declaration {
+            self.check_declaration(declaration);
+            for name in declared_names(declaration) {
+                if let Some(id) = self.lookup(&name) {
+                    self.exports.push((name, id));
+                }
+            }
+            return;
+        }
+
+        for export in &decl.specifiers {
+            let local = export.local.name();
+            let exported = Atom::new(&export.exported.name());
+            match &decl.source {
+                Some(source) => {
+                    let id = self.import_alias(
+                        source.value.as_str(),
+                        &local,
+                        &exported,
+                        export.local.span(),
+                        false,
+                    );
+                    self.exports.push((exported, id));
+                }
+                None => {
+                    if let Some(id) = self.lookup(&local) {
+                        self.references.push((export.local.span(), id));
+                        self.exports.push((exported, id));
+                    }
+                }
+            }
+        }
+    }
+
+    pub(super) fn check_export_default_declaration(&mut self, decl: &ExportDefaultDeclaration) {
+        match &decl.declaration {
+            ExportDefaultDeclarationKind::FunctionDeclaration(func) => {
+                self.check_function_declaration(func);
+                if let Some(ident) = &func.id
+                    && let Some(id) = self.lookup(&ident.name)
+                {
+                    self.exports.push((Atom::new("default"), id));
+                }
+            }
+            ExportDefaultDeclarationKind::ClassDeclaration(class) => {
+                let id = match &class.id {
+                    Some(ident) => {
+                        self.check_class_declaration(class);
+                        self.lookup(&ident.name)
+                    }
+                    None => {
+                        let ty = self.check_class(class);
+                        Some(self.add_symbol("default", SymbolKind::Class, ty, class.span))
+                    }
+                };
+                if let Some(id) = id {
+                    self.exports.push((Atom::new("default"), id));
+                }
+            }
+            kind => {
+                if let Some(expr) = kind.as_expression() {
+                    let ty = self.check_expression(expr);
+                    let id = self.add_symbol("default", SymbolKind::Variable, ty, expr.span());
+                    self.exports.push((Atom::new("default"), id));
+                }
+            }
+        }
+    }
+
+    pub(super) fn check_export_all_declaration(&mut self, decl: &ExportAllDeclaration) {
+        // `export * as ns from` needs namespace object types
+        if decl.exported.is_some() {
+            return;
+        }
+        let Some(exports) = self.module_exports.get(decl.source.value.as_str()).cloned() else {
+            return;
+        };
+        for export in exports.into_iter().filter(|e| e.name != "default") {
+            // `export *` doesn't name the symbol in this file, so it isn't a reference
+            let id = self.create_symbol(&export.name, SymbolKind::Alias, export.ty, decl.span);
+            self.symbols[id.0].origin = Some(export.location);
+            self.exports.push((export.name, id));
+        }
+    }
+}
+
+pub(super) fn declared_names(declaration: &Declaration) -> Vec<Atom> {
+    match declaration {
+        Declaration::VariableDeclaration(var_decl) => var_decl
+            .declarations
+            .iter()
+            .filter_map(|decl| decl.id.get_identifier_name().map(|n| Atom::new(&n)))
+            .collect(),
+        Declaration::FunctionDeclaration(func) => {
+            func.id.iter().map(|id| Atom::new(&id.name)).collect()
+        }
+        Declaration::ClassDeclaration(class) => {
+            class.id.iter().map(|id| Atom::new(&id.name)).collect()
+        }
+        Declaration::TSEnumDeclaration(decl) => vec![Atom::new(&decl.id.name)],
+        Declaration::TSModuleDeclaration(decl) => match &decl.id {
+            TSModuleDeclarationName::Identifier(id) => vec![Atom::new(&id.name)],
+            TSModuleDeclarationName::StringLiteral(_) => Vec::new(),
+        },
+        _ => Vec::new(),
+    }
+}
@@ -0,0 +1,112 @@
+use super::TypeChecker;
+use super::modules::declared_names;
+use crate::intern::Atom;
+use crate::symbols::SymbolKind;
+use crate::types::*;
+use oxc_ast::ast::*;
+use std::sync::Arc;
+
+impl TypeChecker {
+    /// Declares a namespace as an object holding what it exports. Declaring it again, or
+    /// after an enum of the same name, adds to that object instead.
+    pub(super) fn check_namespace_declaration(&mut self, decl: &TSModuleDeclaration) {
+        // `declare module "name"` describes another module
+        let TSModuleDeclarationName::Identifier(ident) = &decl.id else {
+            return;
+        };
+        // What `declare global` declares is in scope here, and on `globalThis`
+        if decl.kind == TSModuleDeclarationKind::Global {
+            if let Some(TSModuleDeclarationBody::TSModuleBlock(block)) = &decl.body {
+                self.declaring_globals = true;
+                self.check_statements(&block.body);
+                self.declaring_globals = false;
+            }
+            return;
+        }
+        let properties = match &decl.body {
+            Some(TSModuleDeclarationBody::TSModuleBlock(block)) => {
+                self.enter_scope(block.span, true);
+                let exported = self.check_namespace_body(&block.body);
+                let properties = self.exported_properties(exported);
+                self.exit_scope();
+                properties
+            }
+            // `namespace A.B {}` is `A` exporting the namespace `B`
+            Some(TSModuleDeclarationBody::TSModuleDeclaration(inner)) => {
+                self.enter_scope(inner.span, true);
+                self.check_namespace_declaration(inner);
+                let properties = match &inner.id {
+                    TSModuleDeclarationName::Identifier(id) => {
+                        self.exported_properties(vec![Atom::new(&id.name)])
+                    }
+                    TSModuleDeclarationName::StringLiteral(_) => Vec::new(),
+                };
+                self.exit_scope();
+                properties
+            }
+            None => Vec::new(),
+        };
+
+        let name = ident.name.as_str();
+        let merged = self.declared_here(name).filter(|&id| {
+            matches!(
+                self.symbols[id.0].kind,
+                SymbolKind::Enum | SymbolKind::Namespace
+            )
+        });
+        match merged {
+            Some(id) => {
+                let mut object = match self.symbols[id.0].ty.resolved() {
+                    Type::ObjectType(object) => (**object).clone(),
+                    _ => ObjectType::default(),
+                };
+                for property in properties {
+                    object.properties.retain(|p| p.name != property.name);
+                    object.properties.push(property);
+                }
+                self.symbols[id.0].ty = Type::ObjectType(Arc::new(object));
+            }
+            None => {
+                let object = ObjectType {
+                    properties,
+                    ..ObjectType::default()
+                };
+                self.declare_symbol(
+                    name,
+                    SymbolKind::Namespace,
+                    Type::ObjectType(Arc::new(object)),
+                    ident.span,
+                );
+            }
+        }
+    }
+
+    /// Checks the statements in a namespace, returning the names it exports.
+    fn check_namespace_body(&mut self, stmts: &[Statement]) -> Vec<Atom> {
+        self.hoist_functions(stmts);
+        let mut exported = Vec::new();
+        for stmt in stmts {
+            // Exports become properties of the namespace rather than of the module
+            if let Statement::ExportNamedDeclaration(export) = stmt {
+                if let Some(declaration) = &export.declaration {
+                    self.check_declaration(declaration);
+                    exported.extend(declared_names(declaration));
+                }
+            } else {
+                self.check_statement(stmt);
+            }
+        }
+        exported
+    }
+
+    /// The properties the namespace being checked exports `names` as.
+    fn exported_properties(&self, names: Vec<Atom>) -> Vec<Property> {
+        names
+            .into_iter()
+            .filter_map(|name| {
+                let id = self.lookup(&name)?;
+                Some(Property::new(name, self.symbol_type(id)))
+            })
+            .collect()
+    }
+}
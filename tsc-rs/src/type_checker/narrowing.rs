@@ -0,0 +1,531 @@
+use super::TypeChecker;
+use super::assignments::merge_types;
+use super::flow::statement_completes_normally;
+use super::operators::{TYPEOF_RESULTS, bigint_literal_value, numeric_literal_value};
+use crate::symbols::SymbolId;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_syntax::operator::{BinaryOperator, UnaryOperator};
+
+/// What `typeof` evaluates to for a value of `ty`, if a single result is known.
+fn typeof_result(ty: &Type) -> Option<&'static str> {
+    Some(match ty.resolved() {
+        Type::String | Type::StringLiteral(_) => "string",
+        Type::Number | Type::NumberLiteral(_) => "number",
+        Type::BigInt | Type::BigIntLiteral(_) => "bigint",
+        Type::Boolean | Type::BooleanLiteral(_) => "boolean",
+        Type::Symbol | Type::UniqueSymbol(_) => "symbol",
+        Type::Undefined | Type::Void => "undefined",
+        Type::Null
+        | Type::Object
+        | Type::ObjectType(_)
+        | Type::Array(_)
+        | Type::Tuple(_)
+        | Type::Readonly(_)
+        | Type::Promise(_) => "object",
+        Type::Function { .. } | Type::Class(_) | Type::Generic { .. } => "function",
+        // A branded primitive is still that primitive
+        Type::Intersection(types) => {
+            let results: Vec<&str> = types.iter().filter_map(typeof_result).collect();
+            return results
+                .iter()
+                .find(|&&result| result != "object")
+                .or(results.first())
+                .copied();
+        }
+        Type::Any
+        | Type::Unknown
+        | Type::Never
+        | Type::Union(_)
+        | Type::Alias(_)
+        | Type::TypeParameter(_) => return None,
+    })
+}
+
+/// The type `typeof` must return `result` for, when nothing more is known about the value.
+fn type_for_typeof(result: &str) -> Type {
+    match result {
+        "string" => Type::String,
+        "number" => Type::Number,
+        "bigint" => Type::BigInt,
+        "boolean" => Type::Boolean,
+        "symbol" => Type::Symbol,
+        "undefined" => Type::Undefined,
+        "object" => Type::Union(vec![Type::Object, Type::Null]),
+        _ => Type::Any,
+    }
+}
+
+/// Narrows `ty` to the values whose `typeof` is one of `results`.
+fn narrow_to_typeof(ty: &Type, results: &[String]) -> Type {
+    match ty.resolved() {
+        Type::Any if results.iter().all(|r| r == "object" || r == "function") => Type::Any,
+        Type::Any | Type::Unknown => union_of(results.iter().map(|r| type_for_typeof(r)).collect()),
+        Type::Union(types) => union_of(
+            types
+                .iter()
+                .filter(|t| typeof_result(t).is_none_or(|r| results.iter().any(|x| x == r)))
+                .cloned()
+                .collect(),
+        ),
+        ty => match typeof_result(ty) {
+            Some(result) if !results.iter().any(|r| r == result) => Type::Never,
+            _ => ty.clone(),
+        },
+    }
+}
+
+/// Removes from `ty` the members whose `typeof` is one of `results`.
+fn narrow_away_typeof(ty: &Type, results: &[String]) -> Type {
+    match ty.resolved() {
+        Type::Union(types) => union_of(
+            types
+                .iter()
+                .filter(|t| typeof_result(t).is_none_or(|r| !results.iter().any(|x| x == r)))
+                .cloned()
+                .collect(),
+        ),
+        ty => match typeof_result(ty) {
+            Some(result) if results.iter().any(|r| r == result) => Type::Never,
+            _ => ty.clone(),
+        },
+    }
+}
+
+/// The literal type of a literal `expr`, like `"a"` or `-1`.
+pub(super) fn literal_type(expr: &Expression) -> Option<Type> {
+    Some(match expr.without_parentheses() {
+        Expression::StringLiteral(literal) => Type::StringLiteral(literal.value.to_string()),
+        Expression::BooleanLiteral(literal) => Type::BooleanLiteral(literal.value),
+        expr => match numeric_literal_value(expr) {
+            Some(value) => Type::NumberLiteral(value),
+            None => Type::BigIntLiteral(bigint_literal_value(expr)?),
+        },
+    })
+}
+
+/// The value a comparison tests for: a literal, `null` or `undefined`.
+fn compared_value(expr: &Expression) -> Option<Type> {
+    match expr.without_parentheses() {
+        Expression::NullLiteral(_) => Some(Type::Null),
+        Expression::Identifier(ident) if ident.name == "undefined" => Some(Type::Undefined),
+        Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::Void => {
+            Some(Type::Undefined)
+        }
+        expr => literal_type(expr),
+    }
+}
+
+/// The values `=== value` matches, or `== value` when `loose`, which also matches the
+/// other of `null` and `undefined`.
+fn matched_values(value: &Type, loose: bool) -> Vec<Type> {
+    match value {
+        Type::Null | Type::Undefined if loose => vec![Type::Null, Type::Undefined],
+        value => vec![value.clone()],
+    }
+}
+
+/// What's left of `member` that's one of `values`, if anything.
+fn member_matching(member: &Type, values: &[Type]) -> Option<Type> {
+    let resolved = member.resolved();
+    match resolved {
+        ty if values.contains(ty) => Some(member.clone()),
+        Type::Void if values.contains(&Type::Undefined) => Some(Type::Undefined),
+        Type::TypeParameter(_) => Some(member.clone()),
+        // A literal is one of the values of its primitive
+        ty => values
+            .iter()
+            .find(|value| *value != ty && value.widened() == *ty)
+            .cloned(),
+    }
+}
+
+/// Narrows `ty` to what equals one of `values`.
+fn narrow_to_values(ty: &Type, values: &[Type]) -> Type {
+    match ty.resolved() {
+        Type::Any | Type::Unknown => union_of(values.to_vec()),
+        Type::Union(types) => union_of(
+            types
+                .iter()
+                .filter_map(|member| member_matching(member, values))
+                .collect(),
+        ),
+        _ => member_matching(ty, values).unwrap_or(Type::Never),
+    }
+}
+
+/// Removes from `ty` the members that can only be one of `values`.
+fn narrow_away_values(ty: &Type, values: &[Type]) -> Type {
+    let without = |member: &Type| match member.resolved() {
+        ty if values.contains(ty) => None,
+        Type::Void if values.contains(&Type::Undefined) => None,
+        Type::Boolean => match values {
+            [Type::BooleanLiteral(value)] => Some(Type::BooleanLiteral(!value)),
+            _ => Some(member.clone()),
+        },
+        _ => Some(member.clone()),
+    };
+    match ty.resolved() {
+        Type::Union(types) => union_of(types.iter().filter_map(without).collect()),
+        Type::Any | Type::Unknown => ty.clone(),
+        _ => without(ty).unwrap_or(Type::Never),
+    }
+}
+
+/// The values a property of type `ty` can hold, when they're all literals.
+fn literal_values(ty: &Type) -> Option<Vec<&Type>> {
+    match ty.resolved() {
+        Type::StringLiteral(_) | Type::NumberLiteral(_) | Type::BooleanLiteral(_) => {
+            Some(vec![ty.resolved()])
+        }
+        Type::Union(types) => types
+            .iter()
+            .map(literal_values)
+            .collect::<Option<Vec<_>>>()
+            .map(|values| values.concat()),
+        _ => None,
+    }
+}
+
+/// What a guard compares about its variable.
+#[derive(Clone)]
+enum GuardTest {
+    /// `typeof x` against one of the strings `typeof` can return.
+    Typeof(String),
+    /// A discriminant property, `x.kind`, against a literal.
+    Property(String, Type),
+    /// The variable itself against a literal, `null` or `undefined`; `==` when loose.
+    Value(Type, bool),
+}
+
+/// A comparison that narrows a variable when it's tested.
+#[derive(Clone)]
+pub(super) struct Guard {
+    symbol: SymbolId,
+    test: GuardTest,
+    /// Whether the guard holds when the comparison is true, as opposed to `!==`.
+    equal: bool,
+}
+
+impl TypeChecker {
+    /// The type of a symbol at the current point, after any narrowing.
+    pub(super) fn symbol_type(&self, id: SymbolId) -> Type {
+        self.narrowed
+            .iter()
+            .rev()
+            .find(|(narrowed, _)| *narrowed == id)
+            .map_or_else(|| self.symbols[id.0].ty.clone(), |(_, ty)| ty.clone())
+    }
+
+    /// The symbol `typeof <identifier>` refers to.
+    fn typeof_operand(&self, expr: &Expression) -> Option<SymbolId> {
+        let Expression::UnaryExpression(unary) = expr.without_parentheses() else {
+            return None;
+        };
+        let Expression::Identifier(ident) = unary.argument.without_parentheses() else {
+            return None;
+        };
+        if unary.operator != UnaryOperator::Typeof {
+            return None;
+        }
+        self.lookup(&ident.name)
+    }
+
+    /// The variable and property a discriminant `expr` reads: `x.kind`, or a constant
+    /// destructured from `x` as `const { kind } = x`.
+    fn discriminant_operand(&self, expr: &Expression) -> Option<(SymbolId, String)> {
+        match expr.without_parentheses() {
+            Expression::StaticMemberExpression(member) => {
+                let Expression::Identifier(object) = member.object.without_parentheses() else {
+                    return None;
+                };
+                Some((self.lookup(&object.name)?, member.property.name.to_string()))
+            }
+            Expression::Identifier(ident) => self
+                .discriminant_aliases
+                .get(&self.lookup(&ident.name)?)
+                .cloned(),
+            _ => None,
+        }
+    }
+
+    fn guard(&self, test: &Expression) -> Option<Guard> {
+        let binary = match test.without_parentheses() {
+            Expression::BinaryExpression(binary) => binary,
+            Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::LogicalNot => {
+                let guard = self.guard(&unary.argument)?;
+                return Some(Guard {
+                    equal: !guard.equal,
+                    ..guard
+                });
+            }
+            Expression::Identifier(ident) => {
+                let alias = self.lookup(&ident.name)?;
+                return self.condition_aliases.get(&alias).cloned();
+            }
+            _ => return None,
+        };
+        let equal = match binary.operator {
+            BinaryOperator::StrictEquality | BinaryOperator::Equality => true,
+            BinaryOperator::StrictInequality | BinaryOperator::Inequality => false,
+            _ => return None,
+        };
+        let (operand, literal) = match (compared_value(&binary.left), compared_value(&binary.right))
+        {
+            (_, Some(literal)) => (&binary.left, literal),
+            (Some(literal), _) => (&binary.right, literal),
+            _ => return None,
+        };
+        if let Some(symbol) = self.typeof_operand(operand)
+            && let Type::StringLiteral(result) = literal
+        {
+            return Some(Guard {
+                symbol,
+                test: GuardTest::Typeof(result),
+                equal,
+            });
+        }
+        if let Some((symbol, property)) = self.discriminant_operand(operand) {
+            return Some(Guard {
+                symbol,
+                test: GuardTest::Property(property, literal),
+                equal,
+            });
+        }
+        let Expression::Identifier(ident) = operand.without_parentheses() else {
+            return None;
+        };
+        let loose = matches!(
+            binary.operator,
+            BinaryOperator::Equality | BinaryOperator::Inequality
+        );
+        Some(Guard {
+            symbol: self.lookup(&ident.name)?,
+            test: GuardTest::Value(literal, loose),
+            equal,
+        })
+    }
+
+    /// What `guard` narrows its variable's current type to where it holds, and where it
+    /// doesn't.
+    fn guard_narrowing(&self, guard: &Guard) -> (Type, Type) {
+        let declared = self.symbol_type(guard.symbol);
+        let (matching, rest) = match &guard.test {
+            GuardTest::Typeof(result) => {
+                let results = [result.clone()];
+                (
+                    narrow_to_typeof(&declared, &results),
+                    narrow_away_typeof(&declared, &results),
+                )
+            }
+            GuardTest::Value(value, loose) => {
+                let values = matched_values(value, *loose);
+                (
+                    narrow_to_values(&declared, &values),
+                    narrow_away_values(&declared, &values),
+                )
+            }
+            GuardTest::Property(name, value) => {
+                let Type::Union(types) = declared.resolved() else {
+                    return (declared.clone(), declared);
+                };
+                // Members whose discriminant is some other literal can't match, and
+                // those whose discriminant is only this one always do
+                let values: Vec<_> = types
+                    .iter()
+                    .map(|member| self.property_type(member, name))
+                    .collect();
+                let values: Vec<_> = values
+                    .iter()
+                    .map(|ty| ty.as_ref().and_then(literal_values))
+                    .collect();
+                let pick = |keep: &dyn Fn(&Option<Vec<&Type>>) -> bool| {
+                    union_of(
+                        types
+                            .iter()
+                            .zip(&values)
+                            .filter(|(_, values)| keep(values))
+                            .map(|(member, _)| member.clone())
+                            .collect(),
+                    )
+                };
+                (
+                    pick(&|values| values.as_ref().is_none_or(|v| v.contains(&value))),
+                    pick(&|values| values.as_deref() != Some(&[value][..])),
+                )
+            }
+        };
+        match guard.equal {
+            true => (matching, rest),
+            false => (rest, matching),
+        }
+    }
+
+    /// Whether the variable `id` holds the same value wherever it's read: when it's a
+    /// constant, or never assigned at all.
+    fn is_unchanging(&self, id: SymbolId) -> bool {
+        let name = self.symbols[id.0].name;
+        self.constants.contains(&id)
+            || !self
+                .assignments
+                .iter()
+                .any(|(assigned, _)| *assigned == name)
+    }
+
+    /// Remembers the constant `id` as an alias for `init` when that's a `typeof` or
+    /// discriminant comparison, so testing the constant narrows as the comparison would.
+    ///
+    /// As in TypeScript, this only holds while the variable compared can't change.
+    pub(super) fn record_condition_alias(&mut self, id: SymbolId, init: &Expression) {
+        if let Some(guard) = self.guard(init)
+            && self.is_unchanging(guard.symbol)
+        {
+            self.condition_aliases.insert(id, guard);
+        }
+    }
+
+    /// Remembers the constant `id`, destructured as `property` from `init`, as that
+    /// property of the variable `init` names, so comparing it narrows the variable as
+    /// comparing `init.property` would.
+    pub(super) fn record_discriminant_alias(
+        &mut self,
+        id: SymbolId,
+        property: &str,
+        init: &Expression,
+    ) {
+        if let Expression::Identifier(source) = init.without_parentheses()
+            && let Some(source) = self.lookup(&source.name)
+            && self.is_unchanging(source)
+        {
+            self.discriminant_aliases
+                .insert(id, (source, property.to_string()));
+        }
+    }
+
+    /// Checks `stmts` as one branch of a conditional, with `narrowing` applied, and
+    /// returns the narrowings its assignments left at the end of it.
+    pub(super) fn check_branch(
+        &mut self,
+        narrowing: Option<(SymbolId, Type)>,
+        stmts: &[Statement],
+    ) -> Vec<(SymbolId, Type)> {
+        let entry = self.narrowed.len();
+        let assignments_from = entry + usize::from(narrowing.is_some());
+        self.narrowed.extend(narrowing);
+        self.check_statements(stmts);
+        let assigned = self.narrowed.split_off(assignments_from);
+        self.narrowed.truncate(entry);
+        assigned
+    }
+
+    /// Continues after branches that ended with the narrowings in `exits`: each variable
+    /// assigned in any of them may hold what any of them left it as.
+    pub(super) fn merge_branches(&mut self, exits: Vec<Vec<(SymbolId, Type)>>) {
+        let mut assigned: Vec<SymbolId> = Vec::new();
+        for (id, _) in exits.iter().flatten() {
+            if !assigned.contains(id) {
+                assigned.push(*id);
+            }
+        }
+        for id in assigned {
+            let entry = self.symbol_type(id);
+            let declared = &self.symbols[id.0].ty;
+            let merged = merge_types(
+                declared,
+                exits.iter().map(|exit| {
+                    exit.iter()
+                        .rev()
+                        .find(|(assigned, _)| *assigned == id)
+                        .map_or_else(|| entry.clone(), |(_, ty)| ty.clone())
+                }),
+            );
+            if merged != entry {
+                self.narrowed.push((id, merged));
+            }
+        }
+    }
+
+    pub(super) fn check_if_statement(&mut self, stmt: &IfStatement) {
+        self.check_expression(&stmt.test);
+        let (consequent, alternate) = match self.guard(&stmt.test) {
+            Some(guard) => {
+                let (consequent, alternate) = self.guard_narrowing(&guard);
+                (
+                    Some((guard.symbol, consequent)),
+                    Some((guard.symbol, alternate)),
+                )
+            }
+            None => (None, None),
+        };
+        let mut exits = Vec::new();
+        let exit = self.check_branch(consequent, std::slice::from_ref(&stmt.consequent));
+        if statement_completes_normally(&stmt.consequent) {
+            exits.push(exit);
+        }
+        match &stmt.alternate {
+            Some(stmt) => {
+                let exit = self.check_branch(alternate, std::slice::from_ref(stmt));
+                if statement_completes_normally(stmt) {
+                    exits.push(exit);
+                }
+            }
+            None => exits.push(Vec::new()),
+        }
+        self.merge_branches(exits);
+    }
+
+    pub(super) fn check_switch_statement(&mut self, stmt: &SwitchStatement) {
+        self.check_expression(&stmt.discriminant);
+        self.check_switch_fallthrough(stmt);
+        for case in &stmt.cases {
+            if let Some(test) = &case.test {
+                self.check_expression(test);
+            }
+        }
+        // Cases are left by `break` as often as by running off the end, and one may not
+        // match at all, so what each assigned is merged with what came before
+        let mut exits = vec![Vec::new()];
+        let Some(id) = self.typeof_operand(&stmt.discriminant) else {
+            for case in &stmt.cases {
+                exits.push(self.check_branch(None, &case.consequent));
+            }
+            self.merge_branches(exits);
+            return;
+        };
+
+        let declared = self.symbol_type(id);
+        let tested: Vec<String> = stmt
+            .cases
+            .iter()
+            .filter_map(|case| match &case.test {
+                Some(Expression::StringLiteral(literal)) => Some(literal.value.to_string()),
+                _ => None,
+            })
+            .collect();
+        // Cases without statements fall through, so they share the next case's narrowing
+        let mut falling: Option<Vec<String>> = Some(Vec::new());
+        for case in &stmt.cases {
+            let is_default = case.test.is_none() && falling.as_ref().is_some_and(Vec::is_empty);
+            falling = match (falling, &case.test) {
+                (Some(mut results), Some(Expression::StringLiteral(literal)))
+                    if TYPEOF_RESULTS.contains(&literal.value.as_str()) =>
+                {
+                    results.push(literal.value.to_string());
+                    Some(results)
+                }
+                _ => None,
+            };
+            if case.consequent.is_empty() {
+                continue;
+            }
+            let narrowed = match &falling {
+                Some(results) => narrow_to_typeof(&declared, results),
+                None if is_default => narrow_away_typeof(&declared, &tested),
+                None => declared.clone(),
+            };
+            exits.push(self.check_branch(Some((id, narrowed)), &case.consequent));
+            falling = Some(Vec::new());
+        }
+        self.merge_branches(exits);
+    }
+}
@@ -0,0 +1,780 @@
+use super::TypeChecker;
+use super::functions::function_type;
+use super::generics::instantiate;
+use crate::builtins::{apparent_properties, apparent_property};
+use crate::diagnostics::Message;
+use crate::symbols::SymbolKind;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+use std::sync::Arc;
+
+/// The type a property named `name` is expected to have in an object literal written
+/// where `expected` is wanted.
+fn expected_property_type(expected: &Type, name: &str) -> Option<Type> {
+    match expected.resolved() {
+        Type::ObjectType(object) => object
+            .property(name)
+            .map(|p| p.ty.clone())
+            .or_else(|| {
+                object
+                    .number_index
+                    .clone()
+                    .filter(|_| name.parse::<f64>().is_ok())
+            })
+            .or_else(|| object.string_index.clone()),
+        Type::Union(types) => {
+            let types: Vec<Type> = types
+                .iter()
+                .filter_map(|ty| expected_property_type(ty, name))
+                .collect();
+            (!types.is_empty()).then(|| union_of(types))
+        }
+        _ => None,
+    }
+}
+
+/// The name of a type literal member, writing a key computed from a constant, such as a
+/// `unique symbol` brand, as `[name]`.
+fn signature_key_name(key: &PropertyKey) -> Option<String> {
+    match key {
+        PropertyKey::Identifier(ident) => Some(format!("[{}]", ident.name)),
+        key => well_known_symbol_name(key).or_else(|| key.static_name().map(|n| n.to_string())),
+    }
+}
+
+/// The name of a member keyed by a well-known symbol, like `[Symbol.iterator]`.
+pub(super) fn well_known_symbol_name(key: &PropertyKey) -> Option<String> {
+    let PropertyKey::StaticMemberExpression(member) = key else {
+        return None;
+    };
+    match &member.object {
+        Expression::Identifier(object) if object.name == "Symbol" => {
+            Some(format!("[Symbol.{}]", member.property.name))
+        }
+        _ => None,
+    }
+}
+
+/// `Record<keys, value>`: a property of type `value` for each literal key, and an index
+/// signature for `string` or `number`.
+pub(super) fn record_type(keys: &Type, value: Type) -> Type {
+    let mut object = ObjectType::default();
+    let keys = match keys.resolved() {
+        Type::Union(keys) => keys.clone(),
+        key => vec![key.clone()],
+    };
+    for key in keys {
+        match key.resolved() {
+            Type::StringLiteral(name) => object.properties.push(Property::new(name, value.clone())),
+            Type::NumberLiteral(number) => object
+                .properties
+                .push(Property::new(number.to_string(), value.clone())),
+            Type::Number => object.number_index = Some(value.clone()),
+            Type::String | Type::Any => object.string_index = Some(value.clone()),
+            _ => {}
+        }
+    }
+    Type::ObjectType(Arc::new(object))
+}
+
+impl TypeChecker {
+    /// A mapped type `{ [K in Keys]: Value }`: a property for each literal key with `Value`
+    /// instantiated for it, and an index signature for `string` or `number`. Keys this
+    /// checker can't list, like `keyof T`, and keys renamed with `as` make it `any`.
+    pub(super) fn check_mapped_type(&self, mapped: &TSMappedType) -> Type {
+        let param = &mapped.type_parameter;
+        let keys = param
+            .constraint
+            .as_ref()
+            .map_or(Type::Any, |keys| self.check_type(keys));
+        if mapped.name_type.is_some() || matches!(keys.resolved(), Type::Any) {
+            return Type::Any;
+        }
+        let key = Arc::new(TypeParameter {
+            name: param.name.name.to_string(),
+            constraint: Some(keys.clone()),
+            default: None,
+            is_const: false,
+        });
+        self.mapped_keys.borrow_mut().push(key.clone());
+        let template = mapped
+            .type_annotation
+            .as_ref()
+            .map_or(Type::Any, |value| self.check_type(value));
+        self.mapped_keys.borrow_mut().pop();
+        let value_for = |key_type: Type| instantiate(&template, &[(key.clone(), key_type)]);
+        let is_set = |modifier| {
+            matches!(
+                modifier,
+                TSMappedTypeModifierOperator::True | TSMappedTypeModifierOperator::Plus
+            )
+        };
+        let (optional, readonly) = (is_set(mapped.optional), is_set(mapped.readonly));
+
+        let mut object = ObjectType::default();
+        let keys = match keys.resolved() {
+            Type::Union(keys) => keys.clone(),
+            key => vec![key.clone()],
+        };
+        for key_type in keys {
+            let name = match key_type.resolved() {
+                Type::StringLiteral(name) => name.clone(),
+                Type::NumberLiteral(number) => number.to_string(),
+                Type::Number => {
+                    object.number_index = Some(value_for(Type::Number));
+                    continue;
+                }
+                Type::String => {
+                    object.string_index = Some(value_for(Type::String));
+                    continue;
+                }
+                _ => continue,
+            };
+            let ty = self.optional_type(value_for(key_type), optional);
+            object.properties.push(Property {
+                optional,
+                readonly,
+                ..Property::new(name, ty)
+            });
+        }
+        Type::ObjectType(Arc::new(object))
+    }
+}
+
+/// What an object literal property's key contributes to the literal's type.
+enum PropertyKeyKind {
+    /// A key known while checking, from its name or a literal-typed computed key.
+    Named(String),
+    /// A computed key of a general string type, which becomes a string index signature.
+    String,
+    /// A computed key of type `number`, which becomes a number index signature.
+    Number,
+}
+
+/// Whether `ty` is `null` or `undefined`, which reading a property of is reported
+/// separately from the property missing.
+fn is_nullish(ty: &Type) -> bool {
+    matches!(ty.resolved(), Type::Null | Type::Undefined | Type::Void)
+}
+
+/// The `call`, `apply` or `bind` method of `function`, typed after its signature as
+/// under strictBindCallApply. `bind` returns the whole function; the call binding it
+/// drops the parameters it binds.
+fn bound_method(function: &Type, name: &str) -> Option<Type> {
+    let Type::Function {
+        params,
+        return_type,
+        required,
+        rest,
+    } = function.resolved()
+    else {
+        return None;
+    };
+    // Each takes what `this` is first
+    let method = |params: &[Type], required: usize, rest: bool, return_type: Type| Type::Function {
+        params: [Type::Any].iter().chain(params).cloned().collect(),
+        return_type: Arc::new(return_type),
+        required: required + 1,
+        rest,
+    };
+    match name {
+        "call" => Some(method(params, *required, *rest, (**return_type).clone())),
+        "apply" => {
+            // Arguments that may be left out can't be told apart in an array
+            let arguments = match *rest || *required < params.len() {
+                false => Type::Tuple(params.clone()),
+                true => Type::Array(Arc::new(union_of(
+                    (0..params.len())
+                        .filter_map(|index| Type::parameter_at(params, *rest, index))
+                        .collect(),
+                ))),
+            };
+            let required = usize::from(*required > 0);
+            Some(method(
+                &[arguments],
+                required,
+                false,
+                (**return_type).clone(),
+            ))
+        }
+        "bind" => Some(method(params, 0, *rest, function.clone())),
+        _ => None,
+    }
+}
+
+/// The union of `types`, each listed once.
+fn union_of_distinct(types: impl IntoIterator<Item = Type>) -> Type {
+    let mut members: Vec<Type> = Vec::new();
+    for ty in types {
+        if !members.contains(&ty) {
+            members.push(ty);
+        }
+    }
+    union_of(members)
+}
+
+impl TypeChecker {
+    /// The type of an object literal. Where an object type is expected, its members are
+    /// the context each property's value is checked in.
+    pub(super) fn check_object_expression(&mut self, object: &ObjectExpression) -> Type {
+        let contextual = self
+            .contextual_type
+            .as_ref()
+            .filter(|(span, _)| *span == object.span)
+            .map(|(_, ty)| ty.clone());
+        let mut members: Vec<(&ObjectProperty, PropertyKeyKind)> = Vec::new();
+        for property in &object.properties {
+            let ObjectPropertyKind::ObjectProperty(property) = property else {
+                continue;
+            };
+            if let Some(key) = self.property_key_kind(&property.key) {
+                members.push((property, key));
+            }
+        }
+        // Methods and accessors are checked once the other values are known, since `this`
+        // in them is the literal
+        let is_method =
+            |property: &ObjectProperty| property.method || property.kind != PropertyKind::Init;
+        let mut values: Vec<Option<Type>> = Vec::new();
+        for (property, key) in &members {
+            values.push(match is_method(property) {
+                true => None,
+                false => Some(self.check_property_value(property, key, contextual.as_ref())),
+            });
+        }
+        let this_type = match &contextual {
+            _ if !self.strictness.no_implicit_this => None,
+            Some(contextual) => Some(contextual.clone()),
+            // Methods aren't known yet, so they're `any` in the literal's own type
+            None => Some(self.object_literal_type(members.iter().zip(&values).map(
+                |((property, key), value)| (*property, key, value.clone().unwrap_or(Type::Any)),
+            ))),
+        };
+        for ((property, key), value) in members.iter().zip(&mut values) {
+            if value.is_none() {
+                self.method_this = this_type.clone();
+                *value = Some(self.check_property_value(property, key, contextual.as_ref()));
+                self.method_this = None;
+            }
+        }
+        self.object_literal_type(
+            members
+                .iter()
+                .zip(values)
+                .map(|((property, key), value)| (*property, key, value.unwrap_or(Type::Any))),
+        )
+    }
+
+    /// Checks the value of an object literal property, in the context of the type the
+    /// literal is expected to give it.
+    fn check_property_value(
+        &mut self,
+        property: &ObjectProperty,
+        key: &PropertyKeyKind,
+        contextual: Option<&Type>,
+    ) -> Type {
+        let expected = contextual.and_then(|contextual| match key {
+            PropertyKeyKind::Named(name) => expected_property_type(contextual, name),
+            PropertyKeyKind::String | PropertyKeyKind::Number => match contextual.resolved() {
+                Type::ObjectType(object) => object.string_index.clone(),
+                _ => None,
+            },
+        });
+        match &expected {
+            // An accessor's function isn't the property's value
+            Some(expected) if property.kind == PropertyKind::Init => {
+                self.check_expression_in_context(&property.value, expected)
+            }
+            _ => self.check_expression(&property.value),
+        }
+    }
+
+    /// The type of an object literal with these properties and the types of their values,
+    /// where an accessor's value is its function.
+    fn object_literal_type<'p>(
+        &self,
+        members: impl Iterator<Item = (&'p ObjectProperty<'p>, &'p PropertyKeyKind, Type)>,
+    ) -> Type {
+        let mut properties: Vec<Property> = Vec::new();
+        let mut property_spans = Vec::new();
+        // Values under computed keys that aren't a single literal
+        let mut string_indexed: Vec<Type> = Vec::new();
+        let mut number_indexed: Vec<Type> = Vec::new();
+        for (property, key, value_type) in members {
+            let accessor = match (property.kind, value_type.resolved()) {
+                (PropertyKind::Get, Type::Function { return_type, .. }) => {
+                    Some(((**return_type).clone(), true))
+                }
+                (PropertyKind::Set, Type::Function { params, .. }) => {
+                    Some((params.first().cloned().unwrap_or(Type::Any), false))
+                }
+                _ => None,
+            };
+            if let PropertyKeyKind::Named(name) = key {
+                property_spans.push((name.as_str().into(), property.key.span()));
+            }
+            match key {
+                PropertyKeyKind::Named(name) => match accessor {
+                    Some((ty, is_getter)) => add_accessor(&mut properties, name, ty, is_getter),
+                    None => {
+                        // Later duplicates win, as at runtime
+                        properties.retain(|p| p.name != name.as_str());
+                        properties.push(Property {
+                            readonly: self.const_context,
+                            ..Property::new(name.as_str(), value_type)
+                        });
+                    }
+                },
+                PropertyKeyKind::String => {
+                    string_indexed.push(accessor.map_or(value_type, |(ty, _)| ty))
+                }
+                PropertyKeyKind::Number => {
+                    number_indexed.push(accessor.map_or(value_type, |(ty, _)| ty))
+                }
+            }
+        }
+
+        // As in TypeScript, index signatures also cover the named properties they'd match
+        let number_index = (!number_indexed.is_empty()).then(|| {
+            let numeric = properties
+                .iter()
+                .filter(|p| p.name.parse::<f64>().is_ok())
+                .map(|p| p.ty.clone());
+            union_of_distinct(number_indexed.iter().cloned().chain(numeric))
+        });
+        let string_index = (!string_indexed.is_empty()).then(|| {
+            let named = properties.iter().map(|p| p.ty.clone());
+            union_of_distinct(
+                string_indexed
+                    .into_iter()
+                    .chain(number_indexed)
+                    .chain(named),
+            )
+        });
+        Type::ObjectType(Arc::new(ObjectType {
+            string_index,
+            number_index,
+            fresh: true,
+            property_spans,
+            ..ObjectType::new(properties)
+        }))
+    }
+
+    /// Checks a computed key's expression and says what the key contributes. Keys that
+    /// can't name a member, like symbols, contribute nothing.
+    fn property_key_kind(&mut self, key: &PropertyKey) -> Option<PropertyKeyKind> {
+        if let Some(name) = well_known_symbol_name(key) {
+            return Some(PropertyKeyKind::Named(name));
+        }
+        if let Some(name) = key.static_name() {
+            return Some(PropertyKeyKind::Named(name.into_owned()));
+        }
+        let key_type = self.check_expression(key.as_expression()?);
+        match key_type.resolved() {
+            Type::StringLiteral(name) => Some(PropertyKeyKind::Named(name.clone())),
+            Type::NumberLiteral(value) => Some(PropertyKeyKind::Named(value.to_string())),
+            Type::Number => Some(PropertyKeyKind::Number),
+            Type::Symbol => None,
+            _ => Some(PropertyKeyKind::String),
+        }
+    }
+
+    pub(super) fn check_static_member_expression(
+        &mut self,
+        member: &StaticMemberExpression,
+    ) -> Type {
+        let object_type = self.check_member_object(&member.object);
+        if self.report_unknown_operand(&member.object, &object_type) {
+            return Type::Any;
+        }
+        self.member_type(&object_type, &member.property)
+    }
+
+    /// Reports using a value of type `unknown` as more than a value, which it must be
+    /// narrowed before it can be. Returns whether it reported one.
+    pub(super) fn report_unknown_operand(&mut self, expr: &Expression, ty: &Type) -> bool {
+        if *ty.resolved() != Type::Unknown {
+            return false;
+        }
+        match expr.without_parentheses() {
+            Expression::Identifier(ident) => self.error(18046, &[&ident.name], expr.span()),
+            _ => self.error(2571, &[], expr.span()),
+        }
+        true
+    }
+
+    /// The type of `property` on `object_type`, reporting properties that don't exist.
+    pub(super) fn member_type(&mut self, object_type: &Type, property: &IdentifierName) -> Type {
+        match self.property_type(object_type, &property.name) {
+            Some(ty) => ty,
+            None => {
+                self.error(2339, &[&property.name, object_type], property.span);
+                Type::ERROR
+            }
+        }
+    }
+
+    /// Declares the variables an object pattern binds from a value of type `ty`, each
+    /// with the type of the property it's bound to. Constants destructured straight from
+    /// a variable stay linked to it, so comparing one narrows the variable.
+    pub(super) fn declare_object_pattern(
+        &mut self,
+        pattern: &ObjectPattern,
+        ty: &Type,
+        kind: VariableDeclarationKind,
+        init: &Option<Expression>,
+    ) {
+        let declare = |checker: &mut Self, ident: &BindingIdentifier, ty: Type| {
+            let id = checker.add_symbol(&ident.name, SymbolKind::Variable, ty, ident.span);
+            checker.bind(&ident.name, id, kind.is_var());
+            if kind.is_const() {
+                checker.constants.insert(id);
+            }
+            id
+        };
+        for property in &pattern.properties {
+            let Some(name) = property.key.static_name() else {
+                continue;
+            };
+            let (ident, default) = match &property.value.kind {
+                BindingPatternKind::BindingIdentifier(ident) => (ident, None),
+                BindingPatternKind::AssignmentPattern(assignment) => match &assignment.left.kind {
+                    BindingPatternKind::BindingIdentifier(ident) => {
+                        (ident, Some(&assignment.right))
+                    }
+                    _ => continue,
+                },
+                _ => continue,
+            };
+            let mut property_type = match self.property_type(ty, &name) {
+                Some(ty) => ty,
+                None => {
+                    self.error(2339, &[&name, ty], property.key.span());
+                    Type::ERROR
+                }
+            };
+            // A default stands in for `undefined`
+            if let Some(default) = default {
+                let default = self.check_expression(default);
+                let defined = match property_type.resolved() {
+                    Type::Union(types) => types
+                        .iter()
+                        .filter(|ty| *ty.resolved() != Type::Undefined)
+                        .cloned()
+                        .collect(),
+                    Type::Undefined => Vec::new(),
+                    ty => vec![ty.clone()],
+                };
+                property_type = union_of(defined.into_iter().chain([default]).collect());
+            }
+            let id = declare(self, ident, property_type);
+            if kind.is_const()
+                && let Some(init) = init
+            {
+                self.record_discriminant_alias(id, &name, init);
+            }
+        }
+        if let Some(rest) = &pattern.rest
+            && let BindingPatternKind::BindingIdentifier(ident) = &rest.argument.kind
+        {
+            // The rest holds what the named properties leave
+            let rest_type = match ty.resolved() {
+                Type::ObjectType(object) => {
+                    let mut object = (**object).clone();
+                    object.properties.retain(|p| {
+                        !pattern
+                            .properties
+                            .iter()
+                            .any(|named| named.key.static_name().as_deref() == Some(&*p.name))
+                    });
+                    Type::ObjectType(Arc::new(object))
+                }
+                _ => Type::Any,
+            };
+            declare(self, ident, rest_type);
+        }
+    }
+
+    pub(super) fn check_computed_member_expression(
+        &mut self,
+        member: &ComputedMemberExpression,
+    ) -> Type {
+        let object_type = self.check_member_object(&member.object);
+        let key_type = self.check_expression(&member.expression);
+        if !self.check_const_enum_index(member)
+            || self.report_unknown_operand(&member.object, &object_type)
+        {
+            return Type::ERROR;
+        }
+        // Literal keys are looked up by value, not just by their primitive type
+        let key_type = match &member.expression {
+            Expression::StringLiteral(literal) => Type::StringLiteral(literal.value.to_string()),
+            Expression::NumericLiteral(literal) => Type::NumberLiteral(literal.value),
+            _ => key_type,
+        };
+        match self.element_type(&object_type, &key_type) {
+            Ok(ty) => ty,
+            Err(message) => {
+                self.report(message, member.expression.span());
+                Type::ERROR
+            }
+        }
+    }
+
+    /// The type of `object[key]`, or the message explaining why `key` can't index it.
+    pub(super) fn element_type(&self, object: &Type, key: &Type) -> Result<Type, Message> {
+        let (object, key) = (object.resolved(), key.resolved());
+        let object = match object {
+            Type::Readonly(ty) => ty.resolved(),
+            object => object,
+        };
+        let is_numeric = matches!(key, Type::Number | Type::NumberLiteral(_) | Type::Any);
+        match key {
+            Type::Any
+            | Type::Number
+            | Type::NumberLiteral(_)
+            | Type::String
+            | Type::StringLiteral(_)
+            | Type::Symbol => {}
+            Type::Union(types) => {
+                let types = types
+                    .iter()
+                    .map(|key| self.element_type(object, key))
+                    .collect::<Result<Vec<_>, _>>()?;
+                return Ok(union_of(types));
+            }
+            _ => return Err(Message::new(2538, &[key])),
+        }
+        if let Type::StringLiteral(name) = key
+            && !matches!(object, Type::ObjectType(_))
+        {
+            return self
+                .property_type(object, name)
+                .ok_or_else(|| Message::new(2339, &[name, object]));
+        }
+        // Without noImplicitAny, indexing with no matching signature is quietly `any`
+        let no_index = || match self.strictness.no_implicit_any {
+            true => Err(Message::new(7053, &[key, object])),
+            false => Ok(Type::Any),
+        };
+        match object {
+            Type::Any => Ok(Type::Any),
+            Type::Array(elem) if is_numeric => Ok((**elem).clone()),
+            Type::String | Type::StringLiteral(_) if is_numeric => Ok(Type::String),
+            Type::Tuple(types) => match key {
+                Type::NumberLiteral(index) => types
+                    .get(*index as usize)
+                    .cloned()
+                    .ok_or_else(|| Message::new(2493, &[object, &types.len(), index])),
+                _ if is_numeric => Ok(union_of(types.clone())),
+                _ => no_index(),
+            },
+            Type::ObjectType(shape) => {
+                if let Type::StringLiteral(name) = key
+                    && let Some(property) = shape.property(name)
+                {
+                    return Ok(property.ty.clone());
+                }
+                let index = match is_numeric {
+                    true => shape.number_index.as_ref().or(shape.string_index.as_ref()),
+                    false => shape.string_index.as_ref(),
+                };
+                match (index, key) {
+                    (Some(ty), _) => Ok(ty.clone()),
+                    (None, Type::StringLiteral(name)) => Err(Message::new(2339, &[name, object])),
+                    (None, _) => no_index(),
+                }
+            }
+            _ => Ok(Type::Any),
+        }
+    }
+
+    /// The type of `name` on `ty`, or `None` when `ty` is known not to have it.
+    ///
+    /// Types whose members aren't modeled yet resolve every property to `any`.
+    pub fn property_type(&self, ty: &Type, name: &str) -> Option<Type> {
+        match ty.resolved() {
+            Type::ObjectType(object) => object
+                .property(name)
+                .map(|p| p.ty.clone())
+                .or_else(|| object.string_index.clone()),
+            Type::Class(class) => class.statics.property(name).map(|p| p.ty.clone()),
+            Type::Intersection(types) => {
+                let mut found: Vec<Type> = types
+                    .iter()
+                    .filter_map(|member| match member.resolved() {
+                        Type::ObjectType(_) | Type::Class(_) => self.property_type(member, name),
+                        _ => apparent_property(member, name).map(|p| p.ty),
+                    })
+                    .collect();
+                match found.len() {
+                    0 | 1 => found.pop(),
+                    _ => Some(intersection_of(found)),
+                }
+            }
+            // Only what every member has can be read from a union
+            Type::Union(types) => {
+                let found = types
+                    .iter()
+                    .filter(|member| !is_nullish(member))
+                    .map(|member| self.union_member_property(member, name))
+                    .collect::<Option<Vec<Type>>>()?;
+                Some(union_of_distinct(found.into_iter().flat_map(
+                    |ty| match ty {
+                        Type::Union(types) => types,
+                        ty => vec![ty],
+                    },
+                )))
+            }
+            Type::Function { .. } if self.strictness.strict_bind_call_apply => {
+                Some(bound_method(ty, name).unwrap_or(Type::Any))
+            }
+            _ => Some(apparent_property(ty, name).map_or(Type::Any, |p| p.ty)),
+        }
+    }
+
+    /// The type of property `name` on one member of a union, or `None` when the member
+    /// certainly lacks it. Members whose properties aren't known have any property.
+    fn union_member_property(&self, member: &Type, name: &str) -> Option<Type> {
+        match member.resolved() {
+            Type::ObjectType(_) | Type::Class(_) | Type::Intersection(_) | Type::Union(_) => {
+                self.property_type(member, name)
+            }
+            Type::Any => Some(Type::Any),
+            ty => {
+                let properties = apparent_properties(ty);
+                match properties.is_empty() {
+                    true => Some(Type::Any),
+                    false => properties
+                        .into_iter()
+                        .find(|p| p.name == name)
+                        .map(|p| p.ty),
+                }
+            }
+        }
+    }
+
+    /// All properties known to exist on `ty`.
+    pub fn properties_of(&self, ty: &Type) -> Vec<Property> {
+        match ty.resolved() {
+            Type::ObjectType(object) => object.properties.clone(),
+            Type::Class(class) => class.statics.properties.clone(),
+            Type::Intersection(types) => {
+                let mut properties: Vec<Property> = Vec::new();
+                for property in types.iter().flat_map(|member| self.properties_of(member)) {
+                    merge_property(&mut properties, property);
+                }
+                properties
+            }
+            Type::Union(types) => {
+                let members: Vec<&Type> = types.iter().filter(|ty| !is_nullish(ty)).collect();
+                let Some((first, rest)) = members.split_first() else {
+                    return Vec::new();
+                };
+                self.properties_of(first)
+                    .into_iter()
+                    .filter(|property| {
+                        rest.iter().all(|member| {
+                            self.union_member_property(member, &property.name).is_some()
+                        })
+                    })
+                    .filter_map(|property| {
+                        let ty = self.property_type(ty, &property.name)?;
+                        Some(Property { ty, ..property })
+                    })
+                    .collect()
+            }
+            _ => apparent_properties(ty),
+        }
+    }
+
+    pub(super) fn check_type_literal(&self, literal: &TSTypeLiteral) -> Type {
+        let (object, call_signature) = self.signature_members(&literal.members);
+        let object = Type::ObjectType(Arc::new(object));
+        // A callable object is both a function and an object with its other members
+        match call_signature {
+            Some(function) if literal.members.len() == 1 => function,
+            Some(function) => intersection_of(vec![function, object]),
+            None => object,
+        }
+    }
+
+    /// The members a type literal or interface body declares, and the first of its call
+    /// signatures, which stands in for any overloads.
+    pub(super) fn signature_members(&self, members: &[TSSignature]) -> (ObjectType, Option<Type>) {
+        let mut properties = Vec::new();
+        let mut object = ObjectType::default();
+        let mut call_signature = None;
+        for member in members {
+            match member {
+                TSSignature::TSCallSignatureDeclaration(signature) if call_signature.is_none() => {
+                    let params = self.signature_parameters(&signature.params);
+                    let return_type = signature
+                        .return_type
+                        .as_ref()
+                        .map_or(Type::Any, |ann| self.check_type(&ann.type_annotation));
+                    call_signature = Some(function_type(&signature.params, params, return_type));
+                }
+                TSSignature::TSIndexSignature(signature) => {
+                    let ty = self.check_type(&signature.type_annotation.type_annotation);
+                    let Some(key) = signature.parameters.first() else {
+                        continue;
+                    };
+                    match self.check_type(&key.type_annotation.type_annotation) {
+                        Type::Number => object.number_index = Some(ty),
+                        _ => object.string_index = Some(ty),
+                    }
+                }
+                TSSignature::TSPropertySignature(signature) => {
+                    let Some(name) = signature_key_name(&signature.key) else {
+                        continue;
+                    };
+                    let ty = signature
+                        .type_annotation
+                        .as_ref()
+                        .map_or(Type::Any, |ann| self.check_type(&ann.type_annotation));
+                    let ty = self.optional_type(ty, signature.optional);
+                    properties.push(Property {
+                        optional: signature.optional,
+                        readonly: signature.readonly,
+                        ..Property::new(name, ty)
+                    });
+                }
+                TSSignature::TSMethodSignature(signature) => {
+                    let Some(name) = signature_key_name(&signature.key) else {
+                        continue;
+                    };
+                    let mut params = self.signature_parameters(&signature.params);
+                    let return_type = signature
+                        .return_type
+                        .as_ref()
+                        .map_or(Type::Any, |ann| self.check_type(&ann.type_annotation));
+                    match signature.kind {
+                        TSMethodSignatureKind::Get => {
+                            add_accessor(&mut properties, &name, return_type, true);
+                            continue;
+                        }
+                        TSMethodSignatureKind::Set => {
+                            let ty = match params.is_empty() {
+                                true => Type::Any,
+                                false => params.remove(0),
+                            };
+                            add_accessor(&mut properties, &name, ty, false);
+                            continue;
+                        }
+                        TSMethodSignatureKind::Method => {}
+                    }
+                    let method = function_type(&signature.params, params, return_type);
+                    properties.push(Property {
+                        optional: signature.optional,
+                        ..Property::new(name, self.optional_type(method, signature.optional))
+                    });
+                }
+                _ => {}
+            }
+        }
+        object.properties = properties;
+        (object, call_signature)
+    }
+}
@@ -0,0 +1,243 @@
+use super::TypeChecker;
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::GetSpan;
+use oxc_syntax::operator::UnaryOperator;
+
+/// The strings `typeof` can evaluate to.
+pub(super) const TYPEOF_RESULTS: [&str; 8] = [
+    "string",
+    "number",
+    "bigint",
+    "boolean",
+    "symbol",
+    "undefined",
+    "object",
+    "function",
+];
+
+/// How a type behaves as the operand of an arithmetic operator.
+enum Numeric {
+    Number,
+    BigInt,
+    Invalid,
+}
+
+fn numeric_kind(ty: &Type) -> Numeric {
+    match ty.resolved() {
+        Type::Any | Type::Number | Type::NumberLiteral(_) => Numeric::Number,
+        Type::BigInt | Type::BigIntLiteral(_) => Numeric::BigInt,
+        Type::Union(types) => {
+            let kinds: Vec<Numeric> = types.iter().map(numeric_kind).collect();
+            if kinds.iter().all(|k| matches!(k, Numeric::Number)) {
+                Numeric::Number
+            } else if kinds.iter().all(|k| matches!(k, Numeric::BigInt)) {
+                Numeric::BigInt
+            } else {
+                Numeric::Invalid
+            }
+        }
+        _ => Numeric::Invalid,
+    }
+}
+
+/// Whether `ty` is a symbol, which can't be converted to a number.
+fn is_symbol(ty: &Type) -> bool {
+    match ty.resolved() {
+        Type::Symbol | Type::UniqueSymbol(_) => true,
+        Type::Union(types) => types.iter().any(is_symbol),
+        _ => false,
+    }
+}
+
+/// Whether `undefined` is assignable to `ty`, so removing the value is allowed.
+fn can_be_undefined(ty: &Type) -> bool {
+    match ty.resolved() {
+        Type::Any | Type::Unknown | Type::Undefined | Type::Void => true,
+        Type::Union(types) => types.iter().any(can_be_undefined),
+        _ => false,
+    }
+}
+
+/// The value of a number literal, negated or not, like `-1`. `-0` is `0`, as its literal
+/// type is in TypeScript.
+pub(super) fn numeric_literal_value(expr: &Expression) -> Option<f64> {
+    match expr.without_parentheses() {
+        Expression::NumericLiteral(literal) => Some(literal.value),
+        Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::UnaryNegation => {
+            numeric_literal_value(&unary.argument).map(|value| 0.0 - value)
+        }
+        _ => None,
+    }
+}
+
+/// The value of a bigint literal, negated or not, like `-1n`, in decimal.
+pub(super) fn bigint_literal_value(expr: &Expression) -> Option<String> {
+    match expr.without_parentheses() {
+        Expression::BigIntLiteral(literal) => parse_bigint_literal(&literal.raw),
+        Expression::UnaryExpression(unary) if unary.operator == UnaryOperator::UnaryNegation => {
+            bigint_literal_value(&unary.argument).map(|value| negated_bigint(&value))
+        }
+        _ => None,
+    }
+}
+
+/// The decimal bigint `value` with its sign flipped, leaving `0` as it is.
+pub(super) fn negated_bigint(value: &str) -> String {
+    match value.strip_prefix('-') {
+        Some(positive) => positive.to_string(),
+        None if value == "0" => value.to_string(),
+        None => format!("-{}", value),
+    }
+}
+
+/// The type of `expr`, keeping the value of literals so `"a" === "b"` can be caught.
+fn literal_type(expr: &Expression, ty: Type) -> Type {
+    if let Some(value) = numeric_literal_value(expr) {
+        return Type::NumberLiteral(value);
+    }
+    if let Some(value) = bigint_literal_value(expr) {
+        return Type::BigIntLiteral(value);
+    }
+    match expr.without_parentheses() {
+        Expression::StringLiteral(literal) => Type::StringLiteral(literal.value.to_string()),
+        Expression::BooleanLiteral(literal) => Type::BooleanLiteral(literal.value),
+        _ => ty,
+    }
+}
+
+/// Whether some value could have both types, so comparing them can be true.
+fn is_comparable(left: &Type, right: &Type) -> bool {
+    match (left.resolved(), right.resolved()) {
+        (Type::Any | Type::Unknown | Type::Never, _)
+        | (_, Type::Any | Type::Unknown | Type::Never) => true,
+        // Anything may be checked against `null` and `undefined`
+        (Type::Null | Type::Undefined, _) | (_, Type::Null | Type::Undefined) => true,
+        (Type::Union(types), other) | (other, Type::Union(types)) => {
+            types.iter().any(|ty| is_comparable(ty, other))
+        }
+        _ => check_type_compatibility(left, right) || check_type_compatibility(right, left),
+    }
+}
+
+impl TypeChecker {
+    pub(super) fn check_unary_expression(&mut self, expr: &UnaryExpression) -> Type {
+        if expr.operator == UnaryOperator::Delete {
+            return self.check_delete_expression(expr);
+        }
+        let operand = self.check_expression(&expr.argument);
+        if expr.operator == UnaryOperator::UnaryNegation
+            && matches!(expr.argument, Expression::NumericLiteral(_))
+            && self.keeps_literal(expr.span)
+            && let Some(value) = numeric_literal_value(&expr.argument)
+        {
+            return Type::NumberLiteral(0.0 - value);
+        }
+        if expr.operator == UnaryOperator::UnaryNegation
+            && matches!(expr.argument, Expression::BigIntLiteral(_))
+            && self.keeps_literal(expr.span)
+            && let Some(value) = bigint_literal_value(&expr.argument)
+        {
+            return Type::BigIntLiteral(negated_bigint(&value));
+        }
+        // `-`, `~` and `+` convert anything but a symbol to a number
+        if matches!(
+            expr.operator,
+            UnaryOperator::UnaryNegation | UnaryOperator::BitwiseNot | UnaryOperator::UnaryPlus
+        ) && is_symbol(&operand)
+        {
+            self.error(2469, &[&expr.operator.as_str()], expr.span);
+        }
+        match expr.operator {
+            UnaryOperator::UnaryNegation | UnaryOperator::BitwiseNot => {
+                match numeric_kind(&operand) {
+                    Numeric::BigInt => Type::BigInt,
+                    _ => Type::Number,
+                }
+            }
+            UnaryOperator::UnaryPlus => {
+                if matches!(numeric_kind(&operand), Numeric::BigInt) {
+                    self.error(2736, &[&"+", &operand], expr.span);
+                }
+                Type::Number
+            }
+            UnaryOperator::LogicalNot | UnaryOperator::Delete => Type::Boolean,
+            UnaryOperator::Typeof => Type::Union(
+                TYPEOF_RESULTS
+                    .iter()
+                    .map(|result| Type::StringLiteral(result.to_string()))
+                    .collect(),
+            ),
+            UnaryOperator::Void => Type::Undefined,
+        }
+    }
+
+    fn check_delete_expression(&mut self, expr: &UnaryExpression) -> Type {
+        let Expression::StaticMemberExpression(member) = expr.argument.without_parentheses() else {
+            self.check_expression(&expr.argument);
+            if !matches!(
+                expr.argument.without_parentheses(),
+                Expression::ComputedMemberExpression(_)
+                    | Expression::PrivateFieldExpression(_)
+                    | Expression::ChainExpression(_)
+            ) {
+                self.error(2703, &[], expr.argument.span());
+            }
+            return Type::Boolean;
+        };
+
+        let object_type = self.check_member_object(&member.object);
+        let ty = self.member_type(&object_type, &member.property);
+        self.node_types.push((member.span, ty.clone()));
+        if let Type::ObjectType(object) = &object_type
+            && let Some(property) = object.property(&member.property.name)
+        {
+            if property.readonly {
+                self.error(2704, &[], member.span);
+            } else if !property.optional && !can_be_undefined(&property.ty) {
+                self.error(2790, &[], member.span);
+            }
+        }
+        Type::Boolean
+    }
+
+    /// Reports `===`, `!==`, `==`, and `!=` between types that can never be equal.
+    pub(super) fn check_comparison(&mut self, expr: &BinaryExpression, left: &Type, right: &Type) {
+        let left = literal_type(&expr.left, left.clone());
+        let right = literal_type(&expr.right, right.clone());
+        if !is_comparable(&left, &right) {
+            self.error(2367, &[&left, &right], expr.span);
+        }
+    }
+
+    pub(super) fn check_update_expression(&mut self, expr: &UpdateExpression) -> Type {
+        let operand = match &expr.argument {
+            SimpleAssignmentTarget::AssignmentTargetIdentifier(ident) => {
+                match self.lookup(&ident.name) {
+                    Some(id) => {
+                        self.references.push((ident.span, id));
+                        self.symbol_type(id)
+                    }
+                    None => Type::Any,
+                }
+            }
+            SimpleAssignmentTarget::StaticMemberExpression(member) => {
+                self.check_static_member_expression(member)
+            }
+            _ => Type::Any,
+        };
+        self.arithmetic_result(&operand, expr.span)
+    }
+
+    /// The result of `++` or `--` on `operand`, reporting non-numeric operands.
+    fn arithmetic_result(&mut self, operand: &Type, span: Span) -> Type {
+        match numeric_kind(operand) {
+            Numeric::Number => Type::Number,
+            Numeric::BigInt => Type::BigInt,
+            Numeric::Invalid => {
+                self.error(2356, &[], span);
+                Type::Number
+            }
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use super::TypeChecker;
+use crate::builtins::global_values;
+use crate::intern::Atom;
+use crate::symbols::{Symbol, SymbolId, SymbolKind};
+use crate::types::*;
+use oxc_ast::ast::*;
+use oxc_span::Span;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// The names declared directly in a file, block, or function.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Scope {
+    span: Span,
+    /// Whether `var` declarations inside belong here rather than to an enclosing scope.
+    is_function: bool,
+    names: HashMap<Atom, SymbolId>,
+}
+
+impl Scope {
+    pub(super) fn module() -> Self {
+        Self {
+            is_function: true,
+            ..Self::default()
+        }
+    }
+
+    pub(super) fn names(&self) -> impl Iterator<Item = &SymbolId> {
+        self.names.values()
+    }
+}
+
+/// The function declaration a statement declares, including through `export`.
+fn declared_function<'a>(stmt: &'a Statement<'a>) -> Option<&'a Function<'a>> {
+    match stmt {
+        Statement::FunctionDeclaration(func) => Some(func),
+        Statement::ExportNamedDeclaration(export) => match &export.declaration {
+            Some(Declaration::FunctionDeclaration(func)) => Some(func),
+            _ => None,
+        },
+        Statement::ExportDefaultDeclaration(export) => match &export.declaration {
+            ExportDefaultDeclarationKind::FunctionDeclaration(func) => Some(func),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+impl TypeChecker {
+    /// Starts a scope for the block or function at `span`; names declared until the matching
+    /// [`Self::exit_scope`] are only visible inside it.
+    pub(super) fn enter_scope(&mut self, span: Span, is_function: bool) {
+        self.scopes.push(Scope {
+            span,
+            is_function,
+            names: HashMap::new(),
+        });
+    }
+
+    pub(super) fn exit_scope(&mut self) {
+        // The module scope stays open for exports and queries after checking
+        if self.scopes.len() > 1
+            && let Some(scope) = self.scopes.pop()
+        {
+            self.closed_scopes.push(scope);
+        }
+    }
+
+    /// Whether a function is being checked, rather than the file's top level.
+    pub(super) fn in_function(&self) -> bool {
+        self.scopes.iter().skip(1).any(|scope| scope.is_function)
+    }
+
+    /// Brings `name` into the innermost scope, or the innermost function scope for `var`.
+    pub(super) fn bind(&mut self, name: &str, id: SymbolId, is_var: bool) {
+        let index = match is_var {
+            true => self.scopes.iter().rposition(|scope| scope.is_function),
+            false => self.scopes.len().checked_sub(1),
+        };
+        let Some(index) = index else {
+            return;
+        };
+        self.scopes[index].names.insert(Atom::new(name), id);
+        // Only `var`s and functions become properties of the global object
+        if index == 0
+            && (self.is_script || self.declaring_globals)
+            && (is_var || self.symbols[id.0].kind == SymbolKind::Function)
+        {
+            self.globals.push(id);
+        }
+    }
+
+    /// The type of `globalThis`: the lib's global values, and those the file adds.
+    pub(super) fn global_this_type(&self) -> Type {
+        let mut properties: Vec<Property> = global_values()
+            .into_iter()
+            .map(|(name, ty)| Property::new(name, ty))
+            .collect();
+        for id in &self.globals {
+            let symbol = &self.symbols[id.0];
+            properties.retain(|p| p.name != symbol.name);
+            properties.push(Property::new(symbol.name, symbol.ty.clone()));
+        }
+        let alias = AliasType::new("typeof globalThis");
+        alias.resolve(Type::ObjectType(Arc::new(ObjectType::new(properties))));
+        Type::Alias(Arc::new(alias))
+    }
+
+    /// The symbol `name` refers to here, from the innermost scope declaring it.
+    pub(super) fn lookup(&self, name: &str) -> Option<SymbolId> {
+        self.scopes
+            .iter()
+            .rev()
+            .find_map(|scope| scope.names.get(name).copied())
+    }
+
+    /// The symbol `name` was declared as in the innermost scope, which a declaration of the
+    /// same name there could merge with.
+    pub(super) fn declared_here(&self, name: &str) -> Option<SymbolId> {
+        self.scopes.last()?.names.get(name).copied()
+    }
+
+    /// Whether `name` was already declared at `span` in the innermost scope, as hoisted
+    /// function declarations are.
+    pub(super) fn is_declared_here(&self, name: &str, span: Span) -> bool {
+        self.scopes
+            .last()
+            .and_then(|scope| scope.names.get(name))
+            .is_some_and(|id| self.symbols[id.0].span == span)
+    }
+
+    /// Checks statements sharing a scope, declaring their functions first so they can be
+    /// called before they're written.
+    pub(super) fn check_statements(&mut self, stmts: &[Statement]) {
+        self.hoist_functions(stmts);
+        for stmt in stmts {
+            self.check_statement(stmt);
+        }
+    }
+
+    pub(super) fn hoist_functions(&mut self, stmts: &[Statement]) {
+        for func in stmts.iter().filter_map(declared_function) {
+            self.declare_function(func);
+        }
+    }
+
+    /// Symbols whose names are in scope at `offset`, innermost declarations shadowing outer
+    /// ones.
+    pub fn visible_symbols_at(&self, offset: u32) -> Vec<&Symbol> {
+        let mut enclosing: Vec<&Scope> = self
+            .closed_scopes
+            .iter()
+            .filter(|scope| scope.span.start <= offset && offset <= scope.span.end)
+            .collect();
+        enclosing.sort_by_key(|scope| (scope.span.start, std::cmp::Reverse(scope.span.end)));
+
+        let mut names: HashMap<&str, SymbolId> = HashMap::new();
+        for scope in self.scopes.iter().take(1).chain(enclosing) {
+            names.extend(scope.names.iter().map(|(name, id)| (name.as_str(), *id)));
+        }
+        names.values().map(|id| &self.symbols[id.0]).collect()
+    }
+}
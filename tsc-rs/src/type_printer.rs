@@ -0,0 +1,414 @@
+//! Writing types out the way TypeScript does, with limits that keep huge inferred types
+//! readable in diagnostics.
+
+use crate::types::{ObjectType, Type};
+use std::cell::Cell;
+
+/// How many characters of a type diagnostics print before eliding the rest, as tsc does
+/// unless `noErrorTruncation` is set.
+pub const DEFAULT_TRUNCATION_LENGTH: usize = 160;
+
+/// A number the way JavaScript's `String(n)` writes it, which is how TypeScript names
+/// number literal types: `1e+21` rather than Rust's `1000000000000000000000`, and `0`
+/// for `-0`.
+pub fn number_text(n: f64) -> String {
+    if n == 0.0 {
+        return "0".to_string();
+    }
+    if !n.is_finite() {
+        return match n {
+            n if n.is_nan() => "NaN".to_string(),
+            n if n > 0.0 => "Infinity".to_string(),
+            _ => "-Infinity".to_string(),
+        };
+    }
+    let sign = if n < 0.0 { "-" } else { "" };
+    // The shortest digits that read back as `n`, and where the decimal point goes
+    let scientific = format!("{:e}", n.abs());
+    let (mantissa, exponent) = scientific.split_once('e').unwrap();
+    let digits = mantissa.replace('.', "");
+    let point = exponent.parse::<i32>().unwrap() + 1;
+    let count = digits.len() as i32;
+    let text = if count <= point && point <= 21 {
+        format!("{}{}", digits, "0".repeat((point - count) as usize))
+    } else if 0 < point && point <= 21 {
+        format!(
+            "{}.{}",
+            &digits[..point as usize],
+            &digits[point as usize..]
+        )
+    } else if -6 < point && point <= 0 {
+        format!("0.{}{}", "0".repeat(-point as usize), digits)
+    } else {
+        let exponent = point - 1;
+        let mantissa = match count {
+            1 => digits,
+            _ => format!("{}.{}", &digits[..1], &digits[1..]),
+        };
+        let sign = if exponent < 0 { "-" } else { "+" };
+        format!("{}e{}{}", mantissa, sign, exponent.abs())
+    };
+    format!("{}{}", sign, text)
+}
+
+thread_local! {
+    static CURRENT: Cell<TypePrinter> = const { Cell::new(TypePrinter::UNLIMITED) };
+}
+
+/// Settings for writing types out. `Display` for [`Type`] prints with the thread's current
+/// printer, which [`TypePrinter::scope`] sets, and which prints everything in full on one
+/// line otherwise.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TypePrinter {
+    /// Once a type's text is this long, the members of objects, unions and tuples still
+    /// to be written are left out as `... 3 more ...`.
+    pub max_length: Option<usize>,
+    /// Object types nested more deeply than this print as `{ ...; }`.
+    pub max_depth: Option<usize>,
+    /// Puts each object member on a line of its own, indented by how deeply it's nested.
+    pub multiline: bool,
+}
+
+impl TypePrinter {
+    const UNLIMITED: Self = Self {
+        max_length: None,
+        max_depth: None,
+        multiline: false,
+    };
+
+    /// The printer diagnostics use unless `noErrorTruncation` is set.
+    pub fn truncating() -> Self {
+        Self {
+            max_length: Some(DEFAULT_TRUNCATION_LENGTH),
+            ..Self::default()
+        }
+    }
+
+    /// The printer `Display` for types uses on this thread.
+    pub fn current() -> Self {
+        CURRENT.get()
+    }
+
+    /// Runs `f` with this as the printer `Display` for types uses on this thread.
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(TypePrinter);
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CURRENT.set(self.0);
+            }
+        }
+        let _restore = Restore(CURRENT.replace(self));
+        f()
+    }
+
+    pub fn print(&self, ty: &Type) -> String {
+        let mut writer = Writer::new(self);
+        writer.write_type(ty);
+        writer.out
+    }
+
+    pub fn print_object(&self, object: &ObjectType) -> String {
+        let mut writer = Writer::new(self);
+        writer.write_object(object);
+        writer.out
+    }
+}
+
+struct Writer<'a> {
+    printer: &'a TypePrinter,
+    out: String,
+    /// How many object types enclose what's being written.
+    depth: usize,
+}
+
+impl<'a> Writer<'a> {
+    fn new(printer: &'a TypePrinter) -> Self {
+        Self {
+            printer,
+            out: String::new(),
+            depth: 0,
+        }
+    }
+
+    fn is_full(&self) -> bool {
+        self.printer
+            .max_length
+            .is_some_and(|max_length| self.out.len() >= max_length)
+    }
+
+    /// Writes `items` with `separator` between them, leaving out whatever remains once
+    /// the text is as long as the printer allows.
+    fn write_list<T>(
+        &mut self,
+        items: &[T],
+        separator: &str,
+        mut write: impl FnMut(&mut Self, &T),
+    ) {
+        for (index, item) in items.iter().enumerate() {
+            if index > 0 {
+                self.out.push_str(separator);
+                if self.is_full() && index + 1 < items.len() {
+                    self.out
+                        .push_str(&format!("... {} more ...", items.len() - index));
+                    return;
+                }
+            }
+            write(self, item);
+        }
+    }
+
+    fn write_type(&mut self, ty: &Type) {
+        match ty {
+            Type::Any => self.out.push_str("any"),
+            Type::Number => self.out.push_str("number"),
+            Type::String => self.out.push_str("string"),
+            Type::Boolean => self.out.push_str("boolean"),
+            Type::Null => self.out.push_str("null"),
+            Type::Undefined => self.out.push_str("undefined"),
+            Type::Never => self.out.push_str("never"),
+            Type::BigInt => self.out.push_str("bigint"),
+            Type::Symbol => self.out.push_str("symbol"),
+            Type::UniqueSymbol(_) => self.out.push_str("unique symbol"),
+            Type::Object => self.out.push_str("object"),
+            Type::Unknown => self.out.push_str("unknown"),
+            Type::Void => self.out.push_str("void"),
+            Type::StringLiteral(s) => self.out.push_str(&format!("\"{}\"", s)),
+            Type::NumberLiteral(n) => self.out.push_str(&number_text(*n)),
+            Type::BooleanLiteral(b) => self.out.push_str(&b.to_string()),
+            Type::BigIntLiteral(value) => self.out.push_str(&format!("{}n", value)),
+            Type::Union(types) => self.write_list(types, " | ", Self::write_type),
+            Type::Intersection(types) => self.write_list(types, " & ", |writer, ty| match ty {
+                Type::Union(_) | Type::Function { .. } => writer.write_parenthesized(ty),
+                ty => writer.write_type(ty),
+            }),
+            Type::Array(element) => {
+                match &**element {
+                    Type::Union(_) | Type::Intersection(_) | Type::Function { .. } => {
+                        self.write_parenthesized(element)
+                    }
+                    element => self.write_type(element),
+                }
+                self.out.push_str("[]");
+            }
+            Type::Tuple(types) => {
+                self.out.push('[');
+                self.write_list(types, ", ", Self::write_type);
+                self.out.push(']');
+            }
+            Type::Readonly(ty) => {
+                self.out.push_str("readonly ");
+                self.write_type(ty);
+            }
+            Type::Promise(ty) => {
+                self.out.push_str("Promise<");
+                self.write_type(ty);
+                self.out.push('>');
+            }
+            Type::Function {
+                params,
+                return_type,
+                rest,
+                ..
+            } => {
+                self.out.push('(');
+                let last = params.len().saturating_sub(1);
+                let mut index = 0;
+                self.write_list(params, ", ", |writer, param| {
+                    if *rest && index == last {
+                        writer.out.push_str("...");
+                    }
+                    index += 1;
+                    writer.write_type(param);
+                });
+                self.out.push_str(") => ");
+                self.write_type(return_type);
+            }
+            Type::ObjectType(object) => self.write_object(object),
+            Type::Class(class) => self.out.push_str(&format!("typeof {}", class.name)),
+            Type::Alias(alias) => self.out.push_str(&alias.name),
+            Type::TypeParameter(param) => self.out.push_str(&param.name),
+            Type::Generic { type_params, body } => {
+                let names: Vec<&str> = type_params.iter().map(|p| p.name.as_str()).collect();
+                self.out.push_str(&format!("<{}>", names.join(", ")));
+                self.write_type(body);
+            }
+        }
+    }
+
+    fn write_parenthesized(&mut self, ty: &Type) {
+        self.out.push('(');
+        self.write_type(ty);
+        self.out.push(')');
+    }
+
+    fn write_object(&mut self, object: &ObjectType) {
+        let mut members: Vec<Member> = Vec::new();
+        if let Some(ty) = &object.string_index {
+            members.push(Member::Index("string", ty));
+        }
+        if let Some(ty) = &object.number_index {
+            members.push(Member::Index("number", ty));
+        }
+        members.extend(object.properties.iter().map(|property| Member::Property {
+            readonly: property.readonly,
+            name: &property.name,
+            optional: property.optional,
+            ty: &property.ty,
+        }));
+        if members.is_empty() {
+            self.out.push_str("{}");
+            return;
+        }
+        if self
+            .printer
+            .max_depth
+            .is_some_and(|max_depth| self.depth >= max_depth)
+        {
+            self.out.push_str("{ ...; }");
+            return;
+        }
+
+        self.depth += 1;
+        let indent = "    ".repeat(self.depth);
+        let separator = match self.printer.multiline {
+            true => format!("\n{}", indent),
+            false => " ".to_string(),
+        };
+        self.out.push('{');
+        self.out.push_str(&separator);
+        self.write_list(&members, &separator, |writer, member| {
+            match member {
+                Member::Index(key, ty) => {
+                    writer.out.push_str(&format!("[key: {}]: ", key));
+                    writer.write_type(ty);
+                }
+                Member::Property {
+                    readonly,
+                    name,
+                    optional,
+                    ty,
+                } => {
+                    if *readonly {
+                        writer.out.push_str("readonly ");
+                    }
+                    writer.out.push_str(name);
+                    if *optional {
+                        writer.out.push('?');
+                    }
+                    writer.out.push_str(": ");
+                    writer.write_type(ty);
+                }
+            }
+            writer.out.push(';');
+        });
+        // Left out members are followed by the semicolon written ones have
+        if !self.out.ends_with(';') {
+            self.out.push(';');
+        }
+        self.depth -= 1;
+        match self.printer.multiline {
+            true => self
+                .out
+                .push_str(&format!("\n{}}}", "    ".repeat(self.depth))),
+            false => self.out.push_str(" }"),
+        }
+    }
+}
+
+enum Member<'a> {
+    Index(&'static str, &'a Type),
+    Property {
+        readonly: bool,
+        name: &'a str,
+        optional: bool,
+        ty: &'a Type,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Property;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_number_text() {
+        assert_eq!(number_text(42.0), "42");
+        assert_eq!(number_text(-0.0), "0");
+        assert_eq!(number_text(1.5), "1.5");
+        assert_eq!(number_text(1e21), "1e+21");
+        assert_eq!(number_text(1e20), "100000000000000000000");
+        assert_eq!(number_text(0.000001), "0.000001");
+        assert_eq!(number_text(-1.5e-7), "-1.5e-7");
+        assert_eq!(number_text(f64::INFINITY), "Infinity");
+    }
+
+    fn object(properties: Vec<(&str, Type)>) -> Type {
+        let properties = properties
+            .into_iter()
+            .map(|(name, ty)| Property::new(name, ty))
+            .collect();
+        Type::ObjectType(Arc::new(ObjectType::new(properties)))
+    }
+
+    #[test]
+    fn test_truncation() {
+        let wide = Type::ObjectType(Arc::new(ObjectType::new(
+            (0..40)
+                .map(|index| Property::new(format!("field{}", index), Type::Number))
+                .collect(),
+        )));
+        let printed = TypePrinter::truncating().print(&wide);
+        assert!(printed.starts_with("{ field0: number; field1: number; "));
+        assert!(
+            printed.ends_with("field9: number; ... 30 more ...; }"),
+            "{}",
+            printed
+        );
+        assert!(
+            TypePrinter::default()
+                .print(&wide)
+                .ends_with("field39: number; }")
+        );
+
+        let printer = TypePrinter {
+            max_length: Some(10),
+            ..TypePrinter::default()
+        };
+        let union = Type::Union((0..6).map(|n| Type::NumberLiteral(n as f64)).collect());
+        assert_eq!(printer.print(&union), "0 | 1 | 2 | ... 3 more ...");
+    }
+
+    #[test]
+    fn test_depth_and_multiline() {
+        let nested = object(vec![
+            ("id", Type::Number),
+            ("owner", object(vec![("name", Type::String)])),
+        ]);
+        let shallow = TypePrinter {
+            max_depth: Some(1),
+            ..TypePrinter::default()
+        };
+        assert_eq!(shallow.print(&nested), "{ id: number; owner: { ...; }; }");
+
+        let multiline = TypePrinter {
+            multiline: true,
+            ..TypePrinter::default()
+        };
+        assert_eq!(
+            multiline.print(&nested),
+            "{\n    id: number;\n    owner: {\n        name: string;\n    };\n}"
+        );
+    }
+
+    #[test]
+    fn test_display_uses_the_scoped_printer() {
+        let nested = object(vec![("owner", object(vec![("name", Type::String)]))]);
+        let shallow = TypePrinter {
+            max_depth: Some(1),
+            ..TypePrinter::default()
+        };
+        assert_eq!(shallow.scope(|| nested.to_string()), "{ owner: { ...; }; }");
+        assert_eq!(nested.to_string(), "{ owner: { name: string; }; }");
+    }
+}
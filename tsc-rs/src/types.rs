@@ -1,7 +1,11 @@
 // This module will contain our type system implementation
+use crate::diagnostics::Message;
+use crate::intern::Atom;
+use crate::options::Strictness;
+use crate::type_printer::TypePrinter;
 use oxc_span::Span;
 use std::fmt;
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum Type {
@@ -15,6 +19,8 @@ pub enum Type {
     Never,
     BigInt,
     Symbol,
+    /// A `unique symbol`, related only to itself; identified by where it's written.
+    UniqueSymbol(Span),
     Object,
     Unknown,
     Void,
@@ -22,71 +28,366 @@ pub enum Type {
     StringLiteral(String),
     NumberLiteral(f64),
     BooleanLiteral(bool),
+    /// A bigint literal type like `10n`, holding the value in decimal.
+    BigIntLiteral(String),
     // Compound types
     Union(Vec<Type>),
+    /// Values of every member type at once, such as a primitive branded with a marker
+    /// object; build with [`intersection_of`] so impossible ones become `never`.
+    Intersection(Vec<Type>),
     Array(Arc<Type>),
     Tuple(Vec<Type>),
+    /// A `readonly` array or tuple, which can be read but not modified.
+    Readonly(Arc<Type>),
+    /// A `Promise<T>`; build with [`Type::promise`] so promises of promises are flattened.
+    Promise(Arc<Type>),
+    /// A function type; build with [`Type::function`] when every parameter is required.
     Function {
         params: Vec<Type>,
         return_type: Arc<Type>,
+        /// How many leading parameters must be passed an argument.
+        required: usize,
+        /// Whether the last parameter is a `...rest` array taking the remaining arguments.
+        rest: bool,
+    },
+    ObjectType(Arc<ObjectType>),
+    /// The constructor a class declaration or expression evaluates to.
+    Class(Arc<ClassType>),
+    /// A reference to a type alias, resolved lazily so aliases can refer to themselves.
+    Alias(Arc<AliasType>),
+    /// A type parameter such as `T`, standing for whatever a generic is instantiated with.
+    TypeParameter(Arc<TypeParameter>),
+    /// A generic function: `body` mentions `type_params`, which are inferred at each call.
+    Generic {
+        type_params: Vec<Arc<TypeParameter>>,
+        body: Arc<Type>,
     },
 }
 
-impl fmt::Display for Type {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+#[derive(Debug, PartialEq)]
+pub struct TypeParameter {
+    pub name: String,
+    /// What inferred arguments must be assignable to; also the fallback when nothing is inferred.
+    pub constraint: Option<Type>,
+    pub default: Option<Type>,
+    /// Declared `const T`, so arguments are inferred as if written with `as const`.
+    pub is_const: bool,
+}
+
+impl Type {
+    /// The type of an expression whose error has been reported. Like tsc's `errorType`,
+    /// it's `any`, so whatever the expression's value goes on to be used for isn't
+    /// reported again.
+    pub const ERROR: Type = Type::Any;
+
+    /// Follows alias references to the type they stand for.
+    pub fn resolved(&self) -> &Type {
+        let mut ty = self;
+        // Aliases of aliases are rejected as circular before they get here, but stay bounded
+        for _ in 0..64 {
+            match ty {
+                Type::Alias(alias) => ty = alias.target(),
+                _ => return ty,
+            }
+        }
+        &Type::Any
+    }
+
+    /// The type without object literal freshness, as it is once the literal has been
+    /// stored somewhere, like a variable or a function's inferred return type.
+    pub fn regular(self) -> Type {
         match self {
-            Type::Any => write!(f, "any"),
-            Type::Number => write!(f, "number"),
-            Type::String => write!(f, "string"),
-            Type::Boolean => write!(f, "boolean"),
-            Type::Null => write!(f, "null"),
-            Type::Undefined => write!(f, "undefined"),
-            Type::Never => write!(f, "never"),
-            Type::BigInt => write!(f, "bigint"),
-            Type::Symbol => write!(f, "symbol"),
-            Type::Object => write!(f, "object"),
-            Type::Unknown => write!(f, "unknown"),
-            Type::Void => write!(f, "void"),
-            Type::StringLiteral(s) => write!(f, "\"{}\"", s),
-            Type::NumberLiteral(n) => write!(f, "{}", n),
-            Type::BooleanLiteral(b) => write!(f, "{}", b),
-            Type::Union(types) => {
-                let types_str: Vec<String> = types.iter().map(|t| t.to_string()).collect();
-                write!(f, "{}", types_str.join(" | "))
+            Type::ObjectType(object) if object.fresh => {
+                let mut object = Arc::unwrap_or_clone(object);
+                object.fresh = false;
+                object.property_spans = Vec::new();
+                for property in &mut object.properties {
+                    property.ty = std::mem::replace(&mut property.ty, Type::Any).regular();
+                }
+                Type::ObjectType(Arc::new(object))
             }
-            Type::Array(elem_type) => write!(f, "{}[]", elem_type),
-            Type::Tuple(types) => {
-                let types_str: Vec<String> = types.iter().map(|t| t.to_string()).collect();
-                write!(f, "[{}]", types_str.join(", "))
+            Type::Union(types) if types.iter().any(Type::is_fresh) => {
+                Type::Union(types.into_iter().map(Type::regular).collect())
             }
-            Type::Function {
-                params,
-                return_type,
-            } => {
-                let params_str: Vec<String> = params.iter().map(|t| t.to_string()).collect();
-                write!(f, "({}) => {}", params_str.join(", "), return_type)
+            ty => ty,
+        }
+    }
+
+    fn is_fresh(&self) -> bool {
+        matches!(self, Type::ObjectType(object) if object.fresh)
+    }
+
+    /// The primitive a literal type belongs to, like `bigint` for `1n`; other types are
+    /// left as they are.
+    pub fn widened(&self) -> Type {
+        match self.resolved() {
+            Type::StringLiteral(_) => Type::String,
+            Type::NumberLiteral(_) => Type::Number,
+            Type::BooleanLiteral(_) => Type::Boolean,
+            Type::BigIntLiteral(_) => Type::BigInt,
+            ty => ty.clone(),
+        }
+    }
+
+    /// A function taking an argument for each of `params`.
+    pub fn function(params: Vec<Type>, return_type: Type) -> Type {
+        Type::Function {
+            required: params.len(),
+            params,
+            return_type: Arc::new(return_type),
+            rest: false,
+        }
+    }
+
+    /// The parameter of a function taking `params` that the argument at `index` is passed
+    /// to, which for a `...rest` parameter is its element type.
+    pub fn parameter_at(params: &[Type], rest: bool, index: usize) -> Option<Type> {
+        match (rest, params.split_last()) {
+            (true, Some((rest_param, leading))) if index >= leading.len() => {
+                match rest_param.resolved() {
+                    Type::Array(element) => Some((**element).clone()),
+                    Type::Readonly(array) => match array.resolved() {
+                        Type::Array(element) => Some((**element).clone()),
+                        _ => Some(Type::Any),
+                    },
+                    Type::Tuple(types) => types.get(index - leading.len()).cloned(),
+                    _ => Some(Type::Any),
+                }
             }
+            _ => params.get(index).cloned(),
+        }
+    }
+
+    /// A promise resolving to `ty`, which resolves to what `ty` does when it's thenable.
+    pub fn promise(ty: Type) -> Type {
+        Type::Promise(Arc::new(ty.awaited()))
+    }
+
+    /// What `await` evaluates to: the value a thenable resolves to, or the type itself.
+    pub fn awaited(&self) -> Type {
+        let mut ty = self.resolved().clone();
+        for _ in 0..64 {
+            ty = match ty.resolved() {
+                Type::Promise(value) => (**value).clone(),
+                Type::Union(types) => return union_of(types.iter().map(Type::awaited).collect()),
+                // Anything with `then(onfulfilled: (value: T) => ...)` resolves to `T`
+                Type::ObjectType(object) => {
+                    match object.property("then").map(|p| p.ty.resolved()) {
+                        Some(Type::Function { params, .. }) => match params.first() {
+                            Some(Type::Function { params, .. }) => {
+                                params.first().cloned().unwrap_or(Type::Undefined)
+                            }
+                            _ => return ty,
+                        },
+                        _ => return ty,
+                    }
+                }
+                _ => return ty,
+            };
+        }
+        ty
+    }
+}
+
+/// A generic function's signature as a call instantiated it, like `pick<string>(string[]):
+/// string`, naming the function and the type arguments it was given.
+pub fn display_signature(name: Option<&str>, type_arguments: &[Type], signature: &Type) -> String {
+    let mut text = name.unwrap_or_default().to_string();
+    if !type_arguments.is_empty() {
+        let arguments: Vec<String> = type_arguments.iter().map(Type::to_string).collect();
+        text.push_str(&format!("<{}>", arguments.join(", ")));
+    }
+    match signature {
+        Type::Function {
+            params,
+            return_type,
+            rest,
+            ..
+        } => {
+            let params: Vec<String> = params
+                .iter()
+                .enumerate()
+                .map(|(index, param)| match *rest && index + 1 == params.len() {
+                    true => format!("...{}", param),
+                    false => param.to_string(),
+                })
+                .collect();
+            text.push_str(&format!("({}): {}", params.join(", "), return_type));
+        }
+        signature => text.push_str(&format!(": {}", signature)),
+    }
+    text
+}
+
+/// A named `type X = ...` declaration whose target is filled in after its name is visible.
+///
+/// Recursive aliases hold a reference to themselves through their target, so they live as
+/// long as anything refers to them.
+pub struct AliasType {
+    pub name: String,
+    target: OnceLock<Type>,
+}
+
+impl AliasType {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            target: OnceLock::new(),
         }
     }
+
+    /// Sets what the alias stands for; later calls are ignored.
+    pub fn resolve(&self, target: Type) {
+        let _ = self.target.set(target);
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.target.get().is_some()
+    }
+
+    /// The aliased type, or `any` while it is still being resolved.
+    pub fn target(&self) -> &Type {
+        self.target.get().unwrap_or(&Type::Any)
+    }
+}
+
+// Aliases compare and print by identity; walking the target could loop forever
+impl PartialEq for AliasType {
+    fn eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+}
+
+impl fmt::Debug for AliasType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AliasType").field(&self.name).finish()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct ObjectType {
+    pub properties: Vec<Property>,
+    /// The type of `[key: string]` entries not named by a property.
+    pub string_index: Option<Type>,
+    /// The type of `[key: number]` entries not named by a property.
+    pub number_index: Option<Type>,
+    /// Whether this is the type of an object literal itself, rather than of a variable or
+    /// anything else it's gone through, so properties a target lacks are reported.
+    pub fresh: bool,
+    /// Where a fresh literal names each of its properties, for reporting one a target lacks.
+    pub property_spans: Vec<(Atom, Span)>,
 }
 
-#[derive(Debug)]
+// Freshness is where a type came from, not part of it
+impl PartialEq for ObjectType {
+    fn eq(&self, other: &Self) -> bool {
+        self.properties == other.properties
+            && self.string_index == other.string_index
+            && self.number_index == other.number_index
+    }
+}
+
+impl ObjectType {
+    pub fn new(properties: Vec<Property>) -> Self {
+        Self {
+            properties,
+            ..Self::default()
+        }
+    }
+
+    pub fn property(&self, name: &str) -> Option<&Property> {
+        self.properties.iter().find(|p| p.name == name)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClassType {
+    pub name: String,
+    /// Parameters of the constructor, empty when the class doesn't declare one.
+    pub params: Vec<Type>,
+    /// The shape of instances created with `new`.
+    pub instance: ObjectType,
+    pub statics: ObjectType,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Property {
+    pub name: Atom,
+    /// The type reading the property gives.
+    pub ty: Type,
+    pub optional: bool,
+    pub readonly: bool,
+    /// The type values written to the property must have, when an accessor pair gives it
+    /// a setter taking something other than what its getter returns.
+    pub write_type: Option<Type>,
+}
+
+impl Property {
+    pub fn new(name: impl Into<Atom>, ty: Type) -> Self {
+        Self {
+            name: name.into(),
+            ty,
+            optional: false,
+            readonly: false,
+            write_type: None,
+        }
+    }
+
+    /// The type values written to the property must have.
+    pub fn write_type(&self) -> &Type {
+        self.write_type.as_ref().unwrap_or(&self.ty)
+    }
+}
+
+/// Adds the `get` or `set` accessor `name` to `properties`, combining it with the other
+/// half of the pair if that's already there. A getter on its own makes a read-only
+/// property.
+pub fn add_accessor(properties: &mut Vec<Property>, name: &str, ty: Type, is_getter: bool) {
+    let Some(property) = properties.iter_mut().find(|p| p.name == name) else {
+        properties.push(Property {
+            readonly: is_getter,
+            ..Property::new(name, ty)
+        });
+        return;
+    };
+    let (read, write) = match is_getter {
+        true => (ty, property.ty.clone()),
+        false => (property.ty.clone(), ty),
+    };
+    property.readonly = false;
+    property.write_type = (write != read).then_some(write);
+    property.ty = read;
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&TypePrinter::current().print(self))
+    }
+}
+
+impl fmt::Display for ObjectType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&TypePrinter::current().print_object(self))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct TypeError {
-    pub message: String,
+    pub message: Message,
     pub span: Option<Span>,
 }
 
 impl TypeError {
-    pub fn new(message: impl Into<String>) -> Self {
+    pub fn new(message: Message) -> Self {
         Self {
-            message: message.into(),
+            message,
             span: None,
         }
     }
 
-    pub fn with_span(message: impl Into<String>, span: Span) -> Self {
+    pub fn with_span(message: Message, span: Span) -> Self {
         Self {
-            message: message.into(),
+            message,
             span: Some(span),
         }
     }
@@ -99,7 +400,7 @@ pub fn infer_type_from_literal(value: &str) -> Type {
     // Basic type inference from string literals
     if value == "null" {
         Type::Null
-    } else if let Ok(num) = value.parse::<f64>() {
+    } else if let Some(num) = parse_numeric_literal(value) {
         Type::NumberLiteral(num)
     } else if value == "true" {
         Type::BooleanLiteral(true)
@@ -110,57 +411,523 @@ pub fn infer_type_from_literal(value: &str) -> Type {
     }
 }
 
+/// The value of a JavaScript numeric literal, like `1_000`, `0x1F`, `0o17`, `0b101` or
+/// `1.5e-3`. Words Rust reads as numbers, like `NaN` and `inf`, aren't literals.
+pub fn parse_numeric_literal(text: &str) -> Option<f64> {
+    // Separators only go between digits
+    if text.starts_with('_') || text.ends_with('_') || text.contains("__") {
+        return None;
+    }
+    let text = text.replace('_', "");
+    let radix = match text.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0x") => 16,
+        Some("0o") => 8,
+        Some("0b") => 2,
+        _ => {
+            let decimal = text
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, '.' | 'e' | 'E' | '+' | '-'));
+            return decimal.then(|| text.parse().ok()).flatten();
+        }
+    };
+    let digits = &text[2..];
+    if digits.is_empty() {
+        return None;
+    }
+    digits.chars().try_fold(0.0, |value, c| {
+        Some(value * radix as f64 + c.to_digit(radix)? as f64)
+    })
+}
+
+/// The value of a JavaScript bigint literal like `1_000n` or `0xFFn`, in decimal, the way
+/// TypeScript names bigint literal types. Bigints have no size limit, so neither does this.
+pub fn parse_bigint_literal(text: &str) -> Option<String> {
+    let text = text.strip_suffix('n')?;
+    if text.is_empty() || text.starts_with('_') || text.ends_with('_') || text.contains("__") {
+        return None;
+    }
+    let text = text.replace('_', "");
+    let (radix, digits) = match text.get(..2).map(str::to_ascii_lowercase).as_deref() {
+        Some("0x") => (16, &text[2..]),
+        Some("0o") => (8, &text[2..]),
+        Some("0b") => (2, &text[2..]),
+        _ => (10, text.as_str()),
+    };
+    if digits.is_empty() {
+        return None;
+    }
+    // Least significant digit first
+    let mut decimal: Vec<u32> = vec![0];
+    for c in digits.chars() {
+        let mut carry = c.to_digit(radix)?;
+        for digit in &mut decimal {
+            let value = *digit * radix + carry;
+            *digit = value % 10;
+            carry = value / 10;
+        }
+        while carry > 0 {
+            decimal.push(carry % 10);
+            carry /= 10;
+        }
+    }
+    while decimal.len() > 1 && decimal.last() == Some(&0) {
+        decimal.pop();
+    }
+    Some(
+        decimal
+            .iter()
+            .rev()
+            .map(|digit| char::from_digit(*digit, 10).unwrap())
+            .collect(),
+    )
+}
+
+/// A union of `types`, collapsing to `never` or the single member when there are fewer than
+/// two, and to `any` or `unknown` when either is a member, since they already hold every value.
+/// `never` members hold no values, so they're left out.
+pub fn union_of(mut types: Vec<Type>) -> Type {
+    for top in [Type::Any, Type::Unknown] {
+        if types.contains(&top) {
+            return top;
+        }
+    }
+    types.retain(|ty| *ty != Type::Never);
+    match types.len() {
+        0 => Type::Never,
+        1 => types.remove(0),
+        _ => Type::Union(types),
+    }
+}
+
+/// Whether `ty` is a primitive, which can't also be a different primitive.
+fn is_primitive(ty: &Type) -> bool {
+    matches!(
+        ty,
+        Type::Number
+            | Type::String
+            | Type::Boolean
+            | Type::Null
+            | Type::Undefined
+            | Type::Void
+            | Type::BigInt
+            | Type::Symbol
+            | Type::UniqueSymbol(_)
+            | Type::StringLiteral(_)
+            | Type::NumberLiteral(_)
+            | Type::BooleanLiteral(_)
+            | Type::BigIntLiteral(_)
+    )
+}
+
+/// An intersection of `types`, flattened, without repeated or `unknown` members, and
+/// distributed over any union among them. It's `never` when two members are primitives no
+/// value can be at once, and keeps only the narrower of two that overlap, so `string & "a"`
+/// is `"a"`.
+pub fn intersection_of(types: Vec<Type>) -> Type {
+    let flattened: Vec<Type> = types
+        .into_iter()
+        .flat_map(|ty| match ty {
+            Type::Intersection(types) => types,
+            ty => vec![ty],
+        })
+        .collect();
+    for special in [Type::Never, Type::Any] {
+        if flattened.iter().any(|ty| *ty.resolved() == special) {
+            return special;
+        }
+    }
+    // Intersections distribute over unions: `(A | B) & C` is `(A & C) | (B & C)`
+    if let Some(index) = flattened
+        .iter()
+        .position(|ty| matches!(ty.resolved(), Type::Union(_)))
+    {
+        let Type::Union(alternatives) = flattened[index].resolved() else {
+            unreachable!()
+        };
+        let alternatives = alternatives
+            .iter()
+            .map(|alternative| {
+                let mut members = flattened.clone();
+                members[index] = alternative.clone();
+                intersection_of(members)
+            })
+            .filter(|ty| *ty != Type::Never)
+            .collect();
+        return union_of(alternatives);
+    }
+    let mut members: Vec<Type> = Vec::new();
+    for member in flattened {
+        if *member.resolved() == Type::Unknown || members.contains(&member) {
+            continue;
+        }
+        if is_primitive(member.resolved()) {
+            let primitive = members.iter().position(|m| is_primitive(m.resolved()));
+            if let Some(index) = primitive {
+                let existing = members[index].resolved();
+                if check_type_compatibility(existing, member.resolved()) {
+                    members[index] = member;
+                } else if !check_type_compatibility(member.resolved(), existing) {
+                    return Type::Never;
+                }
+                continue;
+            }
+        }
+        members.push(member);
+    }
+    match members.len() {
+        0 => Type::Unknown,
+        1 => members.remove(0),
+        _ if conflicting_property(&members).is_some() => Type::Never,
+        _ => Type::Intersection(members),
+    }
+}
+
+/// Whether `ty` is a unit type or a union of them, the kind of type a property that
+/// discriminates between object types has.
+fn is_discriminant(ty: &Type) -> bool {
+    match ty.resolved() {
+        Type::Union(types) => types.iter().all(is_discriminant),
+        Type::StringLiteral(_)
+        | Type::NumberLiteral(_)
+        | Type::BooleanLiteral(_)
+        | Type::BigIntLiteral(_)
+        | Type::UniqueSymbol(_)
+        | Type::Null
+        | Type::Undefined => true,
+        _ => false,
+    }
+}
+
+/// The first property the object members of an intersection give discriminant types no
+/// value can have at once, like `kind` in `{ kind: "a" } & { kind: "b" }`. No object can
+/// be both, so the whole intersection is `never`, not just the property.
+pub fn conflicting_property(types: &[Type]) -> Option<Atom> {
+    let objects: Vec<&ObjectType> = types
+        .iter()
+        .flat_map(|ty| match ty.resolved() {
+            Type::Intersection(types) => types.iter().collect(),
+            _ => vec![ty],
+        })
+        .filter_map(|ty| match ty.resolved() {
+            Type::ObjectType(object) => Some(&**object),
+            _ => None,
+        })
+        .collect();
+    for (index, object) in objects.iter().enumerate() {
+        for property in &object.properties {
+            let declared: Vec<Type> = objects[index..]
+                .iter()
+                .filter_map(|object| object.property(&property.name))
+                .map(|property| property.ty.clone())
+                .collect();
+            if declared.len() > 1
+                && declared.iter().any(is_discriminant)
+                && intersection_of(declared) == Type::Never
+            {
+                return Some(property.name);
+            }
+        }
+    }
+    None
+}
+
+/// The first property an object literal gives that `expected` has no place for, the type
+/// it's missing from, and where the literal names it, when `actual` is the literal's fresh
+/// type. Literals nested in it are checked against the types of the properties they're
+/// given to.
+pub fn excess_property(expected: &Type, actual: &Type) -> Option<(String, Type, Option<Span>)> {
+    let actual_object = match actual.resolved() {
+        Type::ObjectType(object) if object.fresh => object,
+        Type::Union(types) => return types.iter().find_map(|ty| excess_property(expected, ty)),
+        _ => return None,
+    };
+    let targets: Vec<ObjectType> = match expected.resolved() {
+        Type::ObjectType(object) => vec![(**object).clone()],
+        Type::Intersection(types) => vec![merged_object(types)],
+        Type::Union(types) => types
+            .iter()
+            .filter_map(|ty| match ty.resolved() {
+                Type::ObjectType(object) => Some((**object).clone()),
+                _ => None,
+            })
+            .collect(),
+        _ => return None,
+    };
+    // Empty object types and index signatures take anything
+    let open = |target: &ObjectType| {
+        target.string_index.is_some()
+            || (target.properties.is_empty() && target.number_index.is_none())
+    };
+    if targets.is_empty() || targets.iter().any(open) {
+        return None;
+    }
+    for property in &actual_object.properties {
+        let numeric = property.name.parse::<f64>().is_ok();
+        let mut expected_types =
+            targets
+                .iter()
+                .filter_map(|target| match target.property(&property.name) {
+                    Some(expected) => Some(Some(expected.ty.clone())),
+                    None => (numeric && target.number_index.is_some()).then_some(None),
+                });
+        match expected_types.next() {
+            None => {
+                // A later duplicate is the one the literal keeps
+                let span = actual_object
+                    .property_spans
+                    .iter()
+                    .rev()
+                    .find(|(name, _)| *name == property.name)
+                    .map(|(_, span)| *span);
+                return Some((property.name.to_string(), expected.clone(), span));
+            }
+            Some(Some(expected)) if targets.len() == 1 => {
+                if let Some(excess) = excess_property(&expected, &property.ty) {
+                    return Some(excess);
+                }
+            }
+            Some(_) => {}
+        }
+    }
+    None
+}
+
+/// The properties of the object types among `types`, as one object. A property more than
+/// one of them gives has the intersection of their types.
+pub fn merged_object(types: &[Type]) -> ObjectType {
+    let mut merged = ObjectType::default();
+    for ty in types {
+        if let Type::ObjectType(object) = ty.resolved() {
+            for property in &object.properties {
+                merge_property(&mut merged.properties, property.clone());
+            }
+            merged.string_index = merged.string_index.or(object.string_index.clone());
+            merged.number_index = merged.number_index.or(object.number_index.clone());
+        }
+    }
+    merged
+}
+
+/// Adds `property` to the properties of an intersection, intersecting it with the one
+/// already there by the same name. It's only optional or read-only when both are.
+pub fn merge_property(properties: &mut Vec<Property>, property: Property) {
+    match properties.iter_mut().find(|p| p.name == property.name) {
+        Some(existing) => {
+            existing.ty = intersection_of(vec![existing.ty.clone(), property.ty]);
+            existing.optional &= property.optional;
+            existing.readonly &= property.readonly;
+        }
+        None => properties.push(property),
+    }
+}
+
 pub fn check_type_compatibility(expected: &Type, actual: &Type) -> bool {
+    is_assignable_with(expected, actual, Strictness::STRICT)
+}
+
+/// Assignability under the strict flags in `strictness`.
+pub fn is_assignable_with(expected: &Type, actual: &Type, strictness: Strictness) -> bool {
+    let mut relation = Relation {
+        assumed: Vec::new(),
+        strictness,
+    };
+    is_assignable(expected, actual, &mut relation)
+}
+
+struct Relation {
+    // Pairs already being compared, by `identity`
+    assumed: Vec<(usize, usize)>,
+    strictness: Strictness,
+}
+
+/// Identifies a type for cycle detection: aliases by declaration, everything else by address.
+fn identity(ty: &Type) -> usize {
+    match ty {
+        Type::Alias(alias) => Arc::as_ptr(alias) as usize,
+        ty => ty as *const Type as usize,
+    }
+}
+
+/// Assignability that assumes pairs already being compared are related, so recursive
+/// aliases relate by their structure instead of unfolding forever.
+fn is_assignable(expected: &Type, actual: &Type, relation: &mut Relation) -> bool {
+    if matches!(expected, Type::Alias(_)) || matches!(actual, Type::Alias(_)) {
+        if expected == actual {
+            return true;
+        }
+        let pair = (identity(expected), identity(actual));
+        if relation.assumed.contains(&pair) {
+            return true;
+        }
+        relation.assumed.push(pair);
+        let related = is_assignable(expected.resolved(), actual.resolved(), relation);
+        relation.assumed.pop();
+        return related;
+    }
     match (expected, actual) {
-        // Any type can be assigned to any
+        // Any type can be assigned to any, and `any` to anything but `never`
         (Type::Any, _) => true,
+        (expected, Type::Any) => !matches!(expected, Type::Never),
+        // Everything can be assigned to `unknown`, and `never` can be assigned to everything
+        (Type::Unknown, _) => true,
+        (_, Type::Never) => true,
+        // Without strictNullChecks, `null` and `undefined` belong to every type
+        (expected, Type::Null | Type::Undefined)
+            if !relation.strictness.strict_null_checks && !matches!(expected, Type::Never) =>
+        {
+            true
+        }
         (Type::Number, Type::Number) => true,
         (Type::String, Type::String) => true,
         (Type::Boolean, Type::Boolean) => true,
         (Type::Null, Type::Null) => true,
         (Type::Undefined, Type::Undefined) => true,
-        (Type::Never, Type::Never) => true,
         (Type::BigInt, Type::BigInt) => true,
         (Type::Symbol, Type::Symbol) => true,
+        (Type::Symbol, Type::UniqueSymbol(_)) => true,
+        (Type::UniqueSymbol(expected), Type::UniqueSymbol(actual)) => expected == actual,
         (Type::Object, Type::Object) => true,
-        (Type::Unknown, Type::Unknown) => true,
         (Type::Void, Type::Void) => true,
         // Literal types can be assigned to their corresponding base types
         (Type::Number, Type::NumberLiteral(_)) => true,
         (Type::String, Type::StringLiteral(_)) => true,
         (Type::Boolean, Type::BooleanLiteral(_)) => true,
+        (Type::BigInt, Type::BigIntLiteral(_)) => true,
         // Literal types must match exactly
         (Type::NumberLiteral(n1), Type::NumberLiteral(n2)) => n1 == n2,
         (Type::StringLiteral(s1), Type::StringLiteral(s2)) => s1 == s2,
         (Type::BooleanLiteral(b1), Type::BooleanLiteral(b2)) => b1 == b2,
-        (Type::Union(types), actual) => types.iter().any(|t| check_type_compatibility(t, actual)),
+        (Type::BigIntLiteral(b1), Type::BigIntLiteral(b2)) => b1 == b2,
+        // A union is assignable when each of its members is
+        (expected, Type::Union(actual_types)) => actual_types
+            .iter()
+            .all(|actual| is_assignable(expected, actual, relation)),
+        (Type::Union(types), actual) => types.iter().any(|t| is_assignable(t, actual, relation)),
+        // An intersection is assignable to each of its members, and from what fits them all
+        (Type::Intersection(types), actual) => types
+            .iter()
+            .all(|expected| is_assignable(expected, actual, relation)),
+        (expected, Type::Intersection(types)) => {
+            types
+                .iter()
+                .any(|actual| is_assignable(expected, actual, relation))
+                || (matches!(expected, Type::ObjectType(_))
+                    && is_assignable(
+                        expected,
+                        &Type::ObjectType(Arc::new(merged_object(types))),
+                        relation,
+                    ))
+        }
         (Type::Array(expected_elem), Type::Array(actual_elem)) => {
-            check_type_compatibility(expected_elem, actual_elem)
+            is_assignable(expected_elem, actual_elem, relation)
+        }
+        // Mutable arrays can be read through a readonly view, but not the other way around
+        (Type::Readonly(expected), Type::Readonly(actual)) => {
+            is_assignable(expected, actual, relation)
+        }
+        (Type::Readonly(expected), actual) => is_assignable(expected, actual, relation),
+        (Type::Promise(expected), Type::Promise(actual)) => {
+            is_assignable(expected, actual, relation)
         }
+        // A tuple is an array of its element types
+        (Type::Array(expected_elem), Type::Tuple(actual_types)) => actual_types
+            .iter()
+            .all(|actual| is_assignable(expected_elem, actual, relation)),
         (Type::Tuple(expected_types), Type::Tuple(actual_types)) => {
             expected_types.len() == actual_types.len()
                 && expected_types
                     .iter()
                     .zip(actual_types.iter())
-                    .all(|(expected, actual)| check_type_compatibility(expected, actual))
+                    .all(|(expected, actual)| is_assignable(expected, actual, relation))
         }
         (
             Type::Function {
                 params: params1,
                 return_type: return1,
+                rest: rest1,
+                ..
             },
             Type::Function {
                 params: params2,
                 return_type: return2,
+                required: required2,
+                rest: rest2,
             },
         ) => {
-            params1.len() == params2.len()
-                && params1
-                    .iter()
-                    .zip(params2.iter())
-                    .all(|(p1, p2)| check_type_compatibility(p1, p2))
-                && check_type_compatibility(return1, return2)
+            // Parameters are compared both ways unless strictFunctionTypes is set, which
+            // requires the assigned function to accept everything the expected one may be passed.
+            // It may ignore trailing arguments, but can't require more than it's given
+            (*required2 <= params1.len() || *rest1)
+                && (0..params1.len()).all(|index| {
+                    let (Some(p1), Some(p2)) = (
+                        Type::parameter_at(params1, *rest1, index),
+                        Type::parameter_at(params2, *rest2, index),
+                    ) else {
+                        return true;
+                    };
+                    is_assignable(&p2, &p1, relation)
+                        || (!relation.strictness.strict_function_types
+                            && is_assignable(&p1, &p2, relation))
+                })
+                // A caller expecting nothing back ignores whatever the function returns
+                && (*return1.resolved() == Type::Void || is_assignable(return1, return2, relation))
+        }
+        // Object types are structural: every required property must be present and compatible
+        (Type::ObjectType(expected), Type::ObjectType(actual)) => {
+            expected
+                .properties
+                .iter()
+                .all(|p| match actual.property(&p.name) {
+                    Some(actual_property) => is_assignable(&p.ty, &actual_property.ty, relation),
+                    None => p.optional,
+                })
+                // Every other property must fit the index signature
+                && expected.string_index.as_ref().is_none_or(|index| {
+                    actual
+                        .properties
+                        .iter()
+                        .filter(|p| expected.property(&p.name).is_none())
+                        .all(|p| is_assignable(index, &p.ty, relation))
+                        && [&actual.string_index, &actual.number_index]
+                            .into_iter()
+                            .flatten()
+                            .all(|actual| is_assignable(index, actual, relation))
+                })
+                // And every other numerically named one the number index signature
+                && expected.number_index.as_ref().is_none_or(|index| {
+                    actual
+                        .properties
+                        .iter()
+                        .filter(|p| expected.property(&p.name).is_none())
+                        .filter(|p| p.name.parse::<f64>().is_ok())
+                        .all(|p| is_assignable(index, &p.ty, relation))
+                        && actual
+                            .number_index
+                            .as_ref()
+                            .is_none_or(|actual| is_assignable(index, actual, relation))
+                })
+        }
+        (Type::Class(expected), Type::Class(actual)) => expected == actual,
+        (Type::TypeParameter(expected), Type::TypeParameter(actual)) if expected == actual => true,
+        // Nothing is known about `T` beyond its constraint
+        (expected, Type::TypeParameter(actual)) => actual
+            .constraint
+            .as_ref()
+            .is_some_and(|constraint| is_assignable(expected, constraint, relation)),
+        (Type::Generic { body: expected, .. }, Type::Generic { body: actual, .. }) => {
+            is_assignable(expected, actual, relation)
         }
+        (expected, Type::Generic { body, .. }) => is_assignable(expected, body, relation),
+        (
+            Type::Object,
+            Type::ObjectType(_)
+            | Type::Array(_)
+            | Type::Tuple(_)
+            | Type::Readonly(_)
+            | Type::Promise(_)
+            | Type::Function { .. }
+            | Type::Class(_),
+        ) => true,
         _ => false,
     }
 }
@@ -168,11 +935,24 @@ pub fn check_type_compatibility(expected: &Type, actual: &Type) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use proptest::prelude::*;
 
     #[test]
     fn test_type_inference() {
         assert_eq!(infer_type_from_literal("null"), Type::Null);
         assert_eq!(infer_type_from_literal("42"), Type::NumberLiteral(42.0));
+        assert_eq!(infer_type_from_literal("0x1F"), Type::NumberLiteral(31.0));
+        assert_eq!(parse_bigint_literal("1_000n").as_deref(), Some("1000"));
+        assert_eq!(
+            parse_bigint_literal("0xFFFF_FFFF_FFFF_FFFF_FFFFn").as_deref(),
+            Some("1208925819614629174706175")
+        );
+        assert_eq!(parse_bigint_literal("0b0n").as_deref(), Some("0"));
+        assert_eq!(parse_bigint_literal("12"), None);
+        assert_eq!(
+            infer_type_from_literal("NaN"),
+            Type::StringLiteral("NaN".to_string())
+        );
         assert_eq!(infer_type_from_literal("true"), Type::BooleanLiteral(true));
         assert_eq!(
             infer_type_from_literal("false"),
@@ -188,6 +968,8 @@ mod tests {
     fn test_type_compatibility() {
         // Test basic type compatibility
         assert!(check_type_compatibility(&Type::Any, &Type::Number));
+        assert!(check_type_compatibility(&Type::Number, &Type::Any));
+        assert!(!check_type_compatibility(&Type::Never, &Type::Any));
         assert!(check_type_compatibility(&Type::Number, &Type::Number));
         assert!(!check_type_compatibility(&Type::String, &Type::Number));
 
@@ -201,21 +983,22 @@ mod tests {
         assert!(!check_type_compatibility(&string_array, &number_array));
 
         // Test function type compatibility
-        let func1 = Type::Function {
-            params: vec![Type::Number],
-            return_type: Arc::new(Type::Boolean),
-        };
-        let func2 = Type::Function {
-            params: vec![Type::Number],
-            return_type: Arc::new(Type::Boolean),
-        };
-        let func3 = Type::Function {
-            params: vec![Type::String],
-            return_type: Arc::new(Type::Boolean),
-        };
+        let func1 = Type::function(vec![Type::Number], Type::Boolean);
+        let func2 = Type::function(vec![Type::Number], Type::Boolean);
+        let func3 = Type::function(vec![Type::String], Type::Boolean);
 
         assert!(check_type_compatibility(&func1, &func2));
         assert!(!check_type_compatibility(&func1, &func3));
+
+        // Functions returning anything can be used where nothing is expected back
+        let callback = Type::function(vec![Type::Number], Type::Void);
+        assert!(check_type_compatibility(&callback, &func1));
+        assert!(!check_type_compatibility(&func1, &callback));
+
+        // Functions may ignore arguments they're passed, but not ask for more
+        let no_params = Type::function(Vec::new(), Type::Boolean);
+        assert!(check_type_compatibility(&func1, &no_params));
+        assert!(!check_type_compatibility(&no_params, &func1));
     }
 
     #[test]
@@ -247,9 +1030,238 @@ mod tests {
         assert!(!check_type_compatibility(&true_type, &false_type));
         assert!(check_type_compatibility(&true_type, &true_type));
 
+        // Test unions of literals
+        let greeting = Type::Union(vec![hello_type.clone(), world_type.clone()]);
+        assert!(check_type_compatibility(&string_type, &greeting));
+        assert!(check_type_compatibility(&greeting, &greeting));
+        assert!(!check_type_compatibility(&hello_type, &greeting));
+
         // Test literal type display
         assert_eq!(hello_type.to_string(), "\"hello\"");
         assert_eq!(num_42.to_string(), "42");
         assert_eq!(true_type.to_string(), "true");
     }
+
+    #[test]
+    fn test_object_types() {
+        let point = Type::ObjectType(Arc::new(ObjectType::new(vec![
+            Property::new("x", Type::Number),
+            Property::new("y", Type::Number),
+        ])));
+        let point_3d = Type::ObjectType(Arc::new(ObjectType::new(vec![
+            Property::new("x", Type::Number),
+            Property::new("y", Type::Number),
+            Property::new("z", Type::Number),
+        ])));
+        let named = Type::ObjectType(Arc::new(ObjectType::new(vec![
+            Property::new("x", Type::Number),
+            Property {
+                optional: true,
+                ..Property::new("name", Type::String)
+            },
+        ])));
+
+        assert!(check_type_compatibility(&point, &point_3d));
+        assert!(!check_type_compatibility(&point_3d, &point));
+        assert!(check_type_compatibility(&named, &point));
+        assert!(check_type_compatibility(&Type::Object, &point));
+        assert!(!check_type_compatibility(&point, &Type::Number));
+
+        assert_eq!(point.to_string(), "{ x: number; y: number; }");
+        assert_eq!(named.to_string(), "{ x: number; name?: string; }");
+
+        let dictionary = Type::ObjectType(Arc::new(ObjectType {
+            string_index: Some(Type::Number),
+            ..ObjectType::default()
+        }));
+        assert!(check_type_compatibility(&dictionary, &point));
+        assert!(!check_type_compatibility(&dictionary, &named));
+        assert_eq!(dictionary.to_string(), "{ [key: string]: number; }");
+    }
+
+    /// Random types for checking the laws assignability should obey, nested up to `depth`
+    /// deep.
+    fn arbitrary_type(depth: u32) -> impl Strategy<Value = Type> {
+        let leaf = prop_oneof![
+            Just(Type::Any),
+            Just(Type::Unknown),
+            Just(Type::Never),
+            Just(Type::Number),
+            Just(Type::String),
+            Just(Type::Boolean),
+            Just(Type::Null),
+            Just(Type::Undefined),
+            Just(Type::Void),
+            Just(Type::Object),
+            Just(Type::BigInt),
+            Just(Type::Symbol),
+            (0..3u8).prop_map(|n| Type::NumberLiteral(n as f64)),
+            prop::sample::select(vec!["a", "b", "c"])
+                .prop_map(|s| Type::StringLiteral(s.to_string())),
+            any::<bool>().prop_map(Type::BooleanLiteral),
+        ];
+        leaf.prop_recursive(depth, 64, 3, |inner| {
+            // Each of `a`, `b` and `c` is left out, optional or required
+            let property = |name: &'static str| {
+                prop::option::of((inner.clone(), any::<bool>())).prop_map(move |property| {
+                    property.map(|(ty, optional)| Property {
+                        optional,
+                        ..Property::new(name, ty)
+                    })
+                })
+            };
+            prop_oneof![
+                prop::collection::vec(inner.clone(), 2..4).prop_map(Type::Union),
+                inner.clone().prop_map(|ty| Type::Array(Arc::new(ty))),
+                prop::collection::vec(inner.clone(), 0..3).prop_map(Type::Tuple),
+                inner
+                    .clone()
+                    .prop_map(|ty| Type::Readonly(Arc::new(Type::Array(Arc::new(ty))))),
+                inner.clone().prop_map(|ty| Type::Promise(Arc::new(ty))),
+                (prop::collection::vec(inner.clone(), 0..2), inner.clone())
+                    .prop_map(|(params, return_type)| Type::function(params, return_type)),
+                (property("a"), property("b"), property("c")).prop_map(|(a, b, c)| {
+                    let properties = [a, b, c].into_iter().flatten().collect();
+                    Type::ObjectType(Arc::new(ObjectType::new(properties)))
+                }),
+            ]
+        })
+    }
+
+    fn mentions_any(ty: &Type) -> bool {
+        match ty {
+            Type::Any => true,
+            Type::Union(types) | Type::Tuple(types) => types.iter().any(mentions_any),
+            Type::Array(ty) | Type::Readonly(ty) | Type::Promise(ty) => mentions_any(ty),
+            Type::Function {
+                params,
+                return_type,
+                ..
+            } => params.iter().any(mentions_any) || mentions_any(return_type),
+            Type::ObjectType(object) => object.properties.iter().any(|p| mentions_any(&p.ty)),
+            _ => false,
+        }
+    }
+
+    fn assignable(expected: &Type, actual: &Type) -> Result<(), TestCaseError> {
+        prop_assert!(
+            check_type_compatibility(expected, actual),
+            "'{}' is not assignable to '{}'",
+            actual,
+            expected
+        );
+        Ok(())
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(1000))]
+
+        #[test]
+        fn test_assignability_is_reflexive(ty in arbitrary_type(3)) {
+            assignable(&ty, &ty)?;
+        }
+
+        #[test]
+        fn test_any_is_assignable_both_ways(ty in arbitrary_type(3)) {
+            assignable(&Type::Any, &ty)?;
+            if ty != Type::Never {
+                assignable(&ty, &Type::Any)?;
+            }
+        }
+
+        #[test]
+        fn test_unknown_is_the_top_type(ty in arbitrary_type(3)) {
+            assignable(&Type::Unknown, &ty)?;
+        }
+
+        #[test]
+        fn test_never_is_the_bottom_type(ty in arbitrary_type(3)) {
+            assignable(&ty, &Type::Never)?;
+        }
+
+        #[test]
+        fn test_union_members_are_assignable_to_the_union(
+            members in prop::collection::vec(arbitrary_type(2), 3),
+        ) {
+            let union = Type::Union(members.clone());
+            for member in &members {
+                assignable(&union, member)?;
+            }
+        }
+
+        #[test]
+        fn test_union_is_assignable_where_all_its_members_are(
+            members in prop::collection::vec(arbitrary_type(2), 2),
+            target in arbitrary_type(2),
+        ) {
+            let each = members
+                .iter()
+                .all(|member| check_type_compatibility(&target, member));
+            let union = Type::Union(members);
+            prop_assert_eq!(
+                each,
+                check_type_compatibility(&target, &union),
+                "'{}' and '{}' disagree",
+                union,
+                target
+            );
+        }
+
+        #[test]
+        fn test_intersection_is_assignable_to_each_of_its_members(
+            members in prop::collection::vec(arbitrary_type(2), 3),
+        ) {
+            let intersection = intersection_of(members.clone());
+            for member in &members {
+                assignable(member, &intersection)?;
+            }
+        }
+
+        #[test]
+        fn test_tuple_is_an_array_of_its_elements(
+            elements in prop::collection::vec(arbitrary_type(2), 3),
+            element in arbitrary_type(2),
+        ) {
+            let each = elements
+                .iter()
+                .all(|e| check_type_compatibility(&element, e));
+            let tuple = Type::Tuple(elements);
+            let array = Type::Array(Arc::new(element));
+            prop_assert_eq!(
+                each,
+                check_type_compatibility(&array, &tuple),
+                "'{}' and '{}' disagree",
+                tuple,
+                array
+            );
+        }
+
+        #[test]
+        fn test_arrays_can_be_read_through_readonly_views(element in arbitrary_type(2)) {
+            let array = Type::Array(Arc::new(element));
+            let readonly = Type::Readonly(Arc::new(array.clone()));
+            assignable(&readonly, &array)?;
+            prop_assert!(
+                !check_type_compatibility(&array, &readonly),
+                "'{}' is assignable to '{}'",
+                readonly,
+                array
+            );
+        }
+
+        // `any` relates both ways to everything, so only holds without it
+        #[test]
+        fn test_assignability_is_transitive(
+            a in arbitrary_type(2),
+            b in arbitrary_type(2),
+            c in arbitrary_type(2),
+        ) {
+            if !mentions_any(&b)
+                && check_type_compatibility(&b, &a)
+                && check_type_compatibility(&c, &b)
+            {
+                assignable(&c, &a)?;
+            }
+        }
+    }
 }
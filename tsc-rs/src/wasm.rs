@@ -0,0 +1,25 @@
+//! `wasm-bindgen` exports for running the checker in browsers and Node.
+//!
+//! Build with `wasm-pack build tsc-rs --features wasm`. Options and results cross the
+//! boundary as JSON strings; see [`crate::api`] for their shapes.
+
+use crate::api;
+use wasm_bindgen::prelude::*;
+
+/// `check(source, optionsJson)` -> JSON array of diagnostics.
+#[wasm_bindgen]
+pub fn check(source: &str, options: &str) -> Result<String, JsError> {
+    api::check(source, options).map_err(|err| JsError::new(&err))
+}
+
+/// `checkProject(filesJson, optionsJson)` -> JSON array of diagnostics across all files.
+#[wasm_bindgen(js_name = checkProject)]
+pub fn check_project(files: &str, options: &str) -> Result<String, JsError> {
+    api::check_project(files, options).map_err(|err| JsError::new(&err))
+}
+
+/// `moduleGraph(filesJson, optionsJson)` -> JSON `{ files, edges }` import graph.
+#[wasm_bindgen(js_name = moduleGraph)]
+pub fn module_graph(files: &str, options: &str) -> Result<String, JsError> {
+    api::module_graph(files, options).map_err(|err| JsError::new(&err))
+}
@@ -0,0 +1,51 @@
+//! Checks every fixture under `tests/fixtures` and compares its diagnostics with the
+//! golden `<name>.errors.txt` beside it, one `file(line,column): error: message` per line.
+//!
+//! Fixtures may split into several files with `// @filename:` and set compiler options
+//! with `// @option: value`, as in TypeScript's own test cases. Run with
+//! `UPDATE_BASELINES=1` to write the golden files from the current output.
+
+use std::path::Path;
+use tsc_rs::conformance::{parse_test_case, run_case};
+
+#[test]
+fn test_fixture_baselines() {
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let update = std::env::var_os("UPDATE_BASELINES").is_some();
+    let mut paths: Vec<_> = std::fs::read_dir(&fixtures)
+        .expect("tests/fixtures should exist")
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "ts"))
+        .collect();
+    paths.sort();
+    assert!(!paths.is_empty());
+
+    let mut mismatched = Vec::new();
+    for path in paths {
+        let name = path.file_name().unwrap().to_string_lossy().to_string();
+        let case = parse_test_case(&name, &std::fs::read_to_string(&path).unwrap());
+        let output: String = run_case(&case, Vec::new())
+            .actual
+            .iter()
+            .map(|error| format!("{}\n", error))
+            .collect();
+
+        let baseline = path.with_extension("errors.txt");
+        if update {
+            std::fs::write(&baseline, &output).unwrap();
+            continue;
+        }
+        let expected = std::fs::read_to_string(&baseline).unwrap_or_default();
+        if expected != output {
+            mismatched.push(format!(
+                "{}:\n--- expected\n{}--- actual\n{}",
+                name, expected, output
+            ));
+        }
+    }
+    assert!(
+        mismatched.is_empty(),
+        "Diagnostics differ from their baselines; rerun with UPDATE_BASELINES=1 to accept them.\n\n{}",
+        mismatched.join("\n")
+    );
+}
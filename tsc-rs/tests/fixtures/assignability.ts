@@ -0,0 +1,7 @@
+let count: number = "one";
+let label: string = 42;
+let flag: boolean = true;
+
+function labelOf(n: number): string {
+    return n;
+}
@@ -0,0 +1,2 @@
+let point = { x: 1, y: 2 };
+let total: number = point.x + point.y;
@@ -0,0 +1,8 @@
+// @filename: math.ts
+export function square(n: number): number {
+    return n * n;
+}
+
+// @filename: main.ts
+import { square } from "./math";
+let text: string = square(3);
@@ -0,0 +1,14 @@
+// @noFallthroughCasesInSwitch: true
+function describe(n: number): string {
+    let result = "";
+    switch (n) {
+        case 0:
+            result = "zero";
+        case 1:
+            result = "one";
+            break;
+        default:
+            result = "many";
+    }
+    return result;
+}